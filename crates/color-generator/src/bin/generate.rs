@@ -1,4 +1,4 @@
-use color_generator::{Appearance, ColorGenerator};
+use color_generator::{Appearance, ColorGenerator, CssOptions};
 use std::env;
 
 fn main() {
@@ -8,12 +8,24 @@ fn main() {
     let mut accent_color = "";
     let mut gray_color = "#6b7280"; // Default gray
     let mut show_css = false;
+    let mut show_json = false;
     let mut color_name = "accent";
+    let mut prefix = None;
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
             "--css" => show_css = true,
+            "--json" => show_json = true,
+            "--prefix" => {
+                i += 1;
+                if i < args.len() {
+                    prefix = Some(args[i].clone());
+                } else {
+                    eprintln!("Error: --prefix requires a value");
+                    std::process::exit(1);
+                }
+            }
             "--name" => {
                 i += 1;
                 if i < args.len() {
@@ -40,7 +52,7 @@ fn main() {
 
     if accent_color.is_empty() {
         eprintln!(
-            "Usage: {} <accent-color> [gray-color] [--css] [--name <color-name>]",
+            "Usage: {} <accent-color> [gray-color] [--css | --json] [--name <color-name>] [--prefix <prefix>]",
             args[0]
         );
         eprintln!("Examples:");
@@ -48,10 +60,14 @@ fn main() {
         eprintln!("  {} \"#3b82f6\" \"#6b7280\"", args[0]);
         eprintln!("  {} \"#10b981\" \"#64748b\" --css", args[0]);
         eprintln!("  {} \"#3b82f6\" --css --name blue", args[0]);
+        eprintln!("  {} \"#3b82f6\" --css --prefix dv", args[0]);
+        eprintln!("  {} \"#3b82f6\" --json", args[0]);
         eprintln!("");
         eprintln!("Flags:");
         eprintln!("  --css              Output raw CSS instead of color preview");
-        eprintln!("  --name <name>      Set the CSS variable prefix (default: accent)");
+        eprintln!("  --json             Output the palette as JSON tokens");
+        eprintln!("  --name <name>      Set the name of the accent variables (default: accent)");
+        eprintln!("  --prefix <prefix>  Prepend a prefix to every CSS variable");
         std::process::exit(1);
     }
 
@@ -69,7 +85,15 @@ fn main() {
     // Create the color generator
     let generator = ColorGenerator::new();
 
-    if show_css {
+    if show_json {
+        let light =
+            generator.generate_tokens_json(Appearance::Light, accent_color, gray_color, "#ffffff");
+        let dark =
+            generator.generate_tokens_json(Appearance::Dark, accent_color, gray_color, "#0f0f0f");
+
+        let tokens = serde_json::json!({ "light": light, "dark": dark });
+        println!("{}", serde_json::to_string_pretty(&tokens).unwrap());
+    } else if show_css {
         // Output CSS for both light and dark modes
         let light_palette =
             generator.generate_scale(Appearance::Light, accent_color, gray_color, "#ffffff");
@@ -78,8 +102,18 @@ fn main() {
             generator.generate_scale(Appearance::Dark, accent_color, gray_color, "#0f0f0f");
 
         // Generate CSS for light mode
-        let light_css = light_palette.generate_css(color_name, ":root, .light, .light-theme");
-        let dark_css = dark_palette.generate_css(color_name, ".dark, .dark-theme");
+        let light_css = light_palette.generate_css_with_options(&CssOptions {
+            color_name: color_name.to_string(),
+            selector: ":root, .light, .light-theme".to_string(),
+            variable_prefix: prefix.clone(),
+            wide_gamut: true,
+        });
+        let dark_css = dark_palette.generate_css_with_options(&CssOptions {
+            color_name: color_name.to_string(),
+            selector: ".dark, .dark-theme".to_string(),
+            variable_prefix: prefix,
+            wide_gamut: true,
+        });
 
         println!("{}", light_css);
         println!("{}", dark_css);
@@ -5,7 +5,8 @@
 // serde_json = "1.0"
 
 use palette::{FromColor, IntoColor, Lab, LinSrgb, OklabHue, Oklch, Srgb};
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 
 pub mod colors;
 
@@ -156,6 +157,169 @@ pub struct Scale {
     pub accent_surface_wide_gamut: String,
 
     pub background: String,
+
+    /// Extra colors emitted as `--{name}`, e.g. `--warning`.
+    pub named_colors: BTreeMap<String, String>,
+}
+
+/// Manual adjustments applied on top of a generated scale.
+///
+/// Steps are numbered 1-12 like in the CSS variables. Colors must be
+/// opaque hex colors, e.g. `#ffb224` or `#fb2`.
+#[derive(Debug, Clone, Default)]
+pub struct ScaleOverrides {
+    pub accent_steps: BTreeMap<usize, String>,
+    pub gray_steps: BTreeMap<usize, String>,
+    /// Names may only contain lowercase letters, numbers, and dashes, as
+    /// they end up in CSS variable names.
+    pub named_colors: BTreeMap<String, String>,
+}
+
+impl ScaleOverrides {
+    /// Checks that every step and color can be applied to a scale.
+    pub fn validate(&self) -> Result<(), OverrideError> {
+        for (name, steps) in [
+            ("accent_steps", &self.accent_steps),
+            ("gray_steps", &self.gray_steps),
+        ] {
+            for (step, color) in steps {
+                let key = format!("{}.{}", name, step);
+
+                if !(1..=12).contains(step) {
+                    return Err(OverrideError::new(key, "expected a step between 1 and 12"));
+                }
+                check_override_color(key, color)?;
+            }
+        }
+
+        for (name, color) in &self.named_colors {
+            let key = format!("named_colors.{}", name);
+
+            if !is_color_name(name) {
+                return Err(OverrideError::new(
+                    key,
+                    "names may only contain lowercase letters, numbers, and dashes",
+                ));
+            }
+            check_override_color(key, color)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An override that can't be applied to a scale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverrideError {
+    /// The offending override, e.g. `accent_steps.9` or `named_colors.warning`
+    pub key: String,
+    pub reason: String,
+}
+
+impl OverrideError {
+    fn new(key: String, reason: impl Into<String>) -> Self {
+        OverrideError {
+            key,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for OverrideError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid override `{}`: {}", self.key, self.reason)
+    }
+}
+
+impl std::error::Error for OverrideError {}
+
+fn check_override_color(key: String, color: &str) -> Result<(), OverrideError> {
+    if is_opaque_hex_color(color) {
+        Ok(())
+    } else {
+        Err(OverrideError::new(
+            key,
+            format!("expected a hex color like \"#ffb224\", found \"{}\"", color),
+        ))
+    }
+}
+
+/// An opaque hex color with either 3 or 6 digits, e.g. `#fb2` or `#ffb224`.
+fn is_opaque_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn is_color_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Options for [`Scale::generate_css_with_options`].
+#[derive(Debug, Clone)]
+pub struct CssOptions {
+    /// Name of the accent variables, e.g. `accent` for `--accent-9`
+    pub color_name: String,
+    /// Selector the variables are declared under, e.g. `.dark`
+    pub selector: String,
+    /// Prepended to every variable, e.g. `dv` for `--dv-accent-9` and
+    /// `--dv-gray-1`. Avoids clashing with other themes on the same page.
+    pub variable_prefix: Option<String>,
+    /// Whether to emit the `display-p3` block for wide-gamut screens
+    pub wide_gamut: bool,
+}
+
+impl Default for CssOptions {
+    fn default() -> Self {
+        CssOptions {
+            color_name: "accent".to_string(),
+            selector: ":root".to_string(),
+            variable_prefix: None,
+            wide_gamut: true,
+        }
+    }
+}
+
+impl CssOptions {
+    fn variable(&self, name: &str) -> String {
+        match &self.variable_prefix {
+            Some(prefix) => format!("--{}-{}", prefix, name),
+            None => format!("--{}", name),
+        }
+    }
+}
+
+/// A [`Scale`] as structured data, e.g. for design tools or native apps.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScaleTokens {
+    /// Steps 1-12, in order
+    pub accent: Vec<StepToken>,
+    /// Steps 1-12, in order
+    pub gray: Vec<StepToken>,
+    /// Text color to use on top of accent step 9
+    pub accent_contrast: String,
+    pub accent_surface: SurfaceToken,
+    pub gray_surface: SurfaceToken,
+    pub background: String,
+    pub named_colors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepToken {
+    pub step: usize,
+    pub hex: String,
+    pub alpha: String,
+    pub wide_gamut: String,
+    pub alpha_wide_gamut: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SurfaceToken {
+    pub hex: String,
+    pub wide_gamut: String,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -173,111 +337,176 @@ pub struct ColorGenerator {
 
 impl Scale {
     pub fn generate_css(&self, color_name: &str, theme_selector: &str) -> String {
+        self.generate_css_with_options(&CssOptions {
+            color_name: color_name.to_string(),
+            selector: theme_selector.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn generate_css_with_options(&self, options: &CssOptions) -> String {
+        let color_name = &options.color_name;
+        let var = |name: &str| options.variable(name);
         let mut css = String::new();
 
         // Regular hex values
-        css.push_str(&format!("{} {{\n", theme_selector));
+        css.push_str(&format!("{} {{\n", options.selector));
 
         // Accent scale (1-12)
         for (i, color) in self.accent_scale.iter().enumerate() {
-            css.push_str(&format!("  --{}-{}: {};\n", color_name, i + 1, color));
+            css.push_str(&format!(
+                "  {}: {};\n",
+                var(&format!("{color_name}-{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // Accent alpha scale (a1-a12)
         for (i, color) in self.accent_scale_alpha.iter().enumerate() {
-            css.push_str(&format!("  --{}-a{}: {};\n", color_name, i + 1, color));
+            css.push_str(&format!(
+                "  {}: {};\n",
+                var(&format!("{color_name}-a{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // Gray scale (1-12)
         for (i, color) in self.gray_scale.iter().enumerate() {
-            css.push_str(&format!("  --gray-{}: {};\n", i + 1, color));
+            css.push_str(&format!(
+                "  {}: {};\n",
+                var(&format!("gray-{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // Gray alpha scale (a1-a12)
         for (i, color) in self.gray_scale_alpha.iter().enumerate() {
-            css.push_str(&format!("  --gray-a{}: {};\n", i + 1, color));
+            css.push_str(&format!(
+                "  {}: {};\n",
+                var(&format!("gray-a{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // Accent special colors
         css.push_str(&format!(
-            "  --{}-contrast: {};\n",
-            color_name, self.accent_contrast
+            "  {}: {};\n",
+            var(&format!("{color_name}-contrast")),
+            self.accent_contrast
         ));
         css.push_str(&format!(
-            "  --{}-surface: {};\n",
-            color_name, self.accent_surface
+            "  {}: {};\n",
+            var(&format!("{color_name}-surface")),
+            self.accent_surface
         ));
         css.push_str(&format!(
-            "  --{}-indicator: {};\n",
-            color_name, self.accent_scale[8]
+            "  {}: {};\n",
+            var(&format!("{color_name}-indicator")),
+            self.accent_scale[8]
         )); // step 9
         css.push_str(&format!(
-            "  --{}-track: {};\n",
-            color_name, self.accent_scale[8]
+            "  {}: {};\n",
+            var(&format!("{color_name}-track")),
+            self.accent_scale[8]
         )); // step 9
 
         // Gray special colors
-        css.push_str(&format!("  --gray-surface: {};\n", self.gray_surface));
+        css.push_str(&format!(
+            "  {}: {};\n",
+            var("gray-surface"),
+            self.gray_surface
+        ));
 
         // Background
-        css.push_str(&format!("  --background: {};\n", self.background));
+        css.push_str(&format!("  {}: {};\n", var("background"), self.background));
+
+        // Named colors
+        for (name, color) in &self.named_colors {
+            css.push_str(&format!("  {}: {};\n", var(name), color));
+        }
+
+        css.push_str("}\n");
 
-        css.push_str("}\n\n");
+        if !options.wide_gamut {
+            return css;
+        }
 
         // P3 wide-gamut support
+        css.push('\n');
         css.push_str("@supports (color: color(display-p3 1 1 1)) {\n");
         css.push_str("  @media (color-gamut: p3) {\n");
-        css.push_str(&format!("    {} {{\n", theme_selector));
+        css.push_str(&format!("    {} {{\n", options.selector));
 
         // P3 accent scale (1-12)
         for (i, color) in self.accent_scale_wide_gamut.iter().enumerate() {
-            css.push_str(&format!("      --{}-{}: {};\n", color_name, i + 1, color));
+            css.push_str(&format!(
+                "      {}: {};\n",
+                var(&format!("{color_name}-{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // P3 accent alpha scale (a1-a12)
         for (i, color) in self.accent_scale_alpha_wide_gamut.iter().enumerate() {
-            css.push_str(&format!("      --{}-a{}: {};\n", color_name, i + 1, color));
+            css.push_str(&format!(
+                "      {}: {};\n",
+                var(&format!("{color_name}-a{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // P3 gray scale (1-12)
         for (i, color) in self.gray_scale_wide_gamut.iter().enumerate() {
-            css.push_str(&format!("      --gray-{}: {};\n", i + 1, color));
+            css.push_str(&format!(
+                "      {}: {};\n",
+                var(&format!("gray-{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // P3 gray alpha scale (a1-a12)
         for (i, color) in self.gray_scale_alpha_wide_gamut.iter().enumerate() {
-            css.push_str(&format!("      --gray-a{}: {};\n", i + 1, color));
+            css.push_str(&format!(
+                "      {}: {};\n",
+                var(&format!("gray-a{}", i + 1)),
+                color
+            ));
         }
-        css.push_str("\n");
+        css.push('\n');
 
         // P3 accent special colors
         css.push_str(&format!(
-            "      --{}-contrast: {};\n",
-            color_name, self.accent_contrast
+            "      {}: {};\n",
+            var(&format!("{color_name}-contrast")),
+            self.accent_contrast
         ));
         css.push_str(&format!(
-            "      --{}-surface: {};\n",
-            color_name, self.accent_surface_wide_gamut
+            "      {}: {};\n",
+            var(&format!("{color_name}-surface")),
+            self.accent_surface_wide_gamut
         ));
         css.push_str(&format!(
-            "      --{}-indicator: {};\n",
-            color_name, self.accent_scale_wide_gamut[8]
+            "      {}: {};\n",
+            var(&format!("{color_name}-indicator")),
+            self.accent_scale_wide_gamut[8]
         )); // step 9
         css.push_str(&format!(
-            "      --{}-track: {};\n",
-            color_name, self.accent_scale_wide_gamut[8]
+            "      {}: {};\n",
+            var(&format!("{color_name}-track")),
+            self.accent_scale_wide_gamut[8]
         )); // step 9
 
         // P3 gray special colors
         css.push_str(&format!(
-            "      --gray-surface: {};\n",
+            "      {}: {};\n",
+            var("gray-surface"),
             self.gray_surface_wide_gamut
         ));
 
@@ -287,6 +516,118 @@ impl Scale {
 
         css
     }
+
+    /// The scale as structured data, for consumers that can't use CSS.
+    pub fn to_tokens(&self) -> ScaleTokens {
+        let steps = |hex: &ArrayOf12<String>,
+                     alpha: &ArrayOf12<String>,
+                     wide_gamut: &ArrayOf12<String>,
+                     alpha_wide_gamut: &ArrayOf12<String>| {
+            (0..12)
+                .map(|i| StepToken {
+                    step: i + 1,
+                    hex: hex[i].clone(),
+                    alpha: alpha[i].clone(),
+                    wide_gamut: wide_gamut[i].clone(),
+                    alpha_wide_gamut: alpha_wide_gamut[i].clone(),
+                })
+                .collect()
+        };
+
+        ScaleTokens {
+            accent: steps(
+                &self.accent_scale,
+                &self.accent_scale_alpha,
+                &self.accent_scale_wide_gamut,
+                &self.accent_scale_alpha_wide_gamut,
+            ),
+            gray: steps(
+                &self.gray_scale,
+                &self.gray_scale_alpha,
+                &self.gray_scale_wide_gamut,
+                &self.gray_scale_alpha_wide_gamut,
+            ),
+            accent_contrast: self.accent_contrast.clone(),
+            accent_surface: SurfaceToken {
+                hex: self.accent_surface.clone(),
+                wide_gamut: self.accent_surface_wide_gamut.clone(),
+            },
+            gray_surface: SurfaceToken {
+                hex: self.gray_surface.clone(),
+                wide_gamut: self.gray_surface_wide_gamut.clone(),
+            },
+            background: self.background.clone(),
+            named_colors: self.named_colors.clone(),
+        }
+    }
+
+    /// Replaces individual steps of the scale, recomputing the alpha, wide
+    /// gamut, and contrast variants that depend on them.
+    ///
+    /// Nothing is changed if any of the overrides is invalid.
+    fn apply_overrides(
+        &mut self,
+        appearance: Appearance,
+        overrides: &ScaleOverrides,
+    ) -> Result<(), OverrideError> {
+        overrides.validate()?;
+
+        let background_hex = self.background.clone();
+
+        for (&step, color) in &overrides.accent_steps {
+            let i = step - 1;
+            let hex = format_hex(&color.to_lowercase());
+            let color = parse_color(&hex);
+
+            self.accent_scale[i] = hex;
+            self.accent_scale_wide_gamut[i] = to_oklch_string(color);
+            self.accent_scale_alpha[i] =
+                get_alpha_color_srgb(&self.accent_scale[i], &background_hex, None);
+            self.accent_scale_alpha_wide_gamut[i] =
+                get_alpha_color_p3(&self.accent_scale_wide_gamut[i], &background_hex, None);
+
+            // Step 9 is used for solid backgrounds, so text on top of it
+            // has to stay readable.
+            if i == 8 {
+                self.accent_contrast = to_hex(get_text_color(color));
+            }
+
+            // Step 2 is the base for the translucent accent surface
+            if i == 1 {
+                let alpha = match appearance {
+                    Appearance::Light => 0.8,
+                    Appearance::Dark => 0.5,
+                };
+                self.accent_surface =
+                    get_alpha_color_srgb(&self.accent_scale[1], &background_hex, Some(alpha));
+                self.accent_surface_wide_gamut = get_alpha_color_p3(
+                    &self.accent_scale_wide_gamut[1],
+                    &background_hex,
+                    Some(alpha),
+                );
+            }
+        }
+
+        for (&step, color) in &overrides.gray_steps {
+            let i = step - 1;
+            let hex = format_hex(&color.to_lowercase());
+            let color = parse_color(&hex);
+
+            self.gray_scale[i] = hex;
+            self.gray_scale_wide_gamut[i] = to_oklch_string(color);
+            self.gray_scale_alpha[i] =
+                get_alpha_color_srgb(&self.gray_scale[i], &background_hex, None);
+            self.gray_scale_alpha_wide_gamut[i] =
+                get_alpha_color_p3(&self.gray_scale_wide_gamut[i], &background_hex, None);
+        }
+
+        for (name, color) in &overrides.named_colors {
+            self.named_colors
+                .insert(name.clone(), format_hex(&color.to_lowercase()));
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for ColorGenerator {
@@ -424,9 +765,42 @@ impl ColorGenerator {
             accent_surface,
             accent_surface_wide_gamut,
             background: background_hex,
+            named_colors: BTreeMap::new(),
         }
     }
 
+    /// Generates a scale like [`ColorGenerator::generate_scale`], and then
+    /// applies the given overrides on top of it. Fails if any of the
+    /// overrides is invalid, naming the offending key.
+    pub fn generate_scale_with_overrides(
+        &self,
+        appearance: Appearance,
+        accent: &str,
+        gray: &str,
+        background: &str,
+        overrides: &ScaleOverrides,
+    ) -> Result<Scale, OverrideError> {
+        let mut scale = self.generate_scale(appearance, accent, gray, background);
+        scale.apply_overrides(appearance, overrides)?;
+        Ok(scale)
+    }
+
+    /// Generates a scale like [`ColorGenerator::generate_scale`], as JSON
+    /// tokens. See [`Scale::to_tokens`].
+    pub fn generate_tokens_json(
+        &self,
+        appearance: Appearance,
+        accent: &str,
+        gray: &str,
+        background: &str,
+    ) -> serde_json::Value {
+        let tokens = self
+            .generate_scale(appearance, accent, gray, background)
+            .to_tokens();
+
+        serde_json::to_value(tokens).expect("tokens only contain strings and maps")
+    }
+
     fn get_scale_from_color(
         &self,
         source: Oklch,
@@ -444,7 +818,9 @@ impl ColorGenerator {
             }
         }
 
-        all_colors.sort_by(|a, b| a.2.partial_cmp(&b.2).unwrap());
+        // Scales share some of their colors, so break ties by name. Otherwise
+        // the result would depend on the iteration order of the HashMap.
+        all_colors.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
 
         // Remove non-unique scales
         let mut closest_colors: Vec<(String, Oklch, f32)> = Vec::new();
@@ -1018,10 +1394,8 @@ mod tests {
         assert_eq!(blue_scale.len(), 12);
     }
 
-    #[test]
-    fn test_css_generation() {
-        // Create a sample Scale struct for testing
-        let colors = Scale {
+    fn sample_scale() -> Scale {
+        Scale {
             accent_scale: [
                 "#f9fcfd".to_string(),
                 "#f3f8fb".to_string(),
@@ -1090,8 +1464,13 @@ mod tests {
             accent_surface: "#f0f6facc".to_string(),
             accent_surface_wide_gamut: "color(display-p3 0.9451 0.9647 0.9804 / 0.8)".to_string(),
             background: "#ffffff".to_string(),
-        };
+            named_colors: BTreeMap::new(),
+        }
+    }
 
+    #[test]
+    fn test_css_generation() {
+        let colors = sample_scale();
         let css = colors.generate_css("blue", ":root, .light, .light-theme");
 
         // Test that the CSS contains expected elements
@@ -1110,4 +1489,226 @@ mod tests {
         assert!(css.contains("oklch(98.9% 0.0031 230.7)"));
         assert!(css.contains("color(display-p3 0.0157 0.5059 0.7529 / 0.016)"));
     }
+
+    #[test]
+    fn test_css_generation_with_prefix_and_without_p3() {
+        let css = sample_scale().generate_css_with_options(&CssOptions {
+            color_name: "blue".to_string(),
+            selector: ".dark".to_string(),
+            variable_prefix: Some("dv".to_string()),
+            wide_gamut: false,
+        });
+
+        assert!(css.starts_with(".dark {\n  --dv-blue-1: #f9fcfd;\n"));
+        assert!(css.contains("  --dv-blue-a1: #2aa9d406;\n"));
+        assert!(css.contains("  --dv-blue-indicator: #4490b3;\n"));
+        assert!(css.contains("  --dv-gray-1: #000000;\n"));
+        assert!(css.contains("  --dv-gray-surface: #ffffffcc;\n"));
+        assert!(css.contains("  --dv-background: #ffffff;\n"));
+        assert!(!css.contains("  --blue-1:"));
+        assert!(!css.contains("@supports"));
+        assert!(css.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_generate_css_uses_default_options() {
+        let scale = sample_scale();
+
+        assert_eq!(
+            scale.generate_css("accent", ":root"),
+            scale.generate_css_with_options(&CssOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_scale_to_tokens() {
+        let tokens = sample_scale().to_tokens();
+
+        assert_eq!(tokens.accent.len(), 12);
+        assert_eq!(tokens.gray.len(), 12);
+        assert_eq!(
+            tokens.accent[0],
+            StepToken {
+                step: 1,
+                hex: "#f9fcfd".to_string(),
+                alpha: "#2aa9d406".to_string(),
+                wide_gamut: "oklch(98.9% 0.0031 230.7)".to_string(),
+                alpha_wide_gamut: "color(display-p3 0.0157 0.5059 0.7529 / 0.016)".to_string(),
+            }
+        );
+        assert_eq!(tokens.accent[11].step, 12);
+        assert_eq!(tokens.accent_contrast, "#fff");
+        assert_eq!(tokens.accent_surface.hex, "#f0f6facc");
+        assert_eq!(
+            tokens.gray_surface.wide_gamut,
+            "color(display-p3 1 1 1 / 80%)"
+        );
+        assert_eq!(tokens.background, "#ffffff");
+    }
+
+    #[test]
+    fn test_tokens_json_matches_scale() {
+        let generator = ColorGenerator::new();
+        let scale = generator.generate_scale(Appearance::Dark, "#5B5BD6", "gray", "#000000");
+        let json = generator.generate_tokens_json(Appearance::Dark, "#5B5BD6", "gray", "#000000");
+
+        assert_eq!(json["accent"].as_array().unwrap().len(), 12);
+        assert_eq!(json["accent"][8]["step"], 9);
+        assert_eq!(json["accent"][8]["hex"], scale.accent_scale[8]);
+        assert_eq!(json["gray"][0]["alpha"], scale.gray_scale_alpha[0]);
+        assert_eq!(
+            json["accent_surface"]["wide_gamut"],
+            scale.accent_surface_wide_gamut
+        );
+        assert_eq!(json["accent_contrast"], scale.accent_contrast);
+        assert_eq!(json["background"], "#000000");
+    }
+
+    #[test]
+    fn test_overrides_replace_steps() {
+        let generator = ColorGenerator::new();
+        let generated = generator.generate_scale(Appearance::Dark, "#5B5BD6", "gray", "#000000");
+
+        let overrides = ScaleOverrides {
+            accent_steps: [(9, "#FFE629".to_string())].into(),
+            gray_steps: [(1, "#111".to_string())].into(),
+            named_colors: [("warning".to_string(), "#FFB224".to_string())].into(),
+        };
+        let scale = generator
+            .generate_scale_with_overrides(
+                Appearance::Dark,
+                "#5B5BD6",
+                "gray",
+                "#000000",
+                &overrides,
+            )
+            .unwrap();
+
+        assert_eq!(scale.accent_scale[8], "#ffe629");
+        assert_ne!(scale.accent_scale_alpha[8], generated.accent_scale_alpha[8]);
+        assert_ne!(
+            scale.accent_scale_wide_gamut[8],
+            generated.accent_scale_wide_gamut[8]
+        );
+        assert_eq!(scale.gray_scale[0], "#111111");
+        assert_ne!(scale.gray_scale_alpha[0], generated.gray_scale_alpha[0]);
+
+        // Other steps are untouched
+        assert_eq!(scale.accent_scale[7], generated.accent_scale[7]);
+        assert_eq!(scale.accent_scale_alpha[9], generated.accent_scale_alpha[9]);
+
+        let css = scale.generate_css("accent", ".dark");
+        assert!(css.contains("--accent-9: #ffe629;"));
+        assert!(css.contains("--accent-indicator: #ffe629;"));
+        assert!(css.contains("--warning: #ffb224;"));
+    }
+
+    #[test]
+    fn test_overriding_step_9_recomputes_contrast() {
+        let generator = ColorGenerator::new();
+
+        // A dark accent gets white text on step 9...
+        let scale = generator.generate_scale(Appearance::Light, "#1d1d8f", "gray", "#ffffff");
+        assert_eq!(scale.accent_contrast, "#ffffff");
+
+        // ...but a light step 9 override needs dark text instead
+        let overrides = ScaleOverrides {
+            accent_steps: [(9, "#ffe629".to_string())].into(),
+            ..Default::default()
+        };
+        let scale = generator
+            .generate_scale_with_overrides(
+                Appearance::Light,
+                "#1d1d8f",
+                "gray",
+                "#ffffff",
+                &overrides,
+            )
+            .unwrap();
+        assert_ne!(scale.accent_contrast, "#ffffff");
+    }
+
+    #[test]
+    fn test_out_of_range_steps_are_rejected() {
+        let generator = ColorGenerator::new();
+
+        for step in [0, 13] {
+            let overrides = ScaleOverrides {
+                accent_steps: [(step, "#ffe629".to_string())].into(),
+                ..Default::default()
+            };
+            let error = generator
+                .generate_scale_with_overrides(
+                    Appearance::Light,
+                    "#5B5BD6",
+                    "gray",
+                    "#ffffff",
+                    &overrides,
+                )
+                .unwrap_err();
+
+            assert_eq!(error.key, format!("accent_steps.{}", step));
+        }
+    }
+
+    #[test]
+    fn test_invalid_override_colors_name_the_key() {
+        let generator = ColorGenerator::new();
+
+        for color in ["ffe629", "#ff", "#ffe62980", "#ggg", "red"] {
+            let overrides = ScaleOverrides {
+                gray_steps: [(3, color.to_string())].into(),
+                ..Default::default()
+            };
+            let error = generator
+                .generate_scale_with_overrides(
+                    Appearance::Dark,
+                    "#5B5BD6",
+                    "gray",
+                    "#000000",
+                    &overrides,
+                )
+                .unwrap_err();
+
+            assert_eq!(error.key, "gray_steps.3", "{}", color);
+            assert!(error.reason.contains(color), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_named_colors_must_be_css_safe() {
+        let generator = ColorGenerator::new();
+
+        for name in ["Warning", "warn ing", "warning;", "", "warning_1"] {
+            let overrides = ScaleOverrides {
+                named_colors: [(name.to_string(), "#ffb224".to_string())].into(),
+                ..Default::default()
+            };
+            let error = generator
+                .generate_scale_with_overrides(
+                    Appearance::Light,
+                    "#5B5BD6",
+                    "gray",
+                    "#ffffff",
+                    &overrides,
+                )
+                .unwrap_err();
+
+            assert_eq!(error.key, format!("named_colors.{}", name));
+        }
+
+        let overrides = ScaleOverrides {
+            named_colors: [("warning-2".to_string(), "#ffb224".to_string())].into(),
+            ..Default::default()
+        };
+        assert!(generator
+            .generate_scale_with_overrides(
+                Appearance::Light,
+                "#5B5BD6",
+                "gray",
+                "#ffffff",
+                &overrides,
+            )
+            .is_ok());
+    }
 }
@@ -1,22 +1,44 @@
-use crate::file_gatherer::gather_files;
+use crate::file_gatherer::{gather_files, gather_metadata};
+use crate::images::{self, ImageOptions};
+use crate::remote_specs;
+use crate::reporter::{Event, Phase, Reporter};
 use crate::Result;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use libdoctave::content_api::ViewMode;
-use libdoctave::{renderer::Renderer, ContentApiResponse, Project, ResponseContext};
-use owo_colors::{OwoColorize as _, Stream};
+use libdoctave::quick_nav::QUICK_NAV_FILE_NAME;
+use libdoctave::{
+    renderer::Renderer, Analysis, ContentApiResponse, InputContent, InputFile, Project,
+    RenderOptions, ResponseContext,
+};
 use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 
 /// Builds the project by finding all the files in the working directory and rendering them to
-/// the output directory.
-pub(crate) fn build<W: std::io::Write>(
-    stdout: &mut W,
+/// the output directory. Progress is reported through the given reporter.
+///
+/// With `git_metadata`, when pages were last updated and by whom is read from
+/// git instead of the filesystem.
+///
+/// With `images`, resized versions of the images shown on pages are generated
+/// and pages are rendered to use them.
+///
+/// OpenAPI specs set with `spec_url` are read from the cache, and aren't
+/// fetched. See [`remote_specs::fetch`].
+///
+/// Returns the built project, so that the dev server can compare it to the next build,
+/// along with what verifying it found.
+pub fn build(
+    reporter: &mut dyn Reporter,
     working_dir: &Path,
     out_dir: &Path,
     view_mode: ViewMode,
-) -> Result<()> {
+    git_metadata: bool,
+    images: Option<&ImageOptions>,
+) -> Result<(Project, Analysis)> {
     // Gather the files
-    let files = gather_files(working_dir)?;
+    let mut files = gather_files(working_dir)?;
 
     if files.is_empty() {
         return Err(crate::Error::General(format!(
@@ -25,12 +47,23 @@ pub(crate) fn build<W: std::io::Write>(
         )));
     }
 
+    let metadata = gather_metadata(working_dir, &files, git_metadata);
+    remote_specs::add_cached(working_dir, &mut files);
+    let cache_bust_value = content_hash(working_dir, &files);
+
     let renderer = Renderer::new().expect("Failed to create renderer");
 
-    match Project::from_file_list(files) {
+    match Project::from_file_list_with_metadata(files, metadata) {
         Ok(project) => {
+            let build_start = std::time::Instant::now();
+            reporter.report(Event::PhaseStarted {
+                phase: Phase::Build,
+            })?;
+
             let start = std::time::Instant::now();
-            writeln!(stdout, "Verifying project...")?;
+            reporter.report(Event::PhaseStarted {
+                phase: Phase::Verify,
+            })?;
 
             let dir = out_dir.to_path_buf();
             let clearer_thread_handle = std::thread::spawn(move || {
@@ -38,52 +71,73 @@ pub(crate) fn build<W: std::io::Write>(
                 let _ = std::fs::remove_dir_all(dir);
             });
 
-            let verify_results = project.verify(None, None);
-
-            let verify_duration = start.elapsed();
-
-            if let Err(e) = &verify_results {
-                writeln!(
-                    stdout,
-                    "Found {} issues while building documentation in {:?}",
-                    e.len(),
-                    verify_duration
-                )?;
-
-                for issue in e {
-                    writeln!(
-                        stdout,
-                        "--------------------------------------------\n{} {}\n",
-                        issue.message.bold(),
-                        issue
-                            .file
-                            .as_ref()
-                            .map(|f| format!("[{}]", f.display()))
-                            .unwrap_or(String::from(""))
-                            .bold()
-                    )?;
-                    writeln!(stdout, "{}", issue.description)?;
-                }
+            let analysis = project.analyze(None, None);
 
-                writeln!(stdout, "--------------------------------------------",)?;
+            for error in &analysis.errors {
+                reporter.report(Event::Error { error })?;
             }
 
+            for warning in &analysis.warnings {
+                reporter.report(Event::Warning {
+                    message: &format!("{}\n{}", warning.message, warning.description),
+                    owner: warning.owner.as_deref(),
+                })?;
+            }
+
+            reporter.report(Event::PhaseFinished {
+                phase: Phase::Verify,
+                duration: start.elapsed(),
+            })?;
+
             clearer_thread_handle
                 .join()
                 .expect("Failed to join clearer thread");
 
-            let start = std::time::Instant::now();
-
-            if view_mode == ViewMode::Prod && verify_results.is_err() {
+            if view_mode == ViewMode::Prod && !analysis.passed() {
                 return Err(crate::Error::General(String::from(
                     "Production build failed",
                 )));
             }
 
-            let results: Vec<Result<()>> = project
-                .pages()
+            let image_variants = match images {
+                Some(opts) => {
+                    let start = std::time::Instant::now();
+                    reporter.report(Event::PhaseStarted {
+                        phase: Phase::Images,
+                    })?;
+
+                    let manifest = images::generate(&project, working_dir, out_dir, opts)?;
+
+                    reporter.report(Event::PhaseFinished {
+                        phase: Phase::Images,
+                        duration: start.elapsed(),
+                    })?;
+
+                    manifest.render_variants()
+                }
+                None => Default::default(),
+            };
+
+            let start = std::time::Instant::now();
+            reporter.report(Event::PhaseStarted {
+                phase: Phase::Render,
+            })?;
+
+            // The dev server shows drafts, production builds leave them out
+            let publish_drafts = view_mode == ViewMode::Dev;
+            let render_opts = RenderOptions {
+                publish_drafts,
+                ..Default::default()
+            };
+
+            // Pages are rendered in parallel, so their events are reported
+            // once all of them are done.
+            let results: Vec<Result<(String, PathBuf, Duration)>> = project
+                .published_pages(Some(&render_opts))
                 .into_par_iter()
                 .map(|page| {
+                    let page_start = std::time::Instant::now();
+
                     let mut path = out_dir.to_path_buf();
                     path.push(page.out_path());
 
@@ -95,82 +149,216 @@ pub(crate) fn build<W: std::io::Write>(
                     ctx.options.webbify_internal_urls = true;
                     ctx.view_mode = view_mode.clone();
                     ctx.options.bust_image_caches = true;
+                    ctx.options.cache_bust_value = Some(cache_bust_value.clone());
+                    ctx.options.publish_drafts = publish_drafts;
+                    ctx.options.image_variants = image_variants.clone();
 
+                    let uri_path = page.uri_path().to_string();
                     let response = ContentApiResponse::content(page, &project, ctx);
 
                     let rendered = renderer.render_page(response).map_err(|e| {
                         crate::Error::General(format!("Failed to render page: {:?}", e))
                     })?;
 
-                    std::fs::write(path, rendered)?;
+                    std::fs::write(&path, rendered)?;
 
-                    Ok(())
+                    Ok((uri_path, path, page_start.elapsed()))
                 })
                 .collect();
 
-            let mut errors: Vec<crate::Error> = vec![];
+            let mut failed = false;
             for result in results {
-                if let Err(e) = result {
-                    errors.push(e);
+                match result {
+                    Ok((uri_path, out_path, duration)) => reporter.report(Event::PageRendered {
+                        uri_path: &uri_path,
+                        out_path: &out_path,
+                        duration,
+                    })?,
+                    Err(e) => {
+                        failed = true;
+                        reporter.report(Event::Failed {
+                            message: &format!("{:?}", e),
+                        })?;
+                    }
                 }
             }
 
-            if !errors.is_empty() {
-                writeln!(
-                    stdout,
-                    "Failed to build project. Found {} errors.",
-                    errors.len()
-                )?;
-                for error in errors {
-                    writeln!(stdout, "{:?}", error)?;
-                }
-
+            if failed {
                 return Err(crate::Error::General(String::from(
                     "Failed to build project",
                 )));
             }
 
+            reporter.report(Event::PhaseFinished {
+                phase: Phase::Render,
+                duration: start.elapsed(),
+            })?;
+
             // Copy assets
-            if !project.assets.is_empty() {
-                for asset in &project.assets {
-                    let path = out_dir.join(&asset.path);
+            let start = std::time::Instant::now();
+            reporter.report(Event::PhaseStarted {
+                phase: Phase::Assets,
+            })?;
 
-                    if !path.exists() {
-                        std::fs::create_dir_all(path.parent().unwrap())?;
-                    }
+            for asset in &project.assets {
+                let path = out_dir.join(&asset.path);
 
-                    if !asset.path.exists() {
-                        // The OpenAPI spec might not exist, but is counted as an asset, so we'll just skip it
-                        // in this case. We'll have an error in verify informing the user.
-                        continue;
-                    }
+                if !path.exists() {
+                    std::fs::create_dir_all(path.parent().unwrap())?;
+                }
 
-                    std::fs::copy(working_dir.join(&asset.path), out_dir.join(&asset.path))?;
+                if !asset.path.exists() {
+                    // The OpenAPI spec might not exist, but is counted as an asset, so we'll just skip it
+                    // in this case. We'll have an error in verify informing the user.
+                    continue;
                 }
+
+                std::fs::copy(working_dir.join(&asset.path), out_dir.join(&asset.path))?;
             }
 
+            reporter.report(Event::PhaseFinished {
+                phase: Phase::Assets,
+                duration: start.elapsed(),
+            })?;
+
             // Generate the search index
+            let start = std::time::Instant::now();
+            reporter.report(Event::PhaseStarted {
+                phase: Phase::SearchIndex,
+            })?;
+
             if let Ok(index) = project.search_index() {
                 std::fs::create_dir_all(out_dir.join("_assets"))?;
                 std::fs::write(out_dir.join("_assets/search.json"), index.to_json())?;
             } else {
-                writeln!(
-                    stdout,
-                    "Failed to generate search index. This is not a fatal error, but you may not be able to search your project."
-                )?;
+                reporter.report(Event::Warning {
+                    message: "Failed to generate search index. This is not a fatal error, but you may not be able to search your project.",
+                    owner: None,
+                })?;
             }
 
-            let build_duration = start.elapsed();
+            if project.settings().search().quick_nav {
+                let manifest = project.quick_nav_manifest(Some(&render_opts));
+                let json = serde_json::to_string(&manifest).map_err(|e| {
+                    crate::Error::General(format!("Could not serialize quick-nav manifest: {}", e))
+                })?;
+                std::fs::write(out_dir.join(QUICK_NAV_FILE_NAME), json)?;
+            }
+
+            reporter.report(Event::PhaseFinished {
+                phase: Phase::SearchIndex,
+                duration: start.elapsed(),
+            })?;
+
+            for feed in project.feeds(Some(&render_opts)) {
+                let path = out_dir.join(feed.out_path());
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::write(path, crate::feed::to_rss(&feed))?;
+            }
 
-            writeln!(
-                stdout,
-                "{} {}",
-                "Build complete in".if_supports_color(Stream::Stdout, |s| s.green()),
-                format!("{:?}", build_duration).if_supports_color(Stream::Stdout, |s| s.bold()),
-            )?;
+            reporter.report(Event::PhaseFinished {
+                phase: Phase::Build,
+                duration: build_start.elapsed(),
+            })?;
 
-            Ok(())
+            Ok((project, analysis))
         }
         Err(e) => Err(crate::Error::FatalBuildError(e)),
     }
 }
+
+/// A short hash of the paths and contents of the files, for busting image
+/// caches. Unlike the time of the build, it's the same for every build of
+/// the same files.
+fn content_hash(working_dir: &Path, files: &[InputFile]) -> String {
+    let mut files = files.iter().collect::<Vec<_>>();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut hasher = Sha256::new();
+
+    for file in files {
+        hasher.update(file.path.to_string_lossy().as_bytes());
+        hasher.update([0u8]);
+
+        match &file.content {
+            InputContent::Text(text) => hasher.update(text.as_bytes()),
+            // The signature of binary files is when they were modified, which
+            // changes with every checkout, so their contents are hashed instead
+            InputContent::Binary { signature, .. } => {
+                match std::fs::read(working_dir.join(&file.path)) {
+                    Ok(bytes) => hasher.update(&bytes),
+                    Err(_) => hasher.update(signature.as_bytes()),
+                }
+            }
+        }
+
+        hasher.update([0u8]);
+    }
+
+    format!("{:x}", hasher.finalize())[..12].to_owned()
+}
+
+/// Renders all pages under the given URI prefix into a single standalone HTML file in the output
+/// directory, along with the project's assets.
+pub fn build_single_page(
+    reporter: &mut dyn Reporter,
+    working_dir: &Path,
+    out_dir: &Path,
+    uri_prefix: &str,
+) -> Result<()> {
+    let mut files = gather_files(working_dir)?;
+
+    if files.is_empty() {
+        return Err(crate::Error::General(format!(
+            "No files found in directory: {}",
+            working_dir.display()
+        )));
+    }
+
+    remote_specs::add_cached(working_dir, &mut files);
+
+    let renderer = Renderer::new().expect("Failed to create renderer");
+
+    let project = Project::from_file_list(files).map_err(crate::Error::FatalBuildError)?;
+
+    let start = std::time::Instant::now();
+
+    let page = project
+        .render_section(uri_prefix, None)
+        .map_err(crate::Error::FatalBuildError)?;
+
+    let rendered = renderer
+        .render_single_page(&page, &project)
+        .map_err(|e| crate::Error::General(format!("Failed to render page: {:?}", e)))?;
+
+    let file_name = match uri_prefix.trim_matches('/') {
+        "" => String::from("index"),
+        prefix => prefix.replace('/', "-"),
+    };
+    let out_path = out_dir.join(format!("{}.html", file_name));
+
+    std::fs::create_dir_all(out_dir)?;
+    std::fs::write(&out_path, rendered)?;
+
+    // Images are linked relative to the document, so the assets go next to it
+    for asset in project
+        .assets
+        .iter()
+        .filter(|a| a.path.starts_with("_assets"))
+    {
+        let path = out_dir.join(&asset.path);
+
+        if !path.exists() {
+            std::fs::create_dir_all(path.parent().unwrap())?;
+        }
+
+        std::fs::copy(working_dir.join(&asset.path), path)?;
+    }
+
+    reporter.report(Event::FileWritten {
+        path: &out_path,
+        duration: start.elapsed(),
+    })?;
+
+    Ok(())
+}
@@ -1,26 +1,154 @@
-use crate::builder::build;
+use crate::builder::{build, build_single_page};
+use crate::images::ImageOptions;
+use crate::manifest::Manifest;
+use crate::remote_specs;
+use crate::reporter::{Event, LogFormat, Reporter};
 use libdoctave::content_api::ViewMode;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct BuildArgs<'a, W: std::io::Write> {
     pub working_dir: PathBuf,
     pub out_dir: PathBuf,
+    /// Render only the pages under this URI prefix into a single HTML file
+    pub single_page: Option<String>,
+    /// Read when pages were last updated, and by whom, from git
+    pub git_metadata: bool,
+    /// Report the size of the content and write it to `stats.json`
+    pub stats: bool,
+    /// Report the health score of the project and write it to `health.json`
+    pub health: bool,
+    /// Generate resized versions of images and use them in pages
+    pub images: Option<ImageOptions>,
+    /// Rebuild the project without writing it, and compare the files to the
+    /// ones listed in this manifest
+    pub verify_manifest: Option<PathBuf>,
+    pub log_format: LogFormat,
     pub stdout: &'a mut W,
 }
 
-pub fn run<W: std::io::Write>(mut args: BuildArgs<W>) -> crate::Result<()> {
-    build(
-        &mut args.stdout,
-        &args.working_dir,
-        &args.out_dir,
+pub fn run<W: std::io::Write>(args: BuildArgs<W>) -> crate::Result<()> {
+    let mut reporter = args.log_format.reporter(args.stdout);
+
+    remote_specs::fetch(&args.working_dir, reporter.as_mut())?;
+
+    if let Some(prefix) = &args.single_page {
+        return build_single_page(reporter.as_mut(), &args.working_dir, &args.out_dir, prefix);
+    }
+
+    let opts = Options {
+        git_metadata: args.git_metadata,
+        stats: args.stats,
+        health: args.health,
+        images: args.images.as_ref(),
+    };
+
+    if let Some(path) = &args.verify_manifest {
+        return verify_manifest(reporter.as_mut(), &args.working_dir, path, &opts);
+    }
+
+    build_into(reporter.as_mut(), &args.working_dir, &args.out_dir, &opts)?;
+    Manifest::of_dir(&args.out_dir)?.write(&args.out_dir)
+}
+
+struct Options<'a> {
+    git_metadata: bool,
+    stats: bool,
+    health: bool,
+    images: Option<&'a ImageOptions>,
+}
+
+fn build_into(
+    reporter: &mut dyn Reporter,
+    working_dir: &Path,
+    out_dir: &Path,
+    opts: &Options,
+) -> crate::Result<()> {
+    let (project, analysis) = build(
+        reporter,
+        working_dir,
+        out_dir,
         ViewMode::Prod,
-    )
+        opts.git_metadata,
+        opts.images,
+    )?;
+
+    if opts.stats {
+        let stats = project.statistics();
+        let path = out_dir.join("stats.json");
+
+        let json = serde_json::to_string_pretty(&stats)
+            .map_err(|e| crate::Error::General(format!("Could not serialize stats: {}", e)))?;
+        std::fs::write(&path, json)?;
+
+        reporter.report(Event::Statistics {
+            stats: &stats,
+            path: &path,
+        })?;
+    }
+
+    if opts.health {
+        let report = project.health_report_for(&analysis, None);
+        let path = out_dir.join("health.json");
+
+        let json = serde_json::to_string_pretty(&report).map_err(|e| {
+            crate::Error::General(format!("Could not serialize health report: {}", e))
+        })?;
+        std::fs::write(&path, json)?;
+
+        reporter.report(Event::Health {
+            report: &report,
+            path: Some(&path),
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Rebuilds the project in a scratch directory, and compares the files to
+/// the ones listed in the manifest. The build directory is left as it is.
+fn verify_manifest(
+    reporter: &mut dyn Reporter,
+    working_dir: &Path,
+    manifest_path: &Path,
+    opts: &Options,
+) -> crate::Result<()> {
+    let expected = Manifest::read(manifest_path)?;
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let scratch_dir =
+        std::env::temp_dir().join(format!("docapella-verify-{}-{}", std::process::id(), nanos));
+
+    let rebuilt = build_into(reporter, working_dir, &scratch_dir, opts)
+        .and_then(|_| Manifest::of_dir(&scratch_dir));
+    let _ = std::fs::remove_dir_all(&scratch_dir);
+
+    let changes = expected.diff(&rebuilt?);
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    Err(crate::Error::General(format!(
+        "The build doesn't match {}. {} files differ:\n{}",
+        manifest_path.display(),
+        changes.len(),
+        changes
+            .iter()
+            .map(|change| format!("  {}", change))
+            .collect::<Vec<_>>()
+            .join("\n")
+    )))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::manifest::MANIFEST_FILE_NAME;
     use std::fs;
     use temp_dir::TempDir;
 
@@ -41,6 +169,13 @@ mod tests {
         let result = run(BuildArgs {
             working_dir: working_dir.path().to_path_buf(),
             out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
             stdout: &mut fake_stdout,
         });
 
@@ -71,6 +206,13 @@ mod tests {
         let result = run(BuildArgs {
             working_dir: working_dir.path().to_path_buf(),
             out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
             stdout: &mut fake_stdout,
         });
 
@@ -105,6 +247,13 @@ mod tests {
         let result = run(BuildArgs {
             working_dir: working_dir.path().to_path_buf(),
             out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
             stdout: &mut fake_stdout,
         });
 
@@ -135,6 +284,13 @@ mod tests {
         let result = run(BuildArgs {
             working_dir: working_dir.path().to_path_buf(),
             out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
             stdout: &mut fake_stdout,
         });
 
@@ -150,4 +306,374 @@ mod tests {
         // Check we didn't write any files
         assert!(!out_dir.path().join("index.html").exists());
     }
+
+    #[test]
+    fn logs_json_events() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World",
+        )
+        .unwrap();
+        fs::write(
+            working_dir.path().join("README.md"),
+            "[broken link](./foo.md)",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+
+        let result = run(BuildArgs {
+            working_dir: working_dir.path().to_path_buf(),
+            out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Json,
+            stdout: &mut fake_stdout,
+        });
+
+        assert!(result.is_err());
+
+        let events = String::from_utf8(fake_stdout.into_inner())
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        let names = events
+            .iter()
+            .map(|e| e["event"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["phase_started", "phase_started", "error", "phase_finished"]
+        );
+
+        assert_eq!(
+            events[2]["error"]["code"],
+            libdoctave::Error::BROKEN_INTERNAL_LINK
+        );
+        assert_eq!(events[2]["error"]["file"], "README.md");
+        assert_eq!(events[3]["phase"], "verify");
+        assert!(events[3]["duration_ms"].is_u64());
+    }
+
+    #[test]
+    fn builds_a_single_page_section() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("README.md"), "# Hello World").unwrap();
+        fs::create_dir(working_dir.path().join("guide")).unwrap();
+        fs::write(
+            working_dir.path().join("guide/README.md"),
+            "# Guide\n\nSee [install](./install.md).",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("guide/install.md"), "# Install").unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+
+        let result = run(BuildArgs {
+            working_dir: working_dir.path().to_path_buf(),
+            out_dir: out_dir.path().to_path_buf(),
+            single_page: Some("/guide".to_string()),
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
+            stdout: &mut fake_stdout,
+        });
+
+        if let Err(err) = result {
+            panic!("{:?}", err);
+        }
+
+        let html = fs::read_to_string(out_dir.path().join("guide.html")).unwrap();
+
+        assert!(html.contains(r#"id="guide-install""#));
+        assert!(html.contains(r##"href="#guide-install""##));
+        assert!(!out_dir.path().join("index.html").exists());
+    }
+
+    #[test]
+    fn writes_stats_when_asked_to() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("README.md"), "# Hello World").unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+        fs::create_dir(working_dir.path().join("_assets")).unwrap();
+        fs::write(working_dir.path().join("_assets/logo.png"), [0xff; 2048]).unwrap();
+
+        let result = run(BuildArgs {
+            working_dir: working_dir.path().to_path_buf(),
+            out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: true,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
+            stdout: &mut fake_stdout,
+        });
+
+        if let Err(err) = result {
+            panic!("{:?}", err);
+        }
+
+        let stats: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(out_dir.path().join("stats.json")).unwrap())
+                .unwrap();
+
+        assert_eq!(stats["page_count"], 1);
+        assert_eq!(stats["assets_size_bytes"], 2048);
+        assert_eq!(stats["largest_assets"][0]["path"], "_assets/logo.png");
+
+        let fake_stdout = String::from_utf8(fake_stdout.into_inner()).unwrap();
+
+        assert!(fake_stdout.contains("2.0 KB  _assets/logo.png"));
+    }
+
+    #[test]
+    fn writes_health_report_when_asked_to() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World",
+        )
+        .unwrap();
+        fs::write(
+            working_dir.path().join("README.md"),
+            "---\nmeta:\n  description: The start\n---\n# Hello World",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+
+        let result = run(BuildArgs {
+            working_dir: working_dir.path().to_path_buf(),
+            out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: true,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
+            stdout: &mut fake_stdout,
+        });
+
+        if let Err(err) = result {
+            panic!("{:?}", err);
+        }
+
+        let health: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(out_dir.path().join("health.json")).unwrap())
+                .unwrap();
+
+        assert_eq!(health["score"], 100);
+        assert_eq!(health["page_count"], 1);
+        assert_eq!(health["search_coverage"]["percent"], 100.0);
+
+        let fake_stdout = String::from_utf8(fake_stdout.into_inner()).unwrap();
+
+        assert!(fake_stdout.contains("Health score 100/100"));
+    }
+
+    #[test]
+    fn renders_images_with_their_variants() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World",
+        )
+        .unwrap();
+        fs::write(
+            working_dir.path().join("README.md"),
+            "# Hello World\n\n![Shot](/_assets/shot.png)",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+        fs::create_dir(working_dir.path().join("_assets")).unwrap();
+        image::RgbImage::new(100, 50)
+            .save(working_dir.path().join("_assets/shot.png"))
+            .unwrap();
+
+        let result = run(BuildArgs {
+            working_dir: working_dir.path().to_path_buf(),
+            out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: Some(ImageOptions {
+                widths: vec![40],
+                webp: true,
+            }),
+            verify_manifest: None,
+            log_format: LogFormat::Human,
+            stdout: &mut fake_stdout,
+        });
+
+        if let Err(err) = result {
+            panic!("{:?}", err);
+        }
+
+        let html = fs::read_to_string(out_dir.path().join("index.html")).unwrap();
+
+        assert!(html.contains("/_assets/shot-40w.png"), "{}", html);
+        assert!(html.contains(r#"type="image/webp""#), "{}", html);
+        assert!(out_dir.path().join("_assets/shot-40w.webp").exists());
+    }
+
+    #[test]
+    fn writes_the_quick_nav_manifest_when_enabled() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World\nsearch:\n  quick_nav: true\n",
+        )
+        .unwrap();
+        fs::write(
+            working_dir.path().join("README.md"),
+            "# Hello World\n\n## Setup",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+
+        let result = run(BuildArgs {
+            working_dir: working_dir.path().to_path_buf(),
+            out_dir: out_dir.path().to_path_buf(),
+            single_page: None,
+            git_metadata: false,
+            stats: false,
+            health: false,
+            images: None,
+            verify_manifest: None,
+            log_format: LogFormat::Human,
+            stdout: &mut fake_stdout,
+        });
+
+        if let Err(err) = result {
+            panic!("{:?}", err);
+        }
+
+        let manifest: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(out_dir.path().join("quick-nav.json")).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(manifest[0]["kind"], "page");
+        assert_eq!(manifest[0]["uri"], "/");
+        assert_eq!(manifest[1]["kind"], "heading");
+        assert_eq!(manifest[1]["uri"], "/#setup");
+    }
+
+    #[test]
+    fn writes_a_manifest_that_later_builds_are_checked_against() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello World",
+        )
+        .unwrap();
+        fs::write(
+            working_dir.path().join("README.md"),
+            "# Hello World\n\n![Logo](/_assets/logo.png)",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+        fs::create_dir(working_dir.path().join("_assets")).unwrap();
+        fs::write(
+            working_dir.path().join("_assets/logo.png"),
+            [0x89, 0x50, 0xff],
+        )
+        .unwrap();
+
+        fn build_args<'a>(
+            working_dir: &TempDir,
+            out_dir: &TempDir,
+            verify_manifest: Option<PathBuf>,
+            stdout: &'a mut std::io::Cursor<Vec<u8>>,
+        ) -> BuildArgs<'a, std::io::Cursor<Vec<u8>>> {
+            BuildArgs {
+                working_dir: working_dir.path().to_path_buf(),
+                out_dir: out_dir.path().to_path_buf(),
+                single_page: None,
+                git_metadata: false,
+                stats: false,
+                health: false,
+                images: None,
+                verify_manifest,
+                log_format: LogFormat::Human,
+                stdout,
+            }
+        }
+
+        run(build_args(&working_dir, &out_dir, None, &mut fake_stdout)).unwrap();
+
+        let manifest_path = out_dir.path().join(MANIFEST_FILE_NAME);
+        let manifest = Manifest::read(&manifest_path).unwrap();
+
+        assert!(manifest.files.iter().any(|f| f.path == "index.html"));
+        assert!(manifest.files.iter().any(|f| f.path == "_assets/logo.png"));
+
+        // Rebuilding the same files gives the same output
+        let saved_manifest = working_dir.path().join("saved-manifest.json");
+        fs::copy(&manifest_path, &saved_manifest).unwrap();
+        run(build_args(
+            &working_dir,
+            &out_dir,
+            Some(saved_manifest.clone()),
+            &mut fake_stdout,
+        ))
+        .unwrap();
+
+        fs::write(working_dir.path().join("README.md"), "# Changed").unwrap();
+
+        match run(build_args(
+            &working_dir,
+            &out_dir,
+            Some(saved_manifest),
+            &mut fake_stdout,
+        )) {
+            Err(crate::Error::General(message)) => {
+                assert!(message.contains("changed: index.html"), "{}", message);
+                assert!(!message.contains("logo.png"), "{}", message);
+            }
+            other => panic!("Expected the manifest not to match, got {:?}", other),
+        }
+    }
 }
@@ -1,40 +1,114 @@
 use crate::builder::build;
+use crate::error_page;
+use crate::remote_specs;
+use crate::reporter::{Event, LogFormat};
+use crate::watcher::{watch, WatcherMessage};
 use bus::Bus;
 use libdoctave::content_api::ViewMode;
+use libdoctave::settings::Redirect;
+use libdoctave::ProjectDiff;
 use std::path::PathBuf;
 use std::sync::{
     mpsc::{self, RecvTimeoutError},
     Arc, Mutex,
 };
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+/// Tells connected browsers which pages to reload after a rebuild.
+#[derive(Debug, Clone, PartialEq)]
+enum ReloadSignal {
+    /// Something shown on every page changed, like the navigation
+    AllPages,
+    /// Only these pages, by URI path
+    Pages(Vec<String>),
+}
+
+impl ReloadSignal {
+    /// Returns `None` if nothing that was rendered changed.
+    fn from_diff(diff: &ProjectDiff) -> Option<Self> {
+        if diff.affects_all_pages() {
+            return Some(ReloadSignal::AllPages);
+        }
+
+        let pages = diff.affected_pages();
+        if pages.is_empty() {
+            None
+        } else {
+            Some(ReloadSignal::Pages(
+                pages.into_iter().map(|p| p.to_string()).collect(),
+            ))
+        }
+    }
+
+    /// The payload sent to the browser, where `null` pages means every page.
+    fn event_data(&self) -> String {
+        let pages = match self {
+            ReloadSignal::AllPages => None,
+            ReloadSignal::Pages(pages) => Some(pages),
+        };
 
-#[derive(Debug)]
-enum WatcherMessage {
-    RebuildNeeded,
-    WatchError(String),
+        serde_json::json!({ "pages": pages }).to_string()
+    }
+}
+
+/// The error page served in place of every page while the project doesn't
+/// build, or `None` when the last build succeeded.
+type ErrorPage = Arc<Mutex<Option<String>>>;
+
+/// The redirects of the last successful build. Only followed for paths
+/// without a built file, so pages take precedence like in
+/// `Project::resolve_redirect`.
+type Redirects = Arc<Mutex<Vec<Redirect>>>;
+
+fn redirects_of(project: &libdoctave::Project) -> Vec<Redirect> {
+    project
+        .redirects()
+        .into_iter()
+        .map(|(from, to)| Redirect { from, to })
+        .collect()
 }
 
-#[derive(Debug, Clone)]
-struct ReloadSignal;
+pub const DEFAULT_HOST: &str = "localhost";
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// How many ports after the requested one are tried when it's taken
+const PORT_ATTEMPTS: u16 = 20;
+
+/// How often OpenAPI specs set with `spec_url` are fetched again, by default
+pub const DEFAULT_OPENAPI_REFRESH_SECS: u64 = 60;
 
 pub struct DevArgs<'a, W: std::io::Write> {
     pub working_dir: PathBuf,
-    pub port: Option<u16>,
+    pub host: String,
+    pub port: u16,
+    /// Fail instead of trying the next port when `port` is taken
+    pub strict_port: bool,
+    /// Open the site in a browser once the server is running
+    pub open: bool,
+    /// How often remote OpenAPI specs are fetched again. `None` only fetches
+    /// them at startup, and when Enter is pressed.
+    pub openapi_refresh: Option<Duration>,
+    pub log_format: LogFormat,
     pub stdout: &'a mut W,
 }
 
-pub fn run<W: std::io::Write>(mut args: DevArgs<W>) -> crate::Result<()> {
-    let port = args.port.unwrap_or(8080);
+pub fn run<W: std::io::Write>(args: DevArgs<W>) -> crate::Result<()> {
     let build_dir = args.working_dir.join("_build");
+    let mut reporter = args.log_format.reporter(args.stdout);
+
+    remote_specs::fetch(&args.working_dir, reporter.as_mut())?;
+    let mut last_fetch = Instant::now();
+    let has_remote_specs = !remote_specs::remote_specs(&args.working_dir).is_empty();
 
     // Build the project first
-    writeln!(args.stdout, "Building project...")?;
-    build(
-        &mut args.stdout,
+    let (mut project, _) = build(
+        reporter.as_mut(),
         &args.working_dir,
         &build_dir,
         ViewMode::Dev,
+        false,
+        None,
     )?;
 
     // Create watcher communication channel
@@ -42,51 +116,96 @@ pub fn run<W: std::io::Write>(mut args: DevArgs<W>) -> crate::Result<()> {
 
     // Create broadcast bus for reload signals
     let reload_bus = Arc::new(Mutex::new(Bus::<ReloadSignal>::new(10)));
+    let error_page: ErrorPage = Arc::new(Mutex::new(None));
+    let redirects: Redirects = Arc::new(Mutex::new(redirects_of(&project)));
+
+    // Bind before spawning the server, so that the address we print is the
+    // one that is actually used
+    let (server, port) = bind_server(&args.host, args.port, args.strict_port)?;
+
+    if port != args.port {
+        reporter.report(Event::Warning {
+            message: &format!("Port {} is in use, using port {} instead", args.port, port),
+            owner: None,
+        })?;
+    }
 
     // Spawn HTTP server thread
     let http_build_dir = build_dir.clone();
     let http_reload_bus = reload_bus.clone();
-    let http_handle =
-        thread::spawn(move || spawn_http_server(http_build_dir, port, http_reload_bus));
+    let http_error_page = error_page.clone();
+    let http_redirects = redirects.clone();
+    let http_handle = thread::spawn(move || {
+        spawn_http_server(
+            server,
+            http_build_dir,
+            http_reload_bus,
+            http_error_page,
+            http_redirects,
+        )
+    });
 
     // Spawn file watcher thread
     let watcher_working_dir = args.working_dir.clone();
-    let watcher_handle = thread::spawn(move || spawn_file_watcher(watcher_working_dir, watcher_tx));
+    let watcher_build_dir = build_dir.clone();
+    let stdin_tx = watcher_tx.clone();
+    let watcher_handle =
+        thread::spawn(move || watch(&watcher_working_dir, &watcher_build_dir, watcher_tx));
+
+    if has_remote_specs {
+        thread::spawn(move || {
+            for _ in std::io::stdin().lines() {
+                if stdin_tx.send(WatcherMessage::FetchSpecsRequested).is_err() {
+                    break;
+                }
+            }
+        });
+    }
 
-    writeln!(
-        args.stdout,
-        "Dev server running on http://localhost:{}",
-        port
-    )?;
-    writeln!(args.stdout, "Watching for file changes...")?;
+    let url = local_url(&args.host, port);
+    let network_url = network_url(&args.host, port);
+
+    reporter.report(Event::ServerStarted {
+        url: &url,
+        network_url: network_url.as_deref(),
+    })?;
+
+    if args.open {
+        if let Err(e) = open_browser(&url) {
+            reporter.report(Event::Warning {
+                message: &format!("Failed to open a browser: {}", e),
+                owner: None,
+            })?;
+        }
+    }
 
     // Main coordination loop
     loop {
+        let mut rebuild = false;
+
         match watcher_rx.recv_timeout(Duration::from_secs(5)) {
             Ok(WatcherMessage::RebuildNeeded) => {
-                writeln!(args.stdout, "Rebuilding...")?;
-
-                match build(
-                    &mut args.stdout,
-                    &args.working_dir,
-                    &build_dir,
-                    ViewMode::Dev,
-                ) {
-                    Ok(_) => {
-                        // Build function already prints "Build complete" message
-                        // Send reload signal to all connected browsers
-                        if let Ok(mut bus) = reload_bus.lock() {
-                            bus.broadcast(ReloadSignal);
-                        }
-                    }
-                    Err(e) => {
-                        writeln!(args.stdout, "Build failed: {:?}", e)?;
-                        // No reload signal on build failure
+                // Changes made during the previous build are covered by this one
+                for message in watcher_rx.try_iter() {
+                    if let WatcherMessage::WatchError(e) = message {
+                        reporter.report(Event::Warning {
+                            message: &format!("Watch error: {}", e),
+                            owner: None,
+                        })?;
                     }
                 }
+
+                rebuild = true;
+            }
+            Ok(WatcherMessage::FetchSpecsRequested) => {
+                last_fetch = Instant::now();
+                rebuild = remote_specs::fetch(&args.working_dir, reporter.as_mut())?;
             }
             Ok(WatcherMessage::WatchError(e)) => {
-                writeln!(args.stdout, "Watch error: {}", e)?;
+                reporter.report(Event::Warning {
+                    message: &format!("Watch error: {}", e),
+                    owner: None,
+                })?;
             }
             Err(RecvTimeoutError::Timeout) => {
                 // Periodic health check every 5 seconds
@@ -98,6 +217,15 @@ pub fn run<W: std::io::Write>(mut args: DevArgs<W>) -> crate::Result<()> {
                         "File watcher thread died".to_string(),
                     ));
                 }
+
+                if has_remote_specs
+                    && args
+                        .openapi_refresh
+                        .is_some_and(|interval| last_fetch.elapsed() >= interval)
+                {
+                    last_fetch = Instant::now();
+                    rebuild = remote_specs::fetch(&args.working_dir, reporter.as_mut())?;
+                }
             }
             Err(RecvTimeoutError::Disconnected) => {
                 return Err(crate::Error::General(
@@ -105,17 +233,173 @@ pub fn run<W: std::io::Write>(mut args: DevArgs<W>) -> crate::Result<()> {
                 ));
             }
         }
+
+        if rebuild {
+            reporter.report(Event::ChangeDetected)?;
+
+            match build(
+                reporter.as_mut(),
+                &args.working_dir,
+                &build_dir,
+                ViewMode::Dev,
+                false,
+                None,
+            ) {
+                Ok((rebuilt, _)) => {
+                    // Build function already prints "Build complete" message
+                    let was_broken = error_page
+                        .lock()
+                        .map(|mut page| page.take().is_some())
+                        .unwrap_or(false);
+
+                    // Tell connected browsers which pages to reload. Every
+                    // page is showing the error page if the last build failed.
+                    let signal = if was_broken {
+                        Some(ReloadSignal::AllPages)
+                    } else {
+                        ReloadSignal::from_diff(&project.diff_pages(&rebuilt))
+                    };
+
+                    if let Some(signal) = signal {
+                        if let Ok(mut bus) = reload_bus.lock() {
+                            bus.broadcast(signal);
+                        }
+                    }
+
+                    if let Ok(mut redirects) = redirects.lock() {
+                        *redirects = redirects_of(&rebuilt);
+                    }
+
+                    project = rebuilt;
+                }
+                Err(e) => {
+                    reporter.report_failure(&e)?;
+
+                    // Show the errors in the browser until the next build
+                    // succeeds
+                    if let crate::Error::FatalBuildError(errors) = &e {
+                        if let Ok(mut page) = error_page.lock() {
+                            *page = Some(error_page::render(errors, &args.working_dir));
+                        }
+
+                        if let Ok(mut bus) = reload_bus.lock() {
+                            bus.broadcast(ReloadSignal::AllPages);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Binds the server to the port, or to the next free one unless `strict_port`
+/// is set. Returns the server along with the port it's listening on.
+fn bind_server(
+    host: &str,
+    port: u16,
+    strict_port: bool,
+) -> crate::Result<(tiny_http::Server, u16)> {
+    let attempts = if strict_port { 1 } else { PORT_ATTEMPTS };
+
+    for candidate in (port..=u16::MAX).take(attempts as usize) {
+        match tiny_http::Server::http((host, candidate)) {
+            Ok(server) => {
+                // Port 0 means any free port
+                let bound = server
+                    .server_addr()
+                    .to_ip()
+                    .map(|addr| addr.port())
+                    .unwrap_or(candidate);
+
+                return Ok((server, bound));
+            }
+            Err(e) if is_addr_in_use(e.as_ref()) => continue,
+            Err(e) => {
+                return Err(crate::Error::General(format!(
+                    "Failed to start server on {}:{}: {}",
+                    host, candidate, e
+                )))
+            }
+        }
     }
+
+    Err(crate::Error::General(if strict_port {
+        format!("Port {} is already in use", port)
+    } else {
+        format!(
+            "Ports {} to {} are already in use. Pick another one with --port",
+            port,
+            port.saturating_add(attempts - 1)
+        )
+    }))
+}
+
+fn is_addr_in_use(error: &(dyn std::error::Error + 'static)) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::AddrInUse)
+}
+
+/// The URL to open on this machine. Wildcard addresses aren't something a
+/// browser can connect to, so they are shown as `localhost`.
+fn local_url(host: &str, port: u16) -> String {
+    let host = match host {
+        "0.0.0.0" | "::" | "[::]" => "localhost",
+        host if host.contains(':') && !host.starts_with('[') => {
+            return format!("http://[{}]:{}", host, port)
+        }
+        host => host,
+    };
+
+    format!("http://{}:{}", host, port)
+}
+
+/// The URL other devices on the network can use, when the server is bound to
+/// all interfaces.
+fn network_url(host: &str, port: u16) -> Option<String> {
+    if !matches!(host, "0.0.0.0" | "::" | "[::]") {
+        return None;
+    }
+
+    // Connecting a UDP socket doesn't send anything, but picks the interface
+    // that routes outside this machine
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+
+    if ip.is_loopback() || ip.is_unspecified() {
+        None
+    } else {
+        Some(format!("http://{}:{}", ip, port))
+    }
+}
+
+fn open_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+
+    command
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
 }
 
 fn spawn_http_server(
+    server: tiny_http::Server,
     build_dir: PathBuf,
-    port: u16,
     reload_bus: Arc<Mutex<Bus<ReloadSignal>>>,
+    error_page: ErrorPage,
+    redirects: Redirects,
 ) -> Result<(), String> {
-    let server = tiny_http::Server::http(format!("localhost:{}", port))
-        .map_err(|e| format!("Failed to start server: {}", e))?;
-
     loop {
         let request = server
             .recv()
@@ -133,84 +417,18 @@ fn spawn_http_server(
                 });
             }
             _ => {
-                let response = handle_request(&request, &build_dir);
+                let page = error_page.lock().ok().and_then(|page| page.clone());
+
+                let response = match page {
+                    Some(page) => error_response(page),
+                    None => handle_request(&request, &build_dir, &redirects),
+                };
                 let _ = request.respond(response);
             }
         }
     }
 }
 
-fn spawn_file_watcher(
-    working_dir: PathBuf,
-    watcher_tx: mpsc::Sender<WatcherMessage>,
-) -> Result<(), String> {
-    use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode, DebounceEventResult};
-
-    let mut debouncer = new_debouncer(
-        Duration::from_millis(150),
-        move |res: DebounceEventResult| {
-            match res {
-                Ok(events) => {
-                    // Filter events to only rebuild-worthy files
-                    let should_rebuild = events
-                        .iter()
-                        .any(|event| should_rebuild_for_path(&event.path));
-
-                    if should_rebuild {
-                        // Just notify main thread that rebuild is needed
-                        let _ = watcher_tx.send(WatcherMessage::RebuildNeeded);
-                    }
-                }
-                Err(e) => {
-                    let _ = watcher_tx
-                        .send(WatcherMessage::WatchError(format!("Watch error: {:?}", e)));
-                }
-            }
-        },
-    )
-    .map_err(|e| format!("Failed to create file watcher: {:?}", e))?;
-
-    // Watch the working directory recursively
-    debouncer
-        .watcher()
-        .watch(&working_dir, RecursiveMode::Recursive)
-        .map_err(|e| format!("Failed to start watching: {:?}", e))?;
-
-    // Keep the watcher alive
-    loop {
-        std::thread::sleep(Duration::from_secs(1));
-    }
-}
-
-fn should_rebuild_for_path(path: &std::path::Path) -> bool {
-    // Skip if path contains ignored directories
-    if path.components().any(|component| {
-        let name = component.as_os_str();
-        name == "_build" || name == ".git" || name == "node_modules"
-    }) {
-        return false;
-    }
-
-    // Check file extension
-    if let Some(extension) = path.extension() {
-        if let Some(ext_str) = extension.to_str() {
-            return matches!(
-                ext_str,
-                "md" | "yaml" | "yml" | "json" | "png" | "jpg" | "jpeg" | "svg" | "css" | "js"
-            );
-        }
-    }
-
-    // Also watch config files without extensions or special names
-    if let Some(file_name) = path.file_name() {
-        if let Some(name_str) = file_name.to_str() {
-            return matches!(name_str, "docapella.yaml" | "doctave.yaml");
-        }
-    }
-
-    false
-}
-
 fn handle_sse_connection(request: tiny_http::Request, mut reload_rx: bus::BusReader<ReloadSignal>) {
     use std::io::Write;
 
@@ -231,9 +449,10 @@ fn handle_sse_connection(request: tiny_http::Request, mut reload_rx: bus::BusRea
     // Listen for reload signals
     loop {
         match reload_rx.recv() {
-            Ok(_) => {
+            Ok(signal) => {
                 // Send reload message to browser
-                if writer.write_all(b"data: reload\n\n").is_err() {
+                let message = format!("data: {}\n\n", signal.event_data());
+                if writer.write_all(message.as_bytes()).is_err() {
                     break;
                 }
                 if writer.flush().is_err() {
@@ -251,6 +470,7 @@ fn handle_sse_connection(request: tiny_http::Request, mut reload_rx: bus::BusRea
 fn handle_request(
     request: &tiny_http::Request,
     build_dir: &std::path::Path,
+    redirects: &Redirects,
 ) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
     let url = request.url();
     let url_without_query = url.split('?').next().unwrap_or(url);
@@ -265,6 +485,21 @@ fn handle_request(
             )
         }
         Err(_) => {
+            let destination = redirects.lock().ok().and_then(|redirects| {
+                redirects
+                    .iter()
+                    .find_map(|r| r.destination_for(url_without_query))
+            });
+
+            if let Some(destination) = destination {
+                return tiny_http::Response::from_data(vec![])
+                    .with_status_code(302)
+                    .with_header(
+                        tiny_http::Header::from_bytes(&b"Location"[..], destination.as_bytes())
+                            .expect("Invalid location header"),
+                    );
+            }
+
             let not_found = b"404 Not Found";
             tiny_http::Response::from_data(not_found.to_vec())
                 .with_status_code(404)
@@ -276,6 +511,15 @@ fn handle_request(
     }
 }
 
+fn error_response(page: String) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    tiny_http::Response::from_data(page.into_bytes())
+        .with_status_code(500)
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/html; charset=utf-8"[..])
+                .expect("Invalid content type header"),
+        )
+}
+
 fn resolve_path(url: &str, build_dir: &std::path::Path) -> PathBuf {
     let clean_url = url.trim_start_matches('/');
 
@@ -326,3 +570,94 @@ fn content_type_for_path(path: &std::path::Path) -> String {
     }
     .to_string()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn falls_back_to_the_next_free_port() {
+        let (taken, port) = bind_server("127.0.0.1", 0, true).unwrap();
+
+        let (_server, fallback) = bind_server("127.0.0.1", port, false).unwrap();
+        assert_ne!(fallback, port);
+        assert!(fallback > port);
+
+        drop(taken);
+    }
+
+    #[test]
+    fn fails_on_a_taken_port_with_strict_port() {
+        let (_taken, port) = bind_server("127.0.0.1", 0, true).unwrap();
+
+        let err = bind_server("127.0.0.1", port, true).unwrap_err();
+        assert_eq!(err.to_string(), format!("Port {} is already in use", port));
+    }
+
+    #[test]
+    fn shows_wildcard_hosts_as_localhost() {
+        assert_eq!(local_url("localhost", 8080), "http://localhost:8080");
+        assert_eq!(local_url("0.0.0.0", 8081), "http://localhost:8081");
+        assert_eq!(local_url("192.168.1.5", 3000), "http://192.168.1.5:3000");
+        assert_eq!(local_url("::1", 3000), "http://[::1]:3000");
+        assert_eq!(network_url("localhost", 8080), None);
+    }
+
+    #[test]
+    fn settings_changes_reach_the_next_build() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        let mut out = std::io::Cursor::new(Vec::new());
+        let mut reporter = LogFormat::Human.reporter(&mut out);
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello\nvariables:\n  product: Acme\n",
+        )
+        .unwrap();
+        fs::write(
+            working_dir.path().join("README.md"),
+            "Welcome to {@vars.product}.",
+        )
+        .unwrap();
+        fs::write(working_dir.path().join("navigation.yaml"), "").unwrap();
+
+        let (project, _) = build(
+            reporter.as_mut(),
+            working_dir.path(),
+            out_dir.path(),
+            ViewMode::Dev,
+            false,
+            None,
+        )
+        .unwrap();
+        let index = fs::read_to_string(out_dir.path().join("index.html")).unwrap();
+        assert!(index.contains("Welcome to Acme"));
+
+        fs::write(
+            working_dir.path().join("docapella.yaml"),
+            "---\ntitle: Hello\nvariables:\n  product: Globex\nredirects:\n  - from: /old\n    to: /\n",
+        )
+        .unwrap();
+
+        let (rebuilt, _) = build(
+            reporter.as_mut(),
+            working_dir.path(),
+            out_dir.path(),
+            ViewMode::Dev,
+            false,
+            None,
+        )
+        .unwrap();
+        let index = fs::read_to_string(out_dir.path().join("index.html")).unwrap();
+        assert!(index.contains("Welcome to Globex"));
+
+        assert_eq!(
+            ReloadSignal::from_diff(&project.diff_pages(&rebuilt)),
+            Some(ReloadSignal::AllPages)
+        );
+        assert_eq!(redirects_of(&rebuilt).len(), 1);
+    }
+}
@@ -1,38 +1,102 @@
-use crate::Result;
-use libdoctave::Project;
+use crate::{Error, Result};
+use libdoctave::{Boilerplate, Project, SETTINGS_FILE_NAME};
 use owo_colors::OwoColorize as _;
 
 use std::path::Path;
 
 pub struct InitArgs<'a, W: std::io::Write> {
     pub working_dir: &'a Path,
+    pub template: Boilerplate,
     pub title: Option<&'a str>,
+    /// Add the template's files to a directory that already has a project.
+    /// Existing files are never overwritten.
+    pub force: bool,
     pub stdout: &'a mut W,
 }
 
 pub fn run<W: std::io::Write>(args: InitArgs<W>) -> Result<()> {
+    if !args.force && args.working_dir.join(SETTINGS_FILE_NAME).exists() {
+        return Err(Error::General(format!(
+            "{} already contains a {}. Use --force to add any missing files from the template.",
+            args.working_dir.display(),
+            SETTINGS_FILE_NAME
+        )));
+    }
+
     write!(args.stdout, "Creating project...")?;
 
-    for page in Project::boilerplate_file_list() {
-        let path = args.working_dir.join(page.0);
-        std::fs::create_dir_all(path.parent().unwrap())?;
-        std::fs::write(path, page.1)?;
+    let mut skipped = vec![];
+
+    for (path, mut content) in Project::boilerplate_file_list_for(args.template) {
+        let full_path = args.working_dir.join(&path);
+
+        // Never overwrite the user's own files
+        if full_path.exists() {
+            skipped.push(path);
+            continue;
+        }
+
+        if path == Path::new(SETTINGS_FILE_NAME) {
+            if let Some(title) = args.title {
+                content = with_title(&content, title).into_bytes();
+            }
+        }
+
+        std::fs::create_dir_all(full_path.parent().unwrap())?;
+        std::fs::write(full_path, content)?;
+    }
+
+    writeln!(args.stdout, "{}", "Done ✓".green())?;
+
+    for path in skipped {
+        writeln!(
+            args.stdout,
+            "Skipped {}, which already exists",
+            path.display()
+        )?;
     }
 
     writeln!(
         args.stdout,
-        "{}\nRun {} to preview your documentation",
-        "Done ✓".green(),
+        "Run {} to preview your documentation",
         "`docapella dev`".bold().blue()
     )?;
 
     Ok(())
 }
 
+/// Replaces the title in the template's settings file.
+fn with_title(settings: &[u8], title: &str) -> String {
+    let title = if title
+        .chars()
+        .all(|c| c.is_alphanumeric() || " -_.,!?()".contains(c))
+        && !title.starts_with(' ')
+        && !title.is_empty()
+    {
+        title.to_string()
+    } else {
+        // JSON strings are valid YAML, so this quotes and escapes the title
+        serde_json::to_string(title).expect("strings serialize")
+    };
+
+    String::from_utf8_lossy(settings)
+        .lines()
+        .map(|line| {
+            if line.starts_with("title:") {
+                format!("title: {}", title)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::read_to_string;
+    use std::fs::{self, read_to_string};
     use temp_dir::TempDir;
 
     #[test]
@@ -43,7 +107,9 @@ mod tests {
 
         let result = run(InitArgs {
             working_dir,
+            template: Boilerplate::Basic,
             title: Some("Docapella Starter Template"),
+            force: false,
             stdout: &mut fake_stdout,
         });
 
@@ -62,7 +128,9 @@ mod tests {
 
         let result = run(InitArgs {
             working_dir,
+            template: Boilerplate::Basic,
             title: Some("My Project"),
+            force: false,
             stdout: &mut fake_stdout,
         });
 
@@ -86,7 +154,9 @@ mod tests {
 
         let result = run(InitArgs {
             working_dir,
+            template: Boilerplate::Basic,
             title: Some("My Project"),
+            force: false,
             stdout: &mut fake_stdout,
         });
 
@@ -97,4 +167,106 @@ mod tests {
         assert!(output.contains("Creating project..."));
         assert!(output.contains("Done"));
     }
+
+    fn init(working_dir: &Path, template: Boilerplate, force: bool) -> Result<String> {
+        let mut fake_stdout = std::io::Cursor::new(Vec::new());
+
+        run(InitArgs {
+            working_dir,
+            template,
+            title: None,
+            force,
+            stdout: &mut fake_stdout,
+        })?;
+
+        Ok(String::from_utf8(fake_stdout.into_inner()).unwrap())
+    }
+
+    #[test]
+    fn sets_the_title() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut fake_stdout = std::io::sink();
+
+        run(InitArgs {
+            working_dir: temp_dir.path(),
+            template: Boilerplate::Basic,
+            title: Some("Acme: Docs"),
+            force: false,
+            stdout: &mut fake_stdout,
+        })
+        .unwrap();
+
+        let contents = read_to_string(temp_dir.path().join("docapella.yaml")).unwrap();
+
+        assert!(contents.contains("title: \"Acme: Docs\"\n"), "{}", contents);
+        assert_eq!(contents.matches("title:").count(), 1);
+    }
+
+    #[test]
+    fn creates_an_api_reference() {
+        let temp_dir = TempDir::new().unwrap();
+
+        init(temp_dir.path(), Boilerplate::ApiReference, false).unwrap();
+
+        let settings = read_to_string(temp_dir.path().join("docapella.yaml")).unwrap();
+        let navigation = read_to_string(temp_dir.path().join("navigation.yaml")).unwrap();
+
+        assert!(settings.contains("spec_file: openapi.yaml"));
+        assert!(navigation.contains("open_api_spec: openapi.yaml"));
+        assert!(temp_dir.path().join("openapi.yaml").exists());
+    }
+
+    #[test]
+    fn creates_a_multi_tab_project() {
+        let temp_dir = TempDir::new().unwrap();
+
+        init(temp_dir.path(), Boilerplate::MultiTab, false).unwrap();
+
+        let settings = read_to_string(temp_dir.path().join("docapella.yaml")).unwrap();
+
+        assert!(settings.contains("tabs:"));
+        assert!(temp_dir.path().join("api/navigation.yaml").exists());
+    }
+
+    #[test]
+    fn creates_a_kitchen_sink_project() {
+        let temp_dir = TempDir::new().unwrap();
+
+        init(temp_dir.path(), Boilerplate::KitchenSink, false).unwrap();
+
+        assert!(temp_dir.path().join("components.md").exists());
+        assert!(temp_dir.path().join("_components/card.md").exists());
+        assert!(temp_dir.path().join("openapi.yaml").exists());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_an_existing_project() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("docapella.yaml"), "title: Mine").unwrap();
+
+        let error = init(temp_dir.path(), Boilerplate::Basic, false).unwrap_err();
+
+        assert!(error.to_string().contains("--force"), "{}", error);
+        assert!(!temp_dir.path().join("README.md").exists());
+    }
+
+    #[test]
+    fn force_only_adds_missing_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("docapella.yaml"), "title: Mine").unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# Mine").unwrap();
+
+        let output = init(temp_dir.path(), Boilerplate::Basic, true).unwrap();
+
+        assert_eq!(
+            read_to_string(temp_dir.path().join("docapella.yaml")).unwrap(),
+            "title: Mine"
+        );
+        assert_eq!(
+            read_to_string(temp_dir.path().join("README.md")).unwrap(),
+            "# Mine"
+        );
+        assert!(temp_dir.path().join("navigation.yaml").exists());
+        assert!(output.contains("Skipped README.md"), "{}", output);
+    }
 }
@@ -0,0 +1,126 @@
+use crate::{Error, Result};
+use libdoctave::open_api::diff::ApiDiff;
+
+use std::path::{Path, PathBuf};
+
+/// How the changelog is written to stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "lowercase")]
+pub enum DiffFormat {
+    /// A Markdown page, grouped by breaking and non-breaking changes
+    #[default]
+    Md,
+    Json,
+}
+
+pub struct OpenApiDiffArgs<'a, W: std::io::Write> {
+    pub old: PathBuf,
+    pub new: PathBuf,
+    pub format: DiffFormat,
+    pub stdout: &'a mut W,
+}
+
+pub fn run<W: std::io::Write>(args: OpenApiDiffArgs<W>) -> Result<()> {
+    let old = parse_spec(&args.old)?;
+    let new = parse_spec(&args.new)?;
+
+    let diff = ApiDiff::between(&old, &new);
+
+    match args.format {
+        DiffFormat::Md => write!(args.stdout, "{}", diff.to_markdown())?,
+        DiffFormat::Json => {
+            serde_json::to_writer_pretty(&mut *args.stdout, &diff)
+                .map_err(|e| Error::General(format!("Could not serialize diff: {}", e)))?;
+            writeln!(args.stdout)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_spec(path: &Path) -> Result<openapi_parser::OpenAPI> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| Error::General(format!("Could not read {}: {}", path.display(), e)))?;
+
+    let parsed = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => openapi_parser::openapi30::parser::parse_json(&content),
+        Some("yaml") | Some("yml") => openapi_parser::openapi30::parser::parse_yaml(&content),
+        _ => {
+            return Err(Error::General(format!(
+                "Could not parse {}: OpenAPI spec must be a JSON or YAML file.",
+                path.display()
+            )))
+        }
+    };
+
+    parsed.map_err(|e| Error::General(format!("Could not parse {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use temp_dir::TempDir;
+
+    const OLD: &str = r#"{
+        "openapi": "3.0.0",
+        "info": { "title": "Pets", "version": "1.0.0" },
+        "paths": {
+            "/pets": {
+                "get": { "responses": { "200": { "description": "OK" } } },
+                "delete": { "responses": { "204": { "description": "Deleted" } } }
+            }
+        }
+    }"#;
+
+    fn diff(format: DiffFormat) -> String {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("old.json"), OLD).unwrap();
+        fs::write(
+            dir.path().join("new.json"),
+            OLD.replace("\"delete\"", "\"post\""),
+        )
+        .unwrap();
+
+        let mut out = Vec::new();
+        run(OpenApiDiffArgs {
+            old: dir.path().join("old.json"),
+            new: dir.path().join("new.json"),
+            format,
+            stdout: &mut out,
+        })
+        .unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn renders_markdown() {
+        let out = diff(DiffFormat::Md);
+
+        assert!(out.starts_with("# API changelog"));
+        assert!(out.contains("## Breaking changes\n\n### `DELETE /pets`\n\n- Removed operation"));
+        assert!(out.contains("### `POST /pets`\n\n- Added operation"));
+    }
+
+    #[test]
+    fn renders_json() {
+        let out = diff(DiffFormat::Json);
+        let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(json["changes"][0]["operation"], "POST /pets");
+        assert_eq!(json["changes"][0]["kind"], "operation_added");
+        assert_eq!(json["changes"][0]["breaking"], false);
+        assert_eq!(json["changes"][1]["kind"], "operation_removed");
+        assert_eq!(json["changes"][1]["breaking"], true);
+    }
+
+    #[test]
+    fn rejects_unknown_file_types() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("old.txt"), OLD).unwrap();
+
+        let error = parse_spec(&dir.path().join("old.txt")).unwrap_err();
+        assert!(error.to_string().contains("must be a JSON or YAML file"));
+    }
+}
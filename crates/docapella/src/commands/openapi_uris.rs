@@ -0,0 +1,127 @@
+use crate::file_gatherer::gather_files;
+use crate::remote_specs;
+use libdoctave::settings::UriStrategy;
+use libdoctave::Project;
+
+use std::path::PathBuf;
+
+pub struct OpenApiUrisArgs<'a, W: std::io::Write> {
+    pub working_dir: PathBuf,
+    /// Show how the URIs change with `uri_strategy: operation_id`, instead of
+    /// the current ones
+    pub plan: bool,
+    pub stdout: &'a mut W,
+}
+
+/// Prints the URI of every operation in the project's OpenAPI specs. Specs
+/// set with `spec_url` are read from the cache, and aren't fetched.
+pub fn run<W: std::io::Write>(args: OpenApiUrisArgs<W>) -> crate::Result<()> {
+    let mut files = gather_files(&args.working_dir)?;
+
+    if files.is_empty() {
+        return Err(crate::Error::General(format!(
+            "No files found in directory: {}",
+            args.working_dir.display()
+        )));
+    }
+
+    remote_specs::add_cached(&args.working_dir, &mut files);
+
+    let project = Project::from_file_list(files).map_err(crate::Error::FatalBuildError)?;
+
+    if !args.plan {
+        for uri in project.operation_uris(None) {
+            writeln!(args.stdout, "{}  {}", uri.location, uri.uri)?;
+        }
+
+        return Ok(());
+    }
+
+    let changes = project
+        .operation_uris(Some(UriStrategy::OperationId))
+        .into_iter()
+        .filter_map(|uri| uri.previous_uri.clone().map(|previous| (uri, previous)))
+        .collect::<Vec<_>>();
+
+    if changes.is_empty() {
+        writeln!(
+            args.stdout,
+            "No operation URIs change with `uri_strategy: operation_id`."
+        )?;
+        return Ok(());
+    }
+
+    for (uri, previous) in &changes {
+        writeln!(args.stdout, "{}", uri.location)?;
+        writeln!(args.stdout, "  {} -> {}", previous, uri.uri)?;
+    }
+
+    writeln!(
+        args.stdout,
+        "\n{} operation URIs change with `uri_strategy: operation_id`. Links to the old URIs keep working, as the old anchors stay on the pages.",
+        changes.len()
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use temp_dir::TempDir;
+
+    const SPEC: &str = r#"{
+        "openapi": "3.0.0",
+        "info": { "title": "Pets", "version": "1.0.0" },
+        "paths": {
+            "/v2/pets": {
+                "get": {
+                    "operationId": "listPets",
+                    "summary": "List pets",
+                    "tags": ["pets"],
+                    "responses": { "200": { "description": "OK" } }
+                }
+            }
+        }
+    }"#;
+
+    fn uris(plan: bool) -> String {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("docapella.yaml"),
+            "---\ntitle: Pets\nopen_api:\n  - spec_file: openapi.json\n    uri_prefix: /api\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "# Pets").unwrap();
+        fs::write(dir.path().join("navigation.yaml"), "").unwrap();
+        fs::write(dir.path().join("openapi.json"), SPEC).unwrap();
+
+        let mut out = Vec::new();
+        run(OpenApiUrisArgs {
+            working_dir: dir.path().to_path_buf(),
+            plan,
+            stdout: &mut out,
+        })
+        .unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lists_the_uris_of_operations() {
+        assert_eq!(uris(false), "GET /v2/pets  /api/pets#list-pets\n");
+    }
+
+    #[test]
+    fn plans_the_switch_to_operation_ids() {
+        let plan = uris(true);
+
+        assert!(
+            plan.starts_with("GET /v2/pets\n  /api/pets#list-pets -> /api/pets#listPets\n"),
+            "{}",
+            plan
+        );
+        assert!(plan.contains("1 operation URIs change"));
+    }
+}
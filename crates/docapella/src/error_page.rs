@@ -0,0 +1,226 @@
+//! The page the dev server shows in place of every page while the project
+//! doesn't build, listing the errors with excerpts of their sources.
+use libdoctave::{Error, Excerpt};
+use std::path::Path;
+
+const STYLE: &str = r#"
+body { margin: 0; background: #1c1d21; color: #e6e6e6; font-family: system-ui, sans-serif; }
+main { max-width: 960px; margin: 0 auto; padding: 32px 24px; }
+h1 { color: #ff6b6b; font-size: 24px; margin: 0 0 8px; }
+h2 { font-size: 18px; margin: 0 0 4px; }
+section { margin-top: 24px; padding: 16px; border-radius: 8px; background: #26272d; border-left: 4px solid #ff6b6b; }
+.location { margin: 0 0 12px; color: #a0a0a8; font-family: ui-monospace, monospace; font-size: 14px; }
+pre { margin: 0 0 12px; padding: 12px; overflow-x: auto; border-radius: 4px; background: #16171a; font-size: 13px; line-height: 1.5; }
+.line-number { color: #6b6b75; user-select: none; }
+.highlighted { display: inline-block; min-width: 100%; background: rgba(255, 107, 107, 0.12); }
+mark { background: none; color: #ff6b6b; text-decoration: underline wavy; }
+"#;
+
+/// Any message on the reload channel means the project was rebuilt, so the
+/// page is reloaded to show either the fixed page or the new errors.
+const RELOAD_SCRIPT: &str = r#"
+const eventSource = new EventSource("/dev-reload");
+
+eventSource.onmessage = (event) => {
+  if (event.data !== "connected") {
+    eventSource.close();
+    location.reload();
+  }
+};
+
+window.addEventListener("beforeunload", () => eventSource.close());
+"#;
+
+/// Renders the error page. Sources for the excerpts are read from the files
+/// the errors are in, relative to `working_dir`.
+pub fn render(errors: &[Error], working_dir: &Path) -> String {
+    let mut out = String::new();
+
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    out.push_str("<meta charset=\"utf-8\">\n");
+    out.push_str("<title>Build failed</title>\n");
+    out.push_str(&format!("<style>{}</style>\n", STYLE));
+    out.push_str(&format!("<script>{}</script>\n", RELOAD_SCRIPT));
+    out.push_str("</head>\n<body>\n<main>\n");
+    out.push_str(&format!(
+        "<h1>Build failed with {} {}</h1>\n",
+        errors.len(),
+        if errors.len() == 1 { "error" } else { "errors" }
+    ));
+    out.push_str("<p>This page reloads once the project builds again.</p>\n");
+
+    for error in errors {
+        let excerpt = error
+            .file
+            .as_ref()
+            .and_then(|file| std::fs::read_to_string(working_dir.join(file)).ok())
+            .and_then(|source| error.excerpt(&source));
+
+        render_error(&mut out, error, excerpt.as_ref());
+    }
+
+    out.push_str("</main>\n</body>\n</html>\n");
+
+    out
+}
+
+fn render_error(out: &mut String, error: &Error, excerpt: Option<&Excerpt>) {
+    out.push_str("<section>\n");
+    out.push_str(&format!("<h2>{}</h2>\n", escape(&error.message)));
+
+    if let Some(location) = location(error, excerpt) {
+        out.push_str(&format!(
+            "<p class=\"location\">{}</p>\n",
+            escape(&location)
+        ));
+    }
+
+    if let Some(excerpt) = excerpt {
+        render_excerpt(out, excerpt);
+    }
+
+    out.push_str(&format!(
+        "<pre class=\"description\">{}</pre>\n",
+        escape(error.description.trim_end())
+    ));
+    out.push_str("</section>\n");
+}
+
+/// `file:line:column`, where the line is the one in the file, frontmatter
+/// included, when there's an excerpt to take it from.
+fn location(error: &Error, excerpt: Option<&Excerpt>) -> Option<String> {
+    let file = error.file.as_ref()?.display().to_string();
+
+    let Some(position) = &error.position else {
+        return Some(file);
+    };
+
+    let line = excerpt
+        .and_then(|e| e.lines.iter().find(|line| line.highlight.is_some()))
+        .map(|line| line.number)
+        .unwrap_or(position.start.row);
+
+    Some(format!("{}:{}:{}", file, line, position.start.col))
+}
+
+fn render_excerpt(out: &mut String, excerpt: &Excerpt) {
+    let width = excerpt
+        .lines
+        .last()
+        .map(|line| line.number.to_string().len())
+        .unwrap_or(1);
+
+    out.push_str("<pre class=\"excerpt\">");
+
+    for line in &excerpt.lines {
+        let number = format!(
+            "<span class=\"line-number\">{:>width$} │</span> ",
+            line.number,
+            width = width
+        );
+
+        match &line.highlight {
+            Some(columns) => {
+                let chars = line.text.chars().collect::<Vec<_>>();
+                let start = columns.start.saturating_sub(1).min(chars.len());
+                let end = columns.end.saturating_sub(1).clamp(start, chars.len());
+
+                out.push_str(&format!(
+                    "<span class=\"highlighted\">{}{}<mark>{}</mark>{}</span>\n",
+                    number,
+                    escape(&chars[..start].iter().collect::<String>()),
+                    escape(&chars[start..end].iter().collect::<String>()),
+                    escape(&chars[end..].iter().collect::<String>()),
+                ));
+            }
+            None => {
+                out.push_str(&number);
+                out.push_str(&escape(&line.text));
+                out.push('\n');
+            }
+        }
+    }
+
+    out.push_str("</pre>\n");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use libdoctave::{Point, Position};
+    use std::path::PathBuf;
+    use temp_dir::TempDir;
+
+    fn error(position: Option<Position>) -> Error {
+        Error {
+            code: Error::INVALID_EXPRESSION,
+            message: String::from("Invalid <expression>"),
+            description: String::from("Could not find field `titel`"),
+            file: Some(PathBuf::from("README.md")),
+            position,
+            owner: None,
+            suggestions: vec![],
+        }
+    }
+
+    fn position(row: usize, col: usize, len: usize) -> Position {
+        Position {
+            start: Point {
+                row,
+                col,
+                byte_offset: 0,
+            },
+            end: Point {
+                row,
+                col: col + len,
+                byte_offset: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn highlights_the_error_in_its_source() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("README.md"),
+            "---\ntitle: Home\n---\n\n# Home\n\nWelcome to {@page.titel}\n",
+        )
+        .unwrap();
+
+        let html = render(&[error(Some(position(4, 12, 13)))], dir.path());
+
+        assert!(html.contains("Build failed with 1 error"), "{}", html);
+        assert!(
+            html.contains("<h2>Invalid &lt;expression&gt;</h2>"),
+            "{}",
+            html
+        );
+        assert!(html.contains("README.md:7:12"), "{}", html);
+        assert!(
+            html.contains("Welcome to <mark>{@page.titel}</mark>"),
+            "{}",
+            html
+        );
+        assert!(html.contains("Could not find field `titel`"), "{}", html);
+        assert!(html.contains("/dev-reload"), "{}", html);
+    }
+
+    #[test]
+    fn lists_errors_without_a_source() {
+        let dir = TempDir::new().unwrap();
+
+        let html = render(&[error(Some(position(1, 1, 1))), error(None)], dir.path());
+
+        assert!(html.contains("Build failed with 2 errors"), "{}", html);
+        assert!(html.contains("README.md:1:1"), "{}", html);
+        assert!(!html.contains("class=\"excerpt\""), "{}", html);
+    }
+}
@@ -0,0 +1,134 @@
+use libdoctave::feed::Feed;
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Serializes the feed as RSS 2.0.
+pub fn to_rss(feed: &Feed) -> String {
+    let mut out = String::new();
+
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<rss version=\"2.0\" xmlns:atom=\"http://www.w3.org/2005/Atom\">\n");
+    out.push_str("  <channel>\n");
+    out.push_str(&format!("    <title>{}</title>\n", escape(&feed.title)));
+    out.push_str(&format!("    <link>{}</link>\n", escape(&feed.url)));
+    out.push_str(&format!(
+        "    <description>{}</description>\n",
+        escape(&feed.title)
+    ));
+    out.push_str(&format!(
+        "    <atom:link href=\"{}\" rel=\"self\" type=\"application/rss+xml\"/>\n",
+        escape(&feed.feed_url)
+    ));
+
+    if let Some(updated) = &feed.updated {
+        out.push_str(&format!(
+            "    <lastBuildDate>{}</lastBuildDate>\n",
+            rfc822_date(updated)
+        ));
+    }
+
+    for item in &feed.items {
+        out.push_str("    <item>\n");
+        out.push_str(&format!("      <title>{}</title>\n", escape(&item.title)));
+        out.push_str(&format!("      <link>{}</link>\n", escape(&item.url)));
+        out.push_str(&format!("      <guid>{}</guid>\n", escape(&item.id)));
+        out.push_str(&format!(
+            "      <pubDate>{}</pubDate>\n",
+            rfc822_date(&item.date)
+        ));
+        out.push_str(&format!(
+            "      <description>{}</description>\n",
+            escape(&item.description)
+        ));
+        out.push_str("    </item>\n");
+    }
+
+    out.push_str("  </channel>\n");
+    out.push_str("</rss>\n");
+
+    out
+}
+
+/// Turns a date like `2024-05-01` into `01 May 2024 00:00:00 GMT`. The day
+/// of the week is optional in RFC 822, so it's left out.
+fn rfc822_date(date: &str) -> String {
+    let mut parts = date.splitn(3, '-');
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(year), Some(month), Some(day)) => {
+            let month = month
+                .parse::<usize>()
+                .ok()
+                .and_then(|m| MONTHS.get(m.wrapping_sub(1)))
+                .unwrap_or(&"Jan");
+
+            format!("{} {} {} 00:00:00 GMT", day, month, year)
+        }
+        _ => date.to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use libdoctave::feed::FeedItem;
+
+    use super::*;
+
+    #[test]
+    fn serializes_feeds_as_rss() {
+        let feed = Feed {
+            title: "Product changelog".to_string(),
+            path: "/changelog".to_string(),
+            url: "https://docs.example.com/changelog".to_string(),
+            feed_url: "https://docs.example.com/changelog/feed.xml".to_string(),
+            updated: Some("2024-03-01".to_string()),
+            items: vec![FeedItem {
+                id: "https://docs.example.com/changelog/v3".to_string(),
+                title: "Version 3 & more".to_string(),
+                url: "https://docs.example.com/changelog/v3".to_string(),
+                date: "2024-03-01".to_string(),
+                description: "Adds <blink>".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            to_rss(&feed),
+            indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+              <channel>
+                <title>Product changelog</title>
+                <link>https://docs.example.com/changelog</link>
+                <description>Product changelog</description>
+                <atom:link href="https://docs.example.com/changelog/feed.xml" rel="self" type="application/rss+xml"/>
+                <lastBuildDate>01 Mar 2024 00:00:00 GMT</lastBuildDate>
+                <item>
+                  <title>Version 3 &amp; more</title>
+                  <link>https://docs.example.com/changelog/v3</link>
+                  <guid>https://docs.example.com/changelog/v3</guid>
+                  <pubDate>01 Mar 2024 00:00:00 GMT</pubDate>
+                  <description>Adds &lt;blink&gt;</description>
+                </item>
+              </channel>
+            </rss>
+            "#}
+        );
+    }
+
+    #[test]
+    fn formats_dates_for_rss() {
+        assert_eq!(rfc822_date("2024-12-31"), "31 Dec 2024 00:00:00 GMT");
+        assert_eq!(rfc822_date("2024-01-05"), "05 Jan 2024 00:00:00 GMT");
+    }
+}
@@ -1,72 +1,503 @@
-use libdoctave::{InputContent, InputFile};
+use crate::{Error, Result};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use libdoctave::{FileMetadata, InputContent, InputFile};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::time::UNIX_EPOCH;
 
-pub(crate) fn gather_files(
-    working_dir: &Path,
-) -> std::result::Result<Vec<InputFile>, std::io::Error> {
-    let mut files = Vec::new();
+/// File with gitignore-style patterns for files that should not be part of
+/// the project.
+pub const IGNORE_FILE_NAME: &str = ".docapellaignore";
+
+/// Files and directories that are never part of the project, regardless of
+/// the ignore file. Like `.gitignore`, the ignore file itself is left out too.
+const ALWAYS_IGNORED: &[&str] = &[
+    "_build",
+    ".docapella-cache",
+    ".git",
+    "node_modules",
+    IGNORE_FILE_NAME,
+];
 
-    gather_files_recursively(working_dir, working_dir, &mut files)?;
+/// The most files we'll gather before giving up. Usually a sign that the
+/// project is co-located with code that should be ignored.
+pub(crate) const MAX_FILES: usize = 10_000;
 
-    Ok(files)
+/// Decides which files under the working directory are part of the project.
+///
+/// Shared by the file gatherer and the dev server's file watcher, so that
+/// changes to ignored files don't trigger rebuilds.
+pub(crate) struct IgnoreRules {
+    root: PathBuf,
+    patterns: Gitignore,
 }
 
-fn gather_files_recursively(
-    current_dir: &Path,
-    working_dir: &Path,
-    files: &mut Vec<InputFile>,
-) -> std::result::Result<(), std::io::Error> {
-    for entry in fs::read_dir(current_dir)? {
-        let path = entry?.path();
+impl IgnoreRules {
+    pub(crate) fn load(working_dir: &Path) -> Result<Self> {
+        let root = working_dir
+            .canonicalize()
+            .unwrap_or_else(|_| working_dir.to_path_buf());
+
+        let mut builder = GitignoreBuilder::new(&root);
+        let ignore_file = root.join(IGNORE_FILE_NAME);
+
+        if ignore_file.is_file() {
+            if let Some(e) = builder.add(&ignore_file) {
+                return Err(Error::General(format!(
+                    "Could not read {}: {}",
+                    IGNORE_FILE_NAME, e
+                )));
+            }
+        }
+
+        let patterns = builder.build().map_err(|e| {
+            Error::General(format!("Invalid pattern in {}: {}", IGNORE_FILE_NAME, e))
+        })?;
+
+        Ok(IgnoreRules { root, patterns })
+    }
+
+    /// The canonical working directory the rules are relative to
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Checks whether a path should be left out of the project. The path can
+    /// either be relative to the working directory, or an absolute path inside it.
+    pub(crate) fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+
+        if relative.as_os_str().is_empty() || relative.has_root() {
+            return false;
+        }
+
+        if relative.components().any(|component| {
+            ALWAYS_IGNORED
+                .iter()
+                .any(|name| component.as_os_str() == *name)
+        }) {
+            return true;
+        }
+
+        self.patterns
+            .matched_path_or_any_parents(relative, is_dir)
+            .is_ignore()
+    }
+}
+
+pub(crate) fn gather_files(working_dir: &Path) -> Result<Vec<InputFile>> {
+    gather_files_with_limit(working_dir, MAX_FILES)
+}
+
+fn gather_files_with_limit(working_dir: &Path, limit: usize) -> Result<Vec<InputFile>> {
+    let rules = IgnoreRules::load(working_dir)?;
+
+    let mut gatherer = Gatherer {
+        working_dir,
+        rules: &rules,
+        limit,
+        visited: HashSet::new(),
+        files: Vec::new(),
+    };
+
+    gatherer.visit(working_dir)?;
+
+    Ok(gatherer.files)
+}
+
+struct Gatherer<'a> {
+    working_dir: &'a Path,
+    rules: &'a IgnoreRules,
+    limit: usize,
+    /// Canonical paths of the directories we've already walked, so that
+    /// symlinks pointing back up the tree don't send us into a loop.
+    visited: HashSet<PathBuf>,
+    files: Vec<InputFile>,
+}
+
+impl Gatherer<'_> {
+    fn visit(&mut self, current_dir: &Path) -> Result<()> {
+        if !self.visited.insert(current_dir.canonicalize()?) {
+            return Ok(());
+        }
+
+        // Sorted, so that the pages are in the same order in every build
+        let mut paths = fs::read_dir(current_dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.sort();
 
-        if path.is_dir() {
-            if ignored_directory(&path) {
+        for path in paths {
+            // Broken symlinks
+            if !path.exists() {
                 continue;
             }
-            gather_files_recursively(&path, working_dir, files)?;
-        } else {
-            files.push(InputFile {
-                path: path
-                    .strip_prefix(working_dir)
-                    .expect("Found file was not in working dir")
-                    .to_path_buf(),
-                content: match std::fs::read_to_string(&path) {
-                    Ok(s) => Ok(InputContent::Text(s)),
-                    Err(e) => {
-                        if e.kind() == std::io::ErrorKind::InvalidData {
-                            match std::fs::metadata(&path)
-                                .and_then(|meta| meta.modified())
-                                .and_then(|system_time| {
-                                    system_time.duration_since(UNIX_EPOCH).map_err(|e| {
-                                        std::io::Error::new(
-                                            std::io::ErrorKind::InvalidData,
-                                            e.to_string(),
-                                        )
-                                    })
-                                }) {
-                                Ok(modified_time) => {
-                                    Ok(InputContent::Binary(modified_time.as_millis().to_string()))
-                                }
-                                Err(e) => Err(e),
-                            }
-                        } else {
-                            Err(e)
-                        }
-                    }
-                }?,
-            });
+
+            let relative = path
+                .strip_prefix(self.working_dir)
+                .expect("Found file was not in working dir")
+                .to_path_buf();
+
+            if path.is_dir() {
+                if self.rules.is_ignored(&relative, true) {
+                    continue;
+                }
+                self.visit(&path)?;
+            } else {
+                if self.rules.is_ignored(&relative, false) {
+                    continue;
+                }
+
+                if self.files.len() >= self.limit {
+                    return Err(Error::TooManyFiles(self.limit));
+                }
+
+                self.files.push(InputFile {
+                    path: relative,
+                    content: read_content(&path)?,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Gathers when each file was last modified from the filesystem. With `git`,
+/// the last commit touching the file and its authors are used instead, for
+/// files that have been committed.
+///
+/// Metadata that can't be read is left out, rather than failing the build.
+pub(crate) fn gather_metadata(
+    working_dir: &Path,
+    files: &[InputFile],
+    git: bool,
+) -> HashMap<PathBuf, FileMetadata> {
+    let mut metadata = files
+        .iter()
+        .map(|file| {
+            let modified_at = fs::metadata(working_dir.join(&file.path))
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| rfc3339(duration.as_secs()));
+
+            (
+                file.path.clone(),
+                FileMetadata {
+                    modified_at,
+                    authors: None,
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    if git {
+        for (path, history) in git_history(working_dir).unwrap_or_default() {
+            if let Some(entry) = metadata.get_mut(&path) {
+                entry.modified_at = Some(history.modified_at);
+                entry.authors = Some(history.authors);
+            }
+        }
+    }
+
+    metadata
+}
+
+struct GitHistory {
+    modified_at: String,
+    authors: Vec<String>,
+}
+
+/// Reads the history of every file under the working directory from a single
+/// `git log`, newest commits first. `None` if git isn't available, or the
+/// working directory isn't in a repository.
+fn git_history(working_dir: &Path) -> Option<HashMap<PathBuf, GitHistory>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(working_dir)
+        .args(["-c", "core.quotePath=false", "log"])
+        .args(["--format=%x00%aI%x09%aN", "--name-only", "--relative"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(parse_git_log(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_git_log(log: &str) -> HashMap<PathBuf, GitHistory> {
+    let mut history: HashMap<PathBuf, GitHistory> = HashMap::new();
+
+    for commit in log.split('\0').filter(|c| !c.trim().is_empty()) {
+        let mut lines = commit.lines();
+
+        let Some((date, author)) = lines.next().and_then(|l| l.split_once('\t')) else {
+            continue;
+        };
+
+        for file in lines.filter(|l| !l.is_empty()) {
+            let entry = history
+                .entry(PathBuf::from(file))
+                .or_insert_with(|| GitHistory {
+                    modified_at: date.to_string(),
+                    authors: vec![],
+                });
+
+            if !entry.authors.iter().any(|a| a == author) {
+                entry.authors.push(author.to_string());
+            }
         }
     }
 
-    Ok(())
+    history
+}
+
+/// Formats seconds since the Unix epoch as an RFC 3339 timestamp in UTC.
+fn rfc3339(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let secs_of_day = secs % 86_400;
+
+    // Converts days since the epoch to a civil date. See
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+fn read_content(path: &Path) -> std::result::Result<InputContent, std::io::Error> {
+    // SVGs can be inlined into pages, so they are read as text even if they
+    // aren't quite valid UTF-8
+    if path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("svg"))
+    {
+        let bytes = std::fs::read(path)?;
+        let text = String::from_utf8_lossy(&bytes);
+
+        return Ok(InputContent::Text(
+            text.trim_start_matches('\u{feff}').to_string(),
+        ));
+    }
+
+    match std::fs::read_to_string(path) {
+        Ok(s) => Ok(InputContent::Text(s)),
+        Err(e) => {
+            if e.kind() == std::io::ErrorKind::InvalidData {
+                let metadata = std::fs::metadata(path)?;
+                let modified_time = metadata.modified().and_then(|system_time| {
+                    system_time.duration_since(UNIX_EPOCH).map_err(|e| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+                    })
+                })?;
+
+                Ok(InputContent::Binary {
+                    signature: modified_time.as_millis().to_string(),
+                    size_bytes: metadata.len() as usize,
+                })
+            } else {
+                Err(e)
+            }
+        }
+    }
 }
 
-fn ignored_directory(path: &Path) -> bool {
-    let dir_name = path.file_name();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use temp_dir::TempDir;
+
+    fn gathered_paths(working_dir: &Path) -> Vec<PathBuf> {
+        let mut paths = gather_files(working_dir)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.path)
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn skips_default_ignored_directories() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        fs::write(root.join("README.md"), "# Hello").unwrap();
+        for ignored in ALWAYS_IGNORED {
+            fs::create_dir(root.join(ignored)).unwrap();
+            fs::write(root.join(ignored).join("foo.md"), "# Foo").unwrap();
+        }
+
+        assert_eq!(gathered_paths(root), vec![PathBuf::from("README.md")]);
+    }
+
+    #[test]
+    fn gathers_files_in_a_stable_order() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        fs::create_dir(root.join("guides")).unwrap();
+        for path in ["zebra.md", "guides/setup.md", "apple.md", "guides/about.md"] {
+            fs::write(root.join(path), "# Hello").unwrap();
+        }
+
+        let paths = gather_files(root)
+            .unwrap()
+            .into_iter()
+            .map(|f| f.path)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("apple.md"),
+                PathBuf::from("guides/about.md"),
+                PathBuf::from("guides/setup.md"),
+                PathBuf::from("zebra.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn honors_the_ignore_file() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
 
-    dir_name
-        .map(|dir_name| dir_name == "node_modules" || dir_name == "_build" || dir_name == ".git")
-        .unwrap_or(false)
+        fs::write(
+            root.join(IGNORE_FILE_NAME),
+            "src/\n*.draft.md\n!keep.draft.md\n",
+        )
+        .unwrap();
+        fs::write(root.join("README.md"), "# Hello").unwrap();
+        fs::write(root.join("wip.draft.md"), "# WIP").unwrap();
+        fs::write(root.join("keep.draft.md"), "# Keep").unwrap();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/nested/lib.md"), "# Lib").unwrap();
+
+        assert_eq!(
+            gathered_paths(root),
+            vec![PathBuf::from("README.md"), PathBuf::from("keep.draft.md")]
+        );
+    }
+
+    #[test]
+    fn ignore_rules_accept_absolute_paths() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(IGNORE_FILE_NAME), "drafts/\n").unwrap();
+
+        let rules = IgnoreRules::load(dir.path()).unwrap();
+
+        assert!(rules.is_ignored(&rules.root().join("drafts/foo.md"), false));
+        assert!(rules.is_ignored(&rules.root().join("_build/index.html"), false));
+        assert!(rules.is_ignored(&rules.root().join(IGNORE_FILE_NAME), false));
+        assert!(!rules.is_ignored(&rules.root().join("guides/foo.md"), false));
+        // Outside of the working directory
+        assert!(!rules.is_ignored(Path::new("/node_modules/foo.md"), false));
+    }
+
+    #[test]
+    fn errors_when_there_are_too_many_files() {
+        let dir = TempDir::new().unwrap();
+
+        for i in 0..4 {
+            fs::write(dir.path().join(format!("{}.md", i)), "# Hello").unwrap();
+        }
+
+        assert!(gather_files_with_limit(dir.path(), 4).is_ok());
+
+        let error = gather_files_with_limit(dir.path(), 3).unwrap_err();
+        assert!(matches!(error, Error::TooManyFiles(3)), "{:?}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn does_not_loop_on_symlink_cycles() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+
+        fs::write(root.join("README.md"), "# Hello").unwrap();
+        fs::create_dir(root.join("guides")).unwrap();
+        fs::write(root.join("guides/intro.md"), "# Intro").unwrap();
+        std::os::unix::fs::symlink(root, root.join("guides/back-to-root")).unwrap();
+        std::os::unix::fs::symlink(root.join("missing"), root.join("broken")).unwrap();
+
+        assert_eq!(
+            gathered_paths(root),
+            vec![PathBuf::from("README.md"), PathBuf::from("guides/intro.md")]
+        );
+    }
+
+    #[test]
+    fn reads_svgs_as_text() {
+        let dir = TempDir::new().unwrap();
+        let svg = dir.path().join("arch.svg");
+        fs::write(&svg, b"\xef\xbb\xbf<svg><title>Caf\xe9</title></svg>").unwrap();
+        let png = dir.path().join("arch.png");
+        fs::write(&png, b"\x89PNG\xff").unwrap();
+
+        assert_eq!(
+            read_content(&svg).unwrap(),
+            InputContent::Text("<svg><title>Caf\u{fffd}</title></svg>".to_string())
+        );
+        assert!(matches!(
+            read_content(&png).unwrap(),
+            InputContent::Binary { size_bytes: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn formats_timestamps_as_rfc3339() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(951_827_696), "2000-02-29T12:34:56Z");
+        assert_eq!(rfc3339(1_735_689_599), "2024-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn parses_git_history() {
+        let log = "\02024-05-02T10:00:00+02:00\tBob\n\nREADME.md\nguides/intro.md\n\
+                   \02024-05-01T10:00:00+02:00\tAlice\n\nREADME.md\n\
+                   \02024-04-01T10:00:00+02:00\tBob\n\nREADME.md\n";
+
+        let history = parse_git_log(log);
+
+        let readme = &history[Path::new("README.md")];
+        assert_eq!(readme.modified_at, "2024-05-02T10:00:00+02:00");
+        assert_eq!(readme.authors, vec!["Bob", "Alice"]);
+
+        let intro = &history[Path::new("guides/intro.md")];
+        assert_eq!(intro.authors, vec!["Bob"]);
+    }
+
+    #[test]
+    fn gathers_modification_times() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("README.md"), "# Hello").unwrap();
+
+        let files = gather_files(dir.path()).unwrap();
+        let metadata = gather_metadata(dir.path(), &files, false);
+
+        let readme = &metadata[Path::new("README.md")];
+        assert!(readme.modified_at.as_ref().unwrap().ends_with('Z'));
+        assert_eq!(readme.authors, None);
+    }
 }
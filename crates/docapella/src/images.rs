@@ -0,0 +1,444 @@
+//! Responsive images for static builds.
+//!
+//! Raster images shown on pages are resized to a set of widths, and converted
+//! to WebP, so that browsers can download the smallest version that fits. The
+//! originals are copied to the build as usual, and stay as the fallback.
+//!
+//! Encoding is slow, so the generated files are cached outside of the build
+//! directory, keyed on the signature of the original.
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use libdoctave::project::Asset;
+use libdoctave::{ImageVariant, ImageVariants, Project};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where generated images are cached, relative to the working directory.
+pub const CACHE_DIR: &str = ".docapella-cache/images";
+
+/// Widths used when none are given on the command line.
+pub const DEFAULT_WIDTHS: &[u32] = &[640, 1280, 1920];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    /// Widths to resize images to. Images are never scaled up.
+    pub widths: Vec<u32>,
+    /// Also generate WebP versions
+    pub webp: bool,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        ImageOptions {
+            widths: DEFAULT_WIDTHS.to_vec(),
+            webp: true,
+        }
+    }
+}
+
+/// The variants of every image, keyed by the path of the original. Written to
+/// `_assets/manifest.json` in the build.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    pub images: BTreeMap<PathBuf, ImageVariants>,
+}
+
+/// Formats that are resized. SVGs don't need to be, and resizing GIFs would
+/// drop their animation, so both are copied as they are.
+fn is_resizable(path: &Path) -> bool {
+    matches!(
+        path.extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref(),
+        Some("png" | "jpg" | "jpeg")
+    )
+}
+
+/// Generates the variants of the images shown on published pages into
+/// `out_dir`, and returns them for rendering.
+pub fn generate(
+    project: &Project,
+    working_dir: &Path,
+    out_dir: &Path,
+    opts: &ImageOptions,
+) -> crate::Result<Manifest> {
+    let cache_dir = working_dir.join(CACHE_DIR);
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let images = project
+        .image_assets(None)
+        .into_par_iter()
+        .filter(|asset| is_resizable(&asset.path))
+        .map(|asset| {
+            let variants = cached_variants(asset, working_dir, &cache_dir, opts)?;
+
+            for variant in &variants.variants {
+                if variant.path == asset.path {
+                    continue;
+                }
+
+                let path = out_dir.join(&variant.path);
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::copy(cache_dir.join(cache_file_name(asset, variant)), path)?;
+            }
+
+            Ok((asset.path.clone(), variants))
+        })
+        .collect::<crate::Result<BTreeMap<_, _>>>()?;
+
+    let manifest = Manifest { images };
+
+    let path = out_dir.join("_assets/manifest.json");
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| crate::Error::General(format!("Could not serialize manifest: {}", e)))?;
+    std::fs::write(path, json)?;
+
+    Ok(manifest)
+}
+
+impl Manifest {
+    /// The variants in the form the renderer expects.
+    pub fn render_variants(&self) -> HashMap<PathBuf, ImageVariants> {
+        self.images
+            .iter()
+            .map(|(path, variants)| (path.clone(), variants.clone()))
+            .collect()
+    }
+}
+
+/// Reads the variants of an image from the cache, generating them first if
+/// the image has changed since the last build.
+fn cached_variants(
+    asset: &Asset,
+    working_dir: &Path,
+    cache_dir: &Path,
+    opts: &ImageOptions,
+) -> crate::Result<ImageVariants> {
+    let key = cache_key(asset, opts);
+    let index_path = cache_dir.join(format!("{}.json", key));
+
+    if let Some(variants) = std::fs::read_to_string(&index_path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<ImageVariants>(&json).ok())
+    {
+        if variants
+            .variants
+            .iter()
+            .filter(|v| v.path != asset.path)
+            .all(|v| cache_dir.join(cache_file_name(asset, v)).exists())
+        {
+            return Ok(variants);
+        }
+    }
+
+    let variants = generate_variants(asset, &working_dir.join(&asset.path), cache_dir, opts)?;
+
+    let json = serde_json::to_string(&variants)
+        .map_err(|e| crate::Error::General(format!("Could not serialize variants: {}", e)))?;
+    std::fs::write(index_path, json)?;
+
+    Ok(variants)
+}
+
+fn generate_variants(
+    asset: &Asset,
+    source: &Path,
+    cache_dir: &Path,
+    opts: &ImageOptions,
+) -> crate::Result<ImageVariants> {
+    let image = decode(source).map_err(|e| {
+        crate::Error::General(format!(
+            "Could not read image {}: {}",
+            asset.path.display(),
+            e
+        ))
+    })?;
+
+    let original_width = image.width();
+    let original_mime_type = mime_type(&asset.path);
+
+    let mut widths = opts
+        .widths
+        .iter()
+        .copied()
+        .filter(|w| *w < original_width)
+        .collect::<Vec<_>>();
+    widths.sort_unstable();
+    widths.dedup();
+
+    let mut variants = vec![];
+    let mut webp_variants = vec![];
+
+    for width in widths.iter().map(|w| Some(*w)).chain([None]) {
+        let resized = match width {
+            Some(width) => image.resize(width, u32::MAX, FilterType::Lanczos3),
+            None => image.clone(),
+        };
+
+        // The original is copied to the build with the rest of the assets
+        let variant = ImageVariant {
+            path: variant_path(&asset.path, width, None),
+            width: resized.width(),
+            mime_type: original_mime_type.clone(),
+        };
+        if width.is_some() {
+            save(
+                &resized,
+                &cache_dir.join(cache_file_name(asset, &variant)),
+                None,
+            )?;
+        }
+        variants.push(variant);
+
+        if opts.webp {
+            let variant = ImageVariant {
+                path: variant_path(&asset.path, width, Some("webp")),
+                width: resized.width(),
+                mime_type: String::from("image/webp"),
+            };
+            save(
+                &resized,
+                &cache_dir.join(cache_file_name(asset, &variant)),
+                Some("webp"),
+            )?;
+            webp_variants.push(variant);
+        }
+    }
+
+    variants.append(&mut webp_variants);
+
+    Ok(ImageVariants {
+        sizes: format!("(max-width: {w}px) 100vw, {w}px", w = original_width),
+        variants,
+    })
+}
+
+/// Decodes an image, rotating it the way its EXIF orientation says it should
+/// be shown. Otherwise photos taken sideways would lose the rotation.
+fn decode(path: &Path) -> image::ImageResult<DynamicImage> {
+    let mut decoder = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+
+    Ok(image)
+}
+
+fn save(image: &DynamicImage, path: &Path, format: Option<&str>) -> crate::Result<()> {
+    let result = match format {
+        Some("webp") => std::fs::File::create(path)
+            .map_err(image::ImageError::IoError)
+            .and_then(|file| {
+                // The encoder only supports 8 bit RGB(A)
+                let image = DynamicImage::ImageRgba8(image.to_rgba8());
+                image.write_with_encoder(WebPEncoder::new_lossless(std::io::BufWriter::new(file)))
+            }),
+        _ => image.save(path),
+    };
+
+    result.map_err(|e| crate::Error::General(format!("Could not write {}: {}", path.display(), e)))
+}
+
+/// `_assets/cat.png` becomes e.g. `_assets/cat-640w.png` or `_assets/cat.webp`.
+fn variant_path(path: &Path, width: Option<u32>, extension: Option<&str>) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = extension
+        .map(|e| e.to_string())
+        .or_else(|| path.extension().map(|e| e.to_string_lossy().to_string()))
+        .unwrap_or_default();
+
+    let file_name = match width {
+        Some(width) => format!("{}-{}w.{}", stem, width, extension),
+        None => format!("{}.{}", stem, extension),
+    };
+
+    path.with_file_name(file_name)
+}
+
+fn mime_type(path: &Path) -> String {
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => String::from("image/png"),
+        _ => String::from("image/jpeg"),
+    }
+}
+
+/// Changes whenever the image or the options it was generated with do.
+fn cache_key(asset: &Asset, opts: &ImageOptions) -> String {
+    let widths = opts
+        .widths
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    format!(
+        "{:016x}-{}{}",
+        asset.signature,
+        widths,
+        if opts.webp { "-webp" } else { "" }
+    )
+}
+
+fn cache_file_name(asset: &Asset, variant: &ImageVariant) -> String {
+    format!(
+        "{:016x}-{}",
+        asset.signature,
+        variant
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgb};
+    use libdoctave::{InputContent, InputFile};
+    use temp_dir::TempDir;
+
+    fn project(working_dir: &Path, images: &[&str]) -> Project {
+        let mut files = vec![
+            InputFile {
+                path: PathBuf::from("docapella.yaml"),
+                content: InputContent::Text("---\ntitle: Images".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("navigation.yaml"),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    images
+                        .iter()
+                        .map(|i| format!("![]({})", i))
+                        .collect::<Vec<_>>()
+                        .join("\n\n"),
+                ),
+            },
+        ];
+
+        for image in images {
+            let path = image.trim_start_matches('/');
+            files.push(InputFile {
+                path: PathBuf::from(path),
+                content: InputContent::Binary {
+                    signature: String::from("1"),
+                    size_bytes: std::fs::metadata(working_dir.join(path))
+                        .map(|m| m.len() as usize)
+                        .unwrap_or(0),
+                },
+            });
+        }
+
+        Project::from_file_list(files).unwrap()
+    }
+
+    fn write_png(working_dir: &Path, path: &str, width: u32, height: u32) {
+        let path = working_dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        ImageBuffer::from_pixel(width, height, Rgb::<u8>([200, 100, 50]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn generates_smaller_variants_and_webp_versions() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        write_png(working_dir.path(), "_assets/shot.png", 100, 50);
+
+        let project = project(working_dir.path(), &["/_assets/shot.png"]);
+        let opts = ImageOptions {
+            widths: vec![40, 200],
+            webp: true,
+        };
+
+        let manifest = generate(&project, working_dir.path(), out_dir.path(), &opts).unwrap();
+        let variants = &manifest.images[Path::new("_assets/shot.png")];
+
+        assert_eq!(
+            variants
+                .variants
+                .iter()
+                .map(|v| (v.path.to_str().unwrap(), v.width))
+                .collect::<Vec<_>>(),
+            vec![
+                ("_assets/shot-40w.png", 40),
+                ("_assets/shot.png", 100),
+                ("_assets/shot-40w.webp", 40),
+                ("_assets/shot.webp", 100),
+            ]
+        );
+
+        let resized = image::open(out_dir.path().join("_assets/shot-40w.png")).unwrap();
+        assert_eq!((resized.width(), resized.height()), (40, 20));
+        assert!(out_dir.path().join("_assets/shot.webp").exists());
+        assert!(out_dir.path().join("_assets/manifest.json").exists());
+    }
+
+    #[test]
+    fn passes_svgs_and_gifs_through() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(working_dir.path().join("_assets")).unwrap();
+        std::fs::write(working_dir.path().join("_assets/logo.svg"), "<svg/>").unwrap();
+        std::fs::write(working_dir.path().join("_assets/anim.gif"), "GIF89a").unwrap();
+
+        let project = project(
+            working_dir.path(),
+            &["/_assets/logo.svg", "/_assets/anim.gif"],
+        );
+
+        let manifest = generate(
+            &project,
+            working_dir.path(),
+            out_dir.path(),
+            &ImageOptions::default(),
+        )
+        .unwrap();
+
+        assert!(manifest.images.is_empty());
+    }
+
+    #[test]
+    fn reuses_cached_variants() {
+        let working_dir = TempDir::new().unwrap();
+        let out_dir = TempDir::new().unwrap();
+        write_png(working_dir.path(), "_assets/shot.png", 100, 50);
+
+        let project = project(working_dir.path(), &["/_assets/shot.png"]);
+        let opts = ImageOptions {
+            widths: vec![40],
+            webp: false,
+        };
+
+        generate(&project, working_dir.path(), out_dir.path(), &opts).unwrap();
+
+        // Generating again doesn't need the original, since its signature
+        // hasn't changed
+        std::fs::remove_file(working_dir.path().join("_assets/shot.png")).unwrap();
+        let other_out_dir = TempDir::new().unwrap();
+
+        generate(&project, working_dir.path(), other_out_dir.path(), &opts).unwrap();
+
+        assert!(other_out_dir.path().join("_assets/shot-40w.png").exists());
+    }
+}
@@ -2,12 +2,23 @@ use thiserror::Error;
 
 pub mod commands {
     pub mod build;
+    pub mod check;
     pub mod dev;
     pub mod init;
+    pub mod openapi_diff;
+    pub mod openapi_uris;
 }
 
-mod builder;
+pub mod builder;
+mod error_page;
+pub mod feed;
 pub mod file_gatherer;
+pub mod images;
+pub mod link_checker;
+pub mod manifest;
+pub mod remote_specs;
+pub mod reporter;
+mod watcher;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -19,4 +30,6 @@ pub enum Error {
     General(String),
     #[error("Fatal build error")]
     FatalBuildError(Vec<libdoctave::Error>),
+    #[error("Found more than {0} files in the project. Exclude files that aren't part of your documentation in .docapellaignore")]
+    TooManyFiles(usize),
 }
@@ -0,0 +1,328 @@
+//! Checks that links to other sites respond.
+//!
+//! Links are checked concurrently, but no more than a few at a time per
+//! domain, so that a page linking to the same site dozens of times doesn't
+//! get us rate limited. Each link is first requested with `HEAD`, falling
+//! back to `GET` for servers that don't support it.
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_RETRIES: u32 = 2;
+pub const DEFAULT_PER_DOMAIN: usize = 2;
+const CONCURRENCY: usize = 16;
+
+#[derive(Clone, Debug)]
+pub struct CheckOptions {
+    /// How long to wait for a single request
+    pub timeout: Duration,
+    /// How many times a request is retried after a timeout or server error
+    pub retries: u32,
+    /// How many requests are made to a single domain at once
+    pub per_domain: usize,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            timeout: DEFAULT_TIMEOUT,
+            retries: DEFAULT_RETRIES,
+            per_domain: DEFAULT_PER_DOMAIN,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkStatus {
+    Ok,
+    /// The server responded with an error status code
+    Status(u16),
+    /// The request failed, e.g. it timed out or the domain doesn't exist
+    Failed(String),
+}
+
+impl LinkStatus {
+    pub fn is_ok(&self) -> bool {
+        *self == LinkStatus::Ok
+    }
+}
+
+impl std::fmt::Display for LinkStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkStatus::Ok => write!(f, "OK"),
+            LinkStatus::Status(code) => write!(f, "status {}", code),
+            LinkStatus::Failed(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Checks the given URLs, returning their statuses in the same order.
+pub fn check(urls: &[String], opts: &CheckOptions) -> Vec<LinkStatus> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(opts.timeout)
+        .user_agent(concat!("docapella/", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let queue = Queue {
+        state: Mutex::new(QueueState {
+            pending: (0..urls.len()).collect(),
+            active: HashMap::new(),
+        }),
+        changed: Condvar::new(),
+        per_domain: opts.per_domain.max(1),
+    };
+
+    let results = Mutex::new(vec![None; urls.len()]);
+
+    std::thread::scope(|scope| {
+        for _ in 0..CONCURRENCY.min(urls.len()) {
+            scope.spawn(|| {
+                while let Some(index) = queue.take(urls) {
+                    let status = check_url(&agent, &urls[index], opts.retries);
+                    queue.finish(&urls[index]);
+                    results.lock().unwrap()[index] = Some(status);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|status| status.expect("every link is checked"))
+        .collect()
+}
+
+struct Queue {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+    per_domain: usize,
+}
+
+struct QueueState {
+    pending: VecDeque<usize>,
+    /// How many requests are in flight per domain
+    active: HashMap<String, usize>,
+}
+
+impl Queue {
+    /// Waits for a URL whose domain isn't already at its limit. Returns
+    /// `None` once there is nothing left to check.
+    fn take(&self, urls: &[String]) -> Option<usize> {
+        let mut state = self.state.lock().unwrap();
+
+        loop {
+            if state.pending.is_empty() {
+                return None;
+            }
+
+            let available = state.pending.iter().position(|index| {
+                state
+                    .active
+                    .get(domain(&urls[*index]))
+                    .copied()
+                    .unwrap_or(0)
+                    < self.per_domain
+            });
+
+            if let Some(position) = available {
+                let index = state.pending.remove(position).unwrap();
+                *state
+                    .active
+                    .entry(domain(&urls[index]).to_string())
+                    .or_default() += 1;
+                return Some(index);
+            }
+
+            state = self.changed.wait(state).unwrap();
+        }
+    }
+
+    fn finish(&self, url: &str) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(count) = state.active.get_mut(domain(url)) {
+            *count -= 1;
+        }
+        self.changed.notify_all();
+    }
+}
+
+fn check_url(agent: &ureq::Agent, url: &str, retries: u32) -> LinkStatus {
+    let mut attempt = 0;
+
+    loop {
+        let status = match request(agent, "HEAD", url) {
+            // Plenty of servers don't implement HEAD, or forbid it
+            LinkStatus::Status(403 | 405 | 501) => request(agent, "GET", url),
+            status => status,
+        };
+
+        if attempt >= retries || !is_transient(&status) {
+            return status;
+        }
+
+        attempt += 1;
+        std::thread::sleep(Duration::from_millis(250 * attempt as u64));
+    }
+}
+
+fn request(agent: &ureq::Agent, method: &str, url: &str) -> LinkStatus {
+    match agent.request(method, url).call() {
+        Ok(_) => LinkStatus::Ok,
+        Err(ureq::Error::Status(code, _)) => LinkStatus::Status(code),
+        Err(ureq::Error::Transport(e)) => LinkStatus::Failed(e.to_string()),
+    }
+}
+
+/// Whether trying again later might give a different answer
+fn is_transient(status: &LinkStatus) -> bool {
+    match status {
+        LinkStatus::Ok => false,
+        LinkStatus::Status(code) => *code == 429 || *code >= 500,
+        LinkStatus::Failed(_) => true,
+    }
+}
+
+/// The host of a URL, e.g. `example.com` for `https://user@example.com:8080/a`
+fn domain(url: &str) -> &str {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+
+    match host.rsplit_once(':') {
+        Some((name, port)) if !name.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Serves requests with the status code given in the path, e.g. `/404`.
+    /// `HEAD` requests to `/no-head` are refused.
+    fn serve() -> (String, Arc<AtomicUsize>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let code = match (request.method(), request.url()) {
+                    (tiny_http::Method::Head, "/no-head") => 405,
+                    (_, "/no-head") => 200,
+                    (_, path) => path.trim_start_matches('/').parse::<u16>().unwrap_or(404),
+                };
+
+                let _ = request.respond(tiny_http::Response::empty(code));
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn opts() -> CheckOptions {
+        CheckOptions {
+            timeout: Duration::from_secs(5),
+            retries: 0,
+            per_domain: 2,
+        }
+    }
+
+    #[test]
+    fn reports_status_codes() {
+        let (url, _) = serve();
+        let urls = vec![format!("{}/200", url), format!("{}/404", url)];
+
+        assert_eq!(
+            check(&urls, &opts()),
+            vec![LinkStatus::Ok, LinkStatus::Status(404)]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_get() {
+        let (url, _) = serve();
+
+        assert_eq!(
+            check(&[format!("{}/no-head", url)], &opts()),
+            vec![LinkStatus::Ok]
+        );
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let (url, requests) = serve();
+        let opts = CheckOptions {
+            retries: 2,
+            ..opts()
+        };
+
+        assert_eq!(
+            check(&[format!("{}/503", url)], &opts),
+            vec![LinkStatus::Status(503)]
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn does_not_retry_client_errors() {
+        let (url, requests) = serve();
+        let opts = CheckOptions {
+            retries: 2,
+            ..opts()
+        };
+
+        check(&[format!("{}/404", url)], &opts);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn reports_unreachable_hosts() {
+        // Nothing listens on port 9 of the loopback address
+        let status = check(&["http://127.0.0.1:9/".to_string()], &opts());
+
+        assert!(matches!(status[0], LinkStatus::Failed(_)), "{:?}", status);
+    }
+
+    #[test]
+    fn limits_requests_per_domain() {
+        let queue = Queue {
+            state: Mutex::new(QueueState {
+                pending: (0..3).collect(),
+                active: HashMap::new(),
+            }),
+            changed: Condvar::new(),
+            per_domain: 1,
+        };
+        let urls = vec![
+            "https://a.com/1".to_string(),
+            "https://a.com/2".to_string(),
+            "https://b.com/1".to_string(),
+        ];
+
+        assert_eq!(queue.take(&urls), Some(0));
+        // The second a.com link has to wait for the first to finish
+        assert_eq!(queue.take(&urls), Some(2));
+
+        queue.finish(&urls[0]);
+        assert_eq!(queue.take(&urls), Some(1));
+        assert_eq!(queue.take(&urls), None);
+    }
+
+    #[test]
+    fn finds_the_domain() {
+        assert_eq!(domain("https://example.com"), "example.com");
+        assert_eq!(domain("https://example.com/a?b#c"), "example.com");
+        assert_eq!(domain("https://user@example.com:8080/a"), "example.com");
+        assert_eq!(domain("http://[::1]:8080/"), "[::1]");
+    }
+}
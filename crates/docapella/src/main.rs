@@ -4,8 +4,17 @@ use std::io::Write;
 use std::path::PathBuf;
 
 use docapella::commands::build::{run as build, BuildArgs};
-use docapella::commands::dev::{run as dev, DevArgs};
+use docapella::commands::check::{run as check, CheckArgs};
+use docapella::commands::dev::{
+    run as dev, DevArgs, DEFAULT_HOST, DEFAULT_OPENAPI_REFRESH_SECS, DEFAULT_PORT,
+};
 use docapella::commands::init::{run as init, InitArgs};
+use docapella::commands::openapi_diff::{run as openapi_diff, DiffFormat, OpenApiDiffArgs};
+use docapella::commands::openapi_uris::{run as openapi_uris, OpenApiUrisArgs};
+use docapella::images::{ImageOptions, DEFAULT_WIDTHS};
+use docapella::link_checker::{CheckOptions, DEFAULT_PER_DOMAIN, DEFAULT_RETRIES, DEFAULT_TIMEOUT};
+use docapella::reporter::{JsonReporter, LogFormat, Reporter as _};
+use libdoctave::Boilerplate;
 
 #[derive(Parser, Debug, Clone)]
 #[command(about = "Docapella, a documentation generator", long_about = None)]
@@ -43,16 +52,112 @@ enum Commands {
     Init {
         #[arg(default_value = ".")]
         working_dir: PathBuf,
+        /// The starter project to create
+        #[arg(long, value_enum, default_value = "basic")]
+        template: Boilerplate,
+        /// The title of the project, shown in the browser tab
+        #[arg(long)]
+        title: Option<String>,
+        /// Add the template's missing files to an existing project. Existing files are never overwritten
+        #[arg(long)]
+        force: bool,
     },
     /// Build your documentation and create a publishable bundle
     Build {
         #[arg(default_value = ".")]
         working_dir: PathBuf,
+        /// Render the pages under this URI prefix into a single HTML file, e.g. for printing
+        #[arg(long, value_name = "PREFIX")]
+        single_page: Option<String>,
+        /// Read when pages were last updated, and by whom, from git history
+        #[arg(long)]
+        git_metadata: bool,
+        /// Print the size of the pages and assets, and write them to `_build/stats.json`
+        #[arg(long, conflicts_with = "single_page")]
+        stats: bool,
+        /// Print a health score for the documentation, and write the details to `_build/health.json`
+        #[arg(long, conflicts_with = "single_page")]
+        health: bool,
+        /// Generate resized and WebP versions of the images shown on pages
+        #[arg(long, conflicts_with = "single_page")]
+        responsive_images: bool,
+        /// Widths to resize images to, with `--responsive-images`
+        #[arg(long, value_delimiter = ',', default_values_t = DEFAULT_WIDTHS.to_vec())]
+        image_widths: Vec<u32>,
+        /// Rebuild without writing `_build`, and fail if the files differ from the ones in this manifest
+        #[arg(long, value_name = "PATH", conflicts_with = "single_page")]
+        verify_manifest: Option<PathBuf>,
+        /// How to report progress. `json` writes newline-delimited JSON events
+        #[arg(long, value_enum, default_value = "human")]
+        log_format: LogFormat,
+    },
+    /// Verify your documentation without building it
+    Check {
+        #[arg(default_value = ".")]
+        working_dir: PathBuf,
+        /// Request links to other sites, and warn about the ones that don't respond
+        #[arg(long)]
+        external_links: bool,
+        /// Seconds to wait for each external link
+        #[arg(long, default_value_t = DEFAULT_TIMEOUT.as_secs())]
+        timeout: u64,
+        /// How many times to retry external links that time out or give a server error
+        #[arg(long, default_value_t = DEFAULT_RETRIES)]
+        retries: u32,
+        /// How many external links to request at once from a single domain
+        #[arg(long, default_value_t = DEFAULT_PER_DOMAIN)]
+        max_per_domain: usize,
+        /// Replace API keys and tokens in pages and OpenAPI specs with a placeholder
+        #[arg(long)]
+        redact: bool,
+        /// With `--redact`, show what would change without changing any files
+        #[arg(long, requires = "redact")]
+        dry_run: bool,
+        /// Group the issues by who owns their files, as set in `owners` or `.docapella-owners`
+        #[arg(long)]
+        by_owner: bool,
+        /// How to report progress. `json` writes newline-delimited JSON events
+        #[arg(long, value_enum, default_value = "human")]
+        log_format: LogFormat,
     },
     /// Run a local server to preview your documentation
     Dev {
         #[arg(default_value = ".")]
         working_dir: PathBuf,
+        /// Port to listen on. The next free port is used if it's taken
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// Address to listen on. Use `0.0.0.0` to reach the server from other devices
+        #[arg(long, default_value = DEFAULT_HOST)]
+        host: String,
+        /// Fail instead of using another port when `--port` is taken
+        #[arg(long)]
+        strict_port: bool,
+        /// Open the site in your browser once the server is running
+        #[arg(long)]
+        open: bool,
+        /// Seconds between fetches of OpenAPI specs set with `spec_url`. Use 0 to
+        /// only fetch them at startup, and when Enter is pressed
+        #[arg(long, default_value_t = DEFAULT_OPENAPI_REFRESH_SECS)]
+        openapi_refresh: u64,
+        /// How to report progress. `json` writes newline-delimited JSON events
+        #[arg(long, value_enum, default_value = "human")]
+        log_format: LogFormat,
+    },
+    /// Compare two versions of an OpenAPI spec and print a changelog
+    OpenapiDiff {
+        old: PathBuf,
+        new: PathBuf,
+        #[arg(long, value_enum, default_value = "md")]
+        format: DiffFormat,
+    },
+    /// Print the URI of every operation in your OpenAPI specs
+    OpenapiUris {
+        #[arg(default_value = ".")]
+        working_dir: PathBuf,
+        /// Show how the URIs change with `uri_strategy: operation_id`
+        #[arg(long)]
+        plan: bool,
     },
 }
 
@@ -62,20 +167,103 @@ fn main() {
 
     let mut stdout = std::io::stdout();
 
+    let log_format = match &args.command {
+        Some(Commands::Build { log_format, .. })
+        | Some(Commands::Check { log_format, .. })
+        | Some(Commands::Dev { log_format, .. }) => *log_format,
+        _ => LogFormat::Human,
+    };
+
     let result = match args.command {
-        Some(Commands::Init { working_dir }) => init(InitArgs {
+        Some(Commands::Init {
+            working_dir,
+            template,
+            title,
+            force,
+        }) => init(InitArgs {
             working_dir: &working_dir,
-            title: None,
+            template,
+            title: title.as_deref(),
+            force,
             stdout: &mut stdout,
         }),
-        Some(Commands::Build { working_dir }) => build(BuildArgs {
+        Some(Commands::Build {
+            working_dir,
+            single_page,
+            git_metadata,
+            stats,
+            health,
+            responsive_images,
+            image_widths,
+            verify_manifest,
+            log_format,
+        }) => build(BuildArgs {
             out_dir: working_dir.join("_build"),
             working_dir,
+            single_page,
+            git_metadata,
+            stats,
+            health,
+            images: responsive_images.then(|| ImageOptions {
+                widths: image_widths,
+                webp: true,
+            }),
+            verify_manifest,
+            log_format,
             stdout: &mut stdout,
         }),
-        Some(Commands::Dev { working_dir }) => dev(DevArgs {
+        Some(Commands::Check {
             working_dir,
-            port: None, // Use default port 8080
+            external_links,
+            timeout,
+            retries,
+            max_per_domain,
+            redact,
+            dry_run,
+            by_owner,
+            log_format,
+        }) => check(CheckArgs {
+            working_dir,
+            external_links,
+            link_options: CheckOptions {
+                timeout: std::time::Duration::from_secs(timeout),
+                retries,
+                per_domain: max_per_domain,
+            },
+            redact,
+            dry_run,
+            by_owner,
+            log_format,
+            stdout: &mut stdout,
+        }),
+        Some(Commands::Dev {
+            working_dir,
+            port,
+            host,
+            strict_port,
+            open,
+            openapi_refresh,
+            log_format,
+        }) => dev(DevArgs {
+            working_dir,
+            host,
+            port,
+            strict_port,
+            open,
+            openapi_refresh: (openapi_refresh > 0)
+                .then(|| std::time::Duration::from_secs(openapi_refresh)),
+            log_format,
+            stdout: &mut stdout,
+        }),
+        Some(Commands::OpenapiDiff { old, new, format }) => openapi_diff(OpenApiDiffArgs {
+            old,
+            new,
+            format,
+            stdout: &mut stdout,
+        }),
+        Some(Commands::OpenapiUris { working_dir, plan }) => openapi_uris(OpenApiUrisArgs {
+            working_dir,
+            plan,
             stdout: &mut stdout,
         }),
         None => {
@@ -85,6 +273,13 @@ fn main() {
     };
 
     if let Err(e) = result {
+        if log_format == LogFormat::Json {
+            JsonReporter::new(&mut stdout)
+                .report_failure(&e)
+                .expect("Failed to write to stdout");
+            std::process::exit(1);
+        }
+
         match e {
             docapella::Error::General(msg) => {
                 writeln!(&mut stdout, "{}", msg.red()).expect("Failed to write to stdout");
@@ -92,6 +287,9 @@ fn main() {
             docapella::Error::IoError(e) => {
                 writeln!(&mut stdout, "{}", e.red()).expect("Failed to write to stdout");
             }
+            docapella::Error::TooManyFiles(_) => {
+                writeln!(&mut stdout, "{}", e.red()).expect("Failed to write to stdout");
+            }
             docapella::Error::FatalBuildError(errors) => {
                 writeln!(
                     &mut stdout,
@@ -0,0 +1,213 @@
+//! The list of files in a build with their hashes, for checking that a build
+//! is what it claims to be. Builds of the same files are byte for byte the
+//! same, so rebuilding a project gives the same manifest.
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::Path;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Version of the `manifest.json` format. Bump this when its shape changes.
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    /// Sorted by path
+    pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Relative to the output directory, separated with `/` on all platforms
+    pub path: String,
+    /// Hex encoded SHA-256 of the contents
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// A difference between two manifests.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    Added(String),
+    Removed(String),
+    Changed(String),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added(path) => write!(f, "added:   {}", path),
+            Change::Removed(path) => write!(f, "removed: {}", path),
+            Change::Changed(path) => write!(f, "changed: {}", path),
+        }
+    }
+}
+
+impl Manifest {
+    /// Lists the files in the output directory, other than the manifest.
+    pub fn of_dir(out_dir: &Path) -> Result<Self> {
+        let mut files = vec![];
+        list_files(out_dir, out_dir, &mut files)?;
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(Manifest {
+            version: MANIFEST_VERSION,
+            files,
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+
+        serde_json::from_str(&content).map_err(|e| {
+            Error::General(format!("Could not read manifest {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn write(&self, out_dir: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::General(format!("Could not serialize manifest: {}", e)))?;
+
+        std::fs::write(out_dir.join(MANIFEST_FILE_NAME), json)?;
+
+        Ok(())
+    }
+
+    /// What changed from this manifest to the other, sorted by path.
+    pub fn diff(&self, other: &Manifest) -> Vec<Change> {
+        let mut changes = vec![];
+
+        for entry in &self.files {
+            match other.files.iter().find(|o| o.path == entry.path) {
+                None => changes.push(Change::Removed(entry.path.clone())),
+                Some(o) if o.sha256 != entry.sha256 || o.size != entry.size => {
+                    changes.push(Change::Changed(entry.path.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+
+        for entry in &other.files {
+            if !self.files.iter().any(|e| e.path == entry.path) {
+                changes.push(Change::Added(entry.path.clone()));
+            }
+        }
+
+        changes.sort_by(|a, b| path_of(a).cmp(path_of(b)));
+        changes
+    }
+}
+
+fn path_of(change: &Change) -> &str {
+    match change {
+        Change::Added(path) | Change::Removed(path) | Change::Changed(path) => path,
+    }
+}
+
+fn list_files(root: &Path, dir: &Path, out: &mut Vec<ManifestEntry>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            list_files(root, &path, out)?;
+            continue;
+        }
+
+        let relative = path
+            .strip_prefix(root)
+            .expect("Listed file was not in the output directory");
+
+        if relative == Path::new(MANIFEST_FILE_NAME) {
+            continue;
+        }
+
+        let bytes = std::fs::read(&path)?;
+
+        out.push(ManifestEntry {
+            path: relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            sha256: format!("{:x}", Sha256::digest(&bytes)),
+            size: bytes.len() as u64,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use temp_dir::TempDir;
+
+    #[test]
+    fn lists_files_with_their_hashes() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("guides")).unwrap();
+        fs::write(dir.path().join("index.html"), "hello").unwrap();
+        fs::write(dir.path().join("guides/setup.html"), "").unwrap();
+        fs::write(dir.path().join(MANIFEST_FILE_NAME), "{}").unwrap();
+
+        let manifest = Manifest::of_dir(dir.path()).unwrap();
+
+        assert_eq!(
+            manifest.files,
+            vec![
+                ManifestEntry {
+                    path: "guides/setup.html".to_owned(),
+                    sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                        .to_owned(),
+                    size: 0,
+                },
+                ManifestEntry {
+                    path: "index.html".to_owned(),
+                    sha256: "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+                        .to_owned(),
+                    size: 5,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_manifests() {
+        let entry = |path: &str, sha256: &str| ManifestEntry {
+            path: path.to_owned(),
+            sha256: sha256.to_owned(),
+            size: 1,
+        };
+
+        let old = Manifest {
+            version: MANIFEST_VERSION,
+            files: vec![
+                entry("a.html", "1"),
+                entry("b.html", "2"),
+                entry("c.html", "3"),
+            ],
+        };
+        let new = Manifest {
+            version: MANIFEST_VERSION,
+            files: vec![
+                entry("b.html", "2"),
+                entry("c.html", "4"),
+                entry("d.html", "5"),
+            ],
+        };
+
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                Change::Removed("a.html".to_owned()),
+                Change::Changed("c.html".to_owned()),
+                Change::Added("d.html".to_owned()),
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+}
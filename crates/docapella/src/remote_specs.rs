@@ -0,0 +1,367 @@
+//! OpenAPI specs fetched from a `spec_url` in the settings.
+//!
+//! libdoctave doesn't do IO, so remote specs are fetched here and given to
+//! the project as if they were files at their `spec_file`. Fetched specs are
+//! cached, and requested again with the `ETag` and `Last-Modified` of the
+//! cached copy, so that unchanged specs aren't downloaded again. When a spec
+//! can't be fetched, the cached copy is used instead.
+use crate::reporter::{Event, Phase, Reporter};
+use crate::Result;
+use libdoctave::settings::{OpenApi, Settings};
+use libdoctave::{InputContent, InputFile, SETTINGS_FILE_NAME};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Where fetched specs are cached, relative to the working directory.
+pub const CACHE_DIR: &str = ".docapella-cache/openapi";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Stored next to each cached spec, for the conditional request.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FetchStatus {
+    /// A new version of the spec was downloaded
+    Updated,
+    /// The cached copy is up to date
+    Unchanged,
+    Failed(String),
+}
+
+/// The OpenAPI specs in the settings that are fetched from a URL. Settings
+/// that can't be read or parsed are reported by the build, so there are no
+/// remote specs until they're fixed.
+pub(crate) fn remote_specs(working_dir: &Path) -> Vec<OpenApi> {
+    std::fs::read_to_string(working_dir.join(SETTINGS_FILE_NAME))
+        .ok()
+        .and_then(|content| Settings::parse(&content).ok())
+        .map(|settings| {
+            settings
+                .open_api()
+                .iter()
+                .filter(|spec| spec.spec_url.is_some())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Fetches every remote spec into the cache, and reports the ones that
+/// couldn't be fetched as warnings. Returns whether any of them changed.
+pub fn fetch(working_dir: &Path, reporter: &mut dyn Reporter) -> Result<bool> {
+    let specs = remote_specs(working_dir);
+
+    if specs.is_empty() {
+        return Ok(false);
+    }
+
+    let start = std::time::Instant::now();
+    reporter.report(Event::PhaseStarted {
+        phase: Phase::FetchSpecs,
+    })?;
+
+    let cache_dir = working_dir.join(CACHE_DIR);
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout(TIMEOUT)
+        .user_agent(concat!("docapella/", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let mut changed = false;
+
+    for url in specs.iter().filter_map(|spec| spec.spec_url.as_deref()) {
+        match fetch_spec(&agent, &cache_dir, url) {
+            FetchStatus::Updated => changed = true,
+            FetchStatus::Unchanged => {}
+            FetchStatus::Failed(reason) => {
+                let fallback = if cache_path(&cache_dir, url).exists() {
+                    "Using the cached copy instead."
+                } else {
+                    "There is no cached copy to use instead."
+                };
+
+                reporter.report(Event::Warning {
+                    message: &format!(
+                        "Could not fetch OpenAPI spec from {}: {}\n{}",
+                        url, reason, fallback
+                    ),
+                    owner: None,
+                })?;
+            }
+        }
+    }
+
+    reporter.report(Event::PhaseFinished {
+        phase: Phase::FetchSpecs,
+        duration: start.elapsed(),
+    })?;
+
+    Ok(changed)
+}
+
+/// Adds the cached copies of the remote specs to the files of the project.
+/// Specs that are in the project already, e.g. because they were committed,
+/// are left alone, as are ones that have never been fetched.
+pub(crate) fn add_cached(working_dir: &Path, files: &mut Vec<InputFile>) {
+    let cache_dir = working_dir.join(CACHE_DIR);
+
+    for spec in remote_specs(working_dir) {
+        let Some(url) = spec.spec_url.as_deref() else {
+            continue;
+        };
+
+        if files.iter().any(|file| file.path == spec.spec_file) {
+            continue;
+        }
+
+        if let Ok(content) = std::fs::read_to_string(cache_path(&cache_dir, url)) {
+            files.push(InputFile {
+                path: spec.spec_file.clone(),
+                content: InputContent::Text(content),
+            });
+        }
+    }
+}
+
+fn fetch_spec(agent: &ureq::Agent, cache_dir: &Path, url: &str) -> FetchStatus {
+    let path = cache_path(cache_dir, url);
+    let entry_path = path.with_extension("json");
+
+    let cached = std::fs::read_to_string(&path).ok();
+    let entry = cached
+        .as_ref()
+        .and_then(|_| std::fs::read_to_string(&entry_path).ok())
+        .and_then(|json| serde_json::from_str::<CacheEntry>(&json).ok())
+        .filter(|entry| entry.url == url)
+        .unwrap_or_default();
+
+    let mut request = agent.get(url);
+    if let Some(etag) = &entry.etag {
+        request = request.set("If-None-Match", etag);
+    }
+    if let Some(last_modified) = &entry.last_modified {
+        request = request.set("If-Modified-Since", last_modified);
+    }
+
+    let response = match request.call() {
+        Ok(response) if response.status() == 304 && cached.is_some() => {
+            return FetchStatus::Unchanged
+        }
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, _)) => {
+            return FetchStatus::Failed(format!("status {}", code))
+        }
+        Err(ureq::Error::Transport(e)) => return FetchStatus::Failed(e.to_string()),
+    };
+
+    let entry = CacheEntry {
+        url: url.to_string(),
+        etag: response.header("ETag").map(str::to_owned),
+        last_modified: response.header("Last-Modified").map(str::to_owned),
+    };
+
+    let content = match response.into_string() {
+        Ok(content) => content,
+        Err(e) => return FetchStatus::Failed(e.to_string()),
+    };
+
+    let written = std::fs::write(&path, &content).and_then(|_| {
+        std::fs::write(
+            &entry_path,
+            serde_json::to_string(&entry).unwrap_or_default(),
+        )
+    });
+
+    match written {
+        Err(e) => FetchStatus::Failed(format!("could not write the cache: {}", e)),
+        Ok(()) if cached.as_deref() == Some(content.as_str()) => FetchStatus::Unchanged,
+        Ok(()) => FetchStatus::Updated,
+    }
+}
+
+/// Specs are cached by a hash of their URL, since URLs can't be used as file
+/// names as they are.
+fn cache_path(cache_dir: &Path, url: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+
+    cache_dir.join(format!("{:016x}.spec", hasher.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use temp_dir::TempDir;
+
+    const SPEC: &str = r#"{"openapi":"3.0.0","info":{"title":"Pets","version":"1"},"paths":{}}"#;
+
+    /// Serves the spec at `/spec.json` with an `ETag`, answering requests
+    /// that have it with a 304. Anything else is a 404.
+    fn serve() -> (String, Arc<AtomicUsize>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let downloads = Arc::new(AtomicUsize::new(0));
+        let counter = downloads.clone();
+
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let etag = tiny_http::Header::from_bytes("ETag", "\"v1\"").unwrap();
+                let revalidated = request
+                    .headers()
+                    .iter()
+                    .any(|h| h.field.equiv("If-None-Match") && h.value.as_str() == "\"v1\"");
+
+                let response = match request.url() {
+                    "/spec.json" if revalidated => tiny_http::Response::empty(304).boxed(),
+                    "/spec.json" => {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        tiny_http::Response::from_string(SPEC)
+                            .with_header(etag)
+                            .boxed()
+                    }
+                    _ => tiny_http::Response::empty(404).boxed(),
+                };
+
+                let _ = request.respond(response);
+            }
+        });
+
+        (url, downloads)
+    }
+
+    fn project_with_spec(url: &str) -> TempDir {
+        let working_dir = TempDir::new().unwrap();
+        std::fs::write(
+            working_dir.path().join(SETTINGS_FILE_NAME),
+            format!(
+                "---\ntitle: Pets\nopen_api:\n  - spec_url: {}\n    spec_file: pets.json\n    uri_prefix: /api\n",
+                url
+            ),
+        )
+        .unwrap();
+
+        working_dir
+    }
+
+    fn fetch_quietly(working_dir: &Path) -> (bool, String) {
+        let mut out = Vec::new();
+        let changed = {
+            let mut reporter = crate::reporter::LogFormat::Human.reporter(&mut out);
+            fetch(working_dir, reporter.as_mut()).unwrap()
+        };
+
+        (changed, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn fetches_specs_into_the_cache() {
+        let (url, _) = serve();
+        let working_dir = project_with_spec(&format!("{}/spec.json", url));
+
+        let (changed, _) = fetch_quietly(working_dir.path());
+        assert!(changed);
+
+        let mut files = vec![];
+        add_cached(working_dir.path(), &mut files);
+
+        assert_eq!(
+            files,
+            vec![InputFile {
+                path: PathBuf::from("pets.json"),
+                content: InputContent::Text(SPEC.to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn revalidates_cached_specs() {
+        let (url, downloads) = serve();
+        let working_dir = project_with_spec(&format!("{}/spec.json", url));
+
+        fetch_quietly(working_dir.path());
+        let (changed, _) = fetch_quietly(working_dir.path());
+
+        assert!(!changed);
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn falls_back_to_the_cached_copy() {
+        let (url, _) = serve();
+        let working_dir = project_with_spec(&format!("{}/spec.json", url));
+        fetch_quietly(working_dir.path());
+
+        // A spec whose URL now fails, with the copy cached above
+        let broken = format!("{}/gone.json", url);
+        let cached = cache_path(
+            &working_dir.path().join(CACHE_DIR),
+            &format!("{}/spec.json", url),
+        );
+        let working_dir = project_with_spec(&broken);
+        let cache_dir = working_dir.path().join(CACHE_DIR);
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        std::fs::copy(cached, cache_path(&cache_dir, &broken)).unwrap();
+
+        let (changed, output) = fetch_quietly(working_dir.path());
+
+        assert!(!changed);
+        assert!(
+            output.contains("Could not fetch OpenAPI spec"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("Using the cached copy instead."),
+            "{}",
+            output
+        );
+
+        let mut files = vec![];
+        add_cached(working_dir.path(), &mut files);
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn warns_when_there_is_no_cached_copy() {
+        let (url, _) = serve();
+        let working_dir = project_with_spec(&format!("{}/gone.json", url));
+
+        let (changed, output) = fetch_quietly(working_dir.path());
+
+        assert!(!changed);
+        assert!(output.contains("status 404"), "{}", output);
+        assert!(output.contains("There is no cached copy"), "{}", output);
+
+        let mut files = vec![];
+        add_cached(working_dir.path(), &mut files);
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn leaves_specs_in_the_project_alone() {
+        let (url, _) = serve();
+        let working_dir = project_with_spec(&format!("{}/spec.json", url));
+        fetch_quietly(working_dir.path());
+
+        let mut files = vec![InputFile {
+            path: PathBuf::from("pets.json"),
+            content: InputContent::Text("{}".to_string()),
+        }];
+        add_cached(working_dir.path(), &mut files);
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].content, InputContent::Text("{}".to_string()));
+    }
+}
@@ -0,0 +1,558 @@
+//! Progress reporting for builds and the dev server.
+//!
+//! The builder never writes to stdout directly. Instead it emits [`Event`]s to
+//! a [`Reporter`], which decides how to present them. This keeps the colored
+//! terminal output and the machine readable JSON output in sync, and lets
+//! other frontends hook into the same events.
+use libdoctave::statistics::format_bytes;
+use owo_colors::{OwoColorize as _, Stream};
+use serde::{Serialize, Serializer};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How progress is written to stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+#[clap(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Colored output meant for humans
+    #[default]
+    Human,
+    /// Newline-delimited JSON events
+    Json,
+}
+
+impl LogFormat {
+    pub fn reporter<'a, W: Write>(self, out: &'a mut W) -> Box<dyn Reporter + 'a> {
+        match self {
+            LogFormat::Human => Box::new(HumanReporter::new(out)),
+            LogFormat::Json => Box::new(JsonReporter::new(out)),
+        }
+    }
+
+    /// Like [`LogFormat::reporter`], but the terminal output groups the
+    /// issues by owner. JSON events include the owner either way.
+    pub fn reporter_grouped_by_owner<'a, W: Write>(self, out: &'a mut W) -> Box<dyn Reporter + 'a> {
+        match self {
+            LogFormat::Human => Box::new(HumanReporter::new(out).grouped_by_owner()),
+            LogFormat::Json => Box::new(JsonReporter::new(out)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// The whole build, from verification to the search index
+    Build,
+    Verify,
+    /// Resizing images, when responsive images are enabled
+    Images,
+    Render,
+    Assets,
+    SearchIndex,
+    /// Requesting links to other sites, with `docapella check --external-links`
+    ExternalLinks,
+    /// Downloading OpenAPI specs set with `spec_url`
+    FetchSpecs,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    PhaseStarted {
+        phase: Phase,
+    },
+    PhaseFinished {
+        phase: Phase,
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+    },
+    PageRendered {
+        uri_path: &'a str,
+        out_path: &'a PathBuf,
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+    },
+    /// A single file was written, e.g. by a single page build
+    FileWritten {
+        path: &'a PathBuf,
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+    },
+    /// Something went wrong, but the build can continue
+    Warning {
+        message: &'a str,
+        /// Who owns the file the warning is about, if owners are set
+        #[serde(skip_serializing_if = "Option::is_none")]
+        owner: Option<&'a str>,
+    },
+    /// An issue found in the project, e.g. a broken link
+    Error {
+        error: &'a libdoctave::Error,
+    },
+    /// The build could not be completed
+    Failed {
+        message: &'a str,
+    },
+    /// The dev server is listening. `network_url` is set when it can be
+    /// reached from other devices.
+    ServerStarted {
+        url: &'a str,
+        network_url: Option<&'a str>,
+    },
+    /// The dev server noticed a change and is about to rebuild
+    ChangeDetected,
+    /// Sizes of the built content, written to `path`
+    Statistics {
+        stats: &'a libdoctave::ProjectStatistics,
+        path: &'a PathBuf,
+    },
+    /// The health score of the project, written to `path` if set
+    Health {
+        report: &'a libdoctave::HealthReport,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<&'a PathBuf>,
+    },
+}
+
+fn as_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
+pub trait Reporter {
+    fn report(&mut self, event: Event) -> std::io::Result<()>;
+
+    /// Reports an error that stopped a command.
+    fn report_failure(&mut self, error: &crate::Error) -> std::io::Result<()> {
+        if let crate::Error::FatalBuildError(errors) = error {
+            for error in errors {
+                self.report(Event::Error { error })?;
+            }
+        }
+
+        self.report(Event::Failed {
+            message: &error.to_string(),
+        })
+    }
+}
+
+/// Writes each event as a JSON object on its own line.
+pub struct JsonReporter<'a, W: Write> {
+    out: &'a mut W,
+}
+
+impl<'a, W: Write> JsonReporter<'a, W> {
+    pub fn new(out: &'a mut W) -> Self {
+        JsonReporter { out }
+    }
+}
+
+impl<W: Write> Reporter for JsonReporter<'_, W> {
+    fn report(&mut self, event: Event) -> std::io::Result<()> {
+        serde_json::to_writer(&mut *self.out, &event)?;
+        writeln!(self.out)?;
+        self.out.flush()
+    }
+}
+
+/// The regular terminal output.
+pub struct HumanReporter<'a, W: Write> {
+    out: &'a mut W,
+    /// Issues are buffered while verifying, so that they can be printed
+    /// together with a summary
+    issues: Vec<libdoctave::Error>,
+    phase: Option<Phase>,
+    /// Print the issues grouped by the owner of their files
+    by_owner: bool,
+}
+
+impl<'a, W: Write> HumanReporter<'a, W> {
+    pub fn new(out: &'a mut W) -> Self {
+        HumanReporter {
+            out,
+            issues: vec![],
+            phase: None,
+            by_owner: false,
+        }
+    }
+
+    /// Prints the issues found while verifying grouped by who owns them,
+    /// with unowned issues last.
+    pub fn grouped_by_owner(mut self) -> Self {
+        self.by_owner = true;
+        self
+    }
+
+    fn print_issues(&mut self, duration: Duration) -> std::io::Result<()> {
+        if self.issues.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            self.out,
+            "Found {} issues while building documentation in {:?}",
+            self.issues.len(),
+            duration
+        )?;
+
+        let mut issues = std::mem::take(&mut self.issues);
+        let mut owner = None;
+
+        if self.by_owner {
+            // Stable, so issues keep their order within an owner
+            issues.sort_by(|a, b| match (&a.owner, &b.owner) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
+        }
+
+        for (index, issue) in issues.iter().enumerate() {
+            if self.by_owner && owner != Some(&issue.owner) {
+                owner = Some(&issue.owner);
+                let count = issues[index..]
+                    .iter()
+                    .take_while(|i| i.owner == issue.owner)
+                    .count();
+
+                writeln!(
+                    self.out,
+                    "============================================\n{} ({} issues)",
+                    issue
+                        .owner
+                        .as_deref()
+                        .unwrap_or("No owner")
+                        .if_supports_color(Stream::Stdout, |s| s.bold()),
+                    count
+                )?;
+            }
+
+            writeln!(
+                self.out,
+                "--------------------------------------------\n{} {}\n",
+                issue.message.bold(),
+                issue
+                    .file
+                    .as_ref()
+                    .map(|f| format!("[{}]", f.display()))
+                    .unwrap_or(String::from(""))
+                    .bold()
+            )?;
+            writeln!(self.out, "{}", issue.description)?;
+
+            for suggestion in &issue.suggestions {
+                writeln!(self.out, "Did you mean `{}`?", suggestion.replacement)?;
+            }
+        }
+
+        writeln!(self.out, "--------------------------------------------",)
+    }
+
+    fn print_statistics(
+        &mut self,
+        stats: &libdoctave::ProjectStatistics,
+        path: &PathBuf,
+    ) -> std::io::Result<()> {
+        let heading = |s: &str| {
+            s.if_supports_color(Stream::Stdout, |s| s.bold())
+                .to_string()
+        };
+
+        writeln!(self.out, "{}", heading("Content statistics"))?;
+        writeln!(
+            self.out,
+            "  {:>10}  content",
+            format_bytes(stats.content_size_bytes)
+        )?;
+        writeln!(
+            self.out,
+            "  {:>10}  assets",
+            format_bytes(stats.assets_size_bytes)
+        )?;
+        writeln!(
+            self.out,
+            "  {} pages, {} partials, {} components",
+            stats.page_count, stats.partial_count, stats.component_count
+        )?;
+
+        let mut largest_pages = stats.pages.iter().collect::<Vec<_>>();
+        largest_pages.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+        largest_pages.truncate(libdoctave::statistics::LARGEST_ASSETS_COUNT);
+
+        if !largest_pages.is_empty() {
+            writeln!(self.out, "{}", heading("Largest pages"))?;
+            for page in largest_pages {
+                writeln!(
+                    self.out,
+                    "  {:>10}  {}",
+                    format_bytes(page.size_bytes),
+                    page.path.display()
+                )?;
+            }
+        }
+
+        if !stats.largest_assets.is_empty() {
+            writeln!(self.out, "{}", heading("Largest assets"))?;
+            for asset in &stats.largest_assets {
+                writeln!(
+                    self.out,
+                    "  {:>10}  {}",
+                    format_bytes(asset.size_bytes),
+                    asset.path.display()
+                )?;
+            }
+        }
+
+        if !stats.open_api_specs.is_empty() {
+            writeln!(self.out, "{}", heading("OpenAPI specs"))?;
+            for spec in &stats.open_api_specs {
+                writeln!(
+                    self.out,
+                    "  {:>10}  {}",
+                    format_bytes(spec.size_bytes),
+                    spec.path.display()
+                )?;
+            }
+        }
+
+        writeln!(
+            self.out,
+            "{} {}",
+            "Wrote".if_supports_color(Stream::Stdout, |s| s.green()),
+            path.display()
+                .if_supports_color(Stream::Stdout, |s| s.bold()),
+        )
+    }
+
+    fn print_health(
+        &mut self,
+        report: &libdoctave::HealthReport,
+        path: Option<&PathBuf>,
+    ) -> std::io::Result<()> {
+        writeln!(
+            self.out,
+            "{}",
+            format!("Health score {}/100", report.score)
+                .if_supports_color(Stream::Stdout, |s| s.bold())
+        )?;
+
+        for part in &report.parts {
+            writeln!(
+                self.out,
+                "  {:>5.1}  {} (weight {})",
+                part.score, part.name, part.weight
+            )?;
+        }
+
+        let mut counts = vec![
+            format!("{} pages", report.page_count),
+            format!("{} errors", report.errors.total),
+            format!("{} warnings", report.warnings.total),
+        ];
+        if let Some(orphans) = report.orphan_pages {
+            counts.push(format!("{} orphaned pages", orphans));
+        }
+        writeln!(self.out, "  {}", counts.join(", "))?;
+
+        if let Some(path) = path {
+            writeln!(
+                self.out,
+                "{} {}",
+                "Wrote".if_supports_color(Stream::Stdout, |s| s.green()),
+                path.display()
+                    .if_supports_color(Stream::Stdout, |s| s.bold()),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Reporter for HumanReporter<'_, W> {
+    fn report(&mut self, event: Event) -> std::io::Result<()> {
+        match event {
+            Event::PhaseStarted { phase } => {
+                self.phase = Some(phase);
+
+                match phase {
+                    Phase::Verify => writeln!(self.out, "Verifying project...")?,
+                    Phase::ExternalLinks => writeln!(self.out, "Checking external links...")?,
+                    Phase::FetchSpecs => writeln!(self.out, "Fetching OpenAPI specs...")?,
+                    _ => {}
+                }
+            }
+            Event::PhaseFinished { phase, duration } => {
+                self.phase = None;
+
+                match phase {
+                    Phase::Verify => self.print_issues(duration)?,
+                    Phase::Build => writeln!(
+                        self.out,
+                        "{} {}",
+                        "Build complete in".if_supports_color(Stream::Stdout, |s| s.green()),
+                        format!("{:?}", duration).if_supports_color(Stream::Stdout, |s| s.bold()),
+                    )?,
+                    _ => {}
+                }
+            }
+            Event::PageRendered { .. } => {}
+            Event::FileWritten { path, duration } => writeln!(
+                self.out,
+                "{} {} in {:?}",
+                "Wrote".if_supports_color(Stream::Stdout, |s| s.green()),
+                path.display()
+                    .if_supports_color(Stream::Stdout, |s| s.bold()),
+                duration,
+            )?,
+            Event::Warning { message, .. } => writeln!(self.out, "{}", message)?,
+            Event::Error { error } => {
+                if self.phase == Some(Phase::Verify) {
+                    self.issues.push(error.clone());
+                } else {
+                    writeln!(
+                        self.out,
+                        "{} | [{}]",
+                        error.message,
+                        error.file.clone().unwrap_or_default().display(),
+                    )?;
+                    writeln!(self.out, "{}", error.description)?;
+                }
+            }
+            Event::Failed { message } => writeln!(
+                self.out,
+                "{}",
+                message.if_supports_color(Stream::Stdout, |s| s.red())
+            )?,
+            Event::ServerStarted { url, network_url } => {
+                writeln!(self.out, "Dev server running on {}", url)?;
+                if let Some(network_url) = network_url {
+                    writeln!(self.out, "On your network: {}", network_url)?;
+                }
+                writeln!(self.out, "Watching for file changes...")?;
+            }
+            Event::ChangeDetected => writeln!(self.out, "Rebuilding...")?,
+            Event::Statistics { stats, path } => self.print_statistics(stats, path)?,
+            Event::Health { report, path } => self.print_health(report, path)?,
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error() -> libdoctave::Error {
+        libdoctave::Error {
+            code: libdoctave::Error::BROKEN_INTERNAL_LINK,
+            message: String::from("Broken link detected"),
+            description: String::from("Link ./foo.md does not exist"),
+            file: Some(PathBuf::from("README.md")),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        }
+    }
+
+    #[test]
+    fn writes_one_json_object_per_line() {
+        let mut out = Vec::new();
+        let mut reporter = JsonReporter::new(&mut out);
+
+        reporter
+            .report(Event::PhaseStarted {
+                phase: Phase::Verify,
+            })
+            .unwrap();
+        reporter.report(Event::Error { error: &error() }).unwrap();
+        reporter
+            .report(Event::PhaseFinished {
+                phase: Phase::Verify,
+                duration: Duration::from_millis(12),
+            })
+            .unwrap();
+
+        let lines = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["event"], "phase_started");
+        assert_eq!(lines[0]["phase"], "verify");
+        assert_eq!(lines[1]["event"], "error");
+        assert_eq!(
+            lines[1]["error"]["code"],
+            libdoctave::Error::BROKEN_INTERNAL_LINK
+        );
+        assert_eq!(lines[1]["error"]["file"], "README.md");
+        assert!(lines[1]["error"].get("suggestions").is_none());
+        assert_eq!(lines[2]["event"], "phase_finished");
+        assert_eq!(lines[2]["duration_ms"], 12);
+    }
+
+    #[test]
+    fn summarizes_verification_issues() {
+        let mut out = Vec::new();
+        let mut reporter = HumanReporter::new(&mut out);
+
+        reporter
+            .report(Event::PhaseStarted {
+                phase: Phase::Verify,
+            })
+            .unwrap();
+        reporter.report(Event::Error { error: &error() }).unwrap();
+        reporter.report(Event::Error { error: &error() }).unwrap();
+        reporter
+            .report(Event::PhaseFinished {
+                phase: Phase::Verify,
+                duration: Duration::from_millis(12),
+            })
+            .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with("Verifying project...\nFound 2 issues"));
+        assert_eq!(out.matches("Link ./foo.md does not exist").count(), 2);
+    }
+
+    #[test]
+    fn prints_suggested_fixes() {
+        let mut out = Vec::new();
+        let mut reporter = HumanReporter::new(&mut out);
+
+        let mut error = error();
+        error.suggestions.push(libdoctave::Suggestion {
+            file: PathBuf::from("README.md"),
+            start: 12,
+            end: 20,
+            replacement: String::from("./bar.md"),
+        });
+
+        reporter
+            .report(Event::PhaseStarted {
+                phase: Phase::Verify,
+            })
+            .unwrap();
+        reporter.report(Event::Error { error: &error }).unwrap();
+        reporter
+            .report(Event::PhaseFinished {
+                phase: Phase::Verify,
+                duration: Duration::from_millis(12),
+            })
+            .unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains("Link ./foo.md does not exist\nDid you mean `./bar.md`?\n"));
+    }
+
+    #[test]
+    fn formats_byte_sizes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MB");
+    }
+}
@@ -0,0 +1,500 @@
+use crate::file_gatherer::{IgnoreRules, IGNORE_FILE_NAME};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long the project has to be quiet before a rebuild starts
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// A burst of events that never goes quiet still rebuilds this often
+const MAX_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Files that editors and operating systems write next to the files being
+/// edited. Matched against the end of the file name.
+const TEMP_FILE_SUFFIXES: &[&str] = &[
+    // Vim swap and backup files
+    ".swp",
+    ".swo",
+    ".swx",
+    "~",
+    // JetBrains safe writes
+    "___jb_tmp___",
+    "___jb_old___",
+    // Generic atomic saves, e.g. VS Code and many libraries
+    ".tmp",
+    ".crswap",
+];
+
+/// Temporary files matched by their whole name, or the start of it
+const TEMP_FILE_NAMES: &[&str] = &[".DS_Store", "Thumbs.db", "4913"];
+const TEMP_FILE_PREFIXES: &[&str] = &[".#", ".goutputstream-", ".~lock."];
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum WatcherMessage {
+    RebuildNeeded,
+    WatchError(String),
+    /// Enter was pressed in the terminal, to fetch remote OpenAPI specs again
+    FetchSpecsRequested,
+}
+
+/// What happened to a file during a burst of events.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// Decides which changes under the working directory are worth a rebuild.
+pub(crate) struct WatchFilter {
+    rules: IgnoreRules,
+    /// The build output, both as given and with symlinks resolved, so that
+    /// it's left out wherever it's configured to go
+    out_dirs: Vec<PathBuf>,
+}
+
+impl WatchFilter {
+    pub(crate) fn new(working_dir: &Path, out_dir: &Path) -> crate::Result<Self> {
+        let rules = IgnoreRules::load(working_dir)?;
+
+        let out_dir = working_dir.join(out_dir);
+
+        let mut out_dirs = vec![out_dir.clone()];
+        // Events are reported relative to the canonical working directory
+        if let Ok(relative) = out_dir.strip_prefix(working_dir) {
+            out_dirs.push(rules.root().join(relative));
+        }
+        if let Ok(canonical) = out_dir.canonicalize() {
+            out_dirs.push(canonical);
+        }
+        out_dirs.dedup();
+
+        Ok(WatchFilter { rules, out_dirs })
+    }
+
+    /// The canonical working directory, which is what gets watched
+    pub(crate) fn root(&self) -> &Path {
+        self.rules.root()
+    }
+
+    fn reload_rules(&mut self) -> crate::Result<()> {
+        self.rules = IgnoreRules::load(&self.rules.root().to_path_buf())?;
+        Ok(())
+    }
+
+    pub(crate) fn should_rebuild(&self, path: &Path) -> bool {
+        // The ignore file isn't part of the project, but changing it changes
+        // which files are
+        if path.file_name() == Some(OsStr::new(IGNORE_FILE_NAME)) {
+            return true;
+        }
+
+        if self.out_dirs.iter().any(|dir| path.starts_with(dir)) || is_temp_file(path) {
+            return false;
+        }
+
+        // Skip files that aren't part of the project, like the build directory
+        if self.rules.is_ignored(path, path.is_dir()) {
+            return false;
+        }
+
+        // Check file extension
+        if let Some(extension) = path.extension() {
+            if let Some(ext_str) = extension.to_str() {
+                return matches!(
+                    ext_str,
+                    "md" | "yaml" | "yml" | "json" | "png" | "jpg" | "jpeg" | "svg" | "css" | "js"
+                );
+            }
+        }
+
+        // Also watch config files without extensions or special names
+        if let Some(file_name) = path.file_name() {
+            if let Some(name_str) = file_name.to_str() {
+                return matches!(name_str, "docapella.yaml" | "doctave.yaml");
+            }
+        }
+
+        false
+    }
+}
+
+/// Checks for the scratch files editors write while saving, like Vim's
+/// `.index.md.swp` or JetBrains' `index.md___jb_tmp___`.
+pub(crate) fn is_temp_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+        return false;
+    };
+
+    TEMP_FILE_NAMES.contains(&name)
+        || TEMP_FILE_PREFIXES.iter().any(|p| name.starts_with(p))
+        || TEMP_FILE_SUFFIXES.iter().any(|s| name.ends_with(s))
+        // Emacs auto-saves
+        || (name.len() > 1 && name.starts_with('#') && name.ends_with('#'))
+}
+
+/// Reduces a burst of events to one change per path. Renames become a single
+/// `Modified` event for their destination, since editors save atomically by
+/// writing a temporary file and renaming it over the original.
+pub(crate) fn coalesce(events: Vec<Event>) -> Vec<(PathBuf, ChangeKind)> {
+    let mut changes = Changes::default();
+    // Renames reported as two events are paired up by their tracker
+    let mut renamed_from: HashMap<usize, PathBuf> = HashMap::new();
+
+    for event in events {
+        match event.kind {
+            EventKind::Create(_) => event
+                .paths
+                .into_iter()
+                .for_each(|p| changes.record(p, ChangeKind::Created)),
+            EventKind::Remove(_) => event
+                .paths
+                .into_iter()
+                .for_each(|p| changes.record(p, ChangeKind::Removed)),
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                let mut paths = event.paths.into_iter();
+                if let (Some(from), Some(to)) = (paths.next(), paths.next()) {
+                    changes.renamed(from, to);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                match (event.attrs.tracker(), event.paths.into_iter().next()) {
+                    (Some(tracker), Some(from)) => {
+                        renamed_from.insert(tracker, from);
+                    }
+                    (None, Some(from)) => changes.record(from, ChangeKind::Removed),
+                    _ => {}
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                let from = event
+                    .attrs
+                    .tracker()
+                    .and_then(|tracker| renamed_from.remove(&tracker));
+
+                if let Some(to) = event.paths.into_iter().next() {
+                    match from {
+                        Some(from) => changes.renamed(from, to),
+                        None => changes.record(to, ChangeKind::Modified),
+                    }
+                }
+            }
+            // Reading files, like the build itself does, isn't a change
+            EventKind::Access(_) => {}
+            _ => event
+                .paths
+                .into_iter()
+                .for_each(|p| changes.record(p, ChangeKind::Modified)),
+        }
+    }
+
+    // Files renamed out of the project are gone
+    for (_, from) in renamed_from {
+        changes.record(from, ChangeKind::Removed);
+    }
+
+    changes.0
+}
+
+#[derive(Default)]
+struct Changes(Vec<(PathBuf, ChangeKind)>);
+
+impl Changes {
+    fn record(&mut self, path: PathBuf, kind: ChangeKind) {
+        match self.0.iter_mut().find(|(p, _)| *p == path) {
+            // A file that was created in the same burst is still new
+            Some((_, ChangeKind::Created)) if kind == ChangeKind::Modified => {}
+            Some((_, existing)) => *existing = kind,
+            None => self.0.push((path, kind)),
+        }
+    }
+
+    fn renamed(&mut self, from: PathBuf, to: PathBuf) {
+        // A temporary file that only existed during the burst never happened
+        if let Some(i) = self
+            .0
+            .iter()
+            .position(|(p, kind)| *p == from && *kind == ChangeKind::Created)
+        {
+            self.0.remove(i);
+        } else {
+            self.record(from, ChangeKind::Removed);
+        }
+
+        self.record(to, ChangeKind::Modified);
+    }
+}
+
+/// Watches the working directory, and sends a single `RebuildNeeded` for
+/// every burst of changes to files that are part of the project.
+///
+/// Returns once the receiving end of `watcher_tx` is dropped.
+pub(crate) fn watch(
+    working_dir: &Path,
+    out_dir: &Path,
+    watcher_tx: mpsc::Sender<WatcherMessage>,
+) -> Result<(), String> {
+    let mut filter = WatchFilter::new(working_dir, out_dir).map_err(|e| e.to_string())?;
+
+    let (event_tx, event_rx) = mpsc::channel::<notify::Result<Event>>();
+
+    let mut watcher = notify::recommended_watcher(event_tx)
+        .map_err(|e| format!("Failed to create file watcher: {:?}", e))?;
+
+    // Watch the canonical path so that event paths line up with the ignore rules
+    watcher
+        .watch(filter.root(), RecursiveMode::Recursive)
+        .map_err(|e| format!("Failed to start watching: {:?}", e))?;
+
+    while let Some(results) = next_burst(&event_rx) {
+        let mut events = vec![];
+
+        for result in results {
+            match result {
+                Ok(event) => events.push(event),
+                Err(e) => {
+                    if watcher_tx
+                        .send(WatcherMessage::WatchError(format!("Watch error: {:?}", e)))
+                        .is_err()
+                    {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+
+        let changes = coalesce(events);
+
+        if changes
+            .iter()
+            .any(|(path, _)| path.file_name() == Some(OsStr::new(IGNORE_FILE_NAME)))
+        {
+            if let Err(e) = filter.reload_rules() {
+                if watcher_tx
+                    .send(WatcherMessage::WatchError(e.to_string()))
+                    .is_err()
+                {
+                    return Ok(());
+                }
+            }
+        }
+
+        // Filter events to only rebuild-worthy files
+        let should_rebuild = changes.iter().any(|(path, _)| filter.should_rebuild(path));
+
+        if should_rebuild && watcher_tx.send(WatcherMessage::RebuildNeeded).is_err() {
+            return Ok(());
+        }
+    }
+
+    Err("File watcher stopped".to_string())
+}
+
+/// Waits for the next event, then collects everything that follows until
+/// the events stop for `DEBOUNCE`. Returns `None` once the watcher is gone.
+fn next_burst<T>(rx: &mpsc::Receiver<T>) -> Option<Vec<T>> {
+    let mut burst = vec![rx.recv().ok()?];
+    let started = Instant::now();
+
+    while started.elapsed() < MAX_DEBOUNCE {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(event) => burst.push(event),
+            Err(RecvTimeoutError::Timeout) => break,
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    Some(burst)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
+    use std::fs;
+    use std::thread;
+    use temp_dir::TempDir;
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths
+            .iter()
+            .fold(Event::new(kind), |e, p| e.add_path(PathBuf::from(p)))
+    }
+
+    /// Starts watching a project, and returns the channel the watcher reports to
+    fn start_watching(dir: &Path) -> mpsc::Receiver<WatcherMessage> {
+        let (tx, rx) = mpsc::channel();
+
+        let working_dir = dir.to_path_buf();
+        thread::spawn(move || watch(&working_dir, &working_dir.join("_build"), tx));
+
+        // Give the watcher time to start
+        thread::sleep(Duration::from_millis(300));
+        rx
+    }
+
+    /// Counts the rebuilds, once the watcher has had time to settle
+    fn rebuilds(rx: &mpsc::Receiver<WatcherMessage>) -> usize {
+        thread::sleep(DEBOUNCE * 6);
+        rx.try_iter()
+            .filter(|m| *m == WatcherMessage::RebuildNeeded)
+            .count()
+    }
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("README.md"), "# Hello").unwrap();
+        fs::create_dir(dir.path().join("_build")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn ignores_editor_temp_files() {
+        assert!(is_temp_file(Path::new(".README.md.swp")));
+        assert!(is_temp_file(Path::new("docs/README.md~")));
+        assert!(is_temp_file(Path::new("README.md___jb_tmp___")));
+        assert!(is_temp_file(Path::new(".DS_Store")));
+        assert!(is_temp_file(Path::new("#README.md#")));
+        assert!(is_temp_file(Path::new(".#README.md")));
+        assert!(!is_temp_file(Path::new("README.md")));
+        assert!(!is_temp_file(Path::new("#")));
+    }
+
+    #[test]
+    fn coalesces_renames_into_a_modification() {
+        let changes = coalesce(vec![
+            event(
+                EventKind::Create(CreateKind::File),
+                &["/docs/README.md.tmp"],
+            ),
+            event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                &["/docs/README.md.tmp"],
+            ),
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+                &["/docs/README.md.tmp"],
+            )
+            .set_tracker(1),
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::To)),
+                &["/docs/README.md"],
+            )
+            .set_tracker(1),
+        ]);
+
+        assert_eq!(
+            changes,
+            vec![(PathBuf::from("/docs/README.md"), ChangeKind::Modified)]
+        );
+
+        let changes = coalesce(vec![
+            event(
+                EventKind::Create(CreateKind::File),
+                &["/docs/README.md.tmp"],
+            ),
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["/docs/README.md.tmp", "/docs/README.md"],
+            ),
+        ]);
+
+        assert_eq!(
+            changes,
+            vec![(PathBuf::from("/docs/README.md"), ChangeKind::Modified)]
+        );
+    }
+
+    #[test]
+    fn files_renamed_away_are_removed() {
+        let changes = coalesce(vec![
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+                &["/docs/old.md"],
+            )
+            .set_tracker(2),
+            event(EventKind::Remove(RemoveKind::File), &["/docs/other.md"]),
+        ]);
+
+        assert_eq!(
+            changes,
+            vec![
+                (PathBuf::from("/docs/other.md"), ChangeKind::Removed),
+                (PathBuf::from("/docs/old.md"), ChangeKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_the_build_directory_wherever_it_is() {
+        let dir = project();
+        let out = TempDir::new().unwrap();
+
+        let filter = WatchFilter::new(dir.path(), out.path()).unwrap();
+        let root = filter.root().to_path_buf();
+
+        assert!(filter.should_rebuild(&root.join("README.md")));
+        assert!(!filter.should_rebuild(&out.path().join("index.md")));
+        assert!(!filter.should_rebuild(&root.join("_build/logo.png")));
+        assert!(!filter.should_rebuild(&root.join(".README.md.swp")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skips_a_symlinked_build_directory() {
+        let dir = project();
+        let out = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(out.path(), dir.path().join("site")).unwrap();
+
+        let filter = WatchFilter::new(dir.path(), &dir.path().join("site")).unwrap();
+        let canonical_out = out.path().canonicalize().unwrap();
+
+        assert!(!filter.should_rebuild(&filter.root().join("site/logo.png")));
+        assert!(!filter.should_rebuild(&canonical_out.join("logo.png")));
+    }
+
+    #[test]
+    fn rebuilds_once_for_an_atomic_save() {
+        let dir = project();
+        let rx = start_watching(dir.path());
+
+        // What Vim does with `backupcopy=no`, roughly
+        let swap = dir.path().join(".README.md.swp");
+        let temp = dir.path().join("README.md.tmp");
+        fs::write(&swap, "swap").unwrap();
+        fs::write(&temp, "# Hello again").unwrap();
+        fs::rename(&temp, dir.path().join("README.md")).unwrap();
+        fs::remove_file(&swap).unwrap();
+        fs::write(dir.path().join(".DS_Store"), "").unwrap();
+
+        assert_eq!(rebuilds(&rx), 1);
+    }
+
+    #[test]
+    fn does_not_rebuild_for_the_build_output() {
+        let dir = project();
+        let rx = start_watching(dir.path());
+
+        fs::write(dir.path().join("_build/index.html"), "<html>").unwrap();
+        fs::write(dir.path().join("_build/logo.png"), "png").unwrap();
+
+        assert_eq!(rebuilds(&rx), 0);
+    }
+
+    #[test]
+    fn rebuilds_once_for_a_burst_of_changes() {
+        let dir = project();
+        let rx = start_watching(dir.path());
+
+        for i in 0..5 {
+            fs::write(dir.path().join(format!("page-{}.md", i)), "# Page").unwrap();
+            thread::sleep(DEBOUNCE / 5);
+        }
+
+        assert_eq!(rebuilds(&rx), 1);
+    }
+}
@@ -0,0 +1,210 @@
+//! Docapella's Markdown and content pipeline for the browser, for previewing
+//! pages exactly as `docapella build` renders them, without a server.
+//!
+//! Wraps the parts of libdoctave that don't need a filesystem: projects are
+//! created from files in memory, and pages are rendered, verified and
+//! autocompleted one at a time. See the README for how to build it.
+use std::path::{Path, PathBuf};
+
+use libdoctave::{InputContent, InputFile, Project, RenderOptions, ResponseContext};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TYPES: &str = r#"
+/**
+ * A file in the project. Files that pages only link to, like images, are
+ * given as binary, with a signature that changes whenever the file does.
+ */
+export type DocapellaFile =
+  | { path: string; content: string }
+  | { path: string; signature: string; size_bytes: number };
+
+/**
+ * How pages are rendered. Every field is optional. Set
+ * `webbify_internal_urls` to render links like `docapella build` does.
+ */
+export interface RenderOptions {
+  bust_image_caches?: boolean;
+  cache_bust_value?: string | null;
+  webbify_internal_urls?: boolean;
+  fsify_internal_urls?: boolean;
+  disable_syntax_highlighting?: boolean;
+  highlight_code?: boolean;
+  link_rewrites?: Record<string, string>;
+  prefix_asset_urls?: string | null;
+  prefix_link_urls?: string | null;
+  download_url_prefix?: string | null;
+  locale?: string | null;
+  publish_drafts?: boolean;
+  undefined_variables?: "error" | "warn" | "ignore";
+  url_style?: "no_trailing_slash" | "trailing_slash" | "html_extension" | null;
+  debug_slots?: boolean;
+}
+
+export interface Point {
+  /** 1-indexed */
+  row: number;
+  /** 1-indexed */
+  col: number;
+  /** 0-indexed */
+  byte_offset: number;
+}
+
+export interface DocapellaError {
+  code: number;
+  message: string;
+  description: string;
+  file: string | null;
+  position: { start: Point; end: Point } | null;
+  /** Who owns `file`, from the project's `owners` */
+  owner?: string;
+  /** Replace the bytes between `start` and `end` in `file` with `replacement` */
+  suggestions?: { file: string; start: number; end: number; replacement: string }[];
+}
+
+export interface CompletionItem {
+  label: string;
+  kind: "File" | "Class" | "Field" | "Enum";
+  insert_text: string;
+  trigger_completion: boolean;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "DocapellaFile[]")]
+    pub type DocapellaFiles;
+
+    #[wasm_bindgen(typescript_type = "RenderOptions | undefined")]
+    pub type JsRenderOptions;
+
+    #[wasm_bindgen(typescript_type = "DocapellaError[]")]
+    pub type DocapellaErrors;
+
+    #[wasm_bindgen(typescript_type = "CompletionItem[]")]
+    pub type CompletionItems;
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DocapellaFile {
+    Text {
+        path: PathBuf,
+        content: String,
+    },
+    Binary {
+        path: PathBuf,
+        signature: String,
+        size_bytes: usize,
+    },
+}
+
+impl From<DocapellaFile> for InputFile {
+    fn from(file: DocapellaFile) -> Self {
+        match file {
+            DocapellaFile::Text { path, content } => InputFile {
+                path,
+                content: InputContent::Text(content),
+            },
+            DocapellaFile::Binary {
+                path,
+                signature,
+                size_bytes,
+            } => InputFile {
+                path,
+                content: InputContent::Binary {
+                    signature,
+                    size_bytes,
+                },
+            },
+        }
+    }
+}
+
+/// A project built from files in memory. Create a new one when files change.
+#[wasm_bindgen(js_name = DocapellaProject)]
+pub struct WasmProject {
+    project: Project,
+}
+
+#[wasm_bindgen(js_class = DocapellaProject)]
+impl WasmProject {
+    /// Throws a `DocapellaError[]` if the project can't be built, e.g.
+    /// because `docapella.yaml` is missing or invalid.
+    #[wasm_bindgen(constructor)]
+    pub fn new(files: DocapellaFiles) -> Result<WasmProject, JsValue> {
+        let files: Vec<DocapellaFile> = serde_wasm_bindgen::from_value(files.into())?;
+
+        let project = Project::from_file_list(files.into_iter().map(InputFile::from).collect())
+            .map_err(|errors| to_js(&errors))?;
+
+        Ok(WasmProject { project })
+    }
+
+    /// The page at the URI path as `ContentApiResponse` JSON, the same the
+    /// site's templates are rendered from. Pages that don't exist give a
+    /// "not found" response.
+    #[wasm_bindgen(js_name = renderPage)]
+    pub fn render_page(&self, uri_path: &str, options: JsRenderOptions) -> Result<String, JsValue> {
+        let ctx = ResponseContext {
+            options: render_options(options)?,
+            ..Default::default()
+        };
+
+        let (json, _status) = self
+            .project
+            .get_content_response_as_json_string_by_uri_path(uri_path, ctx);
+
+        Ok(json)
+    }
+
+    /// Problems in the page at the URI path: whether it renders, and whether
+    /// its links lead somewhere. Checks of the whole project, like for pages
+    /// that aren't linked from anywhere, aren't run.
+    #[wasm_bindgen(js_name = verifyPage)]
+    pub fn verify_page(
+        &self,
+        uri_path: &str,
+        options: JsRenderOptions,
+    ) -> Result<DocapellaErrors, JsValue> {
+        let options = render_options(options)?;
+        let errors = self.project.verify_page(uri_path, Some(&options));
+
+        Ok(to_js(&errors).into())
+    }
+
+    /// Completions at the end of `markdown`, the text before the cursor in
+    /// the file at `fs_path`, e.g. link targets and component attributes.
+    pub fn autocomplete(
+        &self,
+        markdown: &str,
+        fs_path: &str,
+        options: JsRenderOptions,
+    ) -> Result<CompletionItems, JsValue> {
+        let options = render_options(options)?;
+        let items = self
+            .project
+            .autocomplete(markdown, Path::new(fs_path), Some(&options));
+
+        Ok(to_js(&items).into())
+    }
+}
+
+fn render_options(options: JsRenderOptions) -> Result<RenderOptions, JsValue> {
+    let options: JsValue = options.into();
+
+    if options.is_undefined() || options.is_null() {
+        return Ok(RenderOptions::default());
+    }
+
+    Ok(serde_wasm_bindgen::from_value(options)?)
+}
+
+/// Plain objects and arrays, rather than `Map`s, so that the values can be
+/// passed on as JSON.
+fn to_js<T: Serialize + ?Sized>(value: &T) -> JsValue {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .expect("Serializing to a JavaScript value failed")
+}
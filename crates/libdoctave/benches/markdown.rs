@@ -49,6 +49,27 @@ fn bench_markdown(c: &mut Criterion) {
         },
     );
 
+    group.bench_with_input(
+        "3000_md_files with build time highlighting",
+        &files,
+        |b, files| {
+            b.iter(|| {
+                ProjectBuilder {
+                    inputs: files.clone(),
+                }
+                .build()
+                .unwrap()
+                .verify(
+                    Some(&RenderOptions {
+                        highlight_code: true,
+                        ..RenderOptions::default()
+                    }),
+                    None,
+                )
+            })
+        },
+    );
+
     group.finish();
 }
 
@@ -0,0 +1,38 @@
+#[path = "../tests/common.rs"]
+mod common;
+
+use common::ProjectBuilder;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+static LARGE_SPEC: &str = include_str!("../examples/open_api_specs/large_openapi.json");
+
+fn bench_verify(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify");
+
+    group.sample_size(10);
+
+    let project = ProjectBuilder {
+        inputs: ProjectBuilder::n_project_files(500),
+    }
+    .build()
+    .unwrap();
+
+    group.bench_function("500_md_files", |b| b.iter(|| project.verify(None, None)));
+
+    let project = ProjectBuilder {
+        inputs: ProjectBuilder::n_project_files(500),
+    }
+    .with_openapi(LARGE_SPEC.to_owned())
+    .build()
+    .unwrap();
+
+    group.bench_function("500_md_files with large OpenAPI spec", |b| {
+        b.iter(|| project.verify(None, None))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
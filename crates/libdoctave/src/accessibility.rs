@@ -0,0 +1,450 @@
+//! Lints for common accessibility problems in pages, configured under
+//! `lints` in the settings.
+//!
+//! The lints walk the rendered AST of each published Markdown page, rendered
+//! with the default options. Code blocks keep their content as a string, so
+//! anything that looks like an image or a link inside one is never checked.
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::page_kind::Ast;
+use crate::parallel::*;
+use crate::renderable_ast::{Node, NodeKind};
+use crate::settings::{LintLevel, LintSettings};
+use crate::{Error, Position, Project};
+
+lazy_static! {
+    static ref IMG_TAG: Regex = Regex::new(r"(?i)^\s*<img\b").unwrap();
+    static ref ALT_ATTRIBUTE: Regex = Regex::new(r"(?i)\salt\s*=").unwrap();
+}
+
+/// Link texts that don't say where the link goes
+const VAGUE_LINK_TEXTS: &[&str] = &["here", "click here"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rule {
+    ImageAlt,
+    HeadingIncrement,
+    SingleH1,
+    LinkText,
+    TableHeader,
+}
+
+impl Rule {
+    const ALL: &'static [Rule] = &[
+        Rule::ImageAlt,
+        Rule::HeadingIncrement,
+        Rule::SingleH1,
+        Rule::LinkText,
+        Rule::TableHeader,
+    ];
+
+    /// The key of the rule under `lints`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::ImageAlt => "image_alt",
+            Rule::HeadingIncrement => "heading_increment",
+            Rule::SingleH1 => "single_h1",
+            Rule::LinkText => "link_text",
+            Rule::TableHeader => "table_header",
+        }
+    }
+}
+
+/// A problem found by a rule, before it's turned into an error or warning
+struct Issue {
+    rule: Rule,
+    message: &'static str,
+    description: String,
+    pos: Position,
+}
+
+/// Whether any of the rules report errors or warnings.
+pub(crate) fn enabled(settings: &LintSettings) -> bool {
+    Rule::ALL
+        .iter()
+        .any(|r| settings.level(*r) != LintLevel::Off)
+}
+
+/// The problems found by the rules set to the given level.
+pub(crate) fn lint(project: &Project, level: LintLevel) -> Vec<Error> {
+    let settings = project.settings.lints();
+
+    if level == LintLevel::Off || !Rule::ALL.iter().any(|r| settings.level(*r) == level) {
+        return vec![];
+    }
+
+    project
+        .published_pages(None)
+        .par_iter()
+        .filter(|page| page.is_markdown())
+        .flat_map_iter(|page| {
+            // Pages that fail to render are reported by `verify`
+            let issues = match page.ast(None) {
+                Ok(Ast::Markdown(root)) => check(&root, settings, level),
+                _ => vec![],
+            };
+
+            issues
+                .into_iter()
+                .map(|issue| to_error(issue, page.fs_path()))
+        })
+        .collect()
+}
+
+fn to_error(issue: Issue, file: &Path) -> Error {
+    Error {
+        code: Error::ACCESSIBILITY,
+        message: issue.message.to_string(),
+        description: format!("{}\nRule: lints.{}", issue.description, issue.rule.name()),
+        file: Some(file.to_owned()),
+        position: Some(issue.pos),
+        owner: None,
+        suggestions: vec![],
+    }
+}
+
+fn check(root: &Node, settings: &LintSettings, level: LintLevel) -> Vec<Issue> {
+    let mut issues = vec![];
+    let mut previous_heading = None;
+    let mut seen_h1 = false;
+
+    for node in root.walk() {
+        match &node.kind {
+            NodeKind::Image { url, alt, .. } if alt.trim().is_empty() => {
+                issues.push(Issue {
+                    rule: Rule::ImageAlt,
+                    message: "Image without alt text",
+                    description: format!(
+                        "Image \"{}\" has no alt text. Describe the image for readers who can't see it.",
+                        url
+                    ),
+                    pos: node.pos.clone(),
+                });
+            }
+            NodeKind::InlineSvg { src, alt, .. } if alt.trim().is_empty() => {
+                issues.push(Issue {
+                    rule: Rule::ImageAlt,
+                    message: "Image without alt text",
+                    description: format!(
+                        "Image \"{}\" has no alt text. Describe the image for readers who can't see it.",
+                        src
+                    ),
+                    pos: node.pos.clone(),
+                });
+            }
+            NodeKind::HtmlBlock { name, attributes }
+                if name == "img" && !attributes.iter().any(|a| a.key == "alt") =>
+            {
+                issues.push(Issue {
+                    rule: Rule::ImageAlt,
+                    message: "Image without alt text",
+                    description: String::from(
+                        "An <img> has no alt attribute. Describe the image, or use alt=\"\" for decorative images.",
+                    ),
+                    pos: node.pos.clone(),
+                });
+            }
+            NodeKind::HtmlTag { value }
+                if IMG_TAG.is_match(value) && !ALT_ATTRIBUTE.is_match(value) =>
+            {
+                issues.push(Issue {
+                    rule: Rule::ImageAlt,
+                    message: "Image without alt text",
+                    description: String::from(
+                        "An <img> has no alt attribute. Describe the image, or use alt=\"\" for decorative images.",
+                    ),
+                    pos: node.pos.clone(),
+                });
+            }
+            NodeKind::Heading { level, .. } => {
+                // The page title is an h1, so a page may start with an h2
+                let previous = previous_heading.unwrap_or(1);
+
+                if *level > previous + 1 {
+                    issues.push(Issue {
+                        rule: Rule::HeadingIncrement,
+                        message: "Heading skips a level",
+                        description: format!(
+                            "Expected at most an h{} after an h{}.\nFound an h{}.",
+                            previous + 1,
+                            previous,
+                            level
+                        ),
+                        pos: node.pos.clone(),
+                    });
+                }
+
+                if *level == 1 {
+                    if seen_h1 {
+                        issues.push(Issue {
+                            rule: Rule::SingleH1,
+                            message: "More than one h1 on the page",
+                            description: String::from(
+                                "Pages should have a single h1 as their title. Use an h2 for this heading.",
+                            ),
+                            pos: node.pos.clone(),
+                        });
+                    }
+                    seen_h1 = true;
+                }
+
+                previous_heading = Some(*level);
+            }
+            NodeKind::Link { url, .. } => {
+                let text = node.inner_text().to_lowercase();
+                let text = text.trim_end_matches(['.', '!', ':']);
+
+                if VAGUE_LINK_TEXTS.contains(&text) {
+                    issues.push(Issue {
+                        rule: Rule::LinkText,
+                        message: "Link text doesn't describe the link",
+                        description: format!(
+                            "Link to {} only says \"{}\". Say where the link goes, so it makes sense out of context.",
+                            url,
+                            node.inner_text()
+                        ),
+                        pos: node.pos.clone(),
+                    });
+                }
+            }
+            NodeKind::Table { .. } if !markdown_table_has_header(node) => {
+                issues.push(Issue {
+                    rule: Rule::TableHeader,
+                    message: "Table without a header row",
+                    description: String::from(
+                        "The first row of the table is empty. Give each column a header.",
+                    ),
+                    pos: node.pos.clone(),
+                });
+            }
+            NodeKind::HtmlBlock { name, .. } if name == "table" && !html_table_has_header(node) => {
+                issues.push(Issue {
+                    rule: Rule::TableHeader,
+                    message: "Table without a header row",
+                    description: String::from(
+                        "The table has no <th> cells. Give each column a header.",
+                    ),
+                    pos: node.pos.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    issues.retain(|issue| settings.level(issue.rule) == level);
+    issues
+}
+
+/// Markdown tables always have a header row, but its cells may be empty
+fn markdown_table_has_header(table: &Node) -> bool {
+    table.children.first().is_some_and(|row| {
+        row.children
+            .iter()
+            .any(|cell| !cell.inner_text().is_empty())
+    })
+}
+
+fn html_table_has_header(table: &Node) -> bool {
+    table
+        .walk()
+        .any(|node| matches!(&node.kind, NodeKind::HtmlBlock { name, .. } if name == "th"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use std::path::PathBuf;
+
+    fn project(lints: &str, readme: &str) -> Project {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        Project::from_file_list(vec![
+            file("README.md", readme),
+            file(NAVIGATION_FILE_NAME, "---"),
+            file(
+                SETTINGS_FILE_NAME,
+                &format!("---\ntitle: Something\nlints:\n{}", lints),
+            ),
+        ])
+        .unwrap()
+    }
+
+    fn rules(errors: &[Error]) -> Vec<String> {
+        errors
+            .iter()
+            .map(|e| {
+                e.description
+                    .rsplit("Rule: lints.")
+                    .next()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_images_without_alt_text() {
+        let project = project(
+            "  accessibility: warn",
+            indoc! {r#"
+            # Images
+
+            ![](/_assets/cat.png)
+
+            ![A dog](/_assets/dog.png)
+
+            <img src="/_assets/bird.png" />
+
+            <img src="/_assets/divider.png" alt="" />
+            "#},
+        );
+
+        let warnings = lint(&project, LintLevel::Warn);
+
+        assert_eq!(
+            rules(&warnings),
+            vec!["image_alt", "image_alt"],
+            "{:#?}",
+            warnings
+        );
+        assert_eq!(warnings[0].code, Error::ACCESSIBILITY);
+        assert_eq!(warnings[0].file, Some(PathBuf::from("README.md")));
+        assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 3);
+        assert_eq!(
+            warnings[0].description,
+            "Image \"/_assets/cat.png\" has no alt text. Describe the image for readers who can't see it.\nRule: lints.image_alt"
+        );
+    }
+
+    #[test]
+    fn finds_heading_problems() {
+        let project = project(
+            "  accessibility: warn",
+            indoc! {r#"
+            # Title
+
+            ### Skipped a level
+
+            ## Fine
+
+            # Another title
+            "#},
+        );
+
+        let warnings = lint(&project, LintLevel::Warn);
+
+        assert_eq!(
+            rules(&warnings),
+            vec!["heading_increment", "single_h1"],
+            "{:#?}",
+            warnings
+        );
+        assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 3);
+        assert_eq!(warnings[1].position.as_ref().unwrap().start.row, 7);
+    }
+
+    #[test]
+    fn finds_vague_links_and_tables_without_headers() {
+        let project = project(
+            "  accessibility: warn",
+            indoc! {r#"
+            # Links
+
+            Read the guide [here](https://example.com), or [Click here.](https://example.com).
+            The [installation guide](https://example.com) is fine.
+
+            |   |   |
+            |---|---|
+            | a | b |
+
+            | Name | Value |
+            |------|-------|
+            | a    | b     |
+            "#},
+        );
+
+        let warnings = lint(&project, LintLevel::Warn);
+
+        assert_eq!(
+            rules(&warnings),
+            vec!["link_text", "link_text", "table_header"],
+            "{:#?}",
+            warnings
+        );
+    }
+
+    #[test]
+    fn skips_code_blocks() {
+        let project = project(
+            "  accessibility: error",
+            indoc! {r#"
+            # Code
+
+            ```markdown
+            ![](/_assets/cat.png)
+
+            ### Skipped
+
+            [here](https://example.com)
+            ```
+            "#},
+        );
+
+        assert!(lint(&project, LintLevel::Error).is_empty());
+    }
+
+    #[test]
+    fn rules_have_their_own_levels() {
+        let project = project(
+            indoc! {"
+              accessibility: warn
+              image_alt: error
+              single_h1: off
+            "},
+            indoc! {r#"
+            # Title
+
+            ![](/_assets/cat.png)
+
+            # Another title
+
+            [here](https://example.com)
+            "#},
+        );
+
+        assert_eq!(rules(&lint(&project, LintLevel::Error)), vec!["image_alt"]);
+        assert_eq!(rules(&lint(&project, LintLevel::Warn)), vec!["link_text"]);
+    }
+
+    #[test]
+    fn is_off_by_default() {
+        let project = project("  {}", "# Title\n\n![](/_assets/cat.png)\n\n# Another");
+
+        assert!(lint(&project, LintLevel::Error).is_empty());
+        assert!(lint(&project, LintLevel::Warn).is_empty());
+    }
+
+    #[test]
+    fn reports_through_verify_and_warnings() {
+        let project = project(
+            "  heading_increment: error\n  link_text: warn",
+            "# Title\n\n### Skipped\n\n[here](https://example.com)",
+        );
+
+        let errors = project.verify(None, None).unwrap_err();
+        assert_eq!(rules(&errors), vec!["heading_increment"]);
+
+        let warnings = project
+            .warnings()
+            .into_iter()
+            .filter(|w| w.code == Error::ACCESSIBILITY)
+            .collect::<Vec<_>>();
+        assert_eq!(rules(&warnings), vec!["link_text"]);
+    }
+}
@@ -0,0 +1,42 @@
+//! The results of verifying a project, kept together so that reports built
+//! from them, like the [`HealthReport`](crate::health::HealthReport), don't
+//! have to run the checks again.
+use crate::{Error, ErrorOptions, Project, RenderOptions};
+
+/// The errors and warnings found in a project by [`Project::analyze`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    /// Same as the errors of [`Project::verify`]
+    pub errors: Vec<Error>,
+    /// [`Project::warnings`] followed by [`Project::render_warnings`]
+    pub warnings: Vec<Error>,
+}
+
+impl Analysis {
+    pub(crate) fn run(
+        project: &Project,
+        opts: Option<&RenderOptions>,
+        err_opts: Option<&ErrorOptions>,
+    ) -> Self {
+        let errors = project.verify(opts, err_opts).err().unwrap_or_default();
+
+        let mut warnings = project.warnings();
+        warnings.extend(project.render_warnings(opts));
+
+        Analysis { errors, warnings }
+    }
+
+    /// Whether the project can be published, i.e. verifying it found no
+    /// errors.
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The errors and warnings with the given code.
+    pub(crate) fn count(&self, code: usize) -> (usize, usize) {
+        (
+            self.errors.iter().filter(|e| e.code == code).count(),
+            self.warnings.iter().filter(|e| e.code == code).count(),
+        )
+    }
+}
@@ -0,0 +1,244 @@
+//! Rendered page ASTs, kept around for when the same page is rendered again
+//! with the same options.
+//!
+//! The cache is opted into with [`Project::with_cache`](crate::Project::with_cache).
+//! Entries are keyed by the page, a signature of its content, the
+//! [`RenderOptions::cache_key`], and a hash of the project settings. Once
+//! the cache is full, the least recently used entry is dropped.
+//!
+//! Clones of a project share its cache, and the cache is behind a mutex, so
+//! it can be used from rayon threads. The lock is only held while looking up
+//! or storing an entry, never while rendering. Two threads rendering the
+//! same page at once may both render it, with the last one stored.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use crate::page_kind::Ast;
+use crate::settings::Settings;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    pub uri_path: String,
+    pub content_signature: u64,
+    pub options: u64,
+    pub settings: u64,
+}
+
+pub(crate) struct AstCache {
+    capacity: usize,
+    settings_hash: u64,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Entries along with when they were last used
+    entries: HashMap<CacheKey, (Ast, u64)>,
+    clock: u64,
+}
+
+impl AstCache {
+    pub fn new(capacity: usize, settings: &Settings) -> Self {
+        AstCache {
+            capacity,
+            settings_hash: settings_hash(settings),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    pub fn key(&self, uri_path: &str, content_signature: u64, options: u64) -> CacheKey {
+        CacheKey {
+            uri_path: uri_path.to_string(),
+            content_signature,
+            options,
+            settings: self.settings_hash,
+        }
+    }
+
+    pub fn get(&self, key: &CacheKey) -> Option<Ast> {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let now = state.clock;
+
+        state.entries.get_mut(key).map(|(ast, last_used)| {
+            *last_used = now;
+            ast.clone()
+        })
+    }
+
+    pub fn insert(&self, key: CacheKey, ast: Ast) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let now = state.clock;
+
+        if !state.entries.contains_key(&key) && state.entries.len() >= self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        state.entries.insert(key, (ast, now));
+    }
+
+    /// Drops the entries for the page with the given URI path
+    pub fn invalidate(&self, uri_path: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.entries.retain(|key, _| key.uri_path != uri_path);
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+}
+
+fn settings_hash(settings: &Settings) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(settings)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::renderable_ast::Node;
+    use crate::{InputContent, InputFile, Project, RenderOptions};
+    use crate::{NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use std::path::PathBuf;
+
+    fn ast() -> Ast {
+        Ast::Markdown(Node::default())
+    }
+
+    #[test]
+    fn drops_the_least_recently_used_entry() {
+        let cache = AstCache::new(2, &Settings::default());
+        let a = cache.key("/a", 1, 1);
+        let b = cache.key("/b", 1, 1);
+        let c = cache.key("/c", 1, 1);
+
+        cache.insert(a.clone(), ast());
+        cache.insert(b.clone(), ast());
+        assert!(cache.get(&a).is_some());
+
+        cache.insert(c.clone(), ast());
+
+        assert!(cache.get(&a).is_some());
+        assert!(cache.get(&b).is_none());
+        assert!(cache.get(&c).is_some());
+    }
+
+    #[test]
+    fn invalidates_a_page() {
+        let cache = AstCache::new(10, &Settings::default());
+        cache.insert(cache.key("/a", 1, 1), ast());
+        cache.insert(cache.key("/a", 1, 2), ast());
+        cache.insert(cache.key("/b", 1, 1), ast());
+
+        cache.invalidate("/a");
+
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&cache.key("/b", 1, 1)).is_some());
+    }
+
+    fn project() -> Project {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        Project::from_file_list(vec![
+            file("README.md", "# Hello\n\n[Guide](/guide)"),
+            file("guide.md", "# Guide"),
+            file(NAVIGATION_FILE_NAME, "---"),
+            file(SETTINGS_FILE_NAME, "---\ntitle: Something"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn caches_pages_per_render_options() {
+        let project = project().with_cache(10);
+        let cache = project.ast_cache.clone().unwrap();
+        let page = project.get_page_by_uri_path("/").unwrap();
+
+        page.ast(None).unwrap();
+        page.ast(None).unwrap();
+        assert_eq!(cache.len(), 1);
+
+        let opts = RenderOptions {
+            prefix_link_urls: Some("/v2".to_string()),
+            ..Default::default()
+        };
+        page.ast(Some(&opts)).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // Clones share the cache
+        let clone = project.clone();
+        clone
+            .get_page_by_uri_path("/guide")
+            .unwrap()
+            .ast(None)
+            .unwrap();
+        assert_eq!(cache.len(), 3);
+
+        project.invalidate_cached_page("/");
+        assert_eq!(cache.len(), 1);
+
+        clone.clear_cache();
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn cached_asts_match_rendered_ones() {
+        let project = project();
+        let cached = project.clone().with_cache(10);
+        let opts = RenderOptions {
+            prefix_link_urls: Some("/v2".to_string()),
+            ..Default::default()
+        };
+
+        let render = |project: &Project| {
+            let ast = project
+                .get_page_by_uri_path("/")
+                .unwrap()
+                .ast(Some(&opts))
+                .unwrap();
+            serde_json::to_string(&ast).unwrap()
+        };
+
+        let expected = render(&project);
+        assert_eq!(render(&cached), expected);
+        assert_eq!(render(&cached), expected);
+    }
+
+    #[test]
+    fn projects_are_not_cached_by_default() {
+        let project = project();
+        project
+            .get_page_by_uri_path("/")
+            .unwrap()
+            .ast(None)
+            .unwrap();
+
+        assert!(project.ast_cache.is_none());
+    }
+}
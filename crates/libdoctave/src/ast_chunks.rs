@@ -0,0 +1,249 @@
+use serde::Serialize;
+
+use crate::{markdown::Node, open_api::ast::PageAst, Ast, Project, RenderOptions};
+
+/// Chunks are kept under this size when serialized, unless a single
+/// top-level node or operation is larger than that by itself.
+pub const MAX_CHUNK_BYTES: usize = 256 * 1024;
+
+/// A part of a page's AST, for streaming pages that are too large to send in
+/// one response. Markdown pages are split between their top-level nodes, and
+/// OpenAPI pages between their operations.
+#[derive(Debug, Clone, Serialize)]
+pub struct AstChunk {
+    pub index: usize,
+    /// How many chunks the page is split into
+    pub count: usize,
+    pub ast: Ast,
+}
+
+pub(crate) fn compute(
+    project: &Project,
+    uri_path: &str,
+    index: usize,
+    opts: Option<&RenderOptions>,
+) -> crate::Result<Option<AstChunk>> {
+    let Some(page) = project.get_page_by_uri_path(uri_path) else {
+        return Ok(None);
+    };
+
+    let mut chunks = split(page.ast(opts)?, MAX_CHUNK_BYTES);
+    let count = chunks.len();
+
+    if index >= count {
+        return Ok(None);
+    }
+
+    Ok(Some(AstChunk {
+        index,
+        count,
+        ast: chunks.swap_remove(index),
+    }))
+}
+
+/// Splits the AST into ASTs of the same kind, each with some of the
+/// top-level children in their original order.
+fn split(ast: Ast, max_bytes: usize) -> Vec<Ast> {
+    match ast {
+        Ast::Markdown(Node {
+            kind,
+            pos,
+            children,
+        }) => group(children, max_bytes)
+            .into_iter()
+            .map(|children| {
+                Ast::Markdown(Node {
+                    kind: kind.clone(),
+                    pos: pos.clone(),
+                    children,
+                })
+            })
+            .collect(),
+        Ast::OpenApi(PageAst {
+            tag,
+            operations,
+            download_url,
+            deprecation_banner,
+        }) => group(operations, max_bytes)
+            .into_iter()
+            .map(|operations| {
+                Ast::OpenApi(PageAst {
+                    tag: tag.clone(),
+                    operations,
+                    download_url: download_url.clone(),
+                    deprecation_banner: deprecation_banner.clone(),
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Groups the items in order, starting a new group whenever the next item
+/// would take the group over `max_bytes`. Items are never split, and there
+/// is always at least one group.
+fn group<T: Serialize>(items: Vec<T>, max_bytes: usize) -> Vec<Vec<T>> {
+    let mut groups = vec![vec![]];
+    let mut size = 0;
+
+    for item in items {
+        let item_size = serde_json::to_vec(&item).map(|v| v.len()).unwrap_or(0);
+
+        if size + item_size > max_bytes && groups.last().is_some_and(|g| !g.is_empty()) {
+            groups.push(vec![]);
+            size = 0;
+        }
+
+        size += item_size;
+        groups.last_mut().unwrap().push(item);
+    }
+
+    groups
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    use super::*;
+
+    fn project(readme: &str) -> Project {
+        Project::from_file_list(vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(readme.to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    title: Something
+                    open_api:
+                      - spec_file: openapi.yaml
+                        uri_prefix: /api
+                    "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.yaml"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    openapi: 3.0.0
+                    info:
+                      title: Trees
+                      version: 1.0.0
+                    paths:
+                      /tree:
+                        get:
+                          summary: Get the tree
+                          tags: [Trees]
+                          responses:
+                            '200':
+                              description: A tree
+                        delete:
+                          summary: Cut down the tree
+                          tags: [Trees]
+                          responses:
+                            '200':
+                              description: Nothing
+                    "#}
+                    .to_string(),
+                ),
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_items_without_going_over_the_limit() {
+        // Each item is 3 bytes serialized
+        let items = vec!["a", "b", "c", "d", "e"];
+
+        assert_eq!(
+            group(items, 7),
+            vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]
+        );
+    }
+
+    #[test]
+    fn keeps_items_over_the_limit_whole() {
+        let items = vec!["a", "a long one", "b"];
+
+        assert_eq!(
+            group(items, 7),
+            vec![vec!["a"], vec!["a long one"], vec!["b"]]
+        );
+    }
+
+    #[test]
+    fn fits_small_pages_in_one_chunk() {
+        let project = project("# Hello\n\nWorld");
+
+        let chunk = project.page_ast_chunk("/", 0, None).unwrap().unwrap();
+
+        assert_eq!(chunk.count, 1);
+        match chunk.ast {
+            Ast::Markdown(root) => assert_eq!(root.children.len(), 2),
+            other => panic!("Unexpected AST: {:?}", other),
+        }
+
+        assert!(project.page_ast_chunk("/", 1, None).unwrap().is_none());
+        assert!(project.page_ast_chunk("/nope", 0, None).unwrap().is_none());
+    }
+
+    #[test]
+    fn splits_markdown_pages_between_top_level_nodes() {
+        let project = project("# Hello\n\nWorld");
+        let ast = project
+            .get_page_by_uri_path("/")
+            .unwrap()
+            .ast(None)
+            .unwrap();
+
+        let chunks = split(ast, 1);
+
+        assert_eq!(chunks.len(), 2);
+        for chunk in chunks {
+            match chunk {
+                Ast::Markdown(root) => assert_eq!(root.children.len(), 1),
+                other => panic!("Unexpected AST: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn splits_openapi_pages_between_operations() {
+        let project = project("# Hello");
+        let ast = project
+            .get_page_by_uri_path("/api/trees")
+            .unwrap()
+            .ast(None)
+            .unwrap();
+
+        let summaries = split(ast, 1)
+            .into_iter()
+            .map(|chunk| match chunk {
+                Ast::OpenApi(page) => {
+                    assert_eq!(page.tag.name, "Trees");
+                    page.operations
+                        .into_iter()
+                        .map(|op| op.summary.unwrap())
+                        .collect::<Vec<_>>()
+                }
+                other => panic!("Unexpected AST: {:?}", other),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summaries,
+            vec![vec!["Get the tree"], vec!["Cut down the tree"]]
+        );
+    }
+}
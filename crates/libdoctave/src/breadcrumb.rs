@@ -24,6 +24,7 @@ pub(crate) fn compute(
         .unwrap_or("/".to_string());
 
     let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
     ctx.with_maybe_options(opts);
 
     let navigation = project.navigation(opts, &subtab_path)?;
@@ -0,0 +1,49 @@
+//! Canonical representation of paths inside a project.
+//!
+//! Input files can come from any platform, so we can't rely on the separator
+//! the caller used. Internally all project paths use forward slashes, and any
+//! path coming from the outside should be passed through [`canonicalize`]
+//! before being compared against paths in the project.
+use std::path::{Path, PathBuf};
+
+/// Convert a path into its canonical, forward slash separated form.
+pub(crate) fn canonicalize(path: &Path) -> PathBuf {
+    let as_str = path.to_string_lossy();
+
+    if as_str.contains('\\') {
+        PathBuf::from(as_str.replace('\\', "/"))
+    } else {
+        path.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_backslashes() {
+        assert_eq!(
+            canonicalize(Path::new("_components\\nested\\card.md")),
+            PathBuf::from("_components/nested/card.md")
+        );
+    }
+
+    #[test]
+    fn leaves_forward_slashes_alone() {
+        assert_eq!(
+            canonicalize(Path::new("foo/bar/README.md")),
+            PathBuf::from("foo/bar/README.md")
+        );
+    }
+
+    #[test]
+    fn canonical_paths_have_the_same_components() {
+        assert_eq!(
+            canonicalize(Path::new("foo\\bar.md"))
+                .components()
+                .collect::<Vec<_>>(),
+            Path::new("foo/bar.md").components().collect::<Vec<_>>()
+        );
+    }
+}
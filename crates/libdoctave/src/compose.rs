@@ -0,0 +1,931 @@
+//! Composing a project out of several documentation sources, e.g. separate
+//! repositories for the product docs, SDK docs, and API reference.
+//!
+//! Each source is mounted under a URI prefix. Links inside a source are
+//! rewritten to where the linked files end up, and links between sources are
+//! written with the prefix of the other source as a scheme, e.g.
+//! `sdk://quickstart.md`. The folders that are only looked for at the root of
+//! a project, like `_components` and `_assets`, get a folder per source, so
+//! that sources can't override each other's components.
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use serde_yaml::{Mapping, Value};
+
+use crate::{
+    canonical_path::canonicalize,
+    fs_to_uri_path,
+    markdown::{parser::parse_internal_link, CustomComponentHandle},
+    settings::{Settings, DEFAULT_AUGMENTATIONS_DIR},
+    Error, InputContent, InputFile, Project, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+};
+
+/// The scheme for links to the source mounted at the root of the composed
+/// project, e.g. `root://guides/setup.md`.
+pub const ROOT_SOURCE_SCHEME: &str = "root";
+
+/// Folders that are only looked for at the root of a project. Each source's
+/// files in them are moved into a folder named after the source's prefix,
+/// e.g. `_components/card.md` in `sdk` becomes `_components/sdk/card.md`.
+const SHARED_FOLDERS: &[&str] = &["_assets", "_components", "_topics", "_partials"];
+
+lazy_static! {
+    /// `[text](target)` and `![alt](target)`
+    static ref INLINE_LINK: Regex = Regex::new(r"(\]\(\s*)(<[^>\n]*>|[^)\s]+)").unwrap();
+
+    /// `[label]: target`
+    static ref LINK_DEFINITION: Regex =
+        Regex::new(r"^(\s{0,3}\[[^\]\n]+\]:\s*)(<[^>\n]*>|\S+)").unwrap();
+
+    /// `href="target"` and `src="target"` in HTML and components
+    static ref LINK_ATTRIBUTE: Regex =
+        Regex::new(r#"(\b(?:href|src)=)("[^"\n]*"|'[^'\n]*')"#).unwrap();
+
+    /// `<Component.Card` and `</Component.Card`
+    static ref COMPONENT_TAG: Regex =
+        Regex::new(r"(</?)((?:Component|Topic)(?:\.[A-Za-z0-9_]+)+)").unwrap();
+}
+
+/// How the navigations of the sources are combined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NavigationMerge {
+    /// The sections of each source's navigation are listed one after another
+    /// in the navigation of the composed project.
+    #[default]
+    Sections,
+    /// Each source gets a tab of its own, labeled with the `title` in the
+    /// source's settings. Tabs set in the composed project's settings are
+    /// used as they are instead.
+    Tabs,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComposeSettings {
+    /// The `docapella.yaml` of the composed project. The settings of the
+    /// sources are only read for their OpenAPI specs and titles.
+    pub settings: String,
+    pub navigation: NavigationMerge,
+}
+
+struct Source {
+    /// E.g. `sdk` or `docs/sdk`, or empty for the source at the root
+    prefix: String,
+    files: Vec<InputFile>,
+}
+
+impl Source {
+    fn scheme(&self) -> String {
+        if self.prefix.is_empty() {
+            ROOT_SOURCE_SCHEME.to_owned()
+        } else {
+            self.prefix.replace('/', "-")
+        }
+    }
+
+    fn file(&self, path: &str) -> Option<&str> {
+        self.files
+            .iter()
+            .find(|f| f.path == Path::new(path))
+            .and_then(|f| f.content.text())
+    }
+
+    fn settings(&self) -> Option<std::result::Result<Value, Error>> {
+        self.file(SETTINGS_FILE_NAME).map(|content| {
+            crate::yaml::value_from_str(content).map_err(|e| {
+                Error::from_yaml(
+                    e,
+                    Error::INVALID_DOCTAVE_YAML,
+                    format!(
+                        "Invalid {} in source \"{}\"",
+                        SETTINGS_FILE_NAME,
+                        self.scheme()
+                    ),
+                    Some(mount(&self.prefix, Path::new(SETTINGS_FILE_NAME))),
+                )
+            })
+        })
+    }
+
+    /// Custom components by their name in the source, with their name in
+    /// the composed project, e.g. `Component.Card` and `Component.Sdk.Card`.
+    fn component_names(&self) -> HashMap<String, String> {
+        self.files
+            .iter()
+            .filter(|f| f.path.starts_with("_components") || f.path.starts_with("_topics"))
+            .filter_map(|f| {
+                let original = CustomComponentHandle::new("", &f.path).title().ok()?;
+                let mounted = CustomComponentHandle::new("", mount(&self.prefix, &f.path))
+                    .title()
+                    .ok()?;
+
+                Some((original, mounted))
+            })
+            .collect()
+    }
+}
+
+/// Rewrites the links of one source to their mounted locations.
+struct LinkRewriter<'a> {
+    prefix: &'a str,
+    /// Prefixes of all sources, by their scheme
+    schemes: &'a HashMap<String, String>,
+}
+
+impl LinkRewriter<'_> {
+    /// Where a link in the file at `from` points once the sources are
+    /// mounted. Returns `None` for links that stay as they are, like
+    /// external links and links to anchors on the same page.
+    fn rewrite(&self, link: &str, from: &Path) -> Option<String> {
+        if link.is_empty() || link.starts_with('#') || link.starts_with("//") {
+            return None;
+        }
+
+        let (prefix, path, base) = match link.split_once("://") {
+            Some((scheme, rest)) => {
+                let prefix = self.schemes.get(scheme)?;
+                (prefix.as_str(), rest, Path::new(""))
+            }
+            None if self.prefix.is_empty() || parse_internal_link(link).is_none() => {
+                return None;
+            }
+            None => (self.prefix, link, from.parent().unwrap_or(Path::new(""))),
+        };
+
+        let (path, suffix) = match path.find(['#', '?']) {
+            Some(index) => path.split_at(index),
+            None => (path, ""),
+        };
+
+        let resolved = match path.strip_prefix('/') {
+            Some(absolute) => normalize(Path::new(absolute))?,
+            None => normalize(&base.join(path))?,
+        };
+
+        let mounted = mount(prefix, &resolved);
+        let trailing_slash = if path.ends_with('/') && !mounted.as_os_str().is_empty() {
+            "/"
+        } else {
+            ""
+        };
+
+        Some(format!(
+            "/{}{}{}",
+            mounted.to_string_lossy(),
+            trailing_slash,
+            suffix
+        ))
+    }
+
+    /// Rewrites the links and component tags in a Markdown file. Code
+    /// blocks are left alone, as links in them are examples.
+    fn rewrite_markdown(
+        &self,
+        content: &str,
+        from: &Path,
+        components: &HashMap<String, String>,
+    ) -> String {
+        let mut out = String::with_capacity(content.len());
+        let mut fence: Option<&str> = None;
+
+        for line in content.split_inclusive('\n') {
+            let trimmed = line.trim_start();
+
+            match fence {
+                Some(marker) => {
+                    if trimmed.starts_with(marker) {
+                        fence = None;
+                    }
+                    out.push_str(line);
+                    continue;
+                }
+                None if trimmed.starts_with("```") || trimmed.starts_with("~~~") => {
+                    fence = Some(&trimmed[..3]);
+                    out.push_str(line);
+                    continue;
+                }
+                None => {}
+            }
+
+            let line = INLINE_LINK.replace_all(line, |caps: &Captures| {
+                format!("{}{}", &caps[1], self.rewrite_target(&caps[2], from))
+            });
+            let line = LINK_DEFINITION.replace_all(&line, |caps: &Captures| {
+                format!("{}{}", &caps[1], self.rewrite_target(&caps[2], from))
+            });
+            let line = LINK_ATTRIBUTE.replace_all(&line, |caps: &Captures| {
+                format!("{}{}", &caps[1], self.rewrite_target(&caps[2], from))
+            });
+            let line = COMPONENT_TAG.replace_all(&line, |caps: &Captures| {
+                let name = components
+                    .get(&caps[2])
+                    .map(String::as_str)
+                    .unwrap_or(&caps[2]);
+
+                format!("{}{}", &caps[1], name)
+            });
+
+            out.push_str(&line);
+        }
+
+        out
+    }
+
+    /// Rewrites a link target that may be wrapped in `<>` or quotes.
+    fn rewrite_target(&self, target: &str, from: &Path) -> String {
+        let (open, link, close) = match target.chars().next() {
+            Some(c @ ('"' | '\'')) => (c.to_string(), &target[1..target.len() - 1], c.to_string()),
+            Some('<') => ("<".to_owned(), &target[1..target.len() - 1], ">".to_owned()),
+            _ => (String::new(), target, String::new()),
+        };
+
+        match self.rewrite(link, from) {
+            Some(rewritten) => format!("{}{}{}", open, rewritten, close),
+            None => target.to_owned(),
+        }
+    }
+
+    /// Rewrites the `href`s and `open_api_spec`s of navigation items.
+    fn rewrite_navigation(&self, value: &mut Value) {
+        match value {
+            Value::Sequence(items) => items.iter_mut().for_each(|i| self.rewrite_navigation(i)),
+            Value::Mapping(mapping) => {
+                if let Some(Value::String(href)) = mapping.get_mut("href") {
+                    if let Some(rewritten) = self.rewrite(href, Path::new(NAVIGATION_FILE_NAME)) {
+                        *href = rewritten;
+                    }
+                }
+
+                if let Some(Value::String(spec)) = mapping.get_mut("open_api_spec") {
+                    *spec = mount(self.prefix, Path::new(spec))
+                        .to_string_lossy()
+                        .to_string();
+                }
+
+                if let Some(items) = mapping.get_mut("items") {
+                    self.rewrite_navigation(items);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Mounts the sources under their prefixes, and builds a project out of
+/// them. See the module documentation for how the sources are combined.
+///
+/// Returns errors for sources whose files would end up at the same path or
+/// URI path. Everything else is verified as for any other project.
+pub(crate) fn compose(
+    sources: Vec<(String, Vec<InputFile>)>,
+    settings: ComposeSettings,
+) -> std::result::Result<Project, Vec<Error>> {
+    // Fail early on invalid settings, as they are edited below
+    Settings::parse(&settings.settings).map_err(|e| vec![e])?;
+    let mut composed_settings: Value =
+        serde_yaml::from_str(&settings.settings).unwrap_or(Value::Mapping(Mapping::new()));
+
+    let sources = sources
+        .into_iter()
+        .map(|(prefix, files)| Source {
+            prefix: prefix.trim_matches('/').to_owned(),
+            files: files
+                .into_iter()
+                .map(|f| InputFile {
+                    path: canonicalize(&f.path)
+                        .strip_prefix("/")
+                        .map(Path::to_path_buf)
+                        .unwrap_or_else(|_| canonicalize(&f.path)),
+                    content: f.content,
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    let schemes = sources
+        .iter()
+        .map(|s| (s.scheme(), s.prefix.clone()))
+        .collect::<HashMap<_, _>>();
+
+    let mut errors = vec![];
+    let mut files = vec![];
+    let mut navigation = vec![];
+    let mut open_api = vec![];
+    let mut tabs = vec![];
+    // Which source each file came from, to report collisions
+    let mut owners: HashMap<PathBuf, (usize, PathBuf)> = HashMap::new();
+    let mut uri_owners: HashMap<String, (usize, PathBuf)> = HashMap::new();
+
+    for (index, source) in sources.iter().enumerate() {
+        let rewriter = LinkRewriter {
+            prefix: &source.prefix,
+            schemes: &schemes,
+        };
+        let components = source.component_names();
+
+        let source_settings = match source.settings() {
+            Some(Ok(value)) => Some(value),
+            Some(Err(e)) => {
+                errors.push(e);
+                None
+            }
+            None => None,
+        };
+
+        if let Some(Value::Sequence(specs)) =
+            source_settings.as_ref().and_then(|s| s.get("open_api"))
+        {
+            open_api.extend(specs.iter().cloned().map(|mut spec| {
+                mount_open_api_spec(&source.prefix, &mut spec);
+                spec
+            }));
+        }
+
+        let mut source_navigation = None;
+
+        if let Some(content) = source.file(NAVIGATION_FILE_NAME) {
+            match crate::yaml::value_from_str(content) {
+                Ok(mut value) => {
+                    rewriter.rewrite_navigation(&mut value);
+                    source_navigation = Some(value);
+                }
+                Err(e) => errors.push(Error::from_yaml(
+                    e,
+                    Error::INVALID_NAVIGATION,
+                    format!(
+                        "Invalid {} in source \"{}\"",
+                        NAVIGATION_FILE_NAME,
+                        source.scheme()
+                    ),
+                    Some(mount(&source.prefix, Path::new(NAVIGATION_FILE_NAME))),
+                )),
+            }
+        }
+
+        match settings.navigation {
+            NavigationMerge::Sections => {
+                if let Some(Value::Sequence(sections)) = source_navigation {
+                    navigation.extend(sections);
+                }
+            }
+            NavigationMerge::Tabs => {
+                if let Some(value) = source_navigation {
+                    files.push(InputFile {
+                        path: mount(&source.prefix, Path::new(NAVIGATION_FILE_NAME)),
+                        content: InputContent::Text(
+                            serde_yaml::to_string(&value).unwrap_or_default(),
+                        ),
+                    });
+                }
+
+                // The source at the root falls back to the composed
+                // project's title, as it has no prefix to show
+                let label = source_settings
+                    .as_ref()
+                    .and_then(|s| s.get("title"))
+                    .or_else(|| {
+                        Some(&composed_settings)
+                            .filter(|_| source.prefix.is_empty())
+                            .and_then(|s| s.get("title"))
+                    })
+                    .and_then(Value::as_str)
+                    .unwrap_or(source.prefix.as_str())
+                    .to_owned();
+
+                let mut tab = Mapping::new();
+                tab.insert("label".into(), label.into());
+                tab.insert("path".into(), format!("/{}", source.prefix).into());
+                tabs.push(Value::Mapping(tab));
+            }
+        }
+
+        for file in &source.files {
+            if file.path == Path::new(SETTINGS_FILE_NAME)
+                || file.path == Path::new(NAVIGATION_FILE_NAME)
+            {
+                continue;
+            }
+
+            let path = mount(&source.prefix, &file.path);
+
+            let content = match &file.content {
+                InputContent::Text(text) if is_markdown(&file.path) => {
+                    InputContent::Text(rewriter.rewrite_markdown(text, &file.path, &components))
+                }
+                content => content.clone(),
+            };
+
+            if let Some((other, other_path)) =
+                owners.insert(path.clone(), (index, file.path.clone()))
+            {
+                errors.push(collision_error(
+                    &path.to_string_lossy(),
+                    (&sources[other], other_path.as_path()),
+                    (source, file.path.as_path()),
+                    &path,
+                ));
+            } else if is_page(&path) {
+                let uri_path = fs_to_uri_path(&path);
+
+                if let Some((other, other_path)) =
+                    uri_owners.insert(uri_path.clone(), (index, file.path.clone()))
+                {
+                    if other != index {
+                        errors.push(collision_error(
+                            &uri_path,
+                            (&sources[other], other_path.as_path()),
+                            (source, file.path.as_path()),
+                            &path,
+                        ));
+                    }
+                }
+            }
+
+            files.push(InputFile { path, content });
+        }
+    }
+
+    if let Value::Mapping(mapping) = &mut composed_settings {
+        if !open_api.is_empty() {
+            match mapping.get_mut("open_api") {
+                Some(Value::Sequence(specs)) => specs.extend(open_api),
+                _ => {
+                    mapping.insert("open_api".into(), Value::Sequence(open_api));
+                }
+            }
+        }
+
+        if settings.navigation == NavigationMerge::Tabs && !mapping.contains_key("tabs") {
+            mapping.insert("tabs".into(), Value::Sequence(tabs));
+        }
+    }
+
+    if settings.navigation == NavigationMerge::Sections && !navigation.is_empty() {
+        files.push(InputFile {
+            path: PathBuf::from(NAVIGATION_FILE_NAME),
+            content: InputContent::Text(
+                serde_yaml::to_string(&Value::Sequence(navigation)).unwrap_or_default(),
+            ),
+        });
+    }
+
+    files.push(InputFile {
+        path: PathBuf::from(SETTINGS_FILE_NAME),
+        content: InputContent::Text(serde_yaml::to_string(&composed_settings).unwrap_or_default()),
+    });
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Project::from_file_list(files)
+}
+
+/// Where a file of the source ends up in the composed project.
+fn mount(prefix: &str, path: &Path) -> PathBuf {
+    if prefix.is_empty() {
+        return path.to_owned();
+    }
+    if path.as_os_str().is_empty() {
+        return PathBuf::from(prefix);
+    }
+
+    let mut components = path.components();
+
+    match components.next() {
+        Some(Component::Normal(first)) if SHARED_FOLDERS.iter().any(|f| first == *f) => {
+            Path::new(first).join(prefix).join(components.as_path())
+        }
+        _ => Path::new(prefix).join(path),
+    }
+}
+
+fn mount_open_api_spec(prefix: &str, spec: &mut Value) {
+    let Value::Mapping(spec) = spec else {
+        return;
+    };
+
+    // The default folder moves with the project too
+    let augmentations_dir = Value::String(DEFAULT_AUGMENTATIONS_DIR.to_owned());
+    spec.entry(Value::String("augmentations_dir".to_owned()))
+        .or_insert(augmentations_dir);
+
+    for key in ["spec_file", "overview_template", "augmentations_dir"] {
+        if let Some(Value::String(path)) = spec.get_mut(key) {
+            *path = mount(prefix, Path::new(path)).to_string_lossy().to_string();
+        }
+    }
+
+    if !prefix.is_empty() {
+        if let Some(Value::String(uri_prefix)) = spec.get_mut("uri_prefix") {
+            *uri_prefix = format!("/{}/{}", prefix, uri_prefix.trim_start_matches('/'))
+                .trim_end_matches('/')
+                .to_owned();
+        }
+    }
+}
+
+/// Resolves `.` and `..` in a path inside the source. Returns `None` for
+/// paths that point outside of it.
+fn normalize(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+
+    Some(out)
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("md")
+}
+
+/// Markdown files outside of the shared folders are pages.
+fn is_page(path: &Path) -> bool {
+    is_markdown(path) && !SHARED_FOLDERS.iter().any(|folder| path.starts_with(folder))
+}
+
+fn collision_error(
+    at: &str,
+    (first, first_path): (&Source, &Path),
+    (second, second_path): (&Source, &Path),
+    mounted: &Path,
+) -> Error {
+    Error {
+        code: Error::DUPLICATE_URI_PATH,
+        message: format!(
+            r#"Sources "{}" and "{}" both have "{}""#,
+            first.scheme(),
+            second.scheme(),
+            at
+        ),
+        description: format!(
+            "\"{}\" in \"{}\" and \"{}\" in \"{}\" end up at the same place in the composed project.\nRename one of the files, or mount the sources under different prefixes.",
+            first_path.display(),
+            first.scheme(),
+            second_path.display(),
+            second.scheme()
+        ),
+        file: Some(mounted.to_owned()),
+        position: None,
+        owner: None,
+        suggestions: vec![],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+
+    fn text(path: &str, content: &str) -> InputFile {
+        InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_owned()),
+        }
+    }
+
+    fn settings(navigation: NavigationMerge) -> ComposeSettings {
+        ComposeSettings {
+            settings: "---\ntitle: Everything\n".to_owned(),
+            navigation,
+        }
+    }
+
+    fn content(project: &Project, path: &str) -> String {
+        project
+            .pages()
+            .into_iter()
+            .find(|p| p.fs_path() == Path::new(path))
+            .and_then(|p| match p.page {
+                crate::page_kind::PageKind::Markdown(page) => Some(page.content.clone()),
+                crate::page_kind::PageKind::OpenApi(_) => None,
+            })
+            .unwrap_or_else(|| panic!("No page at {}", path))
+    }
+
+    #[test]
+    fn mounts_sources_under_their_prefix() {
+        let project = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![
+                        text("README.md", "# Product\n\n[Setup](/guides/setup.md)"),
+                        text("guides/setup.md", "# Setup"),
+                    ],
+                ),
+                (
+                    "sdk".to_owned(),
+                    vec![
+                        text(
+                            "README.md",
+                            indoc! {r#"
+                            # SDK
+
+                            [Install](guides/install.md#install) ![Logo](/_assets/logo.png)
+
+                            <Button href="/guides/install.md">Install</Button>
+
+                            ```md
+                            [Example](/guides/install.md)
+                            ```
+                            "#},
+                        ),
+                        text("guides/install.md", "# Install\n\n[Back](../README.md)"),
+                        text("_assets/logo.png", ""),
+                    ],
+                ),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap();
+
+        assert!(project.get_page_by_uri_path("/guides/setup").is_some());
+        assert!(project.get_page_by_uri_path("/sdk").is_some());
+        assert!(project
+            .get_page_by_uri_path("/sdk/guides/install")
+            .is_some());
+        assert!(project
+            .get_asset_by_fs_path(Path::new("_assets/sdk/logo.png"))
+            .is_some());
+
+        assert_eq!(
+            content(&project, "sdk/README.md"),
+            indoc! {r#"
+            # SDK
+
+            [Install](/sdk/guides/install.md#install) ![Logo](/_assets/sdk/logo.png)
+
+            <Button href="/sdk/guides/install.md">Install</Button>
+
+            ```md
+            [Example](/guides/install.md)
+            ```
+            "#}
+        );
+        assert_eq!(
+            content(&project, "sdk/guides/install.md"),
+            "# Install\n\n[Back](/sdk/README.md)"
+        );
+        // The root source stays where it is
+        assert_eq!(
+            content(&project, "README.md"),
+            "# Product\n\n[Setup](/guides/setup.md)"
+        );
+
+        assert_eq!(project.verify(None, None), Ok(()));
+    }
+
+    #[test]
+    fn resolves_links_to_other_sources() {
+        let project = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![text("README.md", "# Product\n\n[SDK](sdk://quickstart.md)")],
+                ),
+                (
+                    "/sdk/".to_owned(),
+                    vec![
+                        text("README.md", "# SDK\n\n[Home](root://README.md)"),
+                        text("quickstart.md", "# Quickstart"),
+                    ],
+                ),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap();
+
+        assert_eq!(
+            content(&project, "README.md"),
+            "# Product\n\n[SDK](/sdk/quickstart.md)"
+        );
+        assert_eq!(
+            content(&project, "sdk/README.md"),
+            "# SDK\n\n[Home](/README.md)"
+        );
+        assert_eq!(project.verify(None, None), Ok(()));
+    }
+
+    #[test]
+    fn keeps_components_apart() {
+        let project = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![
+                        text("README.md", "<Component.Card />"),
+                        text("_components/card.md", "Product card"),
+                    ],
+                ),
+                (
+                    "sdk".to_owned(),
+                    vec![
+                        text("README.md", "<Component.Card />"),
+                        text("_components/card.md", "SDK card"),
+                    ],
+                ),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap();
+
+        assert_eq!(content(&project, "sdk/README.md"), "<Component.Sdk.Card />");
+        assert_eq!(content(&project, "README.md"), "<Component.Card />");
+
+        let ast = project
+            .get_page_by_uri_path("/sdk")
+            .unwrap()
+            .ast(None)
+            .unwrap();
+        assert!(format!("{:?}", ast).contains("SDK card"));
+    }
+
+    #[test]
+    fn merges_navigations_as_sections() {
+        let project = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![
+                        text("README.md", "# Product"),
+                        text(NAVIGATION_FILE_NAME, "- heading: Product\n  items:\n    - label: Product\n      href: /README.md\n"),
+                    ],
+                ),
+                (
+                    "sdk".to_owned(),
+                    vec![
+                        text("README.md", "# SDK"),
+                        text("install.md", "# Install"),
+                        text(NAVIGATION_FILE_NAME, "- heading: SDK\n  items:\n    - label: Install\n      href: /install.md\n"),
+                    ],
+                ),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap();
+
+        let navigation = project.root_navigation(None).unwrap();
+
+        assert_eq!(
+            navigation
+                .sections
+                .iter()
+                .map(|s| s.heading.clone().unwrap_or_default())
+                .collect::<Vec<_>>(),
+            vec!["Product", "SDK"]
+        );
+        assert_eq!(
+            navigation.gather_links(),
+            vec!["/README.md", "/sdk/install.md"]
+        );
+    }
+
+    #[test]
+    fn merges_navigations_as_tabs() {
+        let project = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![
+                        text("README.md", "# Product"),
+                        text(NAVIGATION_FILE_NAME, "- heading: Product\n"),
+                    ],
+                ),
+                (
+                    "sdk".to_owned(),
+                    vec![
+                        text(SETTINGS_FILE_NAME, "---\ntitle: SDK\n"),
+                        text("README.md", "# SDK"),
+                        text("install.md", "# Install"),
+                        text(NAVIGATION_FILE_NAME, "- heading: SDK\n  items:\n    - label: Install\n      href: /install.md\n"),
+                    ],
+                ),
+            ],
+            settings(NavigationMerge::Tabs),
+        )
+        .unwrap();
+
+        let labels = project
+            .tabs()
+            .unwrap()
+            .tabs
+            .iter()
+            .map(|t| t.label.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, vec!["Everything", "SDK"]);
+
+        let navigation = project.navigation(None, "/sdk").unwrap();
+        assert_eq!(navigation.gather_links(), vec!["/sdk/install.md"]);
+    }
+
+    #[test]
+    fn mounts_openapi_specs_of_sources() {
+        let spec = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Pets
+          version: 1.0.0
+        paths:
+          /pets:
+            get:
+              summary: List pets
+              tags: [Pets]
+              responses:
+                '200':
+                  description: Pets
+        "#};
+
+        let project = Project::compose(
+            vec![
+                (String::new(), vec![text("README.md", "# Product")]),
+                (
+                    "api".to_owned(),
+                    vec![
+                        text(
+                            SETTINGS_FILE_NAME,
+                            "---\ntitle: API\nopen_api:\n  - spec_file: openapi.yaml\n    uri_prefix: /reference\n",
+                        ),
+                        text("README.md", "# API"),
+                        text("openapi.yaml", spec),
+                    ],
+                ),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap();
+
+        assert_eq!(
+            project.settings().open_api()[0].spec_file,
+            PathBuf::from("api/openapi.yaml")
+        );
+        assert_eq!(
+            project.settings().open_api()[0].augmentations_dir(),
+            PathBuf::from("api/_openapi")
+        );
+        assert!(project
+            .get_page_by_uri_path("/api/reference/pets")
+            .is_some());
+    }
+
+    #[test]
+    fn reports_collisions_between_sources() {
+        let errors = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![
+                        text("README.md", "# Product"),
+                        text("sdk/README.md", "# Old SDK"),
+                    ],
+                ),
+                ("sdk".to_owned(), vec![text("README.md", "# SDK")]),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::DUPLICATE_URI_PATH);
+        assert_eq!(
+            errors[0].message,
+            r#"Sources "root" and "sdk" both have "sdk/README.md""#
+        );
+        assert_eq!(errors[0].file, Some(PathBuf::from("sdk/README.md")));
+    }
+
+    #[test]
+    fn reports_uri_collisions_between_sources() {
+        let errors = Project::compose(
+            vec![
+                (
+                    String::new(),
+                    vec![
+                        text("README.md", "# Product"),
+                        text("sdk/setup.md", "# Setup"),
+                    ],
+                ),
+                ("sdk".to_owned(), vec![text("setup/README.md", "# Setup")]),
+            ],
+            settings(NavigationMerge::Sections),
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(
+            errors[0].message,
+            r#"Sources "root" and "sdk" both have "/sdk/setup""#
+        );
+    }
+}
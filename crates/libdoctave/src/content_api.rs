@@ -1,5 +1,3 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-
 /// Module that defines the interface for responses returned by the internal content API consumed
 /// by venue.
 ///
@@ -11,9 +9,12 @@ use crate::{
     frontmatter::PageWidth,
     markdown_page::OnThisPageHeading,
     navigation::{Navigation, Section},
+    openapi_outline::TagGroup,
+    page_links::PageLink,
     render_context::RenderContext,
     settings::Settings,
-    tabs::Tab,
+    tabs::{Tab, TabScope},
+    tutorial::{self, TutorialProgress},
     Ast, Error, PageHandle, Project as LibdoctaveProject, RenderOptions,
 };
 
@@ -39,6 +40,14 @@ pub struct ResponseContext {
     pub sign_assets: bool,
     /// Debug information
     pub debug_info: DebugInfo,
+    /// Include the page's AST. Large pages can be fetched in parts with
+    /// `Project::page_ast_chunk` instead.
+    pub include_ast: bool,
+    /// Include the navigation of the page's tab
+    pub include_navigation: bool,
+    /// Extract the page's description from its content when the
+    /// frontmatter doesn't set one
+    pub include_search_excerpt: bool,
 }
 
 impl Default for ResponseContext {
@@ -53,6 +62,9 @@ impl Default for ResponseContext {
             view_mode: ViewMode::Prod,
             sign_assets: false,
             debug_info: DebugInfo::default(),
+            include_ast: true,
+            include_navigation: true,
+            include_search_excerpt: true,
         }
     }
 }
@@ -78,12 +90,20 @@ pub struct Project {
     tabs: Vec<Tab>,
     /// Index of the currently active version
     active_tab_index: Vec<usize>,
-    /// The currently active navigation structure
-    active_navigation: CurrentNavigation,
+    /// The tab and subtab of the current page. The navigation only covers
+    /// this scope, while `tabs` lists the full structure.
+    active_scope: Option<TabScope>,
+    /// The currently active navigation structure. Left out if the response
+    /// was requested without it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    active_navigation: Option<CurrentNavigation>,
     /// The URL we can load a favicon from
     favicon_url: Option<String>,
     /// Custom CSS contents
     custom_css: Vec<String>,
+    /// Accent color variables for tabs with their own accent color
+    #[serde(skip_serializing_if = "String::is_empty")]
+    tab_accent_css: String,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -156,13 +176,9 @@ pub struct DebugInfo {
 impl DebugInfo {
     pub fn start_performance(&mut self, label: &str) {
         if let Some(perf) = &mut self.performance {
-            let start = SystemTime::now();
-            let since_the_epoch = start
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards");
             perf.push(DebugInfoStep {
                 label: label.to_string(),
-                duration_ms: since_the_epoch.as_millis() as usize,
+                duration_ms: crate::utils::now_millis() as usize,
             });
         }
     }
@@ -170,10 +186,7 @@ impl DebugInfo {
     pub fn end_performance(&mut self, label: &str) {
         if let Some(perf) = &mut self.performance {
             if let Some(found) = perf.iter_mut().find(|entry| entry.label == label) {
-                let end = SystemTime::now();
-                let since_the_epoch = end.duration_since(UNIX_EPOCH).expect("Time went backwards");
-
-                found.duration_ms = (since_the_epoch.as_millis() as usize) - found.duration_ms;
+                found.duration_ms = (crate::utils::now_millis() as usize) - found.duration_ms;
             }
         }
     }
@@ -381,13 +394,35 @@ pub enum CurrentPage {
     Page {
         path: String,
         http_status: u16,
-        ast: Ast,
+        /// Left out if the response was requested without it
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ast: Option<Ast>,
         title: Option<String>,
         description: String,
         page_kind: String,
         breadcrumbs: Vec<Breadcrumb>,
         on_this_page_headings: Vec<OnThisPageHeading>,
         page_options: PageOptions,
+        previous_page: Option<PageLink>,
+        next_page: Option<PageLink>,
+        /// Where the page is in its tutorial, for steps of a tutorial
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tutorial: Option<TutorialProgress>,
+        /// Where the original of the page is, for pages mirrored from elsewhere
+        #[serde(skip_serializing_if = "Option::is_none")]
+        canonical_url: Option<String>,
+        /// RFC 3339 timestamp, or the date set in the frontmatter
+        last_updated: Option<String>,
+        contributors: Option<Vec<String>>,
+        /// The tags and operations of the spec, for OpenAPI pages
+        openapi_outline: Option<Vec<TagGroup>>,
+        /// Style sheets from the page's `css` frontmatter, scoped to
+        /// `css_scope` when it's set
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        custom_css: Vec<String>,
+        /// A class for the page's container, for `css_scope: true`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        css_scope: Option<String>,
     },
     #[serde(rename = "error")]
     Error {
@@ -433,8 +468,9 @@ impl From<crate::Result<Navigation>> for CurrentNavigation {
 /// Internal convenience struct
 struct Surrounding {
     active_tab: Vec<usize>,
+    active_scope: Option<TabScope>,
     tabs: Vec<Tab>,
-    navigation: crate::Result<Navigation>,
+    navigation: Option<crate::Result<Navigation>>,
 }
 
 impl ContentApiResponse {
@@ -445,44 +481,61 @@ impl ContentApiResponse {
     ) -> ContentApiResponse {
         let Surrounding {
             active_tab,
+            active_scope,
             tabs,
             navigation,
         } = Self::surrounding(page_handle.uri_path(), project, &ctx);
 
         let page = match page_handle.ast(Some(&ctx.options)) {
-            Ok(ast) => CurrentPage::Page {
-                path: page_handle.uri_path().to_string(),
-                http_status: 200,
-                title: page_handle.title().ok().flatten(),
-                description: page_handle
-                    .description()
-                    .ok()
-                    .flatten()
-                    .unwrap_or(DescriptionExtractor::extract(&ast)),
-                page_kind: if page_handle.is_openapi() {
-                    "openapi".to_string()
-                } else {
-                    "markdown".to_string()
-                },
-                ast,
-                breadcrumbs: page_handle.breadcrumbs(Some(&ctx.options)),
-                on_this_page_headings: page_handle.on_this_page_headings(Some(&ctx.options)),
-                page_options: PageOptions {
-                    hide_navigation: page_handle.hide_navigation(),
-                    hide_side_table_of_contents: page_handle.hide_side_table_of_contents(),
-                    breadcrumbs: page_handle.show_breadcrumbs(),
-                    page_width: page_handle.page_width(),
-                    hidden_from_search: page_handle.hidden_from_search().unwrap_or(false),
-                },
-            },
+            Ok(ast) => {
+                let (previous_page, next_page) = page_handle.previous_and_next(Some(&ctx.options));
+
+                CurrentPage::Page {
+                    path: page_handle.uri_path().to_string(),
+                    http_status: 200,
+                    title: page_handle.title().ok(),
+                    description: match page_handle.description().ok().flatten() {
+                        Some(description) => description,
+                        None if ctx.include_search_excerpt => DescriptionExtractor::extract(&ast),
+                        None => String::new(),
+                    },
+                    page_kind: if page_handle.is_openapi() {
+                        "openapi".to_string()
+                    } else {
+                        "markdown".to_string()
+                    },
+                    ast: ctx.include_ast.then_some(ast),
+                    breadcrumbs: page_handle.breadcrumbs(Some(&ctx.options)),
+                    on_this_page_headings: page_handle.on_this_page_headings(Some(&ctx.options)),
+                    page_options: PageOptions {
+                        hide_navigation: page_handle.hide_navigation(),
+                        hide_side_table_of_contents: page_handle.hide_side_table_of_contents(),
+                        breadcrumbs: page_handle.show_breadcrumbs(),
+                        page_width: page_handle.page_width(),
+                        hidden_from_search: page_handle.hidden_from_search().unwrap_or(false),
+                        draft: page_handle.draft_status().is_some(),
+                        template: page_handle.template(),
+                        noindex: page_handle.noindex(),
+                    },
+                    previous_page,
+                    next_page,
+                    tutorial: tutorial::progress(&page_handle, project, Some(&ctx.options)),
+                    canonical_url: page_handle.canonical_url(),
+                    last_updated: page_handle.last_updated(),
+                    contributors: page_handle.contributors(),
+                    openapi_outline: page_handle.is_openapi().then(|| {
+                        project.openapi_outline(page_handle.fs_path(), Some(&ctx.options))
+                    }),
+                    custom_css: page_handle.custom_css(),
+                    css_scope: page_handle.css_scope(),
+                }
+            }
             Err(error) => CurrentPage::Error {
                 path: page_handle.uri_path().to_string(),
                 http_status: 400,
                 errors: vec![error],
                 title: page_handle
                     .title()
-                    .ok()
-                    .flatten()
                     .unwrap_or(page_handle.fs_path().display().to_string()),
                 description: page_handle.description().ok().flatten().unwrap_or_default(),
                 page_kind: if page_handle.is_openapi() {
@@ -496,6 +549,9 @@ impl ContentApiResponse {
                     breadcrumbs: page_handle.show_breadcrumbs(),
                     page_width: page_handle.page_width(),
                     hidden_from_search: page_handle.hidden_from_search().unwrap_or(false),
+                    draft: page_handle.draft_status().is_some(),
+                    template: page_handle.template(),
+                    noindex: page_handle.noindex(),
                 },
             },
         };
@@ -514,10 +570,12 @@ impl ContentApiResponse {
                 settings,
                 tabs,
                 active_tab_index: active_tab,
+                active_scope,
                 active_version: ctx.active_version,
                 custom_css: project.custom_css.clone(),
+                tab_accent_css: project.tab_accent_css(),
                 favicon_url: ctx.favicon_url,
-                active_navigation: navigation.into(),
+                active_navigation: navigation.map(Into::into),
             },
             build: ctx.build,
             view_mode: ctx.view_mode,
@@ -533,6 +591,7 @@ impl ContentApiResponse {
     ) -> ContentApiResponse {
         let Surrounding {
             active_tab,
+            active_scope,
             tabs,
             navigation,
         } = Self::surrounding(uri_path, project, &ctx);
@@ -553,6 +612,9 @@ impl ContentApiResponse {
                 breadcrumbs: handle.show_breadcrumbs(),
                 page_width: handle.page_width(),
                 hidden_from_search: handle.hidden_from_search().unwrap_or(false),
+                draft: false,
+                template: None,
+                noindex: handle.noindex(),
             }
         } else {
             PageOptions {
@@ -561,6 +623,9 @@ impl ContentApiResponse {
                 breadcrumbs: true,
                 page_width: PageWidth::Prose,
                 hidden_from_search: false,
+                draft: false,
+                template: None,
+                noindex: false,
             }
         };
 
@@ -581,10 +646,12 @@ impl ContentApiResponse {
                 tabs,
                 settings,
                 active_tab_index: active_tab,
+                active_scope,
                 active_version: ctx.active_version,
                 custom_css: project.custom_css.clone(),
+                tab_accent_css: project.tab_accent_css(),
                 favicon_url: ctx.favicon_url,
-                active_navigation: navigation.into(),
+                active_navigation: navigation.map(Into::into),
             },
             build: ctx.build,
             view_mode: ctx.view_mode,
@@ -599,32 +666,14 @@ impl ContentApiResponse {
         ctx: &ResponseContext,
     ) -> Surrounding {
         let mut tabs = project.tabs().map(|s| s.tabs.clone()).unwrap_or_default();
-        let mut active_tab = vec![];
-        let matching_subtab_path = project.get_subtab_path_by_uri_path(uri_path);
-        let navigation = project.navigation(
-            Some(&ctx.options),
-            matching_subtab_path
-                .clone()
-                .unwrap_or("/".to_string())
-                .as_str(),
-        );
-
-        if let Some(subtab_path) = matching_subtab_path {
-            'outer: for (tab_index, tab) in tabs.iter().enumerate() {
-                for (subtab_index, subtab) in tab.subtabs.iter().enumerate() {
-                    if subtab.href == subtab_path {
-                        active_tab.push(tab_index);
-                        active_tab.push(subtab_index);
-                        break 'outer;
-                    }
-                }
-
-                if tab.href == subtab_path {
-                    active_tab.push(tab_index);
-                    break 'outer;
-                }
-            }
-        }
+        let active_scope = project.tab_scope(uri_path);
+        let active_tab = active_scope
+            .as_ref()
+            .map(|scope| scope.index.clone())
+            .unwrap_or_default();
+        let navigation = ctx
+            .include_navigation
+            .then(|| project.navigation_for_page(uri_path, Some(&ctx.options)));
 
         for tab in &mut tabs {
             let prefix = ctx.options.prefix_link_urls.as_deref().unwrap_or("");
@@ -633,6 +682,7 @@ impl ContentApiResponse {
 
         Surrounding {
             active_tab,
+            active_scope,
             tabs,
             navigation,
         }
@@ -662,13 +712,21 @@ pub struct PageOptions {
     pub page_width: PageWidth,
     pub breadcrumbs: bool,
     pub hidden_from_search: bool,
+    /// Drafts are only rendered when publishing drafts, e.g. in the dev
+    /// server, and are marked as such on the page.
+    pub draft: bool,
+    /// The layout to render the page with, from `templates` in the settings.
+    pub template: Option<String>,
+    /// Asks search engines not to index the page.
+    pub noindex: bool,
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
         settings::{FooterLink, HeaderLink, InternalLink},
-        InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+        test_support, FileMetadata, InputContent, InputFile, NAVIGATION_FILE_NAME,
+        SETTINGS_FILE_NAME,
     };
 
     use std::path::{Path, PathBuf};
@@ -677,31 +735,19 @@ mod test {
 
     #[test]
     fn basic() {
-        let file_list = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(String::from("")),
-            },
-            InputFile {
-                path: PathBuf::from("foo/bar.md"),
-                content: InputContent::Text(String::from("[good link](/)")),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project")),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(String::from(indoc! {r#"
-            - heading: "Guides"
-              items:
-                - href: foo/bar.md
-                  label: Example
-            "#})),
-            },
-        ];
-
-        let project = LibdoctaveProject::from_file_list(file_list).unwrap();
+        let project = test_support::project(&[
+            ("README.md", ""),
+            ("foo/bar.md", "[good link](/)"),
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {r#"
+                - heading: "Guides"
+                  items:
+                    - href: foo/bar.md
+                      label: Example
+                "#},
+            ),
+        ]);
 
         let response =
             project.get_content_response_by_uri_path("/foo/bar", ResponseContext::default());
@@ -718,12 +764,43 @@ mod test {
             response
         );
 
-        let as_json = serde_json::to_value(&response).unwrap();
+        let as_json = test_support::page_snapshot(&project, "/foo/bar");
 
         assert_eq!(as_json["kind"], "content");
         assert_eq!(as_json["page"]["status"], "ok");
         assert_eq!(as_json["page"]["breadcrumbs"][0]["text"], "Guides");
         assert_eq!(as_json["project"]["active_navigation"]["status"], "ok");
+        assert_eq!(
+            as_json["project"]["active_navigation"]["sections"][0]["expanded_for_current_page"],
+            true
+        );
+    }
+
+    #[test]
+    fn leaves_out_parts_of_the_response_when_asked() {
+        let project = test_support::project(&[("README.md", "# Hello\n\nSome content")]);
+
+        let full = test_support::page_snapshot(&project, "/");
+
+        assert!(full["page"].get("ast").is_some());
+        assert!(full["project"].get("active_navigation").is_some());
+        assert_eq!(full["page"]["description"], "Some content");
+
+        let trimmed = serde_json::to_value(project.get_content_response_by_uri_path(
+            "/",
+            ResponseContext {
+                include_ast: false,
+                include_navigation: false,
+                include_search_excerpt: false,
+                ..Default::default()
+            },
+        ))
+        .unwrap();
+
+        assert_eq!(trimmed["page"]["status"], "ok");
+        assert!(trimmed["page"].get("ast").is_none());
+        assert!(trimmed["project"].get("active_navigation").is_none());
+        assert_eq!(trimmed["page"]["description"], "");
     }
 
     #[test]
@@ -933,6 +1010,71 @@ mod test {
         }
     }
 
+    #[test]
+    fn active_scope_uses_longest_matching_path() {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::new()),
+            },
+            InputFile {
+                path: PathBuf::from("api-guide/intro.md"),
+                content: InputContent::Text(String::new()),
+            },
+            InputFile {
+                path: PathBuf::from("api/README.md"),
+                content: InputContent::Text(String::new()),
+            },
+            InputFile {
+                path: PathBuf::from("api/users.md"),
+                content: InputContent::Text(String::new()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! { r#"
+                ---
+                title: An Project
+                tabs:
+                  - label: "Guides"
+                    path: "/"
+                  - label: "API"
+                    path: "/api"
+                "#})),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::new()),
+            },
+            InputFile {
+                path: PathBuf::from("api").join(Path::new(NAVIGATION_FILE_NAME)),
+                content: InputContent::Text(String::new()),
+            },
+        ];
+
+        let project = LibdoctaveProject::from_file_list(file_list).unwrap();
+
+        let scope_of = |uri_path: &str| match project
+            .get_content_response_by_uri_path(uri_path, ResponseContext::default())
+        {
+            ContentApiResponse::Content {
+                project:
+                    Project {
+                        active_scope,
+                        active_tab_index,
+                        ..
+                    },
+                ..
+            } => (active_scope.map(|s| s.path), active_tab_index),
+            response => panic!("Unexpected response: {:#?}", response),
+        };
+
+        assert_eq!(scope_of("/api/users"), (Some("/api".to_string()), vec![1]));
+        assert_eq!(
+            scope_of("/api-guide/intro"),
+            (Some("/".to_string()), vec![0])
+        );
+    }
+
     #[test]
     fn page_not_found() {
         let file_list = vec![
@@ -1183,49 +1325,29 @@ mod test {
 
     #[test]
     fn hide_navigation_from_frontmatter() {
-        let file_list = vec![
-            InputFile {
-                path: PathBuf::from("foo.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
+        let project = test_support::project(&[
+            (
+                "foo.md",
+                indoc! {r#"
                 ---
                 navigation: false
                 ---
 
                 Hi
-                "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
+                "#},
+            ),
+            (
+                "README.md",
+                indoc! {r#"
                 ---
                 hide_navigation: true
                 ---
 
                 Hi
-                "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from(indoc! { r#"
-                ---
-                title: An Project
-                "# })),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(String::from(indoc! {r#"
-                        - heading: "Guides"
-                        "#})),
-            },
-        ];
-
-        let project = LibdoctaveProject::from_file_list(file_list).unwrap();
+                "#},
+            ),
+            (NAVIGATION_FILE_NAME, "- heading: \"Guides\"\n"),
+        ]);
 
         let response = project.get_content_response_by_uri_path("/", ResponseContext::default());
 
@@ -1447,6 +1569,49 @@ mod test {
         );
     }
 
+    #[test]
+    fn template_from_frontmatter_folder_rule_or_default() {
+        let page = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_owned()),
+        };
+
+        let file_list = vec![
+            page("README.md", "---\ntemplate: landing\n---\n\nHi"),
+            page("guide.md", "Hi"),
+            page("changelog/v1.md", "Hi"),
+            page("changelog/v2.md", "---\ntemplate: guide\n---\n\nHi"),
+            page(
+                SETTINGS_FILE_NAME,
+                indoc! { r#"
+                ---
+                title: An Project
+                templates:
+                  allowed: [landing, changelog, guide]
+                  default: guide
+                  rules:
+                    - path: changelog/**
+                      template: changelog
+                "# },
+            ),
+            page(NAVIGATION_FILE_NAME, "---"),
+        ];
+
+        let project = LibdoctaveProject::from_file_list(file_list).unwrap();
+
+        let template = |uri_path: &str| {
+            let response =
+                project.get_content_response_by_uri_path(uri_path, ResponseContext::default());
+            serde_json::to_value(&response).unwrap()["page"]["page_options"]["template"].clone()
+        };
+
+        assert_eq!(template("/"), "landing");
+        assert_eq!(template("/guide"), "guide");
+        assert_eq!(template("/changelog/v1"), "changelog");
+        assert_eq!(template("/changelog/v2"), "guide");
+        assert!(template("/missing").is_null());
+    }
+
     #[test]
     fn error_on_page() {
         let file_list = vec![
@@ -1535,7 +1700,7 @@ mod test {
                 response,
                 ContentApiResponse::Content {
                     project: Project {
-                        active_navigation: CurrentNavigation::Error { .. },
+                        active_navigation: Some(CurrentNavigation::Error { .. }),
                         ..
                     },
                     ..
@@ -1565,6 +1730,8 @@ mod test {
             description: "More info about the error".to_string(),
             file: None,
             position: None,
+            owner: None,
+            suggestions: vec![],
         };
 
         let response = ContentApiResponse::InvalidProject {
@@ -1632,7 +1799,8 @@ mod test {
                     title, description, ..
                 } => {
                     assert_eq!(description, "Hello, world");
-                    assert_eq!(title, None);
+                    // The root README has no file name to fall back to
+                    assert_eq!(title, Some("An Project".to_string()));
                 }
                 _ => panic!(),
             },
@@ -1735,7 +1903,10 @@ mod test {
             },
             InputFile {
                 path: PathBuf::from("_assets/asdf.png"),
-                content: InputContent::Binary(String::from("")),
+                content: InputContent::Binary {
+                    signature: String::new(),
+                    size_bytes: 0,
+                },
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
@@ -1764,7 +1935,7 @@ mod test {
         if let ContentApiResponse::Content {
             page:
                 CurrentPage::Page {
-                    ast: Ast::Markdown(root),
+                    ast: Some(Ast::Markdown(root)),
                     ..
                 },
             ..
@@ -2561,4 +2732,215 @@ mod test {
             _ => panic!("Unexpected response {:#?}", response),
         }
     }
+
+    #[test]
+    fn previous_and_next_pages() {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from("foo.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {r#"
+            - heading: "Guides"
+              items:
+                - href: foo.md
+                  label: Foo
+                - href: bar.md
+                  label: Bar
+            "#})),
+            },
+        ];
+
+        let project = LibdoctaveProject::from_file_list(file_list).unwrap();
+
+        let first = serde_json::to_value(
+            project.get_content_response_by_uri_path("/foo", ResponseContext::default()),
+        )
+        .unwrap();
+
+        assert_eq!(first["page"]["previous_page"], serde_json::Value::Null);
+        assert_eq!(first["page"]["next_page"]["href"], "/bar");
+        assert_eq!(first["page"]["next_page"]["title"], "Bar");
+
+        let last = serde_json::to_value(
+            project.get_content_response_by_uri_path("/bar", ResponseContext::default()),
+        )
+        .unwrap();
+
+        assert_eq!(last["page"]["previous_page"]["href"], "/foo");
+        assert_eq!(last["page"]["previous_page"]["title"], "Foo");
+        assert_eq!(last["page"]["next_page"], serde_json::Value::Null);
+
+        let not_in_navigation = serde_json::to_value(
+            project.get_content_response_by_uri_path("/", ResponseContext::default()),
+        )
+        .unwrap();
+
+        assert_eq!(
+            not_in_navigation["page"]["previous_page"],
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            not_in_navigation["page"]["next_page"],
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn tutorial_steps_follow_the_tutorial_order() {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from("start/install.md"),
+                content: InputContent::Text(String::from("# Install")),
+            },
+            InputFile {
+                path: PathBuf::from("start/configure.md"),
+                content: InputContent::Text(String::from("# Configure")),
+            },
+            InputFile {
+                path: PathBuf::from("start/_tutorial.yaml"),
+                content: InputContent::Text(String::from(indoc! {r#"
+                title: Getting started
+                steps:
+                  - install.md
+                  - configure.md
+                "#})),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {r#"
+            - heading: "Guides"
+              items:
+                - href: start/configure.md
+                  label: Configure
+                - href: start/install.md
+                  label: Install
+            "#})),
+            },
+        ];
+
+        let project = LibdoctaveProject::from_file_list(file_list).unwrap();
+
+        let first = serde_json::to_value(
+            project.get_content_response_by_uri_path("/start/install", ResponseContext::default()),
+        )
+        .unwrap();
+
+        assert_eq!(first["page"]["previous_page"], serde_json::Value::Null);
+        assert_eq!(first["page"]["next_page"]["href"], "/start/configure");
+        assert_eq!(first["page"]["tutorial"]["title"], "Getting started");
+        assert_eq!(first["page"]["tutorial"]["total_steps"], 2);
+        assert_eq!(first["page"]["tutorial"]["current_step"], 1);
+        assert_eq!(
+            first["page"]["tutorial"]["steps"][1]["id"],
+            "start/configure"
+        );
+
+        let not_in_tutorial = serde_json::to_value(
+            project.get_content_response_by_uri_path("/", ResponseContext::default()),
+        )
+        .unwrap();
+
+        assert!(not_in_tutorial["page"].get("tutorial").is_none());
+    }
+
+    #[test]
+    fn exposes_last_updated_and_contributors() {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("# Hello")),
+            },
+            InputFile {
+                path: PathBuf::from("overridden.md"),
+                content: InputContent::Text(String::from(indoc! {r#"
+                ---
+                last_updated: 2024-01-31
+                contributors: [Alice]
+                ---
+                # Overridden
+                "#})),
+            },
+            InputFile {
+                path: PathBuf::from("unknown.md"),
+                content: InputContent::Text(String::from("# Unknown")),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+        ];
+
+        let metadata = std::collections::HashMap::from_iter(vec![
+            (
+                PathBuf::from("README.md"),
+                FileMetadata {
+                    modified_at: Some("2024-05-01T12:00:00Z".to_string()),
+                    authors: Some(vec!["Bob".to_string(), "Carol".to_string()]),
+                },
+            ),
+            (
+                PathBuf::from("overridden.md"),
+                FileMetadata {
+                    modified_at: Some("2024-05-01T12:00:00Z".to_string()),
+                    authors: None,
+                },
+            ),
+        ]);
+
+        let project = LibdoctaveProject::from_file_list_with_metadata(file_list, metadata).unwrap();
+
+        let metadata_for = |uri_path: &str| match project
+            .get_content_response_by_uri_path(uri_path, ResponseContext::default())
+        {
+            ContentApiResponse::Content {
+                page:
+                    CurrentPage::Page {
+                        last_updated,
+                        contributors,
+                        ..
+                    },
+                ..
+            } => (last_updated, contributors),
+            other => panic!("Unexpected response: {:?}", other),
+        };
+
+        assert_eq!(
+            metadata_for("/"),
+            (
+                Some("2024-05-01T12:00:00Z".to_string()),
+                Some(vec!["Bob".to_string(), "Carol".to_string()])
+            )
+        );
+        assert_eq!(
+            metadata_for("/overridden"),
+            (
+                Some("2024-01-31".to_string()),
+                Some(vec!["Alice".to_string()])
+            )
+        );
+        assert_eq!(metadata_for("/unknown"), (None, None));
+    }
 }
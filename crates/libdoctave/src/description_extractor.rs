@@ -1,47 +1,229 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::renderable_ast::{Node, NodeKind};
 use crate::utils::capitalize;
 use crate::Ast;
 
 pub struct DescriptionExtractor {}
 
 impl DescriptionExtractor {
+    /// Length of descriptions in characters, as recommended for meta descriptions.
+    pub const DEFAULT_MAX_LENGTH: usize = 160;
+
     pub fn extract(ast: &Ast) -> String {
+        Self::extract_with_max_length(ast, Self::DEFAULT_MAX_LENGTH)
+    }
+
+    /// Extracts a plain text description of at most `max_length` characters,
+    /// counted as user-perceived characters (grapheme clusters).
+    ///
+    /// Descriptions are taken from the page's prose, skipping headings,
+    /// images, code blocks, and components. Pages that only have prose inside
+    /// components fall back to the text in the components.
+    pub fn extract_with_max_length(ast: &Ast, max_length: usize) -> String {
         match ast {
-            Ast::OpenApi(openapi_ast) => {
-                format!(
+            Ast::OpenApi(openapi_ast) => truncate(
+                &format!(
                     "API Documentation for {}",
                     capitalize(&openapi_ast.tag.name)
-                )
-            }
+                ),
+                max_length,
+            ),
             Ast::Markdown(root) => {
-                let inner_text = root.inner_text().replace('\n', " ");
+                let mut prose = String::new();
+                gather_prose(root, false, &mut prose);
+
+                if prose.trim().is_empty() {
+                    gather_prose(root, true, &mut prose);
+                }
+
+                let prose = prose.split_whitespace().collect::<Vec<_>>().join(" ");
+
+                truncate(&prose, max_length)
+            }
+        }
+    }
+}
 
-                trim_string_at_word_boundary(&inner_text, 160)
+fn gather_prose(node: &Node, include_components: bool, out: &mut String) {
+    match &node.kind {
+        NodeKind::Text { value } | NodeKind::InlineCode { value } => out.push_str(value),
+        NodeKind::Break => out.push(' '),
+        NodeKind::Heading { .. }
+        | NodeKind::Image { .. }
+        | NodeKind::InlineSvg { .. }
+        | NodeKind::Code { .. }
+        | NodeKind::Math { .. }
+        | NodeKind::InlineMath { .. }
+        | NodeKind::HtmlTag { .. }
+        | NodeKind::Table { .. }
+        | NodeKind::ThematicBreak
+        | NodeKind::Footnotes
+        | NodeKind::OpenAPISchema(_) => {}
+        NodeKind::Grid(_)
+        | NodeKind::Flex(_)
+        | NodeKind::Box(_)
+        | NodeKind::Callout(_)
+        | NodeKind::Tabs(_)
+        | NodeKind::Tab(_)
+        | NodeKind::Steps
+        | NodeKind::Step(_)
+        | NodeKind::CodeSelect(_)
+        | NodeKind::HtmlBlock { .. }
+            if !include_components => {}
+        _ => {
+            for child in &node.children {
+                gather_prose(child, include_components, out);
+            }
+
+            // Keep the text of separate blocks apart
+            if matches!(
+                node.kind,
+                NodeKind::Paragraph
+                    | NodeKind::ListItem { .. }
+                    | NodeKind::TaskListItem { .. }
+                    | NodeKind::BlockQuote
+                    | NodeKind::DefinitionTerm
+                    | NodeKind::DefinitionDescription
+            ) {
+                out.push(' ');
             }
         }
     }
 }
 
-fn trim_string_at_word_boundary(s: &str, max_length: usize) -> String {
-    // Check if the string is already within the desired length.
-    if s.len() <= max_length {
-        return s.to_string();
+/// Truncates the text to `max_length` grapheme clusters, ending it with an
+/// ellipsis. Prefers cutting at a word boundary, unless that would lose most
+/// of the text, e.g. for languages that don't separate words with spaces.
+fn truncate(text: &str, max_length: usize) -> String {
+    let graphemes = text.graphemes(true).collect::<Vec<_>>();
+
+    if graphemes.len() <= max_length {
+        return text.to_string();
     }
 
-    let mut last_space_index = None;
-    let mut current_length = 0;
+    // Leave room for the ellipsis
+    let kept = &graphemes[..max_length.saturating_sub(1)];
 
-    for (i, c) in s.char_indices() {
-        if c.is_whitespace() {
-            last_space_index = Some(i);
-        }
+    let ends_at_word_boundary = graphemes[kept.len()].trim().is_empty();
 
-        current_length += c.len_utf8();
-        if current_length > max_length {
-            // If we have seen a space, we trim at the last space. Otherwise, we trim at the current position.
-            return s[..last_space_index.unwrap_or(i)].to_string();
-        }
+    let cut = kept
+        .iter()
+        .rposition(|g| g.trim().is_empty())
+        .filter(|i| !ends_at_word_boundary && *i >= kept.len() / 2)
+        .unwrap_or(kept.len());
+
+    let mut out = kept[..cut].concat();
+    out.truncate(
+        out.trim_end_matches(|c: char| c.is_whitespace() || c == ',')
+            .len(),
+    );
+    out.push('…');
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{ast_mdx, render_context::RenderContext};
+
+    fn describe(markdown: &str, max_length: usize) -> String {
+        let ctx = RenderContext::new();
+        let root = ast_mdx(markdown, &ctx).unwrap();
+
+        DescriptionExtractor::extract_with_max_length(&Ast::Markdown(root), max_length)
+    }
+
+    #[test]
+    fn uses_prose_without_markdown() {
+        let markdown = indoc! {r#"
+        # Getting started
+
+        This is **the** [guide](/guide) for `docapella`.
+
+        ```bash
+        docapella init
+        ```
+
+        - One
+        - Two
+        "#};
+
+        assert_eq!(
+            describe(markdown, DescriptionExtractor::DEFAULT_MAX_LENGTH),
+            "This is the guide for docapella. One Two"
+        );
+    }
+
+    #[test]
+    fn truncates_at_word_boundaries() {
+        assert_eq!(
+            describe("The quick brown fox jumps over the lazy dog", 20),
+            "The quick brown fox…"
+        );
+        assert_eq!(
+            describe("The quick brown fox jumps over the lazy dog", 18),
+            "The quick brown…"
+        );
+    }
+
+    #[test]
+    fn skips_components_and_images_at_the_start() {
+        let markdown = indoc! {r#"
+        ![Banner](/_assets/banner.png)
+
+        <Box>
+          This page is under construction.
+        </Box>
+
+        <Tabs>
+          <Tab title="First">A tab</Tab>
+        </Tabs>
+
+        The actual introduction.
+        "#};
+
+        assert_eq!(
+            describe(markdown, DescriptionExtractor::DEFAULT_MAX_LENGTH),
+            "The actual introduction."
+        );
     }
 
-    // If the loop completes without returning, it means the string is shorter than max_length.
-    s.to_string()
+    #[test]
+    fn falls_back_to_text_in_components() {
+        let markdown = indoc! {r#"
+        # Welcome
+
+        <Box>
+          Everything is in a box.
+        </Box>
+        "#};
+
+        assert_eq!(
+            describe(markdown, DescriptionExtractor::DEFAULT_MAX_LENGTH),
+            "Everything is in a box."
+        );
+    }
+
+    #[test]
+    fn truncates_cjk_text_by_character() {
+        let markdown = "これは日本語で書かれた説明文です。とても長い文章になります。";
+
+        let description = describe(markdown, 10);
+
+        assert_eq!(description, "これは日本語で書か…");
+        assert_eq!(description.graphemes(true).count(), 10);
+    }
+
+    #[test]
+    fn does_not_split_emoji() {
+        let markdown = "👩‍👩‍👧‍👦👩‍👩‍👧‍👦👩‍👩‍👧‍👦👩‍👩‍👧‍👦 family";
+
+        assert_eq!(describe(markdown, 4), "👩‍👩‍👧‍👦👩‍👩‍👧‍👦👩‍👩‍👧‍👦…");
+    }
+
+    #[test]
+    fn leaves_short_descriptions_alone() {
+        assert_eq!(describe("Short and sweet.", 16), "Short and sweet.");
+    }
 }
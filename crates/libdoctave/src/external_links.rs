@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::parallel::*;
+use crate::{
+    markdown::parser::is_external_url,
+    project::{nav_link_position, NavigationHandle},
+    Position, Project, NAVIGATION_FILE_NAME,
+};
+
+/// A link to another site, with every place it appears in the project.
+/// Checking whether it works is left to the caller, as it needs the network.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExternalLink {
+    pub url: String,
+    pub sources: Vec<LinkSource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct LinkSource {
+    /// Path of the file, relative to the project root
+    pub file: PathBuf,
+    /// Where the link was written in the file, if known
+    pub position: Option<Position>,
+}
+
+/// Gathers the external links from published pages, OpenAPI descriptions
+/// and navigations, sorted by URL.
+pub(crate) fn compute(project: &Project) -> Vec<ExternalLink> {
+    let mut sources = project
+        .published_pages(None)
+        .par_iter()
+        .flat_map_iter(|page| {
+            // Pages that fail to render are reported by `verify`
+            let links = page
+                .verification_links(None)
+                .map(|links| links.external)
+                .unwrap_or_default();
+
+            links.into_iter().map(|link| {
+                (
+                    link.uri,
+                    LinkSource {
+                        file: page.fs_path().to_owned(),
+                        position: link.position,
+                    },
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    sources.extend(navigation_links(project));
+
+    let mut links: BTreeMap<String, Vec<LinkSource>> = BTreeMap::new();
+    for (url, source) in sources {
+        links.entry(url).or_default().push(source);
+    }
+
+    links
+        .into_iter()
+        .map(|(url, mut sources)| {
+            sources.sort();
+            sources.dedup();
+            ExternalLink { url, sources }
+        })
+        .collect()
+}
+
+fn navigation_links(project: &Project) -> Vec<(String, LinkSource)> {
+    let Some(navigations) = &project.navigations else {
+        return vec![];
+    };
+
+    let mut out = vec![];
+
+    for (subtab_path, handle) in navigations {
+        let Some(NavigationHandle(source)) = handle else {
+            continue;
+        };
+        // Invalid navigations are reported by `verify`
+        let Ok(navigation) = project.navigation(None, subtab_path) else {
+            continue;
+        };
+
+        let file = PathBuf::from(subtab_path.trim_start_matches('/')).join(NAVIGATION_FILE_NAME);
+
+        for url in navigation.gather_external_links() {
+            if is_external_url(&url) {
+                out.push((
+                    url.clone(),
+                    LinkSource {
+                        file: file.clone(),
+                        position: nav_link_position(source, "external", &url),
+                    },
+                ));
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{InputContent, InputFile, SETTINGS_FILE_NAME};
+
+    use super::*;
+
+    fn project(files: &[(&str, &str)]) -> Project {
+        let mut input = vec![InputFile {
+            path: PathBuf::from(SETTINGS_FILE_NAME),
+            content: InputContent::Text(
+                indoc! {r#"
+            ---
+            title: Links
+            open_api:
+              - spec_file: openapi.yaml
+                uri_prefix: /api
+            "#}
+                .to_string(),
+            ),
+        }];
+
+        input.extend(files.iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        Project::from_file_list(input).unwrap()
+    }
+
+    const SPEC: &str = indoc! {r#"
+    openapi: 3.0.0
+    info:
+      title: Trees
+      version: 1.0.0
+    tags:
+      - name: Trees
+        description: See [the standard](https://example.com/trees)
+    paths:
+      /trees:
+        get:
+          summary: List trees
+          description: Like [this one](https://example.com/oak)
+          tags: [Trees]
+          responses:
+            "200":
+              description: OK
+    "#};
+
+    #[test]
+    fn gathers_links_from_pages_navigation_and_specs() {
+        let project = project(&[
+            (
+                "README.md",
+                "# Home\n\n[Oak](https://example.com/oak) [Guide](/guide.md) [Mail](mailto:a@example.com)",
+            ),
+            (
+                "guide.md",
+                "<Button href=\"https://example.com/signup\">Sign up</Button>\n\n[Oak](https://example.com/oak)",
+            ),
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {r#"
+                - heading: Links
+                  items:
+                    - label: Status
+                      external: https://status.example.com
+                "#},
+            ),
+            ("openapi.yaml", SPEC),
+        ]);
+
+        let links = project.external_links();
+
+        assert_eq!(
+            links.iter().map(|l| l.url.as_str()).collect::<Vec<_>>(),
+            vec![
+                "https://example.com/oak",
+                "https://example.com/signup",
+                "https://example.com/trees",
+                "https://status.example.com",
+            ]
+        );
+
+        let oak = &links[0];
+        assert_eq!(
+            oak.sources
+                .iter()
+                .map(|s| s.file.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("guide.md"),
+                PathBuf::from("openapi.yaml"),
+            ]
+        );
+
+        let readme = oak.sources[0].position.as_ref().unwrap();
+        assert_eq!((readme.start.row, readme.start.col), (3, 7));
+
+        let status = links[3].sources[0].position.as_ref().unwrap();
+        assert_eq!(
+            links[3].sources[0].file,
+            PathBuf::from(NAVIGATION_FILE_NAME)
+        );
+        assert_eq!(status.start.row, 4);
+    }
+
+    #[test]
+    fn lists_each_link_once() {
+        let project = project(&[
+            (
+                "README.md",
+                "[One](https://example.com)\n\n[Two](https://example.com)",
+            ),
+            (NAVIGATION_FILE_NAME, "---"),
+            ("openapi.yaml", SPEC),
+        ]);
+
+        let links = project.external_links();
+        let example = links
+            .iter()
+            .find(|l| l.url == "https://example.com")
+            .unwrap();
+
+        assert_eq!(example.sources.len(), 2);
+        assert_eq!(
+            links
+                .iter()
+                .filter(|l| l.url == "https://example.com")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn leaves_out_drafts() {
+        let project = project(&[
+            ("README.md", "# Home"),
+            (
+                "draft.md",
+                "---\ndraft: true\n---\n[Secret](https://example.com)",
+            ),
+            (NAVIGATION_FILE_NAME, "---"),
+            ("openapi.yaml", SPEC),
+        ]);
+
+        assert!(!project
+            .external_links()
+            .iter()
+            .any(|l| l.url == "https://example.com"));
+    }
+}
@@ -0,0 +1,477 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+    markdown::parser::to_final_link, page_kind::PageKind, render_context::RenderContext,
+    settings::FeedSource, tabs::path_in_scope, DescriptionExtractor, Error, MarkdownPage,
+    PageHandle, Project, RenderOptions,
+};
+
+pub const FEED_FILE_NAME: &str = "feed.xml";
+
+/// The latest pages in a folder, like a changelog, or the latest `changelog`
+/// entries of every page, as configured in the `feeds` setting. Turning it into XML is left to whoever publishes it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Feed {
+    pub title: String,
+    /// Path of the folder, e.g. `/changelog`
+    pub path: String,
+    /// Absolute URL of the folder
+    pub url: String,
+    /// Absolute URL of the feed itself
+    pub feed_url: String,
+    /// Date of the latest item
+    pub updated: Option<String>,
+    /// Newest first
+    pub items: Vec<FeedItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedItem {
+    /// Stays the same for as long as the item is in the feed
+    pub id: String,
+    pub title: String,
+    /// Absolute URL of the page
+    pub url: String,
+    /// From the page's frontmatter, e.g. `2024-05-01`
+    pub date: String,
+    pub description: String,
+}
+
+impl Feed {
+    /// Where the feed is written in a static build, e.g. `changelog/feed.xml`.
+    pub fn out_path(&self) -> PathBuf {
+        PathBuf::from(self.path.trim_start_matches('/')).join(FEED_FILE_NAME)
+    }
+}
+
+pub(crate) fn compute(project: &Project, path: &str, opts: Option<&RenderOptions>) -> Option<Feed> {
+    let path = format!("/{}", path.trim_matches('/'));
+    let settings = project
+        .settings
+        .feeds()
+        .iter()
+        .find(|feed| feed.uri_path() == path)?;
+
+    let base_url = project
+        .settings
+        .base_url()
+        .unwrap_or_default()
+        .trim_end_matches('/');
+
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+    ctx.with_maybe_options(opts);
+
+    let absolute = |uri_path: &str| format!("{}{}", base_url, to_final_link(uri_path, &ctx));
+
+    let items = match settings.source {
+        FeedSource::Pages => page_items(project, &path, settings.limit, opts, &absolute),
+        FeedSource::Changelog => changelog_items(project, settings.limit, opts, &absolute),
+    };
+
+    Some(Feed {
+        title: settings.title.clone(),
+        url: absolute(&path),
+        feed_url: format!(
+            "{}{}/{}",
+            base_url,
+            path.trim_end_matches('/'),
+            FEED_FILE_NAME
+        ),
+        updated: items.first().map(|item| item.date.clone()),
+        path,
+        items,
+    })
+}
+
+fn page_items(
+    project: &Project,
+    path: &str,
+    limit: usize,
+    opts: Option<&RenderOptions>,
+    absolute: &dyn Fn(&str) -> String,
+) -> Vec<FeedItem> {
+    // Pages with invalid dates are reported by `verify`
+    let mut dated = markdown_pages_under(project.published_pages(opts), path)
+        .into_iter()
+        .filter_map(|(page, markdown)| {
+            markdown
+                .date()
+                .filter(|date| is_valid_date(date))
+                .map(|date| (page, date))
+        })
+        .collect::<Vec<_>>();
+
+    dated.sort_by(|(a, a_date), (b, b_date)| {
+        b_date
+            .cmp(a_date)
+            .then_with(|| a.uri_path().cmp(b.uri_path()))
+    });
+    dated.truncate(limit);
+
+    dated
+        .into_iter()
+        .map(|(page, date)| FeedItem {
+            id: absolute(page.uri_path()),
+            title: page.title().unwrap_or_else(|_| page.uri_path().to_owned()),
+            url: absolute(page.uri_path()),
+            date,
+            description: page
+                .description()
+                .ok()
+                .flatten()
+                .or_else(|| {
+                    page.ast(opts)
+                        .ok()
+                        .map(|ast| DescriptionExtractor::extract(&ast))
+                })
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// The `changelog` entries of every page, like on the `whats_new` page. A
+/// page can have several entries on the same day, so they're told apart by
+/// their order in the feed.
+fn changelog_items(
+    project: &Project,
+    limit: usize,
+    opts: Option<&RenderOptions>,
+    absolute: &dyn Fn(&str) -> String,
+) -> Vec<FeedItem> {
+    let since = project
+        .settings
+        .whats_new()
+        .and_then(|w| w.since.as_deref());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    crate::whats_new::entries(project, opts, since, limit)
+        .into_iter()
+        .map(|update| {
+            let url = absolute(&update.uri_path);
+            let id = format!("{}#changelog-{}", url, update.date);
+            let count = seen.entry(id.clone()).or_insert(0);
+            *count += 1;
+
+            FeedItem {
+                id: if *count == 1 {
+                    id
+                } else {
+                    format!("{}-{}", id, count)
+                },
+                title: update.title,
+                url,
+                date: update.date,
+                description: update.note,
+            }
+        })
+        .collect()
+}
+
+/// Checks that every page in a feed has a valid `date` in its frontmatter.
+/// The dates of `changelog` entries are checked by `whats_new::verify`.
+pub(crate) fn verify(project: &Project, opts: Option<&RenderOptions>) -> Vec<Error> {
+    let mut errors = vec![];
+
+    for feed in project.settings.feeds() {
+        if feed.source == FeedSource::Changelog {
+            continue;
+        }
+
+        for (page, markdown) in
+            markdown_pages_under(project.published_pages(opts), &feed.uri_path())
+        {
+            // Invalid frontmatters are reported on their own
+            let Ok(frontmatter) = markdown.frontmatter() else {
+                continue;
+            };
+
+            let (message, description) = match &frontmatter.date {
+                None => (
+                    "Missing date for page in feed",
+                    format!(
+                        "Pages in the feed \"{}\" need a `date` in their frontmatter, like `date: 2024-05-01`.",
+                        feed.title
+                    ),
+                ),
+                Some(date) if !is_valid_date(date) => (
+                    "Invalid date for page in feed",
+                    format!(
+                        "Expected a date like `2024-05-01` for the feed \"{}\".\nFound \"{}\".",
+                        feed.title, date
+                    ),
+                ),
+                Some(_) => continue,
+            };
+
+            errors.push(Error {
+                code: Error::INVALID_FRONTMATTER,
+                message: message.to_owned(),
+                description,
+                file: Some(page.fs_path().to_path_buf()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+
+    errors
+}
+
+/// Markdown pages under the folder, other than the folder's own page.
+/// OpenAPI pages don't have dates, so they are never part of a feed.
+fn markdown_pages_under<'a>(
+    pages: Vec<PageHandle<'a>>,
+    path: &str,
+) -> Vec<(PageHandle<'a>, &'a MarkdownPage)> {
+    pages
+        .into_iter()
+        .filter(|p| p.uri_path() != path && path_in_scope(p.uri_path(), path))
+        .filter_map(|p| match p.page {
+            PageKind::Markdown(markdown) => Some((p, markdown)),
+            PageKind::OpenApi(_) => None,
+        })
+        .collect()
+}
+
+/// Checks that the date is a real day, written like `2024-05-01`.
+pub(crate) fn is_valid_date(date: &str) -> bool {
+    let parts = date.split('-').collect::<Vec<_>>();
+
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+
+    if year.len() != 4
+        || month.len() != 2
+        || day.len() != 2
+        || !parts.iter().all(|p| p.chars().all(|c| c.is_ascii_digit()))
+    {
+        return false;
+    }
+
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    use super::*;
+
+    fn project(settings: &str, pages: &[(&str, &str)]) -> Project {
+        let mut files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Home".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(settings.to_string()),
+            },
+        ];
+
+        files.extend(pages.iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        Project::from_file_list(files).unwrap()
+    }
+
+    const SETTINGS: &str = indoc! {r#"
+    ---
+    title: Something
+    base_url: https://docs.example.com/
+    feeds:
+      - path: /changelog/
+        title: Product changelog
+        limit: 2
+    "#};
+
+    #[test]
+    fn lists_the_latest_pages_newest_first() {
+        let project = project(
+            SETTINGS,
+            &[
+                ("changelog/README.md", "# Changelog"),
+                (
+                    "changelog/v1.md",
+                    "---\ndate: 2024-01-15\n---\n# Version 1\n\nThe first one.",
+                ),
+                (
+                    "changelog/v3.md",
+                    "---\ndate: 2024-03-01\nmeta:\n  description: Third time's the charm\n---\n# Version 3",
+                ),
+                (
+                    "changelog/v2.md",
+                    "---\ndate: 2024-02-10\n---\n# Version 2\n\nThe second one.",
+                ),
+                ("other.md", "---\ndate: 2025-01-01\n---\n# Not in the feed"),
+            ],
+        );
+
+        let feed = project.feed("/changelog", None).unwrap();
+
+        assert_eq!(
+            feed,
+            Feed {
+                title: "Product changelog".to_string(),
+                path: "/changelog".to_string(),
+                url: "https://docs.example.com/changelog".to_string(),
+                feed_url: "https://docs.example.com/changelog/feed.xml".to_string(),
+                updated: Some("2024-03-01".to_string()),
+                items: vec![
+                    FeedItem {
+                        id: "https://docs.example.com/changelog/v3".to_string(),
+                        title: "Version 3".to_string(),
+                        url: "https://docs.example.com/changelog/v3".to_string(),
+                        date: "2024-03-01".to_string(),
+                        description: "Third time's the charm".to_string(),
+                    },
+                    FeedItem {
+                        id: "https://docs.example.com/changelog/v2".to_string(),
+                        title: "Version 2".to_string(),
+                        url: "https://docs.example.com/changelog/v2".to_string(),
+                        date: "2024-02-10".to_string(),
+                        description: "The second one.".to_string(),
+                    },
+                ],
+            }
+        );
+
+        assert_eq!(feed.out_path(), PathBuf::from("changelog/feed.xml"));
+    }
+
+    #[test]
+    fn only_configured_paths_have_feeds() {
+        let project = project(SETTINGS, &[("changelog/README.md", "# Changelog")]);
+
+        assert!(project.feed("/changelog/", None).is_some());
+        assert!(project.feed("/other", None).is_none());
+        assert_eq!(project.feeds(None).len(), 1);
+    }
+
+    #[test]
+    fn leaves_out_drafts() {
+        let project = project(
+            SETTINGS,
+            &[
+                ("changelog/README.md", "# Changelog"),
+                ("changelog/v1.md", "---\ndate: 2024-01-15\n---\n# Version 1"),
+                (
+                    "changelog/v2.md",
+                    "---\ndate: 2024-02-10\ndraft: true\n---\n# Version 2",
+                ),
+            ],
+        );
+
+        let feed = project.feed("/changelog", None).unwrap();
+
+        assert_eq!(
+            feed.items
+                .iter()
+                .map(|i| i.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Version 1"]
+        );
+    }
+
+    #[test]
+    fn verifies_pages_in_feeds_have_dates() {
+        let project = project(
+            SETTINGS,
+            &[
+                ("changelog/README.md", "# Changelog"),
+                ("changelog/v1.md", "# Version 1"),
+                ("changelog/v2.md", "---\ndate: 2024-02-30\n---\n# Version 2"),
+                ("changelog/v3.md", "---\ndate: 2024-03-01\n---\n# Version 3"),
+            ],
+        );
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        let missing = errors
+            .iter()
+            .find(|e| e.message == "Missing date for page in feed")
+            .expect("No error for missing date");
+        assert_eq!(missing.code, Error::INVALID_FRONTMATTER);
+        assert_eq!(missing.file, Some(PathBuf::from("changelog/v1.md")));
+
+        let invalid = errors
+            .iter()
+            .find(|e| e.message == "Invalid date for page in feed")
+            .expect("No error for invalid date");
+        assert_eq!(
+            invalid.description,
+            "Expected a date like `2024-05-01` for the feed \"Product changelog\".\nFound \"2024-02-30\"."
+        );
+        assert_eq!(invalid.file, Some(PathBuf::from("changelog/v2.md")));
+
+        assert_eq!(
+            errors
+                .iter()
+                .filter(|e| e.message.ends_with("for page in feed"))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn verifies_feeds_have_a_base_url() {
+        let project = project(
+            indoc! {r#"
+            ---
+            title: Something
+            feeds:
+              - path: /changelog
+                title: Product changelog
+            "#},
+            &[("changelog/README.md", "# Changelog")],
+        );
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.message == "Feeds require a `base_url`"),
+            "No error for missing base_url"
+        );
+    }
+
+    #[test]
+    fn validates_dates() {
+        assert!(is_valid_date("2024-05-01"));
+        assert!(is_valid_date("2024-02-29"));
+        assert!(!is_valid_date("2023-02-29"));
+        assert!(!is_valid_date("2024-13-01"));
+        assert!(!is_valid_date("2024-5-1"));
+        assert!(!is_valid_date("May 1st"));
+        assert!(!is_valid_date("2024-05-01T10:00:00Z"));
+    }
+}
@@ -0,0 +1,203 @@
+//! Sample projects for testing renderers and themes against, without setting
+//! up files on disk. Enabled with the `fixtures` feature.
+use crate::{Boilerplate, InputContent, InputFile, Project};
+
+/// A project that uses every component and Markdown construct, along with
+/// custom components, nested navigation, and an OpenAPI reference. The same
+/// project `docapella init --template kitchen-sink` creates.
+///
+/// Files under `_assets` are given as binary files.
+pub fn kitchen_sink_project() -> Project {
+    let files = Project::boilerplate_file_list_for(Boilerplate::KitchenSink)
+        .into_iter()
+        .map(|(path, content)| {
+            let content = if path.starts_with("_assets") {
+                InputContent::Binary {
+                    signature: String::new(),
+                    size_bytes: content.len(),
+                }
+            } else {
+                InputContent::Text(String::from_utf8(content).expect("fixture files are UTF-8"))
+            };
+
+            InputFile { path, content }
+        })
+        .collect();
+
+    Project::from_file_list(files).expect("the kitchen sink project is valid")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::content_ast::{self, Node, NodeKind};
+    use crate::frontmatter;
+    use crate::render_context::RenderContext;
+    use std::collections::HashSet;
+
+    /// Only produced when parsing GFM, which pages and OpenAPI descriptions
+    /// never use
+    const GFM_ONLY: &[&str] = &["Delete", "HtmlTag"];
+
+    /// Every variant of `NodeKind`. New variants have to be added here as well
+    /// as to `variant_name`.
+    const VARIANTS: &[&str] = &[
+        "Root",
+        "BlockQuote",
+        "ThematicBreak",
+        "Break",
+        "Strong",
+        "Emphasis",
+        "Delete",
+        "Math",
+        "InlineMath",
+        "Link",
+        "LinkReference",
+        "Image",
+        "ImageReference",
+        "Definition",
+        "List",
+        "ListItem",
+        "FootnoteReference",
+        "FootnoteDefinition",
+        "DefinitionList",
+        "DefinitionTerm",
+        "DefinitionDescription",
+        "Code",
+        "InlineCode",
+        "Text",
+        "Heading",
+        "Paragraph",
+        "Component",
+        "HtmlBlock",
+        "HtmlTag",
+        "Expression",
+        "ExpressionBlock",
+        "TableCell",
+        "TableRow",
+        "Table",
+        "Conditional",
+        "Noop",
+        "Tabs",
+        "Tab",
+        "Steps",
+        "Step",
+        "CodeSelect",
+        "Flex",
+        "Box",
+        "Callout",
+        "Grid",
+        "Download",
+        "Embed",
+        "Slot",
+        "Fill",
+        "OpenAPISchema",
+    ];
+
+    fn variant_name(kind: &NodeKind) -> &'static str {
+        use NodeKind::*;
+
+        // No catch all, so that new variants have to be added
+        match kind {
+            Root => "Root",
+            BlockQuote => "BlockQuote",
+            ThematicBreak => "ThematicBreak",
+            Break => "Break",
+            Strong => "Strong",
+            Emphasis => "Emphasis",
+            Delete => "Delete",
+            Math { .. } => "Math",
+            InlineMath { .. } => "InlineMath",
+            Link { .. } => "Link",
+            LinkReference { .. } => "LinkReference",
+            Image { .. } => "Image",
+            ImageReference { .. } => "ImageReference",
+            Definition { .. } => "Definition",
+            List { .. } => "List",
+            ListItem { .. } => "ListItem",
+            FootnoteReference { .. } => "FootnoteReference",
+            FootnoteDefinition { .. } => "FootnoteDefinition",
+            DefinitionList => "DefinitionList",
+            DefinitionTerm => "DefinitionTerm",
+            DefinitionDescription => "DefinitionDescription",
+            Code { .. } => "Code",
+            InlineCode { .. } => "InlineCode",
+            Text { .. } => "Text",
+            Heading { .. } => "Heading",
+            Paragraph => "Paragraph",
+            Component { .. } => "Component",
+            HtmlBlock { .. } => "HtmlBlock",
+            HtmlTag { .. } => "HtmlTag",
+            Expression { .. } => "Expression",
+            ExpressionBlock { .. } => "ExpressionBlock",
+            TableCell => "TableCell",
+            TableRow => "TableRow",
+            Table { .. } => "Table",
+            Conditional(_) => "Conditional",
+            Noop => "Noop",
+            Tabs { .. } => "Tabs",
+            Tab { .. } => "Tab",
+            Steps { .. } => "Steps",
+            Step { .. } => "Step",
+            CodeSelect { .. } => "CodeSelect",
+            Flex { .. } => "Flex",
+            Box { .. } => "Box",
+            Callout { .. } => "Callout",
+            Grid { .. } => "Grid",
+            Download { .. } => "Download",
+            Embed { .. } => "Embed",
+            Slot { .. } => "Slot",
+            Fill { .. } => "Fill",
+            OpenAPISchema { .. } => "OpenAPISchema",
+        }
+    }
+
+    fn collect(node: &Node, found: &mut HashSet<&'static str>) {
+        found.insert(variant_name(&node.kind));
+
+        // The branches of a conditional aren't its children
+        if let NodeKind::Conditional(cond) = &node.kind {
+            collect(&cond.true_branch, found);
+            collect(&cond.false_branch, found);
+        }
+
+        for child in &node.children {
+            collect(child, found);
+        }
+    }
+
+    #[test]
+    fn kitchen_sink_verifies() {
+        let result = kitchen_sink_project().verify(None, None);
+
+        assert!(result.is_ok(), "{:#?}", result);
+    }
+
+    #[test]
+    fn kitchen_sink_uses_every_content_node_kind() {
+        let ctx = RenderContext::new();
+        let mut found = HashSet::new();
+
+        for (path, content) in Project::boilerplate_file_list_for(Boilerplate::KitchenSink) {
+            if path.extension().is_some_and(|ext| ext == "md") {
+                let content = String::from_utf8(content).unwrap();
+                let root = content_ast::build_mdx(frontmatter::without(&content), &ctx)
+                    .unwrap_or_else(|e| panic!("{}: {:#?}", path.display(), e));
+
+                collect(&root, &mut found);
+            }
+        }
+
+        let missing = VARIANTS
+            .iter()
+            .filter(|v| !found.contains(*v) && !GFM_ONLY.contains(v))
+            .collect::<Vec<_>>();
+
+        assert!(
+            missing.is_empty(),
+            "Add these to the kitchen sink project: {:?}",
+            missing
+        );
+        assert_eq!(found.len() + GFM_ONLY.len(), VARIANTS.len());
+    }
+}
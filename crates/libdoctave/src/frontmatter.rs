@@ -1,6 +1,9 @@
+use serde::{Deserialize, Deserializer};
+
 use crate::{
     markdown::error_renderer::{self, Highlight, Location},
     render_context::RenderContext,
+    Point, Position,
 };
 
 fn default_as_true() -> bool {
@@ -26,6 +29,49 @@ pub struct Frontmatter {
     pub breadcrumbs: bool,
     #[serde(default)]
     pub search: Search,
+    /// Multiplies the weight of this page's search results. E.g. `2` ranks
+    /// matches on this page twice as high.
+    pub search_boost: Option<f32>,
+    /// Drafts are only published when `RenderOptions::publish_drafts` is set.
+    #[serde(default)]
+    pub draft: bool,
+    /// Overrides when the page was last updated, e.g. `2024-05-01`.
+    pub last_updated: Option<String>,
+    /// Overrides the people who have edited the page.
+    pub contributors: Option<Vec<String>>,
+    /// When the page was published, e.g. `2024-05-01`. Required for pages
+    /// that are part of a feed.
+    pub date: Option<String>,
+    /// The layout the page is rendered with, from `templates.allowed` in
+    /// the settings.
+    pub template: Option<String>,
+    /// Style sheets included on this page only, e.g. `_assets/css/landing.css`.
+    #[serde(default)]
+    pub css: Vec<String>,
+    /// Limits the rules in `css` to this page's content.
+    #[serde(default)]
+    pub css_scope: bool,
+    /// Where the original of the page is, for pages mirrored from elsewhere.
+    pub canonical_url: Option<String>,
+    /// Asks search engines not to index the page. Also leaves it out of the
+    /// site's search, unless `search.include_noindex` is set.
+    #[serde(default)]
+    pub noindex: bool,
+    /// The page is meant to be unlisted, like legal texts, so it isn't
+    /// reported when it's not in any navigation or linked from other pages.
+    #[serde(default)]
+    pub orphan_ok: bool,
+    /// The tutorial the page is a step of, by the path of its folder, e.g.
+    /// `tutorials/getting-started`. Checked against the tutorial's steps.
+    pub tutorial: Option<String>,
+    /// Meaningful updates to the page, listed on the `whats_new` page.
+    #[serde(default)]
+    pub changelog: Vec<ChangelogEntry>,
+    /// Components that can't be used on this page, by name, e.g.
+    /// `Component.Fancy` or `Card`. Custom components can leave out the
+    /// `Component.` prefix.
+    #[serde(default)]
+    pub components_disabled: Vec<String>,
 }
 
 impl Default for Frontmatter {
@@ -40,10 +86,34 @@ impl Default for Frontmatter {
             breadcrumbs: true,
             page_width: PageWidth::default(),
             search: Search::default(),
+            search_boost: None,
+            draft: false,
+            last_updated: None,
+            contributors: None,
+            date: None,
+            template: None,
+            css: vec![],
+            css_scope: false,
+            canonical_url: None,
+            noindex: false,
+            orphan_ok: false,
+            tutorial: None,
+            changelog: vec![],
+            components_disabled: vec![],
         }
     }
 }
 
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ChangelogEntry {
+    /// When the page was updated, e.g. `2024-05-01`
+    pub date: String,
+    pub note: String,
+    /// Groups related updates, e.g. `api`
+    pub tag: Option<String>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Default, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum PageWidth {
@@ -57,21 +127,112 @@ pub struct FrontmatterMeta {
     pub description: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug, Default, PartialEq)]
+#[derive(Serialize, Debug, Default, PartialEq)]
 pub struct Search {
     // Defaults to false
-    #[serde(default)]
     pub hidden: bool,
 }
 
+/// Either `search: false`, or `search: { hidden: true }`
+impl<'de> Deserialize<'de> for Search {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SearchDescription {
+            Enabled(bool),
+            Options {
+                #[serde(default)]
+                hidden: bool,
+            },
+        }
+
+        Ok(match SearchDescription::deserialize(deserializer)? {
+            SearchDescription::Enabled(enabled) => Search { hidden: !enabled },
+            SearchDescription::Options { hidden } => Search { hidden },
+        })
+    }
+}
+
+/// How many lines after the opening `---` the closing delimiter is looked
+/// for. A page that starts with a `---` thematic break would otherwise have
+/// everything up to its next `---` taken as frontmatter.
+pub const MAX_FRONTMATTER_LINES: usize = 500;
+
+/// The byte order mark some editors on Windows start UTF-8 files with.
+const BOM: char = '\u{feff}';
+
+/// Where the frontmatter is in the input.
+#[derive(Debug, Clone, PartialEq)]
+enum Located {
+    /// The input doesn't start with `---`. The content starts at `start`,
+    /// after the byte order mark if there is one.
+    Missing { start: usize },
+    /// The input starts with `---`, but there's no closing delimiter within
+    /// `MAX_FRONTMATTER_LINES`, so it's all content.
+    Unclosed { start: usize },
+    /// The YAML is between the end of the opening `---` and the start of
+    /// the closing `---` or `...`. It starts with the line break of the
+    /// opening delimiter, so that its line numbers match the file's.
+    Closed { yaml: (usize, usize), end: usize },
+}
+
+fn locate(input: &str) -> Located {
+    let start = if input.starts_with(BOM) {
+        BOM.len_utf8()
+    } else {
+        0
+    };
+
+    let mut lines = input[start..].split_inclusive('\n');
+    let mut offset = start;
+
+    match lines.next() {
+        Some(line) if is_delimiter(line, &["---"]) => offset += line.len(),
+        _ => return Located::Missing { start },
+    }
+
+    let yaml_start = offset - line_break_len(&input[..offset]);
+
+    for line in lines.take(MAX_FRONTMATTER_LINES) {
+        if is_delimiter(line, &["---", "..."]) {
+            return Located::Closed {
+                yaml: (yaml_start, offset),
+                end: offset + line.len(),
+            };
+        }
+
+        offset += line.len();
+    }
+
+    Located::Unclosed { start }
+}
+
+/// Delimiters are on their own line, optionally followed by whitespace.
+fn is_delimiter(line: &str, delimiters: &[&str]) -> bool {
+    let line = line.trim_end();
+
+    delimiters.iter().any(|d| line == *d)
+}
+
+fn line_break_len(line: &str) -> usize {
+    if line.ends_with("\r\n") {
+        2
+    } else if line.ends_with('\n') {
+        1
+    } else {
+        0
+    }
+}
+
 pub fn parse(input: &str) -> std::result::Result<Frontmatter, String> {
-    let pos = end_pos(input);
+    let (yaml, _) = split(input);
 
-    let yaml = input[0..pos]
-        .trim_end()
-        .trim_end_matches('-')
-        .trim_start()
-        .trim_start_matches('-');
+    if yaml.trim().is_empty() {
+        return Ok(Frontmatter::default());
+    }
 
     serde_yaml::from_str(yaml).map_err(|e| {
         let location = if let Some(loc) = e.location() {
@@ -89,45 +250,74 @@ pub fn parse(input: &str) -> std::result::Result<Frontmatter, String> {
     })
 }
 
-/// Splits input into its frontmatter (excluding ---) and content
+/// Whether the input starts with a `---` that isn't closed within
+/// `MAX_FRONTMATTER_LINES`, in which case it's treated as content.
+pub(crate) fn is_unclosed(input: &str) -> bool {
+    matches!(locate(input), Located::Unclosed { .. })
+}
+
+/// Where the value of a top level key is in the frontmatter of the input,
+/// e.g. the `https://...` in `canonical_url: https://...`.
+pub(crate) fn value_position(input: &str, key: &str) -> Option<Position> {
+    let end = end_pos(input);
+    let mut byte_offset = 0;
+
+    for (index, line) in input[..end].split_inclusive('\n').enumerate() {
+        if let Some(rest) = line
+            .strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(':'))
+        {
+            let value = rest.trim();
+            let col = line.len() - rest.trim_start().len() + 1;
+            let start = Point {
+                row: index + 1,
+                col,
+                byte_offset: byte_offset + col - 1,
+            };
+            let end = Point {
+                col: col + value.len(),
+                byte_offset: start.byte_offset + value.len(),
+                ..start.clone()
+            };
+
+            return Some(Position { start, end });
+        }
+
+        byte_offset += line.len();
+    }
+
+    None
+}
+
+/// Splits input into its frontmatter (excluding the delimiters) and content.
+/// Line numbers in the frontmatter match the ones in the input.
 pub fn split(input: &str) -> (&str, &str) {
-    let pos = end_pos(input);
-    let yaml = input[0..pos]
-        .trim_end()
-        .trim_end_matches('-')
-        .trim_start()
-        .trim_start_matches('-');
-    let content = &input[pos..];
-
-    (yaml, content)
+    match locate(input) {
+        Located::Closed {
+            yaml: (start, end),
+            end: content,
+        } => (&input[start..end], &input[content..]),
+        Located::Missing { start } | Located::Unclosed { start } => ("", &input[start..]),
+    }
 }
 
 pub fn without(input: &str) -> &str {
     &input[end_pos(input)..]
 }
 
+/// Byte offset of the content after the frontmatter.
 pub fn end_pos(input: &str) -> usize {
-    if let Some(after_starter_mark) = input.strip_prefix("---\n") {
-        let end_mark = after_starter_mark.find("---\n");
-
-        if let Some(e) = end_mark {
-            e + 8
-        } else {
-            0
-        }
-    } else if let Some(after_starter_mark) = input.strip_prefix("---\r\n") {
-        let end_mark = after_starter_mark.find("---\r\n");
-
-        if let Some(e) = end_mark {
-            e + 10
-        } else {
-            0
-        }
-    } else {
-        0
+    match locate(input) {
+        Located::Closed { end, .. } => end,
+        Located::Missing { start } | Located::Unclosed { start } => start,
     }
 }
 
+/// Number of lines before the content after the frontmatter.
+pub(crate) fn end_line(input: &str) -> usize {
+    input[..end_pos(input)].matches('\n').count()
+}
+
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_str_eq;
@@ -274,4 +464,131 @@ mod test {
 
         assert_eq!(without_frontmatter, "\r\n# More content\r\n");
     }
+
+    #[test]
+    fn finds_the_position_of_a_value() {
+        let input = indoc! {"
+            ---
+            title: Mirrored
+            canonical_url: /guides/setup
+            ---
+
+            canonical_url: not frontmatter
+        "};
+
+        let position = value_position(input, "canonical_url").unwrap();
+
+        assert_eq!(position.start.row, 3);
+        assert_eq!(position.start.col, 16);
+        assert_eq!(
+            &input[position.start.byte_offset..position.end.byte_offset],
+            "/guides/setup"
+        );
+        assert_eq!(value_position(input, "description"), None);
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let input = "\u{feff}---\ntitle: Runbooks\n---\n\n# Runbooks\n";
+
+        assert_eq!(parse(input).unwrap().title, Some("Runbooks".to_string()));
+        assert_eq!(without(input), "\n# Runbooks\n");
+        assert_eq!(end_line(input), 3);
+
+        // Without frontmatter, only the byte order mark is left out
+        let input = "\u{feff}# Runbooks\n";
+
+        assert_eq!(without(input), "# Runbooks\n");
+        assert_eq!(end_line(input), 0);
+    }
+
+    #[test]
+    fn byte_order_mark_and_windows_line_endings() {
+        let input = "\u{feff}---\r\ntitle: Runbooks\r\n---\r\n\r\n# More content\r\n";
+
+        assert_eq!(parse(input).unwrap().title, Some("Runbooks".to_string()));
+        assert_eq!(without(input), "\r\n# More content\r\n");
+    }
+
+    #[test]
+    fn dots_close_the_frontmatter() {
+        let input = indoc! {"
+            ---
+            title: Runbooks
+            ...
+
+            # Runbooks
+        "};
+
+        assert_eq!(parse(input).unwrap().title, Some("Runbooks".to_string()));
+        assert_eq!(without(input), "\n# Runbooks\n");
+    }
+
+    #[test]
+    fn delimiters_are_whole_lines() {
+        // A page starting with a thematic break, and later a longer one
+        let input = indoc! {"
+            ---
+            # Runbooks
+
+            -----
+
+            title: Not frontmatter ---
+        "};
+
+        assert_eq!(parse(input).unwrap(), Frontmatter::default());
+        assert_eq!(without(input), input);
+        assert!(is_unclosed(input));
+    }
+
+    #[test]
+    fn closing_delimiter_must_be_near_the_start() {
+        let mut input = String::from("---\n");
+        for _ in 0..MAX_FRONTMATTER_LINES {
+            input.push_str("Some text\n\n");
+        }
+        input.push_str("---\n");
+
+        assert_eq!(without(&input), input);
+        assert!(is_unclosed(&input));
+    }
+
+    #[test]
+    fn empty_frontmatter_is_not_unclosed() {
+        let input = "---\r\n---\r\n# Hi\r\n";
+
+        assert_eq!(parse(input).unwrap(), Frontmatter::default());
+        assert_eq!(without(input), "# Hi\r\n");
+        assert!(!is_unclosed(input));
+        assert!(!is_unclosed("# Hi"));
+    }
+
+    #[test]
+    fn yaml_errors_have_the_line_numbers_of_the_file() {
+        let input = "\u{feff}---\r\ntitle: Runbooks\r\n:::blarg: @@!~\r\n---\r\n";
+
+        assert_str_eq!(
+            parse(input).unwrap_err(),
+            indoc! { r#"
+            Invalid frontmatter
+
+                2 │ title: Runbooks
+                3 │ :::blarg: @@!~
+                              ▲
+                              └─ found character that cannot start any token at line 3 column 11, while scanning for the next token
+
+            "# }
+        );
+    }
+
+    #[test]
+    fn lists_are_not_mistaken_for_delimiters() {
+        let input = indoc! {"
+            ---
+            - title
+            ---
+        "};
+
+        assert_eq!(split(input).0, "\n- title\n");
+    }
 }
@@ -0,0 +1,549 @@
+//! A summary of how healthy the documentation is, with a score from 0 to 100
+//! that can be tracked over time.
+//!
+//! The report is built from an [`Analysis`], the errors and warnings that
+//! verifying the project found, so checks aren't run twice when the project
+//! was just verified. The parts of the score are weighed with `health.weights`
+//! in the settings.
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::analysis::Analysis;
+use crate::page_kind::Ast;
+use crate::parallel::*;
+use crate::renderable_ast::ProseStatistics;
+use crate::settings::{HealthWeights, LintLevel};
+use crate::{Error, Project, RenderOptions};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    /// Weighted average of the `parts`, from 0 to 100
+    pub score: u32,
+    /// Published pages, including the ones generated from OpenAPI specs
+    pub page_count: usize,
+    pub errors: IssueCounts,
+    pub warnings: IssueCounts,
+    /// `None` when `lints.orphan_pages` is off
+    pub orphan_pages: Option<usize>,
+    /// Markdown pages with a description, which search results show
+    pub search_coverage: Coverage,
+    pub open_api_coverage: Vec<OpenApiCoverage>,
+    /// `None` when every accessibility lint is off
+    pub accessibility: Option<LintCounts>,
+    /// Averages over the published Markdown pages
+    pub prose: ProseAverages,
+    /// Counts for each owner, if owners are set
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub owners: BTreeMap<String, OwnerCounts>,
+    /// The parts of the score, leaving out the ones without anything to
+    /// measure, like OpenAPI coverage in a project without specs
+    pub parts: Vec<ScorePart>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IssueCounts {
+    pub total: usize,
+    /// Counts by the kind of check that found them, e.g. `links`
+    pub by_category: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintCounts {
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OwnerCounts {
+    pub pages: usize,
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Coverage {
+    pub covered: usize,
+    pub total: usize,
+    /// `None` if there's nothing to cover
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpenApiCoverage {
+    pub spec_file: PathBuf,
+    /// Operations and webhooks with a description
+    pub operations: Coverage,
+    /// Parameters with a description
+    pub parameters: Coverage,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProseAverages {
+    pub headings: f64,
+    pub paragraphs: f64,
+    pub words: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScorePart {
+    /// The key of the part under `health.weights`
+    pub name: &'static str,
+    /// From 0 to 100
+    pub score: f64,
+    pub weight: u32,
+}
+
+impl HealthReport {
+    pub(crate) fn gather(
+        project: &Project,
+        analysis: &Analysis,
+        opts: Option<&RenderOptions>,
+    ) -> Self {
+        let pages = project.published_pages(opts);
+        let page_count = pages.len();
+
+        // Pages that fail to render are reported by `verify`
+        let markdown_pages = pages
+            .par_iter()
+            .filter(|page| page.is_markdown())
+            .map(|page| {
+                let described = page
+                    .description()
+                    .ok()
+                    .flatten()
+                    .is_some_and(|d| !d.trim().is_empty());
+                let prose = match page.ast(opts) {
+                    Ok(Ast::Markdown(root)) => root.statistics(),
+                    _ => ProseStatistics::default(),
+                };
+
+                (described, prose)
+            })
+            .collect::<Vec<_>>();
+
+        let search_coverage = Coverage::new(
+            markdown_pages
+                .iter()
+                .filter(|(described, _)| *described)
+                .count(),
+            markdown_pages.len(),
+        );
+
+        let lints = project.settings().lints();
+
+        let orphan_pages = (lints.orphan_pages != LintLevel::Off).then(|| {
+            let (errors, warnings) = analysis.count(Error::ORPHAN_PAGE);
+            errors + warnings
+        });
+
+        let accessibility = crate::accessibility::enabled(lints).then(|| {
+            let (errors, warnings) = analysis.count(Error::ACCESSIBILITY);
+            LintCounts { errors, warnings }
+        });
+
+        let mut owners = BTreeMap::<String, OwnerCounts>::new();
+        for owner in pages.iter().filter_map(|page| page.owner()) {
+            owners.entry(owner.to_owned()).or_default().pages += 1;
+        }
+        for owner in analysis.errors.iter().filter_map(|e| e.owner.as_ref()) {
+            owners.entry(owner.clone()).or_default().errors += 1;
+        }
+        for owner in analysis.warnings.iter().filter_map(|e| e.owner.as_ref()) {
+            owners.entry(owner.clone()).or_default().warnings += 1;
+        }
+
+        let mut report = HealthReport {
+            score: 100,
+            page_count,
+            errors: IssueCounts::of(&analysis.errors),
+            warnings: IssueCounts::of(&analysis.warnings),
+            orphan_pages,
+            search_coverage,
+            open_api_coverage: open_api_coverage(project),
+            accessibility,
+            prose: ProseAverages::of(markdown_pages.iter().map(|(_, prose)| prose)),
+            owners,
+            parts: vec![],
+        };
+
+        report.parts = report.score_parts(&project.settings().health().weights);
+        report.score = score(&report.parts);
+
+        report
+    }
+
+    fn score_parts(&self, weights: &HealthWeights) -> Vec<ScorePart> {
+        // Issues are measured against the number of pages, so that a large
+        // project isn't scored down for a handful of them
+        let per_page = |count: usize| {
+            (self.page_count > 0).then(|| 1.0 - (count as f64 / self.page_count as f64).min(1.0))
+        };
+
+        let open_api = self
+            .open_api_coverage
+            .iter()
+            .fold((0, 0), |(covered, total), spec| {
+                (
+                    covered + spec.operations.covered + spec.parameters.covered,
+                    total + spec.operations.total + spec.parameters.total,
+                )
+            });
+
+        let parts = [
+            ("errors", weights.errors, per_page(self.errors.total)),
+            ("warnings", weights.warnings, per_page(self.warnings.total)),
+            (
+                "orphan_pages",
+                weights.orphan_pages,
+                self.orphan_pages.and_then(per_page),
+            ),
+            (
+                "search_coverage",
+                weights.search_coverage,
+                self.search_coverage.fraction(),
+            ),
+            (
+                "open_api_coverage",
+                weights.open_api_coverage,
+                Coverage::new(open_api.0, open_api.1).fraction(),
+            ),
+            (
+                "accessibility",
+                weights.accessibility,
+                self.accessibility
+                    .as_ref()
+                    .and_then(|a| per_page(a.errors + a.warnings)),
+            ),
+        ];
+
+        parts
+            .into_iter()
+            .filter(|(_, weight, _)| *weight > 0)
+            .filter_map(|(name, weight, fraction)| {
+                fraction.map(|fraction| ScorePart {
+                    name,
+                    score: round(fraction * 100.0),
+                    weight,
+                })
+            })
+            .collect()
+    }
+}
+
+fn score(parts: &[ScorePart]) -> u32 {
+    let total_weight = parts.iter().map(|p| p.weight).sum::<u32>();
+
+    if total_weight == 0 {
+        return 100;
+    }
+
+    let weighted = parts.iter().map(|p| p.score * p.weight as f64).sum::<f64>();
+
+    (weighted / total_weight as f64).round() as u32
+}
+
+fn open_api_coverage(project: &Project) -> Vec<OpenApiCoverage> {
+    let documented = |text: &Option<String>| text.as_ref().is_some_and(|t| !t.trim().is_empty());
+
+    project
+        .parsed_open_api_specs()
+        .map(|(spec_file, spec)| {
+            let operations = spec
+                .operations()
+                .into_iter()
+                .chain(spec.webhooks.iter().map(|w| &w.operation))
+                .collect::<Vec<_>>();
+            let parameters = operations
+                .iter()
+                .flat_map(|op| op.parameters.iter())
+                .collect::<Vec<_>>();
+
+            OpenApiCoverage {
+                spec_file: spec_file.to_owned(),
+                operations: Coverage::new(
+                    operations
+                        .iter()
+                        .filter(|op| documented(&op.description))
+                        .count(),
+                    operations.len(),
+                ),
+                parameters: Coverage::new(
+                    parameters
+                        .iter()
+                        .filter(|p| documented(&p.description))
+                        .count(),
+                    parameters.len(),
+                ),
+            }
+        })
+        .collect()
+}
+
+impl IssueCounts {
+    fn of(issues: &[Error]) -> Self {
+        let mut by_category = BTreeMap::new();
+
+        for issue in issues {
+            *by_category
+                .entry(category(issue.code).to_owned())
+                .or_default() += 1;
+        }
+
+        IssueCounts {
+            total: issues.len(),
+            by_category,
+        }
+    }
+}
+
+impl Coverage {
+    fn new(covered: usize, total: usize) -> Self {
+        let mut coverage = Coverage {
+            covered,
+            total,
+            percent: None,
+        };
+        coverage.percent = coverage.fraction().map(|f| round(f * 100.0));
+        coverage
+    }
+
+    fn fraction(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.covered as f64 / self.total as f64)
+    }
+}
+
+impl ProseAverages {
+    fn of<'a>(stats: impl ExactSizeIterator<Item = &'a ProseStatistics>) -> Self {
+        let count = stats.len();
+
+        if count == 0 {
+            return ProseAverages::default();
+        }
+
+        let totals = stats.fold(ProseStatistics::default(), |mut totals, s| {
+            totals.headings += s.headings;
+            totals.paragraphs += s.paragraphs;
+            totals.words += s.words;
+            totals
+        });
+
+        let average = |total: u32| round(total as f64 / count as f64);
+
+        ProseAverages {
+            headings: average(totals.headings),
+            paragraphs: average(totals.paragraphs),
+            words: average(totals.words),
+        }
+    }
+}
+
+/// Rounds to one decimal, to keep the report readable.
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// The kind of check that reports errors with the given code.
+fn category(code: usize) -> &'static str {
+    match code {
+        Error::MISSING_DOCTAVE_YAML | Error::INVALID_DOCTAVE_YAML | Error::EMPTY_PROJECT => {
+            "settings"
+        }
+        Error::INVALID_STRUCTURE_YAML
+        | Error::MISSING_ROOT_README
+        | Error::INVALID_STRUCTURE
+        | Error::DUPLICATE_URI_PATH => "structure",
+        Error::MISSING_NAVIGATION | Error::INVALID_NAVIGATION | Error::NAVIGATION_ERROR => {
+            "navigation"
+        }
+        Error::INVALID_REDIRECT => "redirects",
+        Error::OPENAPI_REFERENCE
+        | Error::INVALID_OPENAPI_SPEC
+        | Error::OPENAPI_TEMPLATE_ERROR
+        | Error::INVALID_OPENAPI_SCHEMA
+        | Error::DEPRECATED_OPERATION_LINK => "openapi",
+        Error::INVALID_FRONTMATTER | Error::MISSING_TITLE | Error::DUPLICATE_TITLE => "frontmatter",
+        Error::BROKEN_INTERNAL_LINK => "links",
+        Error::VALE_ERROR => "vale",
+        Error::INVALID_ICON => "icons",
+        Error::ACCESSIBILITY => "accessibility",
+        Error::ORPHAN_PAGE => "orphans",
+        Error::SECRET => "secrets",
+        Error::PAGE_CSS_LOCATION => "styles",
+        Error::IO_ERROR => "io",
+        _ => "content",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use indoc::indoc;
+
+    fn project(settings: &str, files: Vec<(&str, &str)>) -> Project {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        let mut list = vec![
+            file(
+                NAVIGATION_FILE_NAME,
+                "- heading: Guides\n  items:\n    - href: /guides/install.md\n      label: Install\n",
+            ),
+            file(
+                SETTINGS_FILE_NAME,
+                &format!("---\ntitle: Something\n{}", settings),
+            ),
+        ];
+        list.extend(files.into_iter().map(|(path, content)| file(path, content)));
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    #[test]
+    fn reports_counts_and_coverage() {
+        let spec = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Pets
+          version: 1.0.0
+        paths:
+          /pets:
+            get:
+              summary: List pets
+              description: Lists every pet in the store.
+              parameters:
+                - name: limit
+                  in: query
+                  description: How many pets to list
+                  schema:
+                    type: integer
+                - name: cursor
+                  in: query
+                  schema:
+                    type: string
+              responses:
+                '200':
+                  description: Pets
+            post:
+              summary: Add a pet
+              responses:
+                '200':
+                  description: The pet
+        "#};
+
+        let project = project(
+            "open_api:\n  - spec_file: openapi.yaml\n    uri_prefix: /api\n",
+            vec![
+                (
+                    "README.md",
+                    "---\nmeta:\n  description: Where it all starts\n---\n# Home\n\nSee [install](/guides/install.md).",
+                ),
+                ("guides/install.md", "# Install\n\nOne two three.\n\n## Next\n\nFour."),
+                ("guides/upgrade.md", "# Upgrade\n\n[Broken](/nowhere.md)"),
+                ("openapi.yaml", spec),
+            ],
+        );
+
+        let report = project.health_report(None);
+
+        assert_eq!(report.errors.total, 1, "{:#?}", report.errors);
+        assert_eq!(report.errors.by_category.get("links"), Some(&1));
+        assert_eq!(report.orphan_pages, Some(1));
+        assert_eq!(report.accessibility, None);
+
+        assert_eq!(report.search_coverage.covered, 1);
+        assert_eq!(report.search_coverage.total, 3);
+        assert_eq!(report.search_coverage.percent, Some(33.3));
+
+        assert_eq!(report.open_api_coverage.len(), 1);
+        assert_eq!(report.open_api_coverage[0].operations.covered, 1);
+        assert_eq!(report.open_api_coverage[0].operations.total, 2);
+        assert_eq!(report.open_api_coverage[0].parameters.percent, Some(50.0));
+
+        assert!(report.prose.words > 0.0);
+        assert!(report.owners.is_empty());
+        assert!(report.score < 100, "{:#?}", report.parts);
+    }
+
+    #[test]
+    fn weights_come_from_the_settings() {
+        let project = project(
+            indoc! {"
+            health:
+              weights:
+                errors: 0
+                warnings: 0
+                orphan_pages: 0
+                accessibility: 0
+            "},
+            vec![
+                ("README.md", "# Home\n\n[Broken](/nowhere.md)"),
+                (
+                    "guides/install.md",
+                    "---\nmeta:\n  description: How to install\n---\n# Install",
+                ),
+            ],
+        );
+
+        let report = project.health_report(None);
+
+        // Only search coverage is left, and one of the two pages has a description
+        assert_eq!(
+            report.parts.iter().map(|p| p.name).collect::<Vec<_>>(),
+            vec!["search_coverage"]
+        );
+        assert_eq!(report.score, 50);
+    }
+
+    #[test]
+    fn counts_by_owner() {
+        let project = project(
+            "owners:\n  - path: guides/**\n    owner: docs-team\n",
+            vec![
+                ("README.md", "# Home"),
+                ("guides/install.md", "# Install\n\n[Broken](/nowhere.md)"),
+            ],
+        );
+
+        let report = project.health_report(None);
+
+        assert_eq!(
+            report.owners.get("docs-team"),
+            Some(&OwnerCounts {
+                pages: 1,
+                errors: 1,
+                warnings: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn analysis_is_reused() {
+        let project = project(
+            "",
+            vec![("README.md", "# Home"), ("guides/install.md", "# Install")],
+        );
+
+        let analysis = project.analyze(None, None);
+        assert!(analysis.passed(), "{:#?}", analysis.errors);
+
+        // The report only counts what the analysis found
+        let mut failing = analysis.clone();
+        failing.errors.push(Error {
+            code: Error::BROKEN_INTERNAL_LINK,
+            message: String::from("Broken link"),
+            description: String::new(),
+            file: None,
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        });
+
+        let report = project.health_report_for(&failing, None);
+        assert_eq!(report.errors.total, 1);
+        assert_eq!(report.parts[0].name, "errors");
+        assert_eq!(report.parts[0].score, 50.0);
+    }
+}
@@ -1,7 +1,30 @@
 use include_dir::{include_dir, Dir};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::Error;
 
 static LUCIDE: Dir = include_dir!("./crates/libdoctave/icon_sets/lucide");
 
+/// Projects can add their own icons by placing SVG files in this directory.
+/// They are referenced with the `custom` set, using the file name without
+/// the extension as the icon name.
+pub const CUSTOM_ICONS_DIR: &str = "_assets/icons";
+
+lazy_static! {
+    static ref SCRIPT_ELEMENT: Regex =
+        Regex::new(r"(?is)<(script|foreignObject)\b[^>]*?(/>|>.*?</(script|foreignObject)\s*>)")
+            .unwrap();
+    static ref EVENT_HANDLER_ATTRIBUTE: Regex =
+        Regex::new(r#"(?i)\s+on[a-z]+\s*=\s*("[^"]*"|'[^']*'|[^\s>]+)"#).unwrap();
+    static ref JAVASCRIPT_URL: Regex =
+        Regex::new(r#"(?i)((?:xlink:)?href\s*=\s*["']?)\s*javascript:[^"'\s>]*"#).unwrap();
+    static ref PROLOGUE: Regex =
+        Regex::new(r"(?s)^(\s*(<\?xml.*?\?>|<!--.*?-->|<!DOCTYPE[^>]*>))*\s*").unwrap();
+    static ref SVG_ROOT: Regex = Regex::new(r"(?is)^<svg[\s>].*</svg>$").unwrap();
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 /// NOTE: Remember to update `available_sets()` if you add an icon set here.
 ///
@@ -13,6 +36,8 @@ pub enum Icon {
     Lucide(LucideIcon),
     #[serde(rename = "devicon")]
     Devicon(DeviconIcon),
+    #[serde(rename = "custom")]
+    Custom(CustomIcon),
     /// Since the user can give an incorrect set name,
     /// we have this fallback variant so that we can
     /// give nice error messages to the user.
@@ -32,6 +57,18 @@ pub struct DeviconIcon {
     pub html: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CustomIcon {
+    pub name: String,
+    /// The sanitized SVG markup. Empty until the icon has been resolved
+    /// against the project's icons, or if the icon does not exist.
+    pub html: String,
+    /// The built-in set the icon was requested from, if a custom icon with
+    /// the same name took its place.
+    #[serde(skip)]
+    pub shadowed_set: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct UnknownIcon {
     pub unknown_set_name: String,
@@ -41,7 +78,7 @@ pub struct UnknownIcon {
 
 impl Icon {
     pub fn available_sets() -> &'static [&'static str] {
-        &["lucide", "devicon"][..]
+        &["lucide", "devicon", "custom"][..]
     }
 
     pub fn name(&self) -> &str {
@@ -49,6 +86,7 @@ impl Icon {
         match self {
             Lucide(LucideIcon { name, .. }) => name,
             Devicon(DeviconIcon { name, .. }) => name,
+            Custom(CustomIcon { name, .. }) => name,
             Unknown(UnknownIcon { name, .. }) => name,
         }
     }
@@ -58,6 +96,7 @@ impl Icon {
         match self {
             Lucide { .. } => "lucide",
             Devicon { .. } => "devicon",
+            Custom { .. } => "custom",
             Unknown(UnknownIcon {
                 unknown_set_name, ..
             }) => unknown_set_name.as_str(),
@@ -69,6 +108,7 @@ impl Icon {
         match self {
             Lucide(LucideIcon { html, .. }) => html,
             Devicon(DeviconIcon { html, .. }) => html,
+            Custom(CustomIcon { html, .. }) => html,
             Unknown(UnknownIcon { html, .. }) => html.as_str(),
         }
     }
@@ -103,6 +143,11 @@ impl IconDescription {
                 },
                 name: self.name,
             }),
+            "custom" => Icon::Custom(CustomIcon {
+                name: self.name,
+                html: String::new(),
+                shadowed_set: None,
+            }),
             _other => Icon::Unknown(UnknownIcon {
                 unknown_set_name: self.set,
                 name: self.name,
@@ -112,6 +157,166 @@ impl IconDescription {
     }
 }
 
+/// The SVG icons found under [`CUSTOM_ICONS_DIR`], sanitized when the project
+/// is loaded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct CustomIcons {
+    /// Icon name => sanitized markup, or the reason the file can't be used
+    icons: HashMap<String, CustomIconFile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct CustomIconFile {
+    path: PathBuf,
+    html: Result<String, String>,
+}
+
+impl CustomIcons {
+    pub(crate) fn from_files<'a>(files: impl Iterator<Item = (&'a PathBuf, &'a String)>) -> Self {
+        let icons = files
+            .filter(|(path, _)| {
+                path.parent() == Some(Path::new(CUSTOM_ICONS_DIR))
+                    && path.extension().and_then(|e| e.to_str()) == Some("svg")
+            })
+            .filter_map(|(path, content)| {
+                let name = path.file_stem()?.to_str()?.to_string();
+
+                Some((
+                    name,
+                    CustomIconFile {
+                        path: path.clone(),
+                        html: sanitize_svg(content),
+                    },
+                ))
+            })
+            .collect();
+
+        CustomIcons { icons }
+    }
+
+    /// Fills in the markup for custom icons. Built-in icons that share their
+    /// name with a custom icon are replaced by the custom one.
+    pub(crate) fn resolve(&self, icon: Icon) -> Icon {
+        let (name, shadowed_set) = match &icon {
+            Icon::Custom(custom) => (custom.name.clone(), None),
+            Icon::Lucide(_) | Icon::Devicon(_) if self.icons.contains_key(icon.name()) => {
+                (icon.name().to_string(), Some(icon.set().to_string()))
+            }
+            _ => return icon,
+        };
+
+        let html = self
+            .icons
+            .get(&name)
+            .and_then(|file| file.html.clone().ok())
+            .unwrap_or_default();
+
+        Icon::Custom(CustomIcon {
+            name,
+            html,
+            shadowed_set,
+        })
+    }
+
+    /// Checks that every SVG in the icons directory could be sanitized.
+    pub(crate) fn verify(&self) -> Vec<Error> {
+        let mut errors = self
+            .icons
+            .values()
+            .filter_map(|file| {
+                file.html.as_ref().err().map(|reason| Error {
+                    code: Error::INVALID_ICON,
+                    message: "Invalid custom icon".to_owned(),
+                    description: format!(
+                        "Could not use \"{}\" as an icon: {}",
+                        file.path.display(),
+                        reason
+                    ),
+                    file: Some(file.path.clone()),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                })
+            })
+            .collect::<Vec<_>>();
+
+        errors.sort();
+        errors
+    }
+
+    /// Checks that a referenced custom icon exists. Invalid files are
+    /// reported by [`CustomIcons::verify`].
+    pub(crate) fn verify_icon(&self, icon: &Icon, file: &Path) -> Option<Error> {
+        if !matches!(icon, Icon::Custom(_)) || self.icons.contains_key(icon.name()) {
+            return None;
+        }
+
+        let mut available = self
+            .icons
+            .keys()
+            .map(|k| format!("\"{}\"", k))
+            .collect::<Vec<_>>();
+        available.sort();
+
+        Some(Error {
+            code: Error::INVALID_ICON,
+            message: "Unknown custom icon".to_owned(),
+            description: format!(
+                "Could not find an icon named \"{}\". Custom icons are SVG files in the \"{}\" directory.\nFound following icons: [{}].",
+                icon.name(),
+                CUSTOM_ICONS_DIR,
+                available.join(", ")
+            ),
+            file: Some(file.to_path_buf()),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        })
+    }
+
+    /// Warns about a custom icon being used in place of a built-in one.
+    pub(crate) fn shadowing_warning(icon: &Icon, file: &Path) -> Option<Error> {
+        let Icon::Custom(CustomIcon {
+            name,
+            shadowed_set: Some(set),
+            ..
+        }) = icon
+        else {
+            return None;
+        };
+
+        Some(Error {
+            code: Error::INVALID_ICON,
+            message: "Custom icon overrides a built-in icon".to_owned(),
+            description: format!(
+                "The custom icon \"{}\" is used in place of the \"{}\" icon with the same name. Use `set: custom` to refer to it explicitly, or rename the file to use the built-in icon.",
+                name, set
+            ),
+            file: Some(file.to_path_buf()),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        })
+    }
+}
+
+/// Makes an SVG file safe to inline into a page, by removing anything that
+/// could run scripts. Fails if the file does not have an `<svg>` root.
+pub(crate) fn sanitize_svg(source: &str) -> Result<String, String> {
+    let svg = PROLOGUE.replace(source, "");
+    let svg = svg.trim_end();
+
+    if !SVG_ROOT.is_match(svg) {
+        return Err("Expected the file to have an <svg> root element.".to_owned());
+    }
+
+    let svg = SCRIPT_ELEMENT.replace_all(svg, "");
+    let svg = EVENT_HANDLER_ATTRIBUTE.replace_all(&svg, "");
+    let svg = JAVASCRIPT_URL.replace_all(&svg, "${1}#");
+
+    Ok(svg.into_owned())
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -166,4 +371,68 @@ mod test {
         .resolve()
         .is_valid());
     }
+
+    #[test]
+    fn sanitizes_custom_svgs() {
+        let svg = indoc! {r#"
+            <?xml version="1.0" encoding="UTF-8"?>
+            <!-- Brand icon -->
+            <svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)" viewBox="0 0 24 24">
+              <script type="text/javascript">alert(1)</script>
+              <a href="javascript:alert(1)"><path d="M0 0h24v24H0z" onclick='alert(1)'/></a>
+              <foreignObject><div>Hi</div></foreignObject>
+            </svg>
+        "#};
+
+        let sanitized = sanitize_svg(svg).unwrap();
+
+        assert!(sanitized.starts_with("<svg xmlns"));
+        assert!(!sanitized.contains("script"));
+        assert!(!sanitized.contains("alert"));
+        assert!(!sanitized.contains("foreignObject"));
+        assert!(sanitized.contains(r#"<a href="#">"#));
+        assert!(sanitized.contains(r#"<path d="M0 0h24v24H0z"/>"#));
+    }
+
+    #[test]
+    fn rejects_files_without_an_svg_root() {
+        assert!(sanitize_svg("<html><svg></svg></html>").is_err());
+        assert!(sanitize_svg("not an svg").is_err());
+        assert!(sanitize_svg("<svg><path/>").is_err());
+        assert!(sanitize_svg("<svg></svg>").is_ok());
+    }
+
+    #[test]
+    fn custom_icons_take_precedence_over_built_in_ones() {
+        let files = vec![
+            (
+                PathBuf::from("_assets/icons/book.svg"),
+                "<svg><path/></svg>".to_string(),
+            ),
+            (PathBuf::from("_assets/book.svg"), "<svg></svg>".to_string()),
+        ];
+        let icons = CustomIcons::from_files(files.iter().map(|(p, c)| (p, c)));
+
+        let icon = icons.resolve(
+            IconDescription {
+                set: "lucide".to_string(),
+                name: "book".to_owned(),
+            }
+            .resolve(),
+        );
+
+        assert_eq!(icon.set(), "custom");
+        assert_eq!(icon.html(), "<svg><path/></svg>");
+        assert!(CustomIcons::shadowing_warning(&icon, Path::new("docapella.yaml")).is_some());
+
+        let icon = icons.resolve(
+            IconDescription {
+                set: "lucide".to_string(),
+                name: "box".to_owned(),
+            }
+            .resolve(),
+        );
+
+        assert_eq!(icon.set(), "lucide");
+    }
 }
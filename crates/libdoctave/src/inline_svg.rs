@@ -0,0 +1,107 @@
+use regex::Regex;
+
+use crate::icon::sanitize_svg;
+
+/// SVGs larger than this once sanitized are shown as regular images instead,
+/// so that a single diagram can't balloon the size of every page using it.
+pub const MAX_INLINE_SVG_BYTES: usize = 64 * 1024;
+
+lazy_static! {
+    /// `href`s that point anywhere other than an element in the same SVG
+    static ref EXTERNAL_HREF: Regex =
+        Regex::new(r##"(?i)\s+(?:xlink:)?href\s*=\s*("[^"#][^"]*"|'[^'#][^']*'|[^\s>"'#][^\s>]*)"##)
+            .unwrap();
+}
+
+/// Makes an SVG file safe to embed into a page. On top of what custom icons
+/// go through, links out of the SVG are removed, keeping references to its
+/// own elements like `href="#arrow"`. The `viewBox` and `<title>` are kept.
+pub(crate) fn sanitize(source: &str) -> Result<String, String> {
+    let svg = sanitize_svg(source)?;
+
+    Ok(EXTERNAL_HREF.replace_all(&svg, "").into_owned())
+}
+
+/// Removes the `inline` flag from an image URL, like
+/// `/_assets/arch.svg?inline`. Returns `None` if the URL doesn't have it.
+pub(crate) fn strip_inline_flag(url: &str) -> Option<String> {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+    let (path, query) = url.split_once('?')?;
+
+    let is_flag = |param: &&str| *param == "inline" || *param == "inline=true";
+    let params = query.split('&').collect::<Vec<_>>();
+
+    if !params.iter().any(is_flag) {
+        return None;
+    }
+
+    let rest = params
+        .into_iter()
+        .filter(|p| !p.is_empty() && !is_flag(p))
+        .collect::<Vec<_>>();
+
+    let mut out = path.to_string();
+    if !rest.is_empty() {
+        out.push('?');
+        out.push_str(&rest.join("&"));
+    }
+    if let Some(fragment) = fragment {
+        out.push('#');
+        out.push_str(fragment);
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn removes_external_links() {
+        let svg = indoc! {r##"
+            <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">
+              <title>Architecture</title>
+              <a href="https://example.com"><use xlink:href="#box"/></a>
+              <image href='https://example.com/tracker.png'/>
+              <use href=other.svg#box />
+            </svg>
+        "##};
+
+        let sanitized = sanitize(svg).unwrap();
+
+        assert!(sanitized.contains(r#"viewBox="0 0 10 10""#));
+        assert!(sanitized.contains("<title>Architecture</title>"));
+        assert!(sanitized.contains(r##"<use xlink:href="#box"/>"##));
+        assert!(!sanitized.contains("example.com"));
+        assert!(!sanitized.contains("other.svg"));
+        assert!(sanitized.contains("<a>"));
+    }
+
+    #[test]
+    fn keeps_the_icon_sanitization() {
+        let sanitized =
+            sanitize(r#"<svg onload="alert(1)"><script>alert(1)</script></svg>"#).unwrap();
+
+        assert_eq!(sanitized, "<svg></svg>");
+        assert!(sanitize("<html></html>").is_err());
+    }
+
+    #[test]
+    fn strips_the_inline_flag() {
+        assert_eq!(
+            strip_inline_flag("/_assets/arch.svg?inline"),
+            Some("/_assets/arch.svg".to_string())
+        );
+        assert_eq!(
+            strip_inline_flag("arch.svg?v=2&inline=true#top"),
+            Some("arch.svg?v=2#top".to_string())
+        );
+        assert_eq!(strip_inline_flag("/_assets/arch.svg"), None);
+        assert_eq!(strip_inline_flag("/_assets/arch.svg?inlined"), None);
+        assert_eq!(strip_inline_flag("/_assets/arch.svg#inline"), None);
+    }
+}
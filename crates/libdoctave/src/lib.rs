@@ -7,45 +7,87 @@ extern crate serde;
 
 pub use serde_json;
 
+mod accessibility;
+mod analysis;
+mod ast_cache;
+pub mod ast_chunks;
 pub mod breadcrumb;
+mod canonical_path;
+pub mod compose;
 pub mod content_api;
 mod description_extractor;
 mod error_options;
+pub mod external_links;
+pub mod feed;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
 mod frontmatter;
+pub mod health;
 pub mod icon;
+mod inline_svg;
 pub mod markdown;
 pub mod markdown_page;
 pub mod navigation;
 pub mod open_api;
 pub mod open_api_page;
+pub mod openapi_outline;
+mod orphans;
+mod owners;
+mod page_css;
+mod page_globals;
 pub mod page_handle;
 mod page_kind;
+pub mod page_links;
+mod parallel;
 pub mod project;
+pub mod project_diff;
+pub mod quick_nav;
 mod render_context;
 mod render_options;
 pub mod renderer;
 mod search_index;
+mod secrets;
 pub mod settings;
+pub mod single_page;
 mod slug;
+mod snapshot;
+pub mod statistics;
 pub mod tabs;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod tutorial;
 mod utils;
 pub mod vale;
+mod variables;
+pub mod whats_new;
+mod yaml;
 
 pub(crate) use markdown_page::MarkdownPage;
 pub(crate) use open_api_page::OpenApiPage;
 
-pub use renderable_ast::{Node as AstNode, ProseStatistics};
+pub use renderable_ast::{FragmentTarget, Node as AstNode, ProseStatistics};
 
 pub use content_api::{ContentApiResponse, ResponseContext};
 pub use description_extractor::DescriptionExtractor;
 
+pub use analysis::Analysis;
+pub use health::HealthReport;
+pub use owners::OWNERS_FILE_NAME;
 pub use page_handle::PageHandle;
 pub use page_kind::Ast;
-pub use project::{InputContent, InputFile, Project};
+#[cfg(feature = "boilerplate")]
+pub use project::Boilerplate;
+pub use project::{FileMetadata, InputContent, InputFile, Project};
+pub use project_diff::ProjectDiff;
+pub use snapshot::ProjectSnapshot;
+pub use statistics::ProjectStatistics;
 
 pub use error_options::ErrorOptions;
-pub use render_options::RenderOptions;
+pub use render_options::{
+    ImageVariant, ImageVariants, RenderOptions, UndefinedVariables, UrlStyle,
+};
 
+pub use markdown::error_renderer::{Excerpt, ExcerptLine};
 pub use shared_ast::{Point, Position};
 
 pub use search_index::SearchIndex;
@@ -68,6 +110,23 @@ pub struct Error {
     pub description: String,
     pub file: Option<PathBuf>,
     pub position: Option<Position>,
+    /// Who owns `file`, from the `owners` in the settings or the
+    /// `.docapella-owners` file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Likely fixes for the error, which editors can offer to apply.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A fix for an [`Error`]: replace the bytes between `start` and `end` in
+/// `file` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, PartialOrd, Ord)]
+pub struct Suggestion {
+    pub file: PathBuf,
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
 }
 
 impl Error {
@@ -80,6 +139,7 @@ impl Error {
     pub const INVALID_NAVIGATION: usize = 31;
     pub const INVALID_STRUCTURE: usize = 32;
     pub const INVALID_REDIRECT: usize = 33;
+    pub const DUPLICATE_URI_PATH: usize = 34;
     pub const IO_ERROR: usize = 40;
     pub const OPENAPI_REFERENCE: usize = 50;
     pub const INVALID_OPENAPI_SPEC: usize = 51;
@@ -95,23 +155,64 @@ impl Error {
     pub const INVALID_STEPS: usize = 160;
     pub const INVALID_OPENAPI_SCHEMA: usize = 170;
     pub const VALE_ERROR: usize = 180;
+    pub const INVALID_ICON: usize = 190;
+    pub const UNSAFE_HTML: usize = 200;
+    pub const INVALID_INLINE_SVG: usize = 210;
+    pub const DEPRECATED_OPERATION_LINK: usize = 220;
+    pub const ACCESSIBILITY: usize = 230;
+    pub const MISSING_TITLE: usize = 240;
+    pub const DUPLICATE_TITLE: usize = 241;
+    pub const PAGE_CSS_LOCATION: usize = 250;
+    pub const ORPHAN_PAGE: usize = 260;
+    pub const INVALID_TUTORIAL: usize = 270;
+    pub const SECRET: usize = 280;
+    pub const OWNERSHIP: usize = 290;
+    pub const RENDER_ERROR: usize = 300;
+
+    /// The lines around the error in `source`, the contents of its file, or
+    /// `None` if the error doesn't have a position in it.
+    ///
+    /// Positions in Markdown files don't count the frontmatter, like the
+    /// excerpts in the descriptions, so it's skipped.
+    pub fn excerpt(&self, source: &str) -> Option<Excerpt> {
+        let position = self.position.as_ref()?;
+
+        let is_markdown = self
+            .file
+            .as_ref()
+            .is_some_and(|f| f.extension().is_some_and(|ext| ext == "md"));
+
+        if is_markdown {
+            error_renderer::excerpt(
+                frontmatter::without(source),
+                position,
+                frontmatter::end_line(source),
+            )
+        } else {
+            error_renderer::excerpt(source, position, 0)
+        }
+    }
 
     fn in_file(&mut self, path: &Path) {
         self.file = Some(path.to_owned());
     }
 
-    fn from_serde_yaml(
-        serde_error: serde_yaml::Error,
+    fn from_yaml(
+        yaml_error: impl Into<yaml::YamlError>,
         code: usize,
         message: String,
         file: Option<PathBuf>,
     ) -> Self {
+        let yaml_error = yaml_error.into();
+
         Error {
             code,
             message,
             file,
-            position: None,
-            description: format!("{}", serde_error),
+            position: yaml_error.position,
+            owner: None,
+            suggestions: vec![],
+            description: yaml_error.message,
         }
     }
 }
@@ -124,17 +225,24 @@ impl From<std::io::Error> for crate::Error {
             description: format!("{}", other),
             file: None,
             position: None,
+            owner: None,
+            suggestions: vec![],
         }
     }
 }
 
 /// Conver a filesystem path to its URI path.
+///
+/// URI paths written in any [`UrlStyle`] are accepted too, so `/guides/`,
+/// `/guides.html` and `/guides/index.html` all become `/guides`.
 pub fn fs_to_uri_path(fs_path: &Path) -> String {
-    let mut fs_path = fs_path.to_owned();
+    let mut fs_path = canonical_path::canonicalize(fs_path);
+
+    let is_html = fs_path.extension().map(|e| e == "html").unwrap_or(false);
 
     fs_path.set_extension("");
 
-    if fs_path.ends_with("README") {
+    if fs_path.ends_with("README") || (is_html && fs_path.ends_with("index")) {
         fs_path.pop();
     }
     if fs_path.starts_with("/") {
@@ -157,6 +265,21 @@ pub fn fs_to_uri_path(fs_path: &Path) -> String {
     }
 }
 
+/// Removes the trailing slash or `.html` extension from a URI path written in
+/// a [`UrlStyle`], without otherwise changing it. Unlike [`fs_to_uri_path`],
+/// this keeps redirect patterns like `/blog/*` intact.
+pub(crate) fn strip_url_style(uri_path: &str) -> &str {
+    let path = uri_path
+        .strip_suffix("/index.html")
+        .or_else(|| uri_path.strip_suffix(".html"))
+        .unwrap_or(uri_path);
+
+    match path.trim_end_matches('/') {
+        "" => "/",
+        path => path,
+    }
+}
+
 /// Best guess of converting a URI path to its equivalent FS path.
 ///
 /// The reason this is "best guess" is that going the other way
@@ -182,12 +305,12 @@ pub fn uri_to_fs_path(uri_path: &str) -> PathBuf {
     }
 }
 
-/// Convenience method for returning an AST from a Markdown section. This is
-/// used by Jaleo to extract description information out of OpenAPI description
-/// fields, which can be valid Markdown.
+/// Convenience method for returning an AST from a Markdown section, without
+/// any project context.
 ///
-/// TODO(Nik): Surely this context cannot be enough? How does the AST know which
-/// relative URL to use for its links?
+/// This is limited: relative links and images aren't resolved, and custom
+/// components aren't available. Use [`Project::render_markdown_fragment`] to
+/// render a fragment like it was part of a page in the project.
 pub fn markdown_to_ast(input: &str, opts: Option<&RenderOptions>) -> Result<Node> {
     let mut ctx = render_context::RenderContext::new();
     ctx.with_maybe_options(opts);
@@ -274,26 +397,11 @@ mod test {
         # I am Root!
         "};
 
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(navigation.to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project")),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(root.to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from("Foo.md"),
-                content: InputContent::Text(root.to_owned()),
-            },
-        ];
-
-        let project = Project::from_file_list(files).unwrap();
+        let project = test_support::project(&[
+            (NAVIGATION_FILE_NAME, navigation),
+            ("README.md", root),
+            ("Foo.md", root),
+        ]);
 
         let ast = project
             .get_page_by_uri_path("/")
@@ -338,6 +446,28 @@ mod test {
         assert_eq!(&fs_to_uri_path(&fs_path), "/AEuu/Example")
     }
 
+    #[test]
+    fn fs_to_uri_path_accepts_url_styles() {
+        assert_eq!(fs_to_uri_path(Path::new("/guides/")), "/guides");
+        assert_eq!(fs_to_uri_path(Path::new("/guides.html")), "/guides");
+        assert_eq!(fs_to_uri_path(Path::new("/guides/index.html")), "/guides");
+        assert_eq!(fs_to_uri_path(Path::new("/index.html")), "/");
+        // Only the index of a static build is treated as a directory
+        assert_eq!(
+            fs_to_uri_path(Path::new("/guides/index.md")),
+            "/guides/index"
+        );
+    }
+
+    #[test]
+    fn strip_url_style_keeps_patterns() {
+        assert_eq!(strip_url_style("/guides/"), "/guides");
+        assert_eq!(strip_url_style("/guides.html"), "/guides");
+        assert_eq!(strip_url_style("/guides/index.html"), "/guides");
+        assert_eq!(strip_url_style("/"), "/");
+        assert_eq!(strip_url_style("/blog/*"), "/blog/*");
+    }
+
     #[test]
     fn uri_to_fs_path_conversion() {
         assert_eq!(&uri_to_fs_path("/"), &Path::new("README.md"));
@@ -635,7 +765,10 @@ mod test {
             },
             InputFile {
                 path: PathBuf::from("Meh.md"),
-                content: InputContent::Binary("".to_string()),
+                content: InputContent::Binary {
+                    signature: String::new(),
+                    size_bytes: 0,
+                },
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
@@ -789,6 +922,78 @@ mod test {
         );
     }
 
+    #[test]
+    fn it_checks_for_broken_download_components_and_file_links() {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from(indoc! {r#"
+                [Report](/_assets/report.csv)
+
+                <Download src="/_assets/missing.zip" />
+                "#})),
+            },
+            InputFile {
+                path: PathBuf::from("_assets/report.csv"),
+                content: InputContent::Binary {
+                    signature: String::from("1"),
+                    size_bytes: 2048,
+                },
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {r#"
+            - heading: "Guides"
+            "#})),
+            },
+        ];
+
+        let project = Project::from_file_list(file_list).unwrap();
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].message, "Broken asset link detected");
+        assert!(errors[0].description.contains("/_assets/missing.zip"));
+    }
+
+    #[test]
+    fn it_adds_file_details_to_links_to_assets() {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("[Report](/_assets/report.csv)")),
+            },
+            InputFile {
+                path: PathBuf::from("_assets/report.csv"),
+                content: InputContent::Binary {
+                    signature: String::from("1"),
+                    size_bytes: 2048,
+                },
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+        ];
+
+        let project = Project::from_file_list(file_list).unwrap();
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = match page.ast(None).unwrap() {
+            Ast::Markdown(ast) => ast,
+            _ => panic!("Expected a Markdown page"),
+        };
+        let json = serde_json::to_value(&ast).unwrap();
+        let link = &json["children"][0]["children"][0]["kind"]["data"];
+
+        assert_eq!(link["file"]["extension"], "csv");
+        assert_eq!(link["file"]["size"], "2.0 KB");
+        assert_eq!(link["file"]["download"], true);
+    }
+
     #[test]
     fn it_checks_for_broken_links_in_components_v2() {
         let file_list = vec![
@@ -1075,6 +1280,64 @@ mod test {
         assert!(errors[0].description.contains("openapi.json"));
     }
 
+    #[test]
+    fn it_verifies_asset_links_in_openapi_pages() {
+        let spec_str = include_str!("../examples/open_api_specs/broken_internal_link.json")
+            .replace(
+                "See [bad link](/bad/link.md) for examples.",
+                "![Diagram](/_assets/missing.png)",
+            )
+            .replace(
+                "How many items to return at one time (max 100)",
+                "![Limits](/_assets/limits.png)",
+            );
+
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from("_assets/limits.png"),
+                content: InputContent::Binary {
+                    signature: String::new(),
+                    size_bytes: 0,
+                },
+            },
+            InputFile {
+                path: PathBuf::from("openapi.json"),
+                content: InputContent::Text(spec_str),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: openapi.json
+                            uri_prefix: /api
+                        "})),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {r#"
+            - heading: "Guides"
+            "#})),
+            },
+        ];
+
+        let project = Project::from_file_list(file_list).unwrap();
+        let errors = project
+            .verify(None, None)
+            .expect_err("Project was valid with a broken openapi asset link");
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+
+        assert_eq!(errors[0].message, "Broken asset link detected");
+        assert!(errors[0].description.contains("/_assets/missing.png"));
+        assert_eq!(errors[0].file, Some(PathBuf::from("openapi.json")));
+    }
+
     #[test]
     fn it_handles_relative_links_in_openapi_specs() {
         let spec_str = include_str!("../examples/open_api_specs/relative_internal_link.json");
@@ -1226,6 +1489,285 @@ mod test {
         );
     }
 
+    fn overview_template_project(template: &str) -> Vec<InputFile> {
+        vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.yaml"),
+                content: InputContent::Text(String::from(indoc! {r#"
+                    openapi: 3.0.0
+                    info:
+                      title: Nebularis API
+                      version: 2.1.0
+                    servers:
+                      - url: https://api.nebularis.dev
+                      - url: https://sandbox.nebularis.dev
+                    tags:
+                      - name: Clusters
+                        description: Manage your clusters
+                    paths:
+                      /clusters:
+                        get:
+                          tags: [Clusters]
+                          responses:
+                            "200":
+                              description: OK
+                    components:
+                      securitySchemes:
+                        bearerAuth:
+                          type: http
+                          scheme: bearer
+                    "#})),
+            },
+            InputFile {
+                path: PathBuf::from("api-overview.md"),
+                content: InputContent::Text(String::from(template)),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {"
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.yaml
+                        uri_prefix: /api
+                        overview_template: api-overview.md
+                    "})),
+            },
+        ]
+    }
+
+    #[test]
+    fn renders_the_openapi_overview_from_a_template() {
+        let project = Project::from_file_list(overview_template_project(indoc! {r#"
+            # {@info.title} v{@info.version}
+
+            Servers: {@servers | map("url") | join(", ")}
+
+            Tags: {@tags[0].name} at {@tags[0].href}
+
+            Auth: {@security_schemes[0].name} ({@security_schemes[0].scheme})
+            "#}))
+        .unwrap();
+
+        let page = project
+            .get_page_by_uri_path("/api")
+            .expect("API overview page not found");
+
+        let inner_text = page.ast(None).unwrap().as_markdown().unwrap().inner_text();
+
+        assert!(
+            inner_text.contains("Nebularis API v2.1.0"),
+            "{}",
+            inner_text
+        );
+        assert!(
+            inner_text
+                .contains("Servers: https://api.nebularis.dev, https://sandbox.nebularis.dev"),
+            "{}",
+            inner_text
+        );
+        assert!(
+            inner_text.contains("Tags: Clusters at /api/clusters"),
+            "{}",
+            inner_text
+        );
+        assert!(
+            inner_text.contains("Auth: bearerAuth (bearer)"),
+            "{}",
+            inner_text
+        );
+
+        // The template itself is not a page
+        assert!(project.get_page_by_uri_path("/api-overview").is_none());
+    }
+
+    #[test]
+    fn reports_unknown_variables_in_the_openapi_overview_template() {
+        let project = Project::from_file_list(overview_template_project(indoc! {r#"
+            # Overview
+
+            {@nope}
+            "#}))
+        .unwrap();
+
+        let errors = project.verify(None, None).unwrap_err();
+        let error = errors
+            .iter()
+            .find(|e| e.code == Error::OPENAPI_TEMPLATE_ERROR)
+            .expect("No template error found");
+
+        assert_eq!(error.file, Some(PathBuf::from("api-overview.md")));
+        assert_eq!(error.position.as_ref().map(|p| p.start.row), Some(3));
+        assert!(error.description.contains("Variable `@nope` not found"));
+    }
+
+    #[test]
+    fn reports_missing_openapi_overview_template() {
+        let mut files = overview_template_project("");
+        files.retain(|f| f.path != Path::new("api-overview.md"));
+
+        let project = Project::from_file_list(files).unwrap();
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.code == Error::OPENAPI_TEMPLATE_ERROR
+                    && e.message == "Could not find OpenAPI overview template."),
+            "{:#?}",
+            errors
+        );
+
+        // Falls back to the generated overview
+        let page = project
+            .get_page_by_uri_path("/api")
+            .expect("API overview page not found");
+        let inner_text = page.ast(None).unwrap().as_markdown().unwrap().inner_text();
+        assert!(inner_text.contains("Nebularis API"));
+    }
+
+    fn augmented_project(augmentations: &[(&str, &str)]) -> Project {
+        let mut files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.yaml"),
+                content: InputContent::Text(String::from(indoc! {r#"
+                    openapi: 3.0.0
+                    info:
+                      title: Nebularis API
+                      version: 2.1.0
+                    tags:
+                      - name: Clusters
+                    paths:
+                      /clusters:
+                        post:
+                          operationId: createCluster
+                          description: Creates a cluster.
+                          tags: [Clusters]
+                          responses:
+                            "200":
+                              description: OK
+                    "#})),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {"
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.yaml
+                        uri_prefix: /api
+                    "})),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {"
+                    - heading: API
+                      items:
+                        - label: Clusters
+                          href: /api/clusters
+                    "})),
+            },
+        ];
+
+        files.extend(augmentations.iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        Project::from_file_list(files).unwrap()
+    }
+
+    fn operation_description(project: &Project) -> String {
+        let page = project
+            .get_page_by_uri_path("/api/clusters")
+            .expect("Clusters page not found");
+        let ast = page.ast(None).unwrap();
+
+        ast.as_openapi().unwrap().operations[0]
+            .description_ast
+            .as_ref()
+            .map(|d| d.inner_text())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn appends_augmentations_to_operation_descriptions() {
+        let project = augmented_project(&[(
+            "_openapi/createCluster.md",
+            "**Gotcha:** clusters take a minute to start.",
+        )]);
+
+        assert_eq!(
+            operation_description(&project),
+            "Creates a cluster. Gotcha: clusters take a minute to start."
+        );
+
+        // The augmentation itself is not a page
+        assert!(project
+            .get_page_by_fs_path(Path::new("_openapi/createCluster.md"))
+            .is_none());
+        assert!(project.verify(None, None).is_ok());
+    }
+
+    #[test]
+    fn augmentations_can_be_prepended_or_replace_the_description() {
+        let prepended = augmented_project(&[(
+            "_openapi/createCluster.md",
+            "---\nplacement: prepend\n---\n\nRead this first.",
+        )]);
+        assert_eq!(
+            operation_description(&prepended),
+            "Read this first. Creates a cluster."
+        );
+
+        let replaced = augmented_project(&[(
+            "_openapi/createCluster.md",
+            "---\nplacement: replace\n---\n\nCreates a cluster in your account.",
+        )]);
+        assert_eq!(
+            operation_description(&replaced),
+            "Creates a cluster in your account."
+        );
+    }
+
+    #[test]
+    fn reports_augmentations_for_unknown_operations() {
+        let project = augmented_project(&[("_openapi/deleteCluster.md", "Careful!")]);
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::OPENAPI_REFERENCE);
+        assert_eq!(
+            errors[0].file,
+            Some(PathBuf::from("_openapi/deleteCluster.md"))
+        );
+    }
+
+    #[test]
+    fn reports_errors_in_augmentations_in_their_file() {
+        let project = augmented_project(&[(
+            "_openapi/createCluster.md",
+            "---\nplacement: append\n---\n\nSee {@nope}",
+        )]);
+
+        let errors = project.verify(None, None).unwrap_err();
+        let error = errors
+            .iter()
+            .find(|e| e.code == Error::INVALID_EXPRESSION)
+            .expect("No expression error found");
+
+        assert_eq!(error.file, Some(PathBuf::from("_openapi/createCluster.md")));
+    }
+
     #[test]
     fn returns_navigation_file_based_on_subtab() {
         let file_list = vec![
@@ -1332,4 +1874,46 @@ mod test {
         let project = Project::from_file_list(file_list).unwrap();
         assert_eq!(project.verify(None, None), Ok(()));
     }
+
+    #[test]
+    fn excerpts_the_source_of_errors_after_the_frontmatter() {
+        let readme = indoc! {"
+        ---
+        title: Home
+        ---
+
+        # Home
+
+        Welcome to {@page.titel}
+        "};
+
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(readme.to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from("- heading: Guides\n")),
+            },
+        ];
+
+        let project = Project::from_file_list(file_list).unwrap();
+        let errors = project.verify(None, None).unwrap_err();
+        let excerpt = errors[0].excerpt(readme).unwrap();
+
+        let highlighted = excerpt
+            .lines
+            .iter()
+            .find(|line| line.highlight.is_some())
+            .unwrap();
+
+        assert_eq!(highlighted.number, 7);
+        assert_eq!(highlighted.text, "Welcome to {@page.titel}");
+        assert!(excerpt.lines.iter().all(|line| line.number <= 7));
+    }
 }
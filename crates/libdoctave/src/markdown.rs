@@ -2,12 +2,16 @@ use crate::render_context::RenderContext;
 use crate::{Project, Result};
 
 mod anchorizer;
+mod api_links;
+pub(crate) mod attribute_block;
 pub mod attribute_parser;
 mod components;
+pub mod console;
 pub(crate) mod content_ast;
 pub mod control_flow;
 pub(crate) mod error_renderer;
 pub mod expressions;
+pub mod highlight;
 pub(crate) mod interpreter;
 mod markdown_rs_error_wrapper;
 pub mod parser;
@@ -18,12 +22,15 @@ pub(crate) mod shared_ast;
 pub mod autocomplete;
 mod custom_components;
 mod sanitizer;
+mod typography;
 
 pub use anchorizer::Anchorizer;
 pub(crate) use custom_components::custom_component::{
     CustomComponent, CustomComponentHandle, BAKED_COMPONENTS,
 };
-pub use renderable_ast::{Attribute, AttributeValue, Node, NodeKind, TableAlignment};
+pub use renderable_ast::{
+    Attribute, AttributeValue, FragmentTarget, Node, NodeKind, TableAlignment,
+};
 
 use std::path::Path;
 
@@ -114,6 +121,100 @@ mod test {
             // "<p>1 + 1 is 2</p>\n"
         }
 
+        #[test]
+        fn sets_image_attributes_from_a_block() {
+            let markdown = indoc! {r#"
+            ![Diagram](/_assets/arch.png){width=600 align=center caption="System overview"}
+            "#};
+
+            let ctx = RenderContext::new();
+            let root = ast_mdx(markdown, &ctx).unwrap();
+
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <Paragraph>
+                    <Image url={/_assets/arch.png} alt={Diagram} width={600} align={center} caption={System overview} />
+                </Paragraph>
+                "# }
+            );
+            assert_eq!(root.inner_text(), "");
+        }
+
+        #[test]
+        fn sets_link_attributes_from_a_block() {
+            let markdown = indoc! {r#"
+            [Docs](https://example.com){target=_blank rel=noopener}{1 + 1}
+            "#};
+
+            let ctx = RenderContext::new();
+            let html = ast_mdx(markdown, &ctx).unwrap().debug_string().unwrap();
+
+            assert_str_eq!(
+                html,
+                indoc! { r#"
+                <Paragraph>
+                    <Link url={https://example.com} target={_blank} rel={noopener}>
+                        <Text>
+                            Docs
+                        </Text>
+                    </Link>
+                    <Text>
+                        2
+                    </Text>
+                </Paragraph>
+                "# }
+            );
+        }
+
+        #[test]
+        fn unknown_attributes_are_errors() {
+            let markdown = indoc! {r#"
+            Some text
+
+            ![Diagram](arch.png){width=600 border=1}
+            "#};
+
+            let ctx = RenderContext::new();
+            let error = ast_mdx(markdown, &ctx).unwrap_err();
+
+            assert_eq!(error.code, crate::Error::INVALID_MARKDOWN_TEMPLATE);
+            assert!(
+                error.description.contains(
+                    "Unknown attribute `border`. Attributes for images are `width`, `height`, `align`, `caption`"
+                ),
+                "{}",
+                error.description
+            );
+
+            let position = error.position.unwrap();
+            assert_eq!((position.start.row, position.start.col), (3, 32));
+            assert_eq!(
+                &markdown[position.start.byte_offset..position.end.byte_offset],
+                "border=1"
+            );
+        }
+
+        #[test]
+        fn malformed_attribute_blocks_are_errors() {
+            let markdown = "[Docs](/docs){target=_blank rel=\"noopener}";
+
+            let ctx = RenderContext::new();
+            let error = ast_mdx(markdown, &ctx).unwrap_err();
+
+            assert!(
+                error
+                    .description
+                    .contains("Unterminated quotes in the value of `rel`"),
+                "{}",
+                error.description
+            );
+            assert_eq!(
+                &markdown[error.position.as_ref().unwrap().start.byte_offset..],
+                "rel=\"noopener}"
+            );
+        }
+
         #[test]
         fn executes_block_expressions() {
             let markdown = indoc! {r#"
@@ -172,10 +273,7 @@ mod test {
             let item = list.children.first().unwrap();
             assert!(matches!(
                 item.kind,
-                NodeKind::ListItem {
-                    checked: Some(true),
-                    ..
-                }
+                NodeKind::TaskListItem { checked: true, .. }
             ));
         }
 
@@ -951,6 +1049,171 @@ mod test {
                 }
             }
 
+            const SLOTS_TEMPLATE: &str = indoc! {r#"
+            <Box>
+              <Slot name="header">
+                Default header
+              </Slot>
+
+              <Slot />
+
+              <Slot name="footer">
+                Default footer
+              </Slot>
+            </Box>
+            "#};
+
+            fn render_with_slots(markdown: &str, debug_slots: bool) -> Result<Node> {
+                let components = vec![CustomComponentHandle::new(
+                    SLOTS_TEMPLATE,
+                    "_components/example",
+                )];
+
+                let opts = RenderOptions {
+                    debug_slots,
+                    ..Default::default()
+                };
+
+                let mut ctx = RenderContext::new();
+                ctx.custom_components = components.as_slice();
+                ctx.with_options(&opts);
+
+                ast_mdx(markdown, &ctx)
+            }
+
+            #[test]
+            fn renders_named_slots_with_defaults() {
+                let markdown = indoc! {r#"
+                <Component.Example>
+                    <Fill slot="header">
+                        Custom header
+                    </Fill>
+
+                    Body
+                </Component.Example>
+                "#};
+
+                let root = render_with_slots(markdown, false).unwrap();
+
+                assert_str_eq!(
+                    root.debug_string().unwrap(),
+                    indoc! { r#"
+                    <Box padding={0} max_width={full} class={} height={Auto}>
+                        <Paragraph>
+                            <Text>
+                                Custom header
+                            </Text>
+                        </Paragraph>
+                        <Paragraph>
+                            <Text>
+                                Body
+                            </Text>
+                        </Paragraph>
+                        <Paragraph>
+                            <Text>
+                                Default footer
+                            </Text>
+                        </Paragraph>
+                    </Box>
+                    "# }
+                );
+            }
+
+            #[test]
+            fn keeps_slot_boundaries_when_debugging_slots() {
+                let markdown = indoc! {r#"
+                <Component.Example>
+                    <Fill slot="footer">
+                        Custom footer
+                    </Fill>
+                </Component.Example>
+                "#};
+
+                let root = render_with_slots(markdown, true).unwrap();
+
+                assert_str_eq!(
+                    root.debug_string().unwrap(),
+                    indoc! { r#"
+                    <Box padding={0} max_width={full} class={} height={Auto}>
+                        <Slot name={header} filled={false}>
+                            <Paragraph>
+                                <Text>
+                                    Default header
+                                </Text>
+                            </Paragraph>
+                        </Slot>
+                        <Slot filled={false}>
+                        </Slot>
+                        <Slot name={footer} filled={true}>
+                            <Paragraph>
+                                <Text>
+                                    Custom footer
+                                </Text>
+                            </Paragraph>
+                        </Slot>
+                    </Box>
+                    "# }
+                );
+            }
+
+            #[test]
+            fn validates_fills() {
+                let unknown = indoc! {r#"
+                <Component.Example>
+                    <Fill slot="heder">
+                        Custom header
+                    </Fill>
+                </Component.Example>
+                "#};
+
+                let err = render_with_slots(unknown, false).unwrap_err();
+                assert_eq!(err.code, crate::Error::INVALID_COMPONENT);
+                assert_eq!(err.message, r#"Unknown slot "heder" for Component.Example"#);
+                assert!(err.description.contains(r#"Did you mean "header"?"#));
+                assert_eq!(err.position.unwrap().start.row, 2);
+
+                let duplicate = indoc! {r#"
+                <Component.Example>
+                    <Fill slot="header">
+                        One
+                    </Fill>
+
+                    <Fill slot="header">
+                        Two
+                    </Fill>
+                </Component.Example>
+                "#};
+
+                let err = render_with_slots(duplicate, false).unwrap_err();
+                assert_eq!(err.message, r#"Slot "header" filled more than once"#);
+                assert_eq!(err.position.unwrap().start.row, 6);
+
+                let nested = indoc! {r#"
+                <Component.Example>
+                    <Box>
+                        <Fill slot="header">
+                            Custom header
+                        </Fill>
+                    </Box>
+                </Component.Example>
+                "#};
+
+                let err = render_with_slots(nested, false).unwrap_err();
+                assert_eq!(err.message, "Invalid fill");
+                assert_eq!(err.position.unwrap().start.row, 3);
+
+                let missing = indoc! {r#"
+                <Component.Example>
+                    <Fill>
+                        Custom header
+                    </Fill>
+                </Component.Example>
+                "#};
+
+                let err = render_with_slots(missing, false).unwrap_err();
+                assert_eq!(err.message, "Missing slot for fill");
+            }
+
             #[test]
             fn validates_required_attributes() {
                 let custom_component_template = indoc! {r#"
@@ -983,6 +1246,115 @@ mod test {
                             ▲
                             └─ Missing required attribute
 
+                    Attributes for this component are declared in `_components/example`
+                "#}
+                );
+                assert_eq!(err.code, crate::Error::INVALID_COMPONENT);
+                assert_eq!(err.position.as_ref().unwrap().start.row, 1);
+            }
+
+            #[test]
+            fn validates_attribute_types() {
+                let custom_component_template = indoc! {r#"
+                ---
+                attributes:
+                  - title: count
+                    validation:
+                      is_a: number
+                ---
+
+                { @count }
+                "#};
+
+                let markdown = r#"<Component.Example count="many" />"#;
+                let components = vec![CustomComponentHandle::new(
+                    custom_component_template,
+                    "_components/example.md",
+                )];
+
+                let mut ctx = RenderContext::new();
+                ctx.custom_components = components.as_slice();
+
+                let err = ast_mdx(markdown, &ctx).unwrap_err();
+
+                assert_eq!(err.code, crate::Error::INVALID_COMPONENT);
+                assert_eq!(err.position.as_ref().unwrap().start.row, 1);
+                pretty_assertions::assert_eq!(
+                    err.description,
+                    indoc! {r#"
+                    Unexpected type. Found `many`, expected `number`
+
+                        1 │ <Component.Example count="many" />
+                                                      ▲▲▲▲
+
+                    Attributes for this component are declared in `_components/example.md`
+                "#}
+                );
+            }
+
+            #[test]
+            fn attribute_errors_point_at_the_usage() {
+                let custom_component_template = indoc! {r#"
+                ---
+                attributes:
+                  - title: title
+                ---
+
+                ## { @title }
+                "#};
+
+                let markdown = indoc! {r#"
+                # Heading
+
+                <Component.Example titel="Typo" />
+                "#};
+                let components = vec![CustomComponentHandle::new(
+                    custom_component_template,
+                    "_components/example.md",
+                )];
+
+                let mut ctx = RenderContext::new();
+                ctx.custom_components = components.as_slice();
+
+                let err = ast_mdx(markdown, &ctx).unwrap_err();
+
+                assert_eq!(err.code, crate::Error::INVALID_COMPONENT);
+                assert_eq!(err.position.as_ref().unwrap().start.row, 3);
+            }
+
+            #[test]
+            fn rejects_unknown_attributes() {
+                let custom_component_template = indoc! {r#"
+                ---
+                attributes:
+                  - title: title
+                ---
+
+                ## { @title }
+                "#};
+
+                let markdown = r#"<Component.Example titel="Typo" />"#;
+                let components = vec![CustomComponentHandle::new(
+                    custom_component_template,
+                    "_components/example.md",
+                )];
+
+                let mut ctx = RenderContext::new();
+                ctx.custom_components = components.as_slice();
+
+                let err = ast_mdx(markdown, &ctx).unwrap_err();
+
+                assert_eq!(err.code, crate::Error::INVALID_COMPONENT);
+                assert_eq!(err.position.as_ref().unwrap().start.row, 1);
+                pretty_assertions::assert_eq!(
+                    err.description,
+                    indoc! {r#"
+                    Unexpected attribute "titel"
+
+                        1 │ <Component.Example titel="Typo" />
+                                               ▲▲▲▲▲
+
+                    Attributes for this component are declared in `_components/example.md`
                 "#}
                 );
             }
@@ -1273,11 +1645,13 @@ mod test {
     }
 
     #[test]
-    fn footnote_not_supported_and_are_interpreted_as_definitions_and_references() {
+    fn footnotes_are_collected_at_the_end() {
         let markdown = indoc! {r#"
         Note [^1]
 
         [^1]: Foo
+
+        After
         "#};
 
         let ctx = RenderContext::new();
@@ -1290,11 +1664,130 @@ mod test {
                 <Text>
                     Note
                 </Text>
-                <Link url={Foo}>
+                <FootnoteReference number={1} id={fnref-1} definition_id={fn-1} />
+            </Paragraph>
+            <Paragraph>
+                <Text>
+                    After
+                </Text>
+            </Paragraph>
+            <Footnotes>
+                <FootnoteDefinition number={1} id={fn-1} reference_ids={fnref-1}>
+                    <Paragraph>
+                        <Text>
+                            Foo
+                        </Text>
+                    </Paragraph>
+                </FootnoteDefinition>
+            </Footnotes>
+            "# }
+        );
+    }
+
+    #[test]
+    fn footnotes_are_numbered_by_first_reference() {
+        let markdown = indoc! {r#"
+        A[^b] B[^a] C[^b]
+
+        [^a]: Defined first
+        [^b]: Defined second
+        [^unused]: Never referenced
+        "#};
+
+        let ctx = RenderContext::new();
+        let html = ast_mdx(markdown, &ctx).unwrap().debug_string().unwrap();
+
+        assert_str_eq!(
+            &html,
+            indoc! { r#"
+            <Paragraph>
+                <Text>
+                    A
+                </Text>
+                <FootnoteReference number={1} id={fnref-b} definition_id={fn-b} />
+                <Text>
+                    B
+                </Text>
+                <FootnoteReference number={2} id={fnref-a} definition_id={fn-a} />
+                <Text>
+                    C
+                </Text>
+                <FootnoteReference number={1} id={fnref-b-2} definition_id={fn-b} />
+            </Paragraph>
+            <Footnotes>
+                <FootnoteDefinition number={1} id={fn-b} reference_ids={fnref-b,fnref-b-2}>
+                    <Paragraph>
+                        <Text>
+                            Defined second
+                        </Text>
+                    </Paragraph>
+                </FootnoteDefinition>
+                <FootnoteDefinition number={2} id={fn-a} reference_ids={fnref-a}>
+                    <Paragraph>
+                        <Text>
+                            Defined first
+                        </Text>
+                    </Paragraph>
+                </FootnoteDefinition>
+            </Footnotes>
+            "# }
+        );
+    }
+
+    #[test]
+    fn definition_lists() {
+        let markdown = indoc! {r#"
+        Apple
+        : A fruit
+        : A company
+
+        **Carrot**
+        : A vegetable
+
+        Not a list
+        at all
+        "#};
+
+        let ctx = RenderContext::new();
+        let html = ast_mdx(markdown, &ctx).unwrap().debug_string().unwrap();
+
+        assert_str_eq!(
+            &html,
+            indoc! { r#"
+            <DefinitionList>
+                <DefinitionTerm>
                     <Text>
-                        ^1
+                        Apple
                     </Text>
-                </Link>
+                </DefinitionTerm>
+                <DefinitionDescription>
+                    <Text>
+                        A fruit
+                    </Text>
+                </DefinitionDescription>
+                <DefinitionDescription>
+                    <Text>
+                        A company
+                    </Text>
+                </DefinitionDescription>
+                <DefinitionTerm>
+                    <Strong>
+                        <Text>
+                            Carrot
+                        </Text>
+                    </Strong>
+                </DefinitionTerm>
+                <DefinitionDescription>
+                    <Text>
+                        A vegetable
+                    </Text>
+                </DefinitionDescription>
+            </DefinitionList>
+            <Paragraph>
+                <Text>
+                    Not a list
+                    at all
+                </Text>
             </Paragraph>
             "# }
         );
@@ -1936,6 +2429,65 @@ mod test {
         );
     }
 
+    #[test]
+    fn code_is_highlighted_only_when_enabled() {
+        let markdown = indoc! {
+          r#"
+          ```js
+          const foo = "bar";
+          ```
+          "#
+        };
+
+        let highlighted = |node: &Node| match &node.children[0].kind {
+            NodeKind::Code { highlighted, .. } => highlighted.clone(),
+            other => panic!("Expected a code block, found {:?}", other),
+        };
+
+        let ctx = RenderContext::new();
+        assert_eq!(highlighted(&ast(markdown, &ctx).unwrap()), None);
+
+        let opts = RenderOptions {
+            highlight_code: true,
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new();
+        ctx.with_options(&opts);
+
+        let tokens = highlighted(&ast(markdown, &ctx).unwrap()).unwrap();
+        assert_eq!(
+            tokens.iter().map(|t| t.text.as_str()).collect::<String>(),
+            "const foo = \"bar\";"
+        );
+        assert!(tokens.iter().any(|t| t.class.is_some()));
+    }
+
+    #[test]
+    fn console_blocks_know_their_commands() {
+        let markdown = indoc! {
+          r#"
+          ```console
+          $ cargo build
+             Compiling docapella
+          cargo test # [cmd]
+          ```
+          "#
+        };
+
+        let ctx = RenderContext::new();
+        let root = ast(markdown, &ctx).unwrap();
+
+        let NodeKind::Code { value, console, .. } = &root.children[0].kind else {
+            panic!("Expected a code block, found {:?}", root.children[0]);
+        };
+        let console = console.as_ref().unwrap();
+
+        assert_eq!(value, "$ cargo build\n   Compiling docapella\ncargo test");
+        assert_eq!(console.commands, "cargo build\ncargo test");
+        assert_eq!(console.lines.iter().filter(|l| l.is_command()).count(), 2);
+        assert!(!root.inner_text().contains("[cmd]"));
+    }
+
     #[test]
     fn math() {
         let markdown = indoc! {
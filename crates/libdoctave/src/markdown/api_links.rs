@@ -0,0 +1,334 @@
+//! Links inline code naming an OpenAPI operation or schema, like
+//! `` `POST /pets` `` or `` `Pet` ``, to the API reference. Turned on per
+//! spec with `auto_link_code` in its `open_api` settings.
+use std::collections::HashMap;
+
+use super::renderable_ast::{LinkAttributes, Node, NodeKind};
+use crate::{fs_to_uri_path, page_kind::PageKind, render_context::RenderContext, settings};
+
+/// Inline code starting with this is never linked, and is shown without it,
+/// e.g. `` `!Pet` `` for talking about pets instead of the `Pet` schema.
+const NO_LINK_PREFIX: char = '!';
+
+/// Where operations and schemas link to, by the inline code naming them
+type Targets = HashMap<String, String>;
+
+/// Replaces inline code that exactly matches an operation, e.g.
+/// `` `GET /pets` ``, or a component schema, e.g. `` `Pet` ``, with a link to
+/// it. Code in headings and links is left alone.
+///
+/// Schemas don't have pages of their own, so they link to the first
+/// operation that takes or returns them. Schemas that no operation uses
+/// aren't linked.
+pub(crate) fn link_code(node: &mut Node, ctx: &RenderContext) {
+    if !ctx.settings.open_api().iter().any(|s| s.auto_link_code) {
+        return;
+    }
+
+    link(node, &mut None, ctx);
+}
+
+fn link(node: &mut Node, targets: &mut Option<Targets>, ctx: &RenderContext) {
+    match &node.kind {
+        NodeKind::Heading { .. } | NodeKind::Link { .. } => return,
+        NodeKind::HtmlBlock { name, .. } if name == "a" => return,
+        _ => {}
+    }
+
+    for child in &mut node.children {
+        let NodeKind::InlineCode { value } = &mut child.kind else {
+            link(child, targets, ctx);
+            continue;
+        };
+
+        let targets = targets.get_or_insert_with(|| collect_targets(ctx));
+
+        if let Some(name) = value.strip_prefix(NO_LINK_PREFIX) {
+            if targets.contains_key(name) {
+                *value = name.to_owned();
+            }
+        } else if let Some(url) = targets.get(value.as_str()) {
+            let code = std::mem::take(child);
+
+            *child = Node {
+                kind: NodeKind::Link {
+                    url: url.clone(),
+                    title: None,
+                    file: None,
+                    attributes: LinkAttributes::default(),
+                },
+                pos: code.pos.clone(),
+                children: vec![code],
+            };
+        }
+    }
+}
+
+/// When several specs name the same operation or schema, the spec whose
+/// `uri_prefix` is the longest match for the page wins, then the one listed
+/// first in the settings.
+fn collect_targets(ctx: &RenderContext) -> Targets {
+    let page_uri = ctx
+        .file_context
+        .as_ref()
+        .map(|f| fs_to_uri_path(&f.fs_path))
+        .or_else(|| ctx.relative_url_base.clone())
+        .unwrap_or_default();
+
+    let mut specs = ctx
+        .settings
+        .open_api()
+        .iter()
+        .filter(|s| s.auto_link_code)
+        .collect::<Vec<_>>();
+
+    // A stable sort, so that the order of the settings breaks ties
+    specs.sort_by_key(|spec| std::cmp::Reverse(prefix_match_len(spec, &page_uri)));
+
+    let mut targets = Targets::new();
+
+    for spec in specs {
+        let pages = ctx.pages.iter().filter_map(|page| match page {
+            PageKind::OpenApi(page) if page.fs_path == spec.spec_file => Some(page),
+            _ => None,
+        });
+
+        for page in pages {
+            for op in page.operations() {
+                let url = format!("{}#{}", page.uri_path, op.anchor_tag);
+
+                let schemas = op
+                    .request_body
+                    .iter()
+                    .flat_map(|body| &body.content)
+                    .chain(op.responses.iter().flat_map(|r| &r.content))
+                    .filter_map(|media_type| media_type.component_name.clone());
+
+                // Operations listed under several tags link to the first one
+                targets
+                    .entry(format!("{} {}", op.method.to_uppercase(), op.route_pattern))
+                    .or_insert_with(|| url.clone());
+
+                for schema in schemas {
+                    targets.entry(schema).or_insert_with(|| url.clone());
+                }
+            }
+        }
+    }
+
+    targets
+}
+
+/// Length of the spec's `uri_prefix` if the page is under it, or `None`.
+fn prefix_match_len(spec: &settings::OpenApi, page_uri: &str) -> Option<usize> {
+    let prefix = spec.uri_prefix.trim_end_matches('/');
+
+    let under_prefix = page_uri == prefix
+        || page_uri
+            .strip_prefix(prefix)
+            .map(|rest| rest.starts_with('/'))
+            .unwrap_or(false);
+
+    under_prefix.then_some(prefix.len())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{renderable_ast::NodeKind, Ast, InputContent, InputFile, Project};
+
+    const SPEC: &str = indoc! {r#"
+    openapi: 3.0.0
+    info:
+      title: Pets
+      version: 1.0.0
+    paths:
+      /pets:
+        get:
+          summary: List pets
+          tags: [Pets]
+          responses:
+            '200':
+              description: The pets
+              content:
+                application/json:
+                  schema:
+                    type: array
+                    items:
+                      $ref: '#/components/schemas/Pet'
+        post:
+          summary: Add a pet
+          tags: [Pets]
+          requestBody:
+            content:
+              application/json:
+                schema:
+                  $ref: '#/components/schemas/NewPet'
+          responses:
+            '201':
+              description: The pet
+    components:
+      schemas:
+        Pet:
+          type: object
+          properties:
+            name:
+              type: string
+        NewPet:
+          type: object
+          properties:
+            name:
+              type: string
+        Unused:
+          type: object
+    "#};
+
+    fn project(settings: &str, files: &[(&str, &str)]) -> Project {
+        let mut list = vec![
+            InputFile {
+                path: PathBuf::from(crate::SETTINGS_FILE_NAME),
+                content: InputContent::Text(settings.to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.yaml"),
+                content: InputContent::Text(SPEC.to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from("v2.yaml"),
+                content: InputContent::Text(SPEC.to_owned()),
+            },
+        ];
+
+        list.extend(files.iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    const SETTINGS: &str = indoc! {r#"
+    ---
+    title: Pets
+    open_api:
+      - spec_file: openapi.yaml
+        uri_prefix: /api
+        auto_link_code: true
+    "#};
+
+    /// The links on the page, with the text of their inline code
+    fn code_links(project: &Project, uri_path: &str) -> Vec<(String, String)> {
+        let Ast::Markdown(root) = project
+            .get_page_by_uri_path(uri_path)
+            .unwrap()
+            .ast(None)
+            .unwrap()
+        else {
+            panic!("Not a Markdown page");
+        };
+
+        root.walk()
+            .filter_map(|node| match &node.kind {
+                NodeKind::Link { url, .. } => Some((url.clone(), node.inner_text())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn links_operations_and_schemas() {
+        let project = project(
+            SETTINGS,
+            &[(
+                "README.md",
+                "Call `POST /pets` with a `NewPet` to get a `Pet`. Not `post /pets` or `pet`.",
+            )],
+        );
+
+        assert_eq!(
+            code_links(&project, "/"),
+            vec![
+                ("/api/pets#add-a-pet".to_owned(), "POST /pets".to_owned()),
+                ("/api/pets#add-a-pet".to_owned(), "NewPet".to_owned()),
+                ("/api/pets#list-pets".to_owned(), "Pet".to_owned()),
+            ]
+        );
+        assert_eq!(project.verify(None, None), Ok(()));
+    }
+
+    #[test]
+    fn leaves_headings_links_and_unused_schemas_alone() {
+        let project = project(
+            SETTINGS,
+            &[(
+                "README.md",
+                "# The `Pet`\n\n[The `GET /pets` list](https://example.com) and `Unused`.",
+            )],
+        );
+
+        assert_eq!(
+            code_links(&project, "/"),
+            vec![(
+                "https://example.com".to_owned(),
+                "The GET /pets list".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn escaped_code_is_not_linked() {
+        let project = project(SETTINGS, &[("README.md", "A `!Pet` and a `!important`.")]);
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let Ast::Markdown(root) = page.ast(None).unwrap() else {
+            panic!("Not a Markdown page");
+        };
+
+        assert!(code_links(&project, "/").is_empty());
+        assert_eq!(root.inner_text(), "A Pet and a !important.");
+    }
+
+    #[test]
+    fn is_off_by_default() {
+        let project = project(
+            indoc! {r#"
+            ---
+            title: Pets
+            open_api:
+              - spec_file: openapi.yaml
+                uri_prefix: /api
+            "#},
+            &[("README.md", "A `Pet`.")],
+        );
+
+        assert!(code_links(&project, "/").is_empty());
+    }
+
+    #[test]
+    fn prefers_the_spec_the_page_is_under() {
+        let project = project(
+            indoc! {r#"
+            ---
+            title: Pets
+            open_api:
+              - spec_file: openapi.yaml
+                uri_prefix: /api
+                auto_link_code: true
+              - spec_file: v2.yaml
+                uri_prefix: /api/v2
+                auto_link_code: true
+            "#},
+            &[("README.md", "A `Pet`."), ("api/v2/guide.md", "A `Pet`.")],
+        );
+
+        assert_eq!(
+            code_links(&project, "/"),
+            vec![("/api/pets#list-pets".to_owned(), "Pet".to_owned())]
+        );
+        assert_eq!(
+            code_links(&project, "/api/v2/guide"),
+            vec![("/api/v2/pets#list-pets".to_owned(), "Pet".to_owned())]
+        );
+    }
+}
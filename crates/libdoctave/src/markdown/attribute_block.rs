@@ -0,0 +1,260 @@
+//! Attribute blocks written directly after an image or a link, e.g.
+//! `![Diagram](arch.png){width=600 align=center}` or
+//! `[Docs](https://example.com){target=_blank rel=noopener}`.
+//!
+//! Markdown parses the block as an expression, which the interpreter takes
+//! as the attributes of the image or link right before it.
+use std::ops::Range;
+
+use super::renderable_ast::{ImageAlign, ImageAttributes, LinkAttributes};
+
+pub(crate) const IMAGE_ATTRIBUTES: &[&str] = &["width", "height", "align", "caption"];
+pub(crate) const LINK_ATTRIBUTES: &[&str] = &["target", "rel", "class"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Attribute {
+    pub key: String,
+    pub value: String,
+    /// Byte range of the whole `key=value` in the block
+    pub span: Range<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BlockError {
+    pub msg: String,
+    /// Byte range in the block
+    pub span: Range<usize>,
+}
+
+/// Whether the expression is an attribute block rather than a value to show.
+/// Blocks start with `key=`, which no expression does.
+pub(crate) fn is_block(expression: &str) -> bool {
+    let trimmed = expression.trim_start();
+    let key_len = trimmed
+        .find(|c: char| !is_key_char(c))
+        .unwrap_or(trimmed.len());
+    let rest = &trimmed[key_len..];
+
+    key_len > 0 && rest.starts_with('=') && !rest.starts_with("==")
+}
+
+/// Parses space separated `key=value` pairs. Values with spaces are quoted,
+/// e.g. `caption="System overview"`.
+pub(crate) fn parse(input: &str) -> Result<Vec<Attribute>, BlockError> {
+    let mut attributes: Vec<Attribute> = vec![];
+    let mut chars = input.char_indices().peekable();
+
+    loop {
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        let Some(&(start, _)) = chars.peek() else {
+            break;
+        };
+
+        let mut key_end = start;
+        while let Some((i, c)) = chars.next_if(|(_, c)| is_key_char(*c)) {
+            key_end = i + c.len_utf8();
+        }
+
+        let key = &input[start..key_end];
+
+        if key.is_empty() {
+            let len = input[start..].chars().next().map_or(1, char::len_utf8);
+
+            return Err(BlockError {
+                msg: "Expected an attribute name".to_string(),
+                span: start..start + len,
+            });
+        }
+
+        if chars.next_if(|(_, c)| *c == '=').is_none() {
+            return Err(BlockError {
+                msg: format!("Expected a value for `{}`, e.g. `{}=value`", key, key),
+                span: start..key_end,
+            });
+        }
+
+        let value_start = key_end + 1;
+        let (value, end) = if chars.next_if(|(_, c)| *c == '"').is_some() {
+            match input[value_start + 1..].find('"') {
+                Some(len) => {
+                    let end = value_start + 1 + len;
+                    while chars.next_if(|(i, _)| *i <= end).is_some() {}
+
+                    (&input[value_start + 1..end], end + 1)
+                }
+                None => {
+                    return Err(BlockError {
+                        msg: format!("Unterminated quotes in the value of `{}`", key),
+                        span: start..input.trim_end().len(),
+                    })
+                }
+            }
+        } else {
+            let mut end = value_start;
+            while let Some((i, c)) = chars.next_if(|(_, c)| !c.is_whitespace()) {
+                end = i + c.len_utf8();
+            }
+
+            (&input[value_start..end], end)
+        };
+
+        if value.is_empty() {
+            return Err(BlockError {
+                msg: format!("Expected a value for `{}`", key),
+                span: start..end,
+            });
+        }
+
+        if attributes.iter().any(|a| a.key == key) {
+            return Err(BlockError {
+                msg: format!("Attribute `{}` is set more than once", key),
+                span: start..end,
+            });
+        }
+
+        attributes.push(Attribute {
+            key: key.to_string(),
+            value: value.to_string(),
+            span: start..end,
+        });
+    }
+
+    if attributes.is_empty() {
+        return Err(BlockError {
+            msg: "Expected attributes, e.g. `{width=600}`".to_string(),
+            span: 0..input.len(),
+        });
+    }
+
+    Ok(attributes)
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Sets an attribute of an image. Errors describe why the attribute or its
+/// value isn't valid.
+pub(crate) fn set_image_attribute(
+    attributes: &mut ImageAttributes,
+    attribute: &Attribute,
+) -> Result<(), String> {
+    let value = attribute.value.clone();
+
+    match attribute.key.as_str() {
+        "width" => attributes.width = Some(dimension(&attribute.key, value)?),
+        "height" => attributes.height = Some(dimension(&attribute.key, value)?),
+        "align" => {
+            attributes.align = Some(match value.as_str() {
+                "left" => ImageAlign::Left,
+                "center" => ImageAlign::Center,
+                "right" => ImageAlign::Right,
+                _ => {
+                    return Err(format!(
+                    "Invalid value `{}` for `align`. Expected one of `left`, `center` or `right`",
+                    value
+                ))
+                }
+            })
+        }
+        "caption" => attributes.caption = Some(value),
+        key => return Err(unknown_attribute(key, "images", IMAGE_ATTRIBUTES)),
+    }
+
+    Ok(())
+}
+
+/// Sets an attribute of a link. Errors describe why the attribute or its
+/// value isn't valid.
+pub(crate) fn set_link_attribute(
+    attributes: &mut LinkAttributes,
+    attribute: &Attribute,
+) -> Result<(), String> {
+    let value = attribute.value.clone();
+
+    match attribute.key.as_str() {
+        "target" => attributes.target = Some(value),
+        "rel" => attributes.rel = Some(value),
+        "class" => attributes.class = Some(value),
+        key => return Err(unknown_attribute(key, "links", LINK_ATTRIBUTES)),
+    }
+
+    Ok(())
+}
+
+/// A number of pixels, like `600`, or a percentage, like `50%`
+fn dimension(key: &str, value: String) -> Result<String, String> {
+    let number = value.strip_suffix('%').unwrap_or(&value);
+
+    if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) {
+        Ok(value)
+    } else {
+        Err(format!(
+            "Invalid value `{}` for `{}`. Expected a number of pixels, like `600`, or a percentage, like `50%`",
+            value, key
+        ))
+    }
+}
+
+fn unknown_attribute(key: &str, kind: &str, allowed: &[&str]) -> String {
+    format!(
+        "Unknown attribute `{}`. Attributes for {} are {}",
+        key,
+        kind,
+        allowed
+            .iter()
+            .map(|a| format!("`{}`", a))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_attributes() {
+        let attributes = parse(r#" width=600 caption="System overview" "#).unwrap();
+
+        assert_eq!(
+            attributes,
+            vec![
+                Attribute {
+                    key: "width".to_string(),
+                    value: "600".to_string(),
+                    span: 1..10,
+                },
+                Attribute {
+                    key: "caption".to_string(),
+                    value: "System overview".to_string(),
+                    span: 11..36,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_malformed_blocks() {
+        assert_eq!(
+            parse("width").unwrap_err(),
+            BlockError {
+                msg: "Expected a value for `width`, e.g. `width=value`".to_string(),
+                span: 0..5,
+            }
+        );
+        assert_eq!(parse("width=600 caption=\"Open").unwrap_err().span, 10..23);
+        assert_eq!(parse("width=1 width=2").unwrap_err().span, 8..15);
+        assert_eq!(parse("=600").unwrap_err().span, 0..1);
+    }
+
+    #[test]
+    fn tells_blocks_from_expressions() {
+        assert!(is_block("width=600"));
+        assert!(!is_block("@page.title"));
+        assert!(!is_block(" 1 + 1 "));
+        assert!(!is_block("capitalize(\"docs\")"));
+        assert!(!is_block("@page.title == \"Home\""));
+    }
+}
@@ -34,6 +34,8 @@ lazy_static! {
         Box::<primitive_components::CodeSelect>::default(),
         // Box component
         Box::<primitive_components::CBox>::default(),
+        // Callout component
+        Box::<primitive_components::Callout>::default(),
         // Step component
         Box::<primitive_components::Step>::default(),
         // Steps component
@@ -42,6 +44,10 @@ lazy_static! {
         Box::<primitive_components::Tab>::default(),
         // Tabs component
         Box::<primitive_components::Tabs>::default(),
+        // Download component
+        Box::<primitive_components::Download>::default(),
+        // Embed component
+        Box::<primitive_components::Embed>::default(),
     ];
 }
 
@@ -263,7 +269,7 @@ fn autocomplete_link_url(
 fn autocomplete_component_name(prefix: &str, ctx: &RenderContext) -> Vec<CompletionItem> {
     let mut completion_items = vec![];
 
-    for component in ctx.custom_components {
+    for component in ctx.enabled_components() {
         if let Ok(title) = component.title() {
             if title.starts_with(prefix) {
                 completion_items.push(CompletionItem {
@@ -301,7 +307,7 @@ fn autocomplete_component_attribute_name(
 ) -> Vec<CompletionItem> {
     let mut completion_items = vec![];
 
-    for component in ctx.custom_components {
+    for component in ctx.enabled_components() {
         if component.matches_title(component_name) {
             if let Ok(comp) = component.build() {
                 comp.attributes
@@ -353,7 +359,7 @@ fn autocomplete_component_attribute_value(
 ) -> Vec<CompletionItem> {
     let mut completion_items = vec![];
 
-    for component in ctx.custom_components {
+    for component in ctx.enabled_components() {
         if component.matches_title(component_name) {
             if let Ok(comp) = component.build() {
                 if let Some(attribute) = comp.attributes.iter().find(|a| a.title == attribute) {
@@ -1257,6 +1263,29 @@ mod test {
             );
         }
 
+        #[test]
+        fn leaves_out_components_disabled_on_the_page() {
+            let project = crate::test_support::project(&[
+                ("README.md", ""),
+                ("_components/Custom.md", "Custom"),
+                ("_components/Fancy.md", "Fancy"),
+            ]);
+
+            let completion_items = project.autocomplete(
+                "---\ncomponents_disabled: [Component.Fancy]\n---\n\nThe component is <Component.",
+                Path::new("README.md"),
+                None,
+            );
+
+            assert_eq!(
+                completion_items
+                    .iter()
+                    .map(|item| item.label.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["Component.Custom"]
+            );
+        }
+
         #[test]
         fn gets_autocomplete_for_primitive_components() {
             let files = vec![
@@ -0,0 +1,189 @@
+//! Shell sessions in ` ```console ` code blocks.
+//!
+//! Lines starting with a prompt like `$ ` are commands, and everything else
+//! is their output. Lines ending in a backslash continue the command on the
+//! next line. For lines where that guess is wrong, a trailing `# [cmd]` or
+//! `# [out]` marker says which one the line is. Markers are removed from
+//! the code that is shown.
+
+/// Languages whose code blocks are parsed as shell sessions
+pub const CONSOLE_LANGUAGES: &[&str] = &["console", "shell-session"];
+
+const PROMPTS: &[&str] = &["$ ", "% "];
+const COMMAND_MARKER: &str = "# [cmd]";
+const OUTPUT_MARKER: &str = "# [out]";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsoleLineKind {
+    Command,
+    Output,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConsoleLine {
+    pub kind: ConsoleLineKind,
+    /// The prompt in front of a command, e.g. `$ `
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// The line without its prompt or marker
+    pub text: String,
+}
+
+/// A parsed shell session, stored on its code block
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConsoleSession {
+    pub lines: Vec<ConsoleLine>,
+    /// The commands without prompts, as they'd be pasted into a terminal.
+    /// This is what copy buttons copy.
+    pub commands: String,
+}
+
+impl ConsoleLine {
+    pub fn is_command(&self) -> bool {
+        self.kind == ConsoleLineKind::Command
+    }
+}
+
+pub(crate) fn is_console_language(language: Option<&str>) -> bool {
+    language.is_some_and(|l| CONSOLE_LANGUAGES.contains(&l))
+}
+
+/// Splits a shell session into its lines. Returns the code to show, with the
+/// markers removed, along with the session.
+pub(crate) fn parse(code: &str) -> (String, ConsoleSession) {
+    let mut lines = vec![];
+    let mut continues_command = false;
+
+    for line in code.split('\n') {
+        let (line, marker) = strip_marker(line);
+
+        let (prompt, text) = match PROMPTS.iter().find(|p| line.starts_with(**p)) {
+            Some(prompt) => (Some(prompt.to_string()), &line[prompt.len()..]),
+            None => (None, line),
+        };
+
+        let kind = match marker {
+            Some(kind) => kind,
+            None if prompt.is_some() || continues_command => ConsoleLineKind::Command,
+            None => ConsoleLineKind::Output,
+        };
+
+        // An output line with something that looks like a prompt keeps it
+        let (prompt, text) = match kind {
+            ConsoleLineKind::Command => (prompt, text),
+            ConsoleLineKind::Output => (None, line),
+        };
+
+        continues_command = kind == ConsoleLineKind::Command && text.ends_with('\\');
+
+        lines.push(ConsoleLine {
+            kind,
+            prompt,
+            text: text.to_string(),
+        });
+    }
+
+    let value = lines
+        .iter()
+        .map(|l| format!("{}{}", l.prompt.as_deref().unwrap_or(""), l.text))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let commands = lines
+        .iter()
+        .filter(|l| l.is_command())
+        .map(|l| l.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (value, ConsoleSession { lines, commands })
+}
+
+fn strip_marker(line: &str) -> (&str, Option<ConsoleLineKind>) {
+    let trimmed = line.trim_end();
+
+    for (marker, kind) in [
+        (COMMAND_MARKER, ConsoleLineKind::Command),
+        (OUTPUT_MARKER, ConsoleLineKind::Output),
+    ] {
+        if let Some(rest) = trimmed.strip_suffix(marker) {
+            return (rest.trim_end(), Some(kind));
+        }
+    }
+
+    (line, None)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn kinds(session: &ConsoleSession) -> Vec<&ConsoleLineKind> {
+        session.lines.iter().map(|l| &l.kind).collect()
+    }
+
+    #[test]
+    fn splits_commands_from_output() {
+        let (value, session) = parse("$ cargo build\n   Compiling docapella\n% ls\nREADME.md");
+
+        assert_eq!(
+            value,
+            "$ cargo build\n   Compiling docapella\n% ls\nREADME.md"
+        );
+        assert_eq!(
+            kinds(&session),
+            vec![
+                &ConsoleLineKind::Command,
+                &ConsoleLineKind::Output,
+                &ConsoleLineKind::Command,
+                &ConsoleLineKind::Output,
+            ]
+        );
+        assert_eq!(session.lines[0].prompt.as_deref(), Some("$ "));
+        assert_eq!(session.lines[0].text, "cargo build");
+        assert_eq!(session.commands, "cargo build\nls");
+    }
+
+    #[test]
+    fn continues_commands_ending_in_a_backslash() {
+        let (_, session) = parse("$ docker run \\\n  -it ubuntu\nroot@abc:/#");
+
+        assert_eq!(
+            kinds(&session),
+            vec![
+                &ConsoleLineKind::Command,
+                &ConsoleLineKind::Command,
+                &ConsoleLineKind::Output,
+            ]
+        );
+        assert_eq!(session.commands, "docker run \\\n  -it ubuntu");
+    }
+
+    #[test]
+    fn markers_override_the_guess() {
+        let (value, session) =
+            parse("apt install curl # [cmd]\n$ not a command # [out]\n$ echo hi  # [cmd]");
+
+        assert_eq!(value, "apt install curl\n$ not a command\n$ echo hi");
+        assert_eq!(
+            kinds(&session),
+            vec![
+                &ConsoleLineKind::Command,
+                &ConsoleLineKind::Output,
+                &ConsoleLineKind::Command,
+            ]
+        );
+        assert_eq!(session.lines[0].prompt, None);
+        assert_eq!(session.lines[1].text, "$ not a command");
+        assert_eq!(session.commands, "apt install curl\necho hi");
+    }
+
+    #[test]
+    fn only_parses_console_languages() {
+        assert!(is_console_language(Some("console")));
+        assert!(is_console_language(Some("shell-session")));
+        assert!(!is_console_language(Some("bash")));
+        assert!(!is_console_language(None));
+    }
+}
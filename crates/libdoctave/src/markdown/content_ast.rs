@@ -14,10 +14,10 @@ pub(crate) fn build_mdx(markdown_input: &str, ctx: &RenderContext) -> Result<Nod
     parse_opts.constructs.gfm_table = true;
     parse_opts.constructs.gfm_task_list_item = true;
     parse_opts.constructs.gfm_autolink_literal = false;
-    parse_opts.constructs.gfm_footnote_definition = false;
-    parse_opts.constructs.gfm_label_start_footnote = false;
-    parse_opts.constructs.math_flow = true;
-    parse_opts.constructs.math_text = true;
+    parse_opts.constructs.gfm_footnote_definition = true;
+    parse_opts.constructs.gfm_label_start_footnote = true;
+    parse_opts.constructs.math_flow = ctx.settings.markdown().math;
+    parse_opts.constructs.math_text = ctx.settings.markdown().math;
 
     opts.parse = parse_opts;
 
@@ -32,14 +32,14 @@ pub(crate) fn build_mdx(markdown_input: &str, ctx: &RenderContext) -> Result<Nod
             position: markdown_rs_error_wrapper::parse_position(e, markdown_input)
                 .as_ref()
                 .map(|p| (&**p).into()),
+            owner: None,
+            suggestions: vec![],
         })?
 }
 
 pub(crate) fn build_gfm(markdown_input: &str, ctx: &RenderContext) -> Result<Node> {
     let mut opts = markdown_rs::Options::gfm();
     opts.parse.constructs.gfm_autolink_literal = false;
-    opts.parse.constructs.gfm_footnote_definition = false;
-    opts.parse.constructs.gfm_label_start_footnote = false;
 
     // Parse the markdown file into an AST
     markdown_rs::to_mdast(markdown_input, &opts.parse)
@@ -52,6 +52,8 @@ pub(crate) fn build_gfm(markdown_input: &str, ctx: &RenderContext) -> Result<Nod
             position: markdown_rs_error_wrapper::parse_position(e, markdown_input)
                 .as_ref()
                 .map(|p| (&**p).into()),
+            owner: None,
+            suggestions: vec![],
         })?
 }
 
@@ -99,7 +101,6 @@ impl Node {
     }
 
     /// Traverses the children of this node in a depth-first manner
-    #[allow(dead_code)]
     pub fn descendants(&self) -> impl Iterator<Item = &Node> {
         pub struct Descendants<'a> {
             stack: Vec<&'a Node>,
@@ -174,6 +175,8 @@ impl Node {
             })
             .unwrap_or(Ok(vec![]))?;
 
+        merge_definition_lists(&mut children);
+
         let any_conditionals_in_children = children.iter().any(|c| c.is_conditional());
 
         if any_conditionals_in_children {
@@ -183,6 +186,8 @@ impl Node {
                 description: e.render(src, ctx),
                 file: None,
                 position: Some(e.position()),
+                owner: None,
+                suggestions: vec![],
             })?;
         }
 
@@ -196,7 +201,7 @@ impl Node {
 
         let mut conditional_operators = None;
 
-        let kind = match other {
+        let mut kind = match other {
             mdast::Node::Root(_) => NodeKind::Root,
             mdast::Node::Paragraph(_) => {
                 // NOTE: Context for this transformation in DOC-1136
@@ -349,6 +354,8 @@ impl Node {
                                 description: e.render(src, ctx),
                                 file: None,
                                 position: Some(pos.clone()),
+                                owner: None,
+                                suggestions: vec![],
                             })?
                     } else {
                         NodeKind::Component { name, attributes }
@@ -358,19 +365,21 @@ impl Node {
                 }
             }
             mdast::Node::Html(mdast::Html { value, .. }) => NodeKind::HtmlTag { value },
+            mdast::Node::FootnoteDefinition(mdast::FootnoteDefinition { identifier, .. }) => {
+                NodeKind::FootnoteDefinition { identifier }
+            }
+            mdast::Node::FootnoteReference(mdast::FootnoteReference { identifier, .. }) => {
+                NodeKind::FootnoteReference { identifier }
+            }
             // These notes will never show up because they've been disabled in the markdown_rs
             // parser options entirely. There are tests for each of these nodes to ensure the
             // inputs don't tigger the parser to create these nodes.
-            mdast::Node::FootnoteDefinition(_) => {
-                unimplemented!("FootnoteDefinition not supported")
-            }
             mdast::Node::MdxFlowExpression(MdxFlowExpression { value, .. }) => {
                 NodeKind::ExpressionBlock { value }
             }
             mdast::Node::MdxTextExpression(MdxTextExpression { value, .. }) => {
                 NodeKind::Expression { value }
             }
-            mdast::Node::FootnoteReference(_) => unimplemented!("FootnoteReference not supported"),
             mdast::Node::MdxjsEsm(_) => unimplemented!("MdxjsEsm (import tag) not supported"),
             mdast::Node::Toml(_) => unimplemented!("Toml not supported"),
             mdast::Node::Yaml(_) => unimplemented!("Yaml  not supported"),
@@ -380,6 +389,13 @@ impl Node {
 
         ensure_balanced_tables(&kind, &mut children);
 
+        if kind == NodeKind::Paragraph {
+            if let Some(items) = definition_list_items(&children) {
+                kind = NodeKind::DefinitionList;
+                children = items;
+            }
+        }
+
         let node = Node {
             children,
             pos,
@@ -417,6 +433,128 @@ fn ensure_balanced_tables(kind: &NodeKind, children: &mut Vec<Node>) {
     }
 }
 
+/// Splits a paragraph written like
+///
+/// ```markdown
+/// Term
+/// : Definition
+/// ```
+///
+/// into the terms and definitions of a definition list. CommonMark has no
+/// syntax for these, so they are parsed as a paragraph with line breaks.
+/// Returns `None` if the paragraph isn't a definition list.
+fn definition_list_items(children: &[Node]) -> Option<Vec<Node>> {
+    // Split the inline content into lines
+    let mut lines: Vec<Vec<Node>> = vec![vec![]];
+
+    for child in children {
+        match &child.kind {
+            NodeKind::Text { value } if value.contains('\n') => {
+                for (i, part) in value.split('\n').enumerate() {
+                    if i > 0 {
+                        lines.push(vec![]);
+                    }
+
+                    if !part.is_empty() {
+                        lines.last_mut().unwrap().push(Node {
+                            kind: NodeKind::Text {
+                                value: part.to_string(),
+                            },
+                            pos: child.pos.clone(),
+                            children: vec![],
+                        });
+                    }
+                }
+            }
+            _ => lines.last_mut().unwrap().push(child.clone()),
+        }
+    }
+
+    if lines.len() < 2 {
+        return None;
+    }
+
+    let mut items = vec![];
+    let mut has_definition = false;
+
+    for mut line in lines {
+        let definition = match line.first_mut().map(|n| &mut n.kind) {
+            Some(NodeKind::Text { value }) if value.starts_with(": ") => {
+                *value = value[2..].trim_start().to_string();
+                true
+            }
+            Some(_) => false,
+            None => return None,
+        };
+
+        // Every term needs at least one definition after it
+        match (definition, items.last()) {
+            (true, None) => return None,
+            (
+                false,
+                Some(Node {
+                    kind: NodeKind::DefinitionTerm,
+                    ..
+                }),
+            ) => return None,
+            _ => {}
+        }
+
+        if let Some(Node {
+            kind: NodeKind::Text { value },
+            ..
+        }) = line.last_mut()
+        {
+            *value = value.trim_end().to_string();
+        }
+
+        has_definition |= definition;
+
+        let pos = Position {
+            start: line[0].pos.start.clone(),
+            end: line[line.len() - 1].pos.end.clone(),
+        };
+
+        items.push(Node {
+            kind: if definition {
+                NodeKind::DefinitionDescription
+            } else {
+                NodeKind::DefinitionTerm
+            },
+            pos,
+            children: line,
+        });
+    }
+
+    let ends_with_definition = matches!(
+        items.last().map(|n| &n.kind),
+        Some(NodeKind::DefinitionDescription)
+    );
+
+    (has_definition && ends_with_definition).then_some(items)
+}
+
+/// Joins definition lists that are only separated by a blank line, which
+/// are parsed as separate paragraphs.
+fn merge_definition_lists(children: &mut Vec<Node>) {
+    let mut merged: Vec<Node> = Vec::with_capacity(children.len());
+
+    for child in children.drain(..) {
+        match merged.last_mut() {
+            Some(previous)
+                if previous.kind == NodeKind::DefinitionList
+                    && child.kind == NodeKind::DefinitionList =>
+            {
+                previous.pos.end = child.pos.end;
+                previous.children.extend(child.children);
+            }
+            _ => merged.push(child),
+        }
+    }
+
+    *children = merged;
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ReferenceKind {
     Shortcut,
@@ -485,6 +623,19 @@ pub enum NodeKind {
         checked: Option<bool>,
         spread: bool,
     },
+    /// `[^1]` in the text, for a footnote defined elsewhere on the page
+    FootnoteReference {
+        identifier: String,
+    },
+    /// `[^1]: Text` at the start of a line
+    FootnoteDefinition {
+        identifier: String,
+    },
+    /// Terms and definitions, written as a term followed by lines starting
+    /// with `: `
+    DefinitionList,
+    DefinitionTerm,
+    DefinitionDescription,
     Code {
         value: String,
         language: Option<String>,
@@ -548,11 +699,16 @@ pub enum NodeKind {
     Noop,
 
     /* PRIMITIVES */
-    Tabs,
+    Tabs {
+        sync_key: Option<AttributeValue>,
+    },
     Tab {
         title: Option<AttributeValue>,
     },
-    Steps,
+    Steps {
+        start: Option<AttributeValue>,
+        r#continue: Option<AttributeValue>,
+    },
     Step {
         title: Option<AttributeValue>,
     },
@@ -575,11 +731,39 @@ pub enum NodeKind {
         padding: Option<AttributeValue>,
         height: Option<AttributeValue>,
     },
+    Callout {
+        r#type: Option<AttributeValue>,
+        title: Option<AttributeValue>,
+        padding: Option<AttributeValue>,
+        collapsible: Option<AttributeValue>,
+        default_open: Option<AttributeValue>,
+    },
     Grid {
         cols: Option<AttributeValue>,
         gap: Option<AttributeValue>,
     },
-    Slot,
+    Download {
+        src: Option<AttributeValue>,
+        label: Option<AttributeValue>,
+    },
+    Embed {
+        provider: Option<AttributeValue>,
+        id: Option<AttributeValue>,
+        title: Option<AttributeValue>,
+        aspect_ratio: Option<AttributeValue>,
+        /// Any other attributes, passed on to the provider
+        params: Vec<(String, AttributeValue)>,
+    },
+    /// Where content from the call site goes in a component. Unnamed slots
+    /// get the children of the call, and named ones the matching `Fill`.
+    /// The children are used when there's nothing to put in the slot.
+    Slot {
+        name: Option<AttributeValue>,
+    },
+    /// Content for a named slot, given as a direct child of a component call
+    Fill {
+        slot: Option<AttributeValue>,
+    },
 
     // OPENAPI
     OpenAPISchema {
@@ -612,6 +796,11 @@ impl NodeKind {
             Definition { .. } => false,
             List { .. } => false,
             ListItem { .. } => false,
+            FootnoteReference { .. } => false,
+            FootnoteDefinition { .. } => false,
+            DefinitionList => false,
+            DefinitionTerm => false,
+            DefinitionDescription => false,
             Code { .. } => false,
             InlineCode { .. } => false,
             Text { .. } => false,
@@ -627,16 +816,20 @@ impl NodeKind {
             Table { .. } => false,
             Conditional(_) => false,
             Noop => false,
-            Tabs => true,
+            Tabs { .. } => true,
             Tab { .. } => true,
-            Steps => true,
+            Steps { .. } => true,
             Step { .. } => true,
             CodeSelect { .. } => true,
             Flex { .. } => true,
             Box { .. } => true,
+            Callout { .. } => true,
             Grid { .. } => true,
+            Download { .. } => true,
+            Embed { .. } => true,
             OpenAPISchema { .. } => true,
-            Slot => true,
+            Slot { .. } => true,
+            Fill { .. } => true,
             // DON'T ADD A CATCH ALL!
             // We want the compiler to warn us to update this list if a new primitive is added
         }
@@ -6,7 +6,7 @@ use crate::{
     content_ast,
     expressions::Value,
     frontmatter,
-    interpreter::Interpreter,
+    interpreter::{Interpreter, SlotContent},
     markdown::{
         custom_components::attribute::{Attribute, AttributeType, AttributeTypeValue},
         error_renderer::{self, Highlight, Location},
@@ -64,10 +64,6 @@ lazy_static! {
             include_str!("../composite_components/Card.md"),
             PathBuf::from("Card.md"),
         ),
-        CustomComponentHandle::new(
-            include_str!("../composite_components/Callout.md"),
-            PathBuf::from("Callout.md")
-        ),
         CustomComponentHandle::new(
             include_str!("../composite_components/Button.md"),
             PathBuf::from("Button.md")
@@ -105,10 +101,20 @@ pub(crate) enum Error {
     MismatchOneOf(Attribute, AttributeType, AttributeTypeValue),
     #[error("`<Slot />` can only be used in components and topics")]
     InvalidSlot(Position),
+    #[error("`<Fill>` can only be used directly inside a component or topic")]
+    InvalidFill(Position),
+    #[error("`<Fill>` is missing a `slot` attribute")]
+    MissingFillSlot(Position),
+    #[error(r#"Unknown slot "{0}""#)]
+    UnknownSlot(String, Option<String>, Position),
+    #[error(r#"Slot "{0}" is filled more than once"#)]
+    DuplicateFill(String, Position),
     #[error("Invalid title: {1}")]
     InvalidTitle(Attribute, String),
     #[error(r#"Unknown {0} "{1}""#)]
     UnknownComponent(ComponentKind, String, Position),
+    #[error(r#"Unknown {0} "{1}""#)]
+    DisabledComponent(ComponentKind, String, Position),
     #[error(r#"Stack is too deep"#)]
     RecursiveComponent(String, Position),
     #[error(r#"Unexpected attribute "{0}""#)]
@@ -146,6 +152,35 @@ impl Error {
                     msg: Some("Invalid slot".to_string()),
                 }]
             }
+            Error::InvalidFill(node_pos) | Error::MissingFillSlot(node_pos) => {
+                let location = Location::Point(node_pos.start.row, node_pos.start.col);
+
+                vec![Highlight {
+                    location,
+                    span: "<Fill".len(),
+                    msg: None,
+                }]
+            }
+            Error::UnknownSlot(_, closest, node_pos) => {
+                let location = Location::Point(node_pos.start.row, node_pos.start.col);
+
+                vec![Highlight {
+                    location,
+                    span: "<Fill".len(),
+                    msg: closest
+                        .as_ref()
+                        .map(|closest| format!(r#"Did you mean "{}"?"#, closest)),
+                }]
+            }
+            Error::DuplicateFill(_, node_pos) => {
+                let location = Location::Point(node_pos.start.row, node_pos.start.col);
+
+                vec![Highlight {
+                    location,
+                    span: "<Fill".len(),
+                    msg: Some("Already filled above".to_string()),
+                }]
+            }
             Error::InvalidTitle(attribute, _) => {
                 let mut highlights = vec![];
 
@@ -236,6 +271,15 @@ impl Error {
                     msg: None,
                 }]
             }
+            Error::DisabledComponent(_kind, found, node_pos) => {
+                let location = Location::Point(node_pos.start.row, node_pos.start.col + 1);
+
+                vec![Highlight {
+                    location,
+                    span: found.len(),
+                    msg: Some("Disabled by `components_disabled` in the frontmatter".to_string()),
+                }]
+            }
             Error::RecursiveComponent(found, node_pos) => {
                 let location = Location::Point(node_pos.start.row, node_pos.start.col + 1);
 
@@ -321,6 +365,14 @@ impl CustomComponentHandle {
         self
     }
 
+    /// Whether this is one of the components that ship with Docapella, like
+    /// `<Button>`, rather than one defined in the project.
+    pub fn is_built_in(&self) -> bool {
+        BAKED_COMPONENTS
+            .iter()
+            .any(|c| c.path == self.path && c.content == self.content)
+    }
+
     pub fn build(&self) -> Result<CustomComponent> {
         let (frontmatter, _) = frontmatter::split(&self.content);
         let mut component: CustomComponent = serde_yaml::from_str(frontmatter).map_err(|e| {
@@ -377,19 +429,56 @@ impl CustomComponentHandle {
         }
     }
 
+    /// Appends a reference to the file declaring this component to an error
+    /// description, so that usage errors point at both the call site and the
+    /// attribute declarations.
+    ///
+    /// Baked components have no declaration file in the project, so their
+    /// descriptions are returned as is.
+    pub fn with_declaration(&self, description: String) -> String {
+        match ComponentKind::from_path(&self.path) {
+            ComponentKind::Unknown => description,
+            kind => format!(
+                "{}Attributes for this {} are declared in `{}`\n",
+                description,
+                kind,
+                self.path.display()
+            ),
+        }
+    }
+
     pub fn error_lines_offset(&self) -> usize {
-        self.content[..self.error_bytes_offset()].lines().count()
+        frontmatter::end_line(&self.content)
     }
 
     pub fn error_bytes_offset(&self) -> usize {
         frontmatter::end_pos(&self.content)
     }
 
+    /// Names of the named `<Slot />`s in the component, in order
+    pub fn slot_names(&self, ctx: &RenderContext) -> Vec<String> {
+        let Ok(ast) = content_ast::build_mdx(frontmatter::without(&self.content), ctx) else {
+            return vec![];
+        };
+
+        let mut names = vec![];
+
+        for node in ast.descendants() {
+            if let content_ast::NodeKind::Slot { name: Some(name) } = &node.kind {
+                if !names.iter().any(|n| n == name.as_str()) {
+                    names.push(name.as_str().to_string());
+                }
+            }
+        }
+
+        names
+    }
+
     pub fn evaluate(
         &self,
         ctx: &RenderContext,
         attr_values: Vec<(String, Value)>,
-        slot_content: Vec<Node>,
+        slot_content: SlotContent,
         stack_depth: u8,
     ) -> crate::Result<Node> {
         let mut interpreter = Interpreter::new(ctx, frontmatter::without(&self.content));
@@ -940,96 +1029,6 @@ mod test {
         );
     }
 
-    #[test]
-    fn callout_renders_with_default() {
-        let input = indoc! {"
-        <Callout>
-            Hello **world**
-        </Callout>
-        "};
-
-        let ctx = RenderContext::default();
-        let node = &ast_mdx(input, &ctx).unwrap();
-
-        assert_str_eq!(
-            node.debug_string().unwrap(),
-            indoc! {r#"
-            <Box padding={2} max_width={full} class={d-callout d-callout-info} height={Auto}>
-                <Paragraph>
-                    <Text>
-                        Hello
-                    </Text>
-                    <Strong>
-                        <Text>
-                            world
-                        </Text>
-                    </Strong>
-                </Paragraph>
-            </Box>
-            "#}
-        );
-    }
-
-    #[test]
-    fn callout_renders_with_type() {
-        let input = indoc! {r#"
-        <Callout type="warning">
-            Hello **world**
-        </Callout>
-        "#};
-
-        let ctx = RenderContext::default();
-        let node = &ast_mdx(input, &ctx).unwrap();
-
-        assert_str_eq!(
-            node.debug_string().unwrap(),
-            indoc! {r#"
-            <Box padding={2} max_width={full} class={d-callout d-callout-warning} height={Auto}>
-                <Paragraph>
-                    <Text>
-                        Hello
-                    </Text>
-                    <Strong>
-                        <Text>
-                            world
-                        </Text>
-                    </Strong>
-                </Paragraph>
-            </Box>
-            "#}
-        );
-    }
-
-    #[test]
-    fn callout_renders_with_padding() {
-        let input = indoc! {r#"
-        <Callout pad="4">
-            Hello **world**
-        </Callout>
-        "#};
-
-        let ctx = RenderContext::default();
-        let node = &ast_mdx(input, &ctx).unwrap();
-
-        assert_str_eq!(
-            node.debug_string().unwrap(),
-            indoc! {r#"
-            <Box padding={4} max_width={full} class={d-callout d-callout-info} height={Auto}>
-                <Paragraph>
-                    <Text>
-                        Hello
-                    </Text>
-                    <Strong>
-                        <Text>
-                            world
-                        </Text>
-                    </Strong>
-                </Paragraph>
-            </Box>
-            "#}
-        );
-    }
-
     #[test]
     fn card_renders_with_custom_class() {
         let input = indoc! {r#"
@@ -2267,7 +2266,7 @@ mod test {
             assert_str_eq!(
                 err.description,
                 indoc! { r#"
-                Unexpected value. Found `nope`, expected one of devicon, lucide
+                Unexpected value. Found `nope`, expected one of devicon, lucide, custom
 
                     1 │ <Icon set="nope" name="book" />
                                    ▲▲▲▲
@@ -2298,6 +2297,24 @@ mod test {
             );
         }
 
+        #[test]
+        fn renders_custom_icons_from_assets() {
+            let markdown = indoc! {r#"
+            <Icon set="custom" name="brand" />
+            "#};
+
+            let ctx = RenderContext::new();
+            let node = &ast_mdx(markdown, &ctx).unwrap();
+
+            assert_str_eq!(
+                node.debug_string().unwrap(),
+                indoc! { r#"
+                <div if={true} role={img} aria-label={brand icon} data-d-component={Icon} data-color={false} data-size={md} class={d-icon } style={mask-image: url(/_assets/icons/brand.svg); -webkit-mask-image: url(/_assets/icons/brand.svg);}>
+                </div>
+                "# }
+            );
+        }
+
         #[test]
         fn supports_custom_class() {
             let markdown = indoc! {r#"
@@ -4,6 +4,22 @@ use itertools::Itertools;
 
 use crate::{render_context::RenderContext, renderable_ast::Position};
 
+/// Lines of a file around an error, for showing it somewhere other than a
+/// terminal, like the dev server's error page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Excerpt {
+    pub lines: Vec<ExcerptLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExcerptLine {
+    /// Line number in the file, 1-indexed
+    pub number: usize,
+    pub text: String,
+    /// Columns of the error on this line, 1-indexed, if it's on this line
+    pub highlight: Option<Range<usize>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[allow(dead_code)]
 pub(crate) enum Location {
@@ -48,10 +64,7 @@ pub(crate) fn render(
         .unwrap_or(0);
 
     for window in windows {
-        let start_line = window.start.saturating_sub(1).max(1); // Ensure we start within bounds, adjusting for zero indexing
-        let end_line = window.end.min(lines.len()); // Ensure we don't exceed the markdown content
-
-        for line_number in start_line..=end_line {
+        for line_number in window_lines(&window, lines.len()) {
             let line = format!(" {}", lines[line_number - 1]);
             result.push_str(&format!(
                 "{:5} │{}\n",
@@ -80,6 +93,62 @@ pub(crate) fn render(
     result
 }
 
+/// The lines shown for a window: one line of context before it, and never
+/// past the end of the input.
+fn window_lines(window: &Window, line_count: usize) -> std::ops::RangeInclusive<usize> {
+    let start_line = window.start.saturating_sub(1).max(1); // Ensure we start within bounds, adjusting for zero indexing
+    let end_line = window.end.min(line_count); // Ensure we don't exceed the markdown content
+
+    start_line..=end_line
+}
+
+/// The lines around `position` in `input`, with the position highlighted.
+/// `line_offset` is added to the line numbers, for inputs that start after
+/// a frontmatter.
+pub(crate) fn excerpt(input: &str, position: &Position, line_offset: usize) -> Option<Excerpt> {
+    let lines: Vec<&str> = input.lines().collect();
+
+    let start = &position.start;
+    let end = if position.is_span() && position.end.row >= start.row {
+        position.end.clone()
+    } else {
+        let mut end = start.clone();
+        end.col += 1;
+        end
+    };
+
+    if start.row == 0 || start.row > lines.len() {
+        return None;
+    }
+
+    let window = Window {
+        start: start.row,
+        end: end.row,
+    };
+
+    let lines = window_lines(&window, lines.len())
+        .map(|number| {
+            let text = lines[number - 1];
+            let line_end = text.chars().count() + 1;
+
+            let highlight = (start.row..=end.row).contains(&number).then(|| {
+                let from = if number == start.row { start.col } else { 1 };
+                let to = if number == end.row { end.col } else { line_end };
+
+                from..to.max(from + 1)
+            });
+
+            ExcerptLine {
+                number: number + line_offset,
+                text: text.to_string(),
+                highlight,
+            }
+        })
+        .collect();
+
+    Some(Excerpt { lines })
+}
+
 fn render_point(result: &mut String, line_highlights: Vec<&Highlight>) {
     let mut index = 0;
     // --------------------------------------------------------
@@ -358,4 +427,37 @@ mod tests {
         ];
         assert_eq!(compute_windows(&highlights), expected_windows);
     }
+
+    #[test]
+    fn excerpts_the_lines_around_a_position() {
+        let input = "# Title\n\nHello {@nme}\nBye";
+        let position = Position::from_byte_range(input, 15, 21);
+
+        let excerpt = excerpt(input, &position, 3).unwrap();
+
+        assert_eq!(
+            excerpt.lines,
+            vec![
+                ExcerptLine {
+                    number: 5,
+                    text: String::new(),
+                    highlight: None,
+                },
+                ExcerptLine {
+                    number: 6,
+                    text: "Hello {@nme}".to_string(),
+                    highlight: Some(7..13),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn excerpts_nothing_for_positions_outside_the_input() {
+        let mut position = Position::default();
+        position.start.row = 4;
+        position.start.col = 1;
+
+        assert_eq!(excerpt("One\nTwo", &position, 0), None);
+    }
 }
@@ -5,6 +5,7 @@
 ///
 /// Filters are responsible for their own error handling and have to return meaningful errors.
 use super::{interpreter::Value, Error, Result, Span};
+use crate::utils::closest_match;
 
 pub(crate) type ApplyFilter = fn(args: Vec<Value>, pos: Span) -> Result<Value>;
 
@@ -32,6 +33,56 @@ pub(crate) fn apply_filter(name: &str, args: Vec<Value>, pos: Span) -> Result<Va
     }
 }
 
+fn check_arity(filter: &'static str, expected: usize, args: &[Value], pos: &Span) -> Result<()> {
+    if args.len() != expected {
+        Err(Error::InvalidArity(
+            filter,
+            expected,
+            args.len(),
+            Span::new(pos.start, pos.end),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+fn invalid_argument(
+    filter: &'static str,
+    expected_type: &'static str,
+    argument_index: usize,
+    arg: &Value,
+    pos: &Span,
+) -> Error {
+    Error::InvalidArgument {
+        filter,
+        expected_type,
+        argument_index,
+        actual_value: arg.debug_string(),
+        actual_type: arg.type_name().to_owned(),
+        span: Span::new(pos.start, pos.start),
+    }
+}
+
+/// Looks up `attribute` on a list element, for filters like `map` and `filter` that operate
+/// on lists of objects.
+fn element_attribute<'a>(
+    filter: &'static str,
+    element: &'a Value,
+    attribute: &str,
+    pos: &Span,
+) -> Result<&'a Value> {
+    match element {
+        Value::Object(obj) => obj.get(attribute).ok_or(Error::InvalidDotAccess(
+            attribute.to_owned(),
+            element.type_name(),
+            element.debug_string(),
+            closest_match(attribute, obj.keys().map(|k| k.as_str())).map(|k| k.to_owned()),
+            Span::new(pos.start, pos.end),
+        )),
+        other => Err(invalid_argument(filter, "list of objects", 0, other, pos)),
+    }
+}
+
 lazy_static! {
     static ref FILTERS: Vec<Filter> = vec![
         Filter::new(
@@ -102,5 +153,120 @@ lazy_static! {
                 })
             }
         }),
+        Filter::new("contains", |args: Vec<Value>, pos: Span| -> Result<Value> {
+            check_arity("contains", 2, &args, &pos)?;
+
+            match (&args[0], &args[1]) {
+                (Value::List(list), needle) => Ok(Value::Bool(list.contains(needle))),
+                (Value::String(s), Value::String(needle)) => Ok(Value::Bool(s.contains(needle))),
+                (Value::String(_), other) => {
+                    Err(invalid_argument("contains", "string", 1, other, &pos))
+                }
+                (other, _) => Err(invalid_argument("contains", "list", 0, other, &pos)),
+            }
+        }),
+        Filter::new("map", |args: Vec<Value>, pos: Span| -> Result<Value> {
+            check_arity("map", 2, &args, &pos)?;
+
+            match (&args[0], &args[1]) {
+                (Value::List(list), Value::String(attribute)) => {
+                    let mut out = Vec::with_capacity(list.len());
+                    for element in list {
+                        out.push(element_attribute("map", element, attribute, &pos)?.clone());
+                    }
+
+                    Ok(Value::List(out))
+                }
+                (Value::List(_), other) => Err(invalid_argument("map", "string", 1, other, &pos)),
+                (other, _) => Err(invalid_argument("map", "list", 0, other, &pos)),
+            }
+        }),
+        Filter::new("filter", |args: Vec<Value>, pos: Span| -> Result<Value> {
+            check_arity("filter", 3, &args, &pos)?;
+
+            match (&args[0], &args[1]) {
+                (Value::List(list), Value::String(attribute)) => {
+                    let mut out = vec![];
+                    for element in list {
+                        if element_attribute("filter", element, attribute, &pos)? == &args[2] {
+                            out.push(element.clone());
+                        }
+                    }
+
+                    Ok(Value::List(out))
+                }
+                (Value::List(_), other) => {
+                    Err(invalid_argument("filter", "string", 1, other, &pos))
+                }
+                (other, _) => Err(invalid_argument("filter", "list", 0, other, &pos)),
+            }
+        }),
+        Filter::new("join", |args: Vec<Value>, pos: Span| -> Result<Value> {
+            check_arity("join", 2, &args, &pos)?;
+
+            match (&args[0], &args[1]) {
+                (Value::List(list), Value::String(separator)) => Ok(Value::String(
+                    list.iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(separator),
+                )),
+                (Value::List(_), other) => Err(invalid_argument("join", "string", 1, other, &pos)),
+                (other, _) => Err(invalid_argument("join", "list", 0, other, &pos)),
+            }
+        }),
+        Filter::new("size", |args: Vec<Value>, pos: Span| -> Result<Value> {
+            check_arity("size", 1, &args, &pos)?;
+
+            match &args[0] {
+                Value::List(list) => Ok(Value::Number(list.len().into())),
+                Value::Object(obj) => Ok(Value::Number(obj.len().into())),
+                Value::String(s) => Ok(Value::Number(s.chars().count().into())),
+                other => Err(invalid_argument("size", "list", 0, other, &pos)),
+            }
+        }),
+        Filter::new("sort", |args: Vec<Value>, pos: Span| -> Result<Value> {
+            check_arity("sort", 1, &args, &pos)?;
+
+            let list = match &args[0] {
+                Value::List(list) => list,
+                other => return Err(invalid_argument("sort", "list", 0, other, &pos)),
+            };
+
+            // Only homogeneous lists of numbers or strings can be sorted
+            let mut sorted = list.clone();
+            match list.first() {
+                None => {}
+                Some(Value::Number(_)) => {
+                    if let Some(other) = list.iter().find(|v| !matches!(v, Value::Number(_))) {
+                        return Err(invalid_argument("sort", "list of numbers", 0, other, &pos));
+                    }
+                    sorted.sort_by(|a, b| match (a, b) {
+                        (Value::Number(a), Value::Number(b)) => a.cmp(b),
+                        _ => unreachable!(),
+                    });
+                }
+                Some(Value::String(_)) => {
+                    if let Some(other) = list.iter().find(|v| !matches!(v, Value::String(_))) {
+                        return Err(invalid_argument("sort", "list of strings", 0, other, &pos));
+                    }
+                    sorted.sort_by(|a, b| match (a, b) {
+                        (Value::String(a), Value::String(b)) => a.cmp(b),
+                        _ => unreachable!(),
+                    });
+                }
+                Some(other) => {
+                    return Err(invalid_argument(
+                        "sort",
+                        "list of numbers or strings",
+                        0,
+                        other,
+                        &pos,
+                    ))
+                }
+            }
+
+            Ok(Value::List(sorted))
+        }),
     ];
 }
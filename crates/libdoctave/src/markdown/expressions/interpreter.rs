@@ -2,12 +2,14 @@ use std::collections::HashMap;
 use std::fmt::Display;
 
 use indexmap::IndexMap;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 
 use crate::markdown::custom_components::attribute::AttributeTypeValue;
 
 use super::parser::{Expr, ExprKind, Operator};
 use super::{filters, Error, Result, Span};
+use crate::utils::closest_match;
 
 #[derive(Debug)]
 pub struct Environment {
@@ -28,6 +30,12 @@ impl Environment {
             None
         }
     }
+
+    fn names(&self) -> impl Iterator<Item = &str> {
+        self.scopes
+            .iter()
+            .flat_map(|s| s.data.keys().map(|k| k.as_str()))
+    }
 }
 
 impl Default for Environment {
@@ -152,15 +160,47 @@ impl Display for Value {
 
 pub struct Interpreter {
     pub env: Environment,
+    /// Evaluate variables and object fields that don't exist to `null`,
+    /// instead of failing. The lookups are collected in `undefined`.
+    pub undefined_as_null: bool,
+    pub undefined: Vec<Error>,
 }
 
 impl Interpreter {
     pub fn new(env: Option<Environment>) -> Self {
         Interpreter {
             env: env.unwrap_or_default(),
+            undefined_as_null: false,
+            undefined: vec![],
         }
     }
 
+    /// Fails with the error, or records it and evaluates to `null` if
+    /// `undefined_as_null` is set.
+    fn undefined(&mut self, error: Error) -> Result<Value> {
+        if self.undefined_as_null {
+            self.undefined.push(error);
+            Ok(Value::Null)
+        } else {
+            Err(error)
+        }
+    }
+
+    fn missing_field(&mut self, field: &str, obj: &Value, span: Span) -> Result<Value> {
+        let suggestion = match obj {
+            Value::Object(map) => closest_match(field, map.keys().map(|k| k.as_str())),
+            _ => None,
+        };
+
+        self.undefined(Error::InvalidDotAccess(
+            field.to_owned(),
+            obj.type_name(),
+            obj.debug_string(),
+            suggestion.map(|s| s.to_owned()),
+            span,
+        ))
+    }
+
     pub fn interpret(&mut self, expr: Expr) -> Result<Value> {
         match expr.kind {
             ExprKind::Grouping(g) => self.interpret(*g),
@@ -206,10 +246,15 @@ impl Interpreter {
             }
             ExprKind::VariableAccess(identifier) => match self.env.lookup(identifier) {
                 Some(v) => Ok(v.clone()),
-                None => Err(Error::UnknownVariable(
-                    identifier.to_owned(),
-                    Span::new(expr.pos.start, expr.pos.end),
-                )),
+                None => {
+                    let suggestion = closest_match(identifier, self.env.names());
+
+                    self.undefined(Error::UnknownVariable(
+                        identifier.to_owned(),
+                        suggestion.map(|s| s.to_owned()),
+                        Span::new(expr.pos.start, expr.pos.end),
+                    ))
+                }
             },
             ExprKind::DotAccess(expr, identifier) => {
                 // Store offset because `interpret` moves the expr
@@ -217,23 +262,31 @@ impl Interpreter {
                 let end = expr.pos.end;
 
                 match self.interpret(*expr)? {
-                    ref obj @ Value::Object(ref v) => Ok(v
-                        .get(identifier)
-                        .ok_or(Error::InvalidDotAccess(
-                            identifier.to_owned(),
-                            obj.type_name(),
-                            obj.debug_string(),
-                            Span::new(start, end),
-                        ))?
-                        .clone()),
+                    Value::Object(ref v) if v.contains_key(identifier) => Ok(v[identifier].clone()),
+                    obj @ Value::Object(_) => {
+                        self.missing_field(identifier, &obj, Span::new(start, end))
+                    }
+                    // Something earlier in the chain was already undefined
+                    Value::Null if self.undefined_as_null => Ok(Value::Null),
                     other => Err(Error::InvalidDotAccess(
                         identifier.to_owned(),
                         other.type_name(),
                         other.debug_string(),
+                        None,
                         Span::new(start, end),
                     )),
                 }
             }
+            ExprKind::Index(expr, index) => {
+                // Store offset because `interpret` moves the expr
+                let start = expr.pos.start;
+                let end = index.pos.end;
+
+                let target = self.interpret(*expr)?;
+                let index = self.interpret(*index)?;
+
+                self.evaluate_index(target, index, Span::new(start, end))
+            }
             ExprKind::Identifier(identifier) => Err(Error::UnexpectedIdentifier(
                 identifier.to_owned(),
                 Span::new(expr.pos.start, expr.pos.end),
@@ -241,6 +294,45 @@ impl Interpreter {
         }
     }
 
+    fn evaluate_index(&mut self, target: Value, index: Value, pos: Span) -> Result<Value> {
+        match (&target, &index) {
+            (Value::List(list), Value::Number(n)) => {
+                let i = if n.fract().is_zero() {
+                    n.to_usize()
+                } else {
+                    None
+                };
+
+                match i {
+                    Some(i) => list.get(i).cloned().ok_or(Error::IndexOutOfBounds(
+                        n.to_string(),
+                        list.len(),
+                        pos,
+                    )),
+                    None => Err(Error::InvalidIndex(
+                        target.type_name(),
+                        target.debug_string(),
+                        index.debug_string(),
+                        index.type_name(),
+                        pos,
+                    )),
+                }
+            }
+            (Value::Object(obj), Value::String(key)) => match obj.get(key) {
+                Some(v) => Ok(v.clone()),
+                None => self.missing_field(key, &target, pos),
+            },
+            (Value::Null, _) if self.undefined_as_null => Ok(Value::Null),
+            _ => Err(Error::InvalidIndex(
+                target.type_name(),
+                target.debug_string(),
+                index.debug_string(),
+                index.type_name(),
+                pos,
+            )),
+        }
+    }
+
     fn evaluate_binary_op(
         &mut self,
         op: Operator,
@@ -739,6 +831,7 @@ mod test {
 
     mod variable_access {
         use super::*;
+        use crate::markdown::expressions::parse;
 
         #[test]
         fn basic() {
@@ -819,6 +912,67 @@ mod test {
                 "Could not find field `cookie` on `string` `\"bob\"`"
             );
         }
+
+        #[test]
+        fn suggests_near_misses() {
+            let mut env = Environment::default();
+            env.add_global(
+                "alice",
+                Value::Object(IndexMap::from([(
+                    "theme".to_string(),
+                    Value::String("dark".to_owned()),
+                )])),
+            );
+
+            let mut interpreter = Interpreter::new(Some(env));
+
+            let err = interpreter.interpret(parse("@alcie").unwrap()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Variable `@alcie` not found. Did you mean `@alice`?"
+            );
+
+            let err = interpreter
+                .interpret(parse("@alice.thmee").unwrap())
+                .unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Could not find field `thmee` on `object` `{ theme: \"dark\" }`. Did you mean `theme`?"
+            );
+        }
+
+        #[test]
+        fn undefined_as_null() {
+            let mut env = Environment::default();
+            env.add_global("alice", Value::Object(IndexMap::new()));
+
+            let mut interpreter = Interpreter::new(Some(env));
+            interpreter.undefined_as_null = true;
+
+            for expr in [
+                "@unknown",
+                "@alice.typo",
+                "@alice.typo.nested",
+                "@alice[\"typo\"]",
+            ] {
+                assert_eq!(
+                    interpreter.interpret(parse(expr).unwrap()).unwrap(),
+                    Value::Null,
+                    "{}",
+                    expr
+                );
+            }
+
+            assert_eq!(
+                interpreter
+                    .interpret(parse("@alice.typo == \"x\"").unwrap())
+                    .unwrap(),
+                Value::Bool(false)
+            );
+
+            // Nested lookups on something that's already undefined aren't reported again
+            assert_eq!(interpreter.undefined.len(), 5);
+        }
     }
 
     mod capitalize {
@@ -923,4 +1077,246 @@ mod test {
             )
         }
     }
+
+    fn tagged_pages() -> Environment {
+        let mut env = Environment::default();
+        env.add_global(
+            "tags",
+            Value::List(vec![
+                Value::String("beta".to_owned()),
+                Value::String("api".to_owned()),
+            ]),
+        );
+        env.add_global(
+            "items",
+            Value::List(vec![
+                Value::Object(IndexMap::from([
+                    ("name".to_string(), Value::String("Bob".to_owned())),
+                    ("role".to_string(), Value::String("admin".to_owned())),
+                ])),
+                Value::Object(IndexMap::from([
+                    ("name".to_string(), Value::String("Alice".to_owned())),
+                    ("role".to_string(), Value::String("user".to_owned())),
+                ])),
+            ]),
+        );
+
+        env
+    }
+
+    mod index_access {
+        use super::*;
+
+        #[test]
+        fn list_index() {
+            assert_eq!(
+                evaluate_with_env("@tags[1]", tagged_pages()).unwrap(),
+                Value::String("api".to_owned())
+            );
+        }
+
+        #[test]
+        fn nested_index_and_dot_access() {
+            assert_eq!(
+                evaluate_with_env("@items[1].name", tagged_pages()).unwrap(),
+                Value::String("Alice".to_owned())
+            );
+        }
+
+        #[test]
+        fn liquid_style_dot_index() {
+            assert_eq!(
+                evaluate_with_env("@items.0.name", tagged_pages()).unwrap(),
+                Value::String("Bob".to_owned())
+            );
+        }
+
+        #[test]
+        fn object_string_index() {
+            assert_eq!(
+                evaluate_with_env("@items[0][\"role\"]", tagged_pages()).unwrap(),
+                Value::String("admin".to_owned())
+            );
+        }
+
+        #[test]
+        fn out_of_bounds() {
+            let err = evaluate_with_env("@tags[5]", tagged_pages()).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "Index `5` is out of bounds for list of length 2"
+            );
+        }
+
+        #[test]
+        fn invalid_index_type() {
+            let err = evaluate_with_env("@tags[\"foo\"]", tagged_pages()).unwrap_err();
+
+            assert_eq!(
+                err.to_string(),
+                "Cannot index into `list` `[String(\"beta\"), String(\"api\")]` with `\"foo\"` of type `string`"
+            );
+        }
+    }
+
+    mod contains {
+        use super::*;
+
+        #[test]
+        fn list_contains() {
+            assert_eq!(
+                evaluate_with_env("@tags | contains(\"beta\")", tagged_pages()).unwrap(),
+                Value::Bool(true)
+            );
+            assert_eq!(
+                evaluate_with_env("@tags | contains(\"alpha\")", tagged_pages()).unwrap(),
+                Value::Bool(false)
+            );
+        }
+
+        #[test]
+        fn string_contains() {
+            assert_eq!(
+                evaluate("\"foobar\" | contains(\"oba\")").unwrap(),
+                Value::Bool(true)
+            );
+        }
+
+        #[test]
+        fn validates_first_argument() {
+            let e = evaluate("123 | contains(1)").unwrap_err();
+
+            assert_eq!(
+                &e.to_string(),
+                "Unexpected argument to filter `contains`. Expected a `list` as the first argument, found `123` with type `number`"
+            );
+        }
+    }
+
+    mod map {
+        use super::*;
+
+        #[test]
+        fn maps_attribute() {
+            assert_eq!(
+                evaluate_with_env("@items | map(\"name\")", tagged_pages()).unwrap(),
+                Value::List(vec![
+                    Value::String("Bob".to_owned()),
+                    Value::String("Alice".to_owned())
+                ])
+            );
+        }
+
+        #[test]
+        fn validates_elements_are_objects() {
+            let e = evaluate_with_env("@tags | map(\"name\")", tagged_pages()).unwrap_err();
+
+            assert_eq!(
+                &e.to_string(),
+                "Unexpected argument to filter `map`. Expected a `list of objects` as the first argument, found `\"beta\"` with type `string`"
+            );
+        }
+    }
+
+    mod filter {
+        use super::*;
+
+        #[test]
+        fn filters_by_attribute() {
+            assert_eq!(
+                evaluate_with_env(
+                    "@items | filter(\"role\", \"user\") | map(\"name\")",
+                    tagged_pages()
+                )
+                .unwrap(),
+                Value::List(vec![Value::String("Alice".to_owned())])
+            );
+        }
+
+        #[test]
+        fn validates_argument_count() {
+            let e = evaluate_with_env("@items | filter(\"role\")", tagged_pages()).unwrap_err();
+
+            assert_eq!(
+                &e.to_string(),
+                "Wrong number of arguments for filter `filter`. Expected 3 argument(s), found 2"
+            );
+        }
+    }
+
+    mod join {
+        use super::*;
+
+        #[test]
+        fn joins_list() {
+            assert_eq!(
+                evaluate("[1, \"two\", true] | join(\", \")").unwrap(),
+                Value::String("1, two, true".to_owned())
+            );
+        }
+
+        #[test]
+        fn validates_separator() {
+            let e = evaluate("[1, 2] | join(3)").unwrap_err();
+
+            assert_eq!(
+                &e.to_string(),
+                "Unexpected argument to filter `join`. Expected a `string` as the second argument, found `3` with type `number`"
+            );
+        }
+    }
+
+    mod size {
+        use super::*;
+
+        #[test]
+        fn sizes() {
+            assert_eq!(
+                evaluate_with_env("@tags | size", tagged_pages()).unwrap(),
+                Value::Number(2.into())
+            );
+            assert_eq!(evaluate("\"foo\" | size").unwrap(), Value::Number(3.into()));
+        }
+
+        #[test]
+        fn validates_argument() {
+            let e = evaluate("true | size").unwrap_err();
+
+            assert_eq!(
+                &e.to_string(),
+                "Unexpected argument to filter `size`. Expected a `list` as the first argument, found `true` with type `bool`"
+            );
+        }
+    }
+
+    mod sort {
+        use super::*;
+
+        #[test]
+        fn sorts_numbers_and_strings() {
+            assert_eq!(
+                evaluate("[3, 1, 2] | sort").unwrap(),
+                Value::List(vec![
+                    Value::Number(1.into()),
+                    Value::Number(2.into()),
+                    Value::Number(3.into())
+                ])
+            );
+            assert_eq!(
+                evaluate("[\"b\", \"c\", \"a\"] | sort | join(\"\")").unwrap(),
+                Value::String("abc".to_owned())
+            );
+        }
+
+        #[test]
+        fn rejects_mixed_lists() {
+            let e = evaluate("[3, \"a\"] | sort").unwrap_err();
+
+            assert_eq!(
+                &e.to_string(),
+                "Unexpected argument to filter `sort`. Expected a `list of numbers` as the first argument, found `\"a\"` with type `string`"
+            );
+        }
+    }
 }
@@ -52,12 +52,16 @@ pub enum Error {
     InvalidArity(&'static str, usize, usize, Span),
     #[error("Expected a filter function on right side of pipeline. Found `{0}`")]
     InvalidFilterPipeline(String, Span),
-    #[error("Could not find field `{0}` on `{1}` `{2}`")]
-    InvalidDotAccess(String, &'static str, String, Span),
+    #[error("Could not find field `{0}` on `{1}` `{2}`{}", did_you_mean(.3, ""))]
+    InvalidDotAccess(String, &'static str, String, Option<String>, Span),
+    #[error("Cannot index into `{0}` `{1}` with `{2}` of type `{3}`")]
+    InvalidIndex(&'static str, String, String, &'static str, Span),
+    #[error("Index `{0}` is out of bounds for list of length {1}")]
+    IndexOutOfBounds(String, usize, Span),
     #[error("Unknown filter `{0}`")]
     UnknownFilter(String, Span),
-    #[error("Variable `@{0}` not found")]
-    UnknownVariable(String, Span),
+    #[error("Variable `@{0}` not found{}", did_you_mean(.1, "@"))]
+    UnknownVariable(String, Option<String>, Span),
     #[error("Unexpected value. Found `{0}`, expected one of {1}")]
     UnexpectedEnum(String, String, Span),
     #[error("Unexpected type. Found `{0}`, expected `{1}`")]
@@ -83,9 +87,11 @@ impl Error {
             InvalidArgument { span: s, .. } => s,
             InvalidArity(_, _, _, s) => s,
             InvalidFilterPipeline(_, s) => s,
-            InvalidDotAccess(_, _, _, s) => s,
+            InvalidDotAccess(_, _, _, _, s) => s,
+            InvalidIndex(_, _, _, _, s) => s,
+            IndexOutOfBounds(_, _, s) => s,
             UnknownFilter(_, s) => s,
-            UnknownVariable(_, s) => s,
+            UnknownVariable(_, _, s) => s,
             UnexpectedType(_, _, s) => s,
             UnexpectedEnum(_, _, s) => s,
             FreeformError(_, s) => s,
@@ -154,6 +160,13 @@ impl Error {
     }
 }
 
+fn did_you_mean(suggestion: &Option<String>, prefix: &str) -> String {
+    match suggestion {
+        Some(s) => format!(". Did you mean `{}{}`?", prefix, s),
+        None => String::new(),
+    }
+}
+
 fn format_arg_index(index: &usize) -> String {
     match index {
         0 => "first".to_string(),
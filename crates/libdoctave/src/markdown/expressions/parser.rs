@@ -29,6 +29,7 @@ pub enum ExprKind<'a> {
     Identifier(&'a str),
     VariableAccess(&'a str),
     DotAccess(Box<Expr<'a>>, &'a str),
+    Index(Box<Expr<'a>>, Box<Expr<'a>>),
     Bool(bool),
     Null,
 }
@@ -390,7 +391,15 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_dot_access(&mut self, mut expr: Expr<'a>) -> Result<Expr<'a>> {
-        while matches!(self.peek(), Some((Token::Dot, _, _))) {
+        while matches!(
+            self.peek(),
+            Some((Token::Dot, _, _)) | Some((Token::OpenSquareBacket, _, _))
+        ) {
+            if matches!(self.peek(), Some((Token::OpenSquareBacket, _, _))) {
+                expr = self.parse_index(expr)?;
+                continue;
+            }
+
             let t = self.next().unwrap(); // Consume the dot
             match self.next() {
                 Some((Token::Identifier(property), _, e)) => {
@@ -399,6 +408,16 @@ impl<'a> Parser<'a> {
                         kind: ExprKind::DotAccess(Box::new(expr), property),
                     };
                 }
+                // Liquid style list access, e.g. `@items.0.name`
+                Some((Token::Number(i), s, e)) => {
+                    expr = Expr {
+                        pos: Span::new(t.1, e),
+                        kind: ExprKind::Index(
+                            Box::new(expr),
+                            Box::new(Expr::new(ExprKind::Number(i), Span::new(s, e))),
+                        ),
+                    };
+                }
                 Some((_unexpected_token, s, e)) => {
                     return Err(Error::UnexpectedTokenWithExpectation(
                         self.input[s..e].to_owned(),
@@ -416,6 +435,25 @@ impl<'a> Parser<'a> {
         }
         Ok(expr)
     }
+
+    fn parse_index(&mut self, expr: Expr<'a>) -> Result<Expr<'a>> {
+        // Consume the opening bracket
+        let (_, start, _) = self.next().unwrap();
+        let index = self.parse_expression()?;
+
+        match self.next() {
+            Some((Token::CloseSquareBacket, _, end)) => Ok(Expr {
+                pos: Span::new(start, end),
+                kind: ExprKind::Index(Box::new(expr), Box::new(index)),
+            }),
+            Some((_, s, e)) => Err(Error::UnexpectedTokenWithExpectation(
+                self.input[s..e].to_owned(),
+                "Expected `]` after index".to_string(),
+                Span::new(s, e),
+            )),
+            None => Err(Error::UnclosedList(Span::new(start, self.input.len()))),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -594,4 +632,58 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn index_access() {
+        let input = "@foo[0]";
+
+        let ast = parse(input).unwrap();
+
+        assert_eq!(
+            ast,
+            Expr {
+                kind: ExprKind::Index(
+                    Box::new(Expr {
+                        kind: ExprKind::VariableAccess("foo"),
+                        pos: Span::new(0, 4)
+                    }),
+                    Box::new(Expr {
+                        kind: ExprKind::Number(0.into()),
+                        pos: Span::new(5, 6)
+                    })
+                ),
+                pos: Span::new(4, 7)
+            }
+        );
+    }
+
+    #[test]
+    fn numeric_dot_access() {
+        let input = "@foo.0.bar";
+
+        let ast = parse(input).unwrap();
+
+        assert_eq!(
+            ast,
+            Expr {
+                kind: ExprKind::DotAccess(
+                    Box::new(Expr {
+                        kind: ExprKind::Index(
+                            Box::new(Expr {
+                                kind: ExprKind::VariableAccess("foo"),
+                                pos: Span::new(0, 4)
+                            }),
+                            Box::new(Expr {
+                                kind: ExprKind::Number(0.into()),
+                                pos: Span::new(5, 6)
+                            })
+                        ),
+                        pos: Span::new(4, 6)
+                    }),
+                    "bar"
+                ),
+                pos: Span::new(6, 10)
+            }
+        );
+    }
 }
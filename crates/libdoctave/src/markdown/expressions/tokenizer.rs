@@ -75,7 +75,7 @@ impl<'a> Tokenizer<'a> {
         while let Some(c) = self.peek_char() {
             if c.is_ascii_digit() {
                 self.consume_char();
-            } else if c == &'.' && !found_period {
+            } else if c == &'.' && !found_period && self.next_is_digit() {
                 self.consume_char();
                 found_period = true;
             } else {
@@ -123,6 +123,16 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Checks if the character _after_ the peeked one is a digit. Used to tell
+    /// apart a decimal point (`1.5`) from a dot access on a list index (`list.0.name`).
+    fn next_is_digit(&self) -> bool {
+        self.input
+            .as_bytes()
+            .get(self.cursor + 1)
+            .map(|b| b.is_ascii_digit())
+            .unwrap_or(false)
+    }
+
     fn consume_char(&mut self) -> Option<char> {
         if self.cursor >= self.input.len() {
             None
@@ -324,6 +334,24 @@ mod test {
         assert_eq!(tokens, vec![(Token::Unknown("\"foo"), 2, 6)]);
     }
 
+    #[test]
+    fn numeric_dot_access() {
+        let input = "items.0.name";
+
+        let tokens = Tokenizer::new(input).map(|(t, _, _)| t).collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Identifier("items"),
+                Token::Dot,
+                Token::Number(0.into()),
+                Token::Dot,
+                Token::Identifier("name"),
+            ]
+        );
+    }
+
     #[test]
     fn unknown_token() {
         let input = "%";
@@ -0,0 +1,130 @@
+//! Build-time syntax highlighting for code blocks.
+//!
+//! Turns the source of a code block into a list of tokens annotated with
+//! class names derived from the syntax scopes, so that the code can be styled
+//! without running a highlighter in the browser.
+use syntect::easy::ScopeRangeIterator;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Code blocks larger than this are left as plain text to keep builds fast.
+pub const MAX_HIGHLIGHT_BYTES: usize = 50 * 1024;
+
+lazy_static! {
+    static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HighlightToken {
+    pub text: String,
+    /// Space separated class names for the token, e.g. `keyword control rust`.
+    /// `None` for text that isn't part of any token, like whitespace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+}
+
+/// Highlights the code for the given language.
+///
+/// Returns `None` if the language is unknown, the code is too large, or the
+/// code could not be parsed, in which case the code should be shown as plain
+/// text.
+pub(crate) fn highlight(code: &str, language: Option<&str>) -> Option<Vec<HighlightToken>> {
+    if code.len() > MAX_HIGHLIGHT_BYTES {
+        return None;
+    }
+
+    let syntax = find_syntax(language?)?;
+
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut tokens: Vec<HighlightToken> = vec![];
+
+    for line in LinesWithEndings::from(code) {
+        let ops = state.parse_line(line, &SYNTAX_SET).ok()?;
+
+        for (range, op) in ScopeRangeIterator::new(&ops, line) {
+            stack.apply(op).ok()?;
+
+            if range.is_empty() {
+                continue;
+            }
+
+            let text = &line[range];
+            // The first scope is the language itself, e.g. `source.rust`, which
+            // covers the whole block. Only the scopes nested in it are interesting.
+            let class = stack.as_slice().iter().skip(1).last().map(scope_class);
+
+            match tokens.last_mut() {
+                Some(last) if last.class == class => last.text.push_str(text),
+                _ => tokens.push(HighlightToken {
+                    text: text.to_string(),
+                    class,
+                }),
+            }
+        }
+    }
+
+    Some(tokens)
+}
+
+/// Finds the syntax for a code block language. Aliases are resolved through
+/// the same mapping we use for display names, e.g. `js` => `JavaScript`,
+/// before falling back to file extensions such as `rs` or `py`.
+fn find_syntax(language: &str) -> Option<&'static SyntaxReference> {
+    let pretty_name = crate::pretty_language_name(language);
+
+    SYNTAX_SET
+        .find_syntax_by_name(&pretty_name)
+        .or_else(|| SYNTAX_SET.find_syntax_by_token(language))
+        .filter(|syntax| syntax.name != "Plain Text")
+}
+
+fn scope_class(scope: &Scope) -> String {
+    scope.build_string().replace('.', " ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn joined(tokens: &[HighlightToken]) -> String {
+        tokens.iter().map(|t| t.text.as_str()).collect()
+    }
+
+    #[test]
+    fn highlights_known_languages() {
+        let code = "fn main() {\n    let x = 1;\n}\n";
+        let tokens = highlight(code, Some("rust")).unwrap();
+
+        assert_eq!(joined(&tokens), code);
+        assert!(tokens
+            .iter()
+            .any(|t| t.text == "fn" && t.class.as_deref().unwrap_or("").contains("storage")));
+    }
+
+    #[test]
+    fn resolves_language_aliases() {
+        let tokens = highlight("const x = 1;", Some("js")).unwrap();
+
+        assert_eq!(joined(&tokens), "const x = 1;");
+        assert!(tokens.iter().any(|t| t.class.is_some()));
+    }
+
+    #[test]
+    fn unknown_languages_are_plain_text() {
+        assert_eq!(highlight("foo bar", Some("not-a-language")), None);
+        assert_eq!(highlight("foo bar", None), None);
+    }
+
+    #[test]
+    fn skips_highlighting_above_size_threshold() {
+        let line = "let x = 1;\n";
+        let code = line.repeat(MAX_HIGHLIGHT_BYTES / line.len() + 1);
+        assert!(code.len() > MAX_HIGHLIGHT_BYTES);
+
+        assert_eq!(highlight(&code, Some("rust")), None);
+
+        let code = line.repeat(MAX_HIGHLIGHT_BYTES / line.len());
+        assert!(highlight(&code, Some("rust")).is_some());
+    }
+}
@@ -2,19 +2,29 @@ use rust_decimal::Decimal;
 
 use crate::{
     attribute_parser::parse_attributes,
+    canonical_path::canonicalize,
     content_ast::{Node as ContentNode, NodeKind as ContentNodeKind},
     control_flow::conditional::Conditional,
     expressions::{Environment, Value},
+    inline_svg::{self, MAX_INLINE_SVG_BYTES},
     markdown::{
-        custom_components::custom_component::Error as ComponentError,
-        expressions::Interpreter as ExprInterpreter, sanitizer::SANITIZER,
+        custom_components::custom_component::{CustomComponentHandle, Error as ComponentError},
+        expressions::Interpreter as ExprInterpreter,
+        sanitizer::SANITIZER,
+    },
+    open_api::ast::{SchemaAst, SchemaLocation},
+    primitive_components::{
+        callout::Error as CalloutError,
+        download::{asset_path, FileInfo},
+        CBox, Callout, CodeSelect, Download, Embed, Flex, Grid, Step, Steps, Tab, Tabs,
     },
-    open_api::ast::SchemaAst,
-    primitive_components::{CBox, CodeSelect, Flex, Grid, Step, Steps, Tab, Tabs},
     render_context::{FileContext, RenderContext},
-    renderable_ast::{Node, NodeKind, Position},
-    utils::capitalize,
-    Anchorizer, AttributeValue, CustomComponent, Error,
+    renderable_ast::{ImageAttributes, LinkAttributes, Node, NodeKind, Position},
+    settings::HtmlPolicy,
+    slug::slugify,
+    statistics::format_bytes,
+    utils::{capitalize, closest_match},
+    Anchorizer, AttributeValue, CustomComponent, Error, UndefinedVariables,
 };
 /// The interpreter that turns a Content AST that describes _what_ is in the
 /// document into a renderable AST.
@@ -25,12 +35,13 @@ use crate::{
 /// - Evaluating any expressions
 use crate::{Attribute, Result};
 
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, ops::Range, path::Path, str::FromStr};
 
 use super::{
+    api_links, attribute_block, console,
     custom_components::custom_component::ComponentKind,
     error_renderer::{self, Highlight, Location},
-    expressions,
+    expressions, highlight, typography,
 };
 
 pub(crate) struct Interpreter<'a> {
@@ -38,13 +49,34 @@ pub(crate) struct Interpreter<'a> {
     state: ConversionState,
     input: &'a str,
     pub expr_interpreter: ExprInterpreter,
-    pub slot_injection: Option<Vec<Node>>,
+    pub slot_injection: Option<SlotContent>,
     pub stack_depth: u8,
 }
 
+/// Content from the call site of a component, to be placed in its slots
+#[derive(Debug, Default)]
+pub(crate) struct SlotContent {
+    /// Children of the call other than fills, if there were any
+    pub default: Option<Vec<Node>>,
+    /// Rendered `<Fill>`s by slot name
+    pub fills: HashMap<String, Vec<Node>>,
+}
+
 impl<'a> Interpreter<'a> {
     pub fn new(ctx: &'a RenderContext, input: &'a str) -> Self {
-        let expr_interpreter = ExprInterpreter::new(Some(Environment::default()));
+        let mut env = Environment::default();
+        // Pages set `@vars` for their own locale in the globals
+        env.add_global(
+            crate::variables::GLOBAL,
+            crate::variables::value(ctx.settings, ctx.options.locale.as_deref()),
+        );
+        for (key, val) in &ctx.expression_globals {
+            env.add_global(key, val.clone());
+        }
+
+        let mut expr_interpreter = ExprInterpreter::new(Some(env));
+        expr_interpreter.undefined_as_null =
+            ctx.options.undefined_variables != UndefinedVariables::Error;
 
         Interpreter {
             ctx,
@@ -59,7 +91,21 @@ impl<'a> Interpreter<'a> {
     pub fn interpret(&mut self, content_ast: ContentNode) -> Result<Node> {
         self.find_references(&content_ast);
 
-        if let Some(root) = self.walk(content_ast)? {
+        if let Some(mut root) = self.walk(content_ast)? {
+            if let Some(footnotes) = self.take_footnotes() {
+                root.children.push(footnotes);
+            }
+
+            let markdown = self.ctx.settings.markdown();
+            if markdown.hard_breaks {
+                typography::hard_breaks(&mut root);
+            }
+            if markdown.smart_punctuation {
+                typography::smart_punctuation(&mut root);
+            }
+
+            api_links::link_code(&mut root, self.ctx);
+
             Ok(root)
         } else {
             unreachable!("BUG: Root of AST was not a `Root` node")
@@ -141,9 +187,15 @@ impl<'a> Interpreter<'a> {
             }
             ContentNodeKind::Link { url, title } => {
                 let children = self.render_children(children)?;
+                let file = FileInfo::for_link(&url, self.ctx);
 
                 Ok(Some(Node {
-                    kind: NodeKind::Link { url, title },
+                    kind: NodeKind::Link {
+                        url,
+                        title,
+                        file,
+                        attributes: LinkAttributes::default(),
+                    },
                     children,
                     pos,
                 }))
@@ -157,6 +209,8 @@ impl<'a> Interpreter<'a> {
                         kind: NodeKind::Link {
                             url: url.clone(),
                             title: title.clone(),
+                            file: FileInfo::for_link(url, self.ctx),
+                            attributes: LinkAttributes::default(),
                         },
                         children,
                         pos,
@@ -166,6 +220,8 @@ impl<'a> Interpreter<'a> {
                         kind: NodeKind::Link {
                             url: "".to_string(),
                             title: None,
+                            file: None,
+                            attributes: LinkAttributes::default(),
                         },
                         children,
                         pos,
@@ -175,8 +231,28 @@ impl<'a> Interpreter<'a> {
             ContentNodeKind::Image { url, title, alt } => {
                 let children = self.render_children(children)?;
 
+                let url = match inline_svg::strip_inline_flag(&url) {
+                    Some(src) => match self.inline_svg(&src, &pos) {
+                        Some(html) => {
+                            return Ok(Some(Node {
+                                kind: NodeKind::InlineSvg { src, alt, html },
+                                children,
+                                pos,
+                            }))
+                        }
+                        None => src,
+                    },
+                    None => url,
+                };
+
                 Ok(Some(Node {
-                    kind: NodeKind::Image { url, title, alt },
+                    kind: NodeKind::Image {
+                        url,
+                        title,
+                        alt,
+                        responsive: None,
+                        attributes: ImageAttributes::default(),
+                    },
                     children,
                     pos,
                 }))
@@ -192,6 +268,8 @@ impl<'a> Interpreter<'a> {
                     description: e.render(self.input, self.ctx, &pos),
                     file: None,
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })?;
 
                 let kind = NodeKind::Tab(tab);
@@ -202,7 +280,8 @@ impl<'a> Interpreter<'a> {
                     pos,
                 }))
             }
-            ContentNodeKind::Tabs => {
+            ContentNodeKind::Tabs { sync_key } => {
+                let heading = self.state.heading.clone();
                 let mut children = self.render_children(children)?;
 
                 if children.len() == 1
@@ -218,19 +297,45 @@ impl<'a> Interpreter<'a> {
                         description: e.render(self.input, self.ctx, &next.pos),
                         file: None,
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })?;
                 }
 
+                let sync_key = self.evaluate_option_value(sync_key, &pos)?;
+
+                let mut tabs = Tabs::try_new(sync_key).map_err(|e| Error {
+                    code: Error::INVALID_TABS,
+                    message: "Error in tabs".to_string(),
+                    description: e.render(self.input, self.ctx, &pos),
+                    file: None,
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                })?;
+
+                tabs.assign_ids(
+                    &mut children,
+                    &mut self.state.anchorizer,
+                    heading.as_deref(),
+                );
+
                 let component = Node {
-                    kind: NodeKind::Tabs,
+                    kind: NodeKind::Tabs(tabs),
                     children,
                     pos,
                 };
 
                 Ok(Some(component))
             }
-            ContentNodeKind::Steps => {
-                let mut children = self.render_children(children)?;
+            ContentNodeKind::Steps { start, r#continue } => {
+                let depth = self.state.steps_depth;
+                let heading = self.state.heading.clone();
+
+                self.state.steps_depth += 1;
+                let children = self.render_children(children);
+                self.state.steps_depth -= 1;
+                let mut children = children?;
 
                 if children.len() == 1
                     && matches!(children[0].kind, NodeKind::Paragraph | NodeKind::Root)
@@ -240,14 +345,44 @@ impl<'a> Interpreter<'a> {
 
                 for next in &children {
                     Steps::verify(next).map_err(|e| Error {
-                        code: Error::INVALID_TABS,
+                        code: Error::INVALID_STEPS,
                         message: "Error in steps".to_string(),
                         description: e.render(self.input, self.ctx, &next.pos),
                         file: None,
-                        position: None,
+                        position: Some(next.pos.clone()),
+                        owner: None,
+                        suggestions: vec![],
                     })?;
                 }
 
+                let start = self.evaluate_option_value(start, &pos)?;
+                let r#continue = self.evaluate_option_value(r#continue, &pos)?;
+
+                let first =
+                    Steps::first_number(start, r#continue, self.state.last_step_number, depth)
+                        .map_err(|e| Error {
+                            code: Error::INVALID_STEPS,
+                            message: "Error in steps".to_string(),
+                            description: e.render(self.input, self.ctx, &pos),
+                            file: None,
+                            position: Some(pos.clone()),
+                            owner: None,
+                            suggestions: vec![],
+                        })?;
+
+                let last = Steps::number(&mut children, first);
+                Steps::assign_ids(
+                    &mut children,
+                    &mut self.state.anchorizer,
+                    heading.as_deref(),
+                );
+
+                // Only top level blocks can be continued, so nested blocks
+                // don't affect the numbering of the next block.
+                if depth == 0 {
+                    self.state.last_step_number = last;
+                }
+
                 let steps = Node {
                     kind: NodeKind::Steps,
                     children,
@@ -267,6 +402,8 @@ impl<'a> Interpreter<'a> {
                     description: e.render(self.input, self.ctx, &pos),
                     file: None,
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })?;
 
                 let kind = NodeKind::Step(step);
@@ -289,6 +426,8 @@ impl<'a> Interpreter<'a> {
                             url: url.clone(),
                             title: title.clone(),
                             alt,
+                            responsive: None,
+                            attributes: ImageAttributes::default(),
                         },
                         children,
                         pos,
@@ -299,6 +438,8 @@ impl<'a> Interpreter<'a> {
                             url: "".to_string(),
                             title: None,
                             alt,
+                            responsive: None,
+                            attributes: ImageAttributes::default(),
                         },
                         children,
                         pos,
@@ -325,8 +466,88 @@ impl<'a> Interpreter<'a> {
             ContentNodeKind::ListItem { checked, spread } => {
                 let children = self.render_children(children)?;
 
+                let kind = match checked {
+                    Some(checked) => NodeKind::TaskListItem { checked, spread },
+                    None => NodeKind::ListItem { spread },
+                };
+
                 Ok(Some(Node {
-                    kind: NodeKind::ListItem { checked, spread },
+                    kind,
+                    children,
+                    pos,
+                }))
+            }
+            ContentNodeKind::FootnoteReference { identifier } => {
+                let slug = slugify(&identifier);
+
+                let number = match self
+                    .state
+                    .footnote_order
+                    .iter()
+                    .position(|i| i == &identifier)
+                {
+                    Some(index) => index + 1,
+                    None => {
+                        self.state.footnote_order.push(identifier.clone());
+                        self.state.footnote_order.len()
+                    }
+                };
+
+                let references = self
+                    .state
+                    .footnote_references
+                    .entry(identifier)
+                    .or_default();
+
+                let id = if references.is_empty() {
+                    format!("fnref-{}", slug)
+                } else {
+                    format!("fnref-{}-{}", slug, references.len() + 1)
+                };
+                references.push(id.clone());
+
+                Ok(Some(Node {
+                    kind: NodeKind::FootnoteReference {
+                        number,
+                        id,
+                        definition_id: format!("fn-{}", slug),
+                    },
+                    children: vec![],
+                    pos,
+                }))
+            }
+            ContentNodeKind::FootnoteDefinition { identifier } => {
+                // Moved to the end of the page once every reference is known
+                let children = self.render_children(children)?;
+                self.state
+                    .footnote_definitions
+                    .insert(identifier, (children, pos));
+
+                Ok(None)
+            }
+            ContentNodeKind::DefinitionList => {
+                let children = self.render_children(children)?;
+
+                Ok(Some(Node {
+                    kind: NodeKind::DefinitionList,
+                    children,
+                    pos,
+                }))
+            }
+            ContentNodeKind::DefinitionTerm => {
+                let children = self.render_children(children)?;
+
+                Ok(Some(Node {
+                    kind: NodeKind::DefinitionTerm,
+                    children,
+                    pos,
+                }))
+            }
+            ContentNodeKind::DefinitionDescription => {
+                let children = self.render_children(children)?;
+
+                Ok(Some(Node {
+                    kind: NodeKind::DefinitionDescription,
                     children,
                     pos,
                 }))
@@ -363,6 +584,19 @@ impl<'a> Interpreter<'a> {
                     }
                 }
 
+                let (value, console) = if console::is_console_language(language.as_deref()) {
+                    let (value, session) = console::parse(&value);
+                    (value, Some(session))
+                } else {
+                    (value, None)
+                };
+
+                let highlighted = if self.ctx.options.highlight_code {
+                    highlight::highlight(&value, language.as_deref())
+                } else {
+                    None
+                };
+
                 Ok(Some(Node {
                     kind: NodeKind::Code {
                         value,
@@ -372,12 +606,15 @@ impl<'a> Interpreter<'a> {
                         raw,
                         show_whitespace,
                         rendered_value: None,
+                        highlighted,
+                        console,
                     },
                     children,
                     pos,
                 }))
             }
             ContentNodeKind::CodeSelect { title } => {
+                let heading = self.state.heading.clone();
                 let mut children = self.render_children(children)?;
 
                 let title = self.evaluate_option_value(title, &pos)?;
@@ -390,14 +627,23 @@ impl<'a> Interpreter<'a> {
                     description: e.render(self.input, self.ctx, &pos),
                     file: None,
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })?;
 
                 // Unwrap lone codetabs as regular code block
                 if children.len() == 1 {
                     Ok(Some(children.remove(0)))
                 } else {
+                    let mut code_select = CodeSelect::default();
+                    code_select.assign_ids(
+                        &children,
+                        &mut self.state.anchorizer,
+                        heading.as_deref(),
+                    );
+
                     Ok(Some(Node {
-                        kind: NodeKind::CodeSelect,
+                        kind: NodeKind::CodeSelect(code_select),
                         children,
                         pos,
                     }))
@@ -415,6 +661,8 @@ impl<'a> Interpreter<'a> {
             ContentNodeKind::Text { value } => {
                 let children = self.render_children(children)?;
 
+                self.report_undefined_footnotes(&value, &pos);
+
                 Ok(Some(Node {
                     kind: NodeKind::Text { value },
                     children,
@@ -430,6 +678,7 @@ impl<'a> Interpreter<'a> {
                     kind: NodeKind::Heading {
                         level,
                         slug: String::new(),
+                        anchor_link: self.ctx.settings.markdown().heading_anchor_links,
                     },
                     children,
                     pos,
@@ -437,6 +686,8 @@ impl<'a> Interpreter<'a> {
 
                 let anchorized_slug = self.state.anchorizer.anchorize(node.inner_text());
 
+                self.state.heading = Some(anchorized_slug.clone());
+
                 if let NodeKind::Heading { ref mut slug, .. } = &mut node.kind {
                     *slug = anchorized_slug;
                 }
@@ -457,6 +708,23 @@ impl<'a> Interpreter<'a> {
                 }
             }
             ContentNodeKind::Component { name, attributes } => {
+                if self.ctx.disabled_components.contains(&name) {
+                    return Err(Error {
+                        code: Error::INVALID_COMPONENT,
+                        message: format!("Unknown component {}", &name),
+                        description: ComponentError::DisabledComponent(
+                            ComponentKind::from_name(&name),
+                            name,
+                            pos.clone(),
+                        )
+                        .render(self.input, self.ctx),
+                        file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+
                 if let Some(handle) = self
                     .ctx
                     .custom_components
@@ -472,6 +740,8 @@ impl<'a> Interpreter<'a> {
                                 .render(self.input, self.ctx),
                             file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
                             position: None,
+                            owner: None,
+                            suggestions: vec![],
                         });
                     }
 
@@ -487,19 +757,23 @@ impl<'a> Interpreter<'a> {
                         description: e.render(&handle.content, self.ctx),
                         file: Some(handle.path.clone()),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })?;
 
                     let attribute_values =
-                        self.resolve_attributes(&c, attributes.as_slice(), &pos)?;
+                        self.resolve_attributes(&c, handle, attributes.as_slice(), &pos)?;
 
-                    let mut slot_content = self.render_children(children)?;
+                    let mut slot_content = self.render_slot_content(handle, &name, children)?;
 
-                    if handle.unwrap_lone_p
-                        && slot_content.len() == 1
-                        && matches!(slot_content[0].kind, NodeKind::Paragraph)
-                    {
-                        let p_children = slot_content[0].children.drain(..);
-                        slot_content = p_children.collect();
+                    if let Some(default) = slot_content.default.as_mut() {
+                        if handle.unwrap_lone_p
+                            && default.len() == 1
+                            && matches!(default[0].kind, NodeKind::Paragraph)
+                        {
+                            let p_children = default[0].children.drain(..);
+                            *default = p_children.collect();
+                        }
                     }
 
                     let component_ctx = RenderContext {
@@ -508,6 +782,11 @@ impl<'a> Interpreter<'a> {
                             handle.error_bytes_offset(),
                             handle.path.clone(),
                         )),
+                        in_built_in_component: self.ctx.in_built_in_component
+                            || handle.is_built_in(),
+                        // Only the page's own usages are disabled, not the
+                        // ones inside the components it uses
+                        disabled_components: vec![],
                         ..self.ctx.clone()
                     };
 
@@ -533,14 +812,35 @@ impl<'a> Interpreter<'a> {
                         .render(self.input, self.ctx),
                         file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })
                 }
             }
-            ContentNodeKind::Slot => {
+            ContentNodeKind::Slot { name } => {
                 if self.is_custom_component() {
+                    let name = name.map(|n| n.as_str().to_string());
+
+                    let injected = self.slot_injection.as_mut().and_then(|s| match &name {
+                        Some(name) => s.fills.remove(name),
+                        None => s.default.take(),
+                    });
+
+                    let filled = injected.is_some();
+                    let children = match injected {
+                        Some(injected) => injected,
+                        None => self.render_children(children)?,
+                    };
+
+                    let kind = if self.ctx.options.debug_slots {
+                        NodeKind::Slot { name, filled }
+                    } else {
+                        NodeKind::Root
+                    };
+
                     Ok(Some(Node {
-                        kind: NodeKind::Root,
-                        children: self.slot_injection.take().unwrap_or_default(),
+                        kind,
+                        children,
                         pos,
                     }))
                 } else {
@@ -551,18 +851,109 @@ impl<'a> Interpreter<'a> {
                             .render(self.input, self.ctx),
                         file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })
                 }
             }
+            ContentNodeKind::Fill { .. } => Err(Error {
+                code: Error::INVALID_COMPONENT,
+                message: String::from("Invalid fill"),
+                description: ComponentError::InvalidFill(pos.clone()).render(self.input, self.ctx),
+                file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                position: Some(pos),
+                owner: None,
+                suggestions: vec![],
+            }),
             ContentNodeKind::HtmlBlock { name, attributes } => {
                 let children = self.render_children(children)?;
                 let mut resolved_attributes = self.evaluate_attributes(attributes, &pos)?;
 
-                if SANITIZER
-                    .sanitize_html_block(&name, &mut resolved_attributes)
-                    .is_none()
-                {
-                    return Ok(None);
+                let policy = if self.ctx.in_built_in_component {
+                    HtmlPolicy::Sanitize
+                } else {
+                    self.ctx.settings.html_policy()
+                };
+
+                match policy {
+                    HtmlPolicy::Allow => {}
+                    HtmlPolicy::Sanitize => {
+                        match SANITIZER.sanitize_html_block(&name, &mut resolved_attributes) {
+                            Some(removed_attributes) => {
+                                for attribute in removed_attributes {
+                                    self.report_unsafe_html(
+                                        format!("Removed `{attribute}` attribute from `<{name}>`"),
+                                        &name,
+                                        &pos,
+                                    );
+                                }
+                            }
+                            None => {
+                                self.report_unsafe_html(
+                                    format!("Removed `<{name}>` element"),
+                                    &name,
+                                    &pos,
+                                );
+                                return Ok(None);
+                            }
+                        }
+                    }
+                    HtmlPolicy::Strip => {
+                        // Scripts and styles are code rather than text, so
+                        // nothing inside them is kept
+                        if matches!(name.as_str(), "script" | "style") {
+                            return Ok(None);
+                        }
+
+                        return Ok(Some(Node {
+                            kind: NodeKind::Root,
+                            children,
+                            pos,
+                        }));
+                    }
+                }
+
+                if name == "img" {
+                    let src = resolved_attributes
+                        .iter()
+                        .find(|a| a.key == "src")
+                        .and_then(|a| a.value.as_ref())
+                        .and_then(|src| inline_svg::strip_inline_flag(src.as_str()));
+
+                    if let Some(src) = src {
+                        if let Some(html) = self.inline_svg(&src, &pos) {
+                            let alt = resolved_attributes
+                                .iter()
+                                .find(|a| a.key == "alt")
+                                .and_then(|a| a.value.as_ref())
+                                .map(|alt| alt.as_str().to_string())
+                                .unwrap_or_default();
+
+                            return Ok(Some(Node {
+                                kind: NodeKind::InlineSvg { src, alt, html },
+                                children,
+                                pos,
+                            }));
+                        }
+
+                        for attribute in resolved_attributes.iter_mut() {
+                            if attribute.key == "src" {
+                                attribute.value = Some(AttributeValue::Literal(src.clone()));
+                            }
+                        }
+                    }
+                }
+
+                if name == "a" && resolved_attributes.iter().any(|a| a.key == "download") {
+                    let file = resolved_attributes
+                        .iter()
+                        .find(|a| a.key == "href")
+                        .and_then(|a| a.value.as_ref())
+                        .and_then(|href| FileInfo::for_link(href.as_str(), self.ctx));
+
+                    if let Some(file) = file {
+                        resolved_attributes.extend(file.html_attributes());
+                    }
                 }
 
                 Ok(Some(Node {
@@ -611,42 +1002,52 @@ impl<'a> Interpreter<'a> {
                 }))
             }
             ContentNodeKind::Definition { .. } => Ok(None),
-            ContentNodeKind::Expression { ref value } => Ok(Some(Node {
-                kind: NodeKind::Text {
-                    value: self
-                        .evaluate_expr(value.as_str())
-                        .map(|val| val.to_string())
-                        .map_err(|e| Error {
-                            code: Error::INVALID_EXPRESSION,
-                            message: "Error in expression".to_string(),
-                            description: e.render(self.input, self.ctx, None, None, &pos),
-                            file: None,
-                            position: None,
-                        })?,
-                },
-                children: vec![],
-                pos,
-            })),
-            ContentNodeKind::ExpressionBlock { ref value } => Ok(Some(Node {
-                kind: NodeKind::Paragraph,
-                children: vec![Node {
-                    kind: NodeKind::Text {
-                        value: self
-                            .evaluate_expr(value.as_str())
-                            .map(|val| val.to_string())
-                            .map_err(|e| Error {
-                                code: Error::INVALID_EXPRESSION,
-                                message: "Error in expression".to_string(),
-                                description: e.render(self.input, self.ctx, None, None, &pos),
-                                file: None,
-                                position: None,
-                            })?,
-                    },
+            ContentNodeKind::Expression { ref value } => {
+                let value = self
+                    .evaluate_expr(value.as_str())
+                    .map(|val| val.to_string())
+                    .map_err(|e| Error {
+                        code: Error::INVALID_EXPRESSION,
+                        message: "Error in expression".to_string(),
+                        description: e.render(self.input, self.ctx, None, None, &pos),
+                        file: None,
+                        position: Some(pos.clone()),
+                        owner: None,
+                        suggestions: vec![],
+                    })?;
+                self.report_undefined(None, None, &pos);
+
+                Ok(Some(Node {
+                    kind: NodeKind::Text { value },
                     children: vec![],
-                    pos: pos.clone(),
-                }],
-                pos,
-            })),
+                    pos,
+                }))
+            }
+            ContentNodeKind::ExpressionBlock { ref value } => {
+                let value = self
+                    .evaluate_expr(value.as_str())
+                    .map(|val| val.to_string())
+                    .map_err(|e| Error {
+                        code: Error::INVALID_EXPRESSION,
+                        message: "Error in expression".to_string(),
+                        description: e.render(self.input, self.ctx, None, None, &pos),
+                        file: None,
+                        position: Some(pos.clone()),
+                        owner: None,
+                        suggestions: vec![],
+                    })?;
+                self.report_undefined(None, None, &pos);
+
+                Ok(Some(Node {
+                    kind: NodeKind::Paragraph,
+                    children: vec![Node {
+                        kind: NodeKind::Text { value },
+                        children: vec![],
+                        pos: pos.clone(),
+                    }],
+                    pos,
+                }))
+            }
             ContentNodeKind::Conditional(Conditional {
                 true_branch,
                 false_branch,
@@ -661,7 +1062,10 @@ impl<'a> Interpreter<'a> {
                     description: e.render(self.input, self.ctx, None, None, &pos),
                     file: None,
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })?;
+                self.report_undefined(None, None, &pos);
 
                 if val.is_truthy() {
                     self.walk(*true_branch)
@@ -697,7 +1101,9 @@ impl<'a> Interpreter<'a> {
                             message: "Error in flex".to_string(),
                             description: e.render(self.input, self.ctx, &pos),
                             file: None,
-                            position: None,
+                            position: Some(e.pos(self.input, &pos).unwrap_or_else(|| pos.clone())),
+                            owner: None,
+                            suggestions: vec![],
                         })?;
 
                 Ok(Some(Node {
@@ -726,6 +1132,8 @@ impl<'a> Interpreter<'a> {
                         description: e.render(self.input, self.ctx, &pos),
                         file: None,
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })?;
 
                 Ok(Some(Node {
@@ -734,6 +1142,119 @@ impl<'a> Interpreter<'a> {
                     children,
                 }))
             }
+            ContentNodeKind::Callout {
+                r#type,
+                title,
+                padding,
+                collapsible,
+                default_open,
+            } => {
+                if self.state.in_callout {
+                    return Err(Error {
+                        code: Error::INVALID_COMPONENT,
+                        message: "Error in callout".to_string(),
+                        description: CalloutError::Nested.render(self.input, self.ctx, &pos),
+                        file: None,
+                        position: Some(pos.clone()),
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+
+                let heading = self.state.heading.clone();
+
+                self.state.in_callout = true;
+                let children = self.render_children(children);
+                self.state.in_callout = false;
+                let children = children?;
+
+                let r#type = self.evaluate_option_value(r#type, &pos)?;
+                let title = self.evaluate_option_value(title, &pos)?;
+                let padding = self.evaluate_option_value(padding, &pos)?;
+                let collapsible = self.evaluate_option_value(collapsible, &pos)?;
+                let default_open = self.evaluate_option_value(default_open, &pos)?;
+
+                let mut callout =
+                    Callout::try_new(r#type, title, padding, collapsible, default_open).map_err(
+                        |e| Error {
+                            code: Error::INVALID_COMPONENT,
+                            message: "Error in callout".to_string(),
+                            description: e.render(self.input, self.ctx, &pos),
+                            file: None,
+                            position: Some(pos.clone()),
+                            owner: None,
+                            suggestions: vec![],
+                        },
+                    )?;
+
+                callout.assign_id(&mut self.state.anchorizer, heading.as_deref());
+
+                Ok(Some(Node {
+                    kind: NodeKind::Callout(callout),
+                    pos,
+                    children,
+                }))
+            }
+            ContentNodeKind::Download { src, label } => {
+                let children = self.render_children(children)?;
+
+                let src = self.evaluate_option_value(src, &pos)?;
+                let label = self.evaluate_option_value(label, &pos)?;
+
+                let download = Download::try_new(src, label, self.ctx).map_err(|e| Error {
+                    code: Error::INVALID_COMPONENT,
+                    message: "Error in download".to_string(),
+                    description: e.render(self.input, self.ctx, &pos),
+                    file: None,
+                    position: Some(pos.clone()),
+                    owner: None,
+                    suggestions: vec![],
+                })?;
+
+                Ok(Some(Node {
+                    kind: NodeKind::Download(download),
+                    pos,
+                    children,
+                }))
+            }
+            ContentNodeKind::Embed {
+                provider,
+                id,
+                title,
+                aspect_ratio,
+                params,
+            } => {
+                let children = self.render_children(children)?;
+
+                let provider = self.evaluate_option_value(provider, &pos)?;
+                let id = self.evaluate_option_value(id, &pos)?;
+                let title = self.evaluate_option_value(title, &pos)?;
+                let aspect_ratio = self.evaluate_option_value(aspect_ratio, &pos)?;
+
+                let mut values = vec![];
+                for (key, value) in params {
+                    if let Some(value) = self.evaluate_option_value(Some(value), &pos)? {
+                        values.push((key, value));
+                    }
+                }
+
+                let embed = Embed::try_new(provider, id, title, aspect_ratio, values, self.ctx)
+                    .map_err(|e| Error {
+                        code: Error::INVALID_COMPONENT,
+                        message: "Error in embed".to_string(),
+                        description: e.render(self.input, self.ctx, &pos),
+                        file: None,
+                        position: Some(pos.clone()),
+                        owner: None,
+                        suggestions: vec![],
+                    })?;
+
+                Ok(Some(Node {
+                    kind: NodeKind::Embed(embed),
+                    pos,
+                    children,
+                }))
+            }
             ContentNodeKind::Grid { gap, cols } => {
                 let children = self.render_children(children)?;
 
@@ -745,7 +1266,9 @@ impl<'a> Interpreter<'a> {
                     message: "Error in grid".to_string(),
                     description: e.render(self.input, self.ctx, &pos),
                     file: None,
-                    position: None,
+                    position: Some(e.pos(self.input, &pos)),
+                    owner: None,
+                    suggestions: vec![],
                 })?;
 
                 Ok(Some(Node {
@@ -808,6 +1331,19 @@ impl<'a> Interpreter<'a> {
                 let openapi_path = self.evaluate_option_value(openapi_path, &pos)?;
                 let title = self.evaluate_option_value(title, &pos)?;
 
+                let location = openapi_path
+                    .as_ref()
+                    .zip(title.as_ref())
+                    .map(|(path, title)| {
+                        let spec = path.to_string();
+                        let max_depth = self.ctx.settings.max_schema_depth(Path::new(&spec));
+
+                        SchemaLocation::root(spec, max_depth)
+                            .join("components")
+                            .join("schemas")
+                            .join(title)
+                    });
+
                 let schema =
                     SchemaAst::try_new_schema(title, openapi_path, self.ctx).map_err(|e| {
                         Error {
@@ -816,10 +1352,14 @@ impl<'a> Interpreter<'a> {
                             description: e.render(self.input, self.ctx, &pos),
                             file: None,
                             position: None,
+                            owner: None,
+                            suggestions: vec![],
                         }
                     })?;
 
-                let ast = SchemaAst::from_model(schema, self.ctx, expanded)?;
+                // The schema was found, so we know both attributes were set
+                let location = location.expect("Schema found without a location");
+                let ast = SchemaAst::from_model(schema, self.ctx, expanded, &location)?;
 
                 Ok(Some(Node {
                     kind: NodeKind::OpenAPISchema(ast),
@@ -835,27 +1375,112 @@ impl<'a> Interpreter<'a> {
         opt: Option<AttributeValue>,
         pos: &Position,
     ) -> Result<Option<Value>> {
-        opt.map(|v| match v {
-            AttributeValue::Expression(expr) => {
-                expressions::parse(&expr).and_then(|ast| self.expr_interpreter.interpret(ast))
+        let value = opt
+            .map(|v| match v {
+                AttributeValue::Expression(expr) => {
+                    expressions::parse(&expr).and_then(|ast| self.expr_interpreter.interpret(ast))
+                }
+                AttributeValue::Literal(s) => Ok(Value::String(s)),
+            })
+            .map_or(Ok(None), |r| r.map(Some))
+            .map_err(|e| Error {
+                code: Error::INVALID_EXPRESSION,
+                message: "Error in expression".to_string(),
+                description: e.render(self.input, self.ctx, None, None, pos),
+                file: None,
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            })?;
+        self.report_undefined(None, None, pos);
+
+        Ok(value)
+    }
+
+    /// Renders the children of a component call, separating the `<Fill>`s
+    /// for named slots from the rest.
+    fn render_slot_content(
+        &mut self,
+        handle: &CustomComponentHandle,
+        component_name: &str,
+        children: Vec<ContentNode>,
+    ) -> Result<SlotContent> {
+        let (fills, rest): (Vec<_>, Vec<_>) = children
+            .into_iter()
+            .partition(|c| matches!(c.kind, ContentNodeKind::Fill { .. }));
+
+        let mut slot_content = SlotContent::default();
+
+        if !fills.is_empty() {
+            let slot_names = handle.slot_names(self.ctx);
+
+            for fill in fills {
+                let ContentNodeKind::Fill { slot } = fill.kind else {
+                    unreachable!()
+                };
+
+                let component_error = |e: ComponentError, message: String| Error {
+                    code: Error::INVALID_COMPONENT,
+                    message,
+                    description: e.render(self.input, self.ctx),
+                    file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                    position: Some(fill.pos.clone()),
+                    owner: None,
+                    suggestions: vec![],
+                };
+
+                let Some(slot) = slot.map(|s| s.as_str().to_string()) else {
+                    return Err(component_error(
+                        ComponentError::MissingFillSlot(fill.pos.clone()),
+                        String::from("Missing slot for fill"),
+                    ));
+                };
+
+                if !slot_names.contains(&slot) {
+                    let closest = closest_match(&slot, slot_names.iter().map(String::as_str))
+                        .map(str::to_string);
+
+                    return Err(component_error(
+                        ComponentError::UnknownSlot(
+                            slot.clone(),
+                            closest.clone(),
+                            fill.pos.clone(),
+                        ),
+                        format!(r#"Unknown slot "{}" for {}"#, slot, component_name),
+                    ));
+                }
+
+                if slot_content.fills.contains_key(&slot) {
+                    return Err(component_error(
+                        ComponentError::DuplicateFill(slot.clone(), fill.pos.clone()),
+                        format!(r#"Slot "{}" filled more than once"#, slot),
+                    ));
+                }
+
+                let rendered = self.render_children(fill.children)?;
+                slot_content.fills.insert(slot, rendered);
             }
-            AttributeValue::Literal(s) => Ok(Value::String(s)),
-        })
-        .map_or(Ok(None), |r| r.map(Some))
-        .map_err(|e| Error {
-            code: Error::INVALID_EXPRESSION,
-            message: "Error in expression".to_string(),
-            description: e.render(self.input, self.ctx, None, None, pos),
-            file: None,
-            position: None,
-        })
+        }
+
+        if !rest.is_empty() {
+            slot_content.default = Some(self.render_children(rest)?);
+        }
+
+        Ok(slot_content)
     }
 
     fn render_children(&mut self, children: Vec<ContentNode>) -> Result<Vec<Node>> {
         let mut rendered_children = vec![];
+        let mut children = children.into_iter().peekable();
+
+        while let Some(child) = children.next() {
+            let block = children.next_if(|next| is_attribute_block_of(&child, next));
+
+            if let Some(mut c) = self.walk(child)? {
+                if let Some(block) = block {
+                    self.apply_attribute_block(&mut c, block)?;
+                }
 
-        for child in children {
-            if let Some(c) = self.walk(child)? {
                 rendered_children.push(c)
             }
         }
@@ -863,17 +1488,303 @@ impl<'a> Interpreter<'a> {
         Ok(rendered_children)
     }
 
+    /// Sets the attributes from a `{key=value}` block on the image or link
+    /// it follows.
+    fn apply_attribute_block(&self, node: &mut Node, block: ContentNode) -> Result<()> {
+        let ContentNodeKind::Expression { value } = block.kind else {
+            return Ok(());
+        };
+
+        // The block's value starts after the opening brace
+        let offset = block.pos.start.byte_offset + 1;
+
+        let parsed = attribute_block::parse(&value)
+            .map_err(|e| self.attribute_block_error(e.msg, offset, e.span))?;
+
+        for attribute in parsed {
+            let result = match &mut node.kind {
+                NodeKind::Image { attributes, .. } => {
+                    attribute_block::set_image_attribute(attributes, &attribute)
+                }
+                NodeKind::Link { attributes, .. } => {
+                    attribute_block::set_link_attribute(attributes, &attribute)
+                }
+                _ => Err(String::from(
+                    "Attributes can't be set on inline SVGs. Remove `?inline` to set them",
+                )),
+            };
+
+            result.map_err(|msg| self.attribute_block_error(msg, offset, attribute.span))?;
+        }
+
+        Ok(())
+    }
+
+    fn attribute_block_error(&self, msg: String, offset: usize, span: Range<usize>) -> Error {
+        let pos = Position::from_byte_range(self.input, offset + span.start, offset + span.end);
+
+        let highlight = Highlight {
+            location: Location::Point(pos.start.row, pos.start.col),
+            span: span.len().max(1),
+            msg: None,
+        };
+
+        Error {
+            code: Error::INVALID_MARKDOWN_TEMPLATE,
+            message: "Invalid attribute".to_string(),
+            description: error_renderer::render(self.input, &msg, vec![highlight], self.ctx),
+            file: None,
+            position: Some(pos),
+            owner: None,
+            suggestions: vec![],
+        }
+    }
+
     fn evaluate_expr(&mut self, expr: &str) -> expressions::Result<Value> {
         let ast = expressions::parse(expr)?;
         self.expr_interpreter.interpret(ast)
     }
 
+    /// Reports the variables and fields that didn't exist, but were evaluated
+    /// to `null` because of the `undefined_variables` option. Only reported
+    /// when the option is set to warn.
+    fn report_undefined(&mut self, key: Option<&str>, expr: Option<&str>, pos: &Position) {
+        let undefined = std::mem::take(&mut self.expr_interpreter.undefined);
+
+        if undefined.is_empty() || self.ctx.options.undefined_variables != UndefinedVariables::Warn
+        {
+            return;
+        }
+
+        if let Ok(mut warnings) = self.ctx.warnings.lock() {
+            for e in undefined {
+                warnings.push(Error {
+                    code: Error::INVALID_EXPRESSION,
+                    message: "Undefined variable in expression".to_string(),
+                    description: e.render(self.input, self.ctx, key, expr, pos),
+                    file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                    position: Some(pos.clone()),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+    }
+
+    /// Warns about footnote references like `[^1]` left in the text. They
+    /// are only parsed as references when the footnote is defined.
+    fn report_undefined_footnotes(&self, text: &str, pos: &Position) {
+        if self.ctx.in_built_in_component || !text.contains("[^") {
+            return;
+        }
+
+        let source = self
+            .input
+            .get(pos.start.byte_offset..pos.end.byte_offset)
+            .unwrap_or_default();
+        let mut source_offset = 0;
+
+        let mut rest = text;
+        while let Some(start) = rest.find("[^") {
+            let after = &rest[start + 2..];
+            rest = after;
+
+            let Some(end) = after.find(']') else {
+                break;
+            };
+            let label = &after[..end];
+
+            // Footnote labels can't be empty or contain whitespace
+            if label.is_empty() || label.contains(char::is_whitespace) {
+                continue;
+            }
+
+            let reference = format!("[^{}]", label);
+
+            // Point at the reference itself if it's written as is in the source
+            let reference_pos = match source[source_offset..].find(&reference) {
+                Some(offset) => {
+                    let start = pos.start.byte_offset + source_offset + offset;
+                    source_offset += offset + reference.len();
+                    Position::from_byte_range(self.input, start, start + reference.len())
+                }
+                None => pos.clone(),
+            };
+
+            let highlight = Highlight {
+                location: Location::Point(reference_pos.start.row, reference_pos.start.col),
+                span: reference.len(),
+                msg: Some(String::from("Not defined on this page")),
+            };
+
+            if let Ok(mut warnings) = self.ctx.warnings.lock() {
+                warnings.push(Error {
+                    code: Error::BROKEN_INTERNAL_LINK,
+                    message: "Undefined footnote".to_string(),
+                    description: error_renderer::render(
+                        self.input,
+                        &format!("Footnote `{}` is referenced but never defined", reference),
+                        vec![highlight],
+                        self.ctx,
+                    ),
+                    file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                    position: Some(reference_pos),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            rest = &after[end + 1..];
+        }
+    }
+
+    /// Gathers the referenced footnotes into a list for the end of the page,
+    /// numbered in the order they were first referenced.
+    fn take_footnotes(&mut self) -> Option<Node> {
+        let order = std::mem::take(&mut self.state.footnote_order);
+        let mut references = std::mem::take(&mut self.state.footnote_references);
+        let mut definitions = std::mem::take(&mut self.state.footnote_definitions);
+
+        let footnotes = order
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, identifier)| {
+                let (children, pos) = definitions.remove(&identifier)?;
+
+                Some(Node {
+                    kind: NodeKind::FootnoteDefinition {
+                        number: index + 1,
+                        id: format!("fn-{}", slugify(&identifier)),
+                        reference_ids: references.remove(&identifier).unwrap_or_default(),
+                    },
+                    children,
+                    pos,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        if footnotes.is_empty() {
+            return None;
+        }
+
+        Some(Node {
+            kind: NodeKind::Footnotes,
+            pos: footnotes[0].pos.clone(),
+            children: footnotes,
+        })
+    }
+
+    /// Looks up the sanitized markup of an SVG under `_assets` to embed into
+    /// the page. If it can't be embedded, warns and returns `None`, so that
+    /// it's shown as a regular image instead.
+    fn inline_svg(&self, src: &str, pos: &Position) -> Option<String> {
+        let not_an_svg = "Only SVG files under `_assets` can be inlined.";
+
+        let Some(path) = asset_path(src, self.ctx) else {
+            self.report_inline_svg(not_an_svg, src, pos);
+            return None;
+        };
+
+        // Missing files are reported as broken links
+        let asset = self
+            .ctx
+            .assets
+            .iter()
+            .find(|asset| canonicalize(&asset.path) == path)?;
+
+        let reason = match &asset.svg {
+            Some(Ok(html)) if html.len() <= MAX_INLINE_SVG_BYTES => return Some(html.clone()),
+            Some(Ok(html)) => format!(
+                "The SVG is {} once sanitized, over the {} limit for inlining.",
+                format_bytes(html.len()),
+                format_bytes(MAX_INLINE_SVG_BYTES)
+            ),
+            Some(Err(reason)) => reason.clone(),
+            None if path.extension().and_then(|e| e.to_str()) == Some("svg") => {
+                "The contents of the SVG weren't available to inline.".to_owned()
+            }
+            None => not_an_svg.to_owned(),
+        };
+
+        self.report_inline_svg(&reason, src, pos);
+
+        None
+    }
+
+    /// Warns about an SVG shown as a regular image instead of being inlined.
+    /// Images in built-in components, like `<Image>`, aren't highlighted, since
+    /// their position is in the component rather than the page.
+    fn report_inline_svg(&self, reason: &str, src: &str, pos: &Position) {
+        let msg = format!("Could not inline `{}`. {}", src, reason);
+
+        let (description, position) = if self.ctx.in_built_in_component {
+            (msg, None)
+        } else {
+            let span = self
+                .input
+                .get(pos.start.byte_offset..pos.end.byte_offset)
+                .and_then(|source| source.lines().next())
+                .map_or(1, str::len);
+
+            let highlight = Highlight {
+                location: Location::Point(pos.start.row, pos.start.col),
+                span,
+                msg: Some(String::from("Shown as an image")),
+            };
+
+            (
+                error_renderer::render(self.input, &msg, vec![highlight], self.ctx),
+                Some(pos.clone()),
+            )
+        };
+
+        if let Ok(mut warnings) = self.ctx.warnings.lock() {
+            warnings.push(Error {
+                code: Error::INVALID_INLINE_SVG,
+                message: "SVG not inlined".to_string(),
+                description,
+                file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                position,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+
+    /// Warns about HTML removed by the sanitizer, unless it came from a
+    /// built-in component, which the author can't change.
+    fn report_unsafe_html(&self, msg: String, name: &str, pos: &Position) {
+        if self.ctx.in_built_in_component {
+            return;
+        }
+
+        let highlight = Highlight {
+            location: Location::Point(pos.start.row, pos.start.col),
+            span: name.len() + 1,
+            msg: Some(String::from("Removed")),
+        };
+
+        if let Ok(mut warnings) = self.ctx.warnings.lock() {
+            warnings.push(Error {
+                code: Error::UNSAFE_HTML,
+                message: "Unsafe HTML removed".to_string(),
+                description: error_renderer::render(self.input, &msg, vec![highlight], self.ctx),
+                file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                position: Some(pos.clone()),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+
     /// Resolves the attributes from the content node to the custom components expected inputs.
     ///
-    /// If required attributes are missing, returns an error.
+    /// If required attributes are missing, unknown attributes are passed, or a value doesn't
+    /// match the declared type, returns an error.
     fn resolve_attributes(
         &mut self,
         component: &CustomComponent,
+        handle: &CustomComponentHandle,
         attributes: &[Attribute],
         node_pos: &Position,
     ) -> Result<Vec<(String, Value)>> {
@@ -895,23 +1806,47 @@ impl<'a> Interpreter<'a> {
                 return Err(Error {
                     code: Error::INVALID_COMPONENT,
                     message: "Unexpected attribute".to_string(),
-                    description: ComponentError::UnexpectedAttribute(
-                        incoming.clone(),
-                        node_pos.clone(),
-                    )
-                    .render(self.input, self.ctx),
-                    file: None,
-                    position: None,
+                    description: handle.with_declaration(
+                        ComponentError::UnexpectedAttribute(incoming.clone(), node_pos.clone())
+                            .render(self.input, self.ctx),
+                    ),
+                    file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                    position: Some(node_pos.clone()),
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
         }
 
         for attr_spec in expected_attrs {
             if let Some(incoming) = attributes.iter().find(|a| a.key == attr_spec.title) {
-                let val = match &incoming.value {
-                    Some(AttributeValue::Expression(expr)) => expressions::parse(expr)
-                        .and_then(|ast| self.expr_interpreter.interpret(ast))
-                        .and_then(|val| attr_spec.verify_incoming(val, expr)),
+                let render_error = |e: expressions::Error, interpreter: &Self| {
+                    e.render(
+                        interpreter.input,
+                        interpreter.ctx,
+                        Some(&incoming.key),
+                        incoming.value.as_ref().map(|v| v.as_str()),
+                        node_pos,
+                    )
+                };
+
+                let (val, src) = match &incoming.value {
+                    Some(AttributeValue::Expression(expr)) => {
+                        let val = expressions::parse(expr)
+                            .and_then(|ast| self.expr_interpreter.interpret(ast))
+                            .map_err(|e| Error {
+                                code: Error::INVALID_EXPRESSION,
+                                message: "Error in attribute expression".to_string(),
+                                description: render_error(e, self),
+                                file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                                position: Some(node_pos.clone()),
+                                owner: None,
+                                suggestions: vec![],
+                            })?;
+                        self.report_undefined(Some(&incoming.key), Some(expr), node_pos);
+
+                        (val, expr.as_str())
+                    }
                     Some(AttributeValue::Literal(s)) => {
                         let val = if let Ok(dec) = Decimal::from_str(s) {
                             Value::Number(dec)
@@ -921,31 +1856,28 @@ impl<'a> Interpreter<'a> {
                             Value::String(s.clone())
                         };
 
-                        attr_spec.verify_incoming(val, s)
+                        (val, s.as_str())
                     }
-                    None => attr_spec.verify_incoming(Value::Bool(true), ""),
-                }
-                .map_err(|e| Error {
-                    code: Error::INVALID_EXPRESSION,
-                    message: "Error in attribute expression".to_string(),
-                    description: e.render(
-                        self.input,
-                        self.ctx,
-                        Some(&incoming.key),
-                        incoming.value.as_ref().map(|v| v.as_str()),
-                        node_pos,
-                    ),
-                    file: None,
-                    position: None,
+                    None => (Value::Bool(true), ""),
+                };
+
+                let val = attr_spec.verify_incoming(val, src).map_err(|e| Error {
+                    code: Error::INVALID_COMPONENT,
+                    message: "Invalid attribute value".to_string(),
+                    description: handle.with_declaration(render_error(e, self)),
+                    file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                    position: Some(node_pos.clone()),
+                    owner: None,
+                    suggestions: vec![],
                 })?;
 
                 out.push((attr_spec.title.clone(), val));
             } else {
                 if attr_spec.required {
                     return Err(Error {
-                        code: Error::INVALID_EXPRESSION,
-                        message: "Error in attribute expression".to_string(),
-                        description: error_renderer::render(
+                        code: Error::INVALID_COMPONENT,
+                        message: "Missing required attribute".to_string(),
+                        description: handle.with_declaration(error_renderer::render(
                             self.input,
                             &format!(
                                 "Missing required attribute `{}` for component `{}`",
@@ -957,9 +1889,11 @@ impl<'a> Interpreter<'a> {
                                 location: Location::Point(node_pos.start.row, node_pos.start.col),
                             }],
                             self.ctx,
-                        ),
-                        file: None,
-                        position: None,
+                        )),
+                        file: self.ctx.file_context.as_ref().map(|f| f.fs_path.clone()),
+                        position: Some(node_pos.clone()),
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
 
@@ -1022,6 +1956,8 @@ impl<'a> Interpreter<'a> {
                     ),
                     file: None,
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })?;
 
                 let val = self.expr_interpreter.interpret(ast).map_err(|e| Error {
@@ -1036,7 +1972,10 @@ impl<'a> Interpreter<'a> {
                     ),
                     file: None,
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })?;
+                self.report_undefined(Some(&attr.key), Some(expr), node_pos);
 
                 attr.value = Some(AttributeValue::Literal(val.to_string()));
             }
@@ -1061,6 +2000,22 @@ impl<'a> Interpreter<'a> {
     }
 }
 
+/// Whether `next` is a `{key=value}` block written right after the image or
+/// link `node`, without a space in between.
+fn is_attribute_block_of(node: &ContentNode, next: &ContentNode) -> bool {
+    let follows_image_or_link = matches!(
+        node.kind,
+        ContentNodeKind::Image { .. }
+            | ContentNodeKind::ImageReference { .. }
+            | ContentNodeKind::Link { .. }
+            | ContentNodeKind::LinkReference { .. }
+    );
+
+    follows_image_or_link
+        && next.pos.start.byte_offset == node.pos.end.byte_offset
+        && matches!(&next.kind, ContentNodeKind::Expression { value } if attribute_block::is_block(value))
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Hash)]
 struct Reference {
     pub url: String,
@@ -1072,4 +2027,18 @@ struct Reference {
 struct ConversionState {
     pub definitions: HashMap<String, Reference>,
     pub anchorizer: Anchorizer,
+    /// Slug of the last heading, which IDs of components are scoped under
+    pub heading: Option<String>,
+    /// How many `<Steps>` blocks we are currently inside of
+    pub steps_depth: usize,
+    /// The number of the last step in the previous top level `<Steps>` block
+    pub last_step_number: usize,
+    /// Whether we are inside of a `<Callout>`, which can't be nested
+    pub in_callout: bool,
+    /// Identifiers of the footnotes, in the order they were first referenced
+    pub footnote_order: Vec<String>,
+    /// Anchors of the references to each footnote
+    pub footnote_references: HashMap<String, Vec<String>>,
+    /// Rendered footnotes and their positions, by identifier
+    pub footnote_definitions: HashMap<String, (Vec<Node>, Position)>,
 }
@@ -1,15 +1,31 @@
-use std::{collections::BTreeSet, path::PathBuf};
+use std::{borrow::Cow, path::PathBuf};
 
+use super::renderable_ast::ResponsiveImage;
 use super::shared_ast::{Point, Position};
 use super::{Node, NodeKind};
 use crate::{
-    content_ast, interpreter::Interpreter, page_kind::OutgoingLink, render_context::RenderContext,
-    AttributeValue, Error, Result,
+    content_ast,
+    interpreter::Interpreter,
+    page_kind::OutgoingLink,
+    primitive_components::{callout::CalloutType, download::asset_path},
+    render_context::RenderContext,
+    AttributeValue, Error, ImageVariants, Result,
 };
 
+use regex::Regex;
 use unix_path::{self as upath};
 
+lazy_static! {
+    static ref CALLOUT_DIRECTIVE_OPEN: Regex =
+        Regex::new(r#"^( {0,3}):{3,}([A-Za-z_-]+)(?:[ \t]+(.*?))?\s*$"#).unwrap();
+    static ref CALLOUT_DIRECTIVE_CLOSE: Regex = Regex::new(r#"^( {0,3}):{3,}\s*$"#).unwrap();
+    static ref CODE_FENCE: Regex = Regex::new(r#"^ {0,3}(`{3,}|~{3,})"#).unwrap();
+}
+
 pub(crate) fn to_ast_mdx(input: &str, ctx: &RenderContext) -> Result<Node> {
+    let expanded = expand_callout_directives(input);
+    let input = expanded.as_ref();
+
     let content_ast = content_ast::build_mdx(input, ctx)?;
     let mut interpreter = Interpreter::new(ctx, input);
     let mut renderable_ast = interpreter.interpret(content_ast)?;
@@ -23,6 +39,9 @@ pub(crate) fn to_ast_mdx_fault_tolerant(
     input: &str,
     ctx: &RenderContext,
 ) -> std::result::Result<Node, (Option<Node>, Vec<Error>)> {
+    let expanded = expand_callout_directives(input);
+    let input = expanded.as_ref();
+
     let (content_ast, errors) = fault_tolerant_parse(input, ctx);
     let result = interpret_and_rewrite(content_ast, ctx, input);
 
@@ -68,6 +87,8 @@ fn fault_tolerant_parse(content: &str, ctx: &RenderContext) -> (content_ast::Nod
                 description: "Could not parse Markdown template. Please check the syntax to ensure you have a valid Markdown file.".to_string(),
                 file: None,
                 position: None,
+                owner: None,
+                suggestions: vec![],
             }],
         ),
     }
@@ -182,6 +203,107 @@ fn bump_offsets(ast: content_ast::Node, start_offset: usize, input: &str) -> con
     ast
 }
 
+/// Turns the `:::warning` shorthand into `<Callout>` components:
+///
+/// ```md
+/// :::warning Optional title
+/// Some **Markdown**
+/// :::
+/// ```
+///
+/// Every line is replaced by a single line, so that errors still point to
+/// the right line. Only the known callout types are expanded, and directives
+/// inside fenced or indented code blocks are left alone. Blocks that are never
+/// closed run until the end of the document.
+pub(crate) fn expand_callout_directives(input: &str) -> Cow<'_, str> {
+    if !input.contains(":::") {
+        return Cow::Borrowed(input);
+    }
+
+    let mut out = String::with_capacity(input.len());
+    let mut fence: Option<String> = None;
+    let mut indented_code = false;
+    let mut previous_blank = true;
+    let mut open = 0;
+
+    for line in input.split_inclusive('\n') {
+        let blank = line.trim().is_empty();
+        let indented = indentation(line) >= 4;
+
+        // Indented code continues until a non-blank line that isn't indented,
+        // and can only start after a blank line, since it can't interrupt a
+        // paragraph.
+        indented_code = if indented_code {
+            blank || indented
+        } else {
+            fence.is_none() && !blank && indented && previous_blank
+        };
+        previous_blank = blank;
+
+        if indented_code {
+            // Skip the directive checks below
+        } else if let Some(marker) = &fence {
+            if line.trim_start().starts_with(marker.as_str()) {
+                fence = None;
+            }
+        } else if let Some(caps) = CODE_FENCE.captures(line) {
+            fence = Some(caps[1].to_string());
+        } else if let Some(caps) = CALLOUT_DIRECTIVE_OPEN
+            .captures(line)
+            .filter(|caps| CalloutType::try_from(&caps[2]).is_ok())
+        {
+            out.push_str(&caps[1]);
+            out.push_str(&format!(r#"<Callout type="{}""#, &caps[2]));
+            if let Some(title) = caps.get(3).map(|t| t.as_str()) {
+                out.push_str(&format!(r#" title="{}""#, escape_attribute(title)));
+            }
+            out.push_str(">\n");
+            open += 1;
+            continue;
+        } else if open > 0 {
+            if let Some(caps) = CALLOUT_DIRECTIVE_CLOSE.captures(line) {
+                out.push_str(&caps[1]);
+                out.push_str("</Callout>\n");
+                open -= 1;
+                continue;
+            }
+        }
+
+        out.push_str(line);
+    }
+
+    for _ in 0..open {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push_str("</Callout>\n");
+    }
+
+    Cow::Owned(out)
+}
+
+/// Width of the leading whitespace of a line, with tabs stopping at every
+/// 4 columns like in CommonMark.
+fn indentation(line: &str) -> usize {
+    let mut width = 0;
+
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width += 4 - width % 4,
+            _ => break,
+        }
+    }
+
+    width
+}
+
+/// Escapes text for a double quoted attribute string, using character
+/// references since the strings can't escape quotes any other way.
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;").replace('"', "&quot;")
+}
+
 fn interpret_and_rewrite(ast: content_ast::Node, ctx: &RenderContext, input: &str) -> Result<Node> {
     let mut interpreter = Interpreter::new(ctx, input);
     let mut renderable_ast = interpreter.interpret(ast)?;
@@ -225,19 +347,65 @@ fn rewrite_links(renderable_ast: &mut Node, ctx: &RenderContext) {
                 }
             }
         }
-        NodeKind::Image { .. } => {
-            if !ctx.options.link_rewrites.is_empty()
-                || ctx.options.prefix_asset_urls.is_some()
-                || ctx.options.prefix_link_urls.is_some()
-                || ctx.options.bust_image_caches
-            {
-                rewrite_image_node(node, ctx)
-            }
+        NodeKind::Image {
+            ref mut url,
+            ref mut responsive,
+            ..
+        } => rewrite_image(url, responsive, ctx),
+        NodeKind::Download(ref mut download) => {
+            download.src = rewrite_image_src(&download.src, ctx);
         }
         _ => {}
     });
 }
 
+/// Expands and rewrites an image URL, and points the image at its resized
+/// versions when the build generated them.
+fn rewrite_image(url: &mut String, responsive: &mut Option<ResponsiveImage>, ctx: &RenderContext) {
+    if ctx.expand_image_urls && !url.starts_with('/') {
+        if let Some(expanded) = expand_paths_in_local_links(url, ctx) {
+            *url = expanded;
+        }
+    }
+
+    if let Some(variants) =
+        asset_path(url, ctx).and_then(|path| ctx.options.image_variants.get(&path))
+    {
+        *responsive = Some(responsive_image(variants, ctx));
+    }
+
+    if !ctx.options.link_rewrites.is_empty()
+        || ctx.options.prefix_asset_urls.is_some()
+        || ctx.options.prefix_link_urls.is_some()
+        || ctx.options.bust_image_caches
+    {
+        *url = rewrite_image_src(url, ctx);
+    }
+}
+
+fn responsive_image(variants: &ImageVariants, ctx: &RenderContext) -> ResponsiveImage {
+    let srcset = |webp: bool| {
+        variants
+            .variants
+            .iter()
+            .filter(|v| (v.mime_type == "image/webp") == webp)
+            .map(|v| {
+                let src = rewrite_image_src(&format!("/{}", v.path.display()), ctx);
+                format!("{} {}w", src, v.width)
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let webp_srcset = srcset(true);
+
+    ResponsiveImage {
+        srcset: srcset(false),
+        webp_srcset: (!webp_srcset.is_empty()).then_some(webp_srcset),
+        sizes: variants.sizes.clone(),
+    }
+}
+
 pub(crate) fn to_ast(input: &str, ctx: &RenderContext) -> Result<Node> {
     let content_ast = content_ast::build_gfm(input, ctx)?;
     let mut interpreter = Interpreter::new(ctx, input);
@@ -251,14 +419,13 @@ pub(crate) fn to_ast(input: &str, ctx: &RenderContext) -> Result<Node> {
 
             *url = to_final_link(url, ctx);
         }
-        NodeKind::Image { .. } => {
-            if !ctx.options.link_rewrites.is_empty()
-                || ctx.options.prefix_asset_urls.is_some()
-                || ctx.options.prefix_link_urls.is_some()
-                || ctx.options.bust_image_caches
-            {
-                rewrite_image_node(node, ctx)
-            }
+        NodeKind::Image {
+            ref mut url,
+            ref mut responsive,
+            ..
+        } => rewrite_image(url, responsive, ctx),
+        NodeKind::Download(ref mut download) => {
+            download.src = rewrite_image_src(&download.src, ctx);
         }
         _ => {}
     });
@@ -268,8 +435,6 @@ pub(crate) fn to_ast(input: &str, ctx: &RenderContext) -> Result<Node> {
 
 /// Extracts the outgoing **internal** links out of a blob of markdown
 pub(crate) fn extract_links(input: &str, ctx: &RenderContext) -> crate::Result<Vec<OutgoingLink>> {
-    let mut links = vec![];
-
     // Render without expanding links. Copy the context but override any relative URL base.
     let default_ctx = RenderContext {
         relative_url_base: None,
@@ -278,6 +443,14 @@ pub(crate) fn extract_links(input: &str, ctx: &RenderContext) -> crate::Result<V
 
     let ast = to_ast_mdx(input, &default_ctx)?;
 
+    Ok(links_in_ast(&ast, ctx))
+}
+
+/// Gathers the outgoing **internal** links from an AST that was built without
+/// expanding relative links. The links are expanded using the given context.
+pub(crate) fn links_in_ast(ast: &Node, ctx: &RenderContext) -> Vec<OutgoingLink> {
+    let mut links = vec![];
+
     fn _iter_nodes<F>(node: &Node, acc: &mut Vec<OutgoingLink>, f: &F)
     where
         F: Fn(&Node, &mut Vec<OutgoingLink>),
@@ -288,15 +461,21 @@ pub(crate) fn extract_links(input: &str, ctx: &RenderContext) -> crate::Result<V
         }
     }
 
-    _iter_nodes(&ast, &mut links, &|node, acc| match &node.kind {
+    _iter_nodes(ast, &mut links, &|node, acc| match &node.kind {
         NodeKind::Link { url, .. } => {
             let mut link_split = url.split('#');
             let link_without_fragment = link_split.next().unwrap_or(url);
 
+            // Links to files are checked along with the other assets
+            if asset_path(link_without_fragment, ctx).is_some() {
+                return;
+            }
+
             if let Some(url) = expand_paths_in_local_links(link_without_fragment, ctx) {
                 acc.push(OutgoingLink {
                     uri: link_without_fragment.to_string(),
                     expanded_uri: Some(url.clone()),
+                    position: source_position(node, ctx),
                 });
             }
         }
@@ -314,6 +493,7 @@ pub(crate) fn extract_links(input: &str, ctx: &RenderContext) -> crate::Result<V
                                 acc.push(OutgoingLink {
                                     uri: link_without_fragment.to_string(),
                                     expanded_uri: Some(url.clone()),
+                                    position: source_position(node, ctx),
                                 });
                             }
                         }
@@ -324,16 +504,21 @@ pub(crate) fn extract_links(input: &str, ctx: &RenderContext) -> crate::Result<V
         _ => {}
     });
 
-    Ok(links)
+    links
 }
 
-/// Extracts the outgoing **internal** links out of a blob of markdown
+/// The position of a node in the file being verified, relative to the content
+/// after the frontmatter. Snippets parsed without a file have no position.
+fn source_position(node: &Node, ctx: &RenderContext) -> Option<Position> {
+    ctx.file_context.as_ref().map(|_| node.pos.clone())
+}
+
+/// Extracts the links to assets out of a blob of markdown
+#[cfg(test)]
 pub(crate) fn extract_asset_links(
     input: &str,
     ctx: &RenderContext,
 ) -> crate::Result<Vec<OutgoingLink>> {
-    let mut links = vec![];
-
     // Render without expanding links. Copy the context but override any relative URL base.
     let default_ctx = RenderContext {
         relative_url_base: None,
@@ -342,6 +527,14 @@ pub(crate) fn extract_asset_links(
 
     let ast = to_ast_mdx(input, &default_ctx)?;
 
+    Ok(asset_links_in_ast(&ast, ctx))
+}
+
+/// Gathers the links to assets, such as images and downloads, from an AST
+/// that was built without expanding relative links.
+pub(crate) fn asset_links_in_ast(ast: &Node, ctx: &RenderContext) -> Vec<OutgoingLink> {
+    let mut links = vec![];
+
     fn _iter_nodes<F>(node: &Node, acc: &mut Vec<OutgoingLink>, f: &F)
     where
         F: Fn(&Node, &mut Vec<OutgoingLink>),
@@ -352,15 +545,34 @@ pub(crate) fn extract_asset_links(
         }
     }
 
-    _iter_nodes(&ast, &mut links, &|node, acc| match &node.kind {
+    _iter_nodes(ast, &mut links, &|node, acc| match &node.kind {
         NodeKind::Image { url, .. } => {
             if let Some(expanded_url) = expand_paths_in_local_links(url, ctx) {
                 acc.push(OutgoingLink {
                     uri: url.to_string(),
                     expanded_uri: Some(expanded_url.clone()),
+                    position: None,
+                });
+            }
+        }
+        NodeKind::Link { url, .. } => {
+            let link_without_fragment = url.split('#').next().unwrap_or(url);
+
+            if asset_path(link_without_fragment, ctx).is_some() {
+                acc.push(OutgoingLink {
+                    uri: link_without_fragment.to_string(),
+                    expanded_uri: expand_paths_in_local_links(link_without_fragment, ctx),
+                    position: None,
                 });
             }
         }
+        NodeKind::Download(download) => {
+            acc.push(OutgoingLink {
+                uri: download.src.clone(),
+                expanded_uri: Some(download.src.clone()),
+                position: None,
+            });
+        }
         NodeKind::HtmlBlock { attributes, .. } => {
             if attributes.iter().any(|a| a.key == "download") {
                 for attr in attributes {
@@ -370,6 +582,7 @@ pub(crate) fn extract_asset_links(
                                 acc.push(OutgoingLink {
                                     uri: url.to_string(),
                                     expanded_uri: Some(expanded_url.clone()),
+                                    position: None,
                                 });
                             }
                         }
@@ -380,39 +593,46 @@ pub(crate) fn extract_asset_links(
         _ => {}
     });
 
-    Ok(links)
+    links
 }
 
-pub(crate) fn extract_external_links(
-    input: &str,
-    ctx: &RenderContext,
-) -> crate::Result<Vec<String>> {
-    println!("Extracting external links from {}", input);
-    let ast = to_ast_mdx(input, ctx).map_err(|e| {
-        println!("Error: {:#?}", e);
-        e
-    })?;
-    println!("AST: {:#?}", ast);
-
-    let unique_links = ast
-        .walk()
-        .filter_map(|node| {
-            if let NodeKind::Link { url, .. } = &node.kind {
-                if uriparse::URI::try_from(url.as_str()).is_ok() {
-                    Some(url.clone())
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+/// Gathers the links to other sites, e.g. `https://example.com`, from an AST,
+/// including the URLs embeds load their content from.
+/// Other schemes like `mailto:` can't be checked, so they are left out.
+pub(crate) fn external_links_in_ast(ast: &Node, ctx: &RenderContext) -> Vec<OutgoingLink> {
+    let link = |url: &str, node: &Node| OutgoingLink {
+        uri: url.to_string(),
+        expanded_uri: None,
+        position: source_position(node, ctx),
+    };
+
+    ast.walk()
+        .flat_map(|node| match &node.kind {
+            NodeKind::Link { url, .. } if is_external_url(url) => vec![link(url, node)],
+            NodeKind::Embed(embed) => vec![link(&embed.url, node)],
+            NodeKind::HtmlBlock { attributes, .. } => attributes
+                .iter()
+                .filter(|a| a.key == "href")
+                .filter_map(|a| match &a.value {
+                    Some(AttributeValue::Literal(url)) if is_external_url(url) => {
+                        Some(link(url, node))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
         })
-        .collect::<BTreeSet<String>>();
+        .collect()
+}
 
-    Ok(Vec::from_iter(unique_links))
+/// Whether the link points to another site over HTTP(S).
+pub(crate) fn is_external_url(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+
+    url.starts_with("http://") || url.starts_with("https://")
 }
 
-/// Webbifies, expands, and rewrites links
+/// Webbifies, expands, styles, and rewrites links
 pub(crate) fn to_final_link(url: &str, ctx: &RenderContext) -> String {
     let mut link_split = url.split('#');
 
@@ -425,6 +645,8 @@ pub(crate) fn to_final_link(url: &str, ctx: &RenderContext) -> String {
         modified_link = expand_paths_in_local_links(&modified_link, ctx).unwrap_or(modified_link);
     }
 
+    modified_link = localize_link(&modified_link, ctx);
+
     if ctx.options.webbify_internal_urls && !ctx.options.fsify_internal_urls {
         modified_link = webbify_node(&modified_link, ctx)
     }
@@ -433,6 +655,8 @@ pub(crate) fn to_final_link(url: &str, ctx: &RenderContext) -> String {
         modified_link = fsify_node(&modified_link, ctx)
     }
 
+    modified_link = ctx.url_style().apply(&modified_link);
+
     if !ctx.options.link_rewrites.is_empty()
         || ctx.options.prefix_asset_urls.is_some()
         || ctx.options.prefix_link_urls.is_some()
@@ -448,6 +672,38 @@ pub(crate) fn to_final_link(url: &str, ctx: &RenderContext) -> String {
     modified_link
 }
 
+/// Points an absolute internal link at its translation, when the page being
+/// rendered belongs to a locale and the linked page has been translated.
+fn localize_link(link: &str, ctx: &RenderContext) -> String {
+    let page_locale = ctx
+        .relative_url_base
+        .as_deref()
+        .and_then(|base| ctx.settings.split_locale(base).0);
+
+    let Some(locale) = ctx
+        .options
+        .locale
+        .as_deref()
+        .or(page_locale)
+        .filter(|l| Some(*l) != ctx.settings.default_locale())
+    else {
+        return link.to_string();
+    };
+
+    if !link.starts_with('/') || ctx.settings.split_locale(link).0.is_some() {
+        return link.to_string();
+    }
+
+    let localized = crate::project::localized_uri_path(locale, link);
+    let localized_uri = crate::fs_to_uri_path(&PathBuf::from(&localized));
+
+    if ctx.pages.iter().any(|p| p.uri_path() == localized_uri) {
+        localized
+    } else {
+        link.to_string()
+    }
+}
+
 fn iter_nodes_mut<F>(node: &mut Node, f: &F)
 where
     F: Fn(&mut Node),
@@ -494,7 +750,7 @@ fn fsify_node(link: &str, ctx: &RenderContext) -> String {
 }
 
 /// Returns the expanded local link if it was a local link. Otherwise return None.
-fn expand_paths_in_local_links(link: &str, ctx: &RenderContext) -> Option<String> {
+pub(crate) fn expand_paths_in_local_links(link: &str, ctx: &RenderContext) -> Option<String> {
     match parse_internal_link(link) {
         Some(relative_path) if relative_path.is_relative() => Some(prefix_and_expand_path(
             &relative_path,
@@ -569,15 +825,15 @@ pub fn webbify_url(url: &str) -> String {
     }
 }
 
-fn rewrite_image_node(node: &mut Node, ctx: &RenderContext) {
-    if let NodeKind::Image { ref mut url, .. } = node.kind {
-        *url = rewrite_image_src(url, ctx);
-    }
-}
-
 pub(crate) fn rewrite_image_src(src: &str, ctx: &RenderContext) -> String {
     let new_url = if ctx.options.bust_image_caches {
-        format!("{}?c={}", src, ctx.cache_bust_timestamp)
+        let value = ctx
+            .options
+            .cache_bust_value
+            .as_deref()
+            .unwrap_or(&ctx.cache_bust_timestamp);
+
+        format!("{}?c={}", src, value)
     } else {
         src.to_string()
     };
@@ -681,7 +937,8 @@ mod test {
             extract_links("[link](/foo/bar)", &ctx),
             Ok(vec![crate::page_kind::OutgoingLink {
                 expanded_uri: Some("/foo/bar".to_owned()),
-                uri: "/foo/bar".to_owned()
+                uri: "/foo/bar".to_owned(),
+                position: None,
             }])
         );
 
@@ -689,7 +946,8 @@ mod test {
             extract_links("[link](foo.md)", &ctx),
             Ok(vec![crate::page_kind::OutgoingLink {
                 expanded_uri: Some("/foo.md".to_owned()),
-                uri: "foo.md".to_owned()
+                uri: "foo.md".to_owned(),
+                position: None,
             }])
         );
     }
@@ -727,7 +985,8 @@ mod test {
             extract_asset_links(r#"<a download href="/_assets/foo/bar.png">Foo</a>"#, &ctx),
             Ok(vec![OutgoingLink {
                 expanded_uri: Some("/_assets/foo/bar.png".to_owned()),
-                uri: "/_assets/foo/bar.png".to_owned()
+                uri: "/_assets/foo/bar.png".to_owned(),
+                position: None,
             }])
         );
     }
@@ -743,24 +1002,63 @@ mod test {
             extract_asset_links("![bar image](/_assets/foo/bar.png)", &ctx),
             Ok(vec![OutgoingLink {
                 expanded_uri: Some("/_assets/foo/bar.png".to_owned()),
-                uri: "/_assets/foo/bar.png".to_owned()
+                uri: "/_assets/foo/bar.png".to_owned(),
+                position: None,
             }])
         );
     }
 
     #[test]
-    fn skips_links_when_extracting_assets() {
+    fn skips_links_to_pages_when_extracting_assets() {
         let mut ctx = RenderContext::new();
         let settings = Settings::default();
         ctx.with_url_base_by_page_uri("/");
         ctx.settings = &settings;
 
         assert_eq!(
-            extract_asset_links("[foo link](/_assets/foo/bar)", &ctx),
+            extract_asset_links("[foo link](/foo/bar)", &ctx),
             Ok(vec![])
         );
     }
 
+    #[test]
+    fn extracts_links_to_files_when_extracting_assets() {
+        let mut ctx = RenderContext::new();
+        let settings = Settings::default();
+        ctx.with_url_base_by_page_uri("/");
+        ctx.settings = &settings;
+
+        assert_eq!(
+            extract_asset_links("[foo link](/_assets/foo/bar.csv#top)", &ctx),
+            Ok(vec![OutgoingLink {
+                expanded_uri: Some("/_assets/foo/bar.csv".to_owned()),
+                uri: "/_assets/foo/bar.csv".to_owned(),
+                position: None,
+            }])
+        );
+        assert_eq!(
+            extract_links("[foo link](/_assets/foo/bar.csv)", &ctx),
+            Ok(vec![])
+        );
+    }
+
+    #[test]
+    fn extracts_download_components_when_extracting_assets() {
+        let mut ctx = RenderContext::new();
+        let settings = Settings::default();
+        ctx.with_url_base_by_page_uri("/");
+        ctx.settings = &settings;
+
+        assert_eq!(
+            extract_asset_links(r#"<Download src="/_assets/report.csv" />"#, &ctx),
+            Ok(vec![OutgoingLink {
+                expanded_uri: Some("/_assets/report.csv".to_owned()),
+                uri: "/_assets/report.csv".to_owned(),
+                position: None,
+            }])
+        );
+    }
+
     #[test]
     fn skips_html_blocks_without_download_when_extracting_assets() {
         let mut ctx = RenderContext::new();
@@ -896,6 +1194,7 @@ mod test {
             Ok(vec![OutgoingLink {
                 uri: "/foo.md".to_owned(),
                 expanded_uri: Some("/foo.md".to_owned()),
+                position: None,
             }])
         );
     }
@@ -909,7 +1208,8 @@ mod test {
             extract_links(input, &ctx),
             Ok(vec![OutgoingLink {
                 uri: "/bar/../foo.md".to_owned(),
-                expanded_uri: Some("/foo.md".to_owned())
+                expanded_uri: Some("/foo.md".to_owned()),
+                position: None,
             }])
         );
     }
@@ -924,7 +1224,8 @@ mod test {
             extract_links(input, &ctx),
             Ok(vec![OutgoingLink {
                 uri: "../foo.md".to_owned(),
-                expanded_uri: Some("/fizz/foo.md".to_owned())
+                expanded_uri: Some("/fizz/foo.md".to_owned()),
+                position: None,
             }])
         );
     }
@@ -938,7 +1239,8 @@ mod test {
             extract_links(input, &ctx),
             Ok(vec![OutgoingLink {
                 uri: "./foo.md".to_owned(),
-                expanded_uri: Some("/foo.md".to_owned())
+                expanded_uri: Some("/foo.md".to_owned()),
+                position: None,
             }])
         );
     }
@@ -1028,6 +1330,30 @@ mod test {
         );
     }
 
+    #[test]
+    fn to_final_link_applies_the_url_style() {
+        let opts = RenderOptions {
+            webbify_internal_urls: true,
+            prefix_link_urls: Some("/v1".to_string()),
+            url_style: Some(crate::UrlStyle::TrailingSlash),
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new();
+        ctx.with_options(&opts);
+
+        assert_eq!(to_final_link("/foo/bar.md#baz", &ctx), "/v1/foo/bar/#baz");
+        assert_eq!(to_final_link("/foo.html", &ctx), "/v1/foo/");
+        assert_eq!(to_final_link("/", &ctx), "/v1/");
+        assert_eq!(
+            to_final_link("/_assets/logo.png", &ctx),
+            "/v1/_assets/logo.png"
+        );
+        assert_eq!(
+            to_final_link("https://example.com/foo", &ctx),
+            "https://example.com/foo"
+        );
+    }
+
     #[test]
     fn prefix_and_expand_path_returns_correct_links() {
         use upath::Path;
@@ -1086,32 +1412,24 @@ mod test {
         [internal link](./foo.md)
         [external link 1](https://www.example.com)
         [external link 2](https://api.example.com)
-        "# };
-
-        assert_eq!(
-            extract_external_links(input, &RenderContext::new()),
-            Ok(vec![
-                "https://api.example.com".to_string(),
-                "https://www.example.com".to_string()
-            ])
-        )
-    }
+        [email](mailto:hello@example.com)
 
-    #[test]
-    fn it_extracts_links_as_unique() {
-        let input = indoc! { r#"
-        [internal link](./foo.md)
-        [external link 1](https://www.example.com)
-        [external link 2](https://api.example.com)
-        [external link 3](https://api.example.com)
+        <a href="http://old.example.com">Old</a>
         "# };
 
+        let ctx = RenderContext::new();
+        let ast = to_ast_mdx(input, &ctx).unwrap();
+
         assert_eq!(
-            extract_external_links(input, &RenderContext::new()),
-            Ok(vec![
+            external_links_in_ast(&ast, &ctx)
+                .into_iter()
+                .map(|l| l.uri)
+                .collect::<Vec<_>>(),
+            vec![
+                "https://www.example.com".to_string(),
                 "https://api.example.com".to_string(),
-                "https://www.example.com".to_string()
-            ])
+                "http://old.example.com".to_string(),
+            ]
         )
     }
 
@@ -1517,4 +1835,104 @@ mod test {
             assert_eq!(line_number_to_byte_offset(input, 2), 0);
         }
     }
+
+    mod callout_directives {
+        use super::*;
+
+        #[test]
+        fn expands_directives_line_by_line() {
+            let input = indoc! {r#"
+            Before
+
+            :::note Don't say "hi"
+            Inside
+            :::
+
+            After
+            "#};
+
+            assert_str_eq!(
+                expand_callout_directives(input),
+                indoc! {r#"
+                Before
+
+                <Callout type="note" title="Don't say &quot;hi&quot;">
+                Inside
+                </Callout>
+
+                After
+                "#}
+            );
+        }
+
+        #[test]
+        fn leaves_code_blocks_alone() {
+            let input = indoc! {"
+            ```md
+            :::warning
+            Not a callout
+            :::
+            ```
+            "};
+
+            assert_str_eq!(expand_callout_directives(input), input);
+        }
+
+        #[test]
+        fn leaves_indented_code_blocks_alone() {
+            let input = indoc! {"
+            Some text
+
+                :::warning
+                ```
+                Not a callout
+                :::
+
+            :::note
+            ```
+            "};
+
+            assert_str_eq!(
+                expand_callout_directives(input),
+                indoc! {r#"
+                Some text
+
+                    :::warning
+                    ```
+                    Not a callout
+                    :::
+
+                <Callout type="note">
+                ```
+                </Callout>
+                "#}
+            );
+        }
+
+        #[test]
+        fn leaves_unknown_types_alone() {
+            let input = indoc! {"
+            :::details
+            Not a callout
+            :::
+            "};
+
+            assert_str_eq!(expand_callout_directives(input), input);
+        }
+
+        #[test]
+        fn closes_unclosed_directives() {
+            assert_str_eq!(
+                expand_callout_directives(":::danger\nOops"),
+                "<Callout type=\"danger\">\nOops\n</Callout>\n"
+            );
+        }
+
+        #[test]
+        fn leaves_stray_closing_markers_alone() {
+            let input = "Some text\n:::\n";
+
+            assert_str_eq!(expand_callout_directives(input), input);
+        }
+    }
 }
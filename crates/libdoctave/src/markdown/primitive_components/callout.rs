@@ -0,0 +1,482 @@
+use crate::{
+    autocomplete::PrimitiveComponentAutocomplete,
+    expressions::Value,
+    markdown::error_renderer::{self, parse_int_in_range, Highlight, Location},
+    primitive_components::{component_id, r#box::PADDING_KEY, tabs::TITLE_KEY},
+    render_context::RenderContext,
+    renderable_ast::Position,
+    Anchorizer,
+};
+
+use thiserror::Error;
+
+pub static TYPE_KEY: &str = "type";
+pub static COLLAPSIBLE_KEY: &str = "collapsible";
+pub static DEFAULT_OPEN_KEY: &str = "default_open";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Callout {
+    #[serde(rename = "type")]
+    pub kind: CalloutType,
+    pub title: Option<String>,
+    pub padding: usize,
+    /// Collapsible callouts can be opened and closed by clicking the title.
+    pub collapsible: bool,
+    /// Whether a collapsible callout starts out open.
+    pub default_open: bool,
+    /// Only collapsible callouts have an ID, so that they can be linked to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl Callout {
+    pub fn try_new(
+        kind: Option<Value>,
+        title: Option<Value>,
+        padding: Option<Value>,
+        collapsible: Option<Value>,
+        default_open: Option<Value>,
+    ) -> Result<Self> {
+        let mut callout = Self::default();
+
+        if let Some(kind) = kind {
+            callout.kind = kind.to_string().as_str().try_into()?;
+        }
+        if let Some(title) = title {
+            callout.title = Some(title.to_string());
+        }
+        if let Some(padding) = padding {
+            callout.padding = parse_int_in_range(padding.to_string().as_str(), 0..6)
+                .ok_or(Error::InvalidPadding(padding.to_string()))?;
+        }
+        if let Some(collapsible) = collapsible {
+            callout.collapsible = parse_bool(collapsible, COLLAPSIBLE_KEY)?;
+        }
+        if let Some(default_open) = default_open {
+            if !callout.collapsible {
+                return Err(Error::DefaultOpenWithoutCollapsible);
+            }
+
+            callout.default_open = parse_bool(default_open, DEFAULT_OPEN_KEY)?;
+        }
+
+        Ok(callout)
+    }
+
+    /// Gives a collapsible callout an ID from its title, or its type if it
+    /// doesn't have one.
+    pub(crate) fn assign_id(&mut self, anchorizer: &mut Anchorizer, heading: Option<&str>) {
+        if self.collapsible {
+            let name = match &self.title {
+                Some(title) => format!("callout {}", title),
+                None => format!("callout {}", self.kind),
+            };
+
+            self.id = Some(component_id(anchorizer, heading, &name));
+        }
+    }
+}
+
+fn parse_bool(value: Value, key: &'static str) -> Result<bool> {
+    match value {
+        Value::Bool(b) => Ok(b),
+        other => match other.to_string().as_str() {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            found => Err(Error::InvalidBool(key, found.to_string())),
+        },
+    }
+}
+
+impl Default for Callout {
+    fn default() -> Self {
+        Callout {
+            kind: CalloutType::Info,
+            title: None,
+            padding: 2,
+            collapsible: false,
+            default_open: false,
+            id: None,
+        }
+    }
+}
+
+impl PrimitiveComponentAutocomplete for Callout {
+    fn title(&self) -> &str {
+        "Callout"
+    }
+
+    fn attributes(&self) -> Vec<&str> {
+        vec!["type", "title", "pad", "collapsible", "default_open"]
+    }
+
+    fn attribute_values(&self, attribute: &str) -> Vec<&str> {
+        match attribute {
+            "type" => vec!["info", "note", "success", "warning", "danger"],
+            "pad" => vec!["0", "1", "2", "3", "4", "5"],
+            "collapsible" | "default_open" => vec!["true", "false"],
+            _ => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalloutType {
+    Info,
+    Note,
+    Success,
+    Warning,
+    Danger,
+}
+
+impl std::fmt::Display for CalloutType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use CalloutType::*;
+        match self {
+            Info => write!(f, "info"),
+            Note => write!(f, "note"),
+            Success => write!(f, "success"),
+            Warning => write!(f, "warning"),
+            Danger => write!(f, "danger"),
+        }
+    }
+}
+
+impl TryFrom<&str> for CalloutType {
+    type Error = Error;
+
+    fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
+        match value {
+            "info" => Ok(CalloutType::Info),
+            "note" => Ok(CalloutType::Note),
+            "success" => Ok(CalloutType::Success),
+            "warning" => Ok(CalloutType::Warning),
+            // `error` was the name of the type before `danger` was added
+            "danger" | "error" => Ok(CalloutType::Danger),
+            _ => Err(Error::InvalidType(value.to_string())),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(
+        r#"Invalid {TYPE_KEY}. Expected one of `info`, `note`, `success`, `warning` or `danger`."#
+    )]
+    InvalidType(String),
+    #[error(r#"Invalid {PADDING_KEY}. Expected value to be a number between 0 and 5."#)]
+    InvalidPadding(String),
+    #[error(r#"Invalid {0}. Expected value to be true or false."#)]
+    InvalidBool(&'static str, String),
+    #[error(r#"Cannot use {DEFAULT_OPEN_KEY} without {COLLAPSIBLE_KEY}"#)]
+    DefaultOpenWithoutCollapsible,
+    #[error(r#"Callouts cannot be nested"#)]
+    Nested,
+}
+
+impl Error {
+    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, pos: &Position) -> String {
+        let mut highlights = vec![];
+
+        match self {
+            Error::InvalidType(found)
+            | Error::InvalidPadding(found)
+            | Error::InvalidBool(_, found) => {
+                let key = match self {
+                    Error::InvalidType(_) => TYPE_KEY,
+                    Error::InvalidPadding(_) => PADDING_KEY,
+                    Error::InvalidBool(key, _) => *key,
+                    _ => unreachable!(),
+                };
+                let pos = error_renderer::offset_attribute_error_pos(md, key, found, pos);
+                let location = Location::Point(pos.start.row, pos.start.col + 1);
+
+                let highlight = Highlight {
+                    location,
+                    span: found.len(),
+                    msg: None,
+                };
+
+                highlights.push(highlight);
+            }
+            Error::DefaultOpenWithoutCollapsible | Error::Nested => {
+                let location = Location::Point(pos.start.row, pos.start.col);
+
+                let msg = match self {
+                    Error::Nested => "Nested callout".to_string(),
+                    _ => format!("Add {COLLAPSIBLE_KEY}"),
+                };
+
+                let highlight = Highlight {
+                    location,
+                    span: 1,
+                    msg: Some(msg),
+                };
+
+                highlights.push(highlight);
+            }
+        }
+
+        error_renderer::render(md, &self.to_string(), highlights, ctx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_str_eq;
+
+    use crate::{ast_mdx, render_context::RenderContext};
+
+    #[test]
+    fn callout_renders_with_default() {
+        let input = indoc! {"
+        <Callout>
+            Hello **world**
+        </Callout>
+        "};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Callout type={info} padding={2}>
+                <Paragraph>
+                    <Text>
+                        Hello
+                    </Text>
+                    <Strong>
+                        <Text>
+                            world
+                        </Text>
+                    </Strong>
+                </Paragraph>
+            </Callout>
+            "#}
+        );
+    }
+
+    #[test]
+    fn callout_renders_with_type() {
+        let input = indoc! {r#"
+        <Callout type="warning">
+            Hello **world**
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Callout type={warning} padding={2}>
+                <Paragraph>
+                    <Text>
+                        Hello
+                    </Text>
+                    <Strong>
+                        <Text>
+                            world
+                        </Text>
+                    </Strong>
+                </Paragraph>
+            </Callout>
+            "#}
+        );
+    }
+
+    #[test]
+    fn callout_renders_with_padding() {
+        let input = indoc! {r#"
+        <Callout pad="4">
+            Hello **world**
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Callout type={info} padding={4}>
+                <Paragraph>
+                    <Text>
+                        Hello
+                    </Text>
+                    <Strong>
+                        <Text>
+                            world
+                        </Text>
+                    </Strong>
+                </Paragraph>
+            </Callout>
+            "#}
+        );
+    }
+
+    #[test]
+    fn callout_with_attributes() {
+        let input = indoc! {r#"
+        <Callout type="warning" title="Careful" pad="4" collapsible default_open="true">
+            Hello
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Callout type={warning} title={"Careful"} padding={4} collapsible={true} default_open={true}>
+                <Paragraph>
+                    <Text>
+                        Hello
+                    </Text>
+                </Paragraph>
+            </Callout>
+            "#}
+        );
+    }
+
+    #[test]
+    fn callout_error_type_is_danger() {
+        let input = indoc! {r#"
+        <Callout type="error">
+            Hello
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert!(node
+            .debug_string()
+            .unwrap()
+            .starts_with("<Callout type={danger} padding={2}>"));
+    }
+
+    #[test]
+    fn callout_invalid_type() {
+        let input = indoc! {r#"
+        <Callout type="fatal">
+            Hello
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_COMPONENT);
+        assert_str_eq!(error.message, "Error in callout");
+        assert_str_eq!(
+            error.description,
+            indoc! {r#"
+            Invalid type. Expected one of `info`, `note`, `success`, `warning` or `danger`.
+
+                1 │ <Callout type="fatal">
+                                  ▲▲▲▲▲
+
+            "#}
+        );
+    }
+
+    #[test]
+    fn callout_default_open_without_collapsible() {
+        let input = indoc! {r#"
+        <Callout default_open>
+            Hello
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_COMPONENT);
+        assert!(error
+            .description
+            .starts_with("Cannot use default_open without collapsible"));
+    }
+
+    #[test]
+    fn nested_callouts_are_rejected() {
+        let input = indoc! {r#"
+        <Callout>
+            Outer
+
+            <Callout type="warning">
+                Inner
+            </Callout>
+        </Callout>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_COMPONENT);
+        assert_str_eq!(error.message, "Error in callout");
+        assert!(error.description.starts_with("Callouts cannot be nested"));
+        assert_eq!(error.position.as_ref().unwrap().start.row, 4);
+    }
+
+    #[test]
+    fn directive_shorthand() {
+        let input = indoc! {r#"
+        :::warning Be careful
+        Hello **world**
+        :::
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Callout type={warning} title={"Be careful"} padding={2}>
+                <Paragraph>
+                    <Text>
+                        Hello
+                    </Text>
+                    <Strong>
+                        <Text>
+                            world
+                        </Text>
+                    </Strong>
+                </Paragraph>
+            </Callout>
+            "#}
+        );
+    }
+
+    #[test]
+    fn directive_shorthand_title_with_quotes() {
+        let input = indoc! {r#"
+        :::warning Don't say "hi" & run
+        Hello
+        :::
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Callout type={warning} title={"Don't say \"hi\" & run"} padding={2}>
+                <Paragraph>
+                    <Text>
+                        Hello
+                    </Text>
+                </Paragraph>
+            </Callout>
+            "#}
+        );
+    }
+}
@@ -2,10 +2,11 @@ use crate::{
     autocomplete::PrimitiveComponentAutocomplete,
     expressions::Value,
     markdown::error_renderer::{self, Highlight, Location},
+    primitive_components::component_id,
     primitive_components::tabs::TITLE_KEY,
     render_context::RenderContext,
     renderable_ast::Position,
-    Node, NodeKind,
+    Anchorizer, Node, NodeKind,
 };
 use thiserror::Error;
 
@@ -91,7 +92,11 @@ impl Error {
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
-pub struct CodeSelect;
+pub struct CodeSelect {
+    pub id: String,
+    /// IDs of the code blocks to pick from, in order
+    pub option_ids: Vec<String>,
+}
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct CodeTab {
@@ -143,6 +148,24 @@ impl CodeSelect {
         Ok(())
     }
 
+    /// Gives the block and each of its code blocks an ID from their labels.
+    pub(crate) fn assign_ids(
+        &mut self,
+        children: &[Node],
+        anchorizer: &mut Anchorizer,
+        heading: Option<&str>,
+    ) {
+        self.id = component_id(anchorizer, heading, "code");
+        self.option_ids = children
+            .iter()
+            .filter_map(|child| match &child.kind {
+                NodeKind::Code { label, .. } => Some(label.as_deref().unwrap_or_default()),
+                _ => None,
+            })
+            .map(|label| component_id(anchorizer, heading, &format!("code {}", label)))
+            .collect();
+    }
+
     pub fn overwrite_code_blocks(nodes: &mut Vec<Node>, parent_title: Option<&Value>) {
         for node in nodes {
             if let NodeKind::Code { title, .. } = &mut node.kind {
@@ -212,6 +235,45 @@ mod test {
             );
         }
 
+        #[test]
+        fn ids_follow_labels() {
+            let input = indoc! {r#"
+            ## Usage
+
+            <CodeSelect title="foo">
+            ```javascript
+            const foo = 'bar';
+            ```
+            ```rust
+            let foo = "bar";
+            ```
+            </CodeSelect>
+            "#};
+
+            let ctx = RenderContext::default();
+            let node = &ast_mdx(input, &ctx).unwrap();
+            let code_select = node
+                .walk()
+                .find_map(|n| match &n.kind {
+                    crate::NodeKind::CodeSelect(code_select) => Some(code_select.clone()),
+                    _ => None,
+                })
+                .unwrap();
+
+            assert_eq!(code_select.id, "usage--code");
+            assert_eq!(
+                code_select.option_ids,
+                vec!["usage--code-javascript", "usage--code-rust"]
+            );
+            assert_eq!(
+                node.resolve_fragment("usage--code-rust"),
+                Some(crate::FragmentTarget::CodeSelectOption {
+                    code_select_id: "usage--code".to_string(),
+                    index: 1
+                })
+            );
+        }
+
         #[test]
         fn unexpected_attributes() {
             let markdown = indoc! {r#"
@@ -0,0 +1,353 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    autocomplete::PrimitiveComponentAutocomplete,
+    canonical_path::canonicalize,
+    expressions::Value,
+    markdown::error_renderer::{self, Highlight, Location},
+    markdown::parser::expand_paths_in_local_links,
+    render_context::RenderContext,
+    renderable_ast::Position,
+    statistics::format_bytes,
+    Attribute, AttributeValue,
+};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub static SRC_KEY: &str = "src";
+pub static LABEL_KEY: &str = "label";
+
+/// File types that browsers display on their own, so links to them open the
+/// file instead of downloading it.
+const INLINE_EXTENSIONS: &[&str] = &[
+    "avif", "gif", "htm", "html", "jpeg", "jpg", "pdf", "png", "svg", "txt", "webp",
+];
+
+/// Details about a file under `_assets` that is linked to, so that themes can
+/// show e.g. "Download (CSV, 2.3 MB)".
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileInfo {
+    /// Lowercase file extension, like `csv`
+    pub extension: Option<String>,
+    /// Only known if the asset is part of the project being rendered
+    pub size_bytes: Option<usize>,
+    /// Human readable size, like `2.3 MB`
+    pub size: Option<String>,
+    /// Whether the file should be downloaded rather than opened in the browser
+    pub download: bool,
+}
+
+impl FileInfo {
+    /// Looks up the asset a link points at. Returns `None` for links that
+    /// don't point into `_assets`.
+    pub(crate) fn for_link(url: &str, ctx: &RenderContext) -> Option<Self> {
+        asset_path(url, ctx).map(|path| Self::for_asset(&path, ctx))
+    }
+
+    fn for_asset(path: &Path, ctx: &RenderContext) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        let size_bytes = ctx
+            .assets
+            .iter()
+            .find(|asset| canonicalize(&asset.path) == path)
+            .map(|asset| asset.size_bytes);
+
+        FileInfo {
+            download: !extension
+                .as_deref()
+                .is_some_and(|e| INLINE_EXTENSIONS.contains(&e)),
+            extension,
+            size: size_bytes.map(format_bytes),
+            size_bytes,
+        }
+    }
+
+    /// Data attributes for raw `<a download>` links, which can't carry the
+    /// details in the AST node itself.
+    pub(crate) fn html_attributes(&self) -> Vec<Attribute> {
+        let mut attributes = vec![];
+
+        if let Some(extension) = &self.extension {
+            attributes.push(Attribute {
+                key: String::from("data-file-extension"),
+                value: Some(AttributeValue::Literal(extension.clone())),
+            });
+        }
+        if let Some(size) = &self.size {
+            attributes.push(Attribute {
+                key: String::from("data-file-size"),
+                value: Some(AttributeValue::Literal(size.clone())),
+            });
+        }
+
+        attributes
+    }
+}
+
+/// The path of the file under `_assets` that a link points at, if any.
+pub(crate) fn asset_path(url: &str, ctx: &RenderContext) -> Option<PathBuf> {
+    let without_fragment = url.split(['#', '?']).next().unwrap_or(url);
+    let expanded = expand_paths_in_local_links(without_fragment, ctx)?;
+    let path = canonicalize(Path::new(expanded.trim_start_matches('/')));
+
+    path.starts_with("_assets").then_some(path)
+}
+
+/// A card for downloading a file from `_assets`.
+///
+/// `<Download src="/_assets/report.csv" label="Q3 report" />`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Download {
+    pub src: String,
+    /// Defaults to the name of the file
+    pub label: String,
+    pub file: FileInfo,
+}
+
+impl Download {
+    pub(crate) fn try_new(
+        src: Option<Value>,
+        label: Option<Value>,
+        ctx: &RenderContext,
+    ) -> Result<Self> {
+        let src = src.map(|s| s.to_string()).ok_or(Error::MissingSrc)?;
+        let path = asset_path(&src, ctx).ok_or_else(|| Error::NotAnAsset(src.clone()))?;
+
+        let label = match label {
+            Some(label) => label.to_string(),
+            None => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+
+        Ok(Download {
+            src: format!("/{}", path.display()),
+            label,
+            file: FileInfo {
+                // The card is always a download, even for files browsers could show
+                download: true,
+                ..FileInfo::for_asset(&path, ctx)
+            },
+        })
+    }
+}
+
+impl Default for Download {
+    fn default() -> Self {
+        Download {
+            src: String::new(),
+            label: String::new(),
+            file: FileInfo {
+                extension: None,
+                size_bytes: None,
+                size: None,
+                download: true,
+            },
+        }
+    }
+}
+
+impl PrimitiveComponentAutocomplete for Download {
+    fn title(&self) -> &str {
+        "Download"
+    }
+
+    fn attributes(&self) -> Vec<&str> {
+        vec!["src", "label"]
+    }
+
+    fn attribute_values(&self, _attribute: &str) -> Vec<&str> {
+        vec![]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(r#"Missing {SRC_KEY}. Expected the path of a file under `_assets`."#)]
+    MissingSrc,
+    #[error(r#"Invalid {SRC_KEY}. Expected the path of a file under `_assets`."#)]
+    NotAnAsset(String),
+}
+
+impl Error {
+    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, node_pos: &Position) -> String {
+        let highlight = match self {
+            Error::MissingSrc => Highlight {
+                location: Location::Point(node_pos.start.row, node_pos.start.col),
+                span: 1,
+                msg: Some(format!("Add {SRC_KEY}")),
+            },
+            Error::NotAnAsset(found) => {
+                let pos = error_renderer::offset_attribute_error_pos(md, SRC_KEY, found, node_pos);
+
+                Highlight {
+                    location: Location::Point(pos.start.row, pos.start.col + 1),
+                    span: found.len(),
+                    msg: None,
+                }
+            }
+        };
+
+        error_renderer::render(md, &self.to_string(), vec![highlight], ctx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_str_eq;
+
+    use crate::{ast_mdx, project::Asset, render_context::RenderContext};
+
+    use super::*;
+
+    fn assets() -> Vec<Asset> {
+        vec![Asset {
+            path: PathBuf::from("_assets/reports/q3.csv"),
+            signature: 1,
+            size_bytes: 2_400_000,
+            svg: None,
+        }]
+    }
+
+    #[test]
+    fn download_renders_with_file_details() {
+        let input = indoc! {r#"
+        <Download src="/_assets/reports/q3.csv" label="Q3 report" />
+        "#};
+
+        let assets = assets();
+        let mut ctx = RenderContext::default();
+        ctx.with_assets(&assets);
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Download src={/_assets/reports/q3.csv} label={Q3 report} extension={csv} size={2.3 MB}>
+            </Download>
+            "#}
+        );
+    }
+
+    #[test]
+    fn download_label_defaults_to_file_name() {
+        let input = indoc! {r#"
+        <Download src="/_assets/reports/q3.csv" />
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Download src={/_assets/reports/q3.csv} label={q3.csv} extension={csv}>
+            </Download>
+            "#}
+        );
+    }
+
+    #[test]
+    fn download_requires_src() {
+        let input = indoc! {r#"
+        <Download label="Q3 report" />
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_COMPONENT);
+        assert_str_eq!(error.message, "Error in download");
+        assert!(error.description.starts_with("Missing src."));
+    }
+
+    #[test]
+    fn download_must_point_at_an_asset() {
+        let input = indoc! {r#"
+        <Download src="https://example.com/report.csv" />
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_COMPONENT);
+        assert_str_eq!(
+            error.description,
+            indoc! {r#"
+            Invalid src. Expected the path of a file under `_assets`.
+
+                1 │ <Download src="https://example.com/report.csv" />
+                                  ▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲
+
+            "#}
+        );
+    }
+
+    #[test]
+    fn links_to_assets_carry_file_details() {
+        let input = indoc! {r#"
+        [Report](/_assets/reports/q3.csv) and [photo](/_assets/cat.png)
+        "#};
+
+        let assets = assets();
+        let mut ctx = RenderContext::default();
+        ctx.with_assets(&assets);
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Paragraph>
+                <Link url={/_assets/reports/q3.csv} extension={csv} size={2.3 MB} download={true}>
+                    <Text>
+                        Report
+                    </Text>
+                </Link>
+                <Text>
+                    and
+                </Text>
+                <Link url={/_assets/cat.png} extension={png} download={false}>
+                    <Text>
+                        photo
+                    </Text>
+                </Link>
+            </Paragraph>
+            "#}
+        );
+    }
+
+    #[test]
+    fn download_html_links_carry_file_details() {
+        let input = indoc! {r#"
+        <a download href="/_assets/reports/q3.csv">
+          Report
+        </a>
+        "#};
+
+        let assets = assets();
+        let mut ctx = RenderContext::default();
+        ctx.with_assets(&assets);
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <a download href={/_assets/reports/q3.csv} data-file-extension={csv} data-file-size={2.3 MB}>
+                <Paragraph>
+                    <Text>
+                        Report
+                    </Text>
+                </Paragraph>
+            </a>
+            "#}
+        );
+    }
+}
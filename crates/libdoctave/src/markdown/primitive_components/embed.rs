@@ -0,0 +1,377 @@
+use serde::Serialize;
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    autocomplete::PrimitiveComponentAutocomplete,
+    expressions::Value,
+    markdown::error_renderer::{self, Highlight, Location},
+    render_context::RenderContext,
+    renderable_ast::Position,
+    settings::EmbedProvider,
+    SETTINGS_FILE_NAME,
+};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+pub static PROVIDER_KEY: &str = "provider";
+pub static ID_KEY: &str = "id";
+pub static ASPECT_RATIO_KEY: &str = "aspect_ratio";
+
+/// Content from another site, like a video, from one of the providers in
+/// the `embeds` setting. Themes show a placeholder until the reader clicks
+/// on it, and only then load the URL in a sandboxed frame.
+///
+/// `<Embed provider="youtube" id="dQw4w9WgXcQ" title="Product tour" start="30" />`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Embed {
+    pub provider: String,
+    pub id: String,
+    /// The URL to load, with the ID and parameters filled in
+    pub url: String,
+    /// Host the content is loaded from, e.g. `www.youtube-nocookie.com`
+    pub domain: String,
+    /// Describes the content for screen readers
+    pub title: String,
+    /// Width to height, e.g. `16:9`
+    pub aspect_ratio: String,
+    /// Whether the URL is the provider's `privacy_url`
+    pub privacy_mode: bool,
+}
+
+impl Embed {
+    pub(crate) fn try_new(
+        provider: Option<Value>,
+        id: Option<Value>,
+        title: Option<Value>,
+        aspect_ratio: Option<Value>,
+        params: Vec<(String, Value)>,
+        ctx: &RenderContext,
+    ) -> Result<Self> {
+        let name = provider
+            .map(|p| p.to_string())
+            .ok_or(Error::MissingProvider)?;
+        let settings = ctx
+            .settings
+            .embeds()
+            .get(&name)
+            .ok_or_else(|| Error::UnknownProvider(name.clone(), known_providers(ctx)))?;
+
+        let id = id.map(|i| i.to_string()).ok_or(Error::MissingId)?;
+
+        // The pattern is checked when the settings are parsed
+        if !settings
+            .id_regex()
+            .map(|regex| regex.is_match(&id))
+            .unwrap_or(false)
+        {
+            return Err(Error::InvalidId(id, settings.id_pattern.clone()));
+        }
+
+        let title = title
+            .map(|t| t.to_string())
+            .filter(|t| !t.trim().is_empty())
+            .ok_or(Error::MissingTitle)?;
+
+        let aspect_ratio = match aspect_ratio {
+            Some(ratio) => {
+                let ratio = ratio.to_string();
+                EmbedProvider::parse_aspect_ratio(&ratio)
+                    .ok_or_else(|| Error::InvalidAspectRatio(ratio.clone()))?;
+                ratio
+            }
+            None => settings.aspect_ratio.clone(),
+        };
+
+        if let Some((key, _)) = params
+            .iter()
+            .find(|(key, _)| !settings.params.contains(key))
+        {
+            let expected = if settings.params.is_empty() {
+                String::from("It doesn't take any parameters.")
+            } else {
+                format!("Expected one of: {}.", settings.params.join(", "))
+            };
+
+            return Err(Error::UnexpectedParam(key.clone(), name, expected));
+        }
+
+        let mut url = Url::parse(&settings.url_template().replace("{id}", &id))
+            .map_err(|_| Error::InvalidId(id.clone(), settings.id_pattern.clone()))?;
+
+        // In the order the provider lists them, so the URL doesn't depend on
+        // the order of the attributes
+        for allowed in &settings.params {
+            if let Some((key, value)) = params.iter().find(|(key, _)| key == allowed) {
+                url.query_pairs_mut().append_pair(key, &value.to_string());
+            }
+        }
+
+        Ok(Embed {
+            domain: url.host_str().unwrap_or_default().to_owned(),
+            url: url.to_string(),
+            provider: name,
+            id,
+            title,
+            aspect_ratio,
+            privacy_mode: settings.privacy_mode,
+        })
+    }
+}
+
+fn known_providers(ctx: &RenderContext) -> String {
+    if ctx.settings.embeds().is_empty() {
+        format!("none, add one under `embeds` in {}", SETTINGS_FILE_NAME)
+    } else {
+        ctx.settings
+            .embeds()
+            .keys()
+            .map(|k| format!("\"{}\"", k))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl Default for Embed {
+    fn default() -> Self {
+        Embed {
+            provider: String::new(),
+            id: String::new(),
+            url: String::new(),
+            domain: String::new(),
+            title: String::new(),
+            aspect_ratio: String::from("16:9"),
+            privacy_mode: false,
+        }
+    }
+}
+
+impl PrimitiveComponentAutocomplete for Embed {
+    fn title(&self) -> &str {
+        "Embed"
+    }
+
+    fn attributes(&self) -> Vec<&str> {
+        vec!["provider", "id", "title", "aspect_ratio"]
+    }
+
+    fn attribute_values(&self, _attribute: &str) -> Vec<&str> {
+        vec![]
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(r#"Missing {PROVIDER_KEY}. Expected one of the providers under `embeds` in docapella.yaml."#)]
+    MissingProvider,
+    #[error(r#"Unknown {PROVIDER_KEY} "{0}". Expected one of: {1}."#)]
+    UnknownProvider(String, String),
+    #[error(r#"Missing {ID_KEY}. Expected the ID of the content to embed."#)]
+    MissingId,
+    #[error(r#"Invalid {ID_KEY} "{0}". Expected an ID matching `{1}`."#)]
+    InvalidId(String, String),
+    #[error(
+        r#"Missing title. Embeds need a title that describes their content for screen readers."#
+    )]
+    MissingTitle,
+    #[error(r#"Invalid {ASPECT_RATIO_KEY} "{0}". Expected a width and height like "16:9"."#)]
+    InvalidAspectRatio(String),
+    #[error(r#"Unexpected parameter "{0}" for the provider "{1}". {2}"#)]
+    UnexpectedParam(String, String, String),
+}
+
+impl Error {
+    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, node_pos: &Position) -> String {
+        let highlight = match self {
+            Error::MissingProvider | Error::MissingId | Error::MissingTitle => {
+                let key = match self {
+                    Error::MissingProvider => PROVIDER_KEY,
+                    Error::MissingId => ID_KEY,
+                    _ => "title",
+                };
+
+                Highlight {
+                    location: Location::Point(node_pos.start.row, node_pos.start.col),
+                    span: 1,
+                    msg: Some(format!("Add {key}")),
+                }
+            }
+            Error::UnknownProvider(found, _)
+            | Error::InvalidId(found, _)
+            | Error::InvalidAspectRatio(found) => {
+                let key = match self {
+                    Error::UnknownProvider(..) => PROVIDER_KEY,
+                    Error::InvalidId(..) => ID_KEY,
+                    _ => ASPECT_RATIO_KEY,
+                };
+                let pos = error_renderer::offset_attribute_error_pos(md, key, found, node_pos);
+
+                Highlight {
+                    location: Location::Point(pos.start.row, pos.start.col + 1),
+                    span: found.len(),
+                    msg: None,
+                }
+            }
+            Error::UnexpectedParam(key, _, _) => {
+                let pos = error_renderer::offset_attribute_key_error_pos(md, key, node_pos);
+
+                Highlight {
+                    location: Location::Point(pos.start.row, pos.start.col),
+                    span: pos.end.col - pos.start.col,
+                    msg: None,
+                }
+            }
+        };
+
+        error_renderer::render(md, &self.to_string(), vec![highlight], ctx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_str_eq;
+
+    use crate::{ast_mdx, renderable_ast::NodeKind, settings::Settings};
+
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings::parse(indoc! {r#"
+        ---
+        title: Embeds
+        embeds:
+          youtube:
+            url: https://www.youtube.com/embed/{id}
+            privacy_url: https://www.youtube-nocookie.com/embed/{id}
+            privacy_mode: true
+            id_pattern: "[A-Za-z0-9_-]{11}"
+            params: [start, autoplay]
+          loom:
+            url: https://www.loom.com/embed/{id}
+            aspect_ratio: "4:3"
+        "#})
+        .unwrap()
+    }
+
+    #[test]
+    fn embed_resolves_the_url() {
+        let input = indoc! {r#"
+        <Embed provider="youtube" id="dQw4w9WgXcQ" title="Product tour" autoplay="1" start="30" />
+        "#};
+
+        let settings = settings();
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&settings);
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Embed provider={youtube} url={https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ?start=30&autoplay=1} title={Product tour} aspect_ratio={16:9}>
+            </Embed>
+            "#}
+        );
+
+        let NodeKind::Embed(embed) = &node.children[0].kind else {
+            panic!("Not an embed: {:#?}", node);
+        };
+        assert_eq!(embed.domain, "www.youtube-nocookie.com");
+        assert!(embed.privacy_mode);
+    }
+
+    #[test]
+    fn embed_uses_the_providers_aspect_ratio() {
+        let input = indoc! {r#"
+        <Embed provider="loom" id="abc123" title="Walkthrough" />
+
+        <Embed provider="loom" id="abc123" title="Walkthrough" aspect_ratio="1:1" />
+        "#};
+
+        let settings = settings();
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&settings);
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Embed provider={loom} url={https://www.loom.com/embed/abc123} title={Walkthrough} aspect_ratio={4:3}>
+            </Embed>
+            <Embed provider={loom} url={https://www.loom.com/embed/abc123} title={Walkthrough} aspect_ratio={1:1}>
+            </Embed>
+            "#}
+        );
+    }
+
+    #[test]
+    fn embed_requires_a_known_provider() {
+        let input = indoc! {r#"
+        <Embed provider="vimeo" id="123" title="Demo" />
+        "#};
+
+        let settings = settings();
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&settings);
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_COMPONENT);
+        assert_str_eq!(error.message, "Error in embed");
+        assert!(error.position.is_some());
+        assert_str_eq!(
+            error.description,
+            indoc! {r#"
+            Unknown provider "vimeo". Expected one of: "loom", "youtube".
+
+                1 │ <Embed provider="vimeo" id="123" title="Demo" />
+                                    ▲▲▲▲▲
+
+            "#}
+        );
+    }
+
+    #[test]
+    fn embed_validates_the_id() {
+        let input = indoc! {r#"
+        <Embed provider="youtube" id="../../evil" title="Demo" />
+        "#};
+
+        let settings = settings();
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&settings);
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert!(error.description.starts_with(
+            r#"Invalid id "../../evil". Expected an ID matching `[A-Za-z0-9_-]{11}`."#
+        ));
+    }
+
+    #[test]
+    fn embed_requires_a_title() {
+        let input = indoc! {r#"
+        <Embed provider="loom" id="abc123" />
+        "#};
+
+        let settings = settings();
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&settings);
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert!(error.description.starts_with("Missing title."));
+    }
+
+    #[test]
+    fn embed_only_allows_the_providers_params() {
+        let input = indoc! {r#"
+        <Embed provider="loom" id="abc123" title="Demo" start="30" />
+        "#};
+
+        let settings = settings();
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&settings);
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert!(error
+            .description
+            .starts_with(r#"Unexpected parameter "start" for the provider "loom". It doesn't take any parameters."#));
+    }
+}
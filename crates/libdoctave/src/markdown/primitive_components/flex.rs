@@ -7,6 +7,8 @@ use crate::{
 };
 use thiserror::Error;
 
+use super::responsive::{self, Responsive, TokenError};
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 // Keys when we deserialize AST attributes
@@ -23,10 +25,10 @@ pub static CLASS_KEY: &str = "class";
 #[serde(rename_all = "snake_case")]
 pub struct Flex {
     pub align: FlexAlign,
-    pub padding: usize,
-    pub direction: FlexDirection,
+    pub padding: Responsive<usize>,
+    pub direction: Responsive<FlexDirection>,
     pub justify: FlexJustify,
-    pub gap: usize,
+    pub gap: Responsive<usize>,
     pub wrap: FlexWrap,
     pub height: FlexHeight,
     pub class: String,
@@ -52,106 +54,51 @@ pub enum Error {
     InvalidHeight(String),
     #[error(r#"Missing required attribute {0}"#)]
     MissingAttribute(&'static str),
+    #[error(r#"Unknown breakpoint. Expected one of `sm`, `md`, `lg`, or `xl`."#)]
+    UnknownBreakpoint(&'static str, String),
+    #[error(r#"Value given more than once for the same breakpoint."#)]
+    DuplicateBreakpoint(&'static str, String),
 }
 
 impl Error {
-    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, node_pos: &Position) -> String {
-        let mut highlights = vec![];
-
-        match self {
-            Error::InvalidJustify(found) => {
-                let pos =
-                    error_renderer::offset_attribute_error_pos(md, JUSTIFY_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
-
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
-
-                highlights.push(highlight);
+    /// Where the invalid value is in the attribute
+    pub(crate) fn pos(&self, md: &str, node_pos: &Position) -> Option<Position> {
+        let (key, found) = match self {
+            Error::InvalidJustify(found) => (JUSTIFY_KEY, found),
+            Error::InvalidGap(found) => (GAP_KEY, found),
+            Error::InvalidPadding(found) => (PADDING_KEY, found),
+            Error::InvalidAlign(found) => (ALIGN_KEY, found),
+            Error::InvalidDirection(found) => (DIRECTION_KEY, found),
+            Error::InvalidWrap(found) => (WRAP_KEY, found),
+            Error::InvalidHeight(found) => (HEIGHT_KEY, found),
+            Error::UnknownBreakpoint(key, found) | Error::DuplicateBreakpoint(key, found) => {
+                (*key, found)
             }
-            Error::InvalidGap(found) => {
-                let pos = error_renderer::offset_attribute_error_pos(md, GAP_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
+            Error::MissingAttribute(_) => return None,
+        };
 
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
+        Some(responsive::token_pos(md, key, found, node_pos))
+    }
 
-                highlights.push(highlight);
-            }
-            Error::InvalidPadding(found) => {
-                let pos =
-                    error_renderer::offset_attribute_error_pos(md, PADDING_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
-
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
-
-                highlights.push(highlight);
-            }
-            Error::InvalidAlign(found) => {
-                let pos =
-                    error_renderer::offset_attribute_error_pos(md, ALIGN_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
-
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
-
-                highlights.push(highlight);
-            }
-            Error::InvalidDirection(found) => {
-                let pos =
-                    error_renderer::offset_attribute_error_pos(md, DIRECTION_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
-
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
-
-                highlights.push(highlight);
-            }
-            Error::InvalidWrap(found) => {
-                let pos = error_renderer::offset_attribute_error_pos(md, WRAP_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
+    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, node_pos: &Position) -> String {
+        let highlights = self
+            .pos(md, node_pos)
+            .map(|pos| Highlight {
+                location: Location::Point(pos.start.row, pos.start.col + 1),
+                span: pos.end.byte_offset - pos.start.byte_offset,
+                msg: None,
+            })
+            .into_iter()
+            .collect();
 
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
+        error_renderer::render(md, &self.to_string(), highlights, ctx)
+    }
 
-                highlights.push(highlight);
-            }
-            Error::InvalidHeight(found) => {
-                let pos =
-                    error_renderer::offset_attribute_error_pos(md, HEIGHT_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
-
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
-
-                highlights.push(highlight);
-            }
-            _ => {}
+    fn token(key: &'static str) -> impl Fn(TokenError) -> Error {
+        move |e| match e {
+            TokenError::UnknownBreakpoint(token) => Error::UnknownBreakpoint(key, token),
+            TokenError::DuplicateBreakpoint(token) => Error::DuplicateBreakpoint(key, token),
         }
-
-        error_renderer::render(md, &self.to_string(), highlights, ctx)
     }
 }
 
@@ -175,18 +122,38 @@ impl Flex {
             flex.justify = justify.to_string().as_str().try_into()?;
         }
         if let Some(direction) = direction {
-            flex.direction = direction.to_string().as_str().try_into()?;
+            flex.direction = Responsive::parse(
+                &direction.to_string(),
+                flex.direction.base,
+                |value, token| {
+                    FlexDirection::try_from(value)
+                        .map_err(|_| Error::InvalidDirection(token.to_string()))
+                },
+                Error::token(DIRECTION_KEY),
+            )?;
         }
         if let Some(wrap) = wrap {
             flex.wrap = wrap.to_string().as_str().try_into()?;
         }
         if let Some(gap) = gap {
-            flex.gap = parse_int_in_range(gap.to_string().as_str(), 0..6)
-                .ok_or(Error::InvalidGap(gap.to_string()))?;
+            flex.gap = Responsive::parse(
+                &gap.to_string(),
+                flex.gap.base,
+                |value, token| {
+                    parse_int_in_range(value, 0..6).ok_or(Error::InvalidGap(token.to_string()))
+                },
+                Error::token(GAP_KEY),
+            )?;
         }
         if let Some(padding) = padding {
-            flex.padding = parse_int_in_range(padding.to_string().as_str(), 0..6)
-                .ok_or(Error::InvalidPadding(padding.to_string()))?;
+            flex.padding = Responsive::parse(
+                &padding.to_string(),
+                flex.padding.base,
+                |value, token| {
+                    parse_int_in_range(value, 0..6).ok_or(Error::InvalidPadding(token.to_string()))
+                },
+                Error::token(PADDING_KEY),
+            )?;
         }
         if let Some(height) = height {
             flex.height = height.to_string().as_str().try_into()?;
@@ -236,10 +203,10 @@ impl Default for Flex {
         Self {
             justify: FlexJustify::Start,
             align: FlexAlign::Start,
-            direction: FlexDirection::Row,
+            direction: Responsive::new(FlexDirection::Row),
             wrap: FlexWrap::Nowrap,
-            gap: 0,
-            padding: 0,
+            gap: Responsive::new(0),
+            padding: Responsive::new(0),
             height: FlexHeight::Auto,
             class: String::new(),
         }
@@ -539,6 +506,48 @@ mod test {
             );
         }
 
+        #[test]
+        fn responsive_direction() {
+            let markdown = indoc! {r#"
+            <Flex dir="column md:row" gap="1 lg:3">
+            </Flex>
+            "#};
+
+            let ctx = RenderContext::new();
+            let node = &ast_mdx(markdown, &ctx).unwrap().children[0];
+
+            assert_str_eq!(
+                node.debug_string().unwrap(),
+                indoc! { r#"
+                <Flex justify={Start} align={Start} direction={Column md:Row} wrap={Nowrap} gap={1 lg:3} padding={0} height={Auto} class={}>
+                </Flex>
+                "# },
+            );
+        }
+
+        #[test]
+        fn duplicate_breakpoint() {
+            let markdown = indoc! {r#"
+            <Flex pad="1 md:2 md:3">
+            </Flex>
+            "#};
+
+            let ctx = RenderContext::new();
+            let error = &ast_mdx(markdown, &ctx).unwrap_err();
+
+            assert_eq!(error.message, "Error in flex");
+            assert_str_eq!(
+                error.description,
+                indoc! {r#"
+                Value given more than once for the same breakpoint.
+
+                    1 │ <Flex pad="1 md:2 md:3">
+                                          ▲▲▲▲
+
+                "#}
+            );
+        }
+
         #[test]
         fn wrap() {
             let markdown = indoc! {r#"
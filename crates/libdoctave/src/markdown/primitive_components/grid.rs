@@ -10,6 +10,7 @@ use crate::{
 };
 
 use super::flex::GAP_KEY;
+use super::responsive::{self, Responsive, TokenError};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -18,13 +19,16 @@ pub static COLUMNS_KEY: &str = "cols";
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub struct Grid {
-    pub gap: usize,
-    pub columns: usize,
+    pub gap: Responsive<usize>,
+    pub columns: Responsive<usize>,
 }
 
 impl Default for Grid {
     fn default() -> Self {
-        Grid { gap: 1, columns: 2 }
+        Grid {
+            gap: Responsive::new(1),
+            columns: Responsive::new(2),
+        }
     }
 }
 
@@ -34,41 +38,42 @@ pub enum Error {
     InvalidGap(String),
     #[error(r#"Invalid {COLUMNS_KEY}. Expected value to be a number between 1 and 5."#)]
     InvalidColumns(String),
+    #[error(r#"Unknown breakpoint. Expected one of `sm`, `md`, `lg`, or `xl`."#)]
+    UnknownBreakpoint(&'static str, String),
+    #[error(r#"Value given more than once for the same breakpoint."#)]
+    DuplicateBreakpoint(&'static str, String),
 }
 
 impl Error {
-    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, node_pos: &Position) -> String {
-        let mut highlights = vec![];
+    /// Where the invalid value is in the attribute
+    pub(crate) fn pos(&self, md: &str, node_pos: &Position) -> Position {
+        let (key, found) = match self {
+            Error::InvalidGap(found) => (GAP_KEY, found),
+            Error::InvalidColumns(found) => (COLUMNS_KEY, found),
+            Error::UnknownBreakpoint(key, found) | Error::DuplicateBreakpoint(key, found) => {
+                (*key, found)
+            }
+        };
 
-        match self {
-            Error::InvalidGap(found) => {
-                let pos = error_renderer::offset_attribute_error_pos(md, GAP_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
+        responsive::token_pos(md, key, found, node_pos)
+    }
 
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
+    pub(crate) fn render(&self, md: &str, ctx: &RenderContext, node_pos: &Position) -> String {
+        let pos = self.pos(md, node_pos);
+        let highlight = Highlight {
+            location: Location::Point(pos.start.row, pos.start.col + 1),
+            span: pos.end.byte_offset - pos.start.byte_offset,
+            msg: None,
+        };
+
+        error_renderer::render(md, &self.to_string(), vec![highlight], ctx)
+    }
 
-                highlights.push(highlight);
-            }
-            Error::InvalidColumns(found) => {
-                let pos =
-                    error_renderer::offset_attribute_error_pos(md, COLUMNS_KEY, found, node_pos);
-                let location = Location::Point(pos.start.row, pos.start.col + 1);
-
-                let highlight = Highlight {
-                    location,
-                    span: found.len(),
-                    msg: None,
-                };
-
-                highlights.push(highlight);
-            }
+    fn token(key: &'static str) -> impl Fn(TokenError) -> Error {
+        move |e| match e {
+            TokenError::UnknownBreakpoint(token) => Error::UnknownBreakpoint(key, token),
+            TokenError::DuplicateBreakpoint(token) => Error::DuplicateBreakpoint(key, token),
         }
-
-        error_renderer::render(md, &self.to_string(), highlights, ctx)
     }
 }
 
@@ -77,13 +82,25 @@ impl Grid {
         let mut grid = Self::default();
 
         if let Some(gap) = gap {
-            grid.gap = parse_int_in_range(gap.to_string().as_str(), 1..6)
-                .ok_or(Error::InvalidGap(gap.to_string()))?;
+            grid.gap = Responsive::parse(
+                &gap.to_string(),
+                grid.gap.base,
+                |value, token| {
+                    parse_int_in_range(value, 1..6).ok_or(Error::InvalidGap(token.to_string()))
+                },
+                Error::token(GAP_KEY),
+            )?;
         }
 
         if let Some(cols) = cols {
-            grid.columns = parse_int_in_range(cols.to_string().as_str(), 1..5)
-                .ok_or(Error::InvalidColumns(cols.to_string()))?;
+            grid.columns = Responsive::parse(
+                &cols.to_string(),
+                grid.columns.base,
+                |value, token| {
+                    parse_int_in_range(value, 1..5).ok_or(Error::InvalidColumns(token.to_string()))
+                },
+                Error::token(COLUMNS_KEY),
+            )?;
         }
 
         Ok(grid)
@@ -221,6 +238,75 @@ mod test {
             );
         }
 
+        #[test]
+        fn responsive_columns() {
+            let markdown = indoc! {r#"
+            <Grid cols="1 md:2 lg:3">
+                <div></div>
+            </Grid>
+            "#};
+
+            let ctx = RenderContext::new();
+            let node = &ast_mdx(markdown, &ctx).unwrap().children[0];
+
+            assert_str_eq!(
+                node.debug_string().unwrap(),
+                indoc! { r#"
+                <Grid gap={1} columns={1 md:2 lg:3}>
+                    <div>
+                    </div>
+                </Grid>
+                "# }
+            );
+        }
+
+        #[test]
+        fn unknown_breakpoint() {
+            let markdown = indoc! {r#"
+            <Grid cols="1 xs:2">
+                <div></div>
+            </Grid>
+            "#};
+
+            let ctx = RenderContext::new();
+            let err = &ast_mdx(markdown, &ctx).unwrap_err();
+
+            assert_eq!(err.message, "Error in grid");
+            assert_str_eq!(
+                err.description,
+                indoc! { r#"
+                Unknown breakpoint. Expected one of `sm`, `md`, `lg`, or `xl`.
+
+                    1 │ <Grid cols="1 xs:2">
+                                      ▲▲▲▲
+
+                "#},
+            );
+        }
+
+        #[test]
+        fn invalid_responsive_cols() {
+            let markdown = indoc! {r#"
+            <Grid cols="2 lg:9">
+                <div></div>
+            </Grid>
+            "#};
+
+            let ctx = RenderContext::new();
+            let err = &ast_mdx(markdown, &ctx).unwrap_err();
+
+            assert_str_eq!(
+                err.description,
+                indoc! { r#"
+                Invalid cols. Expected value to be a number between 1 and 5.
+
+                    1 │ <Grid cols="2 lg:9">
+                                      ▲▲▲▲
+
+                "#},
+            );
+        }
+
         #[test]
         fn gap() {
             let markdown = indoc! {r#"
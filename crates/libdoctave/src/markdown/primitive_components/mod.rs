@@ -1,17 +1,25 @@
 pub mod r#box;
+pub mod callout;
 pub mod code_tabs;
+pub mod download;
+pub mod embed;
 pub mod flex;
 pub mod grid;
+pub mod responsive;
 // pub mod open_api;
 pub mod steps;
 pub mod tabs;
 
 use std::collections::HashMap;
 
+pub use callout::Callout;
 pub use code_tabs::CodeSelect;
+pub use download::Download;
+pub use embed::Embed;
 pub use flex::Flex;
 pub use grid::Grid;
 pub use r#box::CBox;
+pub use responsive::Responsive;
 pub use steps::{Step, Steps};
 pub use tabs::{Tab, Tabs};
 
@@ -19,22 +27,45 @@ use crate::{
     content_ast::NodeKind as ContentNodeKind,
     markdown::custom_components::custom_component::{Error as ComponentError, Result},
     renderable_ast::Position,
-    Attribute,
+    Anchorizer, Attribute, AttributeValue,
 };
 
 pub static EXPANDED_KEY: &str = "expanded";
 pub static OPENAPI_PATH_KEY: &str = "openapi_path";
+/// Name of a slot in a component, `<Slot name="header" />`
+pub static NAME_KEY: &str = "name";
+/// Slot that a fill is for, `<Fill slot="header">`
+pub static SLOT_KEY: &str = "slot";
 
 pub use self::{
+    callout::{COLLAPSIBLE_KEY, DEFAULT_OPEN_KEY, TYPE_KEY},
+    download::{LABEL_KEY, SRC_KEY},
+    embed::{ASPECT_RATIO_KEY, ID_KEY, PROVIDER_KEY},
     flex::{
         ALIGN_KEY, CLASS_KEY as FLEX_CLASS_KEY, DIRECTION_KEY, GAP_KEY,
         HEIGHT_KEY as FLEX_HEIGHT_KEY, JUSTIFY_KEY, WRAP_KEY,
     },
     grid::COLUMNS_KEY,
     r#box::{CLASS_KEY, HEIGHT_KEY, MAX_WIDTH_KEY, PADDING_KEY},
-    tabs::TITLE_KEY,
+    steps::{CONTINUE_KEY, START_KEY},
+    tabs::{SYNC_KEY, TITLE_KEY},
 };
 
+/// An ID for a part of a component, like the panel of a tab, scoped under
+/// the heading before it, e.g. `setup--tab-macos` for the "macOS" tab under
+/// "Setup". IDs are unique within the page like heading anchors, and stay
+/// the same between builds as long as the page doesn't change.
+pub(crate) fn component_id(
+    anchorizer: &mut Anchorizer,
+    heading: Option<&str>,
+    name: &str,
+) -> String {
+    match heading {
+        Some(heading) => anchorizer.anchorize(format!("{}--{}", heading, name)),
+        None => anchorizer.anchorize(name.to_string()),
+    }
+}
+
 pub enum Primitive {
     Tabs,
     Tab,
@@ -43,8 +74,12 @@ pub enum Primitive {
     CodeSelect,
     Flex,
     Box,
+    Callout,
     Grid,
+    Download,
+    Embed,
     Slot,
+    Fill,
     OpenAPISchema,
 }
 
@@ -58,13 +93,26 @@ impl Primitive {
             "CodeSelect" => Some(Primitive::CodeSelect),
             "Flex" => Some(Primitive::Flex),
             "Box" => Some(Primitive::Box),
+            "Callout" => Some(Primitive::Callout),
             "Grid" => Some(Primitive::Grid),
+            "Download" => Some(Primitive::Download),
+            "Embed" => Some(Primitive::Embed),
             "Slot" => Some(Primitive::Slot),
+            "Fill" => Some(Primitive::Fill),
             "OpenAPISchema" => Some(Primitive::OpenAPISchema),
             _ => None,
         }
     }
 
+    /// Attributes that can be given without a value, e.g. `<Steps continue>`.
+    fn is_flag(&self, key: &str) -> bool {
+        match self {
+            Primitive::Steps => key == CONTINUE_KEY,
+            Primitive::Callout => key == COLLAPSIBLE_KEY || key == DEFAULT_OPEN_KEY,
+            _ => false,
+        }
+    }
+
     pub(crate) fn try_into_content_node_kind(
         self,
         attributes: Vec<Attribute>,
@@ -73,14 +121,17 @@ impl Primitive {
         let mut h = attributes.into_iter().fold(HashMap::new(), |mut a, next| {
             if let Some(val) = next.value {
                 a.insert(next.key, val);
+            } else if self.is_flag(&next.key) {
+                // `<Steps continue>` is shorthand for `continue={true}`
+                a.insert(next.key, AttributeValue::Literal("true".to_string()));
             }
             a
         });
 
         let attributes = match self {
-            Primitive::Tabs => vec![],
+            Primitive::Tabs => vec![SYNC_KEY],
             Primitive::Tab => vec![TITLE_KEY],
-            Primitive::Steps => vec![],
+            Primitive::Steps => vec![START_KEY, CONTINUE_KEY],
             Primitive::Step => vec![TITLE_KEY],
             Primitive::CodeSelect => vec![TITLE_KEY],
             Primitive::Flex => vec![
@@ -94,8 +145,18 @@ impl Primitive {
                 FLEX_CLASS_KEY,
             ],
             Primitive::Box => vec![PADDING_KEY, CLASS_KEY, MAX_WIDTH_KEY, HEIGHT_KEY],
+            Primitive::Callout => vec![
+                TYPE_KEY,
+                TITLE_KEY,
+                PADDING_KEY,
+                COLLAPSIBLE_KEY,
+                DEFAULT_OPEN_KEY,
+            ],
             Primitive::Grid => vec![COLUMNS_KEY, GAP_KEY],
-            Primitive::Slot => vec![],
+            Primitive::Download => vec![SRC_KEY, LABEL_KEY],
+            Primitive::Embed => vec![PROVIDER_KEY, ID_KEY, TITLE_KEY, ASPECT_RATIO_KEY],
+            Primitive::Slot => vec![NAME_KEY],
+            Primitive::Fill => vec![SLOT_KEY],
             Primitive::OpenAPISchema => vec![TITLE_KEY, EXPANDED_KEY, OPENAPI_PATH_KEY],
         };
 
@@ -105,6 +166,11 @@ impl Primitive {
                 continue;
             }
 
+            // The rest are parameters, checked against the provider's settings
+            if matches!(self, Primitive::Embed) {
+                continue;
+            }
+
             if !attributes.contains(&incoming.as_str()) {
                 // we have unexpected attributes
                 return Err(ComponentError::UnexpectedAttribute(
@@ -115,11 +181,16 @@ impl Primitive {
         }
 
         let node_kind = match self {
-            Primitive::Tabs => ContentNodeKind::Tabs,
+            Primitive::Tabs => ContentNodeKind::Tabs {
+                sync_key: h.remove(SYNC_KEY),
+            },
             Primitive::Tab => ContentNodeKind::Tab {
                 title: h.remove(TITLE_KEY),
             },
-            Primitive::Steps => ContentNodeKind::Steps,
+            Primitive::Steps => ContentNodeKind::Steps {
+                start: h.remove(START_KEY),
+                r#continue: h.remove(CONTINUE_KEY),
+            },
             Primitive::Step => ContentNodeKind::Step {
                 title: h.remove(TITLE_KEY),
             },
@@ -142,11 +213,47 @@ impl Primitive {
                 max_width: h.remove(MAX_WIDTH_KEY),
                 height: h.remove(HEIGHT_KEY),
             },
+            Primitive::Callout => ContentNodeKind::Callout {
+                r#type: h.remove(TYPE_KEY),
+                title: h.remove(TITLE_KEY),
+                padding: h.remove(PADDING_KEY),
+                collapsible: h.remove(COLLAPSIBLE_KEY),
+                default_open: h.remove(DEFAULT_OPEN_KEY),
+            },
             Primitive::Grid => ContentNodeKind::Grid {
                 cols: h.remove(COLUMNS_KEY),
                 gap: h.remove(GAP_KEY),
             },
-            Primitive::Slot => ContentNodeKind::Slot,
+            Primitive::Download => ContentNodeKind::Download {
+                src: h.remove(SRC_KEY),
+                label: h.remove(LABEL_KEY),
+            },
+            Primitive::Embed => {
+                let provider = h.remove(PROVIDER_KEY);
+                let id = h.remove(ID_KEY);
+                let title = h.remove(TITLE_KEY);
+                let aspect_ratio = h.remove(ASPECT_RATIO_KEY);
+
+                let mut params = h
+                    .into_iter()
+                    .filter(|(key, _)| key != "if" && key != "elseif" && key != "else")
+                    .collect::<Vec<_>>();
+                params.sort_by(|a, b| a.0.cmp(&b.0));
+
+                ContentNodeKind::Embed {
+                    provider,
+                    id,
+                    title,
+                    aspect_ratio,
+                    params,
+                }
+            }
+            Primitive::Slot => ContentNodeKind::Slot {
+                name: h.remove(NAME_KEY),
+            },
+            Primitive::Fill => ContentNodeKind::Fill {
+                slot: h.remove(SLOT_KEY),
+            },
             Primitive::OpenAPISchema => ContentNodeKind::OpenAPISchema {
                 title: h.remove(TITLE_KEY),
                 expanded: h.remove(EXPANDED_KEY),
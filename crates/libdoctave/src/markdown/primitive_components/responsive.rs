@@ -0,0 +1,217 @@
+//! Attribute values that change with the screen size, like `cols="1 md:2 lg:3"`.
+//!
+//! The first value without a breakpoint applies to all screen sizes, and
+//! each `breakpoint:value` applies from that breakpoint up.
+
+use std::fmt::Debug;
+
+use crate::{markdown::error_renderer, renderable_ast::Position};
+
+/// Breakpoints, from the smallest screen size up. Themes decide the widths.
+pub const BREAKPOINTS: &[&str] = &["sm", "md", "lg", "xl"];
+
+/// A value per breakpoint. Breakpoints without a value of their own use the
+/// value of the next smaller one.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Responsive<T> {
+    pub base: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sm: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub md: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lg: Option<T>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub xl: Option<T>,
+}
+
+/// A token that isn't a value, like `xs:2`, or a breakpoint given twice
+#[derive(Debug)]
+pub enum TokenError {
+    UnknownBreakpoint(String),
+    DuplicateBreakpoint(String),
+}
+
+impl<T> Responsive<T> {
+    pub fn new(base: T) -> Self {
+        Responsive {
+            base,
+            sm: None,
+            md: None,
+            lg: None,
+            xl: None,
+        }
+    }
+
+    /// The values set for breakpoints, from the smallest up
+    pub fn breakpoints(&self) -> impl Iterator<Item = (&'static str, &T)> {
+        [&self.sm, &self.md, &self.lg, &self.xl]
+            .into_iter()
+            .zip(BREAKPOINTS)
+            .filter_map(|(value, name)| value.as_ref().map(|v| (*name, v)))
+    }
+
+    fn slot(&mut self, breakpoint: &str) -> Option<&mut Option<T>> {
+        match breakpoint {
+            "sm" => Some(&mut self.sm),
+            "md" => Some(&mut self.md),
+            "lg" => Some(&mut self.lg),
+            "xl" => Some(&mut self.xl),
+            _ => None,
+        }
+    }
+
+    /// Parses `value md:value ...`, with `parse_value` given each value and
+    /// the token it came from. A missing base value keeps `base`.
+    pub fn parse<E>(
+        input: &str,
+        base: T,
+        parse_value: impl Fn(&str, &str) -> Result<T, E>,
+        token_error: impl Fn(TokenError) -> E,
+    ) -> Result<Self, E> {
+        let mut responsive = Responsive::new(base);
+        let mut has_base = false;
+
+        for token in input.split_whitespace() {
+            match token.split_once(':') {
+                Some((breakpoint, value)) => {
+                    let Some(slot) = responsive.slot(breakpoint) else {
+                        return Err(token_error(TokenError::UnknownBreakpoint(
+                            token.to_string(),
+                        )));
+                    };
+
+                    if slot.is_some() {
+                        return Err(token_error(TokenError::DuplicateBreakpoint(
+                            token.to_string(),
+                        )));
+                    }
+
+                    *slot = Some(parse_value(value, token)?);
+                }
+                None => {
+                    if has_base {
+                        return Err(token_error(TokenError::DuplicateBreakpoint(
+                            token.to_string(),
+                        )));
+                    }
+
+                    responsive.base = parse_value(token, token)?;
+                    has_base = true;
+                }
+            }
+        }
+
+        Ok(responsive)
+    }
+
+    /// E.g. `1 md:2 lg:3`, for debug output
+    pub fn describe(&self, describe_value: impl Fn(&T) -> String) -> String {
+        std::iter::once(describe_value(&self.base))
+            .chain(
+                self.breakpoints()
+                    .map(|(name, value)| format!("{}:{}", name, describe_value(value))),
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<T: Debug> Responsive<T> {
+    pub fn describe_debug(&self) -> String {
+        self.describe(|v| format!("{:?}", v))
+    }
+}
+
+/// Where a token is in the value of an attribute, e.g. `md:8` in
+/// `cols="2 md:8"`.
+pub(crate) fn token_pos(md: &str, key: &str, token: &str, node_pos: &Position) -> Position {
+    let value_pos = error_renderer::offset_attribute_error_pos(md, key, token, node_pos);
+
+    // The position starts at the opening quote or brace
+    let offset = md
+        .get(value_pos.start.byte_offset + 1..)
+        .and_then(|rest| {
+            let value_end = rest.find(['"', '\'', '}', '\n']).unwrap_or(rest.len());
+            rest[..value_end].find(token)
+        })
+        .unwrap_or(0);
+
+    let mut pos = value_pos;
+    pos.start.col += offset;
+    pos.start.byte_offset += offset;
+    pos.end.col += offset;
+    pos.end.byte_offset += offset;
+
+    pos
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Error {
+        Token(String),
+        Value(String),
+    }
+
+    fn parse(input: &str) -> Result<Responsive<usize>, Error> {
+        Responsive::parse(
+            input,
+            2,
+            |value, token| value.parse().map_err(|_| Error::Value(token.to_string())),
+            |e| match e {
+                TokenError::UnknownBreakpoint(t) | TokenError::DuplicateBreakpoint(t) => {
+                    Error::Token(t)
+                }
+            },
+        )
+    }
+
+    #[test]
+    fn parses_values_per_breakpoint() {
+        let columns = parse("1 md:2 lg:3").unwrap();
+
+        assert_eq!(columns.base, 1);
+        assert_eq!(columns.sm, None);
+        assert_eq!(columns.md, Some(2));
+        assert_eq!(columns.lg, Some(3));
+        assert_eq!(columns.describe(|v| v.to_string()), "1 md:2 lg:3");
+    }
+
+    #[test]
+    fn keeps_the_default_base() {
+        let columns = parse("lg:4").unwrap();
+
+        assert_eq!(columns.base, 2);
+        assert_eq!(columns.lg, Some(4));
+    }
+
+    #[test]
+    fn rejects_bad_tokens() {
+        assert_eq!(
+            parse("1 xs:2").unwrap_err(),
+            Error::Token("xs:2".to_string())
+        );
+        assert_eq!(
+            parse("1 md:2 md:3").unwrap_err(),
+            Error::Token("md:3".to_string())
+        );
+        assert_eq!(parse("1 2").unwrap_err(), Error::Token("2".to_string()));
+        assert_eq!(
+            parse("1 md:x").unwrap_err(),
+            Error::Value("md:x".to_string())
+        );
+    }
+
+    #[test]
+    fn serializes_only_the_breakpoints_given() {
+        let columns = parse("1 md:2").unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&columns).unwrap(),
+            serde_json::json!({ "base": 1, "md": 2 })
+        );
+    }
+}
@@ -1,20 +1,110 @@
 use crate::{
     autocomplete::PrimitiveComponentAutocomplete,
     expressions::Value,
-    markdown::error_renderer::{self, Highlight, Location},
-    primitive_components::tabs::TITLE_KEY,
+    markdown::error_renderer::{self, parse_int_in_range, Highlight, Location},
+    primitive_components::{component_id, tabs::TITLE_KEY},
     render_context::RenderContext,
     renderable_ast::{Node as RenderableNode, NodeKind as RenderableNodeKind, Position},
+    Anchorizer,
 };
 
 use thiserror::Error;
 
+pub static START_KEY: &str = "start";
+pub static CONTINUE_KEY: &str = "continue";
+
+/// How many levels of `<Steps>` can be nested inside a `<Step>`.
+const MAX_NESTING: usize = 1;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Steps {
     pub steps: Vec<Step>,
 }
 
 impl Steps {
+    /// Computes the number of the first step in a `<Steps>` block.
+    ///
+    /// `previous` is the last step number of the preceding top level block on
+    /// the page, which blocks with `continue` resume from. `depth` is how many
+    /// `<Steps>` blocks this one is nested inside.
+    pub fn first_number(
+        start: Option<Value>,
+        cont: Option<Value>,
+        previous: usize,
+        depth: usize,
+    ) -> Result<usize> {
+        if depth > MAX_NESTING {
+            return Err(Error::NestedTooDeep);
+        }
+
+        let cont = match cont {
+            None => false,
+            Some(Value::Bool(b)) => b,
+            Some(other) => match other.to_string().as_str() {
+                "true" => true,
+                "false" => false,
+                found => return Err(Error::InvalidContinue(found.to_string())),
+            },
+        };
+
+        if cont && depth > 0 {
+            return Err(Error::ContinueInNestedSteps);
+        }
+
+        match (start, cont) {
+            (Some(_), true) => Err(Error::StartWithContinue),
+            (Some(start), false) => parse_int_in_range(start.to_string().as_str(), 1..usize::MAX)
+                .ok_or(Error::InvalidStart(start.to_string())),
+            (None, true) => Ok(previous + 1),
+            (None, false) => Ok(1),
+        }
+    }
+
+    /// Numbers the `<Step>` children of a block starting from `first`, and
+    /// prefixes the steps of any nested blocks with the number of their
+    /// parent step, e.g. `2.1`.
+    ///
+    /// Returns the number of the last step, or `first - 1` if there were none.
+    pub fn number(children: &mut [RenderableNode], first: usize) -> usize {
+        let mut number = first;
+
+        for child in children.iter_mut() {
+            if let RenderableNodeKind::Step(step) = &mut child.kind {
+                step.number = number.to_string();
+                prefix_nested_steps(&mut child.children, &step.number);
+                number += 1;
+            }
+        }
+
+        number - 1
+    }
+
+    /// Gives each `<Step>` child of a block an ID from its title, and its
+    /// position in the block, e.g. step 2 of 3.
+    pub(crate) fn assign_ids(
+        children: &mut [RenderableNode],
+        anchorizer: &mut Anchorizer,
+        heading: Option<&str>,
+    ) {
+        let total = children
+            .iter()
+            .filter(|child| matches!(child.kind, RenderableNodeKind::Step(_)))
+            .count();
+
+        let steps = children
+            .iter_mut()
+            .filter_map(|child| match &mut child.kind {
+                RenderableNodeKind::Step(step) => Some(step),
+                _ => None,
+            });
+
+        for (index, step) in steps.enumerate() {
+            step.id = component_id(anchorizer, heading, &format!("step {}", step.title));
+            step.index = index + 1;
+            step.total = total;
+        }
+    }
+
     pub fn verify(node: &RenderableNode) -> Result<()> {
         match &node.kind {
             RenderableNodeKind::Step(_) => {}
@@ -30,29 +120,53 @@ impl Steps {
     }
 }
 
+fn prefix_nested_steps(nodes: &mut [RenderableNode], prefix: &str) {
+    for node in nodes {
+        match &mut node.kind {
+            RenderableNodeKind::Step(step) => {
+                step.number = format!("{}.{}", prefix, step.number);
+            }
+            _ => prefix_nested_steps(&mut node.children, prefix),
+        }
+    }
+}
+
 impl PrimitiveComponentAutocomplete for Steps {
     fn title(&self) -> &str {
         "Steps"
     }
 
     fn attributes(&self) -> Vec<&str> {
-        vec![]
+        vec!["start", "continue"]
     }
 
-    fn attribute_values(&self, _attribute: &str) -> Vec<&str> {
-        vec![]
+    fn attribute_values(&self, attribute: &str) -> Vec<&str> {
+        match attribute {
+            "continue" => vec!["true", "false"],
+            _ => vec![],
+        }
     }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct Step {
     pub title: String,
+    /// The number shown for the step, e.g. `3`, or `3.1` for a step nested
+    /// inside step 3. Assigned by the enclosing `<Steps>` block.
+    pub number: String,
+    pub id: String,
+    /// The position of the step in its block, starting from 1, unlike the
+    /// number, which can continue from an earlier block.
+    pub index: usize,
+    /// How many steps are in the block
+    pub total: usize,
 }
 
 impl Step {
     pub fn try_new(title: Option<Value>) -> Result<Self> {
         Ok(Self {
             title: title.ok_or(Error::MissingTitle)?.to_string(),
+            ..Default::default()
         })
     }
 }
@@ -79,6 +193,16 @@ pub enum Error {
     InvalidStepNode,
     #[error(r#"Missing {TITLE_KEY}"#)]
     MissingTitle,
+    #[error(r#"Invalid {START_KEY}. Expected value to be a positive number."#)]
+    InvalidStart(String),
+    #[error(r#"Invalid {CONTINUE_KEY}. Expected value to be true or false."#)]
+    InvalidContinue(String),
+    #[error(r#"Cannot use both {START_KEY} and {CONTINUE_KEY}"#)]
+    StartWithContinue,
+    #[error(r#"Nested steps cannot {CONTINUE_KEY} numbering"#)]
+    ContinueInNestedSteps,
+    #[error(r#"Steps can only be nested one level deep"#)]
+    NestedTooDeep,
 }
 
 impl Error {
@@ -105,6 +229,38 @@ impl Error {
                     msg: Some(format!("Missing {TITLE_KEY}")),
                 };
 
+                highlights.push(highlight);
+            }
+            Error::InvalidStart(found) | Error::InvalidContinue(found) => {
+                let key = match self {
+                    Error::InvalidStart(_) => START_KEY,
+                    _ => CONTINUE_KEY,
+                };
+                let pos = error_renderer::offset_attribute_error_pos(md, key, found, pos);
+                let location = Location::Point(pos.start.row, pos.start.col + 1);
+
+                let highlight = Highlight {
+                    location,
+                    span: found.len(),
+                    msg: None,
+                };
+
+                highlights.push(highlight);
+            }
+            Error::StartWithContinue | Error::ContinueInNestedSteps | Error::NestedTooDeep => {
+                let location = Location::Point(pos.start.row, pos.start.col);
+
+                let msg = match self {
+                    Error::NestedTooDeep => "Nested too deeply".to_string(),
+                    _ => format!("Remove {CONTINUE_KEY}"),
+                };
+
+                let highlight = Highlight {
+                    location,
+                    span: 1,
+                    msg: Some(msg),
+                };
+
                 highlights.push(highlight);
             }
         }
@@ -136,7 +292,7 @@ mod test {
             node.debug_string().unwrap(),
             indoc! {r#"
             <Steps>
-                <Step title={"first"}>
+                <Step title={"first"} number={"1"}>
                     <Text>
                         Foobar 1
                     </Text>
@@ -145,7 +301,7 @@ mod test {
 
 
                 </Text>
-                <Step title={"second"}>
+                <Step title={"second"} number={"2"}>
                     <Text>
                         Foobar 2
                     </Text>
@@ -154,7 +310,7 @@ mod test {
 
 
                 </Text>
-                <Step title={"third"}>
+                <Step title={"third"} number={"3"}>
                     <Text>
                         Foobar 3
                     </Text>
@@ -284,7 +440,7 @@ mod test {
             node.debug_string().unwrap(),
             indoc! {r#"
             <Steps>
-                <Step title={"second"}>
+                <Step title={"second"} number={"1"}>
                     <Text>
                         Foobar 2
                     </Text>
@@ -293,7 +449,7 @@ mod test {
 
 
                 </Text>
-                <Step title={"third"}>
+                <Step title={"third"} number={"2"}>
                     <Text>
                         Foobar 3
                     </Text>
@@ -302,4 +458,245 @@ mod test {
             "#}
         );
     }
+
+    fn step_numbers(node: &crate::Node) -> Vec<String> {
+        node.walk()
+            .filter_map(|n| match &n.kind {
+                crate::NodeKind::Step(step) => Some(step.number.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn steps_start() {
+        let input = indoc! {r#"
+        <Steps start="4">
+            <Step title="first">Foobar 1</Step>
+            <Step title="second">Foobar 2</Step>
+        </Steps>
+
+        <Steps start={7}>
+            <Step title="third">Foobar 3</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_eq!(step_numbers(node), vec!["4", "5", "7"]);
+    }
+
+    #[test]
+    fn steps_continue() {
+        let input = indoc! {r#"
+        <Steps>
+            <Step title="first">Foobar 1</Step>
+            <Step title="second">Foobar 2</Step>
+        </Steps>
+
+        Some prose in between.
+
+        <Steps continue>
+            <Step title="third">Foobar 3</Step>
+        </Steps>
+
+        <Steps continue={true}>
+            <Step title="fourth">Foobar 4</Step>
+        </Steps>
+
+        <Steps>
+            <Step title="first again">Foobar 1</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_eq!(step_numbers(node), vec!["1", "2", "3", "4", "1"]);
+    }
+
+    #[test]
+    fn steps_nested() {
+        let input = indoc! {r#"
+        <Steps>
+            <Step title="first">Foobar 1</Step>
+            <Step title="second">
+                <Steps>
+                    <Step title="second a">Foobar 2a</Step>
+                    <Step title="second b">Foobar 2b</Step>
+                </Steps>
+            </Step>
+        </Steps>
+
+        <Steps continue>
+            <Step title="third">Foobar 3</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_eq!(step_numbers(node), vec!["1", "2", "2.1", "2.2", "3"]);
+    }
+
+    #[test]
+    fn steps_numbers_are_serialized() {
+        let input = indoc! {r#"
+        <Steps start="2">
+            <Step title="first">Foobar 1</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+        let step = node
+            .walk()
+            .find(|n| matches!(n.kind, crate::NodeKind::Step(_)))
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&step.kind).unwrap(),
+            serde_json::json!({
+                "name": "step",
+                "data": { "title": "first", "number": "2", "id": "step-first", "index": 1, "total": 1 }
+            })
+        );
+    }
+
+    #[test]
+    fn steps_non_numeric_start() {
+        let input = indoc! {r#"
+        <Steps start="two">
+            <Step title="first">Foobar 1</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_STEPS);
+        assert!(error.position.is_some());
+        assert_str_eq!(
+            error.description,
+            indoc! {r#"
+            Invalid start. Expected value to be a positive number.
+
+                1 │ <Steps start="two">
+                                  ▲▲▲
+
+            "#}
+        );
+    }
+
+    #[test]
+    fn steps_continue_in_nested_block() {
+        let input = indoc! {r#"
+        <Steps>
+            <Step title="first">
+                <Steps continue>
+                    <Step title="first a">Foobar 1a</Step>
+                </Steps>
+            </Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_STEPS);
+        assert_str_eq!(
+            error.description,
+            indoc! {r#"
+            Nested steps cannot continue numbering
+
+                2 │     <Step title="first">
+                3 │         <Steps continue>
+                            ▲
+                            └─ Remove continue
+
+            "#}
+        );
+    }
+
+    #[test]
+    fn steps_nested_too_deep() {
+        let input = indoc! {r#"
+        <Steps>
+            <Step title="first">
+                <Steps>
+                    <Step title="first a">
+                        <Steps>
+                            <Step title="first a i">Foobar</Step>
+                        </Steps>
+                    </Step>
+                </Steps>
+            </Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_STEPS);
+        assert_str_eq!(error.message, "Error in steps");
+        assert!(error
+            .description
+            .starts_with("Steps can only be nested one level deep"));
+    }
+
+    #[test]
+    fn steps_start_with_continue() {
+        let input = indoc! {r#"
+        <Steps start="3" continue>
+            <Step title="first">Foobar 1</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_STEPS);
+        assert!(error
+            .description
+            .starts_with("Cannot use both start and continue"));
+    }
+
+    #[test]
+    fn steps_ids_and_positions() {
+        let input = indoc! {r#"
+        # Install
+
+        <Steps start="3">
+            <Step title="Download">Foobar 1</Step>
+            <Step title="Run the installer">Foobar 2</Step>
+        </Steps>
+        "#};
+
+        let ctx = RenderContext::default();
+        let steps = |input: &str| {
+            ast_mdx(input, &ctx)
+                .unwrap()
+                .walk()
+                .filter_map(|n| match &n.kind {
+                    crate::NodeKind::Step(step) => Some((step.id.clone(), step.index, step.total)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = steps(input);
+        assert_eq!(first, steps(input));
+        assert_eq!(
+            first,
+            vec![
+                ("install--step-download".to_string(), 1, 2),
+                ("install--step-run-the-installer".to_string(), 2, 2)
+            ]
+        );
+
+        let renamed = steps(&input.replace("Download", "Get the package"));
+        assert_eq!(renamed[0].0, "install--step-get-the-package");
+        assert_eq!(renamed[1].0, first[1].0);
+    }
 }
@@ -2,20 +2,73 @@ use crate::{
     autocomplete::PrimitiveComponentAutocomplete,
     expressions::Value,
     markdown::error_renderer::{self, Highlight, Location},
+    primitive_components::component_id,
     render_context::RenderContext,
     renderable_ast::{Node as RenderableNode, NodeKind as RenderableNodeKind, Position},
+    Anchorizer,
 };
 
 use thiserror::Error;
 
 pub static TITLE_KEY: &str = "title";
+pub static SYNC_KEY: &str = "sync_key";
 
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+#[serde(rename = "MdTabs")]
 pub struct Tabs {
-    pub tabs: Vec<Tab>,
+    /// Tabs blocks with the same key share their selection, which is also
+    /// kept in the URL so that it can be linked to.
+    pub sync_key: Option<String>,
+    pub id: String,
+    /// The index of the tab that is shown before the reader picks one
+    pub selected: usize,
 }
 
 impl Tabs {
+    pub fn try_new(sync_key: Option<Value>) -> Result<Self> {
+        let sync_key = sync_key.map(|key| key.to_string());
+
+        if let Some(key) = &sync_key {
+            if key.is_empty()
+                || !key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(Error::InvalidSyncKey(key.clone()));
+            }
+        }
+
+        Ok(Self {
+            sync_key,
+            ..Default::default()
+        })
+    }
+
+    /// Gives the block and each of its tabs an ID, and marks the selected
+    /// tab. The panel of a tab can be linked to with its ID, e.g.
+    /// `#setup--tab-macos`.
+    pub(crate) fn assign_ids(
+        &mut self,
+        children: &mut [RenderableNode],
+        anchorizer: &mut Anchorizer,
+        heading: Option<&str>,
+    ) {
+        self.id = component_id(anchorizer, heading, "tabs");
+
+        let tabs = children
+            .iter_mut()
+            .filter_map(|child| match &mut child.kind {
+                RenderableNodeKind::Tab(tab) => Some(tab),
+                _ => None,
+            });
+
+        for (index, tab) in tabs.enumerate() {
+            tab.id = component_id(anchorizer, heading, &format!("tab {}", tab.title));
+            tab.label_id = format!("{}--label", tab.id);
+            tab.selected = index == self.selected;
+        }
+    }
+
     pub fn verify(node: &RenderableNode) -> Result<()> {
         match &node.kind {
             RenderableNodeKind::Tab(_) => {}
@@ -37,7 +90,7 @@ impl PrimitiveComponentAutocomplete for Tabs {
     }
 
     fn attributes(&self) -> Vec<&str> {
-        vec![]
+        vec!["sync_key"]
     }
 
     fn attribute_values(&self, _attribute: &str) -> Vec<&str> {
@@ -49,12 +102,18 @@ impl PrimitiveComponentAutocomplete for Tabs {
 #[serde(rename = "MdTab")]
 pub struct Tab {
     pub title: String,
+    /// The ID of the tab's panel. Assigned by the enclosing `<Tabs>` block.
+    pub id: String,
+    /// The ID of the button that shows the panel, which labels it
+    pub label_id: String,
+    pub selected: bool,
 }
 
 impl Tab {
     pub fn try_new(title: Option<Value>) -> Result<Self> {
         Ok(Self {
             title: title.ok_or(Error::MissingTitle)?.to_string(),
+            ..Default::default()
         })
     }
 }
@@ -81,6 +140,8 @@ pub enum Error {
     InvalidTabNode,
     #[error(r#"Missing {TITLE_KEY}"#)]
     MissingTitle,
+    #[error(r#"Invalid {SYNC_KEY}"#)]
+    InvalidSyncKey(String),
 }
 
 impl Error {
@@ -107,6 +168,19 @@ impl Error {
                     msg: Some(format!("Missing {TITLE_KEY}")),
                 };
 
+                highlights.push(highlight);
+            }
+            Error::InvalidSyncKey(key) => {
+                let location = Location::Point(pos.start.row, pos.start.col);
+
+                let highlight = Highlight {
+                    location,
+                    span: 1,
+                    msg: Some(format!(
+                        "Expected letters, numbers, dashes, or underscores, found \"{key}\""
+                    )),
+                };
+
                 highlights.push(highlight);
             }
         }
@@ -279,4 +353,138 @@ mod test {
             "#}
         );
     }
+
+    #[test]
+    fn tabs_with_sync_key() {
+        let input = indoc! {r#"
+        <Tabs sync_key="lang">
+            <Tab title="Rust">Foobar 1</Tab>
+        </Tabs>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_str_eq!(
+            node.debug_string().unwrap(),
+            indoc! {r#"
+            <Tabs sync_key={"lang"}>
+                <Tab title={"Rust"}>
+                    <Text>
+                        Foobar 1
+                    </Text>
+                </Tab>
+            </Tabs>
+            "#}
+        );
+    }
+
+    #[test]
+    fn tabs_invalid_sync_key() {
+        let input = indoc! {r#"
+        <Tabs sync_key="my lang">
+            <Tab title="Rust">Foobar 1</Tab>
+        </Tabs>
+        "#};
+
+        let ctx = RenderContext::default();
+        let error = &ast_mdx(input, &ctx).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::INVALID_TABS);
+        assert_str_eq!(
+            error.description,
+            indoc! {r#"
+            Invalid sync_key
+
+                1 │ <Tabs sync_key="my lang">
+                    ▲
+                    └─ Expected letters, numbers, dashes, or underscores, found "my lang"
+
+            "#}
+        );
+    }
+
+    fn tabs(node: &crate::Node) -> Vec<crate::primitive_components::Tab> {
+        node.walk()
+            .filter_map(|n| match &n.kind {
+                crate::NodeKind::Tab(tab) => Some(tab.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tabs_ids_are_stable_and_follow_titles() {
+        let input = indoc! {r#"
+        # Setup
+
+        <Tabs>
+            <Tab title="Linux">Foobar 1</Tab>
+            <Tab title="macOS">Foobar 2</Tab>
+        </Tabs>
+        "#};
+
+        let ctx = RenderContext::default();
+        let first = tabs(&ast_mdx(input, &ctx).unwrap());
+        let second = tabs(&ast_mdx(input, &ctx).unwrap());
+
+        assert_eq!(first, second);
+        assert_eq!(first[1].id, "setup--tab-macos");
+        assert_eq!(first[1].label_id, "setup--tab-macos--label");
+        assert!(first[0].selected);
+        assert!(!first[1].selected);
+
+        let renamed = tabs(&ast_mdx(&input.replace("macOS", "Mac"), &ctx).unwrap());
+        assert_eq!(renamed[0].id, first[0].id);
+        assert_eq!(renamed[1].id, "setup--tab-mac");
+    }
+
+    #[test]
+    fn tabs_ids_are_unique_on_the_page() {
+        let input = indoc! {r#"
+        <Tabs>
+            <Tab title="Linux">Foobar 1</Tab>
+        </Tabs>
+
+        <Tabs>
+            <Tab title="Linux">Foobar 2</Tab>
+        </Tabs>
+        "#};
+
+        let ctx = RenderContext::default();
+        let ids = tabs(&ast_mdx(input, &ctx).unwrap())
+            .into_iter()
+            .map(|tab| tab.id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec!["tab-linux", "tab-linux-1"]);
+    }
+
+    #[test]
+    fn fragments_resolve_to_tabs() {
+        let input = indoc! {r#"
+        ## Setup
+
+        <Tabs>
+            <Tab title="Linux">Foobar 1</Tab>
+            <Tab title="macOS">Foobar 2</Tab>
+        </Tabs>
+        "#};
+
+        let ctx = RenderContext::default();
+        let node = &ast_mdx(input, &ctx).unwrap();
+
+        assert_eq!(
+            node.resolve_fragment("#setup--tab-macos"),
+            Some(crate::FragmentTarget::Tab {
+                tabs_id: "setup--tabs".to_string(),
+                index: 1
+            })
+        );
+        assert_eq!(
+            node.resolve_fragment("setup"),
+            Some(crate::FragmentTarget::Heading)
+        );
+        assert_eq!(node.resolve_fragment("#setup--tab-windows"), None);
+    }
 }
@@ -4,8 +4,11 @@ use serde::Serialize;
 use std::io::Write;
 
 use crate::{
+    markdown::{console::ConsoleSession, highlight::HighlightToken},
     open_api::ast::SchemaAst,
-    primitive_components::{CBox, Flex, Grid, Step, Tab},
+    primitive_components::{
+        download::FileInfo, CBox, Callout, CodeSelect, Download, Embed, Flex, Grid, Step, Tab, Tabs,
+    },
 };
 
 pub use super::shared_ast::*;
@@ -78,8 +81,12 @@ impl Node {
                 }
                 writeln!(f, "{i}</Paragraph>")?;
             }
-            NodeKind::Tabs => {
-                writeln!(f, "{i}<Tabs>")?;
+            NodeKind::Tabs(tabs) => {
+                write!(f, "{i}<Tabs")?;
+                if let Some(sync_key) = &tabs.sync_key {
+                    write!(f, " sync_key={{{:?}}}", sync_key)?;
+                }
+                writeln!(f, ">")?;
                 for child in &self.children {
                     child._debug_string(indent + 1, f)?;
                 }
@@ -106,6 +113,7 @@ impl Node {
             NodeKind::Step(step) => {
                 write!(f, "{i}<Step")?;
                 write!(f, " title={{{:?}}}", &step.title)?;
+                write!(f, " number={{{:?}}}", &step.number)?;
                 writeln!(f, ">")?;
 
                 for child in &self.children {
@@ -125,10 +133,10 @@ impl Node {
 
                 write!(f, " justify={{{:?}}}", flex.justify)?;
                 write!(f, " align={{{:?}}}", flex.align)?;
-                write!(f, " direction={{{:?}}}", flex.direction)?;
+                write!(f, " direction={{{}}}", flex.direction.describe_debug())?;
                 write!(f, " wrap={{{:?}}}", flex.wrap)?;
-                write!(f, " gap={{{}}}", flex.gap)?;
-                write!(f, " padding={{{}}}", flex.padding)?;
+                write!(f, " gap={{{}}}", flex.gap.describe_debug())?;
+                write!(f, " padding={{{}}}", flex.padding.describe_debug())?;
                 write!(f, " height={{{:?}}}", flex.height)?;
                 write!(f, " class={{{}}}", flex.class)?;
 
@@ -140,15 +148,36 @@ impl Node {
 
                 writeln!(f, "{i}</Flex>")?;
             }
-            NodeKind::Image { url, alt, title } => {
+            NodeKind::Image {
+                url,
+                alt,
+                title,
+                responsive,
+                attributes,
+            } => {
                 write!(f, "{i}<Image url={{{url}}} alt={{{alt}}}")?;
 
                 if let Some(title) = title {
                     write!(f, " title={{{title}}}")?;
                 }
 
+                write_image_attributes(attributes, f)?;
+
+                if let Some(responsive) = responsive {
+                    write!(f, " srcset={{{}}}", responsive.srcset)?;
+
+                    if let Some(webp_srcset) = &responsive.webp_srcset {
+                        write!(f, " webp_srcset={{{}}}", webp_srcset)?;
+                    }
+
+                    write!(f, " sizes={{{}}}", responsive.sizes)?;
+                }
+
                 writeln!(f, " />")?;
             }
+            NodeKind::InlineSvg { src, alt, .. } => {
+                writeln!(f, "{i}<InlineSvg src={{{src}}} alt={{{alt}}} />")?;
+            }
             NodeKind::Code {
                 value,
                 language,
@@ -157,6 +186,8 @@ impl Node {
                 raw,
                 show_whitespace,
                 rendered_value: _,
+                highlighted: _,
+                console: _,
             } => {
                 write!(f, "{i}<Code")?;
 
@@ -187,7 +218,7 @@ impl Node {
 
                 writeln!(f, "{i}</Code>")?;
             }
-            NodeKind::CodeSelect => {
+            NodeKind::CodeSelect(_) => {
                 writeln!(f, "{i}<CodeSelect>")?;
 
                 for child in &self.children {
@@ -223,10 +254,48 @@ impl Node {
 
                 writeln!(f, "{i}</TableCell>")?;
             }
+            NodeKind::Download(download) => {
+                write!(f, "{i}<Download")?;
+                write!(f, " src={{{}}}", download.src)?;
+                write!(f, " label={{{}}}", download.label)?;
+                write_file_info(&download.file, f)?;
+                writeln!(f, ">")?;
+
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</Download>")?;
+            }
+            NodeKind::Embed(embed) => {
+                write!(f, "{i}<Embed")?;
+                write!(f, " provider={{{}}}", embed.provider)?;
+                write!(f, " url={{{}}}", embed.url)?;
+                write!(f, " title={{{}}}", embed.title)?;
+                write!(f, " aspect_ratio={{{}}}", embed.aspect_ratio)?;
+                writeln!(f, ">")?;
+
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</Embed>")?;
+            }
+            NodeKind::Slot { name, filled } => {
+                write!(f, "{i}<Slot")?;
+                if let Some(name) = name {
+                    write!(f, " name={{{}}}", name)?;
+                }
+                write!(f, " filled={{{}}}", filled)?;
+                writeln!(f, ">")?;
+
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</Slot>")?;
+            }
             NodeKind::Grid(grid) => {
                 write!(f, "{i}<Grid")?;
-                write!(f, " gap={{{}}}", grid.gap)?;
-                write!(f, " columns={{{}}}", grid.columns)?;
+                write!(f, " gap={{{}}}", grid.gap.describe_debug())?;
+                write!(f, " columns={{{}}}", grid.columns.describe_debug())?;
                 writeln!(f, ">")?;
 
                 for child in &self.children {
@@ -288,28 +357,103 @@ impl Node {
 
                 writeln!(f, "{i}</Box>")?;
             }
-            NodeKind::Delete => {
-                writeln!(f, "{i}<Delete>")?;
+            NodeKind::Callout(callout) => {
+                write!(f, "{i}<Callout")?;
+
+                write!(f, " type={{{}}}", callout.kind)?;
+                if let Some(title) = &callout.title {
+                    write!(f, " title={{{:?}}}", title)?;
+                }
+                write!(f, " padding={{{}}}", callout.padding)?;
+                if callout.collapsible {
+                    write!(f, " collapsible={{{}}}", callout.collapsible)?;
+                    write!(f, " default_open={{{}}}", callout.default_open)?;
+                }
+
+                writeln!(f, ">")?;
 
                 for child in &self.children {
                     child._debug_string(indent + 1, f)?;
                 }
 
-                writeln!(f, "{i}</Delete>")?;
+                writeln!(f, "{i}</Callout>")?;
             }
-            NodeKind::ListItem { checked, .. } => {
-                write!(f, "{i}<ListItem")?;
+            NodeKind::Delete => {
+                writeln!(f, "{i}<Delete>")?;
 
-                if let Some(checked) = checked {
-                    write!(f, " checked={{{checked}}}")?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
                 }
 
-                writeln!(f, ">")?;
+                writeln!(f, "{i}</Delete>")?;
+            }
+            NodeKind::ListItem { .. } => {
+                writeln!(f, "{i}<ListItem>")?;
                 for child in &self.children {
                     child._debug_string(indent + 1, f)?;
                 }
                 writeln!(f, "{i}</ListItem>")?;
             }
+            NodeKind::TaskListItem { checked, .. } => {
+                writeln!(f, "{i}<TaskListItem checked={{{checked}}}>")?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</TaskListItem>")?;
+            }
+            NodeKind::FootnoteReference {
+                number,
+                id,
+                definition_id,
+            } => {
+                writeln!(
+                    f,
+                    "{i}<FootnoteReference number={{{number}}} id={{{id}}} definition_id={{{definition_id}}} />"
+                )?;
+            }
+            NodeKind::Footnotes => {
+                writeln!(f, "{i}<Footnotes>")?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</Footnotes>")?;
+            }
+            NodeKind::FootnoteDefinition {
+                number,
+                id,
+                reference_ids,
+            } => {
+                writeln!(
+                    f,
+                    "{i}<FootnoteDefinition number={{{number}}} id={{{id}}} reference_ids={{{}}}>",
+                    reference_ids.join(",")
+                )?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</FootnoteDefinition>")?;
+            }
+            NodeKind::DefinitionList => {
+                writeln!(f, "{i}<DefinitionList>")?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</DefinitionList>")?;
+            }
+            NodeKind::DefinitionTerm => {
+                writeln!(f, "{i}<DefinitionTerm>")?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</DefinitionTerm>")?;
+            }
+            NodeKind::DefinitionDescription => {
+                writeln!(f, "{i}<DefinitionDescription>")?;
+                for child in &self.children {
+                    child._debug_string(indent + 1, f)?;
+                }
+                writeln!(f, "{i}</DefinitionDescription>")?;
+            }
             NodeKind::List { ordered, .. } => {
                 write!(f, "{i}<List")?;
 
@@ -372,12 +516,22 @@ impl Node {
                 }
                 writeln!(f, "{i}</{name}>")?;
             }
-            NodeKind::Link { url, title } => {
+            NodeKind::Link {
+                url,
+                title,
+                file,
+                attributes,
+            } => {
                 write!(f, "{i}<Link ")?;
                 write!(f, "url={{{url}}}")?;
                 if let Some(title) = title {
                     write!(f, " title={{{title}}}")?;
                 }
+                if let Some(file) = file {
+                    write_file_info(file, f)?;
+                    write!(f, " download={{{}}}", file.download)?;
+                }
+                write_link_attributes(attributes, f)?;
                 writeln!(f, ">")?;
                 for child in &self.children {
                     child._debug_string(indent + 1, f)?;
@@ -406,6 +560,117 @@ impl Node {
     }
 }
 
+fn write_file_info(file: &FileInfo, f: &mut Vec<u8>) -> std::io::Result<()> {
+    if let Some(extension) = &file.extension {
+        write!(f, " extension={{{extension}}}")?;
+    }
+    if let Some(size) = &file.size {
+        write!(f, " size={{{size}}}")?;
+    }
+
+    Ok(())
+}
+
+fn write_image_attributes(attributes: &ImageAttributes, f: &mut Vec<u8>) -> std::io::Result<()> {
+    if let Some(width) = &attributes.width {
+        write!(f, " width={{{width}}}")?;
+    }
+    if let Some(height) = &attributes.height {
+        write!(f, " height={{{height}}}")?;
+    }
+    if let Some(align) = &attributes.align {
+        write!(f, " align={{{}}}", align.as_str())?;
+    }
+    if let Some(caption) = &attributes.caption {
+        write!(f, " caption={{{caption}}}")?;
+    }
+
+    Ok(())
+}
+
+fn write_link_attributes(attributes: &LinkAttributes, f: &mut Vec<u8>) -> std::io::Result<()> {
+    if let Some(target) = &attributes.target {
+        write!(f, " target={{{target}}}")?;
+    }
+    if let Some(rel) = &attributes.rel {
+        write!(f, " rel={{{rel}}}")?;
+    }
+    if let Some(class) = &attributes.class {
+        write!(f, " class={{{class}}}")?;
+    }
+
+    Ok(())
+}
+
+/// `srcset` and `sizes` attributes for an image with resized versions. The
+/// original `url` is kept as the fallback.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResponsiveImage {
+    /// Versions in the format of the original, e.g. `/_assets/a-640w.png 640w`
+    pub srcset: String,
+    /// WebP versions, offered to browsers that support them
+    pub webp_srcset: Option<String>,
+    pub sizes: String,
+}
+
+/// Set with an attribute block after the image, e.g.
+/// `![Diagram](arch.png){width=600 align=center}`
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct ImageAttributes {
+    /// In pixels, or a percentage like `50%`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub align: Option<ImageAlign>,
+    /// Shown below the image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+impl ImageAttributes {
+    pub fn is_empty(&self) -> bool {
+        self == &ImageAttributes::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl ImageAlign {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImageAlign::Left => "left",
+            ImageAlign::Center => "center",
+            ImageAlign::Right => "right",
+        }
+    }
+}
+
+/// Set with an attribute block after the link, e.g.
+/// `[Docs](https://example.com){target=_blank rel=noopener}`
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct LinkAttributes {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rel: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+}
+
+impl LinkAttributes {
+    pub fn is_empty(&self) -> bool {
+        self == &LinkAttributes::default()
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize)]
 #[serde(tag = "name", content = "data", rename_all = "snake_case")]
 pub enum NodeKind {
@@ -427,11 +692,30 @@ pub enum NodeKind {
     Link {
         url: String,
         title: Option<String>,
+        /// Set when the link points at a file under `_assets`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        file: Option<FileInfo>,
+        #[serde(skip_serializing_if = "LinkAttributes::is_empty")]
+        attributes: LinkAttributes,
     },
     Image {
         url: String,
         alt: String,
         title: Option<String>,
+        /// Set when the build generated resized versions of the image
+        #[serde(skip_serializing_if = "Option::is_none")]
+        responsive: Option<ResponsiveImage>,
+        #[serde(skip_serializing_if = "ImageAttributes::is_empty")]
+        attributes: ImageAttributes,
+    },
+    /// An SVG from `_assets` embedded into the page instead of linked to,
+    /// e.g. `![Diagram](/_assets/arch.svg?inline)`
+    InlineSvg {
+        /// Path of the SVG, without the `inline` flag
+        src: String,
+        alt: String,
+        /// The sanitized markup
+        html: String,
     },
     List {
         ordered: bool,
@@ -439,17 +723,49 @@ pub enum NodeKind {
         spread: bool,
     },
     ListItem {
-        checked: Option<bool>,
         spread: bool,
     },
+    /// A list item with a checkbox, `- [ ] task` or `- [x] task`
+    TaskListItem {
+        checked: bool,
+        spread: bool,
+    },
+    /// A reference to a footnote, shown as the footnote's number
+    FootnoteReference {
+        number: usize,
+        /// Anchor of the reference, for the footnote to link back to
+        id: String,
+        /// Anchor of the footnote
+        definition_id: String,
+    },
+    /// The footnotes of the page, collected at its end in the order they
+    /// are first referenced. Footnotes that aren't referenced are left out.
+    Footnotes,
+    FootnoteDefinition {
+        number: usize,
+        id: String,
+        /// Anchors of every reference to the footnote, for back-links
+        reference_ids: Vec<String>,
+    },
+    DefinitionList,
+    DefinitionTerm,
+    DefinitionDescription,
     Code {
         value: String,
         rendered_value: Option<String>,
+        /// Tokens produced by build-time highlighting. Only set when
+        /// `RenderOptions.highlight_code` is enabled and the language is known.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        highlighted: Option<Vec<HighlightToken>>,
         language: Option<String>,
         title: Option<String>,
         label: Option<String>,
         raw: bool,
         show_whitespace: bool,
+        /// Which lines are commands and which are their output, for
+        /// ` ```console ` blocks
+        #[serde(skip_serializing_if = "Option::is_none")]
+        console: Option<ConsoleSession>,
     },
     InlineCode {
         value: String,
@@ -460,6 +776,9 @@ pub enum NodeKind {
     Heading {
         level: u8,
         slug: String,
+        /// Whether the heading links to itself, from `markdown.heading_anchor_links`
+        #[serde(skip_serializing_if = "crate::utils::is_false")]
+        anchor_link: bool,
     },
     Paragraph,
     /// A block-level HTML node. E.g.
@@ -499,13 +818,24 @@ pub enum NodeKind {
     Grid(Grid),
     Flex(Flex),
     Box(CBox),
+    Callout(Callout),
+    Download(Download),
+    Embed(Embed),
     #[serde(rename = "md_tabs")]
-    Tabs,
+    Tabs(Tabs),
     #[serde(rename = "md_tab")]
     Tab(Tab),
+    /// The content of a slot in a component. Only kept in the AST when
+    /// `RenderOptions::debug_slots` is set.
+    Slot {
+        name: Option<String>,
+        /// Whether the content came from the call site, rather than the
+        /// slot's defaults
+        filled: bool,
+    },
     Steps,
     Step(Step),
-    CodeSelect,
+    CodeSelect(CodeSelect),
     // ----------END Custom components --------------
 
     // ----------START OpenAPI Components -----------
@@ -524,7 +854,10 @@ impl Node {
                 NodeKind::Text { ref value } => {
                     out.push_str(value);
                 }
-                NodeKind::Break | NodeKind::Paragraph => {
+                NodeKind::Break
+                | NodeKind::Paragraph
+                | NodeKind::DefinitionTerm
+                | NodeKind::DefinitionDescription => {
                     out.push(' ');
                 }
                 NodeKind::Code { value, .. } => {
@@ -560,6 +893,50 @@ impl Node {
         Descendants { stack: vec![self] }
     }
 
+    /// Finds the element a fragment of a link to the page points at, e.g.
+    /// `#setup--tab-macos` for the panel of a tab.
+    pub fn resolve_fragment(&self, fragment: &str) -> Option<FragmentTarget> {
+        let fragment = fragment.trim_start_matches('#');
+
+        self.walk().find_map(|node| match &node.kind {
+            NodeKind::Heading { slug, .. } if slug == fragment => Some(FragmentTarget::Heading),
+            NodeKind::FootnoteReference { id, .. } | NodeKind::FootnoteDefinition { id, .. }
+                if id == fragment =>
+            {
+                Some(FragmentTarget::Footnote)
+            }
+            NodeKind::Tabs(tabs) if tabs.id == fragment => Some(FragmentTarget::Block),
+            NodeKind::Tabs(tabs) => node
+                .children
+                .iter()
+                .filter_map(|child| match &child.kind {
+                    NodeKind::Tab(tab) => Some(tab),
+                    _ => None,
+                })
+                .position(|tab| tab.id == fragment || tab.label_id == fragment)
+                .map(|index| FragmentTarget::Tab {
+                    tabs_id: tabs.id.clone(),
+                    index,
+                }),
+            NodeKind::Step(step) if step.id == fragment => Some(FragmentTarget::Step),
+            NodeKind::CodeSelect(code_select) if code_select.id == fragment => {
+                Some(FragmentTarget::Block)
+            }
+            NodeKind::CodeSelect(code_select) => code_select
+                .option_ids
+                .iter()
+                .position(|id| id == fragment)
+                .map(|index| FragmentTarget::CodeSelectOption {
+                    code_select_id: code_select.id.clone(),
+                    index,
+                }),
+            NodeKind::Callout(callout) if callout.id.as_deref() == Some(fragment) => {
+                Some(FragmentTarget::Callout)
+            }
+            _ => None,
+        })
+    }
+
     pub fn statistics(&self) -> ProseStatistics {
         let mut stats = ProseStatistics::default();
 
@@ -572,6 +949,14 @@ impl Node {
                 NodeKind::Paragraph => {
                     stats.paragraphs += 1;
                 }
+                // Each definition reads like a paragraph of its own
+                NodeKind::DefinitionList => {
+                    stats.paragraphs += node
+                        .children
+                        .iter()
+                        .filter(|c| matches!(c.kind, NodeKind::DefinitionDescription))
+                        .count() as u32;
+                }
                 _ => {}
             }
         }
@@ -587,6 +972,29 @@ impl Node {
     }
 }
 
+/// What a fragment of a link to a page points at, see
+/// [`Node::resolve_fragment`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum FragmentTarget {
+    Heading,
+    Footnote,
+    /// A `<Tabs>` or `<CodeSelect>` block
+    Block,
+    /// The panel of a tab, which is hidden until the tab is selected
+    Tab {
+        tabs_id: String,
+        index: usize,
+    },
+    Step,
+    /// A code block of a `<CodeSelect>`, which is hidden until it's selected
+    CodeSelectOption {
+        code_select_id: String,
+        index: usize,
+    },
+    /// A collapsible callout, which may have to be opened
+    Callout,
+}
+
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct ProseStatistics {
     pub headings: u32,
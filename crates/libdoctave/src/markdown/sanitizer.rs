@@ -140,7 +140,14 @@ lazy_static! {
 }
 
 impl Sanitizer<'_> {
-    pub fn sanitize_html_block(&self, name: &str, attributes: &mut Vec<Attribute>) -> Option<()> {
+    /// Removes the attributes that aren't safe from an element. Returns the
+    /// keys of the removed attributes, or `None` if the whole element should
+    /// be removed.
+    pub fn sanitize_html_block(
+        &self,
+        name: &str,
+        attributes: &mut Vec<Attribute>,
+    ) -> Option<Vec<String>> {
         if !self.tags.contains(name) {
             return None;
         }
@@ -154,40 +161,55 @@ impl Sanitizer<'_> {
             allowed_attributes.extend(self.generic_attributes.iter());
         };
 
+        let mut removed = vec![];
+
         attributes.retain(|a| {
-            match (name, a.key.as_str()) {
-                ("iframe", "src") => {
-                    if let Some(value) = &a.value {
-                        if let Ok(url) = Url::parse(value.as_str()) {
-                            return ALLOWED_IFRAME_HOSTS.iter().any(|(host, path)| {
-                                url.host_str() == Some(*host) && url.path().starts_with(path)
-                            });
-                        }
+            let keep = self.is_allowed_attribute(name, a, &allowed_attributes);
+            if !keep {
+                removed.push(a.key.clone());
+            }
+            keep
+        });
+
+        Some(removed)
+    }
+
+    fn is_allowed_attribute(
+        &self,
+        name: &str,
+        a: &Attribute,
+        allowed_attributes: &HashSet<&str>,
+    ) -> bool {
+        match (name, a.key.as_str()) {
+            ("iframe", "src") => {
+                if let Some(value) = &a.value {
+                    if let Ok(url) = Url::parse(value.as_str()) {
+                        return ALLOWED_IFRAME_HOSTS.iter().any(|(host, path)| {
+                            url.host_str() == Some(*host) && url.path().starts_with(path)
+                        });
                     }
                 }
-                ("input", "type") => {
-                    if let Some(value) = &a.value {
-                        return ["radio", "checkbox"].contains(&value.as_str());
-                    }
+            }
+            ("input", "type") => {
+                if let Some(value) = &a.value {
+                    return ["radio", "checkbox"].contains(&value.as_str());
                 }
-                (_, "src") | (_, "href") => {
-                    if let Some(value) = &a.value {
-                        if let Ok(url) = Url::parse(value.as_str()) {
-                            return self.url_schemes.contains(url.scheme());
-                        }
+            }
+            (_, "src") | (_, "href") => {
+                if let Some(value) = &a.value {
+                    if let Ok(url) = Url::parse(value.as_str()) {
+                        return self.url_schemes.contains(url.scheme());
                     }
                 }
-                _ => {}
             }
+            _ => {}
+        }
 
-            allowed_attributes.contains(&a.key.as_str())
-                || self
-                    .generic_attribute_prefixes
-                    .iter()
-                    .any(|prefix| a.key.starts_with(prefix))
-        });
-
-        Some(())
+        allowed_attributes.contains(&a.key.as_str())
+            || self
+                .generic_attribute_prefixes
+                .iter()
+                .any(|prefix| a.key.starts_with(prefix))
     }
 }
 
@@ -572,4 +594,94 @@ mod test {
             );
         }
     }
+
+    mod html_policy {
+        use crate::{
+            ast_mdx,
+            render_context::RenderContext,
+            settings::{HtmlPolicy, Settings},
+            Error,
+        };
+
+        fn settings(html_policy: HtmlPolicy) -> Settings {
+            Settings {
+                html_policy,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn sanitize_warns_about_removed_html() {
+            let markdown = indoc! {r#"
+            <div onclick="steal()">Hello</div>
+
+            <script>steal()</script>
+            "#};
+
+            let ctx = RenderContext::new();
+            let node = ast_mdx(markdown, &ctx).unwrap();
+
+            assert!(!node.debug_string().unwrap().contains("steal"));
+
+            let warnings = ctx.warnings.lock().unwrap();
+            assert_eq!(warnings.len(), 2);
+            assert!(warnings.iter().all(|w| w.code == Error::UNSAFE_HTML));
+            assert!(warnings[0]
+                .description
+                .contains("Removed `onclick` attribute from `<div>`"));
+            assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 1);
+            assert!(warnings[1]
+                .description
+                .contains("Removed `<script>` element"));
+            assert_eq!(warnings[1].position.as_ref().unwrap().start.row, 3);
+        }
+
+        #[test]
+        fn allow_keeps_everything() {
+            let settings = settings(HtmlPolicy::Allow);
+            let mut ctx = RenderContext::new();
+            ctx.with_settings(&settings);
+
+            let node = ast_mdx(r#"<div onclick="wave()">Hello</div>"#, &ctx).unwrap();
+
+            assert!(node.debug_string().unwrap().contains("onclick={wave()}"));
+            assert!(ctx.warnings.lock().unwrap().is_empty());
+        }
+
+        #[test]
+        fn strip_keeps_the_text() {
+            let settings = settings(HtmlPolicy::Strip);
+            let mut ctx = RenderContext::new();
+            ctx.with_settings(&settings);
+
+            let markdown = indoc! {r#"
+            <div class="note">Hello <span>there</span></div>
+
+            <script>steal()</script>
+            "#};
+
+            let output = ast_mdx(markdown, &ctx).unwrap().debug_string().unwrap();
+
+            assert!(output.contains("Hello"));
+            assert!(output.contains("there"));
+            assert!(!output.contains("<div"));
+            assert!(!output.contains("<span"));
+            assert!(!output.contains("steal"));
+        }
+
+        #[test]
+        fn built_in_components_are_not_affected() {
+            let settings = settings(HtmlPolicy::Strip);
+            let mut ctx = RenderContext::new();
+            ctx.with_settings(&settings);
+
+            let output = ast_mdx(r#"<Button href="/foo">Go</Button>"#, &ctx)
+                .unwrap()
+                .debug_string()
+                .unwrap();
+
+            assert!(output.contains("<a"), "{}", output);
+            assert!(ctx.warnings.lock().unwrap().is_empty());
+        }
+    }
 }
@@ -23,6 +23,15 @@ impl Position {
         self.start != self.end
     }
 
+    /// The span between two byte offsets in the input.
+    pub(crate) fn from_byte_range(input: &str, start: usize, end: usize) -> Self {
+        let mut pos = Position::default();
+        pos.start.bump_by_byte_offset(start, input);
+        pos.end.bump_by_byte_offset(end, input);
+
+        pos
+    }
+
     pub fn bump_by_byte_offset(&mut self, original_byte_offset: usize, input: &str) {
         self.start.bump_by_byte_offset(original_byte_offset, input);
         self.end.bump_by_byte_offset(original_byte_offset, input);
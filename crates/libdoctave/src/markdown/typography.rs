@@ -0,0 +1,218 @@
+//! Changes to the text of a rendered page that are turned on in the
+//! `markdown` section of the settings.
+use super::renderable_ast::{Node, NodeKind};
+
+/// Replaces straight quotes with curly ones, `--` and `---` with en and em
+/// dashes, and `...` with an ellipsis. Code and math are left as they are.
+pub(crate) fn smart_punctuation(node: &mut Node) {
+    smarten(node, &mut None);
+}
+
+/// Keeps the line breaks inside paragraphs, by splitting their text on the
+/// breaks and putting `Break` nodes between the lines.
+pub(crate) fn hard_breaks(node: &mut Node) {
+    if matches!(node.kind, NodeKind::Paragraph) {
+        split_lines(node);
+    } else {
+        for child in &mut node.children {
+            hard_breaks(child);
+        }
+    }
+}
+
+/// `previous` is the character before the node, or `None` at the start of a
+/// block, and is used to tell opening quotes from closing ones.
+fn smarten(node: &mut Node, previous: &mut Option<char>) {
+    match &mut node.kind {
+        NodeKind::Text { value } => {
+            *value = smarten_text(value, previous);
+            return;
+        }
+        NodeKind::Code { .. } | NodeKind::Math { .. } => {
+            *previous = None;
+            return;
+        }
+        NodeKind::InlineCode { .. }
+        | NodeKind::InlineMath { .. }
+        | NodeKind::Image { .. }
+        | NodeKind::InlineSvg { .. } => {
+            *previous = Some('x');
+            return;
+        }
+        NodeKind::Break => *previous = None,
+        // Inline nodes continue the text around them
+        NodeKind::Strong | NodeKind::Emphasis | NodeKind::Delete | NodeKind::Link { .. } => {}
+        _ => *previous = None,
+    }
+
+    for child in &mut node.children {
+        smarten(child, previous);
+    }
+}
+
+fn smarten_text(text: &str, previous: &mut Option<char>) -> String {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+
+        let (replacement, consumed) = match rest[0] {
+            '.' if rest.starts_with(&['.', '.', '.']) => ('…', 3),
+            '-' if rest.starts_with(&['-', '-', '-']) => ('—', 3),
+            '-' if rest.starts_with(&['-', '-']) => ('–', 2),
+            '"' if opens_quote(*previous) => ('“', 1),
+            '"' => ('”', 1),
+            '\'' if opens_quote(*previous) => ('‘', 1),
+            '\'' => ('’', 1),
+            c => (c, 1),
+        };
+
+        out.push(replacement);
+        *previous = Some(replacement);
+        i += consumed;
+    }
+
+    out
+}
+
+/// Quotes at the start of a block, or after whitespace or an opening
+/// bracket, open a quotation. Others close one, or are apostrophes.
+fn opens_quote(previous: Option<char>) -> bool {
+    match previous {
+        None => true,
+        Some(c) => c.is_whitespace() || "([{“‘—–".contains(c),
+    }
+}
+
+fn split_lines(node: &mut Node) {
+    let children = std::mem::take(&mut node.children);
+
+    for mut child in children {
+        if let NodeKind::Text { value } = &child.kind {
+            if value.contains('\n') {
+                for (index, line) in value.split('\n').enumerate() {
+                    if index > 0 {
+                        node.children.push(Node {
+                            kind: NodeKind::Break,
+                            pos: child.pos.clone(),
+                            children: vec![],
+                        });
+                    }
+
+                    if !line.is_empty() {
+                        node.children.push(Node {
+                            kind: NodeKind::Text {
+                                value: line.to_string(),
+                            },
+                            pos: child.pos.clone(),
+                            children: vec![],
+                        });
+                    }
+                }
+
+                continue;
+            }
+        }
+
+        split_lines(&mut child);
+        node.children.push(child);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::markdown::parser::to_ast_mdx;
+    use crate::markdown::Node;
+    use crate::render_context::RenderContext;
+    use crate::settings::Settings;
+
+    fn render(input: &str, markdown: &str) -> Node {
+        let settings = Settings::parse(&format!("title: Test\nmarkdown:\n{}", markdown)).unwrap();
+        let mut ctx = RenderContext::new();
+        ctx.with_settings(&settings);
+
+        to_ast_mdx(input, &ctx).unwrap()
+    }
+
+    #[test]
+    fn smart_punctuation() {
+        let ast = render(
+            r#"He said "don't" -- and left... 'Twas --- **"bold"** and `"code"`"#,
+            "  smart_punctuation: true",
+        );
+
+        assert_eq!(
+            ast.inner_text(),
+            "He said “don’t” – and left… ‘Twas — “bold” and \"code\""
+        );
+    }
+
+    #[test]
+    fn punctuation_is_left_alone_by_default() {
+        let input = r#"He said "don't" -- and left..."#;
+        let ast = render(input, "  math: true");
+
+        assert_eq!(ast.inner_text(), input);
+    }
+
+    #[test]
+    fn hard_breaks() {
+        let ast = render("One\nTwo *three*\nfour", "  hard_breaks: true");
+
+        assert_eq!(
+            ast.debug_string().unwrap(),
+            indoc! {"
+            <Paragraph>
+                <Text>
+                    One
+                </Text>
+                <Break />
+                <Text>
+                    Two
+                </Text>
+                <Emphasis>
+                    <Text>
+                        three
+                    </Text>
+                </Emphasis>
+                <Break />
+                <Text>
+                    four
+                </Text>
+            </Paragraph>
+            "}
+        );
+    }
+
+    #[test]
+    fn soft_breaks_by_default() {
+        let ast = render("One\nTwo", "  math: true");
+
+        assert!(!ast.debug_string().unwrap().contains("<Break />"));
+    }
+
+    #[test]
+    fn math_can_be_turned_off() {
+        let with_math = render("Costs $5 and $6", "  math: true");
+        let without_math = render("Costs $5 and $6", "  math: false");
+
+        assert!(with_math.debug_string().unwrap().contains("<InlineMath>"));
+        assert!(!without_math.debug_string().unwrap().contains("Math"));
+        assert_eq!(without_math.inner_text(), "Costs $5 and $6");
+    }
+
+    #[test]
+    fn heading_anchor_links() {
+        let ast = render("# Hello", "  heading_anchor_links: true");
+        let json = serde_json::to_value(&ast.children[0]).unwrap();
+
+        assert_eq!(json["kind"]["data"]["anchor_link"], true);
+
+        let ast = render("# Hello", "  math: true");
+        let json = serde_json::to_value(&ast.children[0]).unwrap();
+
+        assert!(json["kind"]["data"].get("anchor_link").is_none());
+    }
+}
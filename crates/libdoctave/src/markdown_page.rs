@@ -1,17 +1,36 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
-use crate::frontmatter::{Frontmatter, PageWidth};
+use crate::expressions::Value;
+use crate::frontmatter::{ChangelogEntry, Frontmatter, PageWidth};
+use crate::markdown::content_ast;
 use crate::markdown::{Node, NodeKind};
-use crate::page_kind::OutgoingLink;
+use crate::page_kind::LinkSet;
 use crate::render_context::{FileContext, RenderContext};
+use crate::settings::Settings;
 use crate::utils::capitalize;
-use crate::{frontmatter, markdown, Error, Result};
+use crate::{frontmatter, markdown, page_globals, Error, Position, Result};
 
 #[derive(Clone)]
 pub(crate) struct MarkdownPage {
     pub path: PathBuf,
     pub uri_path: String,
     pub content: String,
+    /// Set for generated pages whose content comes from a template in the
+    /// project, such as an OpenAPI overview template.
+    pub template: Option<PageTemplate>,
+    /// The text of the first h1, found the first time the title is asked for
+    first_heading: OnceLock<Option<String>>,
+}
+
+/// The file a generated page's content was read from, and the variables
+/// the content is rendered with.
+#[derive(Clone, Debug)]
+pub(crate) struct PageTemplate {
+    pub path: PathBuf,
+    pub globals: Vec<(String, Value)>,
 }
 
 impl MarkdownPage {
@@ -20,12 +39,83 @@ impl MarkdownPage {
             path: path.to_owned(),
             uri_path: crate::fs_to_uri_path(path),
             content: String::from_utf8(content).expect("Invalid UTF8 sequence"),
+            template: None,
+            first_heading: OnceLock::new(),
         }
     }
 
-    pub fn title(&self) -> Result<Option<String>> {
-        self.frontmatter()
-            .map(|f| f.title.or(Self::titelize(&self.path)))
+    pub(crate) fn from_template(path: &Path, content: String, template: PageTemplate) -> Self {
+        MarkdownPage {
+            path: path.to_owned(),
+            uri_path: crate::fs_to_uri_path(path),
+            content,
+            template: Some(template),
+            first_heading: OnceLock::new(),
+        }
+    }
+
+    /// The file errors in the page should point to. For templated pages,
+    /// this is the template rather than the generated page.
+    pub fn source_path(&self) -> &Path {
+        self.template
+            .as_ref()
+            .map(|t| t.path.as_path())
+            .unwrap_or(&self.path)
+    }
+
+    /// A hash of everything the page is rendered from, used to tell whether
+    /// the page changed between two versions of a project.
+    pub(crate) fn content_signature(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(self.content.as_bytes());
+        if let Some(template) = &self.template {
+            hasher.write(format!("{:?}", template.globals).as_bytes());
+        }
+        hasher.finish()
+    }
+
+    /// The `title` in the frontmatter, the text of the first h1, or the file
+    /// name, in that order. The root README has no file name to fall back to.
+    pub fn title(&self, settings: &Settings) -> Result<Option<String>> {
+        Ok(self
+            .explicit_title(settings)?
+            .or_else(|| Self::titelize(&self.path)))
+    }
+
+    /// The title set by the frontmatter or the first h1, if either is there.
+    /// Variables in the frontmatter title, e.g. `Install {@vars.product}`,
+    /// are filled in for the locale of the page.
+    pub fn explicit_title(&self, settings: &Settings) -> Result<Option<String>> {
+        Ok(self
+            .frontmatter()?
+            .title
+            .map(|title| {
+                let locale = settings.split_locale(&self.uri_path).0;
+                crate::variables::fill_in(title, settings, locale)
+            })
+            .or_else(|| self.first_heading(settings)))
+    }
+
+    /// The text of the first top-level h1. An h1 with expressions in it isn't
+    /// used, since its text is only known once the page is rendered.
+    fn first_heading(&self, settings: &Settings) -> Option<String> {
+        self.first_heading
+            .get_or_init(|| {
+                let mut ctx = RenderContext::new();
+                ctx.with_settings(settings);
+
+                let ast = content_ast::build_mdx(frontmatter::without(&self.content), &ctx).ok()?;
+                let heading = ast.children.into_iter().find(|node| {
+                    matches!(node.kind, content_ast::NodeKind::Heading { level: 1 })
+                })?;
+
+                if has_expression(&heading) {
+                    return None;
+                }
+
+                Some(heading.inner_text()).filter(|text| !text.is_empty())
+            })
+            .clone()
     }
 
     pub fn description(&self) -> Result<Option<String>> {
@@ -36,6 +126,58 @@ impl MarkdownPage {
         self.frontmatter().map(|f| f.search.hidden)
     }
 
+    pub fn search_boost(&self) -> Result<f32> {
+        self.frontmatter()
+            .map(|f| f.search_boost.filter(|b| *b > 0.0).unwrap_or(1.0))
+    }
+
+    pub fn last_updated(&self) -> Option<String> {
+        self.frontmatter().ok().and_then(|f| f.last_updated)
+    }
+
+    pub fn contributors(&self) -> Option<Vec<String>> {
+        self.frontmatter().ok().and_then(|f| f.contributors)
+    }
+
+    pub fn date(&self) -> Option<String> {
+        self.frontmatter().ok().and_then(|f| f.date)
+    }
+
+    pub fn changelog(&self) -> Vec<ChangelogEntry> {
+        self.frontmatter().map(|f| f.changelog).unwrap_or_default()
+    }
+
+    /// The layout set in the frontmatter. Not to be confused with the
+    /// `PageTemplate` the page may be generated from.
+    pub fn layout_template(&self) -> Option<String> {
+        self.frontmatter().ok().and_then(|f| f.template)
+    }
+
+    /// Style sheets listed in the `css` frontmatter, as written.
+    pub fn css(&self) -> Vec<String> {
+        self.frontmatter().map(|f| f.css).unwrap_or_default()
+    }
+
+    pub fn css_scope(&self) -> bool {
+        self.frontmatter().map(|f| f.css_scope).unwrap_or(false)
+    }
+
+    pub fn canonical_url(&self) -> Option<String> {
+        self.frontmatter().ok().and_then(|f| f.canonical_url)
+    }
+
+    pub fn noindex(&self) -> bool {
+        self.frontmatter().map(|f| f.noindex).unwrap_or(false)
+    }
+
+    pub fn orphan_ok(&self) -> bool {
+        self.frontmatter().map(|f| f.orphan_ok).unwrap_or(false)
+    }
+
+    pub fn is_draft(&self) -> bool {
+        self.frontmatter().map(|f| f.draft).unwrap_or(false)
+    }
+
     pub fn show_breadcrumbs(&self) -> bool {
         self.frontmatter().map(|f| f.breadcrumbs).unwrap_or(true)
     }
@@ -61,23 +203,50 @@ impl MarkdownPage {
             code: Error::INVALID_FRONTMATTER,
             message: "Invalid YAML syntax in frontmatter".to_owned(),
             description: e,
-            file: Some(self.path.clone()),
+            file: Some(self.source_path().to_owned()),
             position: None,
+            owner: None,
+            suggestions: vec![],
         })
     }
 
     fn frontmatter_lines_offset(&self) -> usize {
-        self.content[..self.frontmatter_chars_offset()]
-            .lines()
-            .count()
+        frontmatter::end_line(&self.content)
     }
 
     fn frontmatter_chars_offset(&self) -> usize {
         frontmatter::end_pos(&self.content)
     }
 
-    pub(crate) fn outgoing_links(&self, ctx: &mut RenderContext) -> Result<Vec<OutgoingLink>> {
+    /// Makes `@page`, `@project` and `@navigation`, and the template variables,
+    /// available to expressions, and clears any left over from previously
+    /// rendered pages. Also disables the components in `components_disabled`.
+    fn with_globals(&self, ctx: &mut RenderContext) {
+        let mut globals = ctx
+            .project
+            .and_then(|project| project.find_page_by_uri_path(&self.uri_path, true))
+            .map(|page| page_globals::compute(&page, ctx))
+            .unwrap_or_default();
+
+        if let Some(template) = &self.template {
+            globals.extend(template.globals.iter().cloned());
+        }
+
+        ctx.with_expression_globals(globals);
+        ctx.with_disabled_components(
+            self.frontmatter()
+                .map(|f| f.components_disabled)
+                .unwrap_or_default(),
+        );
+    }
+
+    #[cfg(test)]
+    pub(crate) fn outgoing_links(
+        &self,
+        ctx: &mut RenderContext,
+    ) -> Result<Vec<crate::page_kind::OutgoingLink>> {
         ctx.with_url_base_by_fs_path(&self.path);
+        self.with_globals(ctx);
 
         // NOTE(Nik): We want the ast _without the expanding relative links_.
         // We will expand the links below, once we've gathered the links, and
@@ -86,14 +255,67 @@ impl MarkdownPage {
         markdown::parser::extract_links(&self.content, ctx)
     }
 
-    pub(crate) fn asset_links(&self, ctx: &mut RenderContext) -> Result<Vec<OutgoingLink>> {
-        markdown::parser::extract_asset_links(&self.content, ctx)
-    }
+    /// Parses the page once for verification, gathering the internal, asset
+    /// and external links from the same AST. Fails the same way as [`Self::ast`].
+    pub(crate) fn verification_links(&self, ctx: &mut RenderContext) -> crate::Result<LinkSet> {
+        ctx.with_file_context(FileContext::new(
+            self.frontmatter_lines_offset(),
+            self.frontmatter_chars_offset(),
+            self.source_path().to_owned(),
+        ));
+        self.with_globals(ctx);
+
+        // The AST is built _without expanding relative links_, so that errors
+        // show the URI as it was written. Links are expanded once gathered.
+        ctx.relative_url_base = None;
+        let ast =
+            self.template_result(markdown::ast_mdx(frontmatter::without(&self.content), ctx))?;
 
-    pub(crate) fn external_links(&self, ctx: &mut RenderContext) -> Result<Vec<String>> {
-        ctx.with_url_base_by_page_uri(&self.uri_path);
+        let assets = markdown::parser::asset_links_in_ast(&ast, ctx);
+        let mut external = markdown::parser::external_links_in_ast(&ast, ctx);
+
+        ctx.with_url_base_by_fs_path(&self.path);
+        let mut internal = markdown::parser::links_in_ast(&ast, ctx);
+
+        for link in internal.iter_mut().chain(external.iter_mut()) {
+            link.position = match &self.template {
+                // Positions in generated pages point into the template
+                Some(_) => None,
+                None => link
+                    .position
+                    .as_ref()
+                    .and_then(|pos| self.destination_position(pos, &link.uri)),
+            };
+        }
 
-        markdown::parser::extract_external_links(&self.content, ctx)
+        Ok(LinkSet {
+            internal,
+            assets,
+            external,
+        })
+    }
+
+    /// Narrows the position of a link down to its destination, as written in
+    /// the source file, so that it can be replaced by a suggestion.
+    fn destination_position(&self, link_pos: &Position, uri: &str) -> Option<Position> {
+        let offset = self.frontmatter_chars_offset();
+        let span = self
+            .content
+            .get(offset + link_pos.start.byte_offset..offset + link_pos.end.byte_offset)?;
+
+        // Markdown destinations follow a `(` or `<`, HTML attributes a quote
+        let start = ['(', '<', '"', '\'']
+            .iter()
+            .find_map(|c| span.find(&format!("{}{}", c, uri)))?
+            + 1
+            + offset
+            + link_pos.start.byte_offset;
+
+        Some(Position::from_byte_range(
+            &self.content,
+            start,
+            start + uri.len(),
+        ))
     }
 
     fn titelize(path: &Path) -> Option<String> {
@@ -112,10 +334,24 @@ impl MarkdownPage {
         ctx.with_file_context(FileContext::new(
             self.frontmatter_lines_offset(),
             self.frontmatter_chars_offset(),
-            self.path.clone(),
+            self.source_path().to_owned(),
         ));
+        self.with_globals(ctx);
 
-        markdown::ast_mdx(frontmatter::without(&self.content), ctx)
+        self.template_result(markdown::ast_mdx(frontmatter::without(&self.content), ctx))
+    }
+
+    /// Errors in generated pages are reported as errors in their template.
+    fn template_result(&self, result: crate::Result<Node>) -> crate::Result<Node> {
+        if self.template.is_some() {
+            result.map_err(|mut e| {
+                e.code = Error::OPENAPI_TEMPLATE_ERROR;
+                e.message = "Error in OpenAPI overview template".to_string();
+                e
+            })
+        } else {
+            result
+        }
     }
 
     pub fn on_this_page_headings(&self, ctx: &mut RenderContext) -> Vec<OnThisPageHeading> {
@@ -128,7 +364,10 @@ impl MarkdownPage {
         };
 
         for node in ast.children {
-            if let NodeKind::Heading { level, ref slug } = node.kind {
+            if let NodeKind::Heading {
+                level, ref slug, ..
+            } = node.kind
+            {
                 if level > 1 && level < 5 {
                     let title = node.inner_text();
 
@@ -145,6 +384,13 @@ impl MarkdownPage {
     }
 }
 
+fn has_expression(node: &content_ast::Node) -> bool {
+    matches!(
+        node.kind,
+        content_ast::NodeKind::Expression { .. } | content_ast::NodeKind::ExpressionBlock { .. }
+    ) || node.children.iter().any(has_expression)
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OnThisPageHeading {
     pub level: u8,
@@ -158,6 +404,7 @@ impl std::fmt::Debug for MarkdownPage {
             .field("content", &self.content)
             .field("path", &self.path)
             .field("uri_path", &self.uri_path)
+            .field("template", &self.template)
             .finish()?;
 
         Ok(())
@@ -402,9 +649,45 @@ mod test {
             .to_owned(),
         );
 
-        let title = page.title().expect("failed to title");
+        let title = page.title(&Settings::default()).expect("failed to title");
+
+        assert_eq!(title.as_deref(), Some("Hi"));
+    }
 
-        assert_eq!(title.as_deref(), Some("Foo"));
+    #[test]
+    fn title_prefers_frontmatter_then_first_h1_then_file_name() {
+        let title = |content: &str| {
+            MarkdownPage::new(
+                Path::new("getting-started.md"),
+                content.as_bytes().to_owned(),
+            )
+            .title(&Settings::default())
+            .unwrap()
+        };
+
+        assert_eq!(
+            title("---\ntitle: From frontmatter\n---\n# From heading\n").as_deref(),
+            Some("From frontmatter")
+        );
+        assert_eq!(
+            title("Intro\n\n## Not this\n\n# From *heading*\n\n# Nor this\n").as_deref(),
+            Some("From heading")
+        );
+        assert_eq!(title("Just text\n").as_deref(), Some("Getting started"));
+    }
+
+    #[test]
+    fn h1_with_expressions_is_not_used_as_the_title() {
+        let page = MarkdownPage::new(
+            Path::new("greeting.md"),
+            "# Hello { 1 + 1 }\n".as_bytes().to_owned(),
+        );
+
+        assert_eq!(page.explicit_title(&Settings::default()), Ok(None));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("Greeting".to_string()))
+        );
     }
 
     #[test]
@@ -1,9 +1,16 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use crate::render_context::RenderContext;
-use crate::{markdown, page_kind::PageKind, project::Project, Error, Result};
+use crate::{
+    markdown,
+    open_api::model::Operation,
+    page_kind::PageKind,
+    project::{publishes_drafts, Project},
+    variables, Error, RenderOptions, Result, UndefinedVariables,
+};
 use serde::{Deserialize, Serialize};
+use serde_path_to_error::Segment;
 
 /// Build the navigation structure.
 ///
@@ -29,7 +36,14 @@ pub(crate) fn build(input: &str, ctx: &RenderContext, project: &Project) -> Resu
 /// unknown OpenAPI specs, user preferences, etc.
 ///
 /// More specifically, these are contextual errors, not syntactic errors.
-pub(crate) fn verify(input: &str, project: &Project) -> Vec<Error> {
+///
+/// Labels referencing variables that don't exist are only errors when
+/// `undefined_variables` is [`UndefinedVariables::Error`].
+pub(crate) fn verify(
+    input: &str,
+    project: &Project,
+    undefined_variables: UndefinedVariables,
+) -> Vec<Error> {
     match parse_description(input) {
         Ok(sections) => {
             let mut errors = vec![];
@@ -40,15 +54,40 @@ pub(crate) fn verify(input: &str, project: &Project) -> Vec<Error> {
                 }
             }
 
+            verify_badges(input, &sections, project, &mut errors);
+
+            let (mut label_errors, mut undefined) = verify_labels(input, &sections, project);
+            errors.append(&mut label_errors);
+
+            if undefined_variables == UndefinedVariables::Error {
+                errors.append(&mut undefined);
+            }
+
             errors
         }
         Err(error) => vec![error],
     }
 }
 
+/// Labels referencing variables that don't exist, for reporting as warnings
+/// when `undefined_variables` is [`UndefinedVariables::Warn`].
+pub(crate) fn undefined_label_variables(input: &str, project: &Project) -> Vec<Error> {
+    parse_description(input)
+        .map(|sections| verify_labels(input, &sections, project).1)
+        .unwrap_or_default()
+}
+
+/// How deeply items can be nested before the theme can't show them. Deeper
+/// items are reported as warnings.
+pub(crate) const MAX_DEPTH: usize = 5;
+
+/// Colors every badge can use. Named colors from `theme.colors.tokens` can be
+/// used too.
+pub(crate) const BADGE_COLORS: &[&str] = &["gray", "accent", "blue", "green", "orange", "red"];
+
 fn parse_description(input: &str) -> Result<Vec<SectionDescription>> {
-    serde_yaml::from_str(input).map_err(|e| {
-        Error::from_serde_yaml(
+    crate::yaml::from_str(input).map_err(|e| {
+        Error::from_yaml(
             e,
             Error::INVALID_NAVIGATION,
             "Invalid navigation.yaml".to_owned(),
@@ -77,6 +116,92 @@ impl Navigation {
             .flat_map(|s| s.gather_links())
             .collect::<Vec<_>>()
     }
+
+    /// The `external` links of all items, including nested ones.
+    pub(crate) fn gather_external_links(&self) -> Vec<String> {
+        fn external(item: &Item) -> Vec<String> {
+            item.external_href()
+                .map(str::to_owned)
+                .into_iter()
+                .chain(item.items().unwrap_or_default().iter().flat_map(external))
+                .collect()
+        }
+
+        self.sections
+            .iter()
+            .flat_map(|s| s.items.iter().flat_map(external))
+            .collect()
+    }
+
+    /// Marks the sections and items that lead to the page, so that they can
+    /// be shown open. Items are marked when they have nested items, and they
+    /// or one of their nested items link to the page.
+    pub fn expand_for_current_page(&mut self, uri_path: &str) {
+        for section in &mut self.sections {
+            section.expanded_for_current_page = section.has_link_to(uri_path);
+
+            for item in &mut section.items {
+                item.expand_for_current_page(uri_path);
+            }
+        }
+    }
+
+    /// Items nested deeper than [`MAX_DEPTH`]. Their nested items aren't
+    /// listed separately.
+    pub(crate) fn items_too_deep(&self) -> Vec<&Item> {
+        fn too_deep<'a>(item: &'a Item, out: &mut Vec<&'a Item>) {
+            if item.depth() > MAX_DEPTH {
+                out.push(item);
+            } else {
+                for child in item.items().unwrap_or_default() {
+                    too_deep(child, out);
+                }
+            }
+        }
+
+        let mut out = vec![];
+
+        for section in &self.sections {
+            for item in &section.items {
+                too_deep(item, &mut out);
+            }
+        }
+
+        out
+    }
+
+    /// The badges of items linking to pages, keyed by [`link_identity`]. The
+    /// first item linking to a page decides its badge.
+    pub(crate) fn page_badges(&self) -> HashMap<PathBuf, Badge> {
+        let mut badges = HashMap::new();
+
+        for item in self.flattened_links() {
+            if let (Some(href), Some(badge)) = (item.href(), item.badge()) {
+                // Anchors point inside a page, like to an operation
+                if !href.contains('#') {
+                    badges
+                        .entry(link_identity(href))
+                        .or_insert_with(|| badge.clone());
+                }
+            }
+        }
+
+        badges
+    }
+
+    /// All items with an internal link, in the order they are displayed in
+    /// the navigation. Parents come before their children.
+    pub(crate) fn flattened_links(&self) -> Vec<&Item> {
+        let mut out = vec![];
+
+        for section in &self.sections {
+            for item in &section.items {
+                flatten_links_from_item(item, &mut out);
+            }
+        }
+
+        out
+    }
 }
 
 impl std::ops::Deref for Navigation {
@@ -92,6 +217,9 @@ pub struct Section {
     pub heading: Option<String>,
     pub collapsed: bool,
     pub collapsible: bool,
+    /// Whether the page being shown is in this section. Only set by
+    /// [`Navigation::expand_for_current_page`].
+    pub expanded_for_current_page: bool,
     pub items: Vec<Item>,
 }
 
@@ -142,6 +270,18 @@ fn gather_links_from_item(item: &Item) -> Vec<String> {
     }
 }
 
+fn flatten_links_from_item<'a>(item: &'a Item, out: &mut Vec<&'a Item>) {
+    if item.href().is_some() {
+        out.push(item);
+    }
+
+    if let Some(items) = item.items() {
+        for child in items {
+            flatten_links_from_item(child, out);
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Copy, Serialize)]
 pub enum HttpMethod {
     Get,
@@ -159,7 +299,7 @@ pub enum HttpMethod {
 }
 
 impl HttpMethod {
-    fn from_str(s: &str) -> Option<HttpMethod> {
+    pub(crate) fn from_str(s: &str) -> Option<HttpMethod> {
         match s.to_lowercase().as_str() {
             "get" => Some(HttpMethod::Get),
             "post" => Some(HttpMethod::Post),
@@ -175,6 +315,44 @@ impl HttpMethod {
     }
 }
 
+/// A short label shown next to a navigation item, like "Beta", or the method
+/// of an API operation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Badge {
+    pub text: String,
+    /// One of [`BADGE_COLORS`], or a named color from the theme
+    #[serde(default = "Badge::default_color")]
+    pub color: String,
+}
+
+impl Badge {
+    fn default_color() -> String {
+        String::from("gray")
+    }
+
+    /// The method chip of an OpenAPI operation, e.g. a blue `GET`.
+    pub(crate) fn for_method(method: HttpMethod) -> Badge {
+        use HttpMethod::*;
+        let (text, color) = match method {
+            Get => ("GET", "blue"),
+            Post => ("POST", "green"),
+            Put => ("PUT", "orange"),
+            Patch => ("PATCH", "orange"),
+            Delete => ("DELETE", "red"),
+            Option => ("OPTIONS", "gray"),
+            Head => ("HEAD", "gray"),
+            Trace => ("TRACE", "gray"),
+            WebHook => ("EVENT", "accent"),
+        };
+
+        Badge {
+            text: text.to_owned(),
+            color: color.to_owned(),
+        }
+    }
+}
+
 impl std::fmt::Display for HttpMethod {
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
         use HttpMethod::*;
@@ -204,6 +382,9 @@ pub enum Item {
         collapsed: Option<bool>,
         #[serde(skip_serializing_if = "Option::is_none")]
         collapsible: Option<bool>,
+        /// 1 for the items of a section, 2 for the items nested in those, etc.
+        depth: usize,
+        expanded_for_current_page: bool,
         #[serde(skip_serializing_if = "Option::is_none")]
         items: Option<Vec<Item>>,
     },
@@ -223,6 +404,11 @@ pub enum Item {
         #[serde(skip_serializing_if = "Option::is_none")]
         http_method: Option<HttpMethod>,
         #[serde(skip_serializing_if = "Option::is_none")]
+        badge: Option<Badge>,
+        /// 1 for the items of a section, 2 for the items nested in those, etc.
+        depth: usize,
+        expanded_for_current_page: bool,
+        #[serde(skip_serializing_if = "Option::is_none")]
         items: Option<Vec<Item>>,
     },
 }
@@ -231,9 +417,13 @@ fn normalize_link(link: &str) -> String {
     format!("/{}", link.strip_prefix('/').unwrap_or(link))
 }
 
-fn matches_link(uri_or_fs_path: &str, other_uri_or_fs_path: &str) -> bool {
+/// Key that is equal for both the URI and FS path versions of the same link.
+pub(crate) fn link_identity(uri_or_fs_path: &str) -> PathBuf {
     crate::uri_to_fs_path(&normalize_link(uri_or_fs_path))
-        == crate::uri_to_fs_path(&normalize_link(other_uri_or_fs_path))
+}
+
+fn matches_link(uri_or_fs_path: &str, other_uri_or_fs_path: &str) -> bool {
+    link_identity(uri_or_fs_path) == link_identity(other_uri_or_fs_path)
 }
 
 impl Item {
@@ -284,6 +474,13 @@ impl Item {
         }
     }
 
+    pub fn badge(&self) -> Option<&Badge> {
+        match self {
+            Item::Link { badge, .. } => badge.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn heading(&self) -> Option<&str> {
         match self {
             Item::Subheading { label, .. } => Some(label),
@@ -311,6 +508,48 @@ impl Item {
             Item::Link { collapsed, .. } => *collapsed,
         }
     }
+
+    pub fn depth(&self) -> usize {
+        match &self {
+            Item::Subheading { depth, .. } => *depth,
+            Item::Link { depth, .. } => *depth,
+        }
+    }
+
+    pub fn expanded_for_current_page(&self) -> bool {
+        match &self {
+            Item::Subheading {
+                expanded_for_current_page,
+                ..
+            } => *expanded_for_current_page,
+            Item::Link {
+                expanded_for_current_page,
+                ..
+            } => *expanded_for_current_page,
+        }
+    }
+
+    fn expand_for_current_page(&mut self, uri_path: &str) {
+        let expanded =
+            self.items().is_some_and(|items| !items.is_empty()) && self.has_link_to(uri_path);
+
+        let (Item::Subheading {
+            expanded_for_current_page,
+            items,
+            ..
+        }
+        | Item::Link {
+            expanded_for_current_page,
+            items,
+            ..
+        }) = self;
+
+        *expanded_for_current_page = expanded;
+
+        for item in items.iter_mut().flatten() {
+            item.expand_for_current_page(uri_path);
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -320,12 +559,16 @@ pub struct SectionDescription {
     pub collapsed: Option<bool>,
     pub collapsible: Option<bool>,
     pub items: Option<Vec<ItemDescription>>,
+    /// Per-locale overrides for the heading
+    pub translations: Option<HashMap<String, String>>,
 }
 
 impl SectionDescription {
     fn verify(&self, project: &Project) -> Vec<Error> {
         let mut errors = vec![];
 
+        verify_translations(self.translations.as_ref(), project, &mut errors);
+
         if let Some(items) = &self.items {
             for item in items {
                 item.verify(project, &mut errors)
@@ -341,20 +584,21 @@ impl SectionDescription {
             collapsed,
             collapsible,
             items,
+            translations,
         } = self;
 
+        let collapsed = collapsed.or_else(|| {
+            heading
+                .is_some()
+                .then_some(project.settings().navigation().collapsed)
+        });
+
         Some(Section {
-            heading,
+            heading: heading.map(|h| translate(h, translations.as_ref(), ctx)),
             collapsed: collapsed.unwrap_or(false),
             collapsible: collapsible.or(collapsed).unwrap_or(false),
-            items: items
-                .map(|i| {
-                    i.into_iter()
-                        .filter_map(|i| i.resolve(ctx, project))
-                        .flatten()
-                        .collect::<Vec<_>>()
-                })
-                .unwrap_or_default(),
+            expanded_for_current_page: false,
+            items: resolve_items(items, 1, ctx, project).unwrap_or_default(),
         })
     }
 }
@@ -363,19 +607,25 @@ impl SectionDescription {
 #[serde(untagged, deny_unknown_fields)]
 pub enum ItemDescription {
     Link {
-        label: String,
+        /// Falls back to the title of the linked page
+        label: Option<String>,
         href: Option<String>,
         external: Option<String>,
         title: Option<String>,
         collapsed: Option<bool>,
         collapsible: Option<bool>,
+        badge: Option<Badge>,
         items: Option<Vec<ItemDescription>>,
+        /// Per-locale overrides for the label
+        translations: Option<HashMap<String, String>>,
     },
     Subheading {
         subheading: String,
         collapsed: Option<bool>,
         collapsible: Option<bool>,
         items: Option<Vec<ItemDescription>>,
+        /// Per-locale overrides for the subheading
+        translations: Option<HashMap<String, String>>,
     },
     OpenApi {
         open_api_spec: String,
@@ -392,7 +642,30 @@ impl ItemDescription {
         }
 
         match &self {
-            ItemDescription::Link { href, external, .. } => {
+            ItemDescription::Link {
+                label,
+                href,
+                external,
+                translations,
+                ..
+            } => {
+                verify_translations(translations.as_ref(), project, errors);
+
+                if label.is_none() && href.is_none() && external.is_some() {
+                    errors.push(Error {
+                        code: Error::NAVIGATION_ERROR,
+                        message: "Missing label for external link in navigation.yaml".to_string(),
+                        description: format!(
+                            "Found \"{}\" without a `label`. Only links to pages in the project can leave out the label, and use the page's title instead.",
+                            external.as_deref().unwrap_or_default()
+                        ),
+                        file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    })
+                }
+
                 if href.clone().or(external.clone()).is_none() {
                     errors.push(Error {
                         code: Error::NAVIGATION_ERROR,
@@ -401,6 +674,8 @@ impl ItemDescription {
                             .to_string(),
                         file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })
                 }
 
@@ -412,6 +687,8 @@ impl ItemDescription {
                             description: format!("Found \"{}\", which is an external link. Use `external` instead of `href` for external urls", href),
                             file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
             position: None,
+            owner: None,
+            suggestions: vec![],
                         })
                     }
                 }
@@ -429,7 +706,9 @@ impl ItemDescription {
                     errors,
                 );
             }
-            _ => {}
+            ItemDescription::Subheading { translations, .. } => {
+                verify_translations(translations.as_ref(), project, errors);
+            }
         }
     }
 
@@ -462,6 +741,8 @@ impl ItemDescription {
                 ),
                 file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
                 position: None,
+                owner: None,
+                suggestions: vec![],
             });
         }
     }
@@ -501,30 +782,35 @@ impl ItemDescription {
                         ),
                         file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
             }
         }
     }
 
-    fn resolve(self, ctx: &RenderContext, project: &Project) -> Option<Vec<Item>> {
+    fn resolve(self, depth: usize, ctx: &RenderContext, project: &Project) -> Option<Vec<Item>> {
         match self {
             ItemDescription::Subheading {
                 subheading,
                 collapsed,
                 collapsible,
                 items,
-            } => Some(vec![Item::Subheading {
-                label: subheading,
-                collapsed: Some(collapsed.unwrap_or(false)),
-                collapsible: collapsible.or(collapsed).or(Some(false)),
-                items: items.map(|s| {
-                    s.into_iter()
-                        .filter_map(|i| i.resolve(ctx, project))
-                        .flatten()
-                        .collect::<Vec<_>>()
-                }),
-            }]),
+                translations,
+            } => {
+                let items = resolve_items(items, depth + 1, ctx, project);
+                let collapsed = default_collapsed(collapsed, items.as_deref(), project);
+
+                Some(vec![Item::Subheading {
+                    label: translate(subheading, translations.as_ref(), ctx),
+                    collapsed: Some(collapsed.unwrap_or(false)),
+                    collapsible: collapsible.or(collapsed).or(Some(false)),
+                    depth,
+                    expanded_for_current_page: false,
+                    items,
+                }])
+            }
             ItemDescription::Link {
                 label,
                 href,
@@ -532,28 +818,44 @@ impl ItemDescription {
                 title,
                 collapsed,
                 collapsible,
+                badge,
                 items,
-            } => Some(vec![Item::Link {
-                label,
-                href: href.map(|href| markdown::parser::to_final_link(&href, ctx)),
-                external_href: external,
-                title,
-                collapsed: Some(collapsed.unwrap_or(false)),
-                collapsible: collapsible.or(collapsed).or(Some(false)),
-                http_method: None,
-                items: items.map(|s| {
-                    s.into_iter()
-                        .filter_map(|i| i.resolve(ctx, project))
-                        .flatten()
-                        .collect::<Vec<_>>()
-                }),
-            }]),
+                translations,
+            } if !href
+                .as_deref()
+                .map(|href| links_to_unpublished_page(href, ctx, project))
+                .unwrap_or(false) =>
+            {
+                let label = label
+                    .or_else(|| href.as_deref().and_then(|href| page_title(href, project)))
+                    .or_else(|| href.clone().or(external.clone()))
+                    .unwrap_or_default();
+                let items = resolve_items(items, depth + 1, ctx, project);
+                let collapsed = default_collapsed(collapsed, items.as_deref(), project);
+
+                Some(vec![Item::Link {
+                    label: translate(label, translations.as_ref(), ctx),
+                    href: href.map(|href| markdown::parser::to_final_link(&href, ctx)),
+                    external_href: external,
+                    title,
+                    collapsed: Some(collapsed.unwrap_or(false)),
+                    collapsible: collapsible.or(collapsed).or(Some(false)),
+                    http_method: None,
+                    badge,
+                    depth,
+                    expanded_for_current_page: false,
+                    items,
+                }])
+            }
+            // Links to drafts are left out, along with their nested items
+            ItemDescription::Link { .. } => None,
             ItemDescription::OpenApi {
                 open_api_spec,
                 only,
             } => Some(Self::resolve_open_api_specs(
                 open_api_spec.as_str(),
                 only.as_ref(),
+                depth,
                 project,
                 ctx,
             )),
@@ -564,6 +866,7 @@ impl ItemDescription {
     fn resolve_open_api_specs(
         open_api_spec_path: &str,
         only: Option<&Vec<String>>,
+        depth: usize,
         project: &Project,
         ctx: &RenderContext,
     ) -> Vec<Item> {
@@ -585,8 +888,11 @@ impl ItemDescription {
                     href: Some(markdown::parser::to_final_link(&spec.uri_prefix, ctx)),
                     external_href: None,
                     http_method: None,
+                    badge: None,
                     collapsed: Some(false),
                     collapsible: Some(false),
+                    depth,
+                    expanded_for_current_page: false,
                     items: None,
                 });
             }
@@ -624,6 +930,55 @@ impl ItemDescription {
 
             // Create a link per page, with operations links as child items
             for page in pages {
+                let operation_link = |op: &&Operation, depth: usize| {
+                    let http_method = HttpMethod::from_str(op.method.as_str());
+
+                    Item::Link {
+                        label: op.summary.as_ref().unwrap_or(&op.route_pattern).to_owned(),
+                        title: Some(op.summary.as_ref().unwrap_or(&op.route_pattern).to_owned()),
+                        href: Some(markdown::parser::to_final_link(
+                            &format!("{}#{}", page.uri_path, op.anchor_tag),
+                            ctx,
+                        )),
+                        external_href: None,
+                        http_method,
+                        badge: http_method.map(Badge::for_method),
+                        collapsed: Some(false),
+                        collapsible: Some(false),
+                        depth,
+                        expanded_for_current_page: false,
+                        items: None,
+                    }
+                };
+
+                // With the deprecation banner on, deprecated operations are
+                // moved under their own heading at the end of the tag
+                let (deprecated, current): (Vec<_>, Vec<_>) = page
+                    .operations()
+                    .iter()
+                    .partition(|op| spec.deprecation_banner && op.deprecated);
+
+                let mut operations = current
+                    .iter()
+                    .map(|op| operation_link(op, depth + 1))
+                    .collect::<Vec<_>>();
+
+                if !deprecated.is_empty() {
+                    operations.push(Item::Subheading {
+                        label: "Deprecated".to_owned(),
+                        collapsed: Some(false),
+                        collapsible: Some(false),
+                        depth: depth + 1,
+                        expanded_for_current_page: false,
+                        items: Some(
+                            deprecated
+                                .iter()
+                                .map(|op| operation_link(op, depth + 2))
+                                .collect(),
+                        ),
+                    });
+                }
+
                 items.push(Item::Link {
                     label: page
                         .tag()
@@ -632,29 +987,13 @@ impl ItemDescription {
                     href: Some(markdown::parser::to_final_link(&page.uri_path, ctx)),
                     external_href: None,
                     http_method: None,
+                    badge: None,
                     title: page.tag().map(|t| t.to_owned()),
                     collapsed: Some(true),
                     collapsible: Some(true),
-                    items: Some(
-                        page.operations()
-                            .iter()
-                            .map(|op| Item::Link {
-                                label: op.summary.as_ref().unwrap_or(&op.route_pattern).to_owned(),
-                                title: Some(
-                                    op.summary.as_ref().unwrap_or(&op.route_pattern).to_owned(),
-                                ),
-                                href: Some(markdown::parser::to_final_link(
-                                    &format!("{}#{}", page.uri_path, op.anchor_tag),
-                                    ctx,
-                                )),
-                                external_href: None,
-                                http_method: HttpMethod::from_str(op.method.as_str()),
-                                collapsed: Some(false),
-                                collapsible: Some(false),
-                                items: None,
-                            })
-                            .collect::<Vec<_>>(),
-                    ),
+                    depth,
+                    expanded_for_current_page: false,
+                    items: Some(operations),
                 });
             }
 
@@ -682,7 +1021,7 @@ impl ItemDescription {
     #[allow(dead_code)]
     fn label(&self) -> Option<&str> {
         match self {
-            ItemDescription::Link { label, .. } => Some(label),
+            ItemDescription::Link { label, .. } => label.as_deref(),
             _ => None,
         }
     }
@@ -722,6 +1061,401 @@ impl ItemDescription {
     }
 }
 
+fn resolve_items(
+    items: Option<Vec<ItemDescription>>,
+    depth: usize,
+    ctx: &RenderContext,
+    project: &Project,
+) -> Option<Vec<Item>> {
+    items.map(|items| {
+        items
+            .into_iter()
+            .filter_map(|i| i.resolve(depth, ctx, project))
+            .flatten()
+            .collect()
+    })
+}
+
+/// Items with nested items fall back to the `navigation.collapsed` setting.
+fn default_collapsed(
+    collapsed: Option<bool>,
+    items: Option<&[Item]>,
+    project: &Project,
+) -> Option<bool> {
+    collapsed.or_else(|| {
+        items
+            .is_some_and(|items| !items.is_empty())
+            .then_some(project.settings().navigation().collapsed)
+    })
+}
+
+/// Picks the translation for the locale being rendered, if there is one,
+/// and fills in the variables it uses, e.g. `Install {@vars.product}`.
+/// Labels that fail to evaluate are left as they are, and reported by
+/// [`verify`].
+fn translate(
+    text: String,
+    translations: Option<&HashMap<String, String>>,
+    ctx: &RenderContext,
+) -> String {
+    let text = ctx
+        .options
+        .locale
+        .as_ref()
+        .and_then(|locale| translations.and_then(|t| t.get(locale)))
+        .cloned()
+        .unwrap_or(text);
+
+    variables::fill_in(text, ctx.settings, ctx.options.locale.as_deref())
+}
+
+/// The title of the page a link without a label points to.
+fn page_title(href: &str, project: &Project) -> Option<String> {
+    let path = href.split('#').next().unwrap_or(href);
+
+    project
+        .find_page_by_uri_path(&crate::fs_to_uri_path(Path::new(path)), true)
+        .and_then(|page| page.title().ok())
+}
+
+/// Drafts and excluded pages are left out of the navigation, unless drafts
+/// are being published.
+fn links_to_unpublished_page(href: &str, ctx: &RenderContext, project: &Project) -> bool {
+    if publishes_drafts(Some(ctx.options)) {
+        return false;
+    }
+
+    let path = href.split('#').next().unwrap_or(href);
+
+    project
+        .find_page_by_uri_path(&crate::fs_to_uri_path(Path::new(path)), true)
+        .map(|page| page.draft_status().is_some())
+        .unwrap_or(false)
+}
+
+/// The badges of the items linking to pages, from every navigation of the
+/// project, keyed by [`link_identity`]. Navigations that can't be built are
+/// skipped, since they're reported when verifying the project.
+pub(crate) fn page_badges(
+    project: &Project,
+    opts: Option<&RenderOptions>,
+) -> HashMap<PathBuf, Badge> {
+    let nav_paths = project
+        .tabs()
+        .map(|tabs| tabs.nav_paths())
+        .unwrap_or_else(|| vec![String::from("/")]);
+
+    let mut badges = HashMap::new();
+
+    for navigation in nav_paths
+        .iter()
+        .filter_map(|path| project.navigation(opts, path).ok())
+    {
+        for (identity, badge) in navigation.page_badges() {
+            badges.entry(identity).or_insert(badge);
+        }
+    }
+
+    badges
+}
+
+/// Checks the colors of badges against the theme, with errors pointing to
+/// the color in `input`.
+fn verify_badges(
+    input: &str,
+    sections: &[SectionDescription],
+    project: &Project,
+    errors: &mut Vec<Error>,
+) {
+    fn verify_items(
+        items: &[ItemDescription],
+        path: &mut Vec<Segment>,
+        input: &str,
+        project: &Project,
+        errors: &mut Vec<Error>,
+    ) {
+        for (index, item) in items.iter().enumerate() {
+            path.push(Segment::Seq { index });
+
+            if let ItemDescription::Link {
+                badge: Some(badge), ..
+            } = item
+            {
+                if !is_badge_color(&badge.color, project) {
+                    let color_path = [
+                        &path[..],
+                        &[
+                            Segment::Map {
+                                key: "badge".to_owned(),
+                            },
+                            Segment::Map {
+                                key: "color".to_owned(),
+                            },
+                        ],
+                    ]
+                    .concat();
+
+                    errors.push(Error {
+                        code: Error::NAVIGATION_ERROR,
+                        message: "Unknown badge color in navigation.yaml".to_string(),
+                        description: format!(
+                            "Expected one of [{}], or a named color from `theme.colors.tokens`.\nFound \"{}\" for the badge \"{}\".",
+                            BADGE_COLORS
+                                .iter()
+                                .map(|c| format!("\"{}\"", c))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            badge.color,
+                            badge.text
+                        ),
+                        file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
+                        position: crate::yaml::position_of(input, &color_path),
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+            }
+
+            if let Some(children) = item.items() {
+                path.push(Segment::Map {
+                    key: "items".to_owned(),
+                });
+                verify_items(children, path, input, project, errors);
+                path.pop();
+            }
+
+            path.pop();
+        }
+    }
+
+    for (index, section) in sections.iter().enumerate() {
+        if let Some(items) = &section.items {
+            let mut path = vec![
+                Segment::Seq { index },
+                Segment::Map {
+                    key: "items".to_owned(),
+                },
+            ];
+
+            verify_items(items, &mut path, input, project, errors);
+        }
+    }
+}
+
+/// Evaluates the expressions in headings, labels, and their translations in
+/// every locale they are shown in. Returns the expressions that fail, and
+/// the lookups of variables that don't exist, e.g. `{@vars.produt}`.
+fn verify_labels(
+    input: &str,
+    sections: &[SectionDescription],
+    project: &Project,
+) -> (Vec<Error>, Vec<Error>) {
+    struct Labels<'a> {
+        input: &'a str,
+        project: &'a Project,
+        errors: Vec<Error>,
+        undefined: Vec<Error>,
+    }
+
+    impl Labels<'_> {
+        fn verify(
+            &mut self,
+            text: &str,
+            translations: Option<&HashMap<String, String>>,
+            path: &[Segment],
+        ) {
+            let settings = self.project.settings();
+            let locales = std::iter::once(None).chain(settings.locales().iter().map(Some));
+
+            for locale in locales {
+                self.verify_in(text, locale.map(|l| l.as_str()), path);
+            }
+
+            for (locale, translation) in translations.into_iter().flatten() {
+                let path = [
+                    path.split_last().map(|(_, parent)| parent).unwrap_or(&[]),
+                    &[
+                        Segment::Map {
+                            key: "translations".to_owned(),
+                        },
+                        Segment::Map {
+                            key: locale.to_owned(),
+                        },
+                    ],
+                ]
+                .concat();
+
+                self.verify_in(translation, Some(locale), &path);
+            }
+        }
+
+        fn verify_in(&mut self, text: &str, locale: Option<&str>, path: &[Segment]) {
+            if !text.contains('{') {
+                return;
+            }
+
+            let globals = [(
+                variables::GLOBAL.to_string(),
+                variables::value(self.project.settings(), locale),
+            )];
+            let position = crate::yaml::position_of(self.input, path);
+
+            match variables::interpolate(text, &globals, true) {
+                Ok(interpolated) => {
+                    for error in interpolated.undefined {
+                        let error = Error {
+                            code: Error::NAVIGATION_ERROR,
+                            message: "Undefined variable in navigation.yaml".to_string(),
+                            description: format!("{} in \"{}\".", error, text),
+                            file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
+                            position: position.clone(),
+                            owner: None,
+                            suggestions: vec![],
+                        };
+
+                        if !self.undefined.contains(&error) {
+                            self.undefined.push(error);
+                        }
+                    }
+                }
+                Err(error) => {
+                    let error = Error {
+                        code: Error::NAVIGATION_ERROR,
+                        message: "Invalid expression in navigation.yaml".to_string(),
+                        description: format!("{} in \"{}\".", error, text),
+                        file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
+                        position,
+                        owner: None,
+                        suggestions: vec![],
+                    };
+
+                    if !self.errors.contains(&error) {
+                        self.errors.push(error);
+                    }
+                }
+            }
+        }
+
+        fn verify_items(&mut self, items: &[ItemDescription], path: &mut Vec<Segment>) {
+            for (index, item) in items.iter().enumerate() {
+                path.push(Segment::Seq { index });
+
+                match item {
+                    ItemDescription::Link {
+                        label: Some(label),
+                        translations,
+                        ..
+                    } => {
+                        let label_path = [
+                            &path[..],
+                            &[Segment::Map {
+                                key: "label".to_owned(),
+                            }],
+                        ]
+                        .concat();
+                        self.verify(label, translations.as_ref(), &label_path);
+                    }
+                    ItemDescription::Subheading {
+                        subheading,
+                        translations,
+                        ..
+                    } => {
+                        let subheading_path = [
+                            &path[..],
+                            &[Segment::Map {
+                                key: "subheading".to_owned(),
+                            }],
+                        ]
+                        .concat();
+                        self.verify(subheading, translations.as_ref(), &subheading_path);
+                    }
+                    _ => {}
+                }
+
+                if let Some(children) = item.items() {
+                    path.push(Segment::Map {
+                        key: "items".to_owned(),
+                    });
+                    self.verify_items(children, path);
+                    path.pop();
+                }
+
+                path.pop();
+            }
+        }
+    }
+
+    let mut labels = Labels {
+        input,
+        project,
+        errors: vec![],
+        undefined: vec![],
+    };
+
+    for (index, section) in sections.iter().enumerate() {
+        if let Some(heading) = &section.heading {
+            let path = [
+                Segment::Seq { index },
+                Segment::Map {
+                    key: "heading".to_owned(),
+                },
+            ];
+            labels.verify(heading, section.translations.as_ref(), &path);
+        }
+
+        if let Some(items) = &section.items {
+            let mut path = vec![
+                Segment::Seq { index },
+                Segment::Map {
+                    key: "items".to_owned(),
+                },
+            ];
+
+            labels.verify_items(items, &mut path);
+        }
+    }
+
+    (labels.errors, labels.undefined)
+}
+
+fn is_badge_color(color: &str, project: &Project) -> bool {
+    BADGE_COLORS.contains(&color)
+        || project
+            .settings()
+            .theme()
+            .is_some_and(|theme| theme.colors.has_token(color))
+}
+
+fn verify_translations(
+    translations: Option<&HashMap<String, String>>,
+    project: &Project,
+    errors: &mut Vec<Error>,
+) {
+    let locales = project.settings().locales();
+
+    for locale in translations.iter().flat_map(|t| t.keys()) {
+        if !locales.contains(locale) {
+            errors.push(Error {
+                code: Error::NAVIGATION_ERROR,
+                message: "Unknown locale in navigation translations".to_string(),
+                description: format!(
+                    "Expected one of [{}].\nFound \"{}\".",
+                    locales
+                        .iter()
+                        .map(|l| format!("\"{}\"", l))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    locale
+                ),
+                file: Some(PathBuf::from(crate::NAVIGATION_FILE_NAME)),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{InputContent, InputFile, RenderOptions};
@@ -816,6 +1550,67 @@ mod test {
         );
     }
 
+    #[test]
+    fn description_with_shared_items() {
+        let nav = indoc! {r#"
+        - heading: "Guides"
+          items: &common
+            - label: "Support"
+              href: "/support"
+            - label: "Status"
+              external: "https://status.example.com"
+        - heading: "Reference"
+          items: *common
+        "#};
+
+        let sections = parse_description(nav).unwrap();
+
+        assert_eq!(sections[1].heading.as_deref(), Some("Reference"));
+        assert_eq!(sections[1].items, sections[0].items);
+        assert_eq!(sections[1].items.as_ref().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn description_with_merged_items() {
+        let nav = indoc! {r#"
+        - heading: "Guides"
+          items:
+            - &setup
+              label: "Setup"
+              href: "/setup"
+              collapsed: true
+            - <<: *setup
+              label: "Setup again"
+        "#};
+
+        let sections = parse_description(nav).unwrap();
+        let items = sections[0].items.as_ref().unwrap();
+
+        assert_eq!(items[1].label(), Some("Setup again"));
+        assert_eq!(items[1].href(), Some("/setup"));
+    }
+
+    #[test]
+    fn description_errors_point_to_the_alias() {
+        let nav = indoc! {r#"
+        - &guides
+          heading: "Guides"
+          items:
+            - href: "/guides"
+        - heading: "More"
+          items: *guides
+        "#};
+
+        let error = parse_description(nav).unwrap_err();
+        let position = error.position.unwrap();
+
+        assert_eq!((position.start.row, position.start.col), (6, 10));
+        assert_eq!(
+            error.description,
+            ".[1].items: invalid type: map, expected a sequence at line 6 column 10"
+        );
+    }
+
     #[test]
     fn description_without_heading() {
         let nav = indoc! {r#"
@@ -927,6 +1722,161 @@ mod test {
         assert!(sections[0].items[0].items().unwrap()[1].is_link());
     }
 
+    #[test]
+    fn links_without_a_label_use_the_page_title() {
+        let nav = indoc! {r#"
+        - heading: "Guides"
+          items:
+            - href: "/guides/install.md"
+            - href: "/guides/usage.md"
+            - href: "/guides/missing.md"
+            - label: "Configuring"
+              href: "/guides/config.md"
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(
+            "guides/install.md",
+            "---\ntitle: Installing\n---\n# Install",
+        );
+        builder.with_file("guides/usage.md", "Intro\n\n# Using the CLI");
+        builder.with_file("guides/config.md", "# Config");
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        let project = builder.build().unwrap();
+
+        let sections = build(nav, &RenderContext::new(), &project).unwrap();
+        let labels = sections[0]
+            .items
+            .iter()
+            .map(|i| i.label())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            labels,
+            vec![
+                "Installing",
+                "Using the CLI",
+                "/guides/missing.md",
+                "Configuring"
+            ]
+        );
+    }
+
+    #[test]
+    fn external_links_need_a_label() {
+        let nav = indoc! {r#"
+        - heading: "Guides"
+          items:
+            - external: "https://example.com"
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        let project = builder.build().unwrap();
+
+        let errors = verify(nav, &project);
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(
+            errors[0].message,
+            "Missing label for external link in navigation.yaml"
+        );
+    }
+
+    #[test]
+    fn uses_translated_labels_for_locale() {
+        let nav = indoc! {r#"
+        - heading: "Guides"
+          translations:
+            fr: "Guides pratiques"
+          items:
+          - subheading: "Getting started"
+            translations:
+              fr: "Premiers pas"
+            items:
+            - label: "Create an account"
+              href: "/guides/create-an-account"
+              translations:
+                fr: "Créer un compte"
+            - label: "First resource"
+              href: "/guides/first-resource"
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(
+            crate::SETTINGS_FILE_NAME,
+            indoc! {"
+            ---
+            title: An Project
+            locales: [en, fr]
+            "},
+        );
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        let project = builder.build().unwrap();
+
+        let opts = RenderOptions {
+            locale: Some("fr".to_string()),
+            ..Default::default()
+        };
+
+        let mut ctx = RenderContext::new();
+        ctx.with_options(&opts);
+
+        let sections = build(nav, &ctx, &project).unwrap();
+
+        assert_eq!(sections[0].heading.as_deref(), Some("Guides pratiques"));
+        assert_eq!(sections[0].items[0].label(), "Premiers pas");
+        assert_eq!(
+            sections[0].items[0].items().unwrap()[0].label(),
+            "Créer un compte"
+        );
+        assert_eq!(
+            sections[0].items[0].items().unwrap()[1].label(),
+            "First resource"
+        );
+
+        let sections = build(nav, &RenderContext::new(), &project).unwrap();
+
+        assert_eq!(sections[0].heading.as_deref(), Some("Guides"));
+        assert_eq!(sections[0].items[0].label(), "Getting started");
+    }
+
+    #[test]
+    fn verify_translations_use_known_locales() {
+        let nav = indoc! {r#"
+        - heading: "Guides"
+          items:
+          - label: "Create an account"
+            href: "/README.md"
+            translations:
+              de: "Konto erstellen"
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(
+            crate::SETTINGS_FILE_NAME,
+            indoc! {"
+            ---
+            title: An Project
+            locales: [en, fr]
+            "},
+        );
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        let project = builder.build().unwrap();
+
+        let errors = verify(nav, &project);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "Unknown locale in navigation translations"
+        );
+        assert_eq!(
+            errors[0].description,
+            "Expected one of [\"en\", \"fr\"].\nFound \"de\"."
+        );
+    }
+
     #[test]
     fn webbifys_internal_urls() {
         let nav = indoc! {r#"
@@ -1389,8 +2339,11 @@ mod test {
                     href: Some("/api".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
                     collapsible: Some(false),
                     collapsed: Some(false),
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: None,
                 },
                 Item::Link {
@@ -1401,6 +2354,9 @@ mod test {
                     href: Some("/api/pets".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![
                         Item::Link {
                             label: "List all pets".to_owned(),
@@ -1408,8 +2364,11 @@ mod test {
                             href: Some("/api/pets#list-all-pets".to_owned()),
                             external_href: None,
                             http_method: Some(HttpMethod::Get),
+                            badge: Some(Badge::for_method(HttpMethod::Get)),
                             collapsible: Some(false),
                             collapsed: Some(false),
+                            depth: 2,
+                            expanded_for_current_page: false,
                             items: None,
                         },
                         Item::Link {
@@ -1418,8 +2377,11 @@ mod test {
                             href: Some("/api/pets#create-a-pet".to_owned()),
                             external_href: None,
                             http_method: Some(HttpMethod::Post),
+                            badge: Some(Badge::for_method(HttpMethod::Post)),
                             collapsible: Some(false),
                             collapsed: Some(false),
+                            depth: 2,
+                            expanded_for_current_page: false,
                             items: None,
                         },
                         Item::Link {
@@ -1428,8 +2390,11 @@ mod test {
                             href: Some("/api/pets#info-for-a-specific-pet".to_owned()),
                             external_href: None,
                             http_method: Some(HttpMethod::Get),
+                            badge: Some(Badge::for_method(HttpMethod::Get)),
                             collapsible: Some(false),
                             collapsed: Some(false),
+                            depth: 2,
+                            expanded_for_current_page: false,
                             items: None,
                         },
                     ]),
@@ -1492,14 +2457,20 @@ mod test {
                     href: Some("/api/puppies".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![Item::Link {
                         label: "Create a pet".to_owned(),
                         title: Some("Create a pet".to_owned()),
                         href: Some("/api/puppies#create-a-pet".to_owned()),
                         external_href: None,
                         http_method: Some(HttpMethod::Post),
+                        badge: Some(Badge::for_method(HttpMethod::Post)),
                         collapsible: Some(false),
                         collapsed: Some(false),
+                        depth: 2,
+                        expanded_for_current_page: false,
                         items: None,
                     },]),
                 },
@@ -1511,14 +2482,20 @@ mod test {
                     href: Some("/api/bunnies".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![Item::Link {
                         label: "Info for a specific pet".to_owned(),
                         title: Some("Info for a specific pet".to_owned()),
                         href: Some("/api/bunnies#info-for-a-specific-pet".to_owned()),
                         external_href: None,
                         http_method: Some(HttpMethod::Get),
+                        badge: Some(Badge::for_method(HttpMethod::Get)),
                         collapsible: Some(false),
                         collapsed: Some(false),
+                        depth: 2,
+                        expanded_for_current_page: false,
                         items: None,
                     },]),
                 }
@@ -1585,14 +2562,20 @@ mod test {
                     href: Some("/api/kittens".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![Item::Link {
                         label: "List all pets".to_owned(),
                         title: Some("List all pets".to_owned()),
                         href: Some("/api/kittens#list-all-pets".to_owned()),
                         http_method: Some(HttpMethod::Get),
+                        badge: Some(Badge::for_method(HttpMethod::Get)),
                         external_href: None,
                         collapsible: Some(false),
                         collapsed: Some(false),
+                        depth: 2,
+                        expanded_for_current_page: false,
                         items: None,
                     },]),
                 },
@@ -1604,14 +2587,20 @@ mod test {
                     href: Some("/api/puppies".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![Item::Link {
                         label: "Create a pet".to_owned(),
                         title: Some("Create a pet".to_owned()),
                         href: Some("/api/puppies#create-a-pet".to_owned()),
                         external_href: None,
                         http_method: Some(HttpMethod::Post),
+                        badge: Some(Badge::for_method(HttpMethod::Post)),
                         collapsible: Some(false),
                         collapsed: Some(false),
+                        depth: 2,
+                        expanded_for_current_page: false,
                         items: None,
                     },]),
                 },
@@ -1623,14 +2612,20 @@ mod test {
                     href: Some("/api/bunnies".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![Item::Link {
                         label: "Info for a specific pet".to_owned(),
                         title: Some("Info for a specific pet".to_owned()),
                         href: Some("/api/bunnies#info-for-a-specific-pet".to_owned()),
                         external_href: None,
                         http_method: Some(HttpMethod::Get),
+                        badge: Some(Badge::for_method(HttpMethod::Get)),
                         collapsible: Some(false),
                         collapsed: Some(false),
+                        depth: 2,
+                        expanded_for_current_page: false,
                         items: None,
                     },]),
                 },
@@ -1696,14 +2691,20 @@ mod test {
                 href: Some("/api/kittens".to_owned()),
                 external_href: None,
                 http_method: None,
+                badge: None,
+                depth: 1,
+                expanded_for_current_page: false,
                 items: Some(vec![Item::Link {
                     label: "List all pets".to_owned(),
                     title: Some("List all pets".to_owned()),
                     href: Some("/api/kittens#list-all-pets".to_owned()),
                     external_href: None,
                     http_method: Some(HttpMethod::Get),
+                    badge: Some(Badge::for_method(HttpMethod::Get)),
                     collapsible: Some(false),
                     collapsed: Some(false),
+                    depth: 2,
+                    expanded_for_current_page: false,
                     items: None,
                 },]),
             }]
@@ -1761,6 +2762,64 @@ mod test {
         assert!(navigation.has_link_to("/api/pets#create-a-pet"));
     }
 
+    #[test]
+    fn openapi_groups_deprecated_operations_with_the_deprecation_banner() {
+        let nav = indoc! {r#"
+        - heading: API
+          items:
+            - open_api_spec: openapi.yaml
+        "#};
+
+        let settings = indoc! {r#"
+        ---
+        title: OpenAPI Example
+        open_api:
+            - spec_file: openapi.yaml
+              uri_prefix: /api
+              deprecation_banner: true
+        "#};
+
+        let spec = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        paths:
+          /trees:
+            get:
+              summary: List trees
+              tags: [trees]
+              deprecated: true
+              responses:
+                "200":
+                  description: OK
+            post:
+              summary: Plant a tree
+              tags: [trees]
+              responses:
+                "200":
+                  description: OK
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        builder.with_file(crate::SETTINGS_FILE_NAME, settings);
+        builder.with_file("openapi.yaml", spec);
+        let project = builder.build().unwrap();
+
+        let navigation = build(nav, &RenderContext::new(), &project).unwrap();
+        let operations = navigation.sections[0].items[1].items().unwrap();
+
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].label(), "Plant a tree");
+        assert!(operations[1].is_subheading());
+        assert_eq!(operations[1].label(), "Deprecated");
+        assert_eq!(
+            operations[1].items().unwrap()[0].href(),
+            Some("/api/trees#list-trees")
+        );
+    }
+
     #[test]
     fn openapi_tag_ordering_with_explicit_tags() {
         let nav = indoc! {r#"
@@ -2018,8 +3077,11 @@ mod test {
                     href: Some("/tab1/api".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
                     collapsible: Some(false),
                     collapsed: Some(false),
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: None,
                 },
                 Item::Link {
@@ -2030,6 +3092,9 @@ mod test {
                     href: Some("/tab1/api/pets".to_owned()),
                     external_href: None,
                     http_method: None,
+                    badge: None,
+                    depth: 1,
+                    expanded_for_current_page: false,
                     items: Some(vec![
                         Item::Link {
                             label: "List all pets".to_owned(),
@@ -2037,8 +3102,11 @@ mod test {
                             href: Some("/tab1/api/pets#list-all-pets".to_owned()),
                             external_href: None,
                             http_method: Some(HttpMethod::Get),
+                            badge: Some(Badge::for_method(HttpMethod::Get)),
                             collapsible: Some(false),
                             collapsed: Some(false),
+                            depth: 2,
+                            expanded_for_current_page: false,
                             items: None,
                         },
                         Item::Link {
@@ -2047,8 +3115,11 @@ mod test {
                             href: Some("/tab1/api/pets#create-a-pet".to_owned()),
                             external_href: None,
                             http_method: Some(HttpMethod::Post),
+                            badge: Some(Badge::for_method(HttpMethod::Post)),
                             collapsible: Some(false),
                             collapsed: Some(false),
+                            depth: 2,
+                            expanded_for_current_page: false,
                             items: None,
                         },
                         Item::Link {
@@ -2057,8 +3128,11 @@ mod test {
                             href: Some("/tab1/api/pets#info-for-a-specific-pet".to_owned()),
                             external_href: None,
                             http_method: Some(HttpMethod::Get),
+                            badge: Some(Badge::for_method(HttpMethod::Get)),
                             collapsible: Some(false),
                             collapsed: Some(false),
+                            depth: 2,
+                            expanded_for_current_page: false,
                             items: None,
                         },
                     ]),
@@ -2203,4 +3277,202 @@ mod test {
         );
         assert_eq!(links.len(), 2);
     }
+
+    const NESTED: &str = indoc! {r#"
+    - heading: Guides
+      items:
+        - href: /one.md
+          items:
+            - subheading: Two
+              items:
+                - href: /three.md
+                  items:
+                    - href: /four.md
+        - href: /other.md
+          collapsed: false
+          items:
+            - href: /one.md
+    "#};
+
+    fn nested_project(settings: &str) -> Project {
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(crate::NAVIGATION_FILE_NAME, NESTED);
+        builder.with_file(crate::SETTINGS_FILE_NAME, settings);
+        for path in ["one.md", "three.md", "four.md", "other.md"] {
+            builder.with_file(path, format!("# {}", path));
+        }
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn nested_items_keep_their_depth() {
+        let project = nested_project("---\ntitle: Nested\n");
+        let nav = project.root_navigation(None).unwrap();
+
+        let one = &nav.sections[0].items[0];
+        let two = &one.items().unwrap()[0];
+        let three = &two.items().unwrap()[0];
+        let four = &three.items().unwrap()[0];
+
+        assert_eq!(
+            [one, two, three, four].map(|i| (i.label(), i.depth())),
+            [("one.md", 1), ("Two", 2), ("three.md", 3), ("four.md", 4)]
+        );
+        assert_eq!(one.collapsed(), Some(false));
+        assert_eq!(one.collapsible(), Some(false));
+    }
+
+    #[test]
+    fn groups_can_start_collapsed_by_default() {
+        let project = nested_project(indoc! {r#"
+        ---
+        title: Nested
+        navigation:
+          collapsed: true
+        "#});
+        let nav = project.root_navigation(None).unwrap();
+
+        let section = &nav.sections[0];
+        let one = &section.items[0];
+        let four = &one.items().unwrap()[0].items().unwrap()[0].items().unwrap()[0];
+        let other = &section.items[1];
+
+        assert!(section.collapsed && section.collapsible);
+        assert_eq!(
+            (one.collapsed(), one.collapsible()),
+            (Some(true), Some(true))
+        );
+        // Items without nested items have nothing to collapse
+        assert_eq!(four.collapsed(), Some(false));
+        // Set explicitly in the navigation
+        assert_eq!(other.collapsed(), Some(false));
+    }
+
+    #[test]
+    fn marks_the_groups_leading_to_the_current_page() {
+        let project = nested_project("---\ntitle: Nested\n");
+        let nav = project.navigation_for_page("/three", None).unwrap();
+
+        let section = &nav.sections[0];
+        let one = &section.items[0];
+        let two = &one.items().unwrap()[0];
+        let three = &two.items().unwrap()[0];
+        let four = &three.items().unwrap()[0];
+
+        assert!(section.expanded_for_current_page);
+        assert_eq!(
+            [one, two, three, four, &section.items[1]].map(|i| i.expanded_for_current_page()),
+            [true, true, true, false, false]
+        );
+
+        let nav = project.root_navigation(None).unwrap();
+        assert!(!nav.sections[0].expanded_for_current_page);
+    }
+
+    #[test]
+    fn warns_about_items_nested_too_deeply() {
+        let nav = indoc! {r#"
+        - heading: Guides
+          items:
+            - subheading: One
+              items:
+                - subheading: Two
+                  items:
+                    - subheading: Three
+                      items:
+                        - subheading: Four
+                          items:
+                            - subheading: Five
+                              items:
+                                - subheading: Six
+                                  items:
+                                    - subheading: Seven
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        let project = builder.build().unwrap();
+
+        let warnings = project
+            .warnings()
+            .into_iter()
+            .filter(|w| w.code == Error::NAVIGATION_ERROR)
+            .collect::<Vec<_>>();
+
+        assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+        assert_eq!(
+            warnings[0].message,
+            r#"Navigation item "Six" is nested too deeply"#
+        );
+        assert_eq!(
+            warnings[0].file,
+            Some(PathBuf::from(crate::NAVIGATION_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn links_carry_their_badge() {
+        let nav = indoc! {r#"
+        - heading: Guides
+          items:
+            - label: Home
+              href: /
+              badge:
+                text: Beta
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        let project = builder.build().unwrap();
+
+        let navigation = project.root_navigation(None).unwrap();
+
+        assert_eq!(
+            navigation.sections[0].items[0].badge(),
+            Some(&Badge {
+                text: "Beta".to_string(),
+                color: "gray".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn verifies_badge_colors_against_the_theme() {
+        let settings = indoc! {r##"
+        ---
+        title: Example
+        theme:
+          colors:
+            tokens:
+              beta: "#30A46C"
+        "##};
+
+        let nav = indoc! {r#"
+        - heading: Guides
+          items:
+            - label: Home
+              href: /
+              badge:
+                text: Beta
+                color: beta
+              items:
+                - label: Nested
+                  href: /
+                  badge:
+                    text: New
+                    color: pink
+        "#};
+
+        let mut builder = ProjectBuilder::default();
+        builder.with_file(crate::NAVIGATION_FILE_NAME, nav);
+        builder.with_file(crate::SETTINGS_FILE_NAME, settings);
+        let project = builder.build().unwrap();
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].message, "Unknown badge color in navigation.yaml");
+        let position = errors[0].position.as_ref().unwrap();
+        assert_eq!((position.start.row, position.start.col), (13, 13));
+    }
 }
@@ -1,16 +1,57 @@
 pub mod ast;
+pub(crate) mod augmentation;
+pub(crate) mod default_responses;
+pub mod diff;
+pub mod form;
 pub mod model;
 pub mod overview;
+pub(crate) mod serialization;
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 
+use indexmap::IndexMap;
+
+use augmentation::Augmentation;
+use diff::ApiDiff;
 use model::Components;
+use model::DoctaveExtension;
 use model::Operation;
 use model::Page;
 use model::Tag;
+use overview::OverviewTemplate;
+
+use located_yaml::{Yaml, YamlElt, YamlLoader};
 
+use crate::markdown_page::PageTemplate;
 use crate::page_kind::PageKind;
+use crate::settings::{DefaultResponse, UriStrategy};
 use crate::slug;
+use crate::{Point, Position};
+
+/// The operationId of an operation. See [`OpenApi::operation_ids`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct OperationId {
+    /// Where the operation is in the spec, e.g. `GET /users/{id}`
+    pub location: String,
+    pub id: String,
+    /// Whether the spec didn't define an operationId for the operation
+    pub generated: bool,
+}
+
+/// Where an operation is shown. See
+/// [`Project::operation_uris`](crate::Project::operation_uris).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OperationUri {
+    /// The operation's method and path, e.g. `GET /pets`
+    pub location: String,
+    /// The page and anchor of the operation, e.g. `/api/pets#listPets`
+    pub uri: String,
+    /// The URI from the operation's summary, when the operation's URI comes
+    /// from its operationId and the two differ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_uri: Option<String>,
+}
 
 #[derive(Clone)]
 /// Represents currently a v3 openapi spec. Can be used to generate pages based on
@@ -18,167 +59,154 @@ use crate::slug;
 pub(crate) struct OpenApi {}
 
 impl OpenApi {
+    /// Compares two versions of a spec, e.g. to generate an API changelog.
+    pub fn diff(old: &openapi_parser::OpenAPI, new: &openapi_parser::OpenAPI) -> ApiDiff {
+        ApiDiff::between(old, new)
+    }
+
     pub fn pages_from_parsed_spec(
         spec: &openapi_parser::OpenAPI,
         source: PathBuf,
         uri_path: String,
+        overview_template: Option<OverviewTemplate>,
+        augmentations: &[Augmentation],
+        uri_strategy: UriStrategy,
+        default_responses: &BTreeMap<String, DefaultResponse>,
     ) -> crate::Result<Vec<PageKind>> {
         let mut tag_pages = vec![];
         let mut pages = vec![];
 
-        let security_schemes = spec.components.as_ref().map(|c| c.security_schemes.clone());
+        let defaults = default_responses::responses(spec, default_responses)?;
+
+        let anchors = match uri_strategy {
+            UriStrategy::Summary => HashMap::new(),
+            UriStrategy::OperationId => Self::operation_id_anchors(spec),
+        };
 
-        let mut all_tags = vec![];
+        let security_schemes = spec.components.as_ref().map(|c| c.security_schemes.clone());
 
-        all_tags.extend(&spec.tags);
+        let mut all_tags = spec.tags.clone();
 
+        // Operations can also be listed under tags that are only named in
+        // the operation itself, or in its `x-doctave.group`
         let inline_tags = spec
             .operations()
             .iter()
-            .flat_map(|op| {
-                op.tags
-                    .iter()
-                    .map(|t| openapi_parser::Tag {
-                        name: t.to_owned(),
-                        description: None,
-                        external_docs: None,
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+            .flat_map(
+                |op| match DoctaveExtension::from_parsed(&op.extensions).group {
+                    Some(group) => vec![group.into()],
+                    None => op.tags.clone(),
+                },
+            )
+            .collect::<Vec<openapi_parser::String>>();
 
-        for inline_tag in &inline_tags {
-            if !all_tags.iter().any(|t| t.name == inline_tag.name) {
-                all_tags.push(inline_tag);
+        for inline_tag in inline_tags {
+            if !all_tags.iter().any(|t| t.name == inline_tag) {
+                all_tags.push(openapi_parser::Tag::from(inline_tag));
             }
         }
 
         for tag in all_tags {
+            let tag_ext = DoctaveExtension::from_parsed(&tag.extensions);
+
+            if tag_ext.hide {
+                continue;
+            }
+
             let mut tag_operations = vec![];
 
             for (pattern, path_item) in spec.paths.iter() {
-                if let Some(get) = &path_item.get {
-                    if get.tags.iter().any(|t| &tag.name == t) {
-                        let desc = get.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            get.clone(),
-                            "get".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
-                }
-
-                if let Some(post) = &path_item.post {
-                    if post.tags.iter().any(|t| &tag.name == t) {
-                        let desc = post.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            post.clone(),
-                            "post".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
-                }
+                let methods = [
+                    ("get", &path_item.get),
+                    ("post", &path_item.post),
+                    ("put", &path_item.put),
+                    ("delete", &path_item.delete),
+                    ("patch", &path_item.patch),
+                    ("head", &path_item.head),
+                    ("options", &path_item.options),
+                    ("trace", &path_item.trace),
+                ];
 
-                if let Some(put) = &path_item.put {
-                    if put.tags.iter().any(|t| &tag.name == t) {
-                        let desc = put.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            put.clone(),
-                            "put".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
-                }
+                for (method, operation) in methods {
+                    let Some(operation) = operation else {
+                        continue;
+                    };
 
-                if let Some(delete) = &path_item.delete {
-                    if delete.tags.iter().any(|t| &tag.name == t) {
-                        let desc = delete.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            delete.clone(),
-                            "delete".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
-                }
+                    let ext = DoctaveExtension::from_parsed(&operation.extensions);
 
-                if let Some(patch) = &path_item.patch {
-                    if patch.tags.iter().any(|t| &tag.name == t) {
-                        let desc = patch.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            patch.clone(),
-                            "patch".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
+                    if ext.hide || !ext.lists_under(operation, &tag.name) {
+                        continue;
                     }
-                }
 
-                if let Some(head) = &path_item.head {
-                    if head.tags.iter().any(|t| &tag.name == t) {
-                        let desc = head.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            head.clone(),
-                            "head".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
-                }
+                    let mut operation = operation.clone();
+                    ext.apply(&mut operation);
 
-                if let Some(options) = &path_item.options {
-                    if options.tags.iter().any(|t| &tag.name == t) {
-                        let desc = options.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            options.clone(),
-                            "options".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
-                }
+                    let desc = operation.description.as_ref().map(|v| v.to_string());
+                    let mut operation = Operation::from_parsed(
+                        operation,
+                        method.to_string(),
+                        pattern.to_string(),
+                        desc,
+                        &security_schemes,
+                        &spec.security,
+                    )?;
+                    operation.augmentation = augmentation_for(&operation, augmentations);
+                    default_responses::merge(&mut operation, &defaults);
+                    use_anchor(
+                        &mut operation,
+                        &anchors,
+                        &format!("{} {}", method.to_uppercase(), pattern),
+                    );
 
-                if let Some(trace) = &path_item.trace {
-                    if trace.tags.iter().any(|t| &tag.name == t) {
-                        let desc = trace.description.as_ref().map(|v| v.to_string());
-                        tag_operations.push(Operation::from_parsed(
-                            trace.clone(),
-                            "trace".to_string(),
-                            pattern.to_string(),
-                            desc,
-                            &security_schemes,
-                        )?);
-                    }
+                    tag_operations.push((ext.order, operation));
                 }
             }
-            tag_pages.push(Page {
-                tag: Tag {
-                    name: tag.name.to_string(),
-                    description: tag.description.as_ref().map(|d| d.to_string()),
+
+            tag_operations.sort_by_key(|(order, _)| order.unwrap_or(i64::MAX));
+
+            tag_pages.push((
+                tag_ext.order,
+                Page {
+                    tag: Tag {
+                        name: tag.name.to_string(),
+                        description: tag.description.as_ref().map(|d| d.to_string()),
+                    },
+                    operations: tag_operations.into_iter().map(|(_, op)| op).collect(),
+                    uri_path: format!(
+                        "/{}/{}",
+                        uri_path.strip_prefix('/').unwrap_or(&uri_path),
+                        slug::slugify(tag.name.strip_prefix('/').unwrap_or(&tag.name))
+                    ),
+                    fs_path: source.clone(),
                 },
-                operations: tag_operations,
-                uri_path: format!(
-                    "/{}/{}",
-                    uri_path.strip_prefix('/').unwrap_or(&uri_path),
-                    slug::slugify(tag.name.strip_prefix('/').unwrap_or(&tag.name))
-                ),
-                fs_path: source.clone(),
-            });
+            ));
         }
 
+        tag_pages.sort_by_key(|(order, _)| order.unwrap_or(i64::MAX));
+
+        let mut tag_pages = tag_pages
+            .into_iter()
+            .map(|(_, page)| page)
+            .collect::<Vec<_>>();
+
         for webhook in &spec.webhooks {
-            let webhook_op =
-                Operation::from_parsed_webhook(webhook.operation.clone(), &security_schemes)?;
+            let ext = DoctaveExtension::from_parsed(&webhook.operation.extensions);
+
+            if ext.hide {
+                continue;
+            }
+
+            let mut operation = webhook.operation.clone();
+            ext.apply(&mut operation);
+
+            let mut webhook_op =
+                Operation::from_parsed_webhook(operation, &security_schemes, &spec.security)?;
+            webhook_op.augmentation = augmentation_for(&webhook_op, augmentations);
+            use_anchor(
+                &mut webhook_op,
+                &anchors,
+                &format!(r#"webhook "{}""#, webhook.name),
+            );
 
             for page in &mut tag_pages {
                 if webhook_op.tags.iter().any(|t| &page.tag.name == t) {
@@ -187,6 +215,21 @@ impl OpenApi {
             }
         }
 
+        for page in &mut tag_pages {
+            drop_clashing_previous_anchors(&mut page.operations);
+        }
+
+        // Resolve the template variables before the tag pages are consumed
+        let template = overview_template.map(|t| {
+            (
+                t.content,
+                PageTemplate {
+                    path: t.path,
+                    globals: overview::template_globals(spec, &tag_pages),
+                },
+            )
+        });
+
         for model in tag_pages {
             if model.operations.is_empty() {
                 continue;
@@ -194,20 +237,23 @@ impl OpenApi {
             pages.push(PageKind::OpenApi(crate::OpenApiPage::new(model)));
         }
 
-        let overview_page_markdown = overview::build_parsed(
-            &spec.info,
-            spec.servers.as_slice(),
-            spec.external_docs.as_ref(),
-        );
-
         // Create a synthetic path that matches the URI structure for proper out_path() generation
         let synthetic_path = PathBuf::from(format!(
             "{}/README.md",
             uri_path.strip_prefix('/').unwrap_or(&uri_path)
         ));
 
-        let mut overview =
-            crate::MarkdownPage::new(&synthetic_path, overview_page_markdown.into_bytes());
+        let mut overview = if let Some((content, template)) = template {
+            crate::MarkdownPage::from_template(&synthetic_path, content, template)
+        } else {
+            let overview_page_markdown = overview::build_parsed(
+                &spec.info,
+                spec.servers.as_slice(),
+                spec.external_docs.as_ref(),
+            );
+
+            crate::MarkdownPage::new(&synthetic_path, overview_page_markdown.into_bytes())
+        };
         // Override the URI path
         overview.uri_path = uri_path;
         pages.push(PageKind::Markdown(overview));
@@ -215,10 +261,184 @@ impl OpenApi {
         Ok(pages)
     }
 
-    pub fn components_parsed(spec: &mut openapi_parser::OpenAPI) -> crate::Result<Components> {
+    /// Validates the examples in the spec against their schemas, reporting
+    /// each mismatch as an error pointing to the example.
+    pub fn example_errors(
+        spec: &openapi_parser::OpenAPI,
+        spec_file: &std::path::Path,
+        source: &str,
+    ) -> Vec<crate::Error> {
+        let errors = openapi_parser::validate_examples(spec);
+        if errors.is_empty() {
+            return vec![];
+        }
+
+        // Only load the positions when there's something to report
+        let located = YamlLoader::load_from_str(source)
+            .ok()
+            .and_then(|mut loader| loader.docs.drain(..).next());
+
+        errors
+            .into_iter()
+            .map(|e| crate::Error {
+                code: crate::Error::INVALID_OPENAPI_SPEC,
+                message: "Example does not match its schema".to_owned(),
+                description: format!("{}\n\nExample at \"#{}\".", e.reason, e.pointer),
+                file: Some(spec_file.to_path_buf()),
+                position: located
+                    .as_ref()
+                    .and_then(|root| locate_pointer(root, &e.pointer)),
+                owner: None,
+                suggestions: vec![],
+            })
+            .collect()
+    }
+
+    /// The operationId of every operation in the spec, in the order they
+    /// appear in. Operations without one get an ID generated from their
+    /// method and path, e.g. `get-users-id`, with a numeric suffix if that
+    /// would clash with another operation's ID.
+    pub fn operation_ids(spec: &openapi_parser::OpenAPI) -> Vec<OperationId> {
+        let operations = spec
+            .operations()
+            .into_iter()
+            .map(|op| {
+                (
+                    format!("{} {}", op.method.to_uppercase(), op.route_pattern),
+                    format!("{} {}", op.method.to_lowercase(), op.route_pattern),
+                    op,
+                )
+            })
+            .chain(spec.webhooks.iter().map(|w| {
+                (
+                    format!(r#"webhook "{}""#, w.name),
+                    format!("webhook {}", w.name),
+                    &w.operation,
+                )
+            }))
+            .collect::<Vec<_>>();
+
+        let mut taken = operations
+            .iter()
+            .filter_map(|(_, _, op)| op.operation_id.as_ref().map(|id| id.to_string()))
+            .collect::<HashSet<_>>();
+
+        operations
+            .into_iter()
+            .map(|(location, fallback, op)| match &op.operation_id {
+                Some(id) => OperationId {
+                    location,
+                    id: id.to_string(),
+                    generated: false,
+                },
+                None => {
+                    let base = slug::slugify(&fallback);
+                    let mut id = base.clone();
+                    let mut suffix = 2;
+
+                    while taken.contains(&id) {
+                        id = format!("{}-{}", base, suffix);
+                        suffix += 1;
+                    }
+                    taken.insert(id.clone());
+
+                    OperationId {
+                        location,
+                        id,
+                        generated: true,
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// The anchors of operations with `uri_strategy: operation_id`, keyed by
+    /// where the operations are in the spec. Anchors are slugs of the
+    /// operationIds, with a numeric suffix if two operationIds would give
+    /// the same slug.
+    pub(crate) fn operation_id_anchors(spec: &openapi_parser::OpenAPI) -> HashMap<String, String> {
+        let mut taken = HashSet::new();
+
+        Self::operation_ids(spec)
+            .into_iter()
+            .map(|operation_id| {
+                let base = slug::slugify(&operation_id.id);
+                let mut anchor = base.clone();
+                let mut suffix = 2;
+
+                while !taken.insert(anchor.clone()) {
+                    anchor = format!("{}-{}", base, suffix);
+                    suffix += 1;
+                }
+
+                (operation_id.location, anchor)
+            })
+            .collect()
+    }
+
+    /// Reports operationIds that are used by more than one operation, as
+    /// their pages and anchors would collide.
+    pub fn duplicate_operation_id_errors(
+        spec: &openapi_parser::OpenAPI,
+        spec_file: &std::path::Path,
+    ) -> Vec<crate::Error> {
+        let mut locations: IndexMap<String, Vec<String>> = IndexMap::new();
+
+        for operation_id in Self::operation_ids(spec) {
+            if !operation_id.generated {
+                locations
+                    .entry(operation_id.id)
+                    .or_default()
+                    .push(operation_id.location);
+            }
+        }
+
+        locations
+            .into_iter()
+            .filter(|(_, locations)| locations.len() > 1)
+            .map(|(id, locations)| crate::Error {
+                code: crate::Error::INVALID_OPENAPI_SPEC,
+                message: format!(r#"Duplicate operationId "{}""#, id),
+                description: format!(
+                    "The operationId is used by {}. Each operation needs a unique operationId.",
+                    list_locations(&locations)
+                ),
+                file: Some(spec_file.to_path_buf()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            })
+            .collect()
+    }
+
+    /// Warns about operations without an operationId, including the ID that
+    /// was generated for them instead.
+    pub fn missing_operation_id_warnings(
+        spec: &openapi_parser::OpenAPI,
+        spec_file: &std::path::Path,
+    ) -> Vec<crate::Error> {
+        Self::operation_ids(spec)
+            .into_iter()
+            .filter(|operation_id| operation_id.generated)
+            .map(|operation_id| crate::Error {
+                code: crate::Error::INVALID_OPENAPI_SPEC,
+                message: format!("Missing operationId for {}", operation_id.location),
+                description: format!(
+                    r#"The operation was given the generated operationId "{}" instead. Add an operationId to the operation to keep its links stable when the spec changes."#,
+                    operation_id.id
+                ),
+                file: Some(spec_file.to_path_buf()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            })
+            .collect()
+    }
+
+    pub fn components_parsed(spec: &openapi_parser::OpenAPI) -> crate::Result<Components> {
         let components = spec
             .components
-            .take()
+            .clone()
             .map(Components::from_parsed)
             .transpose()?
             .unwrap_or_default();
@@ -226,3 +446,122 @@ impl OpenApi {
         Ok(components)
     }
 }
+
+/// Finds where a JSON pointer points to in the source of a spec. Follows local
+/// `$ref`s, since the pointers are to the resolved spec. Points to the key of
+/// the value when there is one, as that's what readers look for.
+pub(crate) fn locate_pointer(root: &Yaml, pointer: &str) -> Option<Position> {
+    let mut tokens = pointer_tokens(pointer);
+    let mut current = root;
+    let mut marker = root.marker;
+    let mut followed_refs = 0;
+
+    while !tokens.is_empty() {
+        match &current.yaml {
+            YamlElt::Hash(hash) => {
+                if let Some(reference) = hash.iter().find_map(|(k, v)| match (&k.yaml, &v.yaml) {
+                    (YamlElt::String(k), YamlElt::String(v)) if k == "$ref" => Some(v.as_str()),
+                    _ => None,
+                }) {
+                    // Guard against reference cycles
+                    followed_refs += 1;
+                    if followed_refs > 32 {
+                        return None;
+                    }
+
+                    let mut resolved = pointer_tokens(reference.strip_prefix('#')?);
+                    resolved.extend(tokens);
+                    tokens = resolved;
+                    current = root;
+                    continue;
+                }
+
+                let (key, value) = hash
+                    .iter()
+                    .find(|(k, _)| matches!(&k.yaml, YamlElt::String(k) if *k == tokens[0]))?;
+
+                marker = key.marker;
+                current = value;
+            }
+            YamlElt::Array(items) => {
+                current = items.get(tokens[0].parse::<usize>().ok()?)?;
+                marker = current.marker;
+            }
+            _ => return None,
+        }
+
+        tokens.remove(0);
+    }
+
+    let point = Point {
+        row: marker.line,
+        col: marker.col + 1,
+        byte_offset: marker.index,
+    };
+
+    Some(Position {
+        start: point.clone(),
+        end: point,
+    })
+}
+
+/// Lists the locations like "GET /a, POST /b and PUT /c".
+fn list_locations(locations: &[String]) -> String {
+    match locations {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+/// Switches the operation to the anchor of its operationId, if there is one,
+/// keeping the anchor of its summary so that old links to it still work.
+fn use_anchor(operation: &mut Operation, anchors: &HashMap<String, String>, location: &str) {
+    let Some(anchor) = anchors.get(location) else {
+        return;
+    };
+
+    if *anchor != operation.anchor_tag {
+        let previous = std::mem::replace(&mut operation.anchor_tag, anchor.clone());
+        operation.previous_anchor_tag = Some(previous);
+    }
+}
+
+/// Drops the previous anchors that another operation on the same page now
+/// uses, e.g. when one operation's summary is "List pets" and another's
+/// operationId is `list-pets`, so that no two elements share an ID. Links
+/// to the old anchor reach the operation that uses it now.
+fn drop_clashing_previous_anchors(operations: &mut [Operation]) {
+    let current = operations
+        .iter()
+        .map(|op| op.anchor_tag.clone())
+        .collect::<HashSet<_>>();
+
+    for operation in operations {
+        if operation
+            .previous_anchor_tag
+            .as_ref()
+            .is_some_and(|previous| current.contains(previous))
+        {
+            operation.previous_anchor_tag = None;
+        }
+    }
+}
+
+/// The augmentation named after the operation's operationId, if any.
+fn augmentation_for(operation: &Operation, augmentations: &[Augmentation]) -> Option<Augmentation> {
+    let id = operation.operation_id.as_ref()?;
+
+    augmentations
+        .iter()
+        .find(|a| &a.operation_id == id)
+        .cloned()
+}
+
+fn pointer_tokens(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|token| token.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
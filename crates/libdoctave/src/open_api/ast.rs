@@ -10,10 +10,12 @@ use crate::{
     primitive_components::{OPENAPI_PATH_KEY, TITLE_KEY},
     render_context::RenderContext,
     renderable_ast::Node,
+    settings::DEFAULT_SUNSET_FIELD,
     shared_ast::Position,
     Result,
 };
 
+use super::form::FormField;
 use super::model::Schema as SchemaModel;
 
 #[derive(Debug, Error)]
@@ -111,6 +113,26 @@ pub struct PageAst {
     pub tag: Tag,
     pub operations: Vec<OperationAst>,
     pub download_url: Option<String>,
+    /// Set when the spec has `deprecation_banner` turned on, and some of the
+    /// operations on the page are deprecated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecation_banner: Option<DeprecationBanner>,
+}
+
+/// Shown at the top of a page, listing its deprecated operations.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecationBanner {
+    pub operations: Vec<DeprecatedOperation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeprecatedOperation {
+    pub summary: Option<String>,
+    pub method: String,
+    pub route_pattern: String,
+    pub anchor_tag: String,
+    /// When the operation will be removed, from the spec's sunset extension
+    pub sunset: Option<String>,
 }
 
 impl PageAst {
@@ -125,12 +147,37 @@ impl PageAst {
         };
 
         let download_url = page.spec_download_link(ctx);
+        let location = SchemaLocation::root(
+            page.fs_path.to_string_lossy().to_string(),
+            ctx.settings.max_schema_depth(&page.fs_path),
+        );
+
+        let spec = ctx.settings.open_api_spec(&page.fs_path);
+        let sunset_field = spec.map_or(DEFAULT_SUNSET_FIELD, |s| s.sunset_field());
 
         let mut operations = vec![];
         for op in &page.operations {
-            operations.push(OperationAst::from_model(op, ctx)?);
+            operations.push(OperationAst::from_model(op, ctx, &location, sunset_field)?);
         }
 
+        let deprecated = operations
+            .iter()
+            .filter(|op| op.deprecated)
+            .map(|op| DeprecatedOperation {
+                summary: op.summary.clone(),
+                method: op.method.clone(),
+                route_pattern: op.route_pattern.clone(),
+                anchor_tag: op.anchor_tag.clone(),
+                sunset: op.sunset.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let deprecation_banner = (spec.is_some_and(|s| s.deprecation_banner)
+            && !deprecated.is_empty())
+        .then_some(DeprecationBanner {
+            operations: deprecated,
+        });
+
         Ok(PageAst {
             tag: Tag {
                 name: page.tag.name.to_owned(),
@@ -138,6 +185,7 @@ impl PageAst {
             },
             operations,
             download_url,
+            deprecation_banner,
         })
     }
 }
@@ -147,7 +195,15 @@ pub struct OperationAst {
     pub description_ast: Option<Node>,
     pub method: String,
     pub anchor_tag: String,
+    /// The anchor the operation had before the spec switched to
+    /// `uri_strategy: operation_id`, for links to it to keep working
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_anchor_tag: Option<String>,
     pub route_pattern: String,
+    pub deprecated: bool,
+    /// When the operation will be removed, from the spec's sunset extension,
+    /// e.g. `x-sunset: 2025-06-30`
+    pub sunset: Option<String>,
     pub server_route_patterns: Vec<String>, // has server url prepended
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub header_params: Vec<ParameterAst>,
@@ -163,6 +219,12 @@ pub struct OperationAst {
     pub responses: Vec<StatusAst>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub security_requirements: Vec<SecurityRequirementAst>,
+    /// The alternative ways to authorize the operation, for sending requests
+    /// to it. Empty if the operation needs no authorization.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub auth: Vec<AuthAst>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub servers: Vec<ServerAst>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     /// These are code examples provided by `x-doctave > code-samples`
     pub request_examples: Vec<ExampleAst>,
@@ -172,18 +234,26 @@ impl OperationAst {
     pub(crate) fn from_model(
         operation: &super::model::Operation,
         ctx: &crate::render_context::RenderContext,
+        location: &SchemaLocation,
+        sunset_field: &str,
     ) -> Result<Self> {
-        let description_ast = operation
+        let location = location.join("operations").join(&operation.anchor_tag);
+
+        let mut description_ast = operation
             .description
             .as_ref()
             .and_then(|description| ast_for_openapi(description, ctx).ok());
 
+        if let Some(augmentation) = &operation.augmentation {
+            description_ast = Some(augmentation.apply(description_ast, ctx)?);
+        }
+
         let mut header_params = vec![];
         for param in &operation.header_parameters {
             header_params.push(ParameterAst::from_model(
                 param,
                 ctx,
-                &operation.identifier(),
+                &location.join("parameters").join("header"),
             )?);
         }
 
@@ -192,7 +262,7 @@ impl OperationAst {
             query_params.push(ParameterAst::from_model(
                 param,
                 ctx,
-                &operation.identifier(),
+                &location.join("parameters").join("query"),
             )?);
         }
 
@@ -201,7 +271,7 @@ impl OperationAst {
             path_params.push(ParameterAst::from_model(
                 param,
                 ctx,
-                &operation.identifier(),
+                &location.join("parameters").join("path"),
             )?);
         }
 
@@ -210,19 +280,22 @@ impl OperationAst {
             cookie_params.push(ParameterAst::from_model(
                 param,
                 ctx,
-                &operation.identifier(),
+                &location.join("parameters").join("cookie"),
             )?);
         }
 
         let mut responses = vec![];
         for response in &operation.responses {
-            responses.push(StatusAst::from_model(response)?);
+            responses.push(StatusAst::from_model(
+                response,
+                &location.join("responses").join(&response.status),
+            )?);
         }
 
         let request_body = if let Some(req_body) = &operation.request_body {
             Some(RequestBodyAst::from_model(
                 req_body,
-                &operation.identifier(),
+                &location.join("request_body"),
             )?)
         } else {
             None
@@ -233,6 +306,21 @@ impl OperationAst {
             security_requirements.push(SecurityRequirementAst::from_model(req)?);
         }
 
+        let mut auth = vec![];
+        for alternative in &operation.auth {
+            let mut requirements = vec![];
+            for req in alternative {
+                requirements.push(SecurityRequirementAst::from_model(req)?);
+            }
+            auth.push(AuthAst { requirements });
+        }
+
+        let servers = operation
+            .servers
+            .iter()
+            .map(ServerAst::from_model)
+            .collect();
+
         let mut request_examples = vec![];
         for code_sample in &operation.code_examples {
             request_examples.push(ExampleAst::from_model(
@@ -256,7 +344,10 @@ impl OperationAst {
             description_ast,
             method: operation.method.clone(),
             anchor_tag: operation.anchor_tag.clone(),
+            previous_anchor_tag: operation.previous_anchor_tag.clone(),
             route_pattern: operation.route_pattern.clone(),
+            deprecated: operation.deprecated,
+            sunset: operation.sunset(sunset_field).map(str::to_owned),
             header_params,
             query_params,
             path_params,
@@ -264,6 +355,8 @@ impl OperationAst {
             request_body,
             responses,
             security_requirements,
+            auth,
+            servers,
             request_examples,
             server_route_patterns,
         })
@@ -339,11 +432,15 @@ pub struct RequestBodyAst {
 impl RequestBodyAst {
     pub(crate) fn from_model(
         request_body: &super::model::RequestBody,
-        _operation_id: &str,
+        location: &SchemaLocation,
     ) -> Result<Self> {
         let mut media_types = vec![];
         for media_type in &request_body.content {
-            media_types.push(MediaTypeAst::from_model(media_type, "requestBody")?);
+            media_types.push(MediaTypeAst::from_model(
+                media_type,
+                "requestBody",
+                location,
+            )?);
         }
 
         Ok(RequestBodyAst {
@@ -362,10 +459,17 @@ pub struct StatusAst {
     pub description_ast: Node,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub headers: Vec<HeaderAst>,
+    /// Whether the response is an API-wide default from the settings,
+    /// instead of one the operation documents, so that themes can set it
+    /// apart
+    pub api_default: bool,
 }
 
 impl StatusAst {
-    pub(crate) fn from_model(response: &super::model::Response) -> Result<Self> {
+    pub(crate) fn from_model(
+        response: &super::model::Response,
+        location: &SchemaLocation,
+    ) -> Result<Self> {
         let description_ast = ast_for_openapi(
             &response.description,
             &crate::render_context::RenderContext::new(),
@@ -377,12 +481,16 @@ impl StatusAst {
             media_types.push(MediaTypeAst::from_model(
                 media_type,
                 &format!("response-{}", &response.status),
+                location,
             )?);
         }
 
         let mut headers = vec![];
         for header in &response.headers {
-            headers.push(HeaderAst::from_model(header)?);
+            headers.push(HeaderAst::from_model(
+                header,
+                &location.join("headers").join(&header.name),
+            )?);
         }
 
         Ok(StatusAst {
@@ -390,6 +498,7 @@ impl StatusAst {
             media_types,
             headers,
             description_ast,
+            api_default: response.api_default,
         })
     }
 }
@@ -400,13 +509,17 @@ pub struct HeaderAst {
 }
 
 impl HeaderAst {
-    pub(crate) fn from_model(header: &super::model::Header) -> Result<Self> {
+    pub(crate) fn from_model(
+        header: &super::model::Header,
+        location: &SchemaLocation,
+    ) -> Result<Self> {
         Ok(HeaderAst {
             name: header.name.clone(),
             schema: SchemaAst::from_model(
                 &header.schema,
                 &crate::render_context::RenderContext::new(),
                 false,
+                location,
             )?,
         })
     }
@@ -417,19 +530,26 @@ pub struct MediaTypeAst {
     pub name: String,
     pub schemas: Vec<SchemaAst>,
     pub examples: Vec<ExampleAst>,
+    /// The schema flattened into form fields, for request bodies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form: Option<Vec<FormField>>,
 }
 
 impl MediaTypeAst {
     pub(crate) fn from_model(
         media_type: &super::model::MediaType,
         parent_id: &str,
+        location: &SchemaLocation,
     ) -> Result<Self> {
+        let location = location.join(&media_type.name);
+
         let mut schemas = vec![];
-        for schema in &media_type.schemas {
+        for (index, schema) in media_type.schemas.iter().enumerate() {
             schemas.push(SchemaAst::from_model(
                 schema,
                 &crate::render_context::RenderContext::new(),
                 false,
+                &location.join(index),
             )?);
         }
 
@@ -445,6 +565,7 @@ impl MediaTypeAst {
             name: media_type.name.clone(),
             schemas,
             examples,
+            form: media_type.form.clone(),
         })
     }
 }
@@ -453,13 +574,16 @@ impl MediaTypeAst {
 pub struct ParameterAst {
     pub schema: Option<SchemaAst>,
     pub description_ast: Option<Node>,
+    /// How the parameter looks in a request, based on its `style` and `explode`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serialized_example: Option<String>,
 }
 
 impl ParameterAst {
     pub(crate) fn from_model(
         parameter: &super::model::Parameter,
         ctx: &crate::render_context::RenderContext,
-        _operation_id: &str,
+        location: &SchemaLocation,
     ) -> Result<Self> {
         let description_ast = parameter
             .description
@@ -467,7 +591,12 @@ impl ParameterAst {
             .and_then(|description| ast_for_openapi(description, ctx).ok());
 
         let schema = if let Some(s) = parameter.schema.as_ref() {
-            Some(SchemaAst::from_model(s, ctx, false)?)
+            Some(SchemaAst::from_model(
+                s,
+                ctx,
+                false,
+                &location.join(&parameter.name),
+            )?)
         } else {
             None
         };
@@ -475,6 +604,7 @@ impl ParameterAst {
         Ok(ParameterAst {
             schema,
             description_ast,
+            serialized_example: super::serialization::serialized_example(parameter),
         })
     }
 }
@@ -531,6 +661,71 @@ pub struct SchemaAst {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
     pub expanded: bool,
+    /// Set when the nested schemas are left out of the page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collapsed: Option<CollapsedSchema>,
+}
+
+/// Why a schema's nested schemas were not rendered.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CollapsedSchema {
+    /// The schema refers back to a component it's nested in
+    Recursive { component_name: String },
+    /// The schema is nested deeper than `max_schema_depth`. The nested schemas
+    /// can be fetched with `Project::openapi_schema_fragment`.
+    MaxDepth { spec: String, pointer: String },
+}
+
+/// Where a schema is in an OpenAPI spec, as a JSON pointer. Used to collapse
+/// schemas nested deeper than `max_schema_depth`, and to find them again when
+/// they are fetched separately.
+///
+/// Pointers start from an operation, e.g. `/operations/get-users/responses/200/application~1json/0`,
+/// or from a component, e.g. `/components/schemas/User`, followed by the
+/// indexes of the nested schemas.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SchemaLocation {
+    pub spec: String,
+    pub pointer: String,
+    depth: usize,
+    max_depth: usize,
+}
+
+impl SchemaLocation {
+    pub(crate) fn root(spec: String, max_depth: usize) -> Self {
+        SchemaLocation {
+            spec,
+            pointer: String::new(),
+            depth: 0,
+            max_depth,
+        }
+    }
+
+    /// Starts from the given pointer, e.g. when fetching a fragment.
+    pub(crate) fn at(spec: String, pointer: &str, max_depth: usize) -> Self {
+        SchemaLocation {
+            pointer: pointer.trim_end_matches('/').to_string(),
+            ..SchemaLocation::root(spec, max_depth)
+        }
+    }
+
+    /// A location inside this one, at the same depth.
+    pub(crate) fn join(&self, token: impl ToString) -> Self {
+        let token = token.to_string().replace('~', "~0").replace('/', "~1");
+
+        SchemaLocation {
+            pointer: format!("{}/{}", self.pointer, token),
+            ..self.clone()
+        }
+    }
+
+    fn nested(&self, index: usize) -> Self {
+        SchemaLocation {
+            depth: self.depth + 1,
+            ..self.join(index)
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -580,17 +775,39 @@ impl SchemaAst {
         model: &SchemaModel,
         ctx: &RenderContext,
         expanded: bool,
+        location: &SchemaLocation,
     ) -> Result<Self> {
         let description_ast = model
             .description
             .as_ref()
             .and_then(|description| ast_for_openapi(description, ctx).ok());
 
-        let schemas: Vec<SchemaAst> = model
-            .nested_schemas()
-            .iter()
-            .map(|s| SchemaAst::from_model(s, ctx, s.expanded))
-            .try_collect()?;
+        let collapsed =
+            if let Some(name) = model.metadata.as_ref().and_then(|m| m.recursive.as_ref()) {
+                Some(CollapsedSchema::Recursive {
+                    component_name: name.to_string(),
+                })
+            } else if location.depth >= location.max_depth && !model.nested_schemas().is_empty() {
+                Some(CollapsedSchema::MaxDepth {
+                    spec: location.spec.clone(),
+                    pointer: location.pointer.clone(),
+                })
+            } else {
+                None
+            };
+
+        let schemas: Vec<SchemaAst> = if collapsed.is_some() {
+            vec![]
+        } else {
+            model
+                .nested_schemas()
+                .iter()
+                .enumerate()
+                .map(|(index, s)| {
+                    SchemaAst::from_model(s, ctx, s.expanded, &location.nested(index))
+                })
+                .try_collect()?
+        };
 
         let mut schema = SchemaAst {
             schemas,
@@ -615,6 +832,7 @@ impl SchemaAst {
             media_type: model.mediatype.as_ref().map(|v| v.to_owned()),
             metadata: model.metadata.as_ref().map(|m| m.clone().into()),
             expanded,
+            collapsed,
         };
 
         if model.is_nested() {
@@ -690,7 +908,7 @@ impl SecurityRequirementAst {
                 description,
                 all_scopes,
                 required_scopes,
-                flows: _,
+                flows,
             } => Ok(SecurityRequirementAst::OAuth2 {
                 name: name.clone(),
                 description_ast: description.as_ref().and_then(|d| {
@@ -698,7 +916,7 @@ impl SecurityRequirementAst {
                 }),
                 all_scopes: all_scopes.clone(),
                 required_scopes: required_scopes.clone(),
-                flows: vec![], // TODO: Convert OAuth2Flow to OAuth2FlowAst
+                flows: flows.iter().map(OAuth2FlowAst::from_model).collect(),
             }),
             OpenID {
                 name,
@@ -741,9 +959,108 @@ pub enum OAuth2FlowAst {
     },
 }
 
+impl OAuth2FlowAst {
+    fn from_model(flow: &super::model::OAuth2Flow) -> Self {
+        use super::model::OAuth2Flow::*;
+
+        let scopes = flow
+            .scopes()
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        match flow {
+            Implicit {
+                authorization_url,
+                refresh_url,
+                ..
+            } => OAuth2FlowAst::Implicit {
+                authorization_url: authorization_url.clone(),
+                refresh_url: refresh_url.clone(),
+                scopes,
+            },
+            Password {
+                refresh_url,
+                token_url,
+                ..
+            } => OAuth2FlowAst::Password {
+                refresh_url: refresh_url.clone(),
+                token_url: token_url.clone(),
+                scopes,
+            },
+            ClientCredentials {
+                refresh_url,
+                token_url,
+                ..
+            } => OAuth2FlowAst::ClientCredentials {
+                refresh_url: refresh_url.clone(),
+                token_url: token_url.clone(),
+                scopes,
+            },
+            AuthorizationCode {
+                authorization_url,
+                token_url,
+                refresh_url,
+                ..
+            } => OAuth2FlowAst::AuthorizationCode {
+                authorization_url: authorization_url.clone(),
+                token_url: token_url.clone(),
+                refresh_url: refresh_url.clone(),
+                scopes,
+            },
+        }
+    }
+}
+
+/// One way to authorize an operation. All of the requirements have to be met
+/// together, e.g. both an API key and an OAuth2 token.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthAst {
+    pub requirements: Vec<SecurityRequirementAst>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerAst {
+    pub url: String,
+    pub description: Option<String>,
+    /// Variables to substitute in the URL, e.g. `{region}`.
+    pub variables: Vec<ServerVariableAst>,
+}
+
+impl ServerAst {
+    fn from_model(server: &super::model::Server) -> Self {
+        ServerAst {
+            url: server.url.clone(),
+            description: server.description.clone(),
+            variables: server
+                .variables
+                .iter()
+                .map(|(name, variable)| ServerVariableAst {
+                    name: name.clone(),
+                    default: variable.default.clone(),
+                    r#enum: variable.r#enum.clone(),
+                    description: variable.description.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerVariableAst {
+    pub name: String,
+    pub default: String,
+    pub r#enum: Option<Vec<String>>,
+    pub description: Option<String>,
+}
+
 #[cfg(test)]
 mod test {
-    use crate::{page_kind::PageKind, render_context::RenderContext, settings::Settings};
+    use crate::{
+        page_kind::PageKind,
+        render_context::RenderContext,
+        settings::{Settings, UriStrategy},
+    };
 
     use super::super::OpenApi;
     use super::PageAst;
@@ -898,8 +1215,16 @@ mod test {
 
     fn parse_into_value() -> serde_json::Value {
         let spec = openapi_parser::openapi30::parser::parse_yaml(SPEC).unwrap();
-        let pages =
-            OpenApi::pages_from_parsed_spec(&spec, "openapi.yaml".into(), "/api".into()).unwrap();
+        let pages = OpenApi::pages_from_parsed_spec(
+            &spec,
+            "openapi.yaml".into(),
+            "/api".into(),
+            None,
+            &[],
+            UriStrategy::default(),
+            &Default::default(),
+        )
+        .unwrap();
 
         let tag_pages = pages
             .into_iter()
@@ -966,6 +1291,16 @@ mod test {
         );
     }
 
+    #[test]
+    fn param_serialized_example() {
+        let json = parse_into_value();
+
+        assert_eq!(
+            json["operations"][0]["path_params"][0]["serialized_example"],
+            "123"
+        );
+    }
+
     #[test]
     fn request_body() {
         let json = parse_into_value();
@@ -1027,6 +1362,20 @@ mod test {
         );
     }
 
+    #[test]
+    fn request_body_form() {
+        let json = parse_into_value();
+
+        let request_form = &json["operations"][1]["request_body"]["media_types"][0]["form"];
+        assert_eq!(request_form[0]["path"], "id");
+        assert_eq!(request_form[2]["path"], "state");
+        assert_eq!(request_form[2]["kind"], "string");
+        assert_eq!(request_form[2]["options"], json!(["ACTIVE", "INACTIVE"]));
+
+        let response = &json["operations"][1]["responses"][0]["media_types"][0];
+        assert!(response.get("form").is_none());
+    }
+
     #[test]
     fn responses() {
         let mut json = parse_into_value();
@@ -1289,8 +1638,16 @@ mod test {
       "#};
 
         let spec = openapi_parser::openapi30::parser::parse_yaml(spec).unwrap();
-        let pages =
-            OpenApi::pages_from_parsed_spec(&spec, "openapi.yaml".into(), "/api".into()).unwrap();
+        let pages = OpenApi::pages_from_parsed_spec(
+            &spec,
+            "openapi.yaml".into(),
+            "/api".into(),
+            None,
+            &[],
+            UriStrategy::default(),
+            &Default::default(),
+        )
+        .unwrap();
 
         let tag_pages = pages
             .into_iter()
@@ -1313,11 +1670,11 @@ mod test {
         assert_str_eq!(
             tag_desc.debug_string().unwrap(),
             indoc! { r#"
-            <Box padding={2} max_width={full} class={d-callout d-callout-info} height={Auto}>
+            <Callout type={info} padding={2}>
                 <Text>
                     Tag description
                 </Text>
-            </Box>
+            </Callout>
             "# }
         );
 
@@ -1368,4 +1725,230 @@ mod test {
             "# }
         );
     }
+
+    #[test]
+    fn auth_and_servers_for_sending_requests() {
+        let spec = indoc! {r#"
+          openapi: 3.0.0
+          info:
+            title: Sample API
+            version: 0.1.9
+          servers:
+            - url: https://{region}.example.com/v1
+              variables:
+                region:
+                  default: eu
+                  enum: [eu, us]
+          security:
+            - ApiKey: []
+          tags:
+            - name: Users
+          paths:
+            /users:
+              get:
+                summary: List users
+                tags: [Users]
+                responses:
+                  '200':
+                    description: Users
+              post:
+                summary: Create a user
+                tags: [Users]
+                security:
+                  - OAuth: [write]
+                  - Bearer: []
+                    ApiKey: []
+                responses:
+                  '200':
+                    description: A user
+              delete:
+                summary: Delete users
+                tags: [Users]
+                security: []
+                responses:
+                  '200':
+                    description: Nothing
+          components:
+            securitySchemes:
+              ApiKey:
+                type: apiKey
+                in: header
+                name: X-API-Key
+                description: Your API key
+              Bearer:
+                type: http
+                scheme: bearer
+                bearerFormat: JWT
+              OAuth:
+                type: oauth2
+                flows:
+                  clientCredentials:
+                    tokenUrl: https://example.com/token
+                    scopes:
+                      read: Read things
+                      write: Write things
+      "#};
+
+        let spec = openapi_parser::openapi30::parser::parse_yaml(spec).unwrap();
+        let pages = OpenApi::pages_from_parsed_spec(
+            &spec,
+            "openapi.yaml".into(),
+            "/api".into(),
+            None,
+            &[],
+            UriStrategy::default(),
+            &Default::default(),
+        )
+        .unwrap();
+
+        let page = pages
+            .into_iter()
+            .find_map(|p| match p {
+                PageKind::OpenApi(p) => Some(p.get_page().clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        let page_ast = PageAst::from_page(&page, &RenderContext::new()).unwrap();
+        let json = serde_json::to_value(page_ast).unwrap();
+
+        // The security of the spec applies when the operation has none
+        let list = &json["operations"][0];
+        assert_eq!(list["auth"].as_array().unwrap().len(), 1);
+        let api_key = &list["auth"][0]["requirements"][0];
+        assert_eq!(api_key["kind"], "api_key");
+        assert_eq!(api_key["data"]["key_name"], "X-API-Key");
+        assert_eq!(api_key["data"]["key_location"], "header");
+
+        // Each alternative is its own entry
+        let create = &json["operations"][1];
+        assert_eq!(create["auth"].as_array().unwrap().len(), 2);
+        let oauth = &create["auth"][0]["requirements"][0]["data"];
+        assert_eq!(oauth["required_scopes"], json!(["write"]));
+        assert_eq!(
+            oauth["flows"][0],
+            json!({
+                "kind": "client_credentials",
+                "data": {
+                    "refresh_url": null,
+                    "token_url": "https://example.com/token",
+                    "scopes": [["read", "Read things"], ["write", "Write things"]],
+                },
+            })
+        );
+        let both = create["auth"][1]["requirements"].as_array().unwrap();
+        assert_eq!(both.len(), 2);
+        assert_eq!(both[0]["data"]["scheme"], "bearer");
+        assert_eq!(both[0]["data"]["bearer_format"], "JWT");
+        assert_eq!(both[1]["data"]["name"], "ApiKey");
+
+        // An empty list turns the security of the spec off
+        assert!(json["operations"][2].get("auth").is_none());
+
+        assert_eq!(
+            list["servers"],
+            json!([{
+                "url": "https://{region}.example.com/v1",
+                "description": null,
+                "variables": [{
+                    "name": "region",
+                    "default": "eu",
+                    "enum": ["eu", "us"],
+                    "description": null,
+                }],
+            }])
+        );
+    }
+
+    #[test]
+    fn deprecation_banner_lists_deprecated_operations() {
+        let spec = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        paths:
+          /trees:
+            get:
+              summary: List trees
+              tags: [Trees]
+              deprecated: true
+              x-retired-on: "2025-06-30"
+              responses:
+                "200":
+                  description: OK
+            post:
+              summary: Plant a tree
+              tags: [Trees]
+              responses:
+                "200":
+                  description: OK
+      "#};
+
+        let spec = openapi_parser::openapi30::parser::parse_yaml(spec).unwrap();
+        let pages = OpenApi::pages_from_parsed_spec(
+            &spec,
+            "openapi.yaml".into(),
+            "/api".into(),
+            None,
+            &[],
+            UriStrategy::default(),
+            &Default::default(),
+        )
+        .unwrap();
+
+        let page = pages
+            .into_iter()
+            .find_map(|p| match p {
+                PageKind::OpenApi(p) => Some(p.get_page().clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        let ast = |settings: &str| {
+            let settings = Settings::parse(settings).unwrap();
+            let mut ctx = RenderContext::new();
+            ctx.with_settings(&settings);
+
+            serde_json::to_value(PageAst::from_page(&page, &ctx).unwrap()).unwrap()
+        };
+
+        let json = ast(indoc! {r#"
+        ---
+        title: Trees
+        open_api:
+          - spec_file: openapi.yaml
+            uri_prefix: /api
+            deprecation_banner: true
+            sunset_header_field: x-retired-on
+        "#});
+
+        assert_eq!(
+            json["deprecation_banner"],
+            json!({
+                "operations": [{
+                    "summary": "List trees",
+                    "method": "get",
+                    "route_pattern": "/trees",
+                    "anchor_tag": "list-trees",
+                    "sunset": "2025-06-30",
+                }]
+            })
+        );
+        assert_eq!(json["operations"][0]["deprecated"], true);
+        assert_eq!(json["operations"][0]["sunset"], "2025-06-30");
+        assert_eq!(json["operations"][1]["deprecated"], false);
+        assert_eq!(json["operations"][1]["sunset"], Value::Null);
+
+        let json = ast(indoc! {r#"
+        ---
+        title: Trees
+        open_api:
+          - spec_file: openapi.yaml
+            uri_prefix: /api
+        "#});
+
+        assert_eq!(json["deprecation_banner"], Value::Null);
+        assert_eq!(json["operations"][0]["sunset"], Value::Null);
+    }
 }
@@ -0,0 +1,146 @@
+//! Hand-written Markdown added to the pages of operations, like walkthroughs
+//! or gotchas that don't belong in the spec. Each file is named after the
+//! operationId it's for, e.g. `_openapi/createCluster.md`, in the folder set
+//! with `augmentations_dir` in the `open_api` settings.
+//!
+//! The content is added after the operation's description, unless the
+//! frontmatter sets `placement` to `prepend` or `replace`.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    frontmatter, markdown,
+    render_context::{FileContext, RenderContext},
+    renderable_ast::Node,
+    Error, Result,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Augmentation {
+    pub operation_id: String,
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Placement {
+    #[default]
+    Append,
+    Prepend,
+    Replace,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct AugmentationFrontmatter {
+    #[serde(default)]
+    placement: Placement,
+}
+
+impl Augmentation {
+    /// The Markdown files directly in `dir`, sorted by path.
+    pub(crate) fn in_dir<'a>(
+        dir: &Path,
+        files: impl Iterator<Item = (&'a PathBuf, &'a String)>,
+    ) -> Vec<Augmentation> {
+        let mut augmentations = files
+            .filter(|(path, _)| path.parent() == Some(dir))
+            .filter(|(path, _)| path.extension() == Some(std::ffi::OsStr::new("md")))
+            .filter_map(|(path, content)| {
+                Some(Augmentation {
+                    operation_id: path.file_stem()?.to_str()?.to_owned(),
+                    path: path.clone(),
+                    content: content.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        augmentations.sort_by(|a, b| a.path.cmp(&b.path));
+        augmentations
+    }
+
+    fn placement(&self) -> Result<Placement> {
+        let (yaml, _) = frontmatter::split(&self.content);
+
+        if yaml.trim().is_empty() {
+            return Ok(Placement::default());
+        }
+
+        serde_yaml::from_str::<AugmentationFrontmatter>(yaml)
+            .map(|f| f.placement)
+            .map_err(|e| Error {
+                code: Error::INVALID_FRONTMATTER,
+                message: "Invalid frontmatter in OpenAPI augmentation".to_owned(),
+                description: format!(
+                    "{}\n\nExpected `placement` to be one of `append`, `prepend` or `replace`.",
+                    e
+                ),
+                file: Some(self.path.clone()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            })
+    }
+
+    /// Renders the augmentation like any other Markdown page, with errors
+    /// pointing into its file.
+    fn ast(&self, ctx: &RenderContext) -> Result<Node> {
+        let mut ctx = ctx.clone();
+        ctx.with_file_context(FileContext::new(
+            frontmatter::end_line(&self.content),
+            frontmatter::end_pos(&self.content),
+            self.path.clone(),
+        ));
+
+        markdown::ast_mdx(frontmatter::without(&self.content), &ctx).map_err(|mut e| {
+            e.in_file(&self.path);
+            e
+        })
+    }
+
+    /// Combines the augmentation with the rendered description of its
+    /// operation, as set by its `placement`.
+    pub(crate) fn apply(&self, description: Option<Node>, ctx: &RenderContext) -> Result<Node> {
+        let placement = self.placement()?;
+        let mut ast = self.ast(ctx)?;
+
+        Ok(match (placement, description) {
+            (Placement::Replace, _) | (_, None) => ast,
+            (Placement::Append, Some(mut description)) => {
+                description.children.append(&mut ast.children);
+                description
+            }
+            (Placement::Prepend, Some(description)) => {
+                ast.children.extend(description.children);
+                ast
+            }
+        })
+    }
+}
+
+/// Reports augmentations for operations that aren't in any of the specs
+/// sharing their folder, e.g. because the operation was removed.
+pub(crate) fn unknown_operation_errors(
+    augmentations: &[Augmentation],
+    operation_ids: &HashSet<String>,
+) -> Vec<Error> {
+    augmentations
+        .iter()
+        .filter(|a| !operation_ids.contains(&a.operation_id))
+        .map(|a| Error {
+            code: Error::OPENAPI_REFERENCE,
+            message: String::from("OpenAPI augmentation for an unknown operation"),
+            description: format!(
+                "No operation has the operationId \"{}\", which \"{}\" is named after.\nRename the file, or remove it if the operation was removed from the spec.",
+                a.operation_id,
+                a.path.display()
+            ),
+            file: Some(a.path.clone()),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        })
+        .collect()
+}
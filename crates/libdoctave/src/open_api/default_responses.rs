@@ -0,0 +1,226 @@
+//! Responses shown on every operation of a spec, like rate limiting or
+//! authentication errors that the spec doesn't document for each operation.
+//! They're set with `default_responses` in the `open_api` settings, and an
+//! operation's own response for the same status code always wins.
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde_path_to_error::Segment;
+
+use super::model::{Operation, Response};
+use crate::settings::{DefaultResponse, OpenApi as OpenApiSettings};
+use crate::{Error, Result, SETTINGS_FILE_NAME};
+
+/// Default responses can only refer to the component schemas of the spec.
+const SCHEMA_PREFIX: &str = "#/components/schemas/";
+
+/// The default responses of the spec, marked as API-wide defaults.
+pub(crate) fn responses(
+    spec: &openapi_parser::OpenAPI,
+    defaults: &BTreeMap<String, DefaultResponse>,
+) -> Result<Vec<Response>> {
+    defaults
+        .iter()
+        .map(|(status, default)| {
+            let mut response = Response::from_parsed(parsed(spec, default), status.clone())?;
+            response.api_default = true;
+
+            Ok(response)
+        })
+        .collect()
+}
+
+/// Adds the default responses for the status codes the operation doesn't
+/// document, in order of their status codes.
+pub(crate) fn merge(operation: &mut Operation, defaults: &[Response]) {
+    for default in defaults {
+        if operation
+            .responses
+            .iter()
+            .any(|r| r.status == default.status)
+        {
+            continue;
+        }
+
+        let index = operation
+            .responses
+            .iter()
+            .position(|r| r.status > default.status)
+            .unwrap_or(operation.responses.len());
+
+        operation.responses.insert(index, default.clone());
+    }
+}
+
+/// Reports schemas the default responses refer to that aren't in the spec,
+/// and examples that don't match their schemas. Positions point to the
+/// default responses of the spec at `index` in `input`.
+pub(crate) fn errors(
+    spec: &openapi_parser::OpenAPI,
+    settings: &OpenApiSettings,
+    index: usize,
+    input: &str,
+) -> Vec<Error> {
+    let mut errors = vec![];
+
+    for (status, default) in &settings.default_responses {
+        let Some(reference) = &default.schema else {
+            continue;
+        };
+
+        let Some(schema) = find_schema(spec, reference) else {
+            errors.push(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: format!(
+                    r#"Unknown schema "{}" in default response {}"#,
+                    reference, status
+                ),
+                description: format!(
+                    "Default responses can use the schemas of the OpenAPI spec \"{}\", \
+                    e.g. {}Error.",
+                    settings.spec_file.display(),
+                    SCHEMA_PREFIX
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: crate::yaml::position_of(
+                    input,
+                    &default_response_path(index, status, "schema"),
+                ),
+                owner: None,
+                suggestions: vec![],
+            });
+
+            continue;
+        };
+
+        let Some(example) = &default.example else {
+            continue;
+        };
+
+        if let Err(reason) = openapi_parser::validate_example(schema, example) {
+            errors.push(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: "Example does not match its schema".to_owned(),
+                description: format!(
+                    "{}\n\nExample of the default response {} of the OpenAPI spec \"{}\".",
+                    reason,
+                    status,
+                    settings.spec_file.display()
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: crate::yaml::position_of(
+                    input,
+                    &default_response_path(index, status, "example"),
+                ),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+
+    errors
+}
+
+/// The component schema a reference like `#/components/schemas/Error`
+/// points to.
+fn find_schema<'a>(
+    spec: &'a openapi_parser::OpenAPI,
+    reference: &str,
+) -> Option<&'a openapi_parser::Schema> {
+    let name = reference.strip_prefix(SCHEMA_PREFIX)?;
+
+    spec.components.as_ref()?.schemas.get(name)
+}
+
+/// The default response as if the spec had defined it, so that it's shown
+/// like the operation's own responses. Schemas that can't be found are left
+/// out, and reported by [`errors`].
+fn parsed(spec: &openapi_parser::OpenAPI, default: &DefaultResponse) -> openapi_parser::Response {
+    let schema = default
+        .schema
+        .as_deref()
+        .and_then(|reference| find_schema(spec, reference))
+        .cloned();
+
+    let mut content = openapi_parser::Map::new();
+
+    if schema.is_some() || default.example.is_some() {
+        content.insert(
+            "application/json".into(),
+            openapi_parser::MediaType {
+                schema,
+                example: default.example.clone(),
+                examples: openapi_parser::Map::new(),
+                encoding: openapi_parser::Map::new(),
+            },
+        );
+    }
+
+    let headers = default
+        .headers
+        .iter()
+        .map(|(name, header)| {
+            let schema = openapi_parser::Schema {
+                metadata: openapi_parser::Metadata::default(),
+                kind: openapi_parser::SchemaKind::String(openapi_parser::StringSchema {
+                    pattern: None,
+                    r#enum: vec![],
+                    max_length: None,
+                    min_length: None,
+                    format: None,
+                }),
+                title: None,
+                description: None,
+                nullable: None,
+                not: None,
+                example: header
+                    .example
+                    .as_deref()
+                    .map(|example| openapi_parser::Value::String(example.into())),
+                default: None,
+                deprecated: None,
+            };
+
+            (
+                name.as_str().into(),
+                openapi_parser::Header(openapi_parser::Parameter {
+                    name: name.as_str().into(),
+                    kind: openapi_parser::ParameterKind::Header(openapi_parser::HeaderParameter {}),
+                    description: header.description.as_deref().map(Into::into),
+                    deprecated: None,
+                    style: None,
+                    explode: None,
+                    schema_or_content: Some(openapi_parser::SchemaOrContent::Schema(schema)),
+                    example: None,
+                    examples: openapi_parser::Map::new(),
+                    required: None,
+                }),
+            )
+        })
+        .collect();
+
+    openapi_parser::Response {
+        description: default.description.as_str().into(),
+        headers,
+        content,
+        links: openapi_parser::Map::new(),
+    }
+}
+
+fn default_response_path(index: usize, status: &str, key: &str) -> [Segment; 5] {
+    [
+        Segment::Map {
+            key: String::from("open_api"),
+        },
+        Segment::Seq { index },
+        Segment::Map {
+            key: String::from("default_responses"),
+        },
+        Segment::Map {
+            key: status.to_owned(),
+        },
+        Segment::Map {
+            key: key.to_owned(),
+        },
+    ]
+}
@@ -0,0 +1,946 @@
+//! Changelogs between two versions of an OpenAPI spec.
+//!
+//! The parser resolves `$ref`s into the schemas that use them, so the diff
+//! compares the shape of the API rather than how the spec is organised.
+//! Renaming a component, or inlining it, is not reported as long as the
+//! resulting schemas are the same.
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use openapi_parser::{
+    MediaType, OpenAPI, Operation, Parameter, RequestBody, Response, Schema, SchemaKind,
+    SchemaOrContent,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ApiDiff {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change {
+    /// The operation the change applies to, e.g. `GET /users/{id}`
+    pub operation: String,
+    /// Whether existing clients may stop working because of the change
+    pub breaking: bool,
+    #[serde(flatten)]
+    pub kind: ChangeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChangeKind {
+    OperationAdded,
+    OperationRemoved,
+    /// `location` is empty when the operation itself was (un)deprecated
+    DeprecationChanged {
+        location: String,
+        deprecated: bool,
+    },
+    ParameterAdded {
+        name: String,
+        r#in: String,
+        required: bool,
+    },
+    ParameterRemoved {
+        name: String,
+        r#in: String,
+    },
+    RequiredChanged {
+        location: String,
+        required: bool,
+    },
+    RequestBodyAdded {
+        required: bool,
+    },
+    RequestBodyRemoved,
+    ResponseAdded {
+        status: String,
+    },
+    ResponseRemoved {
+        status: String,
+    },
+    MediaTypeAdded {
+        location: String,
+    },
+    MediaTypeRemoved {
+        location: String,
+    },
+    PropertyAdded {
+        location: String,
+        required: bool,
+    },
+    PropertyRemoved {
+        location: String,
+    },
+    TypeChanged {
+        location: String,
+        old: String,
+        new: String,
+    },
+    EnumChanged {
+        location: String,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+}
+
+impl ChangeKind {
+    pub fn description(&self) -> String {
+        match self {
+            ChangeKind::OperationAdded => "Added operation".to_string(),
+            ChangeKind::OperationRemoved => "Removed operation".to_string(),
+            ChangeKind::DeprecationChanged {
+                location,
+                deprecated,
+            } => {
+                let verb = if *deprecated {
+                    "Deprecated"
+                } else {
+                    "Undeprecated"
+                };
+
+                if location.is_empty() {
+                    format!("{} operation", verb)
+                } else {
+                    format!("{} {}", verb, location)
+                }
+            }
+            ChangeKind::ParameterAdded {
+                name,
+                r#in,
+                required,
+            } => format!(
+                "Added {} {} parameter `{}`",
+                if *required { "required" } else { "optional" },
+                r#in,
+                name
+            ),
+            ChangeKind::ParameterRemoved { name, r#in } => {
+                format!("Removed {} parameter `{}`", r#in, name)
+            }
+            ChangeKind::RequiredChanged { location, required } => format!(
+                "Made {} {}",
+                location,
+                if *required { "required" } else { "optional" }
+            ),
+            ChangeKind::RequestBodyAdded { required } => format!(
+                "Added {} request body",
+                if *required { "required" } else { "optional" }
+            ),
+            ChangeKind::RequestBodyRemoved => "Removed request body".to_string(),
+            ChangeKind::ResponseAdded { status } => format!("Added `{}` response", status),
+            ChangeKind::ResponseRemoved { status } => format!("Removed `{}` response", status),
+            ChangeKind::MediaTypeAdded { location } => format!("Added {}", location),
+            ChangeKind::MediaTypeRemoved { location } => format!("Removed {}", location),
+            ChangeKind::PropertyAdded { location, required } => format!(
+                "Added {} {}",
+                if *required { "required" } else { "optional" },
+                location
+            ),
+            ChangeKind::PropertyRemoved { location } => format!("Removed {}", location),
+            ChangeKind::TypeChanged { location, old, new } => {
+                format!("Changed type of {} from `{}` to `{}`", location, old, new)
+            }
+            ChangeKind::EnumChanged {
+                location,
+                added,
+                removed,
+            } => {
+                let mut parts = vec![];
+                if !added.is_empty() {
+                    parts.push(format!("added {}", code_list(added)));
+                }
+                if !removed.is_empty() {
+                    parts.push(format!("removed {}", code_list(removed)));
+                }
+                format!("Changed values of {}: {}", location, parts.join(", "))
+            }
+        }
+    }
+}
+
+fn code_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|v| format!("`{}`", v))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Which way data flows, which decides whether a change is breaking. Clients
+/// have to send what the API expects, but only read what the API returns.
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+impl ApiDiff {
+    /// Compares two versions of a spec. Operations are matched by their
+    /// method and route, and parameters by their name and location.
+    pub fn between(old: &OpenAPI, new: &OpenAPI) -> Self {
+        let mut diff = Differ { changes: vec![] };
+
+        let old_ops = old.operations();
+        let new_ops = new.operations();
+
+        for new_op in &new_ops {
+            let key = operation_key(new_op);
+
+            match old_ops.iter().find(|o| operation_key(o) == key) {
+                Some(old_op) => diff.operation(&key, old_op, new_op),
+                None => diff.push(&key, false, ChangeKind::OperationAdded),
+            }
+        }
+
+        for old_op in &old_ops {
+            let key = operation_key(old_op);
+
+            if !new_ops.iter().any(|o| operation_key(o) == key) {
+                diff.push(&key, true, ChangeKind::OperationRemoved);
+            }
+        }
+
+        ApiDiff {
+            changes: diff.changes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.changes.iter().any(|c| c.breaking)
+    }
+
+    /// Renders the diff as a Markdown changelog, with breaking changes first
+    /// and the changes grouped by operation.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# API changelog\n");
+
+        if self.is_empty() {
+            out.push_str("\nNo changes.\n");
+            return out;
+        }
+
+        for (breaking, heading) in [(true, "Breaking changes"), (false, "Non-breaking changes")] {
+            let changes = self
+                .changes
+                .iter()
+                .filter(|c| c.breaking == breaking)
+                .collect::<Vec<_>>();
+
+            if changes.is_empty() {
+                continue;
+            }
+
+            let _ = write!(out, "\n## {}\n", heading);
+
+            let mut operations: Vec<&str> = vec![];
+            for change in &changes {
+                if !operations.contains(&change.operation.as_str()) {
+                    operations.push(&change.operation);
+                }
+            }
+
+            for operation in operations {
+                let _ = write!(out, "\n### `{}`\n\n", operation);
+
+                for change in changes.iter().filter(|c| c.operation == operation) {
+                    let _ = writeln!(out, "- {}", change.kind.description());
+                }
+            }
+        }
+
+        out
+    }
+}
+
+fn operation_key(op: &Operation) -> String {
+    format!("{} {}", op.method.to_uppercase(), op.route_pattern)
+}
+
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(&mut self, operation: &str, breaking: bool, kind: ChangeKind) {
+        self.changes.push(Change {
+            operation: operation.to_string(),
+            breaking,
+            kind,
+        });
+    }
+
+    fn operation(&mut self, key: &str, old: &Operation, new: &Operation) {
+        let (old_deprecated, new_deprecated) = (
+            old.deprecated.unwrap_or(false),
+            new.deprecated.unwrap_or(false),
+        );
+        if old_deprecated != new_deprecated {
+            self.push(
+                key,
+                false,
+                ChangeKind::DeprecationChanged {
+                    location: String::new(),
+                    deprecated: new_deprecated,
+                },
+            );
+        }
+
+        self.parameters(key, &old.parameters, &new.parameters);
+        self.request_body(key, old.request_body.as_ref(), new.request_body.as_ref());
+
+        let no_responses = Default::default();
+        let old_responses = old
+            .responses
+            .as_ref()
+            .map(|r| &r.0)
+            .unwrap_or(&no_responses);
+        let new_responses = new
+            .responses
+            .as_ref()
+            .map(|r| &r.0)
+            .unwrap_or(&no_responses);
+
+        for (status, new_response) in new_responses {
+            match old_responses.get(status) {
+                Some(old_response) => self.response(key, status, old_response, new_response),
+                None => self.push(
+                    key,
+                    false,
+                    ChangeKind::ResponseAdded {
+                        status: status.to_string(),
+                    },
+                ),
+            }
+        }
+
+        for status in old_responses.keys() {
+            if !new_responses.contains_key(status) {
+                self.push(
+                    key,
+                    true,
+                    ChangeKind::ResponseRemoved {
+                        status: status.to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn parameters(&mut self, key: &str, old: &[Parameter], new: &[Parameter]) {
+        for new_param in new {
+            let r#in = new_param.kind.get_type().to_string();
+            let name = new_param.name.to_string();
+            let required = new_param.required.unwrap_or(false);
+
+            let Some(old_param) = find_parameter(old, new_param) else {
+                self.push(
+                    key,
+                    required,
+                    ChangeKind::ParameterAdded {
+                        name,
+                        r#in,
+                        required,
+                    },
+                );
+                continue;
+            };
+
+            let location = format!("{} parameter `{}`", r#in, name);
+
+            if old_param.required.unwrap_or(false) != required {
+                self.push(
+                    key,
+                    required,
+                    ChangeKind::RequiredChanged {
+                        location: location.clone(),
+                        required,
+                    },
+                );
+            }
+
+            let (old_deprecated, new_deprecated) = (
+                old_param.deprecated.unwrap_or(false),
+                new_param.deprecated.unwrap_or(false),
+            );
+            if old_deprecated != new_deprecated {
+                self.push(
+                    key,
+                    false,
+                    ChangeKind::DeprecationChanged {
+                        location: location.clone(),
+                        deprecated: new_deprecated,
+                    },
+                );
+            }
+
+            match (&old_param.schema_or_content, &new_param.schema_or_content) {
+                (Some(SchemaOrContent::Schema(old)), Some(SchemaOrContent::Schema(new))) => {
+                    self.schema(key, &location, old, new, Direction::Request)
+                }
+                (Some(SchemaOrContent::Content(old)), Some(SchemaOrContent::Content(new))) => {
+                    self.content(key, &location, old, new, Direction::Request)
+                }
+                _ => {}
+            }
+        }
+
+        for old_param in old {
+            if find_parameter(new, old_param).is_none() {
+                self.push(
+                    key,
+                    true,
+                    ChangeKind::ParameterRemoved {
+                        name: old_param.name.to_string(),
+                        r#in: old_param.kind.get_type().to_string(),
+                    },
+                );
+            }
+        }
+    }
+
+    fn request_body(&mut self, key: &str, old: Option<&RequestBody>, new: Option<&RequestBody>) {
+        match (old, new) {
+            (Some(old), Some(new)) => {
+                let required = new.required.unwrap_or(false);
+
+                if old.required.unwrap_or(false) != required {
+                    self.push(
+                        key,
+                        required,
+                        ChangeKind::RequiredChanged {
+                            location: "request body".to_string(),
+                            required,
+                        },
+                    );
+                }
+
+                self.content(
+                    key,
+                    "request body",
+                    &old.content,
+                    &new.content,
+                    Direction::Request,
+                );
+            }
+            (None, Some(new)) => {
+                let required = new.required.unwrap_or(false);
+                self.push(key, required, ChangeKind::RequestBodyAdded { required });
+            }
+            (Some(_), None) => self.push(key, true, ChangeKind::RequestBodyRemoved),
+            (None, None) => {}
+        }
+    }
+
+    fn response(&mut self, key: &str, status: &str, old: &Response, new: &Response) {
+        let location = format!("`{}` response", status);
+
+        self.content(
+            key,
+            &location,
+            &old.content,
+            &new.content,
+            Direction::Response,
+        );
+
+        for (name, new_header) in &new.headers {
+            let header_location = format!("{} header `{}`", location, name);
+
+            match old.headers.get(name) {
+                Some(old_header) => {
+                    if let (
+                        Some(SchemaOrContent::Schema(old)),
+                        Some(SchemaOrContent::Schema(new)),
+                    ) = (
+                        &old_header.0.schema_or_content,
+                        &new_header.0.schema_or_content,
+                    ) {
+                        self.schema(key, &header_location, old, new, Direction::Response);
+                    }
+                }
+                None => self.push(
+                    key,
+                    false,
+                    ChangeKind::PropertyAdded {
+                        location: header_location,
+                        required: new_header.0.required.unwrap_or(false),
+                    },
+                ),
+            }
+        }
+
+        for name in old.headers.keys() {
+            if !new.headers.contains_key(name) {
+                self.push(
+                    key,
+                    true,
+                    ChangeKind::PropertyRemoved {
+                        location: format!("{} header `{}`", location, name),
+                    },
+                );
+            }
+        }
+    }
+
+    fn content(
+        &mut self,
+        key: &str,
+        location: &str,
+        old: &openapi_parser::Map<openapi_parser::String, MediaType>,
+        new: &openapi_parser::Map<openapi_parser::String, MediaType>,
+        direction: Direction,
+    ) {
+        for (media_type, new_content) in new {
+            let media_location = format!("{} (`{}`)", location, media_type);
+
+            match old.get(media_type) {
+                Some(old_content) => {
+                    if let (Some(old), Some(new)) = (&old_content.schema, &new_content.schema) {
+                        self.schema(key, &media_location, old, new, direction);
+                    }
+                }
+                None => self.push(
+                    key,
+                    false,
+                    ChangeKind::MediaTypeAdded {
+                        location: media_location,
+                    },
+                ),
+            }
+        }
+
+        for media_type in old.keys() {
+            if !new.contains_key(media_type) {
+                self.push(
+                    key,
+                    true,
+                    ChangeKind::MediaTypeRemoved {
+                        location: format!("{} (`{}`)", location, media_type),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Compares two schemas structurally. Component names are ignored, so
+    /// that only the shape of the data matters.
+    fn schema(&mut self, key: &str, location: &str, old: &Schema, new: &Schema, dir: Direction) {
+        // Recursive references were cut short by the parser. The schema they
+        // point to is compared where it is first used.
+        if old.metadata.recursive.is_some() || new.metadata.recursive.is_some() {
+            return;
+        }
+
+        match (&old.kind, &new.kind) {
+            (SchemaKind::Object(old_obj), SchemaKind::Object(new_obj)) => {
+                for (name, new_prop) in &new_obj.properties {
+                    let prop_location = property_location(location, name);
+                    let required = new_prop.required || new_obj.required.contains(name);
+
+                    let Some(old_prop) = old_obj.properties.get(name) else {
+                        self.push(
+                            key,
+                            dir == Direction::Request && required,
+                            ChangeKind::PropertyAdded {
+                                location: prop_location,
+                                required,
+                            },
+                        );
+                        continue;
+                    };
+
+                    let was_required = old_prop.required || old_obj.required.contains(name);
+                    if was_required != required {
+                        // Requests break when more is required, responses
+                        // break when less is guaranteed.
+                        let breaking = match dir {
+                            Direction::Request => required,
+                            Direction::Response => !required,
+                        };
+
+                        self.push(
+                            key,
+                            breaking,
+                            ChangeKind::RequiredChanged {
+                                location: prop_location.clone(),
+                                required,
+                            },
+                        );
+                    }
+
+                    let (old_deprecated, new_deprecated) = (
+                        old_prop.schema.deprecated.unwrap_or(false),
+                        new_prop.schema.deprecated.unwrap_or(false),
+                    );
+                    if old_deprecated != new_deprecated {
+                        self.push(
+                            key,
+                            false,
+                            ChangeKind::DeprecationChanged {
+                                location: prop_location.clone(),
+                                deprecated: new_deprecated,
+                            },
+                        );
+                    }
+
+                    self.schema(key, &prop_location, &old_prop.schema, &new_prop.schema, dir);
+                }
+
+                for name in old_obj.properties.keys() {
+                    if !new_obj.properties.contains_key(name) {
+                        self.push(
+                            key,
+                            true,
+                            ChangeKind::PropertyRemoved {
+                                location: property_location(location, name),
+                            },
+                        );
+                    }
+                }
+            }
+            (SchemaKind::Array(old_arr), SchemaKind::Array(new_arr)) => {
+                if let (Some(old_items), Some(new_items)) = (&old_arr.items, &new_arr.items) {
+                    self.schema(key, &items_location(location), old_items, new_items, dir);
+                }
+            }
+            (SchemaKind::OneOf(old_one), SchemaKind::OneOf(new_one)) => {
+                self.variants(key, location, &old_one.one_of, &new_one.one_of, dir)
+            }
+            (SchemaKind::AnyOf(old_any), SchemaKind::AnyOf(new_any)) => {
+                self.variants(key, location, &old_any.any_of, &new_any.any_of, dir)
+            }
+            (old_kind, new_kind) if type_name(old_kind) != type_name(new_kind) => self.push(
+                key,
+                true,
+                ChangeKind::TypeChanged {
+                    location: location.to_string(),
+                    old: type_name(old_kind).to_string(),
+                    new: type_name(new_kind).to_string(),
+                },
+            ),
+            (old_kind, new_kind) => {
+                let old_values = enum_values(old_kind);
+                let new_values = enum_values(new_kind);
+
+                let added = new_values
+                    .difference(&old_values)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let removed = old_values
+                    .difference(&new_values)
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if added.is_empty() && removed.is_empty() {
+                    return;
+                }
+
+                // Going from no enum to an enum narrows the accepted values,
+                // and the other way around widens them.
+                let narrowed = !removed.is_empty() || old_values.is_empty();
+                let widened = !added.is_empty() || new_values.is_empty();
+
+                let breaking = match dir {
+                    Direction::Request => narrowed,
+                    Direction::Response => widened,
+                };
+
+                self.push(
+                    key,
+                    breaking,
+                    ChangeKind::EnumChanged {
+                        location: location.to_string(),
+                        added,
+                        removed,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Variants of `oneOf` and `anyOf` have no names, so they are compared
+    /// by position.
+    fn variants(
+        &mut self,
+        key: &str,
+        location: &str,
+        old: &[Schema],
+        new: &[Schema],
+        dir: Direction,
+    ) {
+        for (i, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+            let variant_location = format!("{} (variant {})", location, i + 1);
+            self.schema(key, &variant_location, old, new, dir);
+        }
+
+        if old.len() != new.len() {
+            self.push(
+                key,
+                true,
+                ChangeKind::TypeChanged {
+                    location: location.to_string(),
+                    old: format!("{} variants", old.len()),
+                    new: format!("{} variants", new.len()),
+                },
+            );
+        }
+    }
+}
+
+fn find_parameter<'a>(params: &'a [Parameter], param: &Parameter) -> Option<&'a Parameter> {
+    params
+        .iter()
+        .find(|p| p.name == param.name && p.kind.get_type() == param.kind.get_type())
+}
+
+fn property_location(parent: &str, name: &str) -> String {
+    format!("property `{}` in {}", name, parent)
+}
+
+fn items_location(parent: &str) -> String {
+    format!("items of {}", parent)
+}
+
+fn type_name(kind: &SchemaKind) -> &'static str {
+    match kind {
+        SchemaKind::String(_) => "string",
+        SchemaKind::Number(_) => "number",
+        SchemaKind::Integer(_) => "integer",
+        SchemaKind::Object(_) => "object",
+        SchemaKind::Array(_) => "array",
+        SchemaKind::Boolean(_) => "boolean",
+        SchemaKind::OneOf(_) => "oneOf",
+        SchemaKind::AnyOf(_) => "anyOf",
+        SchemaKind::Unknown => "unknown",
+    }
+}
+
+fn enum_values(kind: &SchemaKind) -> BTreeSet<String> {
+    match kind {
+        SchemaKind::String(s) => s.r#enum.iter().map(|v| v.to_string()).collect(),
+        SchemaKind::Number(n) => n.r#enum.iter().map(|v| v.to_string()).collect(),
+        SchemaKind::Integer(i) => i.r#enum.iter().map(|v| v.to_string()).collect(),
+        SchemaKind::Boolean(b) => b.r#enum.iter().map(|v| v.to_string()).collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use indoc::indoc;
+    use openapi_parser::openapi30::parser::parse_yaml;
+
+    fn diff(old: &str, new: &str) -> ApiDiff {
+        crate::open_api::OpenApi::diff(&parse_yaml(old).unwrap(), &parse_yaml(new).unwrap())
+    }
+
+    const BASE: &str = indoc! {r##"
+        openapi: 3.0.0
+        info:
+          title: Pets
+          version: 1.0.0
+        paths:
+          /pets:
+            get:
+              parameters:
+                - name: limit
+                  in: query
+                  schema:
+                    type: integer
+              responses:
+                "200":
+                  description: OK
+                  content:
+                    application/json:
+                      schema:
+                        type: array
+                        items:
+                          $ref: "#/components/schemas/Pet"
+            post:
+              requestBody:
+                required: true
+                content:
+                  application/json:
+                    schema:
+                      $ref: "#/components/schemas/Pet"
+              responses:
+                "201":
+                  description: Created
+        components:
+          schemas:
+            Pet:
+              type: object
+              required: [name]
+              properties:
+                name:
+                  type: string
+                status:
+                  type: string
+                  enum: [available, sold]
+    "##};
+
+    #[test]
+    fn identical_specs_have_no_changes() {
+        let diff = diff(BASE, BASE);
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.to_markdown(), "# API changelog\n\nNo changes.\n");
+    }
+
+    #[test]
+    fn ignores_renamed_components() {
+        let renamed = BASE
+            .replace("schemas/Pet\"", "schemas/Animal\"")
+            .replace("    Pet:", "    Animal:");
+
+        assert!(
+            diff(BASE, &renamed).is_empty(),
+            "{:#?}",
+            diff(BASE, &renamed)
+        );
+    }
+
+    #[test]
+    fn reports_added_and_removed_operations() {
+        let new = BASE.replace("    post:", "    put:");
+        let diff = diff(BASE, &new);
+
+        assert_eq!(
+            diff.changes
+                .iter()
+                .map(|c| (c.operation.as_str(), c.breaking, c.kind.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("PUT /pets", false, ChangeKind::OperationAdded),
+                ("POST /pets", true, ChangeKind::OperationRemoved),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_parameter_changes() {
+        let new = BASE.replace(
+            "        - name: limit\n          in: query\n",
+            "        - name: cursor\n          in: query\n          required: true\n",
+        );
+        let diff = diff(BASE, &new);
+
+        assert_eq!(
+            diff.changes,
+            vec![
+                Change {
+                    operation: "GET /pets".to_string(),
+                    breaking: true,
+                    kind: ChangeKind::ParameterAdded {
+                        name: "cursor".to_string(),
+                        r#in: "query".to_string(),
+                        required: true,
+                    },
+                },
+                Change {
+                    operation: "GET /pets".to_string(),
+                    breaking: true,
+                    kind: ChangeKind::ParameterRemoved {
+                        name: "limit".to_string(),
+                        r#in: "query".to_string(),
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_property_changes_by_direction() {
+        let new = BASE.replace(
+            "        name:\n          type: string\n",
+            "        name:\n          type: string\n        age:\n          type: integer\n",
+        );
+        let diff = diff(BASE, &new);
+
+        // An optional property is safe to add to both requests and responses
+        assert_eq!(diff.changes.len(), 2);
+        assert!(!diff.has_breaking_changes());
+
+        let new = BASE.replace("required: [name]", "required: [name, status]");
+        let diff = self::diff(BASE, &new);
+
+        let breaking = diff
+            .changes
+            .iter()
+            .map(|c| (c.operation.as_str(), c.breaking))
+            .collect::<Vec<_>>();
+        assert_eq!(breaking, vec![("GET /pets", false), ("POST /pets", true)]);
+    }
+
+    #[test]
+    fn reports_enum_changes() {
+        let new = BASE.replace("enum: [available, sold]", "enum: [available, pending]");
+        let diff = diff(BASE, &new);
+
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff.changes.iter().all(|c| c.breaking));
+        assert_eq!(
+            diff.changes[0].kind,
+            ChangeKind::EnumChanged {
+                location: "property `status` in items of `200` response (`application/json`)"
+                    .to_string(),
+                added: vec!["pending".to_string()],
+                removed: vec!["sold".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn reports_deprecations_as_non_breaking() {
+        let new = BASE.replace("    get:\n", "    get:\n      deprecated: true\n");
+        let diff = diff(BASE, &new);
+
+        assert_eq!(
+            diff.changes,
+            vec![Change {
+                operation: "GET /pets".to_string(),
+                breaking: false,
+                kind: ChangeKind::DeprecationChanged {
+                    location: String::new(),
+                    deprecated: true,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn renders_markdown_grouped_by_operation() {
+        let new = BASE
+            .replace("    get:\n", "    get:\n      deprecated: true\n")
+            .replace("    post:", "    put:");
+
+        assert_eq!(
+            diff(BASE, &new).to_markdown(),
+            indoc! {"
+                # API changelog
+
+                ## Breaking changes
+
+                ### `POST /pets`
+
+                - Removed operation
+
+                ## Non-breaking changes
+
+                ### `GET /pets`
+
+                - Deprecated operation
+
+                ### `PUT /pets`
+
+                - Added operation
+            "}
+        );
+    }
+}
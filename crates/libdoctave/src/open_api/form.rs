@@ -0,0 +1,407 @@
+//! Request body schemas flattened into a list of form fields, so that an
+//! interactive API console can build its form without walking the schemas.
+//!
+//! Fields are listed depth first, so nested fields come right after the field
+//! they belong to. Paths use dots for object properties, `[]` for array items,
+//! and `*` for the values of free-form maps, e.g. `users[].tags.*`.
+use serde::Serialize;
+use serde_json::Value;
+
+use super::model::{Schema, SchemaKind, Type};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormField {
+    /// Where the value goes in the body. Empty for the body itself.
+    pub path: String,
+    pub kind: FormFieldKind,
+    pub type_name: String,
+    pub required: bool,
+    pub nullable: bool,
+    /// The allowed values, if the schema has an `enum`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub depth: usize,
+    /// The schemas to choose from, for `one_of` and `any_of` fields
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<FormVariant>,
+    /// The component a `recursive` field refers back to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormFieldKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    File,
+    Object,
+    Array,
+    /// Values of properties not listed in the schema, from `additionalProperties`
+    Map,
+    OneOf,
+    AnyOf,
+    /// Refers back to a schema it's nested in. Its fields aren't listed again.
+    Recursive,
+    Any,
+}
+
+/// One of the schemas of a `one_of` or `any_of` field. Its fields have the
+/// same paths as they would without the choice.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormVariant {
+    pub title: String,
+    pub fields: Vec<FormField>,
+}
+
+impl Schema {
+    /// Flattens the schema into form fields. If the schema is an object, its
+    /// properties are listed at the top level, the same way the request body
+    /// is shown on the page.
+    pub fn to_form_model(&self) -> Vec<FormField> {
+        let mut fields = vec![];
+        push_fields(self, "", 0, &mut fields);
+        fields
+    }
+}
+
+/// Pushes the properties of an object schema, or the schema itself if it's
+/// not an object.
+fn push_fields(schema: &Schema, path: &str, depth: usize, fields: &mut Vec<FormField>) {
+    match &schema.schema_kind {
+        SchemaKind::SingleType(Type::Object {
+            properties,
+            additional_properties,
+            ..
+        }) if recursive_component(schema).is_none() => {
+            for property in properties {
+                let name = property.title.as_deref().unwrap_or_default();
+                push_field(property, &join(path, name), depth, fields);
+            }
+
+            if let Some(values) = additional_properties {
+                fields.push(FormField {
+                    kind: FormFieldKind::Map,
+                    required: false,
+                    ..field(values, join(path, "*"), depth)
+                });
+            }
+        }
+        _ => push_field(schema, path, depth, fields),
+    }
+}
+
+fn push_field(schema: &Schema, path: &str, depth: usize, fields: &mut Vec<FormField>) {
+    if let Some(component_name) = recursive_component(schema) {
+        fields.push(FormField {
+            kind: FormFieldKind::Recursive,
+            // The description of a recursive schema only says that it's recursive
+            description: None,
+            component_name: Some(component_name),
+            ..field(schema, path.to_string(), depth)
+        });
+        return;
+    }
+
+    fields.push(field(schema, path.to_string(), depth));
+
+    match &schema.schema_kind {
+        SchemaKind::SingleType(Type::Object { .. }) => {
+            push_fields(schema, path, depth + 1, fields);
+        }
+        SchemaKind::SingleType(Type::Array {
+            items: Some(items), ..
+        }) => {
+            push_field(items, &format!("{}[]", path), depth + 1, fields);
+        }
+        SchemaKind::OneOf { schemas } | SchemaKind::AnyOf { schemas } => {
+            let variants = schemas
+                .iter()
+                .enumerate()
+                .map(|(index, variant)| {
+                    let mut variant_fields = vec![];
+                    push_fields(variant, path, depth + 1, &mut variant_fields);
+
+                    FormVariant {
+                        title: variant_title(variant, index),
+                        fields: variant_fields,
+                    }
+                })
+                .collect();
+
+            if let Some(field) = fields.last_mut() {
+                field.variants = variants;
+            }
+        }
+        SchemaKind::AllOf { schemas } => {
+            for schema in schemas {
+                push_fields(schema, path, depth + 1, fields);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn field(schema: &Schema, path: String, depth: usize) -> FormField {
+    FormField {
+        path,
+        kind: kind(schema),
+        type_name: schema.type_name(),
+        required: schema.required.unwrap_or(false),
+        nullable: schema.nullable,
+        options: options(schema),
+        default: schema.default.clone(),
+        description: schema.description.clone(),
+        depth,
+        variants: vec![],
+        component_name: None,
+    }
+}
+
+fn kind(schema: &Schema) -> FormFieldKind {
+    match &schema.schema_kind {
+        SchemaKind::SingleType(t) => match t {
+            Type::String { .. } => FormFieldKind::String,
+            Type::Integer { .. } => FormFieldKind::Integer,
+            Type::Number { .. } => FormFieldKind::Number,
+            Type::Boolean => FormFieldKind::Boolean,
+            Type::File => FormFieldKind::File,
+            Type::Object { .. } => FormFieldKind::Object,
+            Type::Array { .. } => FormFieldKind::Array,
+        },
+        SchemaKind::OneOf { .. } => FormFieldKind::OneOf,
+        SchemaKind::AnyOf { .. } => FormFieldKind::AnyOf,
+        SchemaKind::AllOf { .. } => FormFieldKind::Object,
+        SchemaKind::Not { .. } | SchemaKind::Any { .. } => FormFieldKind::Any,
+    }
+}
+
+fn options(schema: &Schema) -> Vec<String> {
+    match &schema.schema_kind {
+        SchemaKind::SingleType(Type::String { enumeration, .. }) => {
+            enumeration.iter().flatten().cloned().collect()
+        }
+        SchemaKind::SingleType(Type::Integer { enumeration, .. }) => enumeration
+            .iter()
+            .flatten()
+            .map(|e| e.to_string())
+            .collect(),
+        SchemaKind::SingleType(Type::Number { enumeration, .. }) => enumeration
+            .iter()
+            .flatten()
+            .map(|e| e.to_string())
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn recursive_component(schema: &Schema) -> Option<String> {
+    schema
+        .metadata
+        .as_ref()
+        .and_then(|m| m.recursive.as_ref())
+        .map(|name| name.to_string())
+}
+
+fn variant_title(schema: &Schema, index: usize) -> String {
+    schema
+        .title
+        .clone()
+        .or_else(|| {
+            schema
+                .metadata
+                .as_ref()
+                .and_then(|m| m.component_name.as_ref())
+                .map(|name| name.to_string())
+        })
+        .unwrap_or_else(|| format!("Option {}", index + 1))
+}
+
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_api::model::Components;
+
+    const SPEC: &str = indoc! {r#"
+    openapi: 3.0.3
+    info:
+      title: Forms
+      version: 1.0.0
+    paths: {}
+    components:
+      schemas:
+        Team:
+          type: object
+          properties:
+            name:
+              type: string
+            members:
+              type: array
+              items:
+                $ref: '#/components/schemas/Member'
+        Member:
+          type: object
+          properties:
+            team:
+              $ref: '#/components/schemas/Team'
+        Body:
+    "#};
+
+    /// Parses the schema as the `Body` component of a spec
+    fn form(schema: &str) -> Vec<FormField> {
+        let mut spec = SPEC.to_string();
+        for line in schema.lines() {
+            spec.push_str(&format!("      {}\n", line));
+        }
+
+        let spec = openapi_parser::openapi30::parser::parse_yaml(&spec).unwrap();
+        let components = Components::from_parsed(spec.components.unwrap()).unwrap();
+
+        components.schemas["Body"].to_form_model()
+    }
+
+    fn paths(fields: &[FormField]) -> Vec<(&str, usize)> {
+        fields.iter().map(|f| (f.path.as_str(), f.depth)).collect()
+    }
+
+    #[test]
+    fn flattens_nested_objects_and_arrays() {
+        let fields = form(indoc! {r#"
+        type: object
+        required: [name]
+        properties:
+          name:
+            type: string
+            description: The name
+          tags:
+            type: array
+            items:
+              type: string
+          users:
+            type: array
+            items:
+              type: object
+              properties:
+                id:
+                  type: integer
+                  default: 1
+        "#});
+
+        assert_eq!(
+            paths(&fields),
+            vec![
+                ("name", 0),
+                ("tags", 0),
+                ("tags[]", 1),
+                ("users", 0),
+                ("users[]", 1),
+                ("users[].id", 2),
+            ]
+        );
+
+        assert_eq!(fields[0].kind, FormFieldKind::String);
+        assert!(fields[0].required);
+        assert_eq!(fields[0].description.as_deref(), Some("The name"));
+        assert!(!fields[1].required);
+        assert_eq!(fields[1].kind, FormFieldKind::Array);
+        assert_eq!(fields[5].kind, FormFieldKind::Integer);
+        assert_eq!(fields[5].default, Some(serde_json::json!(1)));
+    }
+
+    #[test]
+    fn lists_enum_options_and_nullable_fields() {
+        let fields = form(indoc! {r#"
+        type: object
+        properties:
+          role:
+            type: string
+            nullable: true
+            enum: [admin, member]
+        "#});
+
+        assert_eq!(fields[0].options, vec!["admin", "member"]);
+        assert!(fields[0].nullable);
+        assert_eq!(fields[0].type_name, "string or null");
+    }
+
+    #[test]
+    fn marks_additional_properties_as_a_map() {
+        let fields = form(indoc! {r#"
+        type: object
+        properties:
+          labels:
+            type: object
+            additionalProperties:
+              type: string
+        additionalProperties:
+          type: integer
+        "#});
+
+        assert_eq!(
+            paths(&fields),
+            vec![("labels", 0), ("labels.*", 1), ("*", 0)]
+        );
+        assert_eq!(fields[1].kind, FormFieldKind::Map);
+        assert_eq!(fields[1].type_name, "string");
+        assert_eq!(fields[2].kind, FormFieldKind::Map);
+        assert_eq!(fields[2].type_name, "int");
+    }
+
+    #[test]
+    fn lists_one_of_schemas_as_variants() {
+        let fields = form(indoc! {r#"
+        type: object
+        properties:
+          pet:
+            oneOf:
+              - title: Cat
+                type: object
+                properties:
+                  meows:
+                    type: boolean
+              - type: string
+        "#});
+
+        assert_eq!(paths(&fields), vec![("pet", 0)]);
+        assert_eq!(fields[0].kind, FormFieldKind::OneOf);
+
+        let variants = &fields[0].variants;
+        assert_eq!(variants[0].title, "Cat");
+        assert_eq!(paths(&variants[0].fields), vec![("pet.meows", 1)]);
+        assert_eq!(variants[1].title, "Option 2");
+        assert_eq!(paths(&variants[1].fields), vec![("pet", 1)]);
+        assert_eq!(variants[1].fields[0].kind, FormFieldKind::String);
+    }
+
+    #[test]
+    fn cuts_recursion_with_a_marker() {
+        let fields = form(indoc! {r#"
+        type: object
+        properties:
+          team:
+            $ref: '#/components/schemas/Team'
+        "#});
+
+        let recursive = fields
+            .iter()
+            .find(|f| f.kind == FormFieldKind::Recursive)
+            .unwrap();
+
+        assert_eq!(recursive.component_name.as_deref(), Some("Team"));
+        assert!(fields.iter().all(|f| f.depth < 6), "{:#?}", fields);
+    }
+}
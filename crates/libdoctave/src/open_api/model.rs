@@ -1,13 +1,16 @@
 use super::ast::PageAst;
+use super::augmentation::Augmentation;
+use super::form::FormField;
 use indexmap::IndexMap;
 use openapi_parser::openapi30::schemas::parameter::ParameterKind;
 use serde::Serialize;
 use serde_json::{json, Value};
 
-use crate::{markdown, page_kind::OutgoingLink, render_context::RenderContext, Error};
+use crate::{markdown, page_kind::LinkSet, render_context::RenderContext, Error};
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Debug, Default, Clone)]
 pub(crate) struct Components {
@@ -35,6 +38,66 @@ pub(crate) struct Tag {
     pub description: Option<String>,
 }
 
+/// Settings for an operation or a tag, given in its `x-doctave` extension.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct DoctaveExtension {
+    /// Leaves the operation or tag out of the docs
+    pub hide: bool,
+    /// Shown instead of the operation's summary
+    pub summary_override: Option<String>,
+    /// The tag to list the operation under, instead of its own tags
+    pub group: Option<String>,
+    /// Sorts operations within a tag, and tags within a spec. Ones without
+    /// an order come last, in the order of the spec.
+    pub order: Option<i64>,
+}
+
+impl DoctaveExtension {
+    pub(crate) fn from_parsed(
+        extensions: &openapi_parser::Map<openapi_parser::String, openapi_parser::Value>,
+    ) -> Self {
+        let mut ext = Self::default();
+
+        if let Some(openapi_parser::Value::Object(map)) = extensions.get("x-doctave") {
+            for (key, val) in map {
+                match (key.as_str(), val) {
+                    ("hide", openapi_parser::Value::Bool(hide)) => ext.hide = *hide,
+                    ("summary_override", openapi_parser::Value::String(summary)) => {
+                        ext.summary_override = Some(summary.to_string())
+                    }
+                    ("group", openapi_parser::Value::String(group)) => {
+                        ext.group = Some(group.to_string())
+                    }
+                    ("order", openapi_parser::Value::Number(order)) => ext.order = order.as_int(),
+                    _ => {}
+                }
+            }
+        }
+
+        ext
+    }
+
+    /// Whether the operation is listed under the tag, taking its `group`
+    /// into account.
+    pub(crate) fn lists_under(&self, operation: &openapi_parser::Operation, tag: &str) -> bool {
+        match &self.group {
+            Some(group) => group == tag,
+            None => operation.tags.iter().any(|t| t == tag),
+        }
+    }
+
+    /// Applies the overrides to an operation, before it's converted into the
+    /// model used for rendering.
+    pub(crate) fn apply(&self, operation: &mut openapi_parser::Operation) {
+        if let Some(summary) = &self.summary_override {
+            operation.summary = Some(summary.as_str().into());
+        }
+        if let Some(group) = &self.group {
+            operation.tags = vec![group.as_str().into()];
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub(crate) struct Page {
     pub uri_path: String,
@@ -57,49 +120,77 @@ impl Page {
         })
     }
 
-    pub fn outgoing_links(&self, ctx: &mut RenderContext) -> crate::Result<Vec<OutgoingLink>> {
-        ctx.with_url_base_by_page_uri(&self.uri_path);
-
-        // Step 1: Concat all descriptions together.
-        let mut all_markdown = String::new();
-
-        fn concat(out: &mut String, s: &str) {
-            out.push_str(s);
-            out.push_str("\n\n");
-        }
-
-        for op in &self.operations {
-            if let Some(desc) = op.description.as_deref() {
-                concat(&mut all_markdown, desc);
-            }
-
-            for param in &op.query_parameters {
-                if let Some(desc) = param.description.as_deref() {
-                    concat(&mut all_markdown, desc);
+    /// Builds the page once and gathers the internal and asset links from
+    /// the descriptions of its operations and their parameters, along with
+    /// the external links from those and the tag description.
+    pub fn verification_links(&self, ctx: &mut RenderContext) -> crate::Result<LinkSet> {
+        // The AST is built _without expanding relative links_, so that errors
+        // show the URI as it was written. Links are expanded once gathered.
+        ctx.relative_url_base = None;
+        let ast = self.ast(ctx)?;
+
+        let mut descriptions = vec![];
+
+        for (op, op_ast) in self.operations.iter().zip(&ast.operations) {
+            descriptions.extend(description_node(
+                op.description.as_deref(),
+                op_ast.description_ast.as_ref(),
+                ctx,
+            )?);
+
+            for (params, param_asts) in [
+                (&op.query_parameters, &op_ast.query_params),
+                (&op.header_parameters, &op_ast.header_params),
+                (&op.path_parameters, &op_ast.path_params),
+                (&op.cookie_parameters, &op_ast.cookie_params),
+            ] {
+                for (param, param_ast) in params.iter().zip(param_asts) {
+                    descriptions.extend(description_node(
+                        param.description.as_deref(),
+                        param_ast.description_ast.as_ref(),
+                        ctx,
+                    )?);
                 }
             }
+        }
 
-            for param in &op.header_parameters {
-                if let Some(desc) = param.description.as_deref() {
-                    concat(&mut all_markdown, desc);
-                }
-            }
+        let assets = descriptions
+            .iter()
+            .flat_map(|node| markdown::parser::asset_links_in_ast(node, ctx))
+            .collect();
 
-            for param in &op.path_parameters {
-                if let Some(desc) = param.description.as_deref() {
-                    concat(&mut all_markdown, desc);
-                }
-            }
+        let external = descriptions
+            .iter()
+            .copied()
+            .chain(ast.tag.description_ast.as_ref())
+            .flat_map(|node| markdown::parser::external_links_in_ast(node, ctx))
+            .collect();
 
-            for param in &op.cookie_parameters {
-                if let Some(desc) = param.description.as_deref() {
-                    concat(&mut all_markdown, desc);
-                }
-            }
-        }
+        ctx.with_url_base_by_page_uri(&self.uri_path);
+        let internal = descriptions
+            .iter()
+            .flat_map(|node| markdown::parser::links_in_ast(node, ctx))
+            .collect();
+
+        Ok(LinkSet {
+            internal,
+            assets,
+            external,
+        })
+    }
+}
 
-        // Step 2: Parse it into Markdown ast
-        markdown::parser::extract_links(&all_markdown, ctx)
+/// The rendered AST of a description. Rendering leaves out descriptions that
+/// fail to parse, so those are parsed again to report the error.
+fn description_node<'a>(
+    source: Option<&str>,
+    ast: Option<&'a markdown::Node>,
+    ctx: &RenderContext,
+) -> crate::Result<Option<&'a markdown::Node>> {
+    match (source, ast) {
+        (_, Some(node)) => Ok(Some(node)),
+        (Some(source), None) => markdown::ast_mdx(source, ctx).map(|_| None),
+        (None, None) => Ok(None),
     }
 }
 
@@ -131,6 +222,94 @@ pub(crate) enum SecurityRequirement {
     },
 }
 
+impl SecurityRequirement {
+    /// Expands a requirement for the named scheme into the scheme's details.
+    /// Returns `None` if the spec doesn't define the scheme.
+    fn from_parsed(
+        key: &openapi_parser::String,
+        scopes: &[openapi_parser::String],
+        security_schemes: &Option<
+            openapi_parser::Map<openapi_parser::String, openapi_parser::SecurityScheme>,
+        >,
+    ) -> Option<Self> {
+        let scheme = security_schemes.as_ref()?.get(key)?;
+        let description = scheme.description.as_ref().map(|d| d.to_string());
+
+        let requirement = match &scheme.kind {
+            openapi_parser::SecuritySchemeKind::Http(kind) => SecurityRequirement::Http {
+                name: key.to_string(),
+                description,
+                scheme: kind.scheme.to_string(),
+                bearer_format: kind.bearer_format.as_ref().map(|v| v.to_string()),
+            },
+            openapi_parser::SecuritySchemeKind::ApiKey(kind) => SecurityRequirement::ApiKey {
+                name: key.to_string(),
+                description,
+                key_location: kind.r#in.to_string(),
+                key_name: kind.name.to_string(),
+            },
+            openapi_parser::SecuritySchemeKind::OpenIdConnect(kind) => {
+                SecurityRequirement::OpenID {
+                    name: key.to_string(),
+                    description,
+                    open_id_connect_url: kind.open_id_connect_url.to_string(),
+                }
+            }
+            openapi_parser::SecuritySchemeKind::OAuth2(kind) => {
+                let mut all_flows = vec![];
+
+                if let Some(flow) = &kind.flows.implicit {
+                    all_flows.push(OAuth2Flow::from(flow.clone()));
+                }
+
+                if let Some(flow) = &kind.flows.password {
+                    all_flows.push(OAuth2Flow::from(flow.clone()));
+                }
+
+                if let Some(flow) = &kind.flows.client_credentials {
+                    all_flows.push(OAuth2Flow::from(flow.clone()));
+                }
+
+                if let Some(flow) = &kind.flows.authorization_code {
+                    all_flows.push(OAuth2Flow::from(flow.clone()));
+                }
+
+                let mut required_scopes = vec![];
+                let mut all_scopes = HashSet::new();
+
+                for flow in &all_flows {
+                    for scope in flow.scopes() {
+                        all_scopes.insert((scope.0.to_owned(), scope.1.to_owned()));
+                    }
+                }
+
+                let mut all_scopes = all_scopes.into_iter().collect::<Vec<_>>();
+                all_scopes.sort_by(|a, b| a.0.cmp(&b.0));
+
+                for scope in scopes {
+                    if all_flows
+                        .iter()
+                        .find_map(|f| f.scopes().get(&scope.to_string()))
+                        .is_some()
+                    {
+                        required_scopes.push(scope.to_string());
+                    }
+                }
+
+                SecurityRequirement::OAuth2 {
+                    name: key.to_string(),
+                    description,
+                    required_scopes,
+                    all_scopes,
+                    flows: all_flows,
+                }
+            }
+        };
+
+        Some(requirement)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub(crate) enum OAuth2Flow {
     Implicit {
@@ -277,7 +456,12 @@ pub(crate) struct Operation {
     pub code_examples: Vec<Example>,
     pub summary: Option<String>,
     pub anchor_tag: String,
+    /// The anchor from the operation's summary, when the spec uses
+    /// `uri_strategy: operation_id` and it's different from `anchor_tag`.
+    /// Kept so that links to the old anchor still reach the operation.
+    pub previous_anchor_tag: Option<String>,
     pub description: Option<String>,
+    pub deprecated: bool,
     pub query_parameters: Vec<Parameter>,
     pub header_parameters: Vec<Parameter>,
     pub path_parameters: Vec<Parameter>,
@@ -289,7 +473,16 @@ pub(crate) struct Operation {
     /// By the spec, the operation has to be authorized by _one_
     /// of these.
     pub security_requirements: Vec<SecurityRequirement>,
+    /// The alternative ways to authorize the operation, each listing the
+    /// requirements that have to be met together. Falls back to the
+    /// security of the whole spec if the operation doesn't set its own.
+    pub auth: Vec<Vec<SecurityRequirement>>,
     pub servers: Vec<Server>,
+    /// Extensions of the operation that have text values, like `x-sunset`
+    pub text_extensions: BTreeMap<String, String>,
+    /// Hand-written Markdown for the operation's page, from the file named
+    /// after its operationId
+    pub augmentation: Option<Augmentation>,
 }
 
 impl Operation {
@@ -301,6 +494,7 @@ impl Operation {
         security_schemes: &Option<
             openapi_parser::Map<openapi_parser::String, openapi_parser::SecurityScheme>,
         >,
+        global_security: &[openapi_parser::SecurityRequirement],
     ) -> crate::Result<Self> {
         let code_examples = code_examples_from_parsed(&spec);
         let tags = spec
@@ -359,95 +553,23 @@ impl Operation {
             .map(RequestBody::from_parsed)
             .transpose()?;
 
-        let security_requirements = {
-            let mut final_requirements = vec![];
-            let requirements = spec.security;
-            for req in requirements {
-                for (key, scopes) in req.requirements {
-                    if let Some(scheme) = security_schemes.as_ref().and_then(|s| s.get(&key)) {
-                        match &scheme.kind {
-                            openapi_parser::SecuritySchemeKind::Http(kind) => {
-                                final_requirements.push(SecurityRequirement::Http {
-                                    name: key.to_string(),
-                                    description: description.clone(),
-                                    scheme: kind.scheme.to_string(),
-                                    bearer_format: kind
-                                        .bearer_format
-                                        .as_ref()
-                                        .map(|v| v.to_string()),
-                                });
-                            }
-                            openapi_parser::SecuritySchemeKind::ApiKey(kind) => {
-                                final_requirements.push(SecurityRequirement::ApiKey {
-                                    name: key.to_string(),
-                                    description: description.clone(),
-                                    key_location: kind.r#in.to_string(),
-                                    key_name: kind.name.to_string(),
-                                });
-                            }
-                            openapi_parser::SecuritySchemeKind::OpenIdConnect(kind) => {
-                                final_requirements.push(SecurityRequirement::OpenID {
-                                    name: key.to_string(),
-                                    description: description.clone(),
-                                    open_id_connect_url: kind.open_id_connect_url.to_string(),
-                                });
-                            }
-                            openapi_parser::SecuritySchemeKind::OAuth2(kind) => {
-                                let mut all_flows = vec![];
-
-                                if let Some(flow) = &kind.flows.implicit {
-                                    all_flows.push(OAuth2Flow::from(flow.clone()));
-                                }
-
-                                if let Some(flow) = &kind.flows.password {
-                                    all_flows.push(OAuth2Flow::from(flow.clone()));
-                                }
-
-                                if let Some(flow) = &kind.flows.client_credentials {
-                                    all_flows.push(OAuth2Flow::from(flow.clone()));
-                                }
-
-                                if let Some(flow) = &kind.flows.authorization_code {
-                                    all_flows.push(OAuth2Flow::from(flow.clone()));
-                                }
-
-                                let mut required_scopes = vec![];
-                                let mut all_scopes = HashSet::new();
-
-                                for flow in &all_flows {
-                                    for scope in flow.scopes() {
-                                        all_scopes.insert((scope.0.to_owned(), scope.1.to_owned()));
-                                    }
-                                }
-
-                                let mut all_scopes = all_scopes.into_iter().collect::<Vec<_>>();
-                                all_scopes.sort_by(|a, b| a.0.cmp(&b.0));
-
-                                for scope in scopes {
-                                    if all_flows
-                                        .iter()
-                                        .find_map(|f| f.scopes().get(&scope.to_string()))
-                                        .is_some()
-                                    {
-                                        required_scopes.push(scope.into());
-                                    }
-                                }
-
-                                final_requirements.push(SecurityRequirement::OAuth2 {
-                                    name: key.to_string(),
-                                    description: description.clone(),
-                                    required_scopes,
-                                    all_scopes,
-                                    flows: all_flows,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
+        let auth = spec
+            .security
+            .as_deref()
+            .unwrap_or(global_security)
+            .iter()
+            .filter_map(|req| {
+                // Alternatives using an undefined scheme can't be satisfied
+                req.requirements
+                    .iter()
+                    .map(|(key, scopes)| {
+                        SecurityRequirement::from_parsed(key, scopes, security_schemes)
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Vec<_>>();
 
-            final_requirements
-        };
+        let security_requirements = auth.iter().flatten().cloned().collect();
 
         let servers = spec
             .servers
@@ -455,6 +577,15 @@ impl Operation {
             .map(Server::from_parsed)
             .collect::<Vec<_>>();
 
+        let text_extensions = spec
+            .extensions
+            .iter()
+            .filter_map(|(key, value)| match value {
+                openapi_parser::Value::String(text) => Some((key.to_string(), text.to_string())),
+                _ => None,
+            })
+            .collect();
+
         Ok(Self {
             method,
             route_pattern,
@@ -463,7 +594,9 @@ impl Operation {
             code_examples,
             summary: spec.summary.map(|s| s.to_string()),
             anchor_tag,
+            previous_anchor_tag: None,
             description,
+            deprecated: spec.deprecated.unwrap_or(false),
             query_parameters,
             header_parameters,
             path_parameters,
@@ -471,38 +604,101 @@ impl Operation {
             request_body,
             responses,
             security_requirements,
+            auth,
             servers,
+            text_extensions,
+            augmentation: None,
         })
     }
 
+    /// The date the operation will be removed, read from the given extension.
+    pub fn sunset(&self, field: &str) -> Option<&str> {
+        self.text_extensions.get(field).map(String::as_str)
+    }
+
     pub fn from_parsed_webhook(
         spec: openapi_parser::Operation,
         security_schemes: &Option<
             openapi_parser::Map<openapi_parser::String, openapi_parser::SecurityScheme>,
         >,
+        global_security: &[openapi_parser::SecurityRequirement],
     ) -> crate::Result<Self> {
-        Operation::from_parsed(spec, "webhook".into(), "".into(), None, security_schemes)
+        Operation::from_parsed(
+            spec,
+            "webhook".into(),
+            "".into(),
+            None,
+            security_schemes,
+            global_security,
+        )
     }
 
-    pub fn identifier(&self) -> String {
-        format!(
-            "{}-{}-{}",
-            self.method,
-            self.route_pattern,
-            self.summary
-                .as_ref()
-                .map(|s| s.trim().replace(' ', "-"))
-                .unwrap_or_else(|| String::from("operation"))
-        )
+    /// Finds a schema in the operation by the path used in schema pointers,
+    /// e.g. `["responses", "200", "application/json", "0", "2"]`.
+    pub(crate) fn find_schema(&self, path: &[String]) -> Option<&Schema> {
+        let (schema, rest) = match path {
+            [kind, location, name, rest @ ..] if kind == "parameters" => {
+                let parameters = match location.as_str() {
+                    "header" => &self.header_parameters,
+                    "query" => &self.query_parameters,
+                    "path" => &self.path_parameters,
+                    "cookie" => &self.cookie_parameters,
+                    _ => return None,
+                };
+
+                let parameter = parameters.iter().find(|p| &p.name == name)?;
+
+                (parameter.schema.as_ref()?, rest)
+            }
+            [kind, media_type, index, rest @ ..] if kind == "request_body" => {
+                let content = &self.request_body.as_ref()?.content;
+
+                (find_media_type_schema(content, media_type, index)?, rest)
+            }
+            [kind, status, headers, name, rest @ ..]
+                if kind == "responses" && headers == "headers" =>
+            {
+                let response = self.responses.iter().find(|r| &r.status == status)?;
+                let header = response.headers.iter().find(|h| &h.name == name)?;
+
+                (&header.schema, rest)
+            }
+            [kind, status, media_type, index, rest @ ..] if kind == "responses" => {
+                let response = self.responses.iter().find(|r| &r.status == status)?;
+
+                (
+                    find_media_type_schema(&response.content, media_type, index)?,
+                    rest,
+                )
+            }
+            _ => return None,
+        };
+
+        schema.find_nested(rest)
     }
 }
 
+fn find_media_type_schema<'a>(
+    content: &'a [MediaType],
+    media_type: &str,
+    index: &str,
+) -> Option<&'a Schema> {
+    content
+        .iter()
+        .find(|m| m.name == media_type)?
+        .schemas
+        .get(index.parse::<usize>().ok()?)
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub(crate) struct Response {
     pub status: String,
     pub description: String,
     pub content: Vec<MediaType>,
     pub headers: Vec<Header>,
+    /// Whether the response comes from the spec's `default_responses`
+    /// instead of the operation
+    pub api_default: bool,
 }
 
 impl Response {
@@ -514,7 +710,7 @@ impl Response {
         let content = spec
             .content
             .into_iter()
-            .map(|(k, v)| MediaType::from_parsed(v, k.into()))
+            .map(|(k, v)| MediaType::from_parsed(v, k.into(), false))
             .collect::<crate::Result<Vec<_>>>()?;
         let headers = spec
             .headers
@@ -527,6 +723,7 @@ impl Response {
             description,
             content,
             headers,
+            api_default: false,
         })
     }
 }
@@ -552,6 +749,8 @@ impl Header {
             description: "".to_string(),
             file: Some(PathBuf::from("")),
             position: None,
+            owner: None,
+            suggestions: vec![],
         })?;
 
         Ok(Header {
@@ -731,7 +930,7 @@ impl Schema {
         }
     }
 
-    fn from_parsed(
+    pub(crate) fn from_parsed(
         spec: openapi_parser::Schema,
         deprecated: Option<bool>,
         title: Option<String>,
@@ -793,7 +992,7 @@ impl Schema {
                 if let Some(not) = spec.not {
                     SchemaKind::Not {
                         schemas: vec![Schema::from_parsed(
-                            *not,
+                            Arc::unwrap_or_clone(not),
                             deprecated,
                             None,
                             None,
@@ -822,6 +1021,17 @@ impl Schema {
         })
     }
 
+    /// Follows the indexes of nested schemas, as used in schema pointers.
+    pub(crate) fn find_nested(&self, path: &[String]) -> Option<&Schema> {
+        match path.split_first() {
+            None => Some(self),
+            Some((index, rest)) => self
+                .nested_schemas()
+                .get(index.parse::<usize>().ok()?)?
+                .find_nested(rest),
+        }
+    }
+
     pub fn is_nested(&self) -> bool {
         match &self.schema_kind {
             SchemaKind::SingleType(ref t) => match t {
@@ -861,6 +1071,21 @@ impl Schema {
         }
     }
 
+    /// Name of the component the schema comes from, e.g. `User` for
+    /// `$ref: '#/components/schemas/User'`. Arrays are named after their
+    /// items.
+    pub fn component_name(&self) -> Option<&str> {
+        match &self.schema_kind {
+            SchemaKind::SingleType(Type::Array {
+                items: Some(items), ..
+            }) => items.component_name(),
+            _ => self
+                .metadata
+                .as_ref()
+                .and_then(|m| m.component_name.as_deref()),
+        }
+    }
+
     pub fn is_object(&self) -> bool {
         matches!(
             self.schema_kind,
@@ -1024,6 +1249,8 @@ pub(crate) enum Type {
     Object {
         properties: Vec<Schema>,
         required: Vec<String>,
+        /// The schema of the values of properties not listed in `properties`
+        additional_properties: Option<Box<Schema>>,
     },
     Array {
         items: Option<Box<Schema>>,
@@ -1078,6 +1305,8 @@ impl Type {
                 let mut properties = vec![];
                 let mut required_props = vec![];
                 for (title, schema) in o.properties {
+                    let schema = Arc::unwrap_or_clone(schema);
+
                     if schema.required {
                         required_props.push(title.to_string());
                     }
@@ -1092,17 +1321,40 @@ impl Type {
                     )?);
                 }
 
+                let additional_properties = o
+                    .additional_properties
+                    .map(|s| {
+                        Schema::from_parsed(
+                            Arc::unwrap_or_clone(s),
+                            deprectated,
+                            None,
+                            Some(false),
+                            None,
+                            false,
+                        )
+                        .map(Box::new)
+                    })
+                    .transpose()?;
+
                 Ok(Type::Object {
                     properties,
                     required: required_props,
+                    additional_properties,
                 })
             }
             ExpSchemaKind::Array(a) => Ok(Type::Array {
                 items: a
                     .items
                     .map(|r| {
-                        Schema::from_parsed(*r, deprectated, None, Some(false), None, false)
-                            .map(Box::new)
+                        Schema::from_parsed(
+                            Arc::unwrap_or_clone(r),
+                            deprectated,
+                            None,
+                            Some(false),
+                            None,
+                            false,
+                        )
+                        .map(Box::new)
                     })
                     .transpose()?,
                 min_items: a.min_items.and_then(|m| m.as_int().map(|m| m as usize)),
@@ -1124,7 +1376,7 @@ impl RequestBody {
     pub fn from_parsed(spec: openapi_parser::RequestBody) -> crate::Result<Self> {
         let mut content = vec![];
         for (key, mediatype) in spec.content.into_iter() {
-            content.push(MediaType::from_parsed(mediatype, key.into())?);
+            content.push(MediaType::from_parsed(mediatype, key.into(), true)?);
         }
 
         Ok(RequestBody {
@@ -1140,12 +1392,19 @@ pub(crate) struct MediaType {
     pub name: String,
     pub schemas: Vec<Schema>,
     pub examples: Vec<Example>,
+    /// The schema flattened into form fields. Only built for request bodies.
+    pub form: Option<Vec<FormField>>,
+    /// The component schema of the content, which is lost when its fields
+    /// are flattened into `schemas`.
+    #[serde(skip)]
+    pub component_name: Option<String>,
 }
 
 impl MediaType {
     pub fn from_parsed(
         spec: openapi_parser::openapi30::schemas::media_type::MediaType,
         name: String,
+        with_form: bool,
     ) -> crate::Result<Self> {
         let schema = spec
             .schema
@@ -1188,8 +1447,26 @@ impl MediaType {
             }
         }
 
+        let form = if with_form {
+            Some(
+                schema
+                    .as_ref()
+                    .map(|s| s.to_form_model())
+                    .unwrap_or_default(),
+            )
+        } else {
+            None
+        };
+
+        let component_name = schema
+            .as_ref()
+            .and_then(|s| s.component_name())
+            .map(str::to_owned);
+
         Ok(MediaType {
             name,
+            form,
+            component_name,
             schemas: schema
                 .map(|s| {
                     // If the top most schema for the mediatype is an object, we want to flatten
@@ -1430,6 +1707,7 @@ fn code_examples_from_parsed(spec: &openapi_parser::Operation) -> Vec<Example> {
 mod test {
     use crate::open_api::OpenApi;
     use crate::page_kind::PageKind;
+    use crate::settings::UriStrategy;
 
     use super::*;
 
@@ -1756,8 +2034,16 @@ mod test {
         "##};
 
         let spec = openapi_parser::openapi30::parser::parse_yaml(base).unwrap();
-        let pages =
-            OpenApi::pages_from_parsed_spec(&spec, "openapi.yaml".into(), "/api".into()).unwrap();
+        let pages = OpenApi::pages_from_parsed_spec(
+            &spec,
+            "openapi.yaml".into(),
+            "/api".into(),
+            None,
+            &[],
+            UriStrategy::default(),
+            &Default::default(),
+        )
+        .unwrap();
 
         let tag_pages = pages
             .into_iter()
@@ -1,3 +1,113 @@
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use rust_decimal::Decimal;
+
+use super::model::Page;
+use crate::expressions::Value;
+
+/// A Markdown file from the project that replaces the generated overview
+/// page. Set with `overview_template` in the `open_api` settings.
+#[derive(Debug, Clone)]
+pub(crate) struct OverviewTemplate {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Variables available to expressions in an overview template:
+///
+/// - `@info`: the `info` object of the spec
+/// - `@servers`: list of servers
+/// - `@tags`: list of tags, with an `href` to the tag's page
+/// - `@security_schemes`: list of security schemes, each with its `name`
+pub(crate) fn template_globals(
+    spec: &openapi_parser::OpenAPI,
+    tag_pages: &[Page],
+) -> Vec<(String, Value)> {
+    let info = from_spec_value(spec.info.clone().into());
+
+    let servers = spec
+        .servers
+        .iter()
+        .map(|s| from_spec_value(s.clone().into()))
+        .collect();
+
+    let tags = tag_pages
+        .iter()
+        .map(|page| {
+            let href = if page.operations.is_empty() {
+                Value::Null
+            } else {
+                Value::String(page.uri_path.clone())
+            };
+
+            Value::Object(IndexMap::from([
+                ("name".to_string(), Value::String(page.tag.name.clone())),
+                (
+                    "description".to_string(),
+                    page.tag
+                        .description
+                        .clone()
+                        .map(Value::String)
+                        .unwrap_or(Value::Null),
+                ),
+                ("href".to_string(), href),
+            ]))
+        })
+        .collect();
+
+    let security_schemes = spec
+        .components
+        .iter()
+        .flat_map(|c| c.security_schemes.iter())
+        .map(|(name, scheme)| {
+            let mut object =
+                IndexMap::from([("name".to_string(), Value::String(name.to_string()))]);
+
+            if let Value::Object(fields) = from_spec_value(scheme.clone().into()) {
+                object.extend(fields);
+            }
+
+            Value::Object(object)
+        })
+        .collect();
+
+    vec![
+        ("info".to_string(), info),
+        ("servers".to_string(), Value::List(servers)),
+        ("tags".to_string(), Value::List(tags)),
+        (
+            "security_schemes".to_string(),
+            Value::List(security_schemes),
+        ),
+    ]
+}
+
+fn from_spec_value(value: openapi_parser::Value) -> Value {
+    use openapi_parser::{Number, Value as SpecValue};
+
+    match value {
+        SpecValue::Null => Value::Null,
+        SpecValue::String(s) => Value::String(s.to_string()),
+        SpecValue::Bool(b) => Value::Bool(b),
+        SpecValue::Number(Number::Int(i)) => Value::Number(i.into()),
+        SpecValue::Number(Number::Float(f)) => Decimal::try_from(f)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        SpecValue::Array(list) => Value::List(list.into_iter().map(from_spec_value).collect()),
+        SpecValue::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(k, v)| (k.to_string(), from_spec_value(v)))
+                .collect(),
+        ),
+        SpecValue::Mapping(map) => Value::Object(
+            map.into_iter()
+                .filter_map(|(k, v)| k.as_str().map(|k| (k.to_string(), from_spec_value(v))))
+                .collect(),
+        ),
+    }
+}
+
 pub(crate) fn build_parsed(
     info: &openapi_parser::Info,
     servers: &[openapi_parser::Server],
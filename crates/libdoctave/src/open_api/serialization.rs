@@ -0,0 +1,262 @@
+//! How parameters look on the wire, given their `style` and `explode`.
+//!
+//! Follows the style values table of the OpenAPI 3.0 spec. The value comes
+//! from the parameter's example, or is generated from its schema the same way
+//! as the request and response examples.
+//!
+//! Path parameters are shown as the path segment, query and cookie parameters
+//! as `name=value` pairs, and header parameters as `Name: value`.
+use itertools::Itertools;
+use serde_json::Value;
+
+use super::model::{Example, Parameter};
+
+/// The serialized example for the parameter, e.g. `filter[name]=foo` for a
+/// `deepObject` query parameter. `None` if the style doesn't apply to the
+/// value, or the parameter is described with `content` instead of a schema.
+pub(crate) fn serialized_example(parameter: &Parameter) -> Option<String> {
+    let schema = parameter.schema.as_ref()?;
+
+    // Styles don't apply to parameters with a media type
+    if schema.mediatype.is_some() {
+        return None;
+    }
+
+    let value = parameter
+        .example_string
+        .as_ref()
+        .and_then(|example| serde_json::from_str(example).ok())
+        .or_else(|| {
+            serde_json::from_str(&Example::autogenerate_from_v3_schema(schema).value).ok()
+        })?;
+
+    let style = style(parameter);
+    let explode = parameter.explode.unwrap_or(style == "form");
+    let serialized = serialize(&parameter.name, style, explode, &value)?;
+
+    if parameter.contained_in == "header" {
+        Some(format!("{}: {}", parameter.name, serialized))
+    } else {
+        Some(serialized)
+    }
+}
+
+/// The style of the parameter, defaulting to `form` for query and cookie
+/// parameters and to `simple` for path and header parameters.
+fn style(parameter: &Parameter) -> &str {
+    if !parameter.style.is_empty() {
+        return &parameter.style;
+    }
+
+    match parameter.contained_in.as_str() {
+        "query" | "cookie" => "form",
+        _ => "simple",
+    }
+}
+
+fn serialize(name: &str, style: &str, explode: bool, value: &Value) -> Option<String> {
+    match value {
+        Value::Array(items) if items.is_empty() => None,
+        Value::Array(items) => {
+            let items = items.iter().map(primitive).collect::<Vec<_>>();
+
+            match (style, explode) {
+                ("simple", _) => Some(items.join(",")),
+                ("form" | "spaceDelimited" | "pipeDelimited", true) => {
+                    Some(items.iter().map(|i| format!("{}={}", name, i)).join("&"))
+                }
+                ("form", false) => Some(format!("{}={}", name, items.join(","))),
+                ("spaceDelimited", false) => Some(format!("{}={}", name, items.join("%20"))),
+                ("pipeDelimited", false) => Some(format!("{}={}", name, items.join("|"))),
+                ("label", true) => Some(format!(".{}", items.join("."))),
+                ("label", false) => Some(format!(".{}", items.join(","))),
+                ("matrix", true) => Some(items.iter().map(|i| format!(";{}={}", name, i)).join("")),
+                ("matrix", false) => Some(format!(";{}={}", name, items.join(","))),
+                _ => None,
+            }
+        }
+        Value::Object(map) if map.is_empty() => None,
+        Value::Object(map) => {
+            let pairs = map
+                .iter()
+                .map(|(key, value)| (key, primitive(value)))
+                .collect::<Vec<_>>();
+            let flat = pairs.iter().map(|(k, v)| format!("{},{}", k, v)).join(",");
+
+            match (style, explode) {
+                ("simple", true) => {
+                    Some(pairs.iter().map(|(k, v)| format!("{}={}", k, v)).join(","))
+                }
+                ("simple", false) => Some(flat),
+                ("form", true) => Some(pairs.iter().map(|(k, v)| format!("{}={}", k, v)).join("&")),
+                ("form", false) => Some(format!("{}={}", name, flat)),
+                ("deepObject", _) => Some(
+                    pairs
+                        .iter()
+                        .map(|(k, v)| format!("{}[{}]={}", name, k, v))
+                        .join("&"),
+                ),
+                ("label", true) => Some(format!(
+                    ".{}",
+                    pairs.iter().map(|(k, v)| format!("{}={}", k, v)).join(".")
+                )),
+                ("label", false) => Some(format!(".{}", flat)),
+                ("matrix", true) => {
+                    Some(pairs.iter().map(|(k, v)| format!(";{}={}", k, v)).join(""))
+                }
+                ("matrix", false) => Some(format!(";{}={}", name, flat)),
+                _ => None,
+            }
+        }
+        value => {
+            let value = primitive(value);
+
+            match style {
+                "simple" => Some(value),
+                "form" => Some(format!("{}={}", name, value)),
+                "label" => Some(format!(".{}", value)),
+                "matrix" => Some(format!(";{}={}", name, value)),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Strings without quotes, and anything nested as compact JSON
+fn primitive(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_api::model::Schema;
+    use serde_json::json;
+
+    fn serialize(name: &str, style: &str, explode: bool, value: Value) -> Option<String> {
+        super::serialize(name, style, explode, &value)
+    }
+
+    #[test]
+    fn primitives() {
+        assert_eq!(serialize("id", "simple", false, json!(5)).unwrap(), "5");
+        assert_eq!(serialize("id", "form", true, json!("a")).unwrap(), "id=a");
+        assert_eq!(serialize("id", "label", false, json!(5)).unwrap(), ".5");
+        assert_eq!(serialize("id", "matrix", false, json!(5)).unwrap(), ";id=5");
+        assert_eq!(serialize("id", "deepObject", true, json!(5)), None);
+    }
+
+    #[test]
+    fn arrays() {
+        let tags = || json!(["a", "b"]);
+
+        assert_eq!(serialize("tags", "simple", false, tags()).unwrap(), "a,b");
+        assert_eq!(
+            serialize("tags", "form", true, tags()).unwrap(),
+            "tags=a&tags=b"
+        );
+        assert_eq!(
+            serialize("tags", "form", false, tags()).unwrap(),
+            "tags=a,b"
+        );
+        assert_eq!(
+            serialize("tags", "spaceDelimited", false, tags()).unwrap(),
+            "tags=a%20b"
+        );
+        assert_eq!(
+            serialize("tags", "pipeDelimited", false, tags()).unwrap(),
+            "tags=a|b"
+        );
+        assert_eq!(serialize("tags", "label", true, tags()).unwrap(), ".a.b");
+        assert_eq!(
+            serialize("tags", "matrix", true, tags()).unwrap(),
+            ";tags=a;tags=b"
+        );
+        assert_eq!(serialize("tags", "deepObject", true, tags()), None);
+    }
+
+    #[test]
+    fn objects() {
+        let filter = || json!({ "name": "foo", "age": 3 });
+
+        assert_eq!(
+            serialize("filter", "deepObject", true, filter()).unwrap(),
+            "filter[name]=foo&filter[age]=3"
+        );
+        assert_eq!(
+            serialize("filter", "form", true, filter()).unwrap(),
+            "name=foo&age=3"
+        );
+        assert_eq!(
+            serialize("filter", "form", false, filter()).unwrap(),
+            "filter=name,foo,age,3"
+        );
+        assert_eq!(
+            serialize("filter", "simple", true, filter()).unwrap(),
+            "name=foo,age=3"
+        );
+        assert_eq!(
+            serialize("filter", "matrix", false, filter()).unwrap(),
+            ";filter=name,foo,age,3"
+        );
+    }
+    fn parameter(contained_in: &str, style: &str, explode: Option<bool>) -> Parameter {
+        Parameter {
+            name: "tags".to_string(),
+            contained_in: contained_in.to_string(),
+            style: style.to_string(),
+            required: false,
+            deprecated: None,
+            description: None,
+            example_string: Some(r#"["a", "b"]"#.to_string()),
+            explode,
+            schema: Some(Schema::default()),
+        }
+    }
+
+    #[test]
+    fn defaults_per_location() {
+        assert_eq!(
+            serialized_example(&parameter("query", "", None)).unwrap(),
+            "tags=a&tags=b"
+        );
+        assert_eq!(
+            serialized_example(&parameter("query", "", Some(false))).unwrap(),
+            "tags=a,b"
+        );
+        assert_eq!(
+            serialized_example(&parameter("path", "", None)).unwrap(),
+            "a,b"
+        );
+        assert_eq!(
+            serialized_example(&parameter("header", "", None)).unwrap(),
+            "tags: a,b"
+        );
+        assert_eq!(
+            serialized_example(&parameter("path", "label", Some(true))).unwrap(),
+            ".a.b"
+        );
+    }
+
+    #[test]
+    fn generates_the_value_from_the_schema() {
+        let mut param = parameter("query", "deepObject", None);
+        param.name = "filter".to_string();
+        param.example_string = None;
+        param.schema = Some(Schema {
+            example: Some(Example {
+                name: String::new(),
+                summary: None,
+                description: None,
+                value: r#"{"name": "foo"}"#.to_string(),
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(serialized_example(&param).unwrap(), "filter[name]=foo");
+    }
+}
@@ -1,8 +1,10 @@
 use crate::open_api;
 use crate::open_api::ast::PageAst;
-use crate::page_kind::OutgoingLink;
+use crate::page_kind::LinkSet;
 use crate::render_context::RenderContext;
 
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
 use std::path::PathBuf;
 
 #[derive(Clone)]
@@ -28,17 +30,29 @@ impl OpenApiPage {
         }
     }
 
+    pub(crate) fn tag_description(&self) -> Option<&str> {
+        self.page.tag.description.as_deref()
+    }
+
     pub fn ast(&self, ctx: &mut RenderContext) -> crate::Result<PageAst> {
         ctx.with_url_base_by_page_uri(self.uri_path.as_str());
 
         self.page.ast(ctx)
     }
 
-    pub(crate) fn outgoing_links(
-        &self,
-        ctx: &mut RenderContext,
-    ) -> crate::Result<Vec<OutgoingLink>> {
-        self.page.outgoing_links(ctx)
+    pub(crate) fn verification_links(&self, ctx: &mut RenderContext) -> crate::Result<LinkSet> {
+        self.page.verification_links(ctx)
+    }
+
+    /// A hash of the operations and tag the page is rendered from.
+    pub(crate) fn content_signature(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(
+            serde_json::to_string(&self.page)
+                .unwrap_or_default()
+                .as_bytes(),
+        );
+        hasher.finish()
     }
 
     pub(crate) fn operations(&self) -> &[open_api::model::Operation] {
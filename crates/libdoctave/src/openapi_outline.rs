@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{
+    markdown, page_kind::PageKind, render_context::RenderContext, settings::DEFAULT_SUNSET_FIELD,
+    Project, RenderOptions,
+};
+
+/// A tag in the sidebar of an API reference, with the operations listed
+/// under it in the same order as on the tag's page.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagGroup {
+    pub name: String,
+    pub description: Option<String>,
+    pub operations: Vec<OutlineOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutlineOperation {
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub deprecated: bool,
+    /// When the operation will be removed, from the spec's sunset extension
+    pub sunset: Option<String>,
+    /// Link to the operation. Operations listed under multiple tags link to
+    /// the page of the first tag they appear under.
+    pub uri: String,
+}
+
+/// Lists the tags of an OpenAPI spec and their operations, as generated into
+/// pages. Hidden tags and operations are left out, and overrides from the
+/// `x-doctave` extensions are applied.
+pub(crate) fn compute(
+    spec_file: &Path,
+    project: &Project,
+    opts: Option<&RenderOptions>,
+) -> Vec<TagGroup> {
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+    ctx.with_maybe_options(opts);
+
+    let sunset_field = project
+        .settings
+        .open_api_spec(spec_file)
+        .map_or(DEFAULT_SUNSET_FIELD, |s| s.sunset_field());
+
+    let pages = project
+        .pages()
+        .into_iter()
+        .filter(|p| p.fs_path() == spec_file)
+        .filter_map(|p| match p.page {
+            PageKind::OpenApi(page) => Some(page),
+            PageKind::Markdown(_) => None,
+        })
+        .collect::<Vec<_>>();
+
+    // Operations are identified by where they are in the spec, since the
+    // operationId is optional.
+    let mut canonical_uris: HashMap<(&str, &str, &str), String> = HashMap::new();
+
+    for page in &pages {
+        for op in page.operations() {
+            canonical_uris
+                .entry((
+                    op.method.as_str(),
+                    op.route_pattern.as_str(),
+                    op.anchor_tag.as_str(),
+                ))
+                .or_insert_with(|| format!("{}#{}", page.uri_path, op.anchor_tag));
+        }
+    }
+
+    pages
+        .iter()
+        .map(|page| TagGroup {
+            name: page.tag().unwrap_or_default().to_owned(),
+            description: page.tag_description().map(|d| d.to_owned()),
+            operations: page
+                .operations()
+                .iter()
+                .map(|op| OutlineOperation {
+                    method: op.method.clone(),
+                    path: op.route_pattern.clone(),
+                    summary: op.summary.clone(),
+                    deprecated: op.deprecated,
+                    sunset: op.sunset(sunset_field).map(str::to_owned),
+                    uri: markdown::parser::to_final_link(
+                        &canonical_uris[&(
+                            op.method.as_str(),
+                            op.route_pattern.as_str(),
+                            op.anchor_tag.as_str(),
+                        )],
+                        &ctx,
+                    ),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{
+        content_api::{ContentApiResponse, CurrentPage, ResponseContext},
+        InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+    };
+
+    use super::*;
+
+    const SPEC: &str = indoc! {r#"
+    openapi: 3.0.0
+    info:
+      title: Trees
+      version: 1.0.0
+    tags:
+      - name: Trees
+        description: All about trees
+      - name: Leaves
+      - name: Internal
+        x-doctave:
+          hide: true
+    paths:
+      /tree:
+        get:
+          summary: Get the tree
+          operationId: getTree
+          tags: [Trees, Leaves]
+          responses:
+            '200':
+              description: A tree
+        delete:
+          summary: Cut down the tree
+          operationId: cutDownTree
+          tags: [Trees]
+          deprecated: true
+          x-sunset: "2025-06-30"
+          responses:
+            '200':
+              description: Nothing
+        put:
+          summary: Replace the tree
+          operationId: replaceTree
+          tags: [Trees]
+          x-doctave:
+            hide: true
+          responses:
+            '200':
+              description: A tree
+      /tree/leaves:
+        get:
+          summary: Get the leaves
+          operationId: getLeaves
+          tags: [Leaves]
+          x-doctave:
+            summary_override: List leaves
+          responses:
+            '200':
+              description: Some leaves
+      /secrets:
+        get:
+          summary: Get the secrets
+          operationId: getSecrets
+          tags: [Internal]
+          responses:
+            '200':
+              description: Secrets
+    "#};
+
+    fn project() -> Project {
+        Project::from_file_list(vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Home".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    title: Something
+                    open_api:
+                      - spec_file: openapi.yaml
+                        uri_prefix: /api
+                    "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.yaml"),
+                content: InputContent::Text(SPEC.to_string()),
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_operations_by_tag() {
+        let project = project();
+
+        let outline = project.openapi_outline(Path::new("openapi.yaml"), None);
+
+        assert_eq!(
+            outline,
+            vec![
+                TagGroup {
+                    name: "Trees".to_string(),
+                    description: Some("All about trees".to_string()),
+                    operations: vec![
+                        OutlineOperation {
+                            method: "get".to_string(),
+                            path: "/tree".to_string(),
+                            summary: Some("Get the tree".to_string()),
+                            deprecated: false,
+                            sunset: None,
+                            uri: "/api/trees#get-the-tree".to_string(),
+                        },
+                        OutlineOperation {
+                            method: "delete".to_string(),
+                            path: "/tree".to_string(),
+                            summary: Some("Cut down the tree".to_string()),
+                            deprecated: true,
+                            sunset: Some("2025-06-30".to_string()),
+                            uri: "/api/trees#cut-down-the-tree".to_string(),
+                        },
+                    ],
+                },
+                TagGroup {
+                    name: "Leaves".to_string(),
+                    description: None,
+                    operations: vec![
+                        OutlineOperation {
+                            method: "get".to_string(),
+                            path: "/tree".to_string(),
+                            summary: Some("Get the tree".to_string()),
+                            deprecated: false,
+                            sunset: None,
+                            // Shared with the Trees tag
+                            uri: "/api/trees#get-the-tree".to_string(),
+                        },
+                        OutlineOperation {
+                            method: "get".to_string(),
+                            path: "/tree/leaves".to_string(),
+                            summary: Some("List leaves".to_string()),
+                            deprecated: false,
+                            sunset: None,
+                            uri: "/api/leaves#list-leaves".to_string(),
+                        },
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_link_prefixes() {
+        let project = project();
+
+        let opts = RenderOptions {
+            prefix_link_urls: Some("/v2".to_string()),
+            ..Default::default()
+        };
+
+        let outline = project.openapi_outline(Path::new("openapi.yaml"), Some(&opts));
+
+        assert_eq!(outline[0].operations[0].uri, "/v2/api/trees#get-the-tree");
+    }
+
+    #[test]
+    fn is_included_in_the_content_response_for_openapi_pages() {
+        let project = project();
+
+        let outline = |uri: &str| match project
+            .get_content_response_by_uri_path(uri, ResponseContext::default())
+        {
+            ContentApiResponse::Content {
+                page: CurrentPage::Page {
+                    openapi_outline, ..
+                },
+                ..
+            } => openapi_outline,
+            other => panic!("Unexpected response: {:#?}", other),
+        };
+
+        let groups = outline("/api/leaves").unwrap();
+        assert_eq!(
+            groups.iter().map(|g| g.name.as_str()).collect::<Vec<_>>(),
+            vec!["Trees", "Leaves"]
+        );
+
+        assert_eq!(outline("/"), None);
+    }
+}
@@ -0,0 +1,395 @@
+//! Finds orphaned pages: Markdown pages that readers can't get to, because
+//! they aren't in any navigation, aren't the target of a redirect, and no
+//! other page links to them. Configured with `lints.orphan_pages`.
+//!
+//! Links in the header, the footer and tabs count as well, as do the links
+//! from each step of a tutorial to the next one. Pages that are meant to be
+//! unlisted opt out with `orphan_ok: true` in their frontmatter.
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+use crate::navigation::{self, Navigation};
+use crate::page_kind::PageKind;
+use crate::parallel::*;
+use crate::render_context::RenderContext;
+use crate::settings::{HeaderLink, LintLevel};
+use crate::{markdown, Error, PageHandle, Project, RenderOptions, NAVIGATION_FILE_NAME};
+
+/// The orphaned pages, if `lints.orphan_pages` is set to the given level.
+pub(crate) fn lint(project: &Project, level: LintLevel) -> Vec<Error> {
+    if level == LintLevel::Off || project.settings.lints().orphan_pages != level {
+        return vec![];
+    }
+
+    let pages = project.published_pages(None);
+    let navigations = navigations(project);
+    let identities = page_identities(project, &pages);
+
+    let mut reachable = HashSet::new();
+
+    for (_, navigation) in &navigations {
+        for item in navigation.flattened_links() {
+            let href = item.href().unwrap();
+
+            // Links to anchors point inside a page, like to an operation
+            if href.contains('#') {
+                continue;
+            }
+
+            if let Some(page) = identities.get(&navigation::link_identity(href)) {
+                reachable.insert(page.uri_path().to_owned());
+            }
+        }
+    }
+
+    let mut targets = project
+        .redirects()
+        .into_iter()
+        .map(|(_, to)| to)
+        .collect::<Vec<_>>();
+
+    if let Some(tabs) = project.tabs() {
+        targets.extend(tabs.nav_paths());
+        targets.extend(
+            tabs.tabs
+                .iter()
+                .chain(tabs.subtabs().iter())
+                .filter_map(|tab| tab.landing_page.clone()),
+        );
+    }
+
+    let header_links = project.settings.header().map(|h| h.links.iter());
+    let footer_links = project.settings.footer().map(|f| f.links.iter());
+
+    for link in header_links
+        .into_iter()
+        .flatten()
+        .chain(footer_links.into_iter().flatten())
+    {
+        if let HeaderLink::Internal(link) = link {
+            if !link.download {
+                targets.push(link.href.clone());
+            }
+        }
+    }
+
+    for target in targets {
+        let uri = crate::fs_to_uri_path(Path::new(&target));
+
+        if let Some(page) = project.find_localized_page(&uri, None, false) {
+            reachable.insert(page.uri_path().to_owned());
+        }
+    }
+
+    reachable.extend(linked_pages(project, &pages));
+
+    for tutorial in crate::tutorial::all(project) {
+        for step in tutorial.steps.iter().skip(1) {
+            if let Some(page) = project.get_page_by_fs_path(step) {
+                reachable.insert(page.uri_path().to_owned());
+            }
+        }
+    }
+
+    pages
+        .iter()
+        .filter(|page| page.uri_path() != "/")
+        .filter(|page| match page.page {
+            PageKind::Markdown(m) => !m.orphan_ok(),
+            _ => false,
+        })
+        .filter(|page| !reachable.contains(page.uri_path()))
+        .map(|page| {
+            let suggestion = nearest_section(page.fs_path(), &navigations, &identities)
+                .map(|(nav_path, heading)| {
+                    format!(
+                        "Add it to the \"{}\" section in {}, which has pages in the same folder, or link",
+                        heading,
+                        nav_file(&nav_path)
+                    )
+                })
+                .unwrap_or_else(|| String::from("Add it to a navigation, link"));
+
+            Error {
+                code: Error::ORPHAN_PAGE,
+                message: String::from("Page isn't linked from anywhere"),
+                description: format!(
+                    "{} isn't in any navigation, isn't the target of a redirect, and no other page links to it.\n{} to it from another page, or set `orphan_ok: true` in its frontmatter if it's meant to be unlisted.\nRule: lints.orphan_pages",
+                    page.fs_path().display(),
+                    suggestion
+                ),
+                file: Some(page.fs_path().to_owned()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            }
+        })
+        .collect()
+}
+
+/// The navigation of every tab and subtab, by their path
+fn navigations(project: &Project) -> Vec<(String, Navigation)> {
+    let nav_paths = project
+        .tabs()
+        .map(|tabs| tabs.nav_paths())
+        .unwrap_or_else(|| vec![String::from("/")]);
+
+    nav_paths
+        .into_iter()
+        .filter_map(|path| {
+            // Navigations that fail to build are reported by `verify`
+            let navigation = project.navigation(None, &path).ok()?;
+            Some((path, navigation))
+        })
+        .collect()
+}
+
+/// The pages by the identity of their final link, which navigation links
+/// are compared against.
+fn page_identities<'a>(
+    project: &Project,
+    pages: &[PageHandle<'a>],
+) -> HashMap<PathBuf, PageHandle<'a>> {
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+
+    pages
+        .iter()
+        .map(|p| {
+            (
+                navigation::link_identity(&markdown::parser::to_final_link(p.uri_path(), &ctx)),
+                p.clone(),
+            )
+        })
+        .collect()
+}
+
+/// URI paths of the pages linked from other pages, including links in the
+/// descriptions of OpenAPI specs.
+fn linked_pages(project: &Project, pages: &[PageHandle]) -> HashSet<String> {
+    let opts = RenderOptions::without_link_rewrites(&RenderOptions::default());
+
+    pages
+        .par_iter()
+        .flat_map_iter(|p| {
+            // Pages that fail to render are reported by `verify`
+            let links = match p.verification_links(Some(&opts)) {
+                Ok(links) => links.internal,
+                Err(_) => vec![],
+            };
+
+            let (locale, _) = project.settings.split_locale(p.uri_path());
+
+            links
+                .into_iter()
+                .filter_map(|link| {
+                    let path = PathBuf::from(link.expanded_uri.as_ref().unwrap_or(&link.uri));
+                    let uri = crate::fs_to_uri_path(&path);
+
+                    project.find_localized_page(&uri, locale, false)
+                })
+                // A page linking to itself doesn't make it reachable
+                .filter(|target| target.uri_path() != p.uri_path())
+                .map(|target| target.uri_path().to_owned())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The navigation section with the pages closest to the given file, by how
+/// many leading folders they share. The first section wins a tie.
+fn nearest_section(
+    fs_path: &Path,
+    navigations: &[(String, Navigation)],
+    identities: &HashMap<PathBuf, PageHandle>,
+) -> Option<(String, String)> {
+    let ours = folders(fs_path);
+    let mut nearest: Option<(usize, String, String)> = None;
+
+    for (nav_path, navigation) in navigations {
+        for section in navigation.iter() {
+            let Some(heading) = &section.heading else {
+                continue;
+            };
+
+            for href in section.gather_links() {
+                let Some(page) = identities.get(&navigation::link_identity(&href)) else {
+                    continue;
+                };
+
+                let theirs = folders(page.fs_path());
+                let shared = ours
+                    .iter()
+                    .zip(theirs.iter())
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                // Pages at the root only match sections with other root pages
+                if shared == 0 && !(ours.is_empty() && theirs.is_empty()) {
+                    continue;
+                }
+
+                let score = shared + 1;
+
+                if nearest.as_ref().map(|(s, _, _)| score > *s).unwrap_or(true) {
+                    nearest = Some((score, nav_path.clone(), heading.clone()));
+                }
+            }
+        }
+    }
+
+    nearest.map(|(_, nav_path, heading)| (nav_path, heading))
+}
+
+fn folders(fs_path: &Path) -> Vec<String> {
+    fs_path
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn nav_file(nav_path: &str) -> String {
+    format!(
+        "{}/{}",
+        nav_path.trim_end_matches('/'),
+        NAVIGATION_FILE_NAME
+    )
+    .trim_start_matches('/')
+    .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, SETTINGS_FILE_NAME};
+    use indoc::indoc;
+
+    fn project(settings: &str, navigation: &str, files: Vec<(&str, &str)>) -> Project {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        let mut list = vec![
+            file("README.md", "# Home"),
+            file(NAVIGATION_FILE_NAME, navigation),
+            file(
+                SETTINGS_FILE_NAME,
+                &format!("---\ntitle: Something\n{}", settings),
+            ),
+        ];
+        list.extend(files.into_iter().map(|(path, content)| file(path, content)));
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    fn files(errors: &[Error]) -> Vec<PathBuf> {
+        errors.iter().filter_map(|e| e.file.clone()).collect()
+    }
+
+    #[test]
+    fn warns_about_pages_that_are_not_linked_from_anywhere() {
+        let project = project(
+            "",
+            indoc! {"
+            - heading: Guides
+              items:
+                - href: /guides/install.md
+                  label: Install
+            "},
+            vec![
+                ("guides/install.md", "# Install"),
+                ("guides/upgrade.md", "# Upgrade"),
+            ],
+        );
+
+        let warnings = lint(&project, LintLevel::Warn);
+
+        assert_eq!(
+            files(&warnings),
+            vec![PathBuf::from("guides/upgrade.md")],
+            "{:#?}",
+            warnings
+        );
+        assert_eq!(warnings[0].code, Error::ORPHAN_PAGE);
+        assert_eq!(
+            warnings[0].description,
+            "guides/upgrade.md isn't in any navigation, isn't the target of a redirect, and no other page links to it.\nAdd it to the \"Guides\" section in navigation.yaml, which has pages in the same folder, or link to it from another page, or set `orphan_ok: true` in its frontmatter if it's meant to be unlisted.\nRule: lints.orphan_pages"
+        );
+    }
+
+    #[test]
+    fn links_and_redirects_make_pages_reachable() {
+        let project = project(
+            indoc! {"
+            redirects:
+              - from: /old-faq
+                to: /faq
+            "},
+            indoc! {"
+            - heading: Guides
+              items:
+                - href: /guides/install.md
+                  label: Install
+            "},
+            vec![
+                (
+                    "guides/install.md",
+                    "# Install\n\nSee [upgrading](/guides/upgrade.md).",
+                ),
+                ("guides/upgrade.md", "# Upgrade"),
+                ("faq.md", "# FAQ"),
+                ("self.md", "# Self\n\n[Me](/self.md)"),
+            ],
+        );
+
+        let warnings = lint(&project, LintLevel::Warn);
+
+        assert_eq!(
+            files(&warnings),
+            vec![PathBuf::from("self.md")],
+            "{:#?}",
+            warnings
+        );
+    }
+
+    #[test]
+    fn pages_can_opt_out() {
+        let project = project(
+            "",
+            "---",
+            vec![("legal.md", "---\norphan_ok: true\n---\n# Legal")],
+        );
+
+        assert!(lint(&project, LintLevel::Warn).is_empty());
+    }
+
+    #[test]
+    fn level_is_configurable() {
+        let pages = vec![("legal.md", "# Legal")];
+
+        let off = project("lints:\n  orphan_pages: off", "---", pages.clone());
+        assert!(lint(&off, LintLevel::Warn).is_empty());
+        assert!(lint(&off, LintLevel::Error).is_empty());
+
+        let error = project("lints:\n  orphan_pages: error", "---", pages);
+        assert!(lint(&error, LintLevel::Warn).is_empty());
+        assert_eq!(
+            files(&lint(&error, LintLevel::Error)),
+            vec![PathBuf::from("legal.md")]
+        );
+        assert!(error
+            .verify(None, None)
+            .unwrap_err()
+            .iter()
+            .any(|e| e.code == Error::ORPHAN_PAGE));
+    }
+}
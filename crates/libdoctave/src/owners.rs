@@ -0,0 +1,316 @@
+//! Who owns which files in the project, so that errors can be routed to the
+//! team that can fix them.
+//!
+//! Owners are set with glob patterns, either under `owners` in the settings,
+//! or one per line in a `.docapella-owners` file, like a `CODEOWNERS` file:
+//!
+//! ```text
+//! # Pattern        Owner
+//! guides/**        docs-team
+//! api/             api-team@example.com
+//! ```
+//!
+//! Like in `CODEOWNERS`, the last matching pattern wins. Patterns in the
+//! settings come after the ones in the file.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::page_kind::PageKind;
+use crate::settings::OwnerRule;
+use crate::{Error, Position};
+
+/// A file with owners for the files in the project, one pattern and owner
+/// per line.
+pub const OWNERS_FILE_NAME: &str = ".docapella-owners";
+
+/// How many unowned pages are listed in the warning about them. The rest are
+/// only counted.
+const LISTED_UNOWNED_PAGES: usize = 20;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Owners {
+    rules: Vec<Rule>,
+    /// Owners of the pages, keyed by their path. Pages without an owner are
+    /// left out.
+    pages: HashMap<PathBuf, String>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    matcher: GlobMatcher,
+    owner: String,
+}
+
+impl Owners {
+    /// Reads the owners file, if there is one, and the rules in the
+    /// settings, and finds the owner of every page. Returns the lines of the
+    /// owners file that couldn't be read along with the owners.
+    ///
+    /// Invalid patterns in the settings are reported by
+    /// [`Settings::verify`](crate::settings::Settings::verify).
+    pub(crate) fn new(
+        owners_file: Option<&str>,
+        settings: &[OwnerRule],
+        pages: &[PageKind],
+    ) -> (Self, Vec<Error>) {
+        let mut rules = vec![];
+        let mut errors = vec![];
+
+        if let Some(content) = owners_file {
+            let mut offset = 0;
+
+            for line in content.split_inclusive('\n') {
+                let start = offset;
+                offset += line.len();
+
+                let text = line.trim();
+                if text.is_empty() || text.starts_with('#') {
+                    continue;
+                }
+
+                let (pattern, owner) = text
+                    .split_once(char::is_whitespace)
+                    .map(|(pattern, owner)| (pattern, owner.trim()))
+                    .unwrap_or((text, ""));
+
+                let position = || {
+                    let indent = line.len() - line.trim_start().len();
+                    Some(Position::from_byte_range(
+                        content,
+                        start + indent,
+                        start + indent + text.len(),
+                    ))
+                };
+
+                if owner.is_empty() {
+                    errors.push(Error {
+                        code: Error::OWNERSHIP,
+                        message: String::from("Missing owner"),
+                        description: format!(
+                            "\"{}\" doesn't have an owner. Write the owner after the pattern, like \"{} docs-team\".",
+                            pattern, pattern
+                        ),
+                        file: Some(PathBuf::from(OWNERS_FILE_NAME)),
+                        position: position(),
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                    continue;
+                }
+
+                match Rule::new(pattern, owner) {
+                    Ok(rule) => rules.push(rule),
+                    Err(e) => errors.push(Error {
+                        code: Error::OWNERSHIP,
+                        message: String::from("Invalid pattern"),
+                        description: format!("Could not parse \"{}\": {}", pattern, e.kind()),
+                        file: Some(PathBuf::from(OWNERS_FILE_NAME)),
+                        position: position(),
+                        owner: None,
+                        suggestions: vec![],
+                    }),
+                }
+            }
+        }
+
+        rules.extend(
+            settings
+                .iter()
+                .filter_map(|rule| Rule::new(&rule.path, &rule.owner).ok()),
+        );
+
+        let mut owners = Owners {
+            rules,
+            pages: HashMap::new(),
+        };
+
+        owners.pages = pages
+            .iter()
+            .filter_map(|page| {
+                owners
+                    .matching(page.fs_path())
+                    .map(|owner| (page.fs_path().to_owned(), owner.to_owned()))
+            })
+            .collect();
+
+        (owners, errors)
+    }
+
+    /// Whether any owners were set.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The owner of a file, from the last pattern that matches it.
+    pub(crate) fn owner_of(&self, path: &Path) -> Option<&str> {
+        match self.pages.get(path) {
+            Some(owner) => Some(owner),
+            None => self.matching(path),
+        }
+    }
+
+    fn matching(&self, path: &Path) -> Option<&str> {
+        let path = path.strip_prefix("/").unwrap_or(path);
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matcher.is_match(path))
+            .map(|rule| rule.owner.as_str())
+    }
+
+    /// Fills in the owner of the errors' files.
+    pub(crate) fn assign(&self, errors: &mut [Error]) {
+        if self.is_empty() {
+            return;
+        }
+
+        for error in errors {
+            if error.owner.is_none() {
+                error.owner = error
+                    .file
+                    .as_deref()
+                    .and_then(|file| self.owner_of(file))
+                    .map(str::to_owned);
+            }
+        }
+    }
+
+    /// A single warning listing the pages without an owner, if owners are
+    /// set at all.
+    pub(crate) fn unowned_pages_warning(&self, pages: &[PageKind]) -> Option<Error> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let unowned = pages
+            .iter()
+            .filter(|page| matches!(page, PageKind::Markdown(_)))
+            .filter(|page| !self.pages.contains_key(page.fs_path()))
+            .map(|page| page.fs_path())
+            .collect::<Vec<_>>();
+
+        if unowned.is_empty() {
+            return None;
+        }
+
+        let mut list = unowned
+            .iter()
+            .take(LISTED_UNOWNED_PAGES)
+            .map(|path| format!("* {}", path.display()))
+            .collect::<Vec<_>>();
+        if unowned.len() > LISTED_UNOWNED_PAGES {
+            list.push(format!(
+                "...and {} more",
+                unowned.len() - LISTED_UNOWNED_PAGES
+            ));
+        }
+
+        Some(Error {
+            code: Error::OWNERSHIP,
+            message: format!("{} pages don't have an owner", unowned.len()),
+            description: format!(
+                "No pattern in `owners` or {} matches these pages:\n{}\n\nAdd a pattern for them so that their errors reach someone.",
+                OWNERS_FILE_NAME,
+                list.join("\n")
+            ),
+            file: None,
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        })
+    }
+}
+
+impl Rule {
+    fn new(pattern: &str, owner: &str) -> std::result::Result<Self, globset::Error> {
+        Ok(Rule {
+            matcher: owner_glob(pattern)?.compile_matcher(),
+            owner: owner.to_owned(),
+        })
+    }
+}
+
+/// Patterns are relative to the root of the project. Like in `CODEOWNERS`,
+/// a pattern ending in a slash matches everything in the folder.
+pub(crate) fn owner_glob(pattern: &str) -> std::result::Result<globset::Glob, globset::Error> {
+    let pattern = pattern.trim_start_matches('/');
+
+    let pattern = match pattern.strip_suffix('/') {
+        Some(folder) => format!("{}/**", folder),
+        None => pattern.to_owned(),
+    };
+
+    GlobBuilder::new(&pattern).literal_separator(true).build()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MarkdownPage;
+
+    fn pages(paths: &[&str]) -> Vec<PageKind> {
+        paths
+            .iter()
+            .map(|path| PageKind::Markdown(MarkdownPage::new(Path::new(path), vec![])))
+            .collect()
+    }
+
+    #[test]
+    fn last_matching_pattern_wins() {
+        let file = "# Owners\nguides/**  docs-team\n\nguides/api/  api-team@example.com\n";
+        let (owners, errors) = Owners::new(
+            Some(file),
+            &[OwnerRule {
+                path: String::from("guides/api/legacy.md"),
+                owner: String::from("legacy-team"),
+            }],
+            &pages(&[
+                "guides/install.md",
+                "guides/api/auth.md",
+                "guides/api/legacy.md",
+                "README.md",
+            ]),
+        );
+
+        assert!(errors.is_empty(), "{:#?}", errors);
+        assert_eq!(
+            owners.owner_of(Path::new("guides/install.md")),
+            Some("docs-team")
+        );
+        assert_eq!(
+            owners.owner_of(Path::new("guides/api/auth.md")),
+            Some("api-team@example.com")
+        );
+        assert_eq!(
+            owners.owner_of(Path::new("guides/api/legacy.md")),
+            Some("legacy-team")
+        );
+        assert_eq!(owners.owner_of(Path::new("README.md")), None);
+    }
+
+    #[test]
+    fn reports_lines_without_an_owner() {
+        let (_, errors) = Owners::new(Some("guides/**  docs\napi/**\n"), &[], &[]);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Missing owner");
+        assert_eq!(errors[0].position.as_ref().unwrap().start.row, 2);
+    }
+
+    #[test]
+    fn warns_once_about_unowned_pages() {
+        let pages = pages(&["guides/install.md", "README.md", "faq.md"]);
+        let (owners, _) = Owners::new(Some("guides/  docs-team\n"), &[], &pages);
+
+        let warning = owners.unowned_pages_warning(&pages).unwrap();
+
+        assert_eq!(warning.message, "2 pages don't have an owner");
+        assert!(warning.description.contains("* README.md\n* faq.md"));
+
+        let (no_owners, _) = Owners::new(None, &[], &pages);
+        assert_eq!(no_owners.unowned_pages_warning(&pages), None);
+    }
+}
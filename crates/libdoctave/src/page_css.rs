@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+
+use lightningcss::rules::{CssRule, CssRuleList};
+use lightningcss::selector::{Combinator, Component, Selector};
+use lightningcss::stylesheet::{ParserOptions, PrinterOptions, StyleSheet};
+use lightningcss::values::ident::Ident;
+
+/// Style sheets listed in a page's `css` frontmatter are expected under this
+/// folder. Ones elsewhere still work, but get a warning.
+pub const PAGE_CSS_DIR: &str = "_assets/css";
+
+/// The project path of a style sheet in the `css` frontmatter. Paths may
+/// start with a `/`, like links to assets do.
+pub(crate) fn asset_path(css: &str) -> PathBuf {
+    PathBuf::from(css.trim_start_matches('/'))
+}
+
+/// The class a page with `css_scope: true` gets on its container, based on
+/// its URI path. E.g. `/guides/intro` becomes `css-scope-guides-intro`.
+pub(crate) fn scope_class(uri_path: &str) -> String {
+    let slug = uri_path
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        "css-scope-index".to_string()
+    } else {
+        format!("css-scope-{}", slug)
+    }
+}
+
+/// Rewrites a style sheet so that its rules only apply inside elements with
+/// the given class. Each selector gets the class as an ancestor, and
+/// selectors for the whole document, like `:root` or `body`, are replaced by
+/// the class itself.
+///
+/// Rules inside `@media`, `@supports`, `@layer` and `@container` are scoped
+/// the same way. Nested rules are relative to their parent, so they are
+/// scoped along with it.
+pub(crate) fn scope(css: &str, class: &str) -> Result<String, String> {
+    let mut sheet = StyleSheet::parse(css, ParserOptions::default()).map_err(|e| e.to_string())?;
+
+    scope_rules(&mut sheet.rules, class);
+
+    sheet
+        .to_css(PrinterOptions::default())
+        .map(|out| out.code)
+        .map_err(|e| e.to_string())
+}
+
+fn scope_rules(rules: &mut CssRuleList, class: &str) {
+    for rule in rules.0.iter_mut() {
+        match rule {
+            CssRule::Style(style) => {
+                for selector in style.selectors.0.iter_mut() {
+                    *selector = scope_selector(selector, class);
+                }
+            }
+            CssRule::Media(media) => scope_rules(&mut media.rules, class),
+            CssRule::Supports(supports) => scope_rules(&mut supports.rules, class),
+            CssRule::LayerBlock(layer) => scope_rules(&mut layer.rules, class),
+            CssRule::Container(container) => scope_rules(&mut container.rules, class),
+            // Keyframes, font faces and the like don't select elements
+            _ => {}
+        }
+    }
+}
+
+fn scope_selector<'i>(selector: &Selector<'i>, class: &str) -> Selector<'i> {
+    let parts = selector
+        .iter_raw_parse_order_from(0)
+        .cloned()
+        .collect::<Vec<_>>();
+    let scope = Component::Class(Ident(class.to_string().into()));
+
+    let first_compound = parts
+        .iter()
+        .position(|c| matches!(c, Component::Combinator(_)))
+        .unwrap_or(parts.len());
+
+    let mut scoped = vec![];
+
+    if first_compound == 1 && selects_document(&parts[0]) {
+        scoped.push(scope);
+        scoped.extend(parts.into_iter().skip(1));
+    } else {
+        scoped.push(scope);
+        scoped.push(Component::Combinator(Combinator::Descendant));
+        scoped.extend(parts);
+    }
+
+    Selector::from(scoped)
+}
+
+/// `:root`, `html` and `body`, which stand for the page as a whole
+fn selects_document(component: &Component) -> bool {
+    match component {
+        Component::Root => true,
+        Component::LocalName(name) => {
+            name.lower_name.0.as_ref() == "html" || name.lower_name.0.as_ref() == "body"
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn compact(css: &str) -> String {
+        css.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn scope_class_is_based_on_the_uri_path() {
+        assert_eq!(scope_class("/landing"), "css-scope-landing");
+        assert_eq!(scope_class("/guides/intro"), "css-scope-guides-intro");
+        assert_eq!(scope_class("/"), "css-scope-index");
+    }
+
+    #[test]
+    fn scopes_every_selector() {
+        let css = scope(".hero, h1 > span { color: red }", "css-scope-landing").unwrap();
+
+        assert!(
+            compact(&css).contains(".css-scope-landing .hero, .css-scope-landing h1 > span {"),
+            "{}",
+            css
+        );
+    }
+
+    #[test]
+    fn replaces_document_selectors_with_the_scope() {
+        let css = scope(
+            ":root { --accent: red } body .hero { color: var(--accent) } html { font-size: 18px }",
+            "s",
+        )
+        .unwrap();
+        let css = compact(&css);
+
+        assert!(css.contains(".s { --accent: red; }"), "{}", css);
+        assert!(css.contains(".s .hero {"), "{}", css);
+        assert!(!css.contains("body"), "{}", css);
+        assert!(!css.contains("html"), "{}", css);
+    }
+
+    #[test]
+    fn scopes_rules_in_media_queries_and_keeps_nested_rules() {
+        let css = scope(
+            indoc! {"
+            @media (min-width: 800px) {
+              .hero { padding: 2rem }
+            }
+            .card {
+              color: blue;
+              & .title { font-weight: bold }
+            }
+            @keyframes fade {
+              from { opacity: 0 }
+              to { opacity: 1 }
+            }
+            "},
+            "s",
+        )
+        .unwrap();
+        let css = compact(&css);
+
+        assert!(
+            css.contains("@media (min-width: 800px) { .s .hero {"),
+            "{}",
+            css
+        );
+        assert!(css.contains(".s .card {"), "{}", css);
+        assert!(css.contains("& .title {"), "{}", css);
+        assert!(!css.contains(".s .title"), "{}", css);
+        assert!(css.contains("from {"), "{}", css);
+        assert!(!css.contains(".s from"), "{}", css);
+    }
+
+    #[test]
+    fn reports_invalid_selectors() {
+        assert!(scope("..hero { color: red }", "s").is_err());
+    }
+}
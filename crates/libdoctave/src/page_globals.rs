@@ -0,0 +1,292 @@
+//! Variables describing the page being rendered, available to expressions
+//! in the page and in the components and partials it uses:
+//!
+//! - `@page`: `title`, `path`, `file`, `description`, `last_updated` and
+//!   `contributors` of the page
+//! - `@project`: `title`, `version` and `base_url` from the settings
+//! - `@navigation`: the `sections` of the navigation the page is listed in,
+//!   each with a `heading` and `items`. Items have a `kind` (`link` or
+//!   `subheading`), `label`, `href`, `external_href`, and nested `items`.
+//! - `@vars`: the `variables` from the settings, with the overrides for the
+//!   locale of the page
+//!
+//! Fields that aren't set are `null`, so that reading a field that doesn't
+//! exist, like `@page.titel`, is an error rather than an empty string.
+use indexmap::IndexMap;
+
+use crate::{
+    expressions::Value,
+    markdown::parser::to_final_link,
+    navigation::{Item, Navigation, Section},
+    page_handle::PageHandle,
+    render_context::RenderContext,
+};
+
+pub(crate) fn compute(page: &PageHandle, ctx: &RenderContext) -> Vec<(String, Value)> {
+    vec![
+        ("page".to_string(), page_value(page, ctx)),
+        ("project".to_string(), project_value(page)),
+        ("navigation".to_string(), navigation_value(page, ctx)),
+        (
+            crate::variables::GLOBAL.to_string(),
+            crate::variables::value(&page.project.settings, page_locale(page, ctx)),
+        ),
+    ]
+}
+
+/// Pages in a locale folder use its variables, and other pages the ones of
+/// the locale being rendered.
+fn page_locale<'a>(page: &'a PageHandle, ctx: &'a RenderContext) -> Option<&'a str> {
+    page.project
+        .settings
+        .split_locale(page.uri_path())
+        .0
+        .or(ctx.options.locale.as_deref())
+}
+
+fn page_value(page: &PageHandle, ctx: &RenderContext) -> Value {
+    object([
+        (
+            "title",
+            page.title()
+                .map(Value::String)
+                .unwrap_or_else(|_| Value::String(page.project.settings.title().to_string())),
+        ),
+        ("path", Value::String(to_final_link(page.uri_path(), ctx))),
+        ("file", Value::String(page.fs_path().display().to_string())),
+        ("description", optional(page.description().ok().flatten())),
+        ("last_updated", optional(page.last_updated())),
+        (
+            "contributors",
+            Value::List(
+                page.contributors()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(Value::String)
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+fn project_value(page: &PageHandle) -> Value {
+    let settings = &page.project.settings;
+
+    object([
+        ("title", Value::String(settings.title().to_string())),
+        ("version", optional(settings.version())),
+        ("base_url", optional(settings.base_url())),
+    ])
+}
+
+/// The navigation of the tab the page is in. Projects with broken
+/// navigations get an empty one, since those are reported by verification.
+fn navigation_value(page: &PageHandle, ctx: &RenderContext) -> Value {
+    let subtab_path = page
+        .project
+        .get_subtab_path_by_uri_path(page.uri_path())
+        .unwrap_or_else(|| "/".to_string());
+
+    let navigation = page
+        .project
+        .navigation(Some(ctx.options), &subtab_path)
+        .unwrap_or_else(|_| Navigation::new(vec![]));
+
+    object([(
+        "sections",
+        Value::List(navigation.sections.iter().map(section_value).collect()),
+    )])
+}
+
+fn section_value(section: &Section) -> Value {
+    object([
+        ("heading", optional(section.heading.as_deref())),
+        (
+            "items",
+            Value::List(section.items.iter().map(item_value).collect()),
+        ),
+    ])
+}
+
+fn item_value(item: &Item) -> Value {
+    let kind = if item.is_subheading() {
+        "subheading"
+    } else {
+        "link"
+    };
+
+    object([
+        ("kind", Value::String(kind.to_string())),
+        ("label", Value::String(item.label().to_string())),
+        ("href", optional(item.href())),
+        ("external_href", optional(item.external_href())),
+        (
+            "items",
+            Value::List(item.items().unwrap_or(&[]).iter().map(item_value).collect()),
+        ),
+    ])
+}
+
+fn object<const N: usize>(fields: [(&str, Value); N]) -> Value {
+    Value::Object(
+        fields
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect::<IndexMap<_, _>>(),
+    )
+}
+
+fn optional<S: Into<String>>(value: Option<S>) -> Value {
+    value
+        .map(|v| Value::String(v.into()))
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{
+        Error, InputContent, InputFile, Project, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+    };
+
+    fn project(files: &[(&str, &str)]) -> Project {
+        let mut list = vec![
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    title: Acme
+                    version: "2.1"
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    - heading: Guides
+                      items:
+                        - href: /guides/setup
+                        - subheading: More
+                          items:
+                            - label: Elsewhere
+                              external: https://example.com
+                    - heading: Reference
+                      items:
+                        - href: /
+                    "#}
+                    .to_owned(),
+                ),
+            },
+        ];
+
+        list.extend(files.iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    fn text(project: &Project, uri_path: &str) -> String {
+        project
+            .get_page_by_uri_path(uri_path)
+            .unwrap()
+            .ast(None)
+            .unwrap()
+            .as_markdown()
+            .unwrap()
+            .inner_text()
+    }
+
+    #[test]
+    fn exposes_the_page_and_project() {
+        let project = project(&[
+            ("README.md", "# Home"),
+            (
+                "guides/setup.md",
+                indoc! {r#"
+                ---
+                title: Setup
+                meta:
+                  description: Getting going
+                ---
+
+                {@page.title} at {@page.path} from {@page.file}: {@page.description}
+
+                {@project.title} {@project.version}
+                "#},
+            ),
+        ]);
+
+        let text = text(&project, "/guides/setup");
+        assert!(
+            text.contains("Setup at /guides/setup from guides/setup.md: Getting going"),
+            "{}",
+            text
+        );
+        assert!(text.contains("Acme 2.1"), "{}", text);
+    }
+
+    #[test]
+    fn exposes_the_navigation() {
+        let project = project(&[
+            (
+                "README.md",
+                indoc! {r#"
+            {@navigation.sections | map("heading") | join(", ")}
+
+            {@navigation.sections[0].items[0].label} {@navigation.sections[0].items[0].href}
+
+            {@navigation.sections[0].items[1].kind} {@navigation.sections[0].items[1].items[0].external_href}
+            "#},
+            ),
+            ("guides/setup.md", "# Setup"),
+        ]);
+
+        let text = text(&project, "/");
+        assert!(text.contains("Guides, Reference"), "{}", text);
+        assert!(text.contains("Setup /guides/setup"), "{}", text);
+        assert!(text.contains("subheading https://example.com"), "{}", text);
+    }
+
+    #[test]
+    fn components_see_the_page_they_are_rendered_on() {
+        let project = project(&[
+            ("README.md", "# Home\n\n<Component.Title />"),
+            ("guides/setup.md", "# Setup\n\n<Component.Title />"),
+            ("_components/title.md", "On {@page.title}"),
+        ]);
+
+        assert!(text(&project, "/").ends_with("On Home"));
+        assert!(text(&project, "/guides/setup").ends_with("On Setup"));
+    }
+
+    #[test]
+    fn unknown_fields_are_errors() {
+        let project = project(&[
+            ("README.md", "{@page.titel}"),
+            ("guides/setup.md", "# Setup"),
+        ]);
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::INVALID_EXPRESSION);
+        assert!(
+            errors[0]
+                .description
+                .contains("Could not find field `titel` on `object`"),
+            "{}",
+            errors[0].description
+        );
+        assert!(
+            errors[0].description.contains("Did you mean `title`?"),
+            "{}",
+            errors[0].description
+        );
+    }
+}
@@ -1,14 +1,42 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 
 use crate::{
     breadcrumb::{self, Breadcrumb},
     frontmatter::PageWidth,
     markdown_page::OnThisPageHeading,
-    page_kind::{Ast, OutgoingLink, PageKind},
+    page_css,
+    page_kind::{Ast, LinkSet, PageKind},
+    page_links::{self, PageLink},
     render_context::RenderContext,
     Project, RenderOptions, Result,
 };
 
+/// Why a page is left out of the published site.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DraftStatus {
+    /// Marked with `draft: true` in the frontmatter
+    Draft,
+    /// Matched by the given pattern in the `exclude` setting
+    Excluded(String),
+}
+
+impl fmt::Display for DraftStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DraftStatus::Draft => write!(f, "a draft (`draft: true` in its frontmatter)"),
+            DraftStatus::Excluded(pattern) => write!(
+                f,
+                "excluded by the pattern \"{}\" in {}",
+                pattern,
+                crate::SETTINGS_FILE_NAME
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct PageHandle<'a> {
     pub(crate) page: &'a PageKind,
@@ -16,8 +44,14 @@ pub struct PageHandle<'a> {
 }
 
 impl PageHandle<'_> {
+    /// The file the page is written to in a static build, following the
+    /// `url_style` in the settings.
     pub fn out_path(&self) -> PathBuf {
-        self.page.out_path()
+        self.project
+            .settings
+            .url_style()
+            .out_path(self.uri_path())
+            .unwrap_or_else(|| self.page.out_path())
     }
 
     pub fn uri_path(&self) -> &str {
@@ -28,8 +62,43 @@ impl PageHandle<'_> {
         self.page.fs_path()
     }
 
-    pub fn title(&self) -> Result<Option<String>> {
-        self.page.title()
+    /// A hash of the page's location and content. Doesn't cover the settings
+    /// or custom components the page is rendered with, so combine it with
+    /// [`Project::fingerprint`] when using it as a cache key.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.uri_path().hash(&mut hasher);
+        self.fs_path().hash(&mut hasher);
+        self.page.content_signature().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// The title used for the page everywhere it's listed: in the navigation,
+    /// breadcrumbs, search, feeds and previous/next links.
+    ///
+    /// Markdown pages use the `title` in their frontmatter, the text of their
+    /// first h1, or their file name, in that order. OpenAPI pages use their
+    /// tag. Pages with none of these, like a root README without a heading,
+    /// use the project's title.
+    pub fn title(&self) -> Result<String> {
+        self.page
+            .title(&self.project.settings)
+            .map(|title| title.unwrap_or_else(|| self.project.settings.title().to_owned()))
+    }
+
+    /// Whether the page sets its title with frontmatter or an h1, rather than
+    /// falling back to its file name.
+    pub(crate) fn has_explicit_title(&self) -> bool {
+        match self.page {
+            // Generated pages get their titles from the template's variables
+            PageKind::Markdown(m) if m.template.is_some() => true,
+            PageKind::Markdown(m) => m
+                .explicit_title(&self.project.settings)
+                .map(|title| title.is_some())
+                // Invalid frontmatter is reported on its own
+                .unwrap_or(true),
+            PageKind::OpenApi(_) => true,
+        }
     }
 
     pub fn description(&self) -> Result<Option<String>> {
@@ -40,10 +109,82 @@ impl PageHandle<'_> {
         self.page.hidden_from_search()
     }
 
+    pub fn search_boost(&self) -> Result<f32> {
+        self.page.search_boost()
+    }
+
+    /// Where the original of the page is. Set in the frontmatter, or by the
+    /// `canonical_urls` in the settings.
+    pub fn canonical_url(&self) -> Option<String> {
+        match self.page {
+            PageKind::Markdown(m) => m.canonical_url(),
+            PageKind::OpenApi(_) => None,
+        }
+        .or_else(|| {
+            self.project
+                .settings
+                .canonical_url_for(self.fs_path(), self.uri_path())
+        })
+    }
+
+    /// Whether search engines are asked not to index the page.
+    pub fn noindex(&self) -> bool {
+        match self.page {
+            PageKind::Markdown(m) => m.noindex(),
+            PageKind::OpenApi(_) => false,
+        }
+    }
+
     pub fn openapi_tag(&self) -> Option<&str> {
         self.page.openapi_tag()
     }
 
+    /// When the page was last updated. Set in the frontmatter, or gathered
+    /// from the page's file when the project was created.
+    pub fn last_updated(&self) -> Option<String> {
+        match self.page {
+            PageKind::Markdown(m) => m.last_updated(),
+            PageKind::OpenApi(_) => None,
+        }
+        .or_else(|| {
+            self.project
+                .file_metadata(self.fs_path())
+                .and_then(|m| m.modified_at.clone())
+        })
+    }
+
+    /// Who owns the page, from the `owners` in the settings or the
+    /// `.docapella-owners` file.
+    pub fn owner(&self) -> Option<&str> {
+        self.project.owner_of(self.fs_path())
+    }
+
+    /// People who have edited the page. Set in the frontmatter, or gathered
+    /// from the page's file when the project was created.
+    pub fn contributors(&self) -> Option<Vec<String>> {
+        match self.page {
+            PageKind::Markdown(m) => m.contributors(),
+            PageKind::OpenApi(_) => None,
+        }
+        .or_else(|| {
+            self.project
+                .file_metadata(self.fs_path())
+                .and_then(|m| m.authors.clone())
+        })
+    }
+
+    /// `None` for pages that are part of the published site.
+    pub fn draft_status(&self) -> Option<DraftStatus> {
+        if self.page.is_draft() {
+            Some(DraftStatus::Draft)
+        } else {
+            self.project
+                .settings
+                .excluded_by(self.fs_path())
+                .map(|pattern| DraftStatus::Excluded(pattern.to_string()))
+        }
+    }
+
     pub fn show_breadcrumbs(&self) -> bool {
         match self.page {
             PageKind::Markdown(m) => m.show_breadcrumbs(),
@@ -56,6 +197,15 @@ impl PageHandle<'_> {
         breadcrumb::compute(self.uri_path(), self.project, opts).unwrap_or_default()
     }
 
+    /// The previous and next pages, in navigation order.
+    pub fn previous_and_next(
+        &self,
+        opts: Option<&RenderOptions>,
+    ) -> (Option<PageLink>, Option<PageLink>) {
+        // NOTE: Don't worry about errors here. They'll be reported elsewhere.
+        page_links::compute(self.uri_path(), self.project, opts).unwrap_or_default()
+    }
+
     pub fn is_markdown(&self) -> bool {
         matches!(&self.page, PageKind::Markdown(_))
     }
@@ -64,12 +214,32 @@ impl PageHandle<'_> {
         matches!(&self.page, PageKind::OpenApi(_))
     }
 
+    /// Renders the page. With [`Project::with_cache`], the AST is cached
+    /// for the next time the page is rendered with the same options.
     pub fn ast(&self, opts: Option<&RenderOptions>) -> Result<Ast> {
+        let cache = self.project.ast_cache.as_ref().map(|cache| {
+            let options = opts
+                .map(|o| o.cache_key())
+                .unwrap_or_else(|| RenderOptions::default().cache_key());
+            let key = cache.key(self.uri_path(), self.page.content_signature(), options);
+            (cache, key)
+        });
+
+        if let Some(ast) = cache.as_ref().and_then(|(cache, key)| cache.get(key)) {
+            return Ok(ast);
+        }
+
         let mut ctx = RenderContext::new();
         ctx.with_maybe_options(opts);
         ctx.with_project(self.project);
 
-        self.page.ast(&mut ctx)
+        let ast = self.page.ast(&mut ctx)?;
+
+        if let Some((cache, key)) = cache {
+            cache.insert(key, ast.clone());
+        }
+
+        Ok(ast)
     }
 
     pub fn hide_side_table_of_contents(&self) -> bool {
@@ -86,6 +256,23 @@ impl PageHandle<'_> {
         }
     }
 
+    /// The layout the page is rendered with: the one in its frontmatter,
+    /// or else the first matching rule or the default in the settings.
+    pub fn template(&self) -> Option<String> {
+        let own = match &self.page {
+            PageKind::Markdown(p) => p.layout_template(),
+            PageKind::OpenApi(_) => None,
+        };
+
+        own.or_else(|| {
+            self.project
+                .settings
+                .templates()
+                .default_for(self.fs_path())
+                .map(str::to_owned)
+        })
+    }
+
     pub fn page_width(&self) -> PageWidth {
         match &self.page {
             PageKind::Markdown(p) => p.page_width(),
@@ -93,6 +280,37 @@ impl PageHandle<'_> {
         }
     }
 
+    /// The contents of the style sheets in the page's `css` frontmatter.
+    /// With `css_scope: true`, their rules are limited to elements inside
+    /// the [`css_scope`](Self::css_scope) class.
+    ///
+    /// NOTE: Missing and invalid style sheets are reported by `verify`, and
+    /// left out here.
+    pub fn custom_css(&self) -> Vec<String> {
+        let PageKind::Markdown(page) = &self.page else {
+            return vec![];
+        };
+        let scope = self.css_scope();
+
+        page.css()
+            .iter()
+            .filter_map(|css| self.project.text_source(&page_css::asset_path(css)))
+            .filter_map(|content| match &scope {
+                Some(class) => page_css::scope(content, class).ok(),
+                None => Some(content.to_owned()),
+            })
+            .collect()
+    }
+
+    /// The class that scoped style sheets are limited to, for pages with
+    /// `css_scope: true`.
+    pub fn css_scope(&self) -> Option<String> {
+        match &self.page {
+            PageKind::Markdown(p) if p.css_scope() => Some(page_css::scope_class(self.uri_path())),
+            _ => None,
+        }
+    }
+
     pub fn on_this_page_headings(&self, opts: Option<&RenderOptions>) -> Vec<OnThisPageHeading> {
         match &self.page {
             PageKind::Markdown(p) => {
@@ -106,27 +324,12 @@ impl PageHandle<'_> {
         }
     }
 
-    pub(crate) fn outgoing_links(&self, opts: Option<&RenderOptions>) -> Result<Vec<OutgoingLink>> {
-        let mut ctx = RenderContext::new();
-        ctx.with_maybe_options(opts);
-        ctx.with_project(self.project);
-
-        self.page.outgoing_links(&mut ctx)
-    }
-
-    pub(crate) fn asset_links(&self, opts: Option<&RenderOptions>) -> Result<Vec<OutgoingLink>> {
-        let mut ctx = RenderContext::new();
-        ctx.with_maybe_options(opts);
-        ctx.with_project(self.project);
-
-        self.page.asset_links(&mut ctx)
-    }
-
-    pub(crate) fn external_links(&self, opts: Option<&RenderOptions>) -> Result<Vec<String>> {
+    /// Builds the page once and gathers its internal and asset links.
+    pub(crate) fn verification_links(&self, opts: Option<&RenderOptions>) -> Result<LinkSet> {
         let mut ctx = RenderContext::new();
         ctx.with_maybe_options(opts);
         ctx.with_project(self.project);
 
-        self.page.external_links(&mut ctx)
+        self.page.verification_links(&mut ctx)
     }
 }
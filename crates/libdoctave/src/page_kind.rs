@@ -1,7 +1,7 @@
 use crate::markdown::Node;
 use crate::open_api::ast::PageAst;
 use crate::utils::capitalize;
-use crate::{render_context::RenderContext, MarkdownPage, OpenApiPage, Result};
+use crate::{render_context::RenderContext, settings::Settings, MarkdownPage, OpenApiPage, Result};
 
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
@@ -40,12 +40,23 @@ pub(crate) enum PageKind {
 }
 
 #[derive(Debug, Clone, PartialEq)]
-/// Return type for the `outgoing_links` function.
+/// A link found in a page while verifying it.
 pub(crate) struct OutgoingLink {
     pub uri: String,
     /// If the original URI was relative, this will be the
     /// expanded absolut version of the URI.
     pub expanded_uri: Option<String>,
+    /// Where the link's destination was written in the source file, if known.
+    pub position: Option<crate::Position>,
+}
+
+/// The links gathered from a page while verifying it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct LinkSet {
+    pub internal: Vec<OutgoingLink>,
+    pub assets: Vec<OutgoingLink>,
+    /// Links to other sites, e.g. `https://example.com`
+    pub external: Vec<OutgoingLink>,
 }
 
 impl PageKind {
@@ -80,6 +91,14 @@ impl PageKind {
         }
     }
 
+    /// Changes whenever the content the page is rendered from changes.
+    pub(crate) fn content_signature(&self) -> u64 {
+        match self {
+            Self::Markdown(md) => md.content_signature(),
+            Self::OpenApi(oapi) => oapi.content_signature(),
+        }
+    }
+
     pub fn fs_path(&self) -> &Path {
         match self {
             Self::Markdown(md) => &md.path,
@@ -87,9 +106,11 @@ impl PageKind {
         }
     }
 
-    pub fn title(&self) -> Result<Option<String>> {
+    /// See [`PageHandle::title`](crate::PageHandle::title), which also falls
+    /// back to the project's title.
+    pub fn title(&self, settings: &Settings) -> Result<Option<String>> {
         match self {
-            Self::Markdown(md) => md.title(),
+            Self::Markdown(md) => md.title(settings),
             Self::OpenApi(oapi) => Ok(oapi.tag().map(capitalize)),
         }
     }
@@ -108,6 +129,20 @@ impl PageKind {
         }
     }
 
+    pub fn search_boost(&self) -> Result<f32> {
+        match self {
+            Self::Markdown(md) => md.search_boost(),
+            Self::OpenApi(_oapi) => Ok(1.0),
+        }
+    }
+
+    pub fn is_draft(&self) -> bool {
+        match self {
+            Self::Markdown(md) => md.is_draft(),
+            Self::OpenApi(_oapi) => false,
+        }
+    }
+
     pub fn openapi_tag(&self) -> Option<&str> {
         match self {
             Self::Markdown(_) => None,
@@ -131,32 +166,32 @@ impl PageKind {
 
         match result {
             Err(mut e) => {
-                e.in_file(self.fs_path());
+                match &self {
+                    Self::Markdown(p) => e.in_file(p.source_path()),
+                    // Errors in augmentations already point to their file
+                    Self::OpenApi(_) if e.file.is_some() => {}
+                    Self::OpenApi(_) => e.in_file(self.fs_path()),
+                }
                 Err(e)
             }
             ok => ok,
         }
     }
 
-    /// Lists all the links from the page.
-    pub(crate) fn outgoing_links(&self, ctx: &mut RenderContext) -> Result<Vec<OutgoingLink>> {
-        match &self {
-            Self::Markdown(p) => p.outgoing_links(ctx),
-            Self::OpenApi(p) => p.outgoing_links(ctx),
-        }
-    }
-
-    pub(crate) fn asset_links(&self, ctx: &mut RenderContext) -> Result<Vec<OutgoingLink>> {
+    /// Builds the page once and lists all the links from it. Errors in the
+    /// page are returned like from [`PageKind::ast`].
+    pub(crate) fn verification_links(&self, ctx: &mut RenderContext) -> Result<LinkSet> {
         match &self {
-            Self::Markdown(p) => p.asset_links(ctx),
-            Self::OpenApi(_p) => Ok(vec![]), // TODO
-        }
-    }
-
-    pub(crate) fn external_links(&self, ctx: &mut RenderContext) -> Result<Vec<String>> {
-        match &self {
-            Self::Markdown(p) => p.external_links(ctx),
-            Self::OpenApi(_) => Ok(vec![]), // TODO,
+            Self::Markdown(p) => p.verification_links(ctx).map_err(|mut e| {
+                e.in_file(p.source_path());
+                e
+            }),
+            Self::OpenApi(p) => p.verification_links(ctx).map_err(|mut e| {
+                if e.file.is_none() {
+                    e.in_file(self.fs_path());
+                }
+                e
+            }),
         }
     }
 }
@@ -172,21 +207,24 @@ mod test {
             String::new().into_bytes(),
         ));
 
-        assert_eq!(page.title(), Ok(None));
+        assert_eq!(page.title(&Settings::default()), Ok(None));
 
         let page = PageKind::Markdown(MarkdownPage::new(
             Path::new("Foo.md"),
             String::new().into_bytes(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("Foo".to_owned())));
+        assert_eq!(page.title(&Settings::default()), Ok(Some("Foo".to_owned())));
 
         let page = PageKind::Markdown(MarkdownPage::new(
             Path::new("something/README.md"),
             String::new().into_bytes(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("Something".to_owned())));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("Something".to_owned()))
+        );
     }
 
     #[test]
@@ -196,7 +234,10 @@ mod test {
             String::new().into_bytes(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("Foo in the Bar".to_string())));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("Foo in the Bar".to_string()))
+        );
     }
 
     #[test]
@@ -206,7 +247,10 @@ mod test {
             String::new().into_bytes(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("Foo in the Bar".to_string())));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("Foo in the Bar".to_string()))
+        );
     }
 
     #[test]
@@ -216,7 +260,10 @@ mod test {
             String::new().into_bytes(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("My case".to_string())));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("My case".to_string()))
+        );
     }
 
     #[test]
@@ -232,7 +279,10 @@ mod test {
             .to_owned(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("Something else".to_string())));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("Something else".to_string()))
+        );
     }
 
     #[test]
@@ -248,7 +298,10 @@ mod test {
             .to_owned(),
         ));
 
-        assert_eq!(page.title(), Ok(Some("lowercase".to_string())));
+        assert_eq!(
+            page.title(&Settings::default()),
+            Ok(Some("lowercase".to_string()))
+        );
     }
 
     #[test]
@@ -265,7 +318,7 @@ mod test {
             .to_owned(),
         ));
 
-        let e = page.title().unwrap_err();
+        let e = page.title(&Settings::default()).unwrap_err();
         assert_eq!(e.code, crate::Error::INVALID_FRONTMATTER);
     }
 
@@ -318,4 +371,36 @@ mod test {
 
         assert_eq!(page.hidden_from_search(), Ok(true));
     }
+
+    #[test]
+    fn can_opt_out_of_search_with_a_boolean() {
+        let page = PageKind::Markdown(MarkdownPage::new(
+            Path::new("Not-Me.md"),
+            indoc! {r#"
+            ---
+            search: false
+            ---
+            "#}
+            .as_bytes()
+            .to_owned(),
+        ));
+
+        assert_eq!(page.hidden_from_search(), Ok(true));
+    }
+
+    #[test]
+    fn reads_search_boost() {
+        let page = PageKind::Markdown(MarkdownPage::new(
+            Path::new("Boosted.md"),
+            indoc! {r#"
+            ---
+            search_boost: 2.5
+            ---
+            "#}
+            .as_bytes()
+            .to_owned(),
+        ));
+
+        assert_eq!(page.search_boost(), Ok(2.5));
+    }
 }
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+    markdown, navigation, project::publishes_drafts, render_context::RenderContext, tutorial,
+    PageHandle, Project, RenderOptions, Result,
+};
+
+/// A link to a neighbouring page, used for "Previous / Next" footer navigation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageLink {
+    pub href: String,
+    pub title: String,
+}
+
+/// Computes the previous and next pages for the given URI path, based on the
+/// order the pages appear in the active navigation.
+///
+/// Pages that are not in the navigation don't have neighbours. OpenAPI pages
+/// only link to other pages generated from the same spec. Steps of a
+/// tutorial link to the steps before and after them instead.
+pub(crate) fn compute(
+    uri_path: &str,
+    project: &Project,
+    opts: Option<&RenderOptions>,
+) -> Result<(Option<PageLink>, Option<PageLink>)> {
+    if let Some(links) = tutorial::previous_and_next(uri_path, project, opts) {
+        return Ok(links);
+    }
+
+    let subtab_path = project
+        .get_subtab_path_by_uri_path(uri_path)
+        .unwrap_or("/".to_string());
+
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+    ctx.with_maybe_options(opts);
+
+    let navigation = project.navigation(opts, &subtab_path)?;
+
+    // NOTE: Same as with breadcrumbs, the navigation links have any prefixes applied to them,
+    // so we compare against the final version of the links.
+    let pages = project
+        .published_pages(opts)
+        .into_iter()
+        .map(|p| {
+            (
+                navigation::link_identity(&markdown::parser::to_final_link(p.uri_path(), &ctx)),
+                p,
+            )
+        })
+        .collect::<HashMap<PathBuf, PageHandle>>();
+
+    let current = match project.find_page_by_uri_path(uri_path, publishes_drafts(opts)) {
+        Some(page) => page,
+        None => return Ok((None, None)),
+    };
+
+    let mut entries: Vec<(&navigation::Item, &PageHandle)> = vec![];
+
+    for item in navigation.flattened_links() {
+        let href = item.href().unwrap();
+
+        // Links to anchors point inside a page, so they are not separate pages.
+        // This also means OpenAPI operations are chained by their tag page.
+        if href.contains('#') {
+            continue;
+        }
+
+        let page = match pages.get(&navigation::link_identity(href)) {
+            Some(page) => page,
+            None => continue,
+        };
+
+        if current.is_openapi() && !(page.is_openapi() && page.fs_path() == current.fs_path()) {
+            continue;
+        }
+
+        if entries
+            .last()
+            .map(|(_, last)| last.uri_path() == page.uri_path())
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        entries.push((item, page));
+    }
+
+    let position = match entries
+        .iter()
+        .position(|(_, page)| page.uri_path() == current.uri_path())
+    {
+        Some(position) => position,
+        None => return Ok((None, None)),
+    };
+
+    // NOTE: The navigation hrefs are written by hand, and can point at the
+    // Markdown file instead of the page. Link to the page itself, with the same
+    // prefixes as every other link in the content API.
+    let to_link = |(item, page): &(&navigation::Item, &PageHandle)| PageLink {
+        href: markdown::parser::to_final_link(page.uri_path(), &ctx),
+        title: page.title().unwrap_or_else(|_| item.label().to_owned()),
+    };
+
+    let previous = position
+        .checked_sub(1)
+        .and_then(|i| entries.get(i))
+        .map(to_link);
+    let next = entries.get(position + 1).map(to_link);
+
+    Ok((previous, next))
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    use super::*;
+
+    fn project(navigation: &str) -> Project {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Home".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("first.md"),
+                content: InputContent::Text("---\ntitle: First page\n---\n".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("second.md"),
+                content: InputContent::Text("---\ntitle: Second page\n---\n".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("third.md"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("orphan.md"),
+                content: InputContent::Text("# Orphan".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(navigation.to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    title: Something
+                    "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        Project::from_file_list(files).unwrap()
+    }
+
+    #[test]
+    fn links_pages_in_navigation_order() {
+        let project = project(indoc! {r#"
+        ---
+        - heading: Something
+          items:
+            - label: First
+              href: first.md
+            - subheading: Nested
+              items:
+                - label: External
+                  external: https://www.example.com
+                - label: Second
+                  href: second.md
+        - heading: Else
+          items:
+            - label: Third label
+              href: third.md
+        "#});
+
+        let (previous, next) = compute("/second", &project, None).unwrap();
+
+        assert_eq!(
+            previous,
+            Some(PageLink {
+                href: "/first".to_string(),
+                title: "First page".to_string()
+            })
+        );
+        assert_eq!(
+            next,
+            Some(PageLink {
+                href: "/third".to_string(),
+                title: "Third".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn links_have_prefixes_applied() {
+        let project = project(indoc! {r#"
+        ---
+        - heading: Something
+          items:
+            - label: First
+              href: first.md
+            - label: Second
+              href: /second
+        "#});
+
+        let opts = RenderOptions {
+            prefix_link_urls: Some("/v2".to_string()),
+            ..Default::default()
+        };
+
+        let (previous, _) = compute("/second", &project, Some(&opts)).unwrap();
+        assert_eq!(previous.unwrap().href, "/v2/first");
+
+        let (_, next) = compute("/first", &project, Some(&opts)).unwrap();
+        assert_eq!(next.unwrap().href, "/v2/second");
+    }
+
+    #[test]
+    fn first_page_has_no_previous() {
+        let project = project(indoc! {r#"
+        ---
+        - heading: Something
+          items:
+            - label: First
+              href: first.md
+            - label: Second
+              href: second.md
+        "#});
+
+        let (previous, next) = compute("/first", &project, None).unwrap();
+
+        assert_eq!(previous, None);
+        assert_eq!(next.unwrap().title, "Second page");
+    }
+
+    #[test]
+    fn last_page_has_no_next() {
+        let project = project(indoc! {r#"
+        ---
+        - heading: Something
+          items:
+            - label: First
+              href: first.md
+            - label: Second
+              href: second.md
+        "#});
+
+        let (previous, next) = compute("/second", &project, None).unwrap();
+
+        assert_eq!(previous.unwrap().title, "First page");
+        assert_eq!(next, None);
+    }
+
+    #[test]
+    fn pages_not_in_navigation_have_no_neighbours() {
+        let project = project(indoc! {r#"
+        ---
+        - heading: Something
+          items:
+            - label: First
+              href: first.md
+            - label: Second
+              href: second.md
+        "#});
+
+        let (previous, next) = compute("/orphan", &project, None).unwrap();
+
+        assert_eq!(previous, None);
+        assert_eq!(next, None);
+    }
+}
@@ -1,32 +1,113 @@
+#[cfg(feature = "boilerplate")]
 use include_dir::{include_dir, Dir, DirEntry};
-use rayon::prelude::*;
 use std::collections::hash_map::DefaultHasher;
 use std::ffi::OsStr;
-use std::hash::Hasher;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use crate::analysis::Analysis;
+use crate::ast_cache::AstCache;
+use crate::canonical_path::canonicalize;
 use crate::content_api::{ContentApiResponse, ResponseContext};
 use crate::error_options::ErrorOptions;
-use crate::open_api::ast::PageAst;
-use crate::open_api::model::Components;
-use crate::open_api::OpenApi;
+use crate::health::HealthReport;
+use crate::icon::CustomIcons;
+use crate::inline_svg;
+use crate::open_api::ast::{PageAst, SchemaAst, SchemaLocation};
+use crate::open_api::augmentation::{self, Augmentation};
+use crate::open_api::default_responses;
+use crate::open_api::model::{Components, DoctaveExtension};
+use crate::open_api::overview::OverviewTemplate;
+use crate::open_api::{OpenApi, OperationUri};
+use crate::owners::{Owners, OWNERS_FILE_NAME};
+use crate::page_css;
 use crate::page_handle::PageHandle;
-use crate::page_kind::PageKind;
+use crate::page_kind::{LinkSet, OutgoingLink, PageKind};
+use crate::parallel::*;
+use crate::primitive_components::download::asset_path;
+use crate::project_diff::{ComponentUsage, ProjectDiff};
 use crate::render_context::{FileContext, RenderContext};
-use crate::settings::Settings;
-use crate::tabs::TabsList;
+use crate::settings::{ColorsV2, HtmlPolicy, LintLevel, Settings, UriStrategy};
+use crate::single_page::SinglePage;
+use crate::snapshot::ProjectSnapshot;
+use crate::statistics::ProjectStatistics;
+use crate::tabs::{path_in_scope, TabScope, TabsList};
+use crate::tutorial::{TutorialFile, TUTORIAL_FILE_NAME};
 use crate::SearchIndex;
 
 use crate::vale::{vale_results_to_errors, vale_runtime_error_to_error};
 use crate::{
     ast_mdx_fault_tolerant, frontmatter, navigation, renderable_ast, uri_to_fs_path, Ast,
-    CustomComponentHandle, Error, MarkdownPage, RenderOptions, BAKED_COMPONENTS,
-    NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+    CustomComponentHandle, Error, MarkdownPage, Point, Position, RenderOptions, Suggestion,
+    UndefinedVariables, BAKED_COMPONENTS, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[cfg(feature = "boilerplate")]
+static BASIC_BOILERPLATE: Dir = include_dir!("./crates/libdoctave/boilerplate_projects/basic");
+#[cfg(feature = "boilerplate")]
+static API_REFERENCE_BOILERPLATE: Dir =
+    include_dir!("./crates/libdoctave/boilerplate_projects/api-reference");
+#[cfg(feature = "boilerplate")]
+static MULTI_TAB_BOILERPLATE: Dir =
+    include_dir!("./crates/libdoctave/boilerplate_projects/multi-tab");
+#[cfg(feature = "boilerplate")]
+static KITCHEN_SINK_BOILERPLATE: Dir =
+    include_dir!("./crates/libdoctave/boilerplate_projects/kitchen-sink");
+/// The sample spec of the boilerplates with an API reference. Kept outside of
+/// them so that the binary only includes it once.
+#[cfg(feature = "boilerplate")]
+static SAMPLE_OPENAPI_SPEC: &[u8] = include_bytes!("../boilerplate_projects/shared/openapi.yaml");
+
+/// The starter projects new projects can be created from.
+#[cfg(feature = "boilerplate")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "clap",
+    derive(clap::ValueEnum),
+    clap(rename_all = "kebab-case")
+)]
+pub enum Boilerplate {
+    /// A single section of Markdown pages
+    #[default]
+    Basic,
+    /// API documentation generated from a sample OpenAPI spec
+    ApiReference,
+    /// Guides and an API reference, split into two tabs
+    MultiTab,
+    /// Every component and Markdown construct, for testing themes and renderers
+    KitchenSink,
+}
+
+#[cfg(feature = "boilerplate")]
+impl Boilerplate {
+    pub const ALL: [Boilerplate; 4] = [
+        Boilerplate::Basic,
+        Boilerplate::ApiReference,
+        Boilerplate::MultiTab,
+        Boilerplate::KitchenSink,
+    ];
+
+    fn dir(&self) -> &'static Dir<'static> {
+        match self {
+            Boilerplate::Basic => &BASIC_BOILERPLATE,
+            Boilerplate::ApiReference => &API_REFERENCE_BOILERPLATE,
+            Boilerplate::MultiTab => &MULTI_TAB_BOILERPLATE,
+            Boilerplate::KitchenSink => &KITCHEN_SINK_BOILERPLATE,
+        }
+    }
 
-static BOILERPLATE_PROJECT: Dir = include_dir!("./crates/libdoctave/boilerplate_project");
+    /// Files shared with other boilerplates, added on top of the directory
+    fn shared_files(&self) -> Vec<(PathBuf, &'static [u8])> {
+        match self {
+            Boilerplate::Basic | Boilerplate::KitchenSink => vec![],
+            Boilerplate::ApiReference | Boilerplate::MultiTab => {
+                vec![(PathBuf::from("openapi.yaml"), SAMPLE_OPENAPI_SPEC)]
+            }
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub(crate) struct NavigationHandle(pub String);
@@ -35,6 +116,11 @@ pub(crate) struct NavigationHandle(pub String);
 pub struct Asset {
     pub path: PathBuf,
     pub signature: u64,
+    /// Size of the file. For binary files this is what the caller reported.
+    pub size_bytes: usize,
+    /// Sanitized markup of SVG files given as text, for inlining into pages,
+    /// or the reason the file can't be inlined
+    pub(crate) svg: Option<Result<String, String>>,
 }
 
 impl PartialEq for Asset {
@@ -55,7 +141,7 @@ pub struct PageOptions {
     pub hide_side_table_of_contents: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 /// A beter typed interface for ensuring that we are explicit about the kinds of
 /// files we get.
 pub struct InputFile {
@@ -65,31 +151,60 @@ pub struct InputFile {
 
 impl InputFile {
     fn into_internal_repr(self) -> (PathBuf, String) {
+        let path = canonicalize(&self.path);
+
         match self.content {
-            InputContent::Binary(signature) => (self.path, signature),
-            InputContent::Text(t) => (self.path, t),
+            InputContent::Binary { signature, .. } => (path, signature),
+            InputContent::Text(t) => (path, t),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// Information about an input file that libdoctave can't find out on its
+/// own, since it doesn't do IO. Gathered by the caller, e.g. from the
+/// filesystem or from git.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileMetadata {
+    /// When the file was last modified, as an RFC 3339 timestamp
+    pub modified_at: Option<String>,
+    /// People who have edited the file, most recent first
+    pub authors: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum InputContent {
-    Binary(String),
+    /// A file whose contents libdoctave doesn't need, like an image. The
+    /// signature should change whenever the file does, e.g. a modification
+    /// time, and the size is only used for reporting.
+    Binary {
+        signature: String,
+        size_bytes: usize,
+    },
+    /// Pages, settings and specs. SVGs under `_assets` should be given as
+    /// text too, so that they can be inlined into pages.
     Text(String),
 }
 
 impl InputContent {
     pub fn text(&self) -> Option<&str> {
         match &self {
-            InputContent::Binary(_) => None,
+            InputContent::Binary { .. } => None,
             InputContent::Text(t) => Some(t.as_str()),
         }
     }
+
+    /// Size of the file in bytes.
+    pub fn size_bytes(&self) -> usize {
+        match &self {
+            InputContent::Binary { size_bytes, .. } => *size_bytes,
+            InputContent::Text(t) => t.len(),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct Project {
-    navigations: Option<HashMap<String, Option<NavigationHandle>>>,
+    pub(crate) navigations: Option<HashMap<String, Option<NavigationHandle>>>,
     pub(crate) pages: Vec<PageKind>,
     tabs: Option<TabsList>,
     /// Number of bytes taken by all the content in this project.
@@ -104,7 +219,66 @@ pub struct Project {
     pub(crate) input_paths: Vec<PathBuf>,
     pub(crate) custom_components: Vec<CustomComponentHandle>,
     pub(crate) open_api_components: HashMap<String, Components>,
+    /// Specs parsed while loading the project, keyed by spec file, along with
+    /// a hash of the content they were parsed from. Shared between clones of
+    /// the project so that rendering OpenAPI pages doesn't parse them again.
+    pub(crate) open_api_specs: HashMap<String, ParsedSpec>,
+    /// Examples that don't match their schemas, for specs that have opted
+    /// into example validation, and problems with the default responses of
+    /// the specs. Reported during `verify`.
+    pub(crate) open_api_example_errors: Vec<Error>,
+    /// Problems with the `uri_prefix` of the specs, located in the settings
+    /// file while loading the project. Reported during `verify`.
+    pub(crate) open_api_prefix_errors: Vec<Error>,
+    /// Specs whose `uri_prefix` is nested under another spec's. Reported
+    /// as warnings.
+    pub(crate) open_api_prefix_warnings: Vec<Error>,
+    /// Redirects from the URI path of a page, located in the settings file
+    /// while loading the project. Reported during `verify`.
+    pub(crate) shadowed_redirect_errors: Vec<Error>,
+    /// Wildcard redirects that cover pages. Reported as warnings.
+    pub(crate) shadowed_redirect_warnings: Vec<Error>,
+    /// Who owns the files, with the owner of every page resolved up front
+    pub(crate) owners: Owners,
+    /// Lines of the owners file that couldn't be read. Reported during
+    /// `verify`.
+    pub(crate) owner_errors: Vec<Error>,
+    /// Markdown files added to the pages of operations, keyed by the spec
+    /// file they were loaded for
+    pub(crate) open_api_augmentations: HashMap<String, Vec<Augmentation>>,
+    /// The `_tutorial.yaml` files, sorted by path
+    pub(crate) tutorials: Vec<TutorialFile>,
+    /// SVG icons from `_assets/icons`, sanitized on load
+    pub(crate) custom_icons: CustomIcons,
+    /// Metadata for the input files, keyed by their canonical path
+    pub(crate) file_metadata: HashMap<PathBuf, FileMetadata>,
+    /// Sizes of the input files in bytes, keyed by their canonical path
+    pub(crate) file_sizes: HashMap<PathBuf, usize>,
+    /// Custom components used by each page, for finding the pages affected
+    /// by a change to a component
+    pub(crate) component_usage: ComponentUsage,
     pub custom_css: Vec<String>,
+    /// Rendered ASTs, if caching was turned on with [`Project::with_cache`].
+    /// Shared between clones of the project.
+    pub(crate) ast_cache: Option<Arc<AstCache>>,
+    /// The input files with canonical paths, sorted by path. Kept for
+    /// fingerprints and snapshots.
+    pub(crate) sources: Arc<Vec<InputFile>>,
+}
+
+/// An OpenAPI spec parsed while loading the project.
+#[derive(Clone)]
+pub(crate) struct ParsedSpec {
+    content_hash: u64,
+    spec: Arc<openapi_parser::OpenAPI>,
+}
+
+impl ParsedSpec {
+    fn hash(content: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(content.as_bytes());
+        hasher.finish()
+    }
 }
 
 impl Project {
@@ -119,7 +293,39 @@ impl Project {
     /// verified in any way.
     ///
     pub fn from_file_list(list: Vec<InputFile>) -> Result<Project, Vec<Error>> {
-        let input_paths = list.iter().map(|i| i.path.clone()).collect::<Vec<_>>();
+        Self::from_file_list_with_metadata(list, HashMap::new())
+    }
+
+    /// Builds a project out of several documentation sources, each mounted
+    /// under its URI prefix, e.g. `("sdk", files)` for SDK docs at `/sdk`.
+    /// Links within and between the sources are rewritten to where the files
+    /// end up. See [`crate::compose`] for the details.
+    pub fn compose(
+        sources: Vec<(String, Vec<InputFile>)>,
+        settings: crate::compose::ComposeSettings,
+    ) -> Result<Project, Vec<Error>> {
+        crate::compose::compose(sources, settings)
+    }
+
+    /// Like [`Project::from_file_list`], but with metadata about the files,
+    /// like when they were last modified. Files without metadata are fine.
+    pub fn from_file_list_with_metadata(
+        list: Vec<InputFile>,
+        metadata: HashMap<PathBuf, FileMetadata>,
+    ) -> Result<Project, Vec<Error>> {
+        let mut sources = list
+            .iter()
+            .map(|i| InputFile {
+                path: canonicalize(&i.path),
+                content: i.content.clone(),
+            })
+            .collect::<Vec<_>>();
+        sources.sort();
+
+        let input_paths = list
+            .iter()
+            .map(|i| canonicalize(&i.path))
+            .collect::<Vec<_>>();
 
         let content_size_bytes =
             list.iter()
@@ -129,6 +335,19 @@ impl Project {
                     acc
                 });
 
+        let file_sizes = list
+            .iter()
+            .map(|i| (canonicalize(&i.path), i.content.size_bytes()))
+            .collect::<HashMap<_, _>>();
+
+        // Only SVGs given as text can be inlined into pages
+        let text_svgs = list
+            .iter()
+            .filter(|i| i.content.text().is_some())
+            .map(|i| canonicalize(&i.path))
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("svg"))
+            .collect::<HashSet<_>>();
+
         let list = list
             .into_iter()
             .map(|f| f.into_internal_repr())
@@ -148,6 +367,8 @@ impl Project {
                         .to_owned(),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })
             }
         }
@@ -158,6 +379,9 @@ impl Project {
         let mut pages = Vec::new();
         let mut custom_components = BAKED_COMPONENTS.to_vec();
         let mut open_api_components = HashMap::new();
+        let mut open_api_example_errors = Vec::new();
+        let mut open_api_augmentations = HashMap::new();
+        let mut open_api_specs = HashMap::new();
 
         // Go through all files in the list, sorting out partials and pages
         for (path, content) in list
@@ -176,12 +400,24 @@ impl Project {
                 assets.push(Asset {
                     path: path.clone(),
                     signature: hasher.finish(),
+                    size_bytes: file_sizes.get(path).copied().unwrap_or_default(),
+                    svg: text_svgs
+                        .contains(path)
+                        .then(|| inline_svg::sanitize(content)),
                 });
             }
         }
 
+        let settings_content = list
+            .iter()
+            .find(|(path, _)| path == Path::new(SETTINGS_FILE_NAME))
+            .map(|(_, content)| content.as_str())
+            .unwrap_or_default();
+
+        let duplicate_prefixes = settings.duplicate_open_api_prefixes();
+
         // Gather open_api specs
-        for spec in settings.open_api() {
+        for (index, spec) in settings.open_api().iter().enumerate() {
             let mut hasher = DefaultHasher::new();
 
             hasher.write(spec.spec_file.to_string_lossy().to_string().as_bytes());
@@ -189,15 +425,49 @@ impl Project {
             assets.push(Asset {
                 path: spec.spec_file.clone(),
                 signature: hasher.finish(),
+                size_bytes: file_sizes
+                    .get(&canonicalize(&spec.spec_file))
+                    .copied()
+                    .unwrap_or_default(),
+                svg: None,
             });
 
-            if let Some(entry) = list.iter().find(|(p, _)| p == &spec.spec_file) {
-                let mut parsed_spec = Self::parse_openapi_spec(spec, &entry.1)?;
+            // Reported by the verify step. Generating pages for only some of
+            // the specs would depend on their order in the settings.
+            if duplicate_prefixes.contains(spec.uri_prefix.as_str()) {
+                continue;
+            }
+
+            if let Some(entry) = list
+                .iter()
+                .find(|(p, _)| p == &canonicalize(&spec.spec_file))
+            {
+                let parsed_spec = Arc::new(Self::parse_openapi_spec(spec, &entry.1)?);
+
+                // A missing template is reported by the verify step, so
+                // fall back to the generated overview in the meantime.
+                let overview_template = spec.overview_template.as_ref().and_then(|template| {
+                    list.iter().find(|(p, _)| p == &canonicalize(template)).map(
+                        |(path, content)| OverviewTemplate {
+                            path: path.clone(),
+                            content: content.clone(),
+                        },
+                    )
+                });
+
+                let augmentations = Augmentation::in_dir(
+                    &canonicalize(&spec.augmentations_dir()),
+                    list.iter().map(|(path, content)| (path, content)),
+                );
 
                 let openapi_pages = OpenApi::pages_from_parsed_spec(
                     &parsed_spec,
                     spec.spec_file.clone(),
                     spec.uri_prefix.clone(),
+                    overview_template,
+                    &augmentations,
+                    spec.uri_strategy,
+                    &spec.default_responses,
                 )
                 .map_err(|e| vec![e])?;
 
@@ -205,9 +475,35 @@ impl Project {
                     pages.push(page);
                 }
 
+                if spec.validate_examples {
+                    open_api_example_errors.extend(OpenApi::example_errors(
+                        &parsed_spec,
+                        &spec.spec_file,
+                        &entry.1,
+                    ));
+                }
+
+                open_api_example_errors.extend(default_responses::errors(
+                    &parsed_spec,
+                    spec,
+                    index,
+                    settings_content,
+                ));
+
                 open_api_components.insert(
                     spec.spec_file.to_string_lossy().to_string(),
-                    OpenApi::components_parsed(&mut parsed_spec).map_err(|e| vec![e])?,
+                    OpenApi::components_parsed(&parsed_spec).map_err(|e| vec![e])?,
+                );
+
+                open_api_augmentations
+                    .insert(spec.spec_file.to_string_lossy().to_string(), augmentations);
+
+                open_api_specs.insert(
+                    spec.spec_file.to_string_lossy().to_string(),
+                    ParsedSpec {
+                        content_hash: ParsedSpec::hash(&entry.1),
+                        spec: parsed_spec,
+                    },
                 );
             } else {
                 // Skip - we tried searching for an OpenAPI spec, but couldn't find one.
@@ -215,7 +511,21 @@ impl Project {
             }
         }
 
-        let tabs = settings.tabs();
+        let custom_icons =
+            CustomIcons::from_files(list.iter().map(|(path, content)| (path, content)));
+
+        let tabs = settings.tabs().map(|mut tabs| {
+            tabs.resolve_icons(&custom_icons);
+
+            if let Some((_, content)) = list
+                .iter()
+                .find(|(path, _)| path == Path::new(SETTINGS_FILE_NAME))
+            {
+                tabs.locate(content);
+            }
+
+            tabs
+        });
 
         let navigations: Option<HashMap<_, _>> = if let Some(ref tabs) = tabs {
             let mut navs = vec![];
@@ -248,14 +558,32 @@ impl Project {
             navigation_handle.map(|nav| HashMap::from_iter(vec![("/".to_string(), Some(nav))]))
         };
 
-        for (path, content) in list
+        // Sorted, so that the page served for a duplicate URI path doesn't
+        // depend on the order the files were read in.
+        let mut markdown_files = list
             .iter()
             .filter(|(path, _)| path != Path::new(SETTINGS_FILE_NAME))
             .filter(|(path, _)| path.extension() == Some(std::ffi::OsStr::new("md")))
-        {
+            .collect::<Vec<_>>();
+        markdown_files.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (path, content) in markdown_files {
+            let is_overview_template = settings.open_api().iter().any(|spec| {
+                spec.overview_template
+                    .as_ref()
+                    .map(|t| &canonicalize(t) == path)
+                    .unwrap_or(false)
+            });
+
+            let is_augmentation = settings.open_api().iter().any(|spec| {
+                path.parent() == Some(canonicalize(&spec.augmentations_dir()).as_path())
+            });
+
             if !path.starts_with("_partials")
                 && !path.starts_with("_components")
                 && !path.starts_with("_topics")
+                && !is_overview_template
+                && !is_augmentation
             {
                 pages.push(PageKind::Markdown(MarkdownPage::new(
                     path,
@@ -267,11 +595,30 @@ impl Project {
         let custom_css = settings
             .styles()
             .iter()
-            .flat_map(|path| list.iter().find(|(p, _)| p == path).map(|(_, c)| c.clone()))
+            .flat_map(|path| {
+                list.iter()
+                    .find(|(p, _)| p == &canonicalize(path))
+                    .map(|(_, c)| c.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let mut tutorials = list
+            .iter()
+            .filter(|(path, _)| path.file_name() == Some(OsStr::new(TUTORIAL_FILE_NAME)))
+            .map(|(path, content)| TutorialFile {
+                path: path.clone(),
+                content: content.clone(),
+            })
             .collect::<Vec<_>>();
+        tutorials.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let open_api_prefix_errors = settings.open_api_prefix_errors(settings_content, &pages);
+        let open_api_prefix_warnings = settings.open_api_prefix_warnings(settings_content);
+
+        let component_usage = ComponentUsage::build(&pages, &custom_components);
 
         // Safe to unwrap here as errors have been found already
-        Ok(Project {
+        let mut project = Project {
             navigations,
             tabs,
             content_size_bytes,
@@ -282,42 +629,188 @@ impl Project {
             input_paths,
             custom_components,
             open_api_components,
-        })
+            open_api_specs,
+            open_api_example_errors,
+            open_api_prefix_errors,
+            open_api_prefix_warnings,
+            shadowed_redirect_errors: vec![],
+            shadowed_redirect_warnings: vec![],
+            owners: Owners::default(),
+            owner_errors: vec![],
+            open_api_augmentations,
+            tutorials,
+            custom_icons,
+            component_usage,
+            file_sizes,
+            file_metadata: metadata
+                .into_iter()
+                .map(|(path, metadata)| (canonicalize(&path), metadata))
+                .collect(),
+            ast_cache: None,
+            sources: Arc::new(sources),
+        };
+
+        crate::whats_new::add_page(&mut project);
+
+        project.shadowed_redirect_errors = project
+            .settings
+            .shadowed_redirect_errors(settings_content, &project.pages);
+        project.shadowed_redirect_warnings = project
+            .settings
+            .shadowed_redirect_warnings(settings_content, &project.pages);
+
+        let owners_file = list
+            .iter()
+            .find(|(path, _)| path == Path::new(OWNERS_FILE_NAME))
+            .map(|(_, content)| content.as_str());
+        let (owners, owner_errors) =
+            Owners::new(owners_file, project.settings.owners(), &project.pages);
+        project.owners = owners;
+        project.owner_errors = owner_errors;
+
+        Ok(project)
+    }
+
+    /// Caches the ASTs of rendered pages, so that rendering a page again with
+    /// the same options doesn't parse it again. Keeps at most `capacity`
+    /// ASTs, dropping the least recently used ones.
+    ///
+    /// The cache is shared between clones of the project, and is safe to use
+    /// from multiple threads.
+    pub fn with_cache(mut self, capacity: usize) -> Self {
+        self.ast_cache = Some(Arc::new(AstCache::new(capacity, &self.settings)));
+        self
+    }
+
+    /// A hash of everything the project was built from: the settings,
+    /// navigation, pages, custom components and CSS, specs, assets, and file
+    /// metadata. Doesn't depend on the order the files were given in, so it
+    /// can be used as a cache key for builds.
+    ///
+    /// Only stable between runs of the same version of libdoctave.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.sources.hash(&mut hasher);
+
+        let mut metadata = self.file_metadata.iter().collect::<Vec<_>>();
+        metadata.sort_by(|a, b| a.0.cmp(b.0));
+        metadata.hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// The files the project was built from, for persisting it and restoring
+    /// it later with [`Project::from_snapshot`].
+    pub fn snapshot(&self) -> ProjectSnapshot {
+        let mut metadata = self
+            .file_metadata
+            .iter()
+            .map(|(path, metadata)| (path.clone(), metadata.clone()))
+            .collect::<Vec<_>>();
+        metadata.sort_by(|a, b| a.0.cmp(&b.0));
+
+        ProjectSnapshot {
+            fingerprint: self.fingerprint(),
+            files: self.sources.as_ref().clone(),
+            metadata,
+        }
+    }
+
+    /// Restores a project from a [`ProjectSnapshot`]. Parsed specs, rendered
+    /// ASTs and other derived state are rebuilt, and caching has to be turned
+    /// on again with [`Project::with_cache`].
+    pub fn from_snapshot(snapshot: ProjectSnapshot) -> Result<Project, Vec<Error>> {
+        Self::from_file_list_with_metadata(snapshot.files, snapshot.metadata.into_iter().collect())
+    }
+
+    /// Drops the cached ASTs of the page with the given URI path
+    pub fn invalidate_cached_page(&self, uri_path: &str) {
+        if let Some(cache) = &self.ast_cache {
+            cache.invalidate(uri_path);
+        }
+    }
+
+    /// Drops all cached ASTs
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.ast_cache {
+            cache.clear();
+        }
+    }
+
+    pub(crate) fn file_metadata(&self, path: &Path) -> Option<&FileMetadata> {
+        self.file_metadata.get(&canonicalize(path))
+    }
+
+    /// The contents of a text file in the project, like a style sheet.
+    pub(crate) fn text_source(&self, path: &Path) -> Option<&str> {
+        let path = canonicalize(path);
+
+        self.sources
+            .iter()
+            .find(|file| canonicalize(&file.path) == path)
+            .and_then(|file| file.content.text())
     }
 
     pub fn parse_openapi_spec(
         spec: &crate::settings::OpenApi,
         content: &str,
     ) -> Result<openapi_parser::OpenAPI, Vec<Error>> {
+        use openapi_parser::openapi30::parser;
+
+        let to_errors = |e: parser::Error| {
+            let message = match e {
+                parser::Error::Limit(_) => "OpenAPI spec is too large",
+                _ => "Could not parse OpenAPI spec",
+            };
+
+            vec![Error {
+                code: Error::INVALID_OPENAPI_SPEC,
+                message: message.to_owned(),
+                description: e.to_string(),
+                file: Some(spec.spec_file.clone()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            }]
+        };
+
         match spec.spec_file.extension().and_then(OsStr::to_str) {
-            Some("json") => openapi_parser::openapi30::parser::parse_json(content).map_err(|e| {
-                vec![Error {
-                    code: Error::INVALID_OPENAPI_SPEC,
-                    message: "Could not parse OpenAPI spec".to_owned(),
-                    description: e.to_string(),
-                    file: Some(spec.spec_file.clone()),
-                    position: None,
-                }]
-            }),
-            Some("yaml") => openapi_parser::openapi30::parser::parse_yaml(content).map_err(|e| {
-                vec![Error {
-                    code: Error::INVALID_OPENAPI_SPEC,
-                    message: "Could not parse OpenAPI spec".to_owned(),
-                    description: e.to_string(),
-                    file: Some(spec.spec_file.clone()),
-                    position: None,
-                }]
-            }),
+            Some("json") => {
+                parser::parse_json_with_limits(content, spec.parser_limits()).map_err(to_errors)
+            }
+            Some("yaml") => {
+                parser::parse_yaml_with_limits(content, spec.parser_limits()).map_err(to_errors)
+            }
             _ => Err(vec![Error {
                 code: Error::INVALID_OPENAPI_SPEC,
                 message: "Could not parse OpenAPI spec".to_owned(),
                 description: "OpenAPI spec must be a JSON or YAML file.".to_string(),
                 file: Some(spec.spec_file.clone()),
                 position: None,
+                owner: None,
+                suggestions: vec![],
             }])?,
         }
     }
 
+    /// Returns the spec parsed when the project was loaded if it was parsed
+    /// from the same content, and parses the content otherwise.
+    fn cached_openapi_spec(
+        &self,
+        spec: &crate::settings::OpenApi,
+        content: &str,
+    ) -> Result<Arc<openapi_parser::OpenAPI>, Vec<Error>> {
+        match self
+            .open_api_specs
+            .get(spec.spec_file.to_string_lossy().as_ref())
+        {
+            Some(cached) if cached.content_hash == ParsedSpec::hash(content) => {
+                Ok(cached.spec.clone())
+            }
+            _ => Self::parse_openapi_spec(spec, content).map(Arc::new),
+        }
+    }
+
     pub fn openapi_ast_for_tag(
         &self,
         spec: &crate::settings::OpenApi,
@@ -325,7 +818,7 @@ impl Project {
         tag: Option<&str>,
         opts: Option<&RenderOptions>,
     ) -> Result<(PageAst, Vec<String>), Vec<Error>> {
-        let parsed_spec = Self::parse_openapi_spec(spec, content)?;
+        let parsed_spec = self.cached_openapi_spec(spec, content)?;
 
         let all_tags = parsed_spec.tag_names();
 
@@ -333,6 +826,10 @@ impl Project {
             &parsed_spec,
             spec.spec_file.clone(),
             spec.uri_prefix.clone(),
+            None,
+            self.augmentations_for(spec),
+            spec.uri_strategy,
+            &spec.default_responses,
         ) {
             if let Some(oapi) = openapi_pages.iter().find(|p| p.openapi_tag() == tag) {
                 let mut ctx = RenderContext::new();
@@ -356,14 +853,29 @@ impl Project {
         content: &str,
         opts: Option<&RenderOptions>,
     ) -> Result<(renderable_ast::Node, Vec<String>), Vec<Error>> {
-        let parsed_spec = Self::parse_openapi_spec(spec, content)?;
+        let parsed_spec = self.cached_openapi_spec(spec, content)?;
 
         let all_tags = parsed_spec.tag_names();
 
+        // Reuse the template of the project's current overview page, if any
+        let overview_template = self.pages.iter().find_map(|p| match p {
+            PageKind::Markdown(md) if md.uri_path == spec.uri_prefix => {
+                md.template.as_ref().map(|t| OverviewTemplate {
+                    path: t.path.clone(),
+                    content: md.content.clone(),
+                })
+            }
+            _ => None,
+        });
+
         if let Ok(openapi_pages) = OpenApi::pages_from_parsed_spec(
             &parsed_spec,
             spec.spec_file.clone(),
             spec.uri_prefix.clone(),
+            overview_template,
+            self.augmentations_for(spec),
+            spec.uri_strategy,
+            &spec.default_responses,
         ) {
             if let Some(overview_page) = openapi_pages.iter().find(|p| p.markdown().is_some()) {
                 let mut ctx = RenderContext::new();
@@ -381,6 +893,64 @@ impl Project {
         Err(vec![])
     }
 
+    /// Finds a schema in an OpenAPI spec by the JSON pointer of a collapsed
+    /// schema, so that schemas nested deeper than `max_schema_depth` can be
+    /// loaded on demand. The returned schema is collapsed again
+    /// `max_schema_depth` levels further down.
+    pub fn openapi_schema_fragment(
+        &self,
+        spec_file: &Path,
+        pointer: &str,
+        opts: Option<&RenderOptions>,
+    ) -> crate::Result<SchemaAst> {
+        let spec = spec_file.to_string_lossy().to_string();
+        let tokens = pointer
+            .split('/')
+            .skip(1)
+            .map(|t| t.replace("~1", "/").replace("~0", "~"))
+            .collect::<Vec<_>>();
+
+        let schema = match tokens.as_slice() {
+            [components, schemas, name, rest @ ..]
+                if components == "components" && schemas == "schemas" =>
+            {
+                self.open_api_components
+                    .get(&spec)
+                    .and_then(|c| c.schemas.get(name))
+                    .and_then(|s| s.find_nested(rest))
+            }
+            [operations, anchor, rest @ ..] if operations == "operations" => self
+                .pages
+                .iter()
+                .filter_map(|p| match p {
+                    PageKind::OpenApi(page) if page.fs_path == spec_file => Some(page),
+                    _ => None,
+                })
+                .flat_map(|page| page.operations())
+                .find(|op| &op.anchor_tag == anchor)
+                .and_then(|op| op.find_schema(rest)),
+            _ => None,
+        };
+
+        let schema = schema.ok_or_else(|| Error {
+            code: Error::INVALID_OPENAPI_SCHEMA,
+            message: "OpenAPI schema not found".to_owned(),
+            description: format!("Could not find a schema at `{}`", pointer),
+            file: Some(spec_file.to_path_buf()),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        })?;
+
+        let mut ctx = RenderContext::new();
+        ctx.with_maybe_options(opts);
+        ctx.with_project(self);
+
+        let location = SchemaLocation::at(spec, pointer, self.settings.max_schema_depth(spec_file));
+
+        SchemaAst::from_model(schema, &ctx, true, &location)
+    }
+
     pub fn get_openapi_paths(settings_content: String) -> crate::Result<Vec<String>> {
         let settings = Settings::parse(&settings_content)?;
 
@@ -396,7 +966,7 @@ impl Project {
         uri_path: &str,
         ctx: ResponseContext,
     ) -> ContentApiResponse {
-        let page = self.get_page_by_uri_path(uri_path);
+        let page = self.get_localized_page_by_uri_path(uri_path, Some(&ctx.options));
 
         if let Some(page) = page {
             ContentApiResponse::content(page, self, ctx)
@@ -411,7 +981,7 @@ impl Project {
         mut ctx: ResponseContext,
     ) -> (String, u16) {
         ctx.debug_info.start_performance("LIBDOCTAVE_CONTENT");
-        let page = self.get_page_by_uri_path(uri_path);
+        let page = self.get_localized_page_by_uri_path(uri_path, Some(&ctx.options));
 
         let mut res = if let Some(page) = page {
             ContentApiResponse::content(page, self, ctx)
@@ -441,7 +1011,7 @@ impl Project {
         let mut ctx = RenderContext::new();
 
         ctx.with_file_context(FileContext::new(
-            markdown[..frontmatter::end_pos(markdown)].lines().count(),
+            frontmatter::end_line(markdown),
             frontmatter::end_pos(markdown),
             fs_path.to_owned(),
         ));
@@ -456,8 +1026,10 @@ impl Project {
         self.pages()
             .iter()
             .find(|p| match p.page {
-                PageKind::Markdown(_) => p.fs_path() == fs_path,
-                PageKind::OpenApi(o) => p.fs_path() == fs_path && o.tag() == tag_name,
+                PageKind::Markdown(_) => p.fs_path() == canonicalize(fs_path).as_path(),
+                PageKind::OpenApi(o) => {
+                    canonicalize(p.fs_path()) == canonicalize(fs_path) && o.tag() == tag_name
+                }
             })
             .map(|p| p.uri_path().to_owned())
             .unwrap_or_else(|| fs_path.display().to_string())
@@ -505,6 +1077,12 @@ impl Project {
             errors.push(error);
         }
 
+        for error in self.verify_unique_uri_paths() {
+            errors.push(error);
+        }
+
+        errors.extend(self.custom_icons.verify());
+
         for error in self.verify_navigation(opts) {
             errors.push(error);
         }
@@ -515,14 +1093,29 @@ impl Project {
 
         self.settings.verify(self, &mut errors);
 
-        let shared = std::sync::Mutex::new(&mut errors);
+        errors.extend(crate::feed::verify(self, opts));
 
-        self.pages().iter().for_each(|p| {
-            if let Err(error) = p.ast(opts) {
-                let mut e = shared.lock().unwrap();
-                e.push(error);
-            }
-        });
+        errors.extend(crate::whats_new::verify(self));
+
+        errors.extend(crate::tutorial::verify(self));
+
+        errors.extend(self.open_api_example_errors.iter().cloned());
+
+        errors.extend(self.open_api_prefix_errors.iter().cloned());
+
+        errors.extend(self.shadowed_redirect_errors.iter().cloned());
+
+        errors.extend(self.owner_errors.iter().cloned());
+
+        errors.extend(
+            self.parsed_open_api_specs().flat_map(|(spec_file, spec)| {
+                OpenApi::duplicate_operation_id_errors(spec, spec_file)
+            }),
+        );
+
+        errors.extend(self.verify_open_api_augmentations());
+
+        self.verify_pages(opts, &mut errors);
 
         let mut ctx = RenderContext::new();
         ctx.with_project(self);
@@ -537,6 +1130,8 @@ impl Project {
                         description: e.render(&handle.content, &ctx),
                         file: Some(handle.path.clone()),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
             }
@@ -547,11 +1142,19 @@ impl Project {
                     description: e.render(&handle.content, &ctx),
                     file: Some(handle.path.clone()),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })
             }
         }
 
-        self.verify_internal_links(&mut errors);
+        self.verify_navigation_links(&mut errors);
+
+        errors.extend(crate::accessibility::lint(self, LintLevel::Error));
+        errors.extend(crate::orphans::lint(self, LintLevel::Error));
+        errors.extend(crate::secrets::lint(self, LintLevel::Error));
+
+        self.owners.assign(&mut errors);
 
         if !errors.is_empty() {
             errors.sort();
@@ -572,88 +1175,213 @@ impl Project {
             _ => None,
         });
 
+        let templates = self.settings.templates();
+
         for page in markdown_pages {
             // First, check that we have a valid frontmatter
-            if let Err(error) = page.frontmatter() {
-                errors.push(error);
+            let frontmatter = match page.frontmatter() {
+                Ok(frontmatter) => frontmatter,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+
+            if let Some(template) = frontmatter
+                .template
+                .filter(|name| !templates.is_allowed(name))
+            {
+                errors.push(Error {
+                    code: Error::INVALID_FRONTMATTER,
+                    message: String::from("Unknown template in frontmatter"),
+                    description: templates.unknown_template_description(&template),
+                    file: Some(page.source_path().to_owned()),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            if let Some(url) = frontmatter
+                .canonical_url
+                .as_ref()
+                .filter(|url| !crate::settings::is_absolute_url(url))
+            {
+                errors.push(Error {
+                    code: Error::INVALID_FRONTMATTER,
+                    message: String::from("Invalid `canonical_url` in frontmatter"),
+                    description: format!(
+                        "Expected an absolute URL, like \"https://docs.example.com/guides/setup\".\nFound \"{}\".",
+                        url
+                    ),
+                    file: Some(page.source_path().to_owned()),
+                    position: frontmatter::value_position(&page.content, "canonical_url"),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            for css in &frontmatter.css {
+                let Some(content) = self.text_source(&page_css::asset_path(css)) else {
+                    errors.push(Error {
+                        code: Error::INVALID_FRONTMATTER,
+                        message: format!("Could not find style sheet file at \"{}\".", css),
+                        description: format!(
+                            "Found [{}].\nMake sure the file name is correct and located under the \"{}\" directory.",
+                            self.assets
+                                .iter()
+                                .filter(|a| a.path.extension().and_then(|s| s.to_str()) == Some("css"))
+                                .map(|s| format!("\"{}\"", s.path.display()))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            page_css::PAGE_CSS_DIR,
+                        ),
+                        file: Some(page.source_path().to_owned()),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                    continue;
+                };
+
+                if frontmatter.css_scope {
+                    if let Err(e) = page_css::scope(content, "css-scope") {
+                        errors.push(Error {
+                            code: Error::INVALID_FRONTMATTER,
+                            message: format!("Could not scope style sheet \"{}\"", css),
+                            description: format!(
+                                "The style sheet is included with `css_scope: true`, but it could not be parsed.\n{}",
+                                e
+                            ),
+                            file: Some(page.source_path().to_owned()),
+                            position: None,
+                            owner: None,
+                            suggestions: vec![],
+                        });
+                    }
+                }
             }
         }
 
         errors
     }
 
-    fn verify_internal_links(&self, errors: &mut Vec<Error>) {
-        self.verify_page_links(errors);
-        self.verify_navigation_links(errors);
+    /// Builds every page once, in parallel, reporting errors in the pages
+    /// along with broken links found in the resulting ASTs.
+    fn verify_pages(&self, opts: Option<&RenderOptions>, errors: &mut Vec<Error>) {
+        let opts = opts
+            .map(RenderOptions::without_link_rewrites)
+            .unwrap_or_default();
+
+        let page_errors = self
+            .pages()
+            .par_iter()
+            .flat_map_iter(|p| self.page_errors(p, &opts))
+            .collect::<Vec<_>>();
+
+        errors.extend(page_errors);
     }
 
-    fn verify_page_links(&self, mut errors: &mut Vec<Error>) {
-        let shared = std::sync::Mutex::new(&mut errors);
-        let render_opts = vec![RenderOptions::default()];
+    /// Verifies a single page: that it renders, and that its links lead
+    /// somewhere. For previewing a page while it's edited. Checks that need
+    /// the whole project, like for orphaned pages, are left to `verify`.
+    pub fn verify_page(&self, uri_path: &str, opts: Option<&RenderOptions>) -> Vec<Error> {
+        let opts = opts
+            .map(RenderOptions::without_link_rewrites)
+            .unwrap_or_default();
 
-        self.pages().par_iter().for_each(|p| {
-            for opts in &render_opts {
-                if let Ok(links) = p.outgoing_links(Some(opts)) {
-                    for link in links.iter() {
-                        let path = PathBuf::from(link.expanded_uri.as_ref().unwrap_or(&link.uri));
-                        let uri = crate::fs_to_uri_path(&path);
+        let mut errors = match self.find_page_by_uri_path(uri_path, true) {
+            Some(page) => self.page_errors(&page, &opts),
+            None => vec![],
+        };
 
-                        if self.get_page_by_uri_path(&uri).is_none()
-                            && !self.redirects().iter().any(|r| r.0 == uri)
-                        {
-                            let error = if p.is_markdown() {
-                                Error {
-                                    code: Error::BROKEN_INTERNAL_LINK,
-                                    message: String::from("Broken link detected"),
-                                    description: format!(
-                                        "Link {} points to an unknown file.",
-                                        link.uri
-                                    ),
-                                    file: Some(p.fs_path().to_owned()),
-                                    position: None,
-                                }
-                            } else {
-                                Error {
-                                    code: Error::BROKEN_INTERNAL_LINK,
-                                    message: String::from("Broken link in OpenAPI spec"),
-                                    description: format!(
-                                        "Link {} in OpenAPI spec {} points to an unknown file.",
-                                        link.uri,
-                                        p.fs_path().display(),
-                                    ),
-                                    file: Some(PathBuf::from(p.uri_path())),
-                                    position: None,
-                                }
-                            };
-                            let mut e = shared.lock().unwrap();
-                            e.push(error);
-                        }
-                    }
-                }
+        self.owners.assign(&mut errors);
 
-                if let Ok(asset_links) = p.asset_links(Some(opts)) {
-                    for link in asset_links.iter() {
-                        let path = PathBuf::from(link.expanded_uri.as_ref().unwrap_or(&link.uri));
-
-                        if self.get_asset_by_fs_path(&path).is_none() {
-                            let error = Error {
-                                code: Error::BROKEN_INTERNAL_LINK,
-                                message: String::from("Broken asset link detected"),
-                                description: format!(
-                                    "Link {} points to an unknown file.",
-                                    link.uri
-                                ),
-                                file: Some(p.fs_path().to_owned()),
-                                position: None,
-                            };
-
-                            let mut e = shared.lock().unwrap();
-                            e.push(error);
-                        }
+        errors
+    }
+
+    fn page_errors(&self, p: &PageHandle, opts: &RenderOptions) -> Vec<Error> {
+        match p.verification_links(Some(opts)) {
+            Ok(links) => self.verify_page_links(p, &links),
+            Err(error) => vec![error],
+        }
+    }
+
+    fn verify_page_links(&self, p: &PageHandle, links: &LinkSet) -> Vec<Error> {
+        let mut errors = vec![];
+
+        // Links in translated pages resolve within their own locale first
+        let (locale, _) = self.settings.split_locale(p.uri_path());
+
+        for link in links.internal.iter() {
+            let path = PathBuf::from(link.expanded_uri.as_ref().unwrap_or(&link.uri));
+            let uri = crate::fs_to_uri_path(&path);
+
+            let target = self.find_localized_page(&uri, locale, true);
+
+            // Published pages can't link to pages that won't be published
+            if let (None, Some(status)) = (
+                p.draft_status(),
+                target.as_ref().and_then(|t| t.draft_status()),
+            ) {
+                errors.push(Error {
+                    code: Error::BROKEN_INTERNAL_LINK,
+                    message: String::from("Link to an unpublished page detected"),
+                    description: format!("Link {} points to a page that is {}.", link.uri, status),
+                    file: Some(p.fs_path().to_owned()),
+                    position: link.position.clone(),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            if target.is_none() && !self.has_redirect_from(&uri) {
+                let error = if p.is_markdown() {
+                    Error {
+                        code: Error::BROKEN_INTERNAL_LINK,
+                        message: String::from("Broken link detected"),
+                        description: format!("Link {} points to an unknown file.", link.uri),
+                        file: Some(p.fs_path().to_owned()),
+                        position: link.position.clone(),
+                        owner: None,
+                        suggestions: self.link_suggestions(p, link, &uri),
                     }
-                }
+                } else {
+                    Error {
+                        code: Error::BROKEN_INTERNAL_LINK,
+                        message: String::from("Broken link in OpenAPI spec"),
+                        description: format!(
+                            "Link {} in OpenAPI spec {} points to an unknown file.",
+                            link.uri,
+                            p.fs_path().display(),
+                        ),
+                        file: Some(PathBuf::from(p.uri_path())),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    }
+                };
+                errors.push(error);
             }
-        });
+        }
+
+        for link in links.assets.iter() {
+            let path = PathBuf::from(link.expanded_uri.as_ref().unwrap_or(&link.uri));
+
+            if self.get_asset_by_fs_path(&path).is_none() {
+                errors.push(Error {
+                    code: Error::BROKEN_INTERNAL_LINK,
+                    message: String::from("Broken asset link detected"),
+                    description: format!("Link {} points to an unknown file.", link.uri),
+                    file: Some(p.fs_path().to_owned()),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        errors
     }
 
     fn verify_navigation_links(&self, errors: &mut Vec<Error>) {
@@ -664,7 +1392,7 @@ impl Project {
                 let navigations_with_handle = navigations
                     .iter()
                     .filter_map(|(path, handle)| handle.as_ref().map(|h| (path, h)));
-                for (subtab_path, _nav_handle) in navigations_with_handle {
+                for (subtab_path, nav_handle) in navigations_with_handle {
                     let nav_file_path = PathBuf::from(subtab_path).join(NAVIGATION_FILE_NAME);
 
                     if let Ok(nav) = self.navigation(Some(opts), subtab_path) {
@@ -675,8 +1403,11 @@ impl Project {
                                     self.get_page_by_fs_path(&uri_to_fs_path(&internal_link))
                                 })
                                 .is_none()
-                                && !self.redirects().iter().any(|r| r.0 == internal_link)
+                                && !self.has_redirect_from(&internal_link)
                             {
+                                let position =
+                                    nav_link_position(&nav_handle.0, "href", &internal_link);
+
                                 let error = Error {
                                     code: Error::BROKEN_INTERNAL_LINK,
                                     message: String::from("Broken link detected in navigation"),
@@ -685,7 +1416,19 @@ impl Project {
                                         &internal_link
                                     ),
                                     file: Some(nav_file_path.to_owned()),
-                                    position: None,
+                                    owner: None,
+                                    suggestions: position
+                                        .as_ref()
+                                        .and_then(|pos| {
+                                            self.nav_link_suggestion(
+                                                &nav_file_path,
+                                                pos,
+                                                &internal_link,
+                                            )
+                                        })
+                                        .into_iter()
+                                        .collect(),
+                                    position,
                                 };
                                 errors.push(error);
                             }
@@ -708,6 +1451,8 @@ impl Project {
                                 description: "All your project's tabs have to have a root README.md file. This is the first page readers will see in your tab.".to_owned(),
                                 file: None,
             position: None,
+            owner: None,
+            suggestions: vec![],
                             });
                 }
 
@@ -719,6 +1464,8 @@ impl Project {
                                   description: "All your project's tabs have to have a root README.md file. This is the first page readers will see in your tab.".to_owned(),
                                   file: None,
             position: None,
+            owner: None,
+            suggestions: vec![],
                               });
                     }
                 }
@@ -730,1004 +1477,3599 @@ impl Project {
                 description: "Your project has to have a root README.md file. This is the first page readers will see in your project.".to_owned(),
                 file: None,
             position: None,
+            owner: None,
+            suggestions: vec![],
             });
         }
 
         errors
     }
 
-    fn verify_tabs(&self) -> Vec<Error> {
-        match &self.tabs {
-            Some(tabs) => tabs.verify(),
-            None => vec![],
+    /// Reports pages that end up at the same URI path, e.g. `Foo.md` and
+    /// `foo/README.md`, or an OpenAPI tag page and a Markdown page under the
+    /// spec's `uri_prefix`. Only the first page is served, in the same order
+    /// as `find_page_by_uri_path`: OpenAPI pages before Markdown pages, and
+    /// Markdown pages sorted by their path.
+    fn verify_unique_uri_paths(&self) -> Vec<Error> {
+        let mut by_uri_path: HashMap<&str, Vec<&PageKind>> = HashMap::new();
+
+        for page in &self.pages {
+            by_uri_path.entry(page.uri_path()).or_default().push(page);
         }
-    }
 
-    /// Verifies that the structure of the navigation is correct.
-    /// Note this does not check for broken links.
-    fn verify_navigation(&self, _opts: Option<&RenderOptions>) -> Vec<Error> {
         let mut errors = vec![];
 
-        if self.navigations.is_none() {
-            let message = if self.tabs.is_none() {
-                "Missing navigation.yaml in project root".to_owned()
-            } else {
-                "Missing a navigation.yaml".to_owned()
-            };
+        for (uri_path, pages) in by_uri_path {
+            if pages.len() < 2 {
+                continue;
+            }
 
-            errors.push(Error {
-                code: Error::MISSING_NAVIGATION,
-                message,
-                description: "Could not build navigation structure".to_owned(),
-                file: None,
-                position: None,
-            });
+            let sources = pages
+                .iter()
+                .map(|p| match p {
+                    PageKind::OpenApi(oapi) => match oapi.tag() {
+                        Some(tag) => {
+                            format!(r#""{}" (OpenAPI tag "{}")"#, oapi.fs_path.display(), tag)
+                        }
+                        None => format!(r#""{}" (OpenAPI overview)"#, oapi.fs_path.display()),
+                    },
+                    PageKind::Markdown(md) => format!(r#""{}""#, md.path.display()),
+                })
+                .collect::<Vec<_>>();
+
+            for (page, _) in pages.iter().zip(&sources).skip(1) {
+                errors.push(Error {
+                    code: Error::DUPLICATE_URI_PATH,
+                    message: format!(
+                        r#"Duplicate URI path "{}" for {}"#,
+                        uri_path,
+                        sources.join(", ")
+                    ),
+                    description: format!(
+                        "Only one page can be served at \"{}\". {} takes precedence, and the other pages are unreachable.\n\n\
+                        OpenAPI pages take precedence over Markdown pages, and Markdown pages are ordered by their path. \
+                        File names are case insensitive in URLs, so rename or move one of the files to give each page a unique URI path.",
+                        uri_path, sources[0]
+                    ),
+                    file: Some(page.fs_path().to_path_buf()),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
         }
 
-        if let Some(navs) = &self.navigations {
-            for (subtab_path, nav_handle) in navs {
-                let mut errors_for_nav = vec![];
-                if let Some(nav_handle) = nav_handle {
-                    let nav_file_path = PathBuf::from(subtab_path).join(NAVIGATION_FILE_NAME);
-                    let mut nav_errors = navigation::verify(&nav_handle.0, self);
-                    errors_for_nav.append(&mut nav_errors);
+        errors
+    }
 
-                    // This is kind of ugly, but right now a bit hesitant to pass context
-                    // into navigation verification just for error reporting as it runs
-                    // quite deep into it. Therefore, we just mutate the file path after
-                    // verification has ran.
-                    errors_for_nav
-                        .iter_mut()
-                        .for_each(|error| error.file = Some(nav_file_path.to_owned()));
+    fn verify_tabs(&self) -> Vec<Error> {
+        match &self.tabs {
+            Some(tabs) => {
+                let mut errors = tabs.verify();
 
-                    errors.append(&mut errors_for_nav);
-                } else {
-                    errors.push(Error {
-                        code: Error::MISSING_NAVIGATION,
-                        message: match subtab_path.as_str() {
-                            "/" => "Missing navigation.yaml in project root".to_owned(),
-                            _ => "Missing navigation.yaml for tab".to_owned(),
-                        },
-                        description: format!("Could not find navigation.yaml in `{}`", subtab_path),
-                        file: None,
-                        position: None,
-                    });
-                }
+                errors.extend(tabs.icons().filter_map(|icon| {
+                    self.custom_icons
+                        .verify_icon(icon, Path::new(SETTINGS_FILE_NAME))
+                }));
+
+                errors.extend(self.verify_tab_content(tabs));
+                errors.extend(self.verify_tab_landing_pages(tabs));
+
+                errors
             }
+            None => vec![],
         }
-
-        errors
     }
 
-    pub fn get_external_links(&self) -> Vec<String> {
-        let render_opts = vec![RenderOptions::default()];
+    /// Checks that every tab and subtab path has pages or a navigation under
+    /// it. A typo in a path would otherwise quietly show its pages under the
+    /// root tab.
+    fn verify_tab_content(&self, tabs: &TabsList) -> Vec<Error> {
+        let mut errors = vec![];
 
-        self.pages()
+        let candidates = self
+            .pages()
             .iter()
             .flat_map(|p| {
-                let mut out = vec![];
+                Path::new(p.uri_path())
+                    .ancestors()
+                    .map(|a| a.to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<HashSet<_>>();
 
-                for opts in &render_opts {
-                    if let Ok(links) = p.external_links(Some(opts)) {
-                        out.append(&mut links.to_vec());
-                    }
+        for parent in &tabs.tabs {
+            let subtabs = parent.subtabs.iter().map(|subtab| (subtab, "Subtab"));
+
+            for (tab, id) in std::iter::once((parent, "Tab")).chain(subtabs) {
+                if tab.is_external || self.tab_has_content(&tab.href) {
+                    continue;
                 }
 
-                out
-            })
-            .collect()
+                let mut description = format!(
+                    "No pages or {} found under the path \"{}\".",
+                    NAVIGATION_FILE_NAME, tab.href
+                );
+
+                if let Some(closest) =
+                    crate::utils::closest_match(&tab.href, candidates.iter().map(|c| c.as_str()))
+                {
+                    description.push_str(&format!(" Did you mean \"{}\"?", closest));
+                }
+
+                errors.push(Error {
+                    code: Error::INVALID_STRUCTURE,
+                    message: format!(r#"{} "{}" has no content."#, id, tab.label),
+                    description,
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: tab.position.clone(),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        errors
     }
 
-    pub fn search_index(&self) -> crate::Result<SearchIndex> {
-        SearchIndex::new(self)
+    /// Checks that landing pages exist, and belong to their tab. A landing
+    /// page elsewhere would switch the active tab when clicked.
+    fn verify_tab_landing_pages(&self, tabs: &TabsList) -> Vec<Error> {
+        let mut errors = vec![];
+
+        for parent in &tabs.tabs {
+            let subtabs = parent.subtabs.iter().map(|subtab| (subtab, "Subtab"));
+
+            for (tab, id) in std::iter::once((parent, "Tab")).chain(subtabs) {
+                let Some(landing_page) = tab.landing_page.as_deref() else {
+                    continue;
+                };
+
+                let description = if self.get_page_by_uri_path(landing_page).is_none() {
+                    format!(
+                        "Could not find a page at \"{}\" for the landing page of {} \"{}\".",
+                        landing_page,
+                        id.to_lowercase(),
+                        tab.label
+                    )
+                } else if !path_in_scope(landing_page, &tab.href) {
+                    format!(
+                        "Expected the landing page of {} \"{}\" to be under \"{}\".\nFound \"{}\".",
+                        id.to_lowercase(),
+                        tab.label,
+                        tab.href,
+                        landing_page
+                    )
+                } else {
+                    continue;
+                };
+
+                errors.push(Error {
+                    code: Error::INVALID_STRUCTURE,
+                    message: format!("Invalid landing page for {}", id.to_lowercase()),
+                    description,
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: tab.key_position("landing_page"),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        errors
     }
 
-    pub fn boilerplate_file_list() -> Vec<(PathBuf, Vec<u8>)> {
-        let mut files = vec![];
+    /// CSS variables for the tabs and subtabs that override the accent
+    /// color. They apply to pages whose `<body>` has the tab's `data-tab`
+    /// index, or the subtab's `data-subtab` index, like `1-0`.
+    pub fn tab_accent_css(&self) -> String {
+        let Some(tabs) = &self.tabs else {
+            return String::new();
+        };
+        let default_colors = ColorsV2::default();
+        let colors = self
+            .settings
+            .theme()
+            .map(|t| &t.colors)
+            .unwrap_or(&default_colors);
+
+        let mut css = vec![];
+
+        for (tab_index, tab) in tabs.tabs.iter().enumerate() {
+            let selector = format!("body[data-tab=\"{}\"]", tab_index);
+            if let Some(accent) = &tab.accent_color {
+                css.extend(colors.accent_css(accent, &selector));
+            }
 
-        fn gather_recursively(entries: &[DirEntry], files: &mut Vec<(PathBuf, Vec<u8>)>) {
-            for entry in entries {
-                match entry {
-                    DirEntry::Dir(d) => gather_recursively(d.entries(), files),
-                    DirEntry::File(file) => {
-                        // Make sure we don't dump .DS_Store files
-                        if !file.path().ends_with(".DS_Store") {
-                            files.push((file.path().to_owned(), file.contents().to_owned()));
-                        }
-                    }
+            for (subtab_index, subtab) in tab.subtabs.iter().enumerate() {
+                let selector = format!("body[data-subtab=\"{}-{}\"]", tab_index, subtab_index);
+                if let Some(accent) = &subtab.accent_color {
+                    css.extend(colors.accent_css(accent, &selector));
                 }
             }
         }
 
-        gather_recursively(BOILERPLATE_PROJECT.entries(), &mut files);
+        css.join("\n\n")
+    }
 
-        files
+    fn tab_has_content(&self, href: &str) -> bool {
+        self.pages()
+            .iter()
+            .any(|p| path_in_scope(p.uri_path(), href))
+            || self
+                .navigations
+                .as_ref()
+                .and_then(|navs| navs.get(href))
+                .is_some_and(|nav| nav.is_some())
     }
 
-    pub fn root_navigation(
+    /// Compares this project to a newer version of it, reporting which pages
+    /// were added, removed, or changed, and whether anything shown on every
+    /// page changed. Used by the dev server to only reload affected pages.
+    pub fn diff_pages(&self, other: &Project) -> ProjectDiff {
+        ProjectDiff::between(self, other)
+    }
+
+    /// Who owns the file, from the `owners` in the settings or the
+    /// `.docapella-owners` file. Paths are relative to the project root.
+    pub fn owner_of(&self, path: &Path) -> Option<&str> {
+        self.owners.owner_of(path)
+    }
+
+    /// Sizes and counts of the project's pages, assets, and OpenAPI specs,
+    /// for keeping track of how large the published bundle is.
+    pub fn statistics(&self) -> ProjectStatistics {
+        ProjectStatistics::gather(self)
+    }
+
+    /// Verifies the project, and gathers its warnings, keeping the results
+    /// together so that reports like [`Project::health_report_for`] can be
+    /// built from them without running the checks again.
+    pub fn analyze(
         &self,
         opts: Option<&RenderOptions>,
-    ) -> crate::Result<navigation::Navigation> {
-        self.navigation(opts, "/")
+        err_opts: Option<&ErrorOptions>,
+    ) -> Analysis {
+        Analysis::run(self, opts, err_opts)
     }
 
-    pub fn navigation(
+    /// How healthy the documentation is: counts of errors and warnings, how
+    /// many pages and operations are described, and a weighted score. Runs
+    /// [`Project::analyze`] first; use [`Project::health_report_for`] if the
+    /// project was already analyzed.
+    pub fn health_report(&self, opts: Option<&RenderOptions>) -> HealthReport {
+        self.health_report_for(&self.analyze(opts, None), opts)
+    }
+
+    /// Same as [`Project::health_report`], built from an existing analysis.
+    pub fn health_report_for(
         &self,
+        analysis: &Analysis,
         opts: Option<&RenderOptions>,
-        subtab_path: &str,
-    ) -> crate::Result<navigation::Navigation> {
-        let mut ctx = RenderContext::new();
-        ctx.with_project(self);
-        ctx.with_maybe_options(opts);
+    ) -> HealthReport {
+        HealthReport::gather(self, analysis, opts)
+    }
 
-        let mut path = subtab_path.to_string();
+    /// Renders every page, returning the issues found along the way that
+    /// don't stop pages from rendering:
+    ///
+    /// - Variables and fields that expressions in pages and navigation labels
+    ///   referenced but that don't exist, when `undefined_variables` is set to
+    ///   [`UndefinedVariables::Warn`]. They fail rendering by default, and are
+    ///   reported by [`Project::verify`].
+    /// - HTML removed by the sanitizer, when `html_policy` is set to
+    ///   [`HtmlPolicy::Sanitize`], which is the default.
+    /// - References to footnotes that aren't defined on the page.
+    /// - SVGs that couldn't be inlined, and are shown as regular images.
+    ///
+    /// [`Project::expression_warnings`] and [`Project::html_warnings`] return
+    /// the first two kinds on their own.
+    pub fn render_warnings(&self, opts: Option<&RenderOptions>) -> Vec<Error> {
+        let warn_undefined = opts.map(|o| o.undefined_variables) == Some(UndefinedVariables::Warn);
+        let may_reference_footnotes = self.pages.iter().any(|p| match p {
+            PageKind::Markdown(m) => m.content.contains("[^"),
+            PageKind::OpenApi(_) => false,
+        });
+        let may_inline_svgs = self.pages.iter().any(|p| match p {
+            PageKind::Markdown(m) => m.content.contains("inline"),
+            PageKind::OpenApi(_) => false,
+        });
 
-        if let Some(_structure) = &self.tabs {
-            path = format!(
-                "/{}",
-                subtab_path.trim_start_matches('/').trim_end_matches('/')
-            )
+        if !warn_undefined
+            && !may_reference_footnotes
+            && !may_inline_svgs
+            && self.settings.html_policy() != HtmlPolicy::Sanitize
+        {
+            return vec![];
         }
 
-        match path.as_str() {
-            "/" => {}
-            path => {
-                ctx.with_url_base(path);
-            }
-        };
+        let mut warnings: Vec<Error> = self
+            .pages()
+            .par_iter()
+            .flat_map_iter(|p| {
+                let mut ctx = RenderContext::new();
+                ctx.with_maybe_options(opts);
+                ctx.with_project(self);
 
-        match &self.navigations {
-            Some(navs) => match navs.get(&path) {
-                Some(Some(nav_handle)) => navigation::build(&nav_handle.0, &ctx, self),
-                None | Some(None) => Err(Error {
-                    code: Error::MISSING_NAVIGATION,
-                    message: "Missing navigation.yaml for tab".to_owned(),
-                    description: format!("Could not find navigation.yaml in `{}`", subtab_path),
-                    file: None,
-                    position: None,
-                }),
-            },
-            None => Err(Error {
-                code: Error::MISSING_NAVIGATION,
-                message: "Missing navigation.yaml in project root".to_owned(),
-                description: "Could not build navigation structure".to_owned(),
-                file: None,
-                position: None,
-            }),
-        }
-    }
+                // Pages that fail to render are reported by `verify`
+                let _ = p.page.ast(&mut ctx);
 
-    pub fn navigation_has_link_to(&self, path: &str, opts: Option<&RenderOptions>) -> bool {
-        self.navigations
-            .as_ref()
-            .map(|navs| {
-                navs.keys().any(|subtab_path| {
-                    if let Ok(nav) = self.navigation(opts, subtab_path) {
-                        nav.has_link_to(path)
-                    } else {
-                        false
-                    }
+                let warnings = ctx
+                    .warnings
+                    .lock()
+                    .map(|mut w| std::mem::take(&mut *w))
+                    .unwrap_or_default();
+
+                warnings.into_iter().map(|mut w| {
+                    w.file = w.file.or_else(|| Some(p.fs_path().to_owned()));
+                    w
                 })
             })
-            .unwrap_or(false)
-    }
+            .collect();
 
-    pub fn settings(&self) -> &Settings {
-        &self.settings
-    }
+        if warn_undefined {
+            for (subtab_path, nav_handle) in self.navigations.iter().flatten() {
+                let Some(nav_handle) = nav_handle else {
+                    continue;
+                };
 
-    pub fn tabs(&self) -> Option<&TabsList> {
-        self.tabs.as_ref()
-    }
+                warnings.extend(
+                    navigation::undefined_label_variables(&nav_handle.0, self)
+                        .into_iter()
+                        .map(|mut w| {
+                            w.file = Some(PathBuf::from(subtab_path).join(NAVIGATION_FILE_NAME));
+                            w
+                        }),
+                );
+            }
+        }
 
-    pub fn autocomplete(
-        &self,
-        markdown: &str,
-        fs_path: &Path,
-        render_opts: Option<&RenderOptions>,
-    ) -> Vec<crate::CompletionItem> {
-        let mut ctx = RenderContext::new();
-        ctx.with_maybe_options(render_opts);
-        ctx.with_project(self);
+        self.owners.assign(&mut warnings);
 
-        crate::markdown::autocomplete(markdown, fs_path, self, &ctx)
+        warnings
     }
 
-    pub fn get_page_by_uri_path(&self, uri_path: &str) -> Option<PageHandle<'_>> {
-        // If we get an anchor in the URI, remove it.
-        let without_anchor = uri_path.split('#').collect::<Vec<_>>()[0];
-
-        for page in &self.pages {
-            if page.uri_path() == without_anchor {
-                return Some(PageHandle {
-                    page,
-                    project: self,
-                });
-            }
+    /// Renders every page, returning the variables and fields that expressions
+    /// referenced but that don't exist. Only reported when `undefined_variables`
+    /// is set to [`UndefinedVariables::Warn`], since they fail rendering by
+    /// default, and are reported by [`Project::verify`] instead.
+    pub fn expression_warnings(&self, opts: Option<&RenderOptions>) -> Vec<Error> {
+        if opts.map(|o| o.undefined_variables) != Some(UndefinedVariables::Warn) {
+            return vec![];
         }
 
-        None
+        self.render_warnings(opts)
+            .into_iter()
+            .filter(|w| w.code == Error::INVALID_EXPRESSION)
+            .collect()
     }
 
-    pub fn get_page_by_fs_path(&self, path: &Path) -> Option<PageHandle<'_>> {
-        for page in &self.pages {
-            if let PageKind::Markdown(md) = page {
-                if Self::normalize_fs_path(md.path.as_ref()) == Self::normalize_fs_path(path) {
-                    return Some(PageHandle {
-                        page,
-                        project: self,
-                    });
-                }
-            }
+    /// Renders every page, returning the HTML that the sanitizer removed.
+    /// Only reported when `html_policy` is set to [`HtmlPolicy::Sanitize`],
+    /// which is the default.
+    pub fn html_warnings(&self, opts: Option<&RenderOptions>) -> Vec<Error> {
+        if self.settings.html_policy() != HtmlPolicy::Sanitize {
+            return vec![];
         }
 
-        None
+        self.render_warnings(opts)
+            .into_iter()
+            .filter(|w| w.code == Error::UNSAFE_HTML)
+            .collect()
     }
 
-    pub fn get_asset_by_fs_path(&self, path: &Path) -> Option<&Asset> {
-        self.assets
+    /// Issues that don't prevent the project from being published, but that
+    /// the author probably wants to know about.
+    pub fn warnings(&self) -> Vec<Error> {
+        let mut warnings = self
+            .tabs
             .iter()
-            .find(|a| Self::normalize_fs_path(&a.path) == Self::normalize_fs_path(path))
-    }
+            .flat_map(|tabs| tabs.icons())
+            .filter_map(|icon| CustomIcons::shadowing_warning(icon, Path::new(SETTINGS_FILE_NAME)))
+            .collect::<Vec<_>>();
 
-    pub fn get_subtab_path_by_uri_path(&self, uri_path: &str) -> Option<String> {
-        match &self.tabs {
-            Some(tabs) => {
-                let normalized_uri_path = format!(
-                    "/{}",
-                    uri_path.trim_end_matches('/').trim_start_matches('/')
-                );
+        warnings.extend(self.missing_tab_navigation_warnings());
 
-                let mut matching_paths = vec!["/".to_string()];
+        warnings.extend(self.deep_navigation_warnings());
 
-                for tab in tabs.tabs.iter() {
-                    if normalized_uri_path.contains(&tab.href) {
-                        matching_paths.push(tab.href.clone());
-                    }
+        warnings.extend(self.openapi_group_warnings());
 
-                    for subtab in &tab.subtabs {
-                        if normalized_uri_path.contains(&subtab.href) {
-                            matching_paths.push(subtab.href.clone());
-                        };
-                    }
-                }
+        warnings.extend(self.open_api_prefix_warnings.iter().cloned());
 
-                /*
-                    We get the deepest match. For examples, /foo/bar/md
-                    would return /foo/bar, not /foo.
-                */
-                matching_paths
-                    .iter()
-                    .enumerate()
-                    .max_by_key(|(_, path)| path.len())
-                    .map(|(_, value)| value)
-                    .cloned()
-            }
-            None => None,
-        }
-    }
+        warnings.extend(self.shadowed_redirect_warnings.iter().cloned());
 
-    fn normalize_fs_path(path: &Path) -> &Path {
-        path.strip_prefix("/").unwrap_or(path)
-    }
+        warnings.extend(self.deprecated_operation_link_warnings());
 
-    pub fn pages(&self) -> Vec<PageHandle<'_>> {
-        self.pages
-            .iter()
-            .map(|page| PageHandle {
-                page,
-                project: self,
-            })
-            .collect::<Vec<_>>()
-    }
-
-    fn settings_redirects(&self) -> Vec<(String, String)> {
-        self.settings
-            .redirects()
-            .iter()
-            .map(|r| r.as_tuple())
-            .collect()
-    }
+        warnings.extend(self.title_warnings());
 
-    pub fn redirects(&self) -> Vec<(String, String)> {
-        let mut redirects = vec![];
+        warnings.extend(self.page_css_warnings());
 
-        redirects.append(&mut self.settings_redirects());
+        warnings.extend(self.unclosed_frontmatter_warnings());
 
-        redirects
-    }
-}
+        warnings.extend(crate::accessibility::lint(self, LintLevel::Warn));
+        warnings.extend(crate::orphans::lint(self, LintLevel::Warn));
+        warnings.extend(crate::secrets::lint(self, LintLevel::Warn));
 
-impl std::fmt::Debug for Project {
-    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        fmt.debug_struct("Project")
-            .field("navigation", &self.root_navigation(None))
-            .field("pages", &self.pages)
-            .field("settings", &self.settings)
-            .finish()?;
+        warnings.extend(
+            self.parsed_open_api_specs().flat_map(|(spec_file, spec)| {
+                OpenApi::missing_operation_id_warnings(spec, spec_file)
+            }),
+        );
 
-        Ok(())
-    }
-}
+        warnings.extend(self.owners.unowned_pages_warning(&self.pages));
 
-impl Default for Project {
-    fn default() -> Self {
-        let file_list = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(String::from("")),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project")),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(String::from(indoc! {r#"
-            - heading: "Guides"
-              items:
-                - href: foo/bar.md
-                  label: Example
-            "#})),
-            },
-        ];
+        self.owners.assign(&mut warnings);
 
-        Self::from_file_list(file_list).unwrap()
+        warnings
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::Ast;
-    use pretty_assertions::assert_str_eq;
+    /// The parsed OpenAPI specs, in the order of the settings.
+    fn augmentations_for(&self, spec: &crate::settings::OpenApi) -> &[Augmentation] {
+        self.open_api_augmentations
+            .get(spec.spec_file.to_string_lossy().as_ref())
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
 
-    use super::*;
+    /// Reports augmentations named after operationIds that none of the specs
+    /// reading from their folder have, so that they don't go unnoticed when
+    /// operations are removed or renamed.
+    fn verify_open_api_augmentations(&self) -> Vec<Error> {
+        let mut by_dir: BTreeMap<PathBuf, (&[Augmentation], HashSet<String>)> = BTreeMap::new();
+
+        for spec in self.settings.open_api() {
+            let Some(parsed) = self
+                .open_api_specs
+                .get(spec.spec_file.to_string_lossy().as_ref())
+            else {
+                continue;
+            };
 
-    #[test]
-    fn verifies_each_page() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("{% badliquid, %}".to_string()),
-            },
-        ];
+            let (_, ids) = by_dir
+                .entry(spec.augmentations_dir())
+                .or_insert_with(|| (self.augmentations_for(spec), HashSet::new()));
 
-        let project = Project::from_file_list(files).unwrap();
+            ids.extend(
+                OpenApi::operation_ids(&parsed.spec)
+                    .into_iter()
+                    .filter(|id| !id.generated)
+                    .map(|id| id.id),
+            );
+        }
 
-        let opts = RenderOptions::default();
+        by_dir
+            .values()
+            .flat_map(|(augmentations, ids)| {
+                augmentation::unknown_operation_errors(augmentations, ids)
+            })
+            .collect()
+    }
 
-        assert!(project.verify(Some(&opts), None).is_err());
+    pub(crate) fn parsed_open_api_specs(
+        &self,
+    ) -> impl Iterator<Item = (&Path, &openapi_parser::OpenAPI)> {
+        self.settings.open_api().iter().filter_map(|spec| {
+            self.open_api_specs
+                .get(spec.spec_file.to_string_lossy().as_ref())
+                .map(|parsed| (spec.spec_file.as_path(), parsed.spec.as_ref()))
+        })
     }
 
-    #[test]
-    fn verifies_missing_root_navigation() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+    /// Warns about operations listed under a tag with `x-doctave.group`,
+    /// when the spec doesn't otherwise use the tag. This is most likely a typo.
+    fn openapi_group_warnings(&self) -> Vec<Error> {
+        let mut warnings = vec![];
+
+        for spec in self.settings.open_api() {
+            let Some(parsed) = self
+                .open_api_specs
+                .get(spec.spec_file.to_string_lossy().as_ref())
+            else {
+                continue;
+            };
 
-        let project = Project::from_file_list(files).unwrap();
-        let error = &project.verify(None, None).unwrap_err()[0];
+            let tags = parsed.spec.tag_names();
+
+            let operations = parsed
+                .spec
+                .operations()
+                .into_iter()
+                .map(|op| {
+                    (
+                        format!("{} {}", op.method.to_uppercase(), op.route_pattern),
+                        op,
+                    )
+                })
+                .chain(
+                    parsed
+                        .spec
+                        .webhooks
+                        .iter()
+                        .map(|w| (format!(r#"webhook "{}""#, w.name), &w.operation)),
+                );
 
-        assert_eq!(error.message, "Missing navigation.yaml in project root");
-        assert_eq!(error.description, "Could not build navigation structure");
+            for (label, operation) in operations {
+                if let Some(group) = DoctaveExtension::from_parsed(&operation.extensions).group {
+                    if !tags.contains(&group) {
+                        warnings.push(Error {
+                            code: Error::INVALID_OPENAPI_SPEC,
+                            message: format!(r#"Unknown tag "{}" in x-doctave.group"#, group),
+                            description: format!(
+                                r#"The {} operation is grouped under the "{}" tag, which isn't used anywhere else in the spec. Add the tag to the spec's `tags`, or check the spelling of the group."#,
+                                label, group
+                            ),
+                            file: Some(spec.spec_file.clone()),
+                            position: None,
+                            owner: None,
+                            suggestions: vec![],
+                        });
+                    }
+                }
+            }
+        }
+
+        warnings
     }
 
-    #[test]
-    fn verifies_custom_components() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    <Component.Example />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("_components/example.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    <Foo></Bar>
-                    "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+    /// Warns about links from Markdown pages to deprecated operations, for
+    /// specs with `warn_deprecated_links` set.
+    fn deprecated_operation_link_warnings(&self) -> Vec<Error> {
+        if !self
+            .settings
+            .open_api()
+            .iter()
+            .any(|s| s.warn_deprecated_links)
+        {
+            return vec![];
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        let mut warnings = vec![];
 
-        let opts = RenderOptions::default();
+        for page in self.published_pages(None) {
+            if !page.is_markdown() {
+                continue;
+            }
 
-        assert!(
-            project.verify(Some(&opts), None).is_err(),
-            "Component not verified"
-        );
-    }
+            // Pages that fail to render are reported by `verify`
+            let Ok(ast) = page.ast(None) else {
+                continue;
+            };
+            let Some(root) = ast.as_markdown() else {
+                continue;
+            };
 
-    #[test]
-    fn custom_components_topic_alias() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    <Topic.Example />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("_topics/example.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    # This is a nice topic
-                    "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+            let (locale, _) = self.settings.split_locale(page.uri_path());
 
-        let project = Project::from_file_list(files).unwrap();
+            for node in root.walk() {
+                let renderable_ast::NodeKind::Link { url, .. } = &node.kind else {
+                    continue;
+                };
+                let Some((path, fragment)) = url.split_once('#') else {
+                    continue;
+                };
 
-        let opts = RenderOptions::default();
+                let uri = crate::fs_to_uri_path(Path::new(path));
+                let Some(PageKind::OpenApi(target)) =
+                    self.find_localized_page(&uri, locale, true).map(|t| t.page)
+                else {
+                    continue;
+                };
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let ast = page.ast(Some(&opts)).unwrap();
+                let Some(spec) = self
+                    .settings
+                    .open_api_spec(&target.fs_path)
+                    .filter(|s| s.warn_deprecated_links)
+                else {
+                    continue;
+                };
 
-        if let Ast::Markdown(root) = ast {
-            assert_str_eq!(
-                root.debug_string().unwrap(),
-                indoc! { r#"
-                <Heading1>
-                    <Text>
-                        This is a nice topic
-                    </Text>
-                </Heading1>
-                "#}
-                .to_string()
-            );
-        } else {
-            panic!("Expected markdown AST");
+                let Some(operation) = target.operations().iter().find(|op| {
+                    op.deprecated
+                        && (op.anchor_tag == fragment
+                            || op.previous_anchor_tag.as_deref() == Some(fragment))
+                }) else {
+                    continue;
+                };
+
+                let label = format!(
+                    "{} {}",
+                    operation.method.to_uppercase(),
+                    operation.route_pattern
+                );
+                let description = match operation.sunset(spec.sunset_field()) {
+                    Some(sunset) => format!(
+                        "Link {} points to the {} operation, which is deprecated and will be removed on {}.",
+                        url, label, sunset
+                    ),
+                    None => format!(
+                        "Link {} points to the {} operation, which is deprecated.",
+                        url, label
+                    ),
+                };
+
+                warnings.push(Error {
+                    code: Error::DEPRECATED_OPERATION_LINK,
+                    message: String::from("Link to a deprecated operation"),
+                    description,
+                    file: Some(page.fs_path().to_owned()),
+                    position: Some(node.pos.clone()),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
         }
+
+        warnings
     }
 
-    #[test]
-    fn verifies_custom_components_with_topics_alias() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    <Topic.Example />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("_topics/example.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    <Foo></Bar>
-                    "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+    /// Warns about style sheets in the `css` frontmatter that are outside of
+    /// the folder meant for them. Missing ones are errors, from `verify`.
+    fn page_css_warnings(&self) -> Vec<Error> {
+        let mut warnings = vec![];
 
-        let project = Project::from_file_list(files).unwrap();
+        for page in self.published_pages(None) {
+            let PageKind::Markdown(markdown) = page.page else {
+                continue;
+            };
 
-        let opts = RenderOptions::default();
+            for css in markdown.css() {
+                let path = page_css::asset_path(&css);
 
-        assert!(
-            project.verify(Some(&opts), None).is_err(),
-            "Component not verified"
-        );
+                if path.starts_with(page_css::PAGE_CSS_DIR) || self.text_source(&path).is_none() {
+                    continue;
+                }
+
+                warnings.push(Error {
+                    code: Error::PAGE_CSS_LOCATION,
+                    message: format!(r#"Style sheet "{}" is outside of "{}""#, css, page_css::PAGE_CSS_DIR),
+                    description: format!(
+                        "Style sheets listed in a page's `css` frontmatter should be kept under the \"{}\" directory.",
+                        page_css::PAGE_CSS_DIR
+                    ),
+                    file: Some(page.fs_path().to_owned()),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        warnings
     }
 
-    #[test]
-    fn custom_components_with_topics_alias_attributes_work() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <Topic.Example heading="Hello">
-                        from topics!
-                      </Topic.Example>
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("_topics/example.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    attributes:
-                      - title: heading
-                        required: true
-                    ---
+    /// Warns about pages that start with `---` but don't close it, which are
+    /// rendered as content. Usually the closing `---` was forgotten.
+    fn unclosed_frontmatter_warnings(&self) -> Vec<Error> {
+        self.published_pages(None)
+            .into_iter()
+            .filter_map(|page| match page.page {
+                PageKind::Markdown(markdown) if frontmatter::is_unclosed(&markdown.content) => {
+                    Some(Error {
+                        code: Error::INVALID_FRONTMATTER,
+                        message: "Unclosed frontmatter".to_owned(),
+                        description: format!(
+                            "The page starts with `---`, but there's no closing `---` or `...` within the next {} lines, so it's shown as content.\nClose the frontmatter, or start the page with something other than `---` if it's meant as a horizontal rule.",
+                            frontmatter::MAX_FRONTMATTER_LINES
+                        ),
+                        file: Some(markdown.source_path().to_owned()),
+                        position: Some(Position {
+                            start: Point {
+                                row: 1,
+                                col: 1,
+                                byte_offset: 0,
+                            },
+                            end: Point {
+                                row: 1,
+                                col: 4,
+                                byte_offset: 3,
+                            },
+                        }),
+                        owner: None,
+                        suggestions: vec![],
+                    })
+                }
+                _ => None,
+            })
+            .collect()
+    }
 
-                    **{ @heading }**
+    /// Warns about pages that are titled after their file name, and about
+    /// pages with the same title in the same navigation section, which can't
+    /// be told apart in search results or the breadcrumbs.
+    fn title_warnings(&self) -> Vec<Error> {
+        let mut warnings = vec![];
 
-                    <Slot />
-                    "#}
-                    .to_owned(),
+        for page in self.published_pages(None) {
+            // The root README is titled after the project
+            if page.uri_path() == "/" || page.has_explicit_title() {
+                continue;
+            }
+
+            warnings.push(Error {
+                code: Error::MISSING_TITLE,
+                message: String::from("Page has no title"),
+                description: format!(
+                    "The page is titled \"{}\" after its file name. Add a `title` to the frontmatter, or start the page with a heading.",
+                    page.title().unwrap_or_default()
                 ),
-            },
-        ];
+                file: Some(page.fs_path().to_owned()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        // NOTE: The navigation links have any prefixes applied to them, so
+        // we compare against the final version of the links.
+        let mut ctx = RenderContext::new();
+        ctx.with_settings(&self.settings);
+        let pages = self
+            .published_pages(None)
+            .into_iter()
+            .map(|p| {
+                (
+                    navigation::link_identity(&crate::markdown::parser::to_final_link(
+                        p.uri_path(),
+                        &ctx,
+                    )),
+                    p,
+                )
+            })
+            .collect::<HashMap<_, _>>();
 
-        let opts = RenderOptions::default();
+        let mut nav_paths = self
+            .navigations
+            .iter()
+            .flat_map(|navs| navs.keys())
+            .collect::<Vec<_>>();
+        nav_paths.sort();
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let ast = page.ast(Some(&opts)).unwrap();
+        for nav_path in nav_paths {
+            // Broken navigations are reported by `verify`
+            let Ok(navigation) = self.navigation(None, nav_path) else {
+                continue;
+            };
 
-        if let Ast::Markdown(root) = ast {
-            assert_str_eq!(
-                root.debug_string().unwrap(),
-                indoc! { r#"
-                <Paragraph>
-                    <Strong>
-                        <Text>
-                            Hello
-                        </Text>
-                    </Strong>
-                </Paragraph>
-                <Paragraph>
-                    <Text>
-                        from topics!
-                    </Text>
-                </Paragraph>
-                "#}
-                .to_string()
-            );
-        } else {
-            panic!("Expected markdown AST");
+            for section in &navigation.sections {
+                let mut titled: Vec<(String, &PageHandle)> = vec![];
+
+                for href in section.gather_links() {
+                    // Links to anchors point inside a page
+                    if href.contains('#') {
+                        continue;
+                    }
+                    let Some(page) = pages.get(&navigation::link_identity(&href)) else {
+                        continue;
+                    };
+                    let Ok(title) = page.title() else {
+                        continue;
+                    };
+
+                    match titled.iter().find(|(other, _)| *other == title) {
+                        // The same page linked twice
+                        Some((_, other)) if other.uri_path() == page.uri_path() => {}
+                        Some((_, other)) => warnings.push(Error {
+                            code: Error::DUPLICATE_TITLE,
+                            message: format!(r#"Duplicate page title "{}""#, title),
+                            description: format!(
+                                "\"{}\" and \"{}\" are both titled \"{}\" in the {} navigation section. Give one of them a different title, so that they can be told apart.",
+                                other.fs_path().display(),
+                                page.fs_path().display(),
+                                title,
+                                section
+                                    .heading
+                                    .as_deref()
+                                    .map(|h| format!(r#""{}""#, h))
+                                    .unwrap_or_else(|| "same".to_string()),
+                            ),
+                            file: Some(page.fs_path().to_owned()),
+                            position: None,
+                            owner: None,
+                            suggestions: vec![],
+                        }),
+                        None => titled.push((title, page)),
+                    }
+                }
+            }
         }
+
+        warnings
     }
 
-    #[test]
-    fn verifies_the_existence_of_stylesheets_mentioned_in_settings() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("_assets/cat.jpg"),
-                content: InputContent::Text("".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("_assets/stylez.css"),
-                content: InputContent::Text("".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                styles:
-                  - _assets/style.css
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+    /// Tabs and subtabs other than the root can do without a navigation, but
+    /// their pages are then shown without one.
+    fn missing_tab_navigation_warnings(&self) -> Vec<Error> {
+        let (Some(tabs), Some(navs)) = (&self.tabs, &self.navigations) else {
+            return vec![];
+        };
 
-        let project = Project::from_file_list(files).unwrap();
-        let error = &project.verify(None, None).unwrap_err()[0];
+        tabs.tabs
+            .iter()
+            .map(|tab| (tab, "tab"))
+            .chain(
+                tabs.tabs
+                    .iter()
+                    .flat_map(|tab| tab.subtabs.iter().map(|subtab| (subtab, "subtab"))),
+            )
+            .filter(|(tab, _)| {
+                !tab.is_external
+                    && tab.href != "/"
+                    && matches!(navs.get(&tab.href), Some(None))
+                    // Tabs without any content are reported by `verify_tabs`
+                    && self.tab_has_content(&tab.href)
+            })
+            .map(|(tab, id)| {
+                let expected = PathBuf::from(tab.href.trim_start_matches('/'))
+                    .join(NAVIGATION_FILE_NAME);
 
-        assert_eq!(
-            error.message,
-            "Could not find style sheet file at \"_assets/style.css\"."
-        );
-        assert_eq!(
-            error.description,
-            "Found [\"_assets/stylez.css\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
-        );
+                Error {
+                    code: Error::MISSING_NAVIGATION,
+                    message: format!(r#"Missing navigation.yaml for {} "{}""#, id, tab.label),
+                    description: format!(
+                        "Expected a navigation file at \"{}\". Pages under \"{}\" are shown without a navigation.",
+                        expected.display(),
+                        tab.href
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: tab.position.clone(),
+                    owner: None,
+                    suggestions: vec![],
+                }
+            })
+            .collect()
     }
 
-    #[test]
-    fn verifies_the_existence_of_logo_mentioned_in_settings_v2() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("_assets/cat.jpg"),
-                content: InputContent::Text("".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                theme:
-                  logo:
-                    src: _assets/logo.png
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+    /// The theme can only show so many levels of nested items.
+    fn deep_navigation_warnings(&self) -> Vec<Error> {
+        let mut nav_paths = self
+            .navigations
+            .iter()
+            .flat_map(|navs| navs.keys())
+            .collect::<Vec<_>>();
+        nav_paths.sort();
 
-        let project = Project::from_file_list(files).unwrap();
-        let error = &project.verify(None, None).unwrap_err()[0];
+        let mut warnings = vec![];
 
-        assert_eq!(
-            error.message,
-            "Could not find logo at \"_assets/logo.png\"."
-        );
-        assert_eq!(
-            error.description,
-            "Found following images: [\"_assets/cat.jpg\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
-        );
-    }
+        for nav_path in nav_paths {
+            // Broken navigations are reported by `verify`
+            let Ok(navigation) = self.navigation(None, nav_path) else {
+                continue;
+            };
 
-    #[test]
-    fn verifies_the_existence_of_dark_mode_logo_mentioned_in_settings_v2() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("_assets/cat.jpg"),
-                content: InputContent::Text("".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("_assets/logo.png"),
-                content: InputContent::Text("".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                theme:
-                  logo:
-                    src: _assets/logo.png
-                    src_dark: _assets/dark-logo.png
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+            for item in navigation.items_too_deep() {
+                warnings.push(Error {
+                    code: Error::NAVIGATION_ERROR,
+                    message: format!(r#"Navigation item "{}" is nested too deeply"#, item.label()),
+                    description: format!(
+                        "\"{}\" is nested {} levels deep, but the navigation can show at most {} levels. Move it, or the items it's nested in, closer to the top.",
+                        item.label(),
+                        item.depth(),
+                        navigation::MAX_DEPTH
+                    ),
+                    file: Some(
+                        PathBuf::from(nav_path.trim_start_matches('/')).join(NAVIGATION_FILE_NAME),
+                    ),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let error = &project.verify(None, None).unwrap_err()[0];
+        warnings
+    }
 
-        assert_eq!(
-            error.message,
-            "Could not find dark mode logo at \"_assets/dark-logo.png\"."
-        );
-        assert_eq!(
-            error.description,
-            "Found following images: [\"_assets/cat.jpg\", \"_assets/logo.png\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
-        );
+    /// Verifies that the structure of the navigation is correct.
+    /// Note this does not check for broken links.
+    fn verify_navigation(&self, opts: Option<&RenderOptions>) -> Vec<Error> {
+        let mut errors = vec![];
+
+        if self.navigations.is_none() {
+            let message = if self.tabs.is_none() {
+                "Missing navigation.yaml in project root".to_owned()
+            } else {
+                "Missing a navigation.yaml".to_owned()
+            };
+
+            errors.push(Error {
+                code: Error::MISSING_NAVIGATION,
+                message,
+                description: "Could not build navigation structure".to_owned(),
+                file: None,
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        if let Some(navs) = &self.navigations {
+            for (subtab_path, nav_handle) in navs {
+                let mut errors_for_nav = vec![];
+                if let Some(nav_handle) = nav_handle {
+                    let nav_file_path = PathBuf::from(subtab_path).join(NAVIGATION_FILE_NAME);
+                    let mut nav_errors = navigation::verify(
+                        &nav_handle.0,
+                        self,
+                        opts.map(|o| o.undefined_variables).unwrap_or_default(),
+                    );
+                    errors_for_nav.append(&mut nav_errors);
+
+                    // This is kind of ugly, but right now a bit hesitant to pass context
+                    // into navigation verification just for error reporting as it runs
+                    // quite deep into it. Therefore, we just mutate the file path after
+                    // verification has ran.
+                    errors_for_nav
+                        .iter_mut()
+                        .for_each(|error| error.file = Some(nav_file_path.to_owned()));
+
+                    errors.append(&mut errors_for_nav);
+                } else if subtab_path == "/" {
+                    errors.push(Error {
+                        code: Error::MISSING_NAVIGATION,
+                        message: "Missing navigation.yaml in project root".to_owned(),
+                        description: format!("Could not find navigation.yaml in `{}`", subtab_path),
+                        file: None,
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+                // Missing navigations for other tabs are reported as warnings
+            }
+        }
+
+        errors
     }
 
-    #[test]
-    fn verifies_the_existence_of_favicon_mentioned_in_settings_v2() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("_assets/favicon.ico"),
-                content: InputContent::Text("".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                theme:
-                  favicon:
-                    src: _assets/favicon.png
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+    /// Replacements for the API keys and tokens in pages and OpenAPI specs,
+    /// for `docapella check --redact`. Uses the patterns and allowlist in
+    /// `secret_scanning`, if set.
+    pub fn secret_redactions(&self) -> Vec<crate::Suggestion> {
+        crate::secrets::redactions(self)
+    }
 
-        let project = Project::from_file_list(files).unwrap();
-        let error = &project.verify(None, None).unwrap_err()[0];
+    /// Links to other sites from published pages, OpenAPI descriptions and
+    /// navigations, each listed once with everywhere it's used.
+    pub fn external_links(&self) -> Vec<crate::external_links::ExternalLink> {
+        crate::external_links::compute(self)
+    }
 
-        assert_eq!(
-            error.message,
-            "Could not find favicon at \"_assets/favicon.png\"."
-        );
-        assert_eq!(
-            error.description,
-            "Found following possible favicons: [\"_assets/favicon.ico\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
-        );
+    pub fn search_index(&self) -> crate::Result<SearchIndex> {
+        SearchIndex::new(self)
     }
 
-    #[test]
-    fn verifies_the_existence_of_openapi_spec() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                open_api:
-                  - spec_file: openapi.json
-                    uri_prefix: /api
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+    /// Builds a search index for a single locale. Pages without a translation
+    /// are indexed in the default locale, as that's what readers will see.
+    pub fn search_index_for_locale(&self, locale: &str) -> crate::Result<SearchIndex> {
+        SearchIndex::from_pages(self, self.pages_for_locale(locale))
+    }
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let error: &Error = &project.verify(None, None).unwrap_err()[0];
+    /// Renders a Markdown fragment, e.g. an OpenAPI description, as if it
+    /// were content in the file at `base_path`.
+    ///
+    /// Custom components, assets, and settings come from the project, and
+    /// relative links and images are resolved from `base_path`, since the
+    /// fragment usually ends up on some other page than its own file.
+    pub fn render_markdown_fragment(
+        &self,
+        input: &str,
+        base_path: &Path,
+        opts: Option<&RenderOptions>,
+    ) -> crate::Result<renderable_ast::Node> {
+        let mut ctx = RenderContext::new();
+        ctx.with_maybe_options(opts);
+        ctx.with_project(self);
+        ctx.with_url_base_by_fs_path(base_path);
+        ctx.with_file_context(FileContext::new(0, 0, base_path.to_path_buf()));
+        ctx.expand_image_urls = true;
 
-        assert_eq!(error.message, "Could not find OpenAPI spec.");
-        assert_eq!(
-            error.description,
-            "OpenAPI spec at \"openapi.json\" not found. Is it in the correct location?"
-        );
-        assert_eq!(error.file, Some(PathBuf::from("docapella.yaml")));
+        crate::markdown::ast_mdx(input, &ctx).map_err(|mut e| {
+            e.in_file(base_path);
+            e
+        })
     }
 
-    #[test]
-    fn verifies_uri_prefix() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        }
-                      }
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                open_api:
-                  - spec_file: openapi.json
-                    uri_prefix:
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+    /// Renders all the Markdown pages under the given URI prefix into one
+    /// document, ordered by the navigation. Used for printing and PDF exports.
+    pub fn render_section(
+        &self,
+        uri_prefix: &str,
+        opts: Option<&RenderOptions>,
+    ) -> Result<SinglePage, Vec<Error>> {
+        crate::single_page::render(self, uri_prefix, opts)
+    }
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let error: &Error = &project.verify(None, None).unwrap_err()[0];
+    #[cfg(feature = "boilerplate")]
+    pub fn boilerplate_file_list() -> Vec<(PathBuf, Vec<u8>)> {
+        Self::boilerplate_file_list_for(Boilerplate::default())
+    }
 
-        assert_eq!(error.message, "OpenAPI URI prefix should contain a path.");
-        assert_eq!(
-            error.description,
-            "Define a uri_prefix for the OpenAPI spec \"openapi.json\" in docapella.yaml. For example, uri_prefix: /api."
+    #[cfg(feature = "boilerplate")]
+    pub fn boilerplate_file_list_for(boilerplate: Boilerplate) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files = vec![];
+
+        fn gather_recursively(entries: &[DirEntry], files: &mut Vec<(PathBuf, Vec<u8>)>) {
+            for entry in entries {
+                match entry {
+                    DirEntry::Dir(d) => gather_recursively(d.entries(), files),
+                    DirEntry::File(file) => {
+                        // Make sure we don't dump .DS_Store files
+                        if !file.path().ends_with(".DS_Store") {
+                            files.push((file.path().to_owned(), file.contents().to_owned()));
+                        }
+                    }
+                }
+            }
+        }
+
+        gather_recursively(boilerplate.dir().entries(), &mut files);
+        files.extend(
+            boilerplate
+                .shared_files()
+                .into_iter()
+                .map(|(path, content)| (path, content.to_owned())),
         );
-        assert_eq!(error.file, Some(PathBuf::from("docapella.yaml")));
+
+        files
     }
 
-    #[test]
-    fn parses_openapi_schemas() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
+    pub fn root_navigation(
+        &self,
+        opts: Option<&RenderOptions>,
+    ) -> crate::Result<navigation::Navigation> {
+        self.navigation(opts, "/")
+    }
+
+    /// A part of the AST of the page at the URI path, for pages too large to
+    /// fetch in one go. Returns `None` if there's no such page or chunk.
+    pub fn page_ast_chunk(
+        &self,
+        uri_path: &str,
+        chunk_index: usize,
+        opts: Option<&RenderOptions>,
+    ) -> crate::Result<Option<crate::ast_chunks::AstChunk>> {
+        crate::ast_chunks::compute(self, uri_path, chunk_index, opts)
+    }
+
+    /// The tags of an OpenAPI spec and the operations listed under them, in
+    /// the same order as the generated pages. Used to render an API reference
+    /// sidebar.
+    pub fn openapi_outline(
+        &self,
+        spec_file: &Path,
+        opts: Option<&RenderOptions>,
+    ) -> Vec<crate::openapi_outline::TagGroup> {
+        crate::openapi_outline::compute(spec_file, self, opts)
+    }
+
+    /// Every page, heading, and OpenAPI operation, in navigation order, for
+    /// jumping around the site from a command palette. Smaller than the
+    /// search index, as it only has titles and links.
+    pub fn quick_nav_manifest(
+        &self,
+        opts: Option<&RenderOptions>,
+    ) -> Vec<crate::quick_nav::QuickNavEntry> {
+        crate::quick_nav::compute(self, opts)
+    }
+
+    /// The feed configured for the folder at the URI path, e.g.
+    /// `/changelog`, with its latest pages. Returns `None` if no feed is
+    /// configured for the folder.
+    pub fn feed(&self, path: &str, opts: Option<&RenderOptions>) -> Option<crate::feed::Feed> {
+        crate::feed::compute(self, path, opts)
+    }
+
+    /// All feeds configured in the settings.
+    pub fn feeds(&self, opts: Option<&RenderOptions>) -> Vec<crate::feed::Feed> {
+        self.settings
+            .feeds()
+            .iter()
+            .filter_map(|feed| self.feed(&feed.uri_path(), opts))
+            .collect()
+    }
+
+    pub fn navigation(
+        &self,
+        opts: Option<&RenderOptions>,
+        subtab_path: &str,
+    ) -> crate::Result<navigation::Navigation> {
+        let mut ctx = RenderContext::new();
+        ctx.with_project(self);
+        ctx.with_maybe_options(opts);
+
+        let mut path = subtab_path.to_string();
+
+        if let Some(_structure) = &self.tabs {
+            path = format!(
+                "/{}",
+                subtab_path.trim_start_matches('/').trim_end_matches('/')
+            )
+        }
+
+        match path.as_str() {
+            "/" => {}
+            path => {
+                ctx.with_url_base(path);
+            }
+        };
+
+        match &self.navigations {
+            Some(navs) => match navs.get(&path) {
+                Some(Some(nav_handle)) => {
+                    let mut navigation = navigation::build(&nav_handle.0, &ctx, self)?;
+                    crate::whats_new::add_to_navigation(&mut navigation, &path, self, &ctx);
+
+                    Ok(navigation)
+                }
+                None | Some(None) => Err(Error {
+                    code: Error::MISSING_NAVIGATION,
+                    message: "Missing navigation.yaml for tab".to_owned(),
+                    description: format!("Could not find navigation.yaml in `{}`", subtab_path),
+                    file: None,
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                }),
+            },
+            None => Err(Error {
+                code: Error::MISSING_NAVIGATION,
+                message: "Missing navigation.yaml in project root".to_owned(),
+                description: "Could not build navigation structure".to_owned(),
+                file: None,
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            }),
+        }
+    }
+
+    /// The navigation of the tab the page is in, with the sections and items
+    /// leading to the page marked as `expanded_for_current_page`.
+    pub fn navigation_for_page(
+        &self,
+        uri_path: &str,
+        opts: Option<&RenderOptions>,
+    ) -> crate::Result<navigation::Navigation> {
+        let subtab_path = self
+            .get_subtab_path_by_uri_path(uri_path)
+            .unwrap_or("/".to_string());
+
+        let mut navigation = self.navigation(opts, &subtab_path)?;
+
+        // The links in the navigation have any prefixes applied to them
+        let mut ctx = RenderContext::new();
+        ctx.with_settings(&self.settings);
+        ctx.with_maybe_options(opts);
+        navigation.expand_for_current_page(&crate::markdown::parser::to_final_link(uri_path, &ctx));
+
+        Ok(navigation)
+    }
+
+    pub fn navigation_has_link_to(&self, path: &str, opts: Option<&RenderOptions>) -> bool {
+        self.navigations
+            .as_ref()
+            .map(|navs| {
+                navs.keys().any(|subtab_path| {
+                    if let Ok(nav) = self.navigation(opts, subtab_path) {
+                        nav.has_link_to(path)
+                    } else {
+                        false
+                    }
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn tabs(&self) -> Option<&TabsList> {
+        self.tabs.as_ref()
+    }
+
+    pub fn autocomplete(
+        &self,
+        markdown: &str,
+        fs_path: &Path,
+        render_opts: Option<&RenderOptions>,
+    ) -> Vec<crate::CompletionItem> {
+        let mut ctx = RenderContext::new();
+        ctx.with_maybe_options(render_opts);
+        ctx.with_project(self);
+        ctx.with_disabled_components(
+            frontmatter::parse(markdown)
+                .map(|f| f.components_disabled)
+                .unwrap_or_default(),
+        );
+
+        crate::markdown::autocomplete(markdown, fs_path, self, &ctx)
+    }
+
+    /// Finds a published page by its URI path. Drafts and pages matched by
+    /// `exclude` in the settings are not returned.
+    pub fn get_page_by_uri_path(&self, uri_path: &str) -> Option<PageHandle<'_>> {
+        self.find_page_by_uri_path(uri_path, false)
+    }
+
+    /// Finds a page by its URI path, optionally including unpublished pages.
+    pub(crate) fn find_page_by_uri_path(
+        &self,
+        uri_path: &str,
+        include_drafts: bool,
+    ) -> Option<PageHandle<'_>> {
+        // If we get an anchor in the URI, remove it.
+        let without_anchor = crate::strip_url_style(uri_path.split('#').collect::<Vec<_>>()[0]);
+
+        for page in &self.pages {
+            if page.uri_path() == without_anchor {
+                let handle = PageHandle {
+                    page,
+                    project: self,
+                };
+
+                if include_drafts || handle.draft_status().is_none() {
+                    return Some(handle);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds a page by its URI path, preferring the translation for a locale.
+    ///
+    /// The locale is taken from `RenderOptions.locale`, or from the URI path
+    /// itself (e.g. `/fr/foo`). Translations live in a folder named after the
+    /// locale, so `fr/foo.md` is the French variant of `foo.md`. When a page
+    /// hasn't been translated, the page in the default locale is returned.
+    ///
+    /// Drafts are only returned if `RenderOptions.publish_drafts` is set.
+    pub fn get_localized_page_by_uri_path(
+        &self,
+        uri_path: &str,
+        opts: Option<&RenderOptions>,
+    ) -> Option<PageHandle<'_>> {
+        let locale = opts.and_then(|o| o.locale.as_deref());
+
+        self.find_localized_page(uri_path, locale, publishes_drafts(opts))
+    }
+
+    pub(crate) fn find_localized_page(
+        &self,
+        uri_path: &str,
+        locale: Option<&str>,
+        include_drafts: bool,
+    ) -> Option<PageHandle<'_>> {
+        let (uri_locale, base_path) = self.settings.split_locale(uri_path);
+
+        if let Some(locale) = locale
+            .or(uri_locale)
+            .filter(|l| Some(*l) != self.settings.default_locale())
+        {
+            if let Some(page) =
+                self.find_page_by_uri_path(&localized_uri_path(locale, base_path), include_drafts)
+            {
+                return Some(page);
+            }
+        }
+
+        self.find_page_by_uri_path(base_path, include_drafts)
+    }
+
+    /// The pages readers of the given locale see: translated pages, plus pages
+    /// in the default locale that don't have a translation.
+    pub(crate) fn pages_for_locale(&self, locale: &str) -> Vec<PageHandle<'_>> {
+        let is_default = Some(locale) == self.settings.default_locale();
+
+        self.published_pages(None)
+            .into_iter()
+            .filter(|p| match self.settings.split_locale(p.uri_path()) {
+                (Some(page_locale), _) => !is_default && page_locale == locale,
+                (None, base_path) => {
+                    is_default
+                        || self
+                            .get_page_by_uri_path(&localized_uri_path(locale, base_path))
+                            .is_none()
+                }
+            })
+            .collect()
+    }
+
+    pub fn get_page_by_fs_path(&self, path: &Path) -> Option<PageHandle<'_>> {
+        for page in &self.pages {
+            if let PageKind::Markdown(md) = page {
+                if Self::normalize_fs_path(md.path.as_ref()) == Self::normalize_fs_path(path) {
+                    return Some(PageHandle {
+                        page,
+                        project: self,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    pub fn get_asset_by_fs_path(&self, path: &Path) -> Option<&Asset> {
+        self.assets
+            .iter()
+            .find(|a| Self::normalize_fs_path(&a.path) == Self::normalize_fs_path(path))
+    }
+
+    /// Assets shown as images on published pages, e.g. with
+    /// `![Screenshot](/_assets/screenshot.png)`.
+    pub fn image_assets(&self, opts: Option<&RenderOptions>) -> Vec<&Asset> {
+        let opts = RenderOptions {
+            publish_drafts: publishes_drafts(opts),
+            ..Default::default()
+        };
+
+        let paths = self
+            .published_pages(Some(&opts))
+            .into_par_iter()
+            .filter_map(|page| match page.ast(Some(&opts)) {
+                Ok(Ast::Markdown(root)) => {
+                    let mut ctx = RenderContext::new();
+                    ctx.with_url_base_by_fs_path(page.fs_path());
+
+                    Some(
+                        root.walk()
+                            .filter_map(|node| match &node.kind {
+                                renderable_ast::NodeKind::Image { url, .. } => {
+                                    asset_path(url, &ctx)
+                                }
+                                _ => None,
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                }
+                _ => None,
+            })
+            .flatten()
+            .collect::<HashSet<_>>();
+
+        self.assets
+            .iter()
+            .filter(|a| paths.contains(&a.path))
+            .collect()
+    }
+
+    pub fn get_subtab_path_by_uri_path(&self, uri_path: &str) -> Option<String> {
+        self.tabs.as_ref().map(|tabs| {
+            tabs.scope_for(uri_path)
+                .map(|scope| scope.path)
+                .unwrap_or_else(|| String::from("/"))
+        })
+    }
+
+    /// The tab and subtab the page at the given URI path belongs to, if the
+    /// project has tabs.
+    pub fn tab_scope(&self, uri_path: &str) -> Option<TabScope> {
+        self.tabs.as_ref().and_then(|tabs| tabs.scope_for(uri_path))
+    }
+
+    fn normalize_fs_path(path: &Path) -> PathBuf {
+        let path = canonicalize(path);
+
+        path.strip_prefix("/").unwrap_or(&path).to_owned()
+    }
+
+    pub fn pages(&self) -> Vec<PageHandle<'_>> {
+        self.pages
+            .iter()
+            .map(|page| PageHandle {
+                page,
+                project: self,
+            })
+            .collect::<Vec<_>>()
+    }
+
+    /// The pages that are part of the published site. Drafts and pages
+    /// matched by `exclude` in the settings are left out, unless
+    /// `RenderOptions.publish_drafts` is set.
+    pub fn published_pages(&self, opts: Option<&RenderOptions>) -> Vec<PageHandle<'_>> {
+        let include_drafts = publishes_drafts(opts);
+
+        self.pages()
+            .into_iter()
+            .filter(|p| include_drafts || p.draft_status().is_none())
+            .collect()
+    }
+
+    /// Redirects from the settings, other than those from the URI path of a
+    /// page, which are never used.
+    fn settings_redirects(&self) -> Vec<(String, String)> {
+        self.settings
+            .redirects()
+            .iter()
+            .filter(|r| r.is_wildcard() || !self.pages.iter().any(|p| p.uri_path() == r.source()))
+            .map(|r| r.as_tuple())
+            .collect()
+    }
+
+    /// Where a request for `uri_path` is redirected to, if anywhere. Pages
+    /// take precedence over redirects, so there's no redirect for the URI
+    /// path of a page, even one under a wildcard redirect.
+    pub fn resolve_redirect(&self, uri_path: &str) -> Option<String> {
+        let uri_path = crate::strip_url_style(uri_path.split('#').next().unwrap_or_default());
+
+        if self.pages.iter().any(|p| p.uri_path() == uri_path) {
+            return None;
+        }
+
+        self.settings
+            .redirects()
+            .iter()
+            .find_map(|r| r.destination_for(uri_path))
+    }
+
+    pub fn redirects(&self) -> Vec<(String, String)> {
+        let mut redirects = vec![];
+
+        redirects.append(&mut self.settings_redirects());
+
+        redirects
+    }
+
+    /// Where every operation in the OpenAPI specs is shown. With a
+    /// `strategy`, the URIs are the ones the operations would have if every
+    /// spec used it, e.g. to see which links change before switching to
+    /// `uri_strategy: operation_id`.
+    pub fn operation_uris(&self, strategy: Option<UriStrategy>) -> Vec<OperationUri> {
+        let mut uris = vec![];
+
+        for spec in self.settings.open_api() {
+            let Some(parsed) = self
+                .open_api_specs
+                .get(spec.spec_file.to_string_lossy().as_ref())
+            else {
+                continue;
+            };
+
+            let Ok(pages) = OpenApi::pages_from_parsed_spec(
+                &parsed.spec,
+                spec.spec_file.clone(),
+                spec.uri_prefix.clone(),
+                None,
+                self.augmentations_for(spec),
+                strategy.unwrap_or(spec.uri_strategy),
+                &spec.default_responses,
+            ) else {
+                continue;
+            };
+
+            for page in &pages {
+                let PageKind::OpenApi(page) = page else {
+                    continue;
+                };
+
+                uris.extend(page.operations().iter().map(|op| {
+                    OperationUri {
+                        location: match op.method.as_str() {
+                            "webhook" => format!(
+                                "webhook {}",
+                                op.summary.as_deref().unwrap_or(&op.anchor_tag)
+                            ),
+                            method => format!("{} {}", method.to_uppercase(), op.route_pattern),
+                        },
+                        uri: format!("{}#{}", page.uri_path, op.anchor_tag),
+                        previous_uri: op
+                            .previous_anchor_tag
+                            .as_ref()
+                            .map(|anchor| format!("{}#{}", page.uri_path, anchor)),
+                    }
+                }));
+            }
+        }
+
+        uris
+    }
+
+    /// Suggests a fix for a broken link in a Markdown page, if it's clear
+    /// which page it was meant to point to.
+    fn link_suggestions(&self, p: &PageHandle, link: &OutgoingLink, uri: &str) -> Vec<Suggestion> {
+        let (Some(position), Some(page)) = (&link.position, self.closest_page(uri)) else {
+            return vec![];
+        };
+
+        // Keep writing the link the same way, as a file or a URI path
+        let written = link.expanded_uri.as_deref().unwrap_or(&link.uri);
+        let target = if written.ends_with(".md") {
+            format!("/{}", page.fs_path().to_string_lossy())
+        } else {
+            page.uri_path().to_owned()
+        };
+
+        // Relative links to a page in the same directory stay relative
+        let replacement = match (written.rsplit_once('/'), target.rsplit_once('/')) {
+            (Some((dir, _)), Some((target_dir, name)))
+                if dir == target_dir && !link.uri.starts_with('/') =>
+            {
+                match link.uri.rsplit_once('/') {
+                    Some((link_dir, _)) => format!("{}/{}", link_dir, name),
+                    None => name.to_owned(),
+                }
+            }
+            _ => target,
+        };
+
+        vec![Suggestion {
+            file: p.fs_path().to_owned(),
+            start: position.start.byte_offset,
+            end: position.end.byte_offset,
+            replacement,
+        }]
+    }
+
+    /// Suggests a fix for a broken `href` in a navigation file. Hrefs are
+    /// suggested as paths to Markdown files, so a missing leading slash or
+    /// `.md` extension gets added.
+    fn nav_link_suggestion(
+        &self,
+        nav_file: &Path,
+        position: &Position,
+        href: &str,
+    ) -> Option<Suggestion> {
+        let (path, fragment) = match href.split_once('#') {
+            Some((path, fragment)) => (path, format!("#{}", fragment)),
+            None => (href, String::new()),
+        };
+
+        let mut fs_path = PathBuf::from(path.trim_start_matches('/'));
+        if fs_path.extension().is_none() {
+            fs_path.set_extension("md");
+        }
+
+        let page = self
+            .get_page_by_fs_path(&fs_path)
+            .or_else(|| self.closest_page(&crate::fs_to_uri_path(Path::new(path))))?;
+
+        Some(Suggestion {
+            file: nav_file.to_owned(),
+            start: position.start.byte_offset,
+            end: position.end.byte_offset,
+            replacement: format!("/{}{}", page.fs_path().to_string_lossy(), fragment),
+        })
+    }
+
+    /// Finds the page a broken link was most likely meant for: one that was
+    /// moved to another directory under the same name, or one a typo away.
+    fn closest_page(&self, uri_path: &str) -> Option<PageHandle<'_>> {
+        let pages = self.pages();
+        let name = uri_path.rsplit('/').next().unwrap_or_default();
+
+        let mut moved = pages
+            .iter()
+            .filter(|p| !name.is_empty() && p.uri_path().rsplit('/').next() == Some(name));
+        if let (Some(page), None) = (moved.next(), moved.next()) {
+            return Some(page.clone());
+        }
+
+        let closest =
+            crate::utils::closest_match(uri_path, pages.iter().map(|p| p.uri_path()))?.to_owned();
+
+        pages.into_iter().find(|p| p.uri_path() == closest)
+    }
+
+    /// Whether a redirect matches the URI path, whichever URL style either
+    /// of them is written in.
+    pub(crate) fn has_redirect_from(&self, uri_path: &str) -> bool {
+        let uri_path = crate::strip_url_style(uri_path);
+
+        self.redirects()
+            .iter()
+            .any(|r| crate::strip_url_style(&r.0) == uri_path)
+    }
+}
+
+/// Where a link is written in a navigation file, e.g. for the `href` key.
+pub(crate) fn nav_link_position(source: &str, key: &str, link: &str) -> Option<Position> {
+    let key = format!("{}:", key);
+    let start = source.match_indices(link).map(|(i, _)| i).find(|i| {
+        let line = &source[source[..*i].rfind('\n').map(|n| n + 1).unwrap_or(0)..*i];
+
+        line.trim_end_matches(['"', '\''])
+            .trim_end()
+            .ends_with(&key)
+    })?;
+
+    Some(Position::from_byte_range(source, start, start + link.len()))
+}
+
+/// Whether drafts should be treated like published pages.
+pub(crate) fn publishes_drafts(opts: Option<&RenderOptions>) -> bool {
+    opts.map(|o| o.publish_drafts).unwrap_or(false)
+}
+
+/// The URI path of a page within a locale's folder.
+pub(crate) fn localized_uri_path(locale: &str, uri_path: &str) -> String {
+    match uri_path.trim_start_matches('/') {
+        "" => format!("/{}", locale),
+        rest => format!("/{}/{}", locale, rest),
+    }
+}
+
+impl std::fmt::Debug for Project {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        fmt.debug_struct("Project")
+            .field("navigation", &self.root_navigation(None))
+            .field("pages", &self.pages)
+            .field("settings", &self.settings)
+            .finish()?;
+
+        Ok(())
+    }
+}
+
+impl Default for Project {
+    fn default() -> Self {
+        let file_list = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(String::from("")),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project")),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(String::from(indoc! {r#"
+            - heading: "Guides"
+              items:
+                - href: foo/bar.md
+                  label: Example
+            "#})),
+            },
+        ];
+
+        Self::from_file_list(file_list).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Ast;
+    use pretty_assertions::assert_str_eq;
+
+    use super::*;
+
+    #[test]
+    fn verifies_each_page() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("{% badliquid, %}".to_string()),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let opts = RenderOptions::default();
+
+        assert!(project.verify(Some(&opts), None).is_err());
+    }
+
+    #[test]
+    fn verifies_a_single_page() {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_owned()),
+        };
+
+        let project = Project::from_file_list(vec![
+            file(NAVIGATION_FILE_NAME, "---"),
+            file(SETTINGS_FILE_NAME, "---\ntitle: An Project\n"),
+            file("README.md", "# Home\n\n[Guide](/guide.md)"),
+            file("guide.md", "# Guide\n\n[Missing](/missing.md)"),
+        ])
+        .unwrap();
+
+        assert!(project.verify_page("/", None).is_empty());
+
+        let errors = project.verify_page("/guide", None);
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::BROKEN_INTERNAL_LINK);
+        assert_eq!(errors[0].file, Some(PathBuf::from("guide.md")));
+
+        assert!(project.verify_page("/nope", None).is_empty());
+    }
+
+    #[test]
+    fn verifies_missing_root_navigation() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+        let error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(error.message, "Missing navigation.yaml in project root");
+        assert_eq!(error.description, "Could not build navigation structure");
+    }
+
+    #[test]
+    fn verifies_custom_components() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    <Component.Example />
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("_components/example.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    <Foo></Bar>
+                    "#}
+                    .to_owned(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let opts = RenderOptions::default();
+
+        assert!(
+            project.verify(Some(&opts), None).is_err(),
+            "Component not verified"
+        );
+    }
+
+    #[test]
+    fn disables_components_listed_in_the_frontmatter() {
+        let project = crate::test_support::project(&[
+            (
+                "README.md",
+                indoc! {r#"
+                ---
+                components_disabled: [Component.Fancy]
+                ---
+
+                <Component.Fancy />
+                "#},
+            ),
+            ("other.md", "<Component.Fancy />"),
+            ("_components/fancy.md", "Fancy"),
+        ]);
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].message, "Unknown component Component.Fancy");
+        assert_eq!(errors[0].file, Some(PathBuf::from("README.md")));
+        assert!(errors[0]
+            .description
+            .contains("Disabled by `components_disabled` in the frontmatter"));
+    }
+
+    #[test]
+    fn disables_custom_components_listed_without_their_prefix() {
+        let project = crate::test_support::project(&[
+            (
+                "README.md",
+                indoc! {r#"
+                ---
+                components_disabled: [Fancy]
+                ---
+
+                <Component.Fancy />
+                "#},
+            ),
+            (
+                "other.md",
+                indoc! {r#"
+                ---
+                components_disabled: [Card]
+                ---
+
+                <Card>Text</Card>
+                "#},
+            ),
+            ("_components/fancy.md", "Fancy"),
+        ]);
+
+        let errors = project.verify(None, None).unwrap_err();
+        let mut messages = errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>();
+        messages.sort();
+
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown component Card",
+                "Unknown component Component.Fancy"
+            ],
+            "{:#?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn custom_components_topic_alias() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    title: An Project
+                    "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    <Topic.Example />
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("_topics/example.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    # This is a nice topic
+                    "#}
+                    .to_owned(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let opts = RenderOptions::default();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = page.ast(Some(&opts)).unwrap();
+
+        if let Ast::Markdown(root) = ast {
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <Heading1>
+                    <Text>
+                        This is a nice topic
+                    </Text>
+                </Heading1>
+                "#}
+                .to_string()
+            );
+        } else {
+            panic!("Expected markdown AST");
+        }
+    }
+
+    #[test]
+    fn verifies_custom_components_with_topics_alias() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    <Topic.Example />
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("_topics/example.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    <Foo></Bar>
+                    "#}
+                    .to_owned(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let opts = RenderOptions::default();
+
+        assert!(
+            project.verify(Some(&opts), None).is_err(),
+            "Component not verified"
+        );
+    }
+
+    #[test]
+    fn custom_components_with_topics_alias_attributes_work() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    title: An Project
+                    "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                      <Topic.Example heading="Hello">
+                        from topics!
+                      </Topic.Example>
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("_topics/example.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    ---
+                    attributes:
+                      - title: heading
+                        required: true
+                    ---
+
+                    **{ @heading }**
+
+                    <Slot />
+                    "#}
+                    .to_owned(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let opts = RenderOptions::default();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = page.ast(Some(&opts)).unwrap();
+
+        if let Ast::Markdown(root) = ast {
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <Paragraph>
+                    <Strong>
+                        <Text>
+                            Hello
+                        </Text>
+                    </Strong>
+                </Paragraph>
+                <Paragraph>
+                    <Text>
+                        from topics!
+                    </Text>
+                </Paragraph>
+                "#}
+                .to_string()
+            );
+        } else {
+            panic!("Expected markdown AST");
+        }
+    }
+
+    #[test]
+    fn verifies_the_existence_of_stylesheets_mentioned_in_settings() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("_assets/cat.jpg"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("_assets/stylez.css"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                styles:
+                  - _assets/style.css
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+        let error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(
+            error.message,
+            "Could not find style sheet file at \"_assets/style.css\"."
+        );
+        assert_eq!(
+            error.description,
+            "Found [\"_assets/stylez.css\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
+        );
+    }
+
+    #[test]
+    fn verifies_the_existence_of_logo_mentioned_in_settings_v2() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("_assets/cat.jpg"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                theme:
+                  logo:
+                    src: _assets/logo.png
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+        let error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(
+            error.message,
+            "Could not find logo at \"_assets/logo.png\"."
+        );
+        assert_eq!(
+            error.description,
+            "Found following images: [\"_assets/cat.jpg\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
+        );
+    }
+
+    #[test]
+    fn verifies_the_existence_of_dark_mode_logo_mentioned_in_settings_v2() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("_assets/cat.jpg"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("_assets/logo.png"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                theme:
+                  logo:
+                    src: _assets/logo.png
+                    src_dark: _assets/dark-logo.png
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(
+            error.message,
+            "Could not find dark mode logo at \"_assets/dark-logo.png\"."
+        );
+        assert_eq!(
+            error.description,
+            "Found following images: [\"_assets/cat.jpg\", \"_assets/logo.png\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
+        );
+    }
+
+    #[test]
+    fn verifies_the_existence_of_favicon_mentioned_in_settings_v2() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("_assets/favicon.ico"),
+                content: InputContent::Text("".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                theme:
+                  favicon:
+                    src: _assets/favicon.png
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+        let error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(
+            error.message,
+            "Could not find favicon at \"_assets/favicon.png\"."
+        );
+        assert_eq!(
+            error.description,
+            "Found following possible favicons: [\"_assets/favicon.ico\"].\nMake sure the file name is correct and located under the \"_assets\" directory."
+        );
+    }
+
+    #[test]
+    fn verifies_the_existence_of_openapi_spec() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                open_api:
+                  - spec_file: openapi.json
+                    uri_prefix: /api
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let error: &Error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(error.message, "Could not find OpenAPI spec.");
+        assert_eq!(
+            error.description,
+            "OpenAPI spec at \"openapi.json\" not found. Is it in the correct location?"
+        );
+        assert_eq!(error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn reports_remote_openapi_specs_that_could_not_be_fetched() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                open_api:
+                  - spec_url: https://example.com/openapi.json
+                    uri_prefix: /api
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+        let error: &Error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(error.message, "Could not fetch OpenAPI spec.");
+        assert_eq!(
+            error.description,
+            "OpenAPI spec at \"https://example.com/openapi.json\" could not be fetched, and there is no cached copy of it. Check the URL and your connection, and try again."
+        );
+    }
+
+    #[test]
+    fn uses_remote_openapi_specs_given_as_files() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                open_api:
+                  - spec_url: https://example.com/openapi.json
+                    uri_prefix: /api
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("_openapi/example-com-openapi.json"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    {
+                        "openapi": "3.0.0",
+                        "info": { "version": "1.0.0", "title": "Pets" },
+                        "paths": {
+                          "/pets": {
+                            "get": { "tags": ["pets"], "responses": {} }
+                          }
+                        }
+                    }
+                    "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        assert!(project.verify(None, None).is_ok());
+        assert!(project
+            .pages()
+            .iter()
+            .any(|p| p.fs_path() == Path::new("_openapi/example-com-openapi.json")));
+    }
+
+    #[test]
+    fn verifies_uri_prefix() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.json"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        }
+                      }
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                open_api:
+                  - spec_file: openapi.json
+                    uri_prefix:
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let error: &Error = &project.verify(None, None).unwrap_err()[0];
+
+        assert_eq!(error.message, "OpenAPI URI prefix should contain a path.");
+        assert_eq!(
+            error.description,
+            "Define a uri_prefix for the OpenAPI spec \"openapi.json\" in docapella.yaml. For example, uri_prefix: /api.\n\n\
+            The pages of the spec can't be served from the root of the site, where they would collide with your Markdown pages."
+        );
+        assert_eq!(error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn parses_openapi_schemas() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.json"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        },
+                        "components": {
+                          "schemas": {
+                            "Error": {
+                              "type": "object",
+                              "description": "Error response",
+                              "properties": {
+                                "code": {
+                                  "type": "integer",
+                                  "format": "int32"
+                                },
+                                "message": {
+                                  "type": "string"
+                                }
+                              }
+                            }
+                          }
+                        }
+                      }
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                open_api:
+                  - spec_file: openapi.json
+                    uri_prefix: /api
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+
+        let components = project.open_api_components.get("openapi.json").unwrap();
+
+        assert!(components.schemas.contains_key("Error"));
+        assert_eq!(
+            components
+                .schemas
+                .get("Error")
+                .as_ref()
+                .unwrap()
+                .description
+                .as_ref()
+                .unwrap(),
+            "Error response"
+        );
+    }
+
+    #[test]
+    fn verifies_for_overlapping_redirects() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /bar
+                    to: /
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(
+            settings_error.message,
+            "Redirect overlaps with existing page"
+        );
+        assert_eq!(
+            settings_error.description,
+            "Redirect source \"/bar\" already exists as the page bar.md. Pages take precedence over redirects, so the redirect is never used.\n\n\
+            Delete or rename the page, or remove the redirect."
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+        assert_eq!(settings_error.position.as_ref().unwrap().start.row, 4);
+    }
+
+    #[test]
+    fn verifies_for_broken_redirect() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz
+                    to: /foo
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(settings_error.message, "Broken redirect detected");
+        assert_eq!(
+            settings_error.description,
+            r#"Redirect destination "/foo" does not exist."#
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn verifies_for_redirect_handles_anchor() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz
+                    to: /bar#anchor
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        assert!(
+            &project.verify(None, None).is_ok(),
+            "redirect verification with anchor failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_invalid_redirects_slash() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: baz
+                    to: foo
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid redirect detected"
+                    && e.description == r#"Redirect source "baz" must start with a forward slash."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "redirect leading / verification failed"
+        );
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid redirect detected"
+                    && e.description
+                        == r#"Redirect destination "foo" must start with a forward slash, or be an external URL."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "redirect leading / verification failed"
+        );
+    }
+
+    #[test]
+    fn doesnt_verify_external_urls() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz
+                    to: https://example.com
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        assert!(&project.verify(None, None).is_ok());
+    }
+
+    #[test]
+    fn verifies_for_invalid_redirects_md() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz.md
+                    to: /foo.md
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid redirect detected"
+                    && e.description == r#"Redirect source "/baz.md" must not end with .md."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "redirect trailing .md verification failed"
+        );
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid redirect detected"
+                    && e.description == r#"Redirect destination "/foo.md" must not end with .md."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "redirect trailing .md verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_mismatch_wildcard_redirect() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz
+                    to: /foo/:path
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(settings_error.message, "Invalid redirect source");
+        assert_eq!(
+            settings_error.description,
+            r#"Redirect source "/baz" should include a wildcard when `to` has path parameters."#
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn verifies_for_path_param_in_source() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz/:path
+                    to: /bar
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(settings_error.message, "Invalid redirect source");
+        assert_eq!(
+            settings_error.description,
+            r#"Redirect source "/baz/:path" can't include path parameters."#
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn verifies_for_too_many_wildcards() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz/*/**
+                    to: /bar
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(settings_error.message, "Invalid redirect source");
+        assert_eq!(
+            settings_error.description,
+            r#"Redirect source "/baz/*/**" with a wildcard should end with `.../*` or `.../**`."#
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn verifies_for_not_ending_in_wilcard() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz/**/baz
+                    to: /bar
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(settings_error.message, "Invalid redirect source");
+        assert_eq!(
+            settings_error.description,
+            r#"Redirect source "/baz/**/baz" with a wildcard should end with `.../*` or `.../**`."#
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn verifies_redirect_path_param_has_non_alphanumerical() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz/**
+                    to: /bar/:pat.h/foo
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let settings_error: &Error = &errors[0];
+
+        assert_eq!(settings_error.message, "Invalid redirect destination");
+        assert_eq!(
+            settings_error.description,
+            r#"Redirect destination "/bar/:pat.h/foo" path parameters can only contain alphanumerics and underscores."#
+        );
+        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+    }
+
+    #[test]
+    fn verifies_redirect_wildcard_success() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("bar.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Bar redirect overlap
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /**
+                    to: /bar
+                  - from: /foo/**
+                    to: /bar
+                  - from: /**
+                    to: /bar/:path
+                  - from: /**
+                    to: /bar/:path/foo
+
+                  - from: /*
+                    to: /bar
+                  - from: /foo/*
+                    to: /bar
+                  - from: /*
+                    to: /bar/:path
+                  - from: /*
+                    to: /bar/:path/foo
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+
+        assert!(project.verify(None, None).is_ok());
+    }
+
+    #[test]
+    fn verifies_for_non_existing_internal_header_link() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                header:
+                  links:
+                    - label: "Doctave"
+                      href: "/doctave"
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Broken link detected"
+                    && e.description == r#"Header link "/doctave" points to an unknown file."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "header internal link verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_non_existing_internal_footer_link() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                footer:
+                  links:
+                    - label: "Doctave"
+                      href: "/doctave"
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Broken link detected"
+                    && e.description == r#"Footer link "/doctave" points to an unknown file."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "footer internal link verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_non_external_header_link() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                header:
+                  links:
+                    - label: "Doctave"
+                      external: "/doctave"
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid header link in found in docapella.yaml"
+                    && e.description == r#"Found "/doctave", which is not an external link."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "header external link verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_non_external_footer_link() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                footer:
+                  links:
+                    - label: "Doctave"
+                      external: "/doctave"
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid footer link in found in docapella.yaml"
+                    && e.description == r#"Found "/doctave", which is not an external link."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "footer external link verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_non_existing_asset_download_header_link() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                header:
+                  links:
+                    - label: "Doctave"
+                      href: "_assets/doctave"
+                      download: true
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Broken asset link detected"
+                    && e.description
+                        == r#"Header link "_assets/doctave" points to an unknown file."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "header download link verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_non_existing_asset_download_footer_link() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                footer:
+                  links:
+                    - label: "Doctave"
+                      href: "_assets/doctave"
+                      download: true
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Broken asset link detected"
+                    && e.description
+                        == r#"Footer link "_assets/doctave" points to an unknown file."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "footer download link verification failed"
+        );
+    }
+
+    #[test]
+    fn verifies_for_identical_source_destination() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                # Hi
+                "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                - heading: Something
+                "#}
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(
+                    indoc! {r#"
+                ---
+                title: Something
+                redirects:
+                  - from: /baz
+                    to: /baz
+                "#}
+                    .to_string(),
+                ),
+            },
+        ];
+
+        let project = Project::from_file_list(files.clone()).unwrap();
+        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+
+        assert!(
+            errors.iter().any(|e| {
+                e.message == "Invalid redirect detected"
+                    && e.description
+                        == r#"Redirect source "/baz" and destination "/baz" must be different."#
+                    && e.file == Some(PathBuf::from("docapella.yaml"))
+            }),
+            "redirect identical source and destination verification failed"
+        );
+    }
+
+    #[test]
+    fn returns_an_error_if_the_openapi_spec_could_not_be_parsed() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Hi".to_string()),
+            },
+            InputFile {
                 path: PathBuf::from("openapi.json"),
+                content: InputContent::Text("Totally not an openapi spec".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        },
-                        "components": {
-                          "schemas": {
-                            "Error": {
-                              "type": "object",
-                              "description": "Error response",
-                              "properties": {
-                                "code": {
-                                  "type": "integer",
-                                  "format": "int32"
-                                },
-                                "message": {
-                                  "type": "string"
-                                }
-                              }
-                            }
-                          }
-                        }
-                      }
+                ---
+                - heading: Something
                 "#}
                     .to_string(),
                 ),
@@ -1747,54 +5089,104 @@ mod test {
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
+        let error = &Project::from_file_list(files).unwrap_err()[0];
 
-        let components = project.open_api_components.get("openapi.json").unwrap();
+        assert_eq!(error.message, "Could not parse OpenAPI spec");
+        assert_eq!(error.file, Some(PathBuf::from("openapi.json")));
+    }
 
-        assert!(components.schemas.contains_key("Error"));
+    #[test]
+    fn verify_reports_invalid_openapi_examples_when_enabled() {
+        let files = |validate_examples: bool| {
+            vec![
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.json"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        {
+                          "openapi": "3.0.0",
+                          "info": { "title": "Test", "version": "1.0.0" },
+                          "paths": {
+                            "/users": {
+                              "get": {
+                                "tags": ["Users"],
+                                "responses": {
+                                  "200": {
+                                    "description": "OK",
+                                    "content": {
+                                      "application/json": {
+                                        "schema": {
+                                          "type": "object",
+                                          "required": ["id"],
+                                          "properties": { "id": { "type": "integer" } }
+                                        },
+                                        "example": { "id": "abc" }
+                                      }
+                                    }
+                                  }
+                                }
+                              }
+                            }
+                          }
+                        }
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("- heading: Something\n".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(formatdoc! {r#"
+                        ---
+                        title: Something
+                        open_api:
+                          - spec_file: openapi.json
+                            uri_prefix: /api
+                            validate_examples: {}
+                        "#,
+                        validate_examples
+                    }),
+                },
+            ]
+        };
+
+        let project = Project::from_file_list(files(false)).unwrap();
+        assert!(project.verify(None, None).is_ok());
+
+        let project = Project::from_file_list(files(true)).unwrap();
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::INVALID_OPENAPI_SPEC);
+        assert_eq!(errors[0].message, "Example does not match its schema");
         assert_eq!(
-            components
-                .schemas
-                .get("Error")
-                .as_ref()
-                .unwrap()
-                .description
-                .as_ref()
-                .unwrap(),
-            "Error response"
+            errors[0].description,
+            "Expected an integer, found a string at `/id`\n\n\
+            Example at \"#/paths/~1users/get/responses/200/content/application~1json/example\"."
         );
+        assert_eq!(errors[0].file, Some(PathBuf::from("openapi.json")));
+
+        let position = errors[0].position.as_ref().unwrap();
+        assert_eq!((position.start.row, position.start.col), (18, 17));
     }
 
     #[test]
-    fn verifies_for_overlapping_redirects() {
+    fn convert_uri_to_subtab_path_resolves_subtab_path_without_default() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("bar.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Bar redirect overlap
-                "# }
-                    .to_string(),
-                ),
+                content: InputContent::Text("# Hi".to_string()),
             },
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text("- heading: Something\n".to_string()),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
@@ -1802,9 +5194,10 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /bar
-                    to: /
+
+                tabs:
+                  - label: Tab1
+                    path: /tab1/section1/
                 "#}
                     .to_string(),
                 ),
@@ -1812,38 +5205,37 @@ mod test {
         ];
 
         let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
-
         assert_eq!(
-            settings_error.message,
-            "Redirect overlaps with existing page"
+            Project::get_subtab_path_by_uri_path(&project, ""),
+            Some("/".to_string())
         );
         assert_eq!(
-            settings_error.description,
-            r#"Redirect source "/bar" already exists as a page. Delete or rename the page, or change the redirect source."#
+            Project::get_subtab_path_by_uri_path(&project, "/"),
+            Some("/".to_string())
         );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
     }
 
     #[test]
-    fn verifies_for_broken_redirect() {
+    fn verifies_all_navigations() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
+                content: InputContent::Text("# Hi".to_string()),
             },
             InputFile {
-                path: PathBuf::from("bar.md"),
+                path: PathBuf::from("tab1/section1/README.md"),
+                content: InputContent::Text("# Hi, this is section 1".to_string()),
+            },
+            InputFile {
+                path: ["tab1/section1/", NAVIGATION_FILE_NAME].iter().collect(),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Bar redirect overlap
-                "# }
+                ---
+                - heading: Something
+                  items:
+                    - label: Section 1
+                      path: /tab1/section1
+                "#}
                     .to_string(),
                 ),
             },
@@ -1853,6 +5245,9 @@ mod test {
                     indoc! {r#"
                 ---
                 - heading: Something
+                  items:
+                    - label: Section 1
+                      href: /something/notexists/
                 "#}
                     .to_string(),
                 ),
@@ -1863,9 +5258,12 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /baz
-                    to: /foo
+
+                tabs:
+                  - label: Default
+                    path: /
+                  - label: Tab1
+                    path: /tab1/section1/
                 "#}
                     .to_string(),
                 ),
@@ -1874,34 +5272,61 @@ mod test {
 
         let project = Project::from_file_list(files.clone()).unwrap();
         let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
 
-        assert_eq!(settings_error.message, "Broken redirect detected");
+        assert_eq!(errors[0].message, "Invalid navigation.yaml");
         assert_eq!(
-            settings_error.description,
-            r#"Redirect destination "/foo" does not exist."#
+            errors[0].description,
+            ".[0].items: data did not match any variant of untagged enum ItemDescription at line 4 column 5"
         );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+        assert_eq!(
+            errors[0].file,
+            Some(PathBuf::from("/tab1/section1/navigation.yaml"))
+        );
+
+        assert_eq!(errors[1].message, "Broken link detected in navigation");
+        assert_eq!(
+            errors[1].description,
+            "Link /something/notexists/ points to an unknown file."
+        );
+        assert_eq!(errors[1].file, Some(PathBuf::from("/navigation.yaml")));
     }
 
     #[test]
-    fn verifies_for_redirect_handles_anchor() {
+    fn computes_size() {
         let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
             InputFile {
                 path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
+                content: InputContent::Text("Some content!".to_string()),
             },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        assert_eq!(project.content_size_bytes, 38);
+    }
+
+    #[test]
+    fn verifies_the_frontmatter_of_md_files_for_correct_yaml() {
+        let files = vec![
             InputFile {
-                path: PathBuf::from("bar.md"),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Bar redirect overlap
-                "# }
+                ---
+                - not: valid
+                  asdf
+                ---
+
+                # Hi
+                "#}
                     .to_string(),
                 ),
             },
@@ -1921,31 +5346,29 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /baz
-                    to: /bar#anchor
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        assert!(
-            &project.verify(None, None).is_ok(),
-            "redirect verification with anchor failed"
-        );
+        let project = Project::from_file_list(files).unwrap();
+        let result = &project
+            .verify(None, None)
+            .expect_err("Project did not catch broken frontmatter");
+
+        assert_eq!(result[0].message, "Invalid YAML syntax in frontmatter");
     }
 
     #[test]
-    fn verifies_for_invalid_redirects_slash() {
+    fn verifies_all_tabs_have_root_v2() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
                 # Hi
-                "# }
+                "#}
                     .to_string(),
                 ),
             },
@@ -1965,47 +5388,40 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: baz
-                    to: foo
+                tabs:
+                  - label: Default
+                    path: /
+                  - label: Again
+                    path: /foo
+                    subtabs:
+                      - label: Second
+                        path: /foo/bar
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = Project::from_file_list(files).unwrap();
 
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid redirect detected"
-                    && e.description == r#"Redirect source "baz" must start with a forward slash."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "redirect leading / verification failed"
-        );
+        let errors = project.verify(None, None).unwrap_err();
 
         assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid redirect detected"
-                    && e.description
-                        == r#"Redirect destination "foo" must start with a forward slash, or be an external URL."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "redirect leading / verification failed"
+            errors.iter().any(|e| e.message
+                == "Missing root README.md for subtab \"Second\". Add a file at \"/foo/bar/README.md\"."),
+            "No error for missing root readme"
         );
     }
 
     #[test]
-    fn doesnt_verify_external_urls() {
+    fn doesnt_verify_external_tabs_have_root() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
                 # Hi
-                "# }
+                "#}
                     .to_string(),
                 ),
             },
@@ -2025,279 +5441,329 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /baz
-                    to: https://example.com
+                tabs:
+                  - label: Default
+                    external: https://www.example.com
+                  - label: Again
+                    path: /
+                    subtabs:
+                      - label: Second
+                        external: https://www.example.com
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        assert!(&project.verify(None, None).is_ok());
+        let project = Project::from_file_list(files).unwrap();
+
+        project.verify(None, None).unwrap();
     }
 
     #[test]
-    fn verifies_for_invalid_redirects_md() {
+    fn busts_caches_when_asked() {
         let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
             InputFile {
                 path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
+                content: InputContent::Text("![img](/_assets/cat.png)".to_string()),
             },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let root = page
+            .ast(Some(&RenderOptions {
+                bust_image_caches: true,
+                ..Default::default()
+            }))
+            .unwrap();
+        let img = &root.as_markdown().unwrap().children[0].children[0];
+
+        match &img.kind {
+            crate::NodeKind::Image { url, .. } => {
+                assert!(url.contains("cat.png?c=1"), "Image cache not busted");
+            }
+            _ => panic!("Not an image: {:#?}", img),
+        }
+    }
+
+    #[test]
+    fn busts_caches_with_the_given_value() {
+        let files = vec![
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                redirects:
-                  - from: /baz.md
-                    to: /foo.md
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("![img](/_assets/cat.png)".to_string()),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = Project::from_file_list(files).unwrap();
 
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid redirect detected"
-                    && e.description == r#"Redirect source "/baz.md" must not end with .md."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "redirect trailing .md verification failed"
-        );
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let root = page
+            .ast(Some(&RenderOptions {
+                bust_image_caches: true,
+                cache_bust_value: Some("abc123".to_owned()),
+                ..Default::default()
+            }))
+            .unwrap();
+        let img = &root.as_markdown().unwrap().children[0].children[0];
 
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid redirect detected"
-                    && e.description == r#"Redirect destination "/foo.md" must not end with .md."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "redirect trailing .md verification failed"
-        );
+        match &img.kind {
+            crate::NodeKind::Image { url, .. } => {
+                assert_eq!(url, "/_assets/cat.png?c=abc123");
+            }
+            _ => panic!("Not an image: {:#?}", img),
+        }
     }
 
     #[test]
-    fn verifies_for_mismatch_wildcard_redirect() {
+    fn points_images_at_their_variants() {
         let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("bar.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Bar redirect overlap
-                "# }
-                    .to_string(),
-                ),
-            },
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
+            InputFile {
+                path: PathBuf::from("guides/README.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                redirects:
-                  - from: /baz
-                    to: /foo/:path
-                "#}
-                    .to_string(),
+                    "![cat](../_assets/cat.png)\n\n![dog](/_assets/dog.png)".to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
+        let project = Project::from_file_list(files).unwrap();
 
-        assert_eq!(settings_error.message, "Invalid redirect source");
-        assert_eq!(
-            settings_error.description,
-            r#"Redirect source "/baz" should include a wildcard when `to` has path parameters."#
+        let variant = |path: &str, width: u32, mime_type: &str| crate::ImageVariant {
+            path: PathBuf::from(path),
+            width,
+            mime_type: mime_type.to_string(),
+        };
+
+        let mut opts = RenderOptions {
+            prefix_asset_urls: Some("https://cdn.example.com".to_string()),
+            ..Default::default()
+        };
+        opts.image_variants.insert(
+            PathBuf::from("_assets/cat.png"),
+            crate::ImageVariants {
+                sizes: "(max-width: 1280px) 100vw, 1280px".to_string(),
+                variants: vec![
+                    variant("_assets/cat-640w.png", 640, "image/png"),
+                    variant("_assets/cat.png", 1280, "image/png"),
+                    variant("_assets/cat-640w.webp", 640, "image/webp"),
+                ],
+            },
         );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+
+        let page = project.get_page_by_uri_path("/guides").unwrap();
+        let root = page.ast(Some(&opts)).unwrap();
+        let paragraphs = &root.as_markdown().unwrap().children;
+
+        match &paragraphs[0].children[0].kind {
+            crate::NodeKind::Image { responsive, .. } => {
+                let responsive = responsive.as_ref().expect("No variants for relative image");
+                assert_eq!(
+                    responsive.srcset,
+                    "https://cdn.example.com/_assets/cat-640w.png 640w, https://cdn.example.com/_assets/cat.png 1280w"
+                );
+                assert_eq!(
+                    responsive.webp_srcset.as_deref(),
+                    Some("https://cdn.example.com/_assets/cat-640w.webp 640w")
+                );
+                assert_eq!(responsive.sizes, "(max-width: 1280px) 100vw, 1280px");
+            }
+            other => panic!("Not an image: {:#?}", other),
+        }
+
+        match &paragraphs[1].children[0].kind {
+            crate::NodeKind::Image { responsive, .. } => assert!(responsive.is_none()),
+            other => panic!("Not an image: {:#?}", other),
+        }
     }
 
     #[test]
-    fn verifies_for_path_param_in_source() {
+    fn lists_assets_shown_as_images() {
+        let binary = |path: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Binary {
+                signature: String::from("1"),
+                size_bytes: 2048,
+            },
+        };
+
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
-                path: PathBuf::from("bar.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Bar redirect overlap
-                "# }
-                    .to_string(),
-                ),
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("guides/README.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                    "![cat](../_assets/cat.png)\n\n[Report](/_assets/report.pdf)".to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("wip.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                redirects:
-                  - from: /baz/:path
-                    to: /bar
-                "#}
-                    .to_string(),
+                    "---\ndraft: true\n---\n![dog](/_assets/dog.png)".to_string(),
                 ),
             },
+            binary("_assets/cat.png"),
+            binary("_assets/dog.png"),
+            binary("_assets/report.pdf"),
+            binary("_assets/unused.png"),
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
+        let project = Project::from_file_list(files).unwrap();
 
-        assert_eq!(settings_error.message, "Invalid redirect source");
+        let paths = |opts: Option<&RenderOptions>| {
+            let mut paths = project
+                .image_assets(opts)
+                .into_iter()
+                .map(|a| a.path.clone())
+                .collect::<Vec<_>>();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(paths(None), vec![PathBuf::from("_assets/cat.png")]);
+
+        let opts = RenderOptions {
+            publish_drafts: true,
+            ..Default::default()
+        };
         assert_eq!(
-            settings_error.description,
-            r#"Redirect source "/baz/:path" can't include path parameters."#
+            paths(Some(&opts)),
+            vec![
+                PathBuf::from("_assets/cat.png"),
+                PathBuf::from("_assets/dog.png")
+            ]
         );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
     }
 
+    // TODO: This test needs to be rewritten without user preferences
+    // It tests navigation_has_link_to() functionality but used user preferences
+    // to conditionally show links. The test should be updated to test the same
+    // functionality without user preference filtering.
     #[test]
-    fn verifies_for_too_many_wildcards() {
+    #[ignore]
+    fn can_be_asked_if_any_navigation_has_a_link_to_a_specific_page() {
+        // Test temporarily disabled - needs rewrite after user preferences removal
+        todo!("Rewrite test without user preferences");
+    }
+
+    #[test]
+    fn v2_verifies_accent_color() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("bar.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Bar redirect overlap
-                "# }
-                    .to_string(),
-                ),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
-                    indoc! {r#"
+                    indoc! { r#"
                 ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                title: An Project
+
+                theme:
+                  colors:
+                    accent: lolnotacolor
+                "# }
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                redirects:
-                  - from: /baz/*/**
-                    to: /bar
-                "#}
-                    .to_string(),
+                    Hi
+                    "#}
+                    .to_owned(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
+        let project = Project::from_file_list(files).unwrap();
+        let opts = RenderOptions::default();
 
-        assert_eq!(settings_error.message, "Invalid redirect source");
-        assert_eq!(
-            settings_error.description,
-            r#"Redirect source "/baz/*/**" with a wildcard should end with `.../*` or `.../**`."#
-        );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+        let errors = project.verify(Some(&opts), None).unwrap_err();
+
+        let error = &errors[0];
+
+        assert!(
+            &error.description.starts_with("Expected a HEX color code"),
+            "Unexpected error: {:?}",
+            error
+        )
     }
 
     #[test]
-    fn verifies_for_not_ending_in_wilcard() {
+    fn v2_custom_components_with_slots_can_be_verified() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
+                    indoc! { r#"
+                    <Component.LandingPageCard heading="Foo">
+                        Foo bar
+                    </Component.LandingPageCard>
                 "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from("bar.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Bar redirect overlap
-                "# }
+                ---
+                - heading: Something
+                "#}
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("_components/landing-page-card.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
+                    ---
+                    attributes:
+                      - title: heading
+                        required: true
+                    ---
+
+                    <div class="landing-page-card">
+                      <div class="landing-page-card-header">
+                      </div>
+                      <Box>
+                        <Slot />
+                      </Box>
+                    </div>
                 "#}
                     .to_string(),
                 ),
@@ -2308,54 +5774,59 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /baz/**/baz
-                    to: /bar
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
+        let project = Project::from_file_list(files).unwrap();
 
-        assert_eq!(settings_error.message, "Invalid redirect source");
-        assert_eq!(
-            settings_error.description,
-            r#"Redirect source "/baz/**/baz" with a wildcard should end with `.../*` or `.../**`."#
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let result = page.ast(None);
+        assert!(
+            result.is_ok(),
+            "Unable to render page. Got error: {:#?}",
+            result
         );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+        assert_eq!(project.verify(None, None), Ok(()));
     }
 
     #[test]
-    fn verifies_redirect_path_param_has_non_alphanumerical() {
+    fn v2_custom_components_with_syntax_errors_can_be_verified() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
+                    indoc! { r#"
+                    <Component.LandingPageCard heading="Foo">
+                        Foo bar
+                    </Component.LandingPageCard>
                 "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from("bar.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Bar redirect overlap
-                "# }
+                ---
+                - heading: Something
+                "#}
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("_components/landing-page-card.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
+                    ---
+                    attributes:
+                      - title: heading
+                        required: true
+                    ---
+
+                    <div></BOB>
                 "#}
                     .to_string(),
                 ),
@@ -2366,54 +5837,59 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /baz/**
-                    to: /bar/:pat.h/foo
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-        let settings_error: &Error = &errors[0];
+        let project = Project::from_file_list(files).unwrap();
 
-        assert_eq!(settings_error.message, "Invalid redirect destination");
-        assert_eq!(
-            settings_error.description,
-            r#"Redirect destination "/bar/:pat.h/foo" path parameters can only contain alphanumerics and underscores."#
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let result = page.ast(None);
+        assert!(
+            result.is_err(),
+            "Component error not found. Got page: {:#?}",
+            result
         );
-        assert_eq!(settings_error.file, Some(PathBuf::from("docapella.yaml")));
+        let _errors = project.verify(None, None).unwrap_err();
     }
 
     #[test]
-    fn verifies_redirect_wildcard_success() {
+    fn v2_custom_components_are_not_pages() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
+                    indoc! { r#"
+                    <Component.LandingPageCard heading="Foo">
+                        Foo bar
+                    </Component.LandingPageCard>
                 "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from("bar.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Bar redirect overlap
-                "# }
+                ---
+                - heading: Something
+                "#}
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("_components/landing-page-card.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
+                    ---
+                    attributes:
+                      - title: heading
+                        required: true
+                    ---
+
+                    <div></BOB>
                 "#}
                     .to_string(),
                 ),
@@ -2424,43 +5900,30 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                redirects:
-                  - from: /**
-                    to: /bar
-                  - from: /foo/**
-                    to: /bar
-                  - from: /**
-                    to: /bar/:path
-                  - from: /**
-                    to: /bar/:path/foo
-
-                  - from: /*
-                    to: /bar
-                  - from: /foo/*
-                    to: /bar
-                  - from: /*
-                    to: /bar/:path
-                  - from: /*
-                    to: /bar/:path/foo
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
+        let project = Project::from_file_list(files).unwrap();
 
-        assert!(project.verify(None, None).is_ok());
+        assert!(
+            project
+                .get_page_by_uri_path("/_components/landing-page-card")
+                .is_none(),
+            "Custom component parsed as regular page"
+        );
     }
 
     #[test]
-    fn verifies_for_non_existing_internal_header_link() {
+    fn v2_slot_can_only_be_used_in_components() {
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
+                    indoc! { r#"
+                    <Slot />
                 "# }
                     .to_string(),
                 ),
@@ -2481,90 +5944,122 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                header:
-                  links:
-                    - label: "Doctave"
-                      href: "/doctave"
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = Project::from_file_list(files).unwrap();
 
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Broken link detected"
-                    && e.description == r#"Header link "/doctave" points to an unknown file."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "header internal link verification failed"
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let error = page.ast(None).unwrap_err();
+
+        assert_eq!(error.message, "Invalid slot");
+        assert_eq!(
+            error.description,
+            indoc! { r#"
+            `<Slot />` can only be used in components and topics
+
+                1 │ <Slot />
+                    ▲▲▲▲▲▲▲▲
+                    └─ Invalid slot
+
+        "#}
         );
     }
 
     #[test]
-    fn verifies_for_non_existing_internal_footer_link() {
+    fn v2_verify_returns_an_error_if_the_openapi_spec_contains_unknown_components_in_tag_description(
+    ) {
+        // BUG: DOC-1170
         let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text("# Hi".to_string()),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("openapi.yaml"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                footer:
-                  links:
-                    - label: "Doctave"
-                      href: "/doctave"
+                    openapi: "3.0.0"
+                    info:
+                      version: 1.0.0
+                      title: Swagger Petstore
+                      license:
+                        name: MIT
+                    servers:
+                      - url: http://petstore.swagger.io/v1
+                    tags:
+                      - name: pets
+                        description: THIS IS BROKEN <Nope>Hi</Nope>
+                    paths:
+                      /pets:
+                        get:
+                          summary: List all pets
+                          operationId: listPets
+                          tags:
+                            - pets
+                          parameters:
+                            - name: limit
+                              in: query
+                              description: How many items to return at one time (max 100)
+                              required: false
+                              schema:
+                                type: integer
+                                maximum: 100
+                                format: int32
+                          responses:
+                            '200':
+                              description: A paged array of pets
+                              headers:
+                                x-next:
+                                  description: A link to the next page of responses
+                                  schema:
+                                    type: string
+                              content:
+                                application/json:
+                                  schema:
+                                    $ref: '#/components/schemas/Pets'
+                            default:
+                              description: unexpected error
+                              content:
+                                application/json:
+                                  schema:
+                                    $ref: '#/components/schemas/Error'
+                    components:
+                      schemas:
+                        Pet:
+                          type: object
+                          required:
+                            - id
+                            - name
+                          properties:
+                            id:
+                              type: integer
+                              format: int64
+                            name:
+                              type: string
+                            tag:
+                              type: string
+                        Pets:
+                          type: array
+                          maxItems: 100
+                          items:
+                            $ref: '#/components/schemas/Pet'
+                        Error:
+                          type: object
+                          required:
+                            - code
+                            - message
+                          properties:
+                            code:
+                              type: integer
+                              format: int32
+                            message:
+                              type: string
                 "#}
-                    .to_string(),
-                ),
-            },
-        ];
-
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Broken link detected"
-                    && e.description == r#"Footer link "/doctave" points to an unknown file."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "footer internal link verification failed"
-        );
-    }
-
-    #[test]
-    fn verifies_for_non_external_header_link() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
+                    .to_owned(),
                 ),
             },
             InputFile {
@@ -2583,395 +6078,477 @@ mod test {
                     indoc! {r#"
                 ---
                 title: Something
-                header:
-                  links:
-                    - label: "Doctave"
-                      external: "/doctave"
+                open_api:
+                  - spec_file: openapi.yaml
+                    uri_prefix: /api
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = &Project::from_file_list(files).unwrap();
 
         assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid header link in found in docapella.yaml"
-                    && e.description == r#"Found "/doctave", which is not an external link."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "header external link verification failed"
+            project
+                .get_page_by_uri_path("/api/pets")
+                .unwrap()
+                .ast(None)
+                .is_err(),
+            "Did not get error on broken OpenAPI spec"
+        );
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(
+            errors[0].description,
+            indoc! { r#"
+            Unknown element "Nope"
+
+                1 │ THIS IS BROKEN <Nope>Hi</Nope>
+                                    ▲▲▲▲
+
+        "#}
         );
     }
 
     #[test]
-    fn verifies_for_non_external_footer_link() {
+    fn checks_features_dont_exist() {
         let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
+                    ---
+                    - heading: Something
+                    "#}
                     .to_string(),
                 ),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                footer:
-                  links:
-                    - label: "Doctave"
-                      external: "/doctave"
-                "#}
+                    indoc! { r#"
+                    ---
+                    title: An Project
+                    "# }
                     .to_string(),
                 ),
             },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("Hello".to_string()),
+            },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid footer link in found in docapella.yaml"
-                    && e.description == r#"Found "/doctave", which is not an external link."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "footer external link verification failed"
-        );
+        let project = Project::from_file_list(files).unwrap();
+        let expected: Vec<String> = vec![];
+        assert_eq!(project.check_features(), expected);
     }
 
     #[test]
-    fn verifies_for_non_existing_asset_download_header_link() {
+    fn checks_features_custom_css_exists() {
         let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
+                    ---
+                    - heading: Something
+                    "#}
                     .to_string(),
                 ),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                header:
-                  links:
-                    - label: "Doctave"
-                      href: "_assets/doctave"
-                      download: true
-                "#}
+                    indoc! { r#"
+                    ---
+                    title: An Project
+                    styles:
+                      - _assets/custom.css
+
+                    "# }
                     .to_string(),
                 ),
             },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("Hello".to_string()),
+            },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = Project::from_file_list(files).unwrap();
 
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Broken asset link detected"
-                    && e.description
-                        == r#"Header link "_assets/doctave" points to an unknown file."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "header download link verification failed"
-        );
+        assert!(project.check_features().contains(&"custom_css".to_string()))
     }
 
     #[test]
-    fn verifies_for_non_existing_asset_download_footer_link() {
+    fn download_link_works_with_openapi_non_assets_folder() {
         let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                footer:
-                  links:
-                    - label: "Doctave"
-                      href: "_assets/doctave"
-                      download: true
-                "#}
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.json
+                        uri_prefix: /api
+                    "# }
                     .to_string(),
                 ),
             },
-        ];
-
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
-
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Broken asset link detected"
-                    && e.description
-                        == r#"Footer link "_assets/doctave" points to an unknown file."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "footer download link verification failed"
-        );
-    }
-
-    #[test]
-    fn verifies_for_identical_source_destination() {
-        let files = vec![
             InputFile {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Hi
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                      <Link download href="openapi.json">Download</Link>
+                    "#}
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("openapi.json"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                redirects:
-                  - from: /baz
-                    to: /baz
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        }
+                      }
                 "#}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = Project::from_file_list(files).unwrap();
 
-        assert!(
-            errors.iter().any(|e| {
-                e.message == "Invalid redirect detected"
-                    && e.description
-                        == r#"Redirect source "/baz" and destination "/baz" must be different."#
-                    && e.file == Some(PathBuf::from("docapella.yaml"))
-            }),
-            "redirect identical source and destination verification failed"
-        );
+        assert!(project
+            .verify(Some(&RenderOptions::default()), None)
+            .is_ok());
     }
 
     #[test]
-    fn returns_an_error_if_the_openapi_spec_could_not_be_parsed() {
+    fn parses_openapi_schema_node() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text("Totally not an openapi spec".to_string()),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.json
+                        uri_prefix: /api
+                    "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                open_api:
-                  - spec_file: openapi.json
-                    uri_prefix: /api
-                "#}
+                      <OpenAPISchema title="User" openapi_path="openapi.json" />
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.json"),
+                content: InputContent::Text(
+                    indoc! {r##"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        },
+                        "components": {
+                          "schemas": {
+                              "User": {
+                                  "title": "User schema",
+                                  "description": "# Hello, this is a title",
+                                  "type": "object",
+                                  "properties": {
+                                      "id": {
+                                          "type": "integer",
+                                          "format": "int64"
+                                      },
+                                      "username": {
+                                          "type": "string"
+                                      }
+                                  }
+                              }
+                          }
+                        }
+                      }
+                "##}
                     .to_string(),
                 ),
             },
         ];
 
-        let error = &Project::from_file_list(files).unwrap_err()[0];
+        let project = Project::from_file_list(files).unwrap();
 
-        assert_eq!(error.message, "Could not parse OpenAPI spec");
-        assert_eq!(error.file, Some(PathBuf::from("openapi.json")));
+        let opts = RenderOptions::default();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = page.ast(Some(&opts)).unwrap();
+
+        if let Ast::Markdown(root) = ast {
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <OpenAPISchema title={User schema} expanded={true}>
+                    <Heading1>
+                        <Text>
+                            Hello, this is a title
+                        </Text>
+                    </Heading1>
+                </OpenAPISchema>
+                "#}
+                .to_string()
+            );
+        } else {
+            panic!("Expected markdown AST");
+        }
     }
 
     #[test]
-    fn convert_uri_to_subtab_path_resolves_subtab_path_without_default() {
+    fn openapi_schemas_can_be_toggled_to_not_expanded() {
         let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("- heading: Something\n".to_string()),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-
-                tabs:
-                  - label: Tab1
-                    path: /tab1/section1/
-                "#}
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.json
+                        uri_prefix: /api
+                    "# }
+                    .to_string(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text(
+                    indoc! {r#"
+                      <OpenAPISchema title="User" openapi_path="openapi.json" expanded={false} />
+                    "#}
+                    .to_owned(),
+                ),
+            },
+            InputFile {
+                path: PathBuf::from("openapi.json"),
+                content: InputContent::Text(
+                    indoc! {r##"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        },
+                        "components": {
+                          "schemas": {
+                              "User": {
+                                  "title": "User schema",
+                                  "description": "# Hello, this is a title",
+                                  "type": "object",
+                                  "properties": {
+                                      "id": {
+                                          "type": "integer",
+                                          "format": "int64"
+                                      },
+                                      "username": {
+                                          "type": "string"
+                                      }
+                                  }
+                              }
+                          }
+                        }
+                      }
+                "##}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        assert_eq!(
-            Project::get_subtab_path_by_uri_path(&project, ""),
-            Some("/".to_string())
-        );
-        assert_eq!(
-            Project::get_subtab_path_by_uri_path(&project, "/"),
-            Some("/".to_string())
-        );
+        let project = Project::from_file_list(files).unwrap();
+
+        let opts = RenderOptions::default();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = page.ast(Some(&opts)).unwrap();
+
+        if let Ast::Markdown(root) = ast {
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <OpenAPISchema title={User schema} expanded={false}>
+                    <Heading1>
+                        <Text>
+                            Hello, this is a title
+                        </Text>
+                    </Heading1>
+                </OpenAPISchema>
+                "#}
+                .to_string()
+            );
+        } else {
+            panic!("Expected markdown AST");
+        }
     }
 
     #[test]
-    fn verifies_all_navigations() {
+    fn openapi_schema_node_error_when_schema_not_found() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("tab1/section1/README.md"),
-                content: InputContent::Text("# Hi, this is section 1".to_string()),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
             },
             InputFile {
-                path: ["tab1/section1/", NAVIGATION_FILE_NAME].iter().collect(),
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                  items:
-                    - label: Section 1
-                      path: /tab1/section1
-                "#}
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.json
+                        uri_prefix: /api
+                    "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                  items:
-                    - label: Section 1
-                      href: /something/notexists/
-                "#}
-                    .to_string(),
+                      <OpenAPISchema title="Foo" openapi_path="openapi.json" />
+                    "#}
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("openapi.json"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-
-                tabs:
-                  - label: Default
-                    path: /
-                  - label: Tab1
-                    path: /tab1/section1/
-                "#}
+                    indoc! {r##"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        },
+                        "components": {
+                          "schemas": {
+                              "User": {
+                                  "title": "User schema",
+                                  "description": "# Hello, this is a title",
+                                  "type": "object",
+                                  "properties": {
+                                      "id": {
+                                          "type": "integer",
+                                          "format": "int64"
+                                      },
+                                      "username": {
+                                          "type": "string"
+                                      }
+                                  }
+                              }
+                          }
+                        }
+                      }
+                "##}
                     .to_string(),
                 ),
             },
         ];
 
-        let project = Project::from_file_list(files.clone()).unwrap();
-        let errors: &Vec<Error> = &project.verify(None, None).unwrap_err();
+        let project = Project::from_file_list(files).unwrap();
+        let opts = RenderOptions::default();
 
-        assert_eq!(errors[0].message, "Invalid navigation.yaml");
-        assert_eq!(
-            errors[0].description,
-            ".[0].items: data did not match any variant of untagged enum ItemDescription at line 4 column 5"
-        );
-        assert_eq!(
-            errors[0].file,
-            Some(PathBuf::from("/tab1/section1/navigation.yaml"))
-        );
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let error = page.ast(Some(&opts)).unwrap_err();
 
-        assert_eq!(errors[1].message, "Broken link detected in navigation");
-        assert_eq!(
-            errors[1].description,
-            "Link /something/notexists/ points to an unknown file."
+        assert_str_eq!(
+            error.description,
+            indoc! { r#"
+            OpenAPI schema with title "Foo" not found
+
+                1 │ <OpenAPISchema title="Foo" openapi_path="openapi.json" />
+                                          ▲▲▲
+
+            "# }
+            .to_string()
         );
-        assert_eq!(errors[1].file, Some(PathBuf::from("/navigation.yaml")));
     }
 
     #[test]
-    fn computes_size() {
+    fn openapi_schema_node_error_when_openapi_not_found() {
         let files = vec![
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
@@ -2979,103 +6556,117 @@ mod test {
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("Some content!".to_string()),
-            },
-        ];
-
-        let project = Project::from_file_list(files).unwrap();
-
-        assert_eq!(project.content_size_bytes, 38);
-    }
-
-    #[test]
-    fn verifies_the_frontmatter_of_md_files_for_correct_yaml() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - not: valid
-                  asdf
-                ---
-
-                # Hi
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
+                    ---
+                    title: An Project
+                    "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                "#}
-                    .to_string(),
+                      <OpenAPISchema title="User" openapi_path="openapi.json" />
+                    "#}
+                    .to_owned(),
                 ),
             },
         ];
 
         let project = Project::from_file_list(files).unwrap();
-        let result = &project
-            .verify(None, None)
-            .expect_err("Project did not catch broken frontmatter");
 
-        assert_eq!(result[0].message, "Invalid YAML syntax in frontmatter");
+        let opts = RenderOptions::default();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let error = page.ast(Some(&opts)).unwrap_err();
+
+        assert_str_eq!(
+            error.description,
+            indoc! { r#"
+            Can't find an OpenAPI file with path "openapi.json"
+
+                1 │ <OpenAPISchema title="User" openapi_path="openapi.json" />
+                                                              ▲▲▲▲▲▲▲▲▲▲▲▲
+
+            "# }
+            .to_string()
+        );
     }
 
     #[test]
-    fn verifies_all_tabs_have_root_v2() {
+    #[ignore]
+    fn parses_openapi_schema_node_with_expanded() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Hi
-                "#}
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.json
+                        uri_prefix: /api
+                    "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                      <OpenAPISchema title="User" openapi_path="openapi.json" expanded={true} />
+                    "#}
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("openapi.json"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                tabs:
-                  - label: Default
-                    path: /
-                  - label: Again
-                    path: /foo
-                    subtabs:
-                      - label: Second
-                        path: /foo/bar
-                "#}
+                    indoc! {r##"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        },
+                        "components": {
+                          "schemas": {
+                              "User": {
+                                  "title": "User schema",
+                                  "description": "# Hello, this is a title",
+                                  "type": "object",
+                                  "properties": {
+                                      "id": {
+                                          "type": "integer",
+                                          "format": "int64"
+                                      },
+                                      "username": {
+                                          "type": "string"
+                                      }
+                                  }
+                              }
+                          }
+                        }
+                      }
+                "##}
                     .to_string(),
                 ),
             },
@@ -3083,52 +6674,102 @@ mod test {
 
         let project = Project::from_file_list(files).unwrap();
 
-        let errors = project.verify(None, None).unwrap_err();
+        let opts = RenderOptions::default();
 
-        assert!(
-            errors.iter().any(|e| e.message
-                == "Missing root README.md for subtab \"Second\". Add a file at \"/foo/bar/README.md\"."),
-            "No error for missing root readme"
-        );
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = page.ast(Some(&opts)).unwrap();
+
+        if let Ast::Markdown(root) = ast {
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <OpenAPISchema title={User schema} openapi_path="openapi.json" expanded={true}>
+                    <Heading1>
+                        <Text>
+                            Hello, this is a title
+                        </Text>
+                    </Heading1>
+                </OpenAPISchema>
+                "#}
+                .to_string()
+            );
+        } else {
+            panic!("Expected markdown AST");
+        }
     }
 
     #[test]
-    fn doesnt_verify_external_tabs_have_root() {
+    #[ignore]
+    fn parses_openapi_schema_node_with_expanded_stringified() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! {r#"
-                # Hi
-                "#}
+                    ---
+                    title: An Project
+                    open_api:
+                      - spec_file: openapi.json
+                        uri_prefix: /api
+                    "# }
                     .to_string(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                      <OpenAPISchema title="User" openapi_path="openapi.json" expanded="true" />
+                    "#}
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("openapi.json"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                tabs:
-                  - label: Default
-                    external: https://www.example.com
-                  - label: Again
-                    path: /
-                    subtabs:
-                      - label: Second
-                        external: https://www.example.com
-                "#}
+                    indoc! {r##"
+                    {
+                        "openapi": "3.0.0",
+                        "info": {
+                          "version": "1.0.0",
+                          "title": "Example code sample extensions case"
+                        },
+                        "paths": {
+                          "/foo": {
+                            "get": {
+                              "description": "Foo GET",
+                              "tags": [
+                                "test"
+                              ],
+                              "parameters": [],
+                              "responses": {},
+                            }
+                          }
+                        },
+                        "components": {
+                          "schemas": {
+                              "User": {
+                                  "title": "User schema",
+                                  "description": "# Hello, this is a title",
+                                  "type": "object",
+                                  "properties": {
+                                      "id": {
+                                          "type": "integer",
+                                          "format": "int64"
+                                      },
+                                      "username": {
+                                          "type": "string"
+                                      }
+                                  }
+                              }
+                          }
+                        }
+                      }
+                "##}
                     .to_string(),
                 ),
             },
@@ -3136,11 +6777,32 @@ mod test {
 
         let project = Project::from_file_list(files).unwrap();
 
-        project.verify(None, None).unwrap();
+        let opts = RenderOptions::default();
+
+        let page = project.get_page_by_uri_path("/").unwrap();
+        let ast = page.ast(Some(&opts)).unwrap();
+
+        if let Ast::Markdown(root) = ast {
+            assert_str_eq!(
+                root.debug_string().unwrap(),
+                indoc! { r#"
+                <OpenAPISchema title={User schema} expanded={true}>
+                    <Heading1>
+                        <Text>
+                            Hello, this is a title
+                        </Text>
+                    </Heading1>
+                </OpenAPISchema>
+                "#}
+                .to_string()
+            );
+        } else {
+            panic!("Expected markdown AST");
+        }
     }
 
     #[test]
-    fn busts_caches_when_asked() {
+    fn can_get_the_ast_for_a_virtual_page_in_a_project() {
         let files = vec![
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
@@ -3148,46 +6810,47 @@ mod test {
             },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+                content: InputContent::Text(
+                    indoc! { r#"
+                ---
+                title: An Project
+                "# }
+                    .to_owned(),
+                ),
             },
             InputFile {
                 path: PathBuf::from("README.md"),
-                content: InputContent::Text("![img](/_assets/cat.png)".to_string()),
+                content: InputContent::Text(
+                    indoc! {r#"
+                    # Hi
+                    "#}
+                    .to_owned(),
+                ),
             },
         ];
 
         let project = Project::from_file_list(files).unwrap();
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let root = page
-            .ast(Some(&RenderOptions {
-                bust_image_caches: true,
-                ..Default::default()
-            }))
+        let opts = RenderOptions::default();
+        let ast = project
+            .get_ast_mdx_fault_tolerant("hi", Path::new("example.md"), &opts)
             .unwrap();
-        let img = &root.as_markdown().unwrap().children[0].children[0];
-
-        match &img.kind {
-            crate::NodeKind::Image { url, .. } => {
-                assert!(url.contains("cat.png?c=1"), "Image cache not busted");
-            }
-            _ => panic!("Not an image: {:#?}", img),
-        }
-    }
 
-    // TODO: This test needs to be rewritten without user preferences
-    // It tests navigation_has_link_to() functionality but used user preferences
-    // to conditionally show links. The test should be updated to test the same
-    // functionality without user preference filtering.
-    #[test]
-    #[ignore]
-    fn can_be_asked_if_any_navigation_has_a_link_to_a_specific_page() {
-        // Test temporarily disabled - needs rewrite after user preferences removal
-        todo!("Rewrite test without user preferences");
+        assert_str_eq!(
+            ast.debug_string().unwrap(),
+            indoc! { r#"
+            <Paragraph>
+                <Text>
+                    hi
+                </Text>
+            </Paragraph>
+            "# }
+            .to_string()
+        );
     }
 
     #[test]
-    fn v2_verifies_accent_color() {
+    fn can_get_errors_for_a_virtual_page_in_a_project() {
         let files = vec![
             InputFile {
                 path: PathBuf::from(NAVIGATION_FILE_NAME),
@@ -3199,10 +6862,6 @@ mod test {
                     indoc! { r#"
                 ---
                 title: An Project
-
-                theme:
-                  colors:
-                    accent: lolnotacolor
                 "# }
                     .to_owned(),
                 ),
@@ -3211,7 +6870,7 @@ mod test {
                 path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                    Hi
+                    # Hi
                     "#}
                     .to_owned(),
                 ),
@@ -3219,1373 +6878,3446 @@ mod test {
         ];
 
         let project = Project::from_file_list(files).unwrap();
-        let opts = RenderOptions::default();
 
-        let errors = project.verify(Some(&opts), None).unwrap_err();
+        let opts = RenderOptions::default();
+        let result = project
+            .get_ast_mdx_fault_tolerant("hi\n</>", Path::new("example.md"), &opts)
+            .unwrap_err();
 
-        let error = &errors[0];
+        let ast = result.0.unwrap();
+        assert_eq!(ast.children.len(), 1);
 
-        assert!(
-            &error.description.starts_with("Expected a HEX color code"),
-            "Unexpected error: {:?}",
-            error
-        )
+        let errors = result.1;
+        assert_eq!(errors.len(), 1);
     }
 
     #[test]
-    fn v2_custom_components_with_slots_can_be_verified() {
+    fn can_offset_virtual_file_error_offsets_by_frontmatter() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! { r#"
-                    <Component.LandingPageCard heading="Foo">
-                        Foo bar
-                    </Component.LandingPageCard>
+                ---
+                title: An Project
                 "# }
-                    .to_string(),
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                    # Hi
+                    "#}
+                    .to_owned(),
                 ),
             },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let markdown = indoc! {r#"
+        ---
+        title: An Project
+        ---
+
+        # Hi
+
+        </>
+        "#
+        };
+
+        let opts = RenderOptions::default();
+        let result = project
+            .get_ast_mdx_fault_tolerant(markdown, Path::new("example.md"), &opts)
+            .unwrap_err();
+
+        let errors = result.1;
+        assert_eq!(errors.len(), 1);
+
+        let error = &errors[0];
+        assert_eq!(error.position.as_ref().unwrap().start.row, 7);
+        assert_eq!(error.position.as_ref().unwrap().start.col, 1);
+        assert_eq!(error.position.as_ref().unwrap().start.byte_offset, 33);
+        assert_eq!(error.position.as_ref().unwrap().end.row, 7);
+        assert_eq!(error.position.as_ref().unwrap().end.col, 4);
+        assert_eq!(error.position.as_ref().unwrap().end.byte_offset, 36);
+
+        assert_eq!(
+            "</>",
+            &markdown[error.position.as_ref().unwrap().start.byte_offset
+                ..error.position.as_ref().unwrap().end.byte_offset]
+        );
+    }
+
+    #[test]
+    fn verifies_vale_config_file_exists() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
             InputFile {
-                path: PathBuf::from("_components/landing-page-card.md"),
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    attributes:
-                      - title: heading
-                        required: true
-                    ---
+                    indoc! { r#"
+              ---
+              title: An Project
 
-                    <div class="landing-page-card">
-                      <div class="landing-page-card-header">
-                      </div>
-                      <Box>
-                        <Slot />
-                      </Box>
-                    </div>
-                "#}
-                    .to_string(),
+              vale:
+                version: 2.30.0
+                config_file_path: .vale.ini
+              "# }
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                title: Something
-                "#}
-                    .to_string(),
+                  # Hi
+                  "#}
+                    .to_owned(),
                 ),
             },
         ];
 
         let project = Project::from_file_list(files).unwrap();
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let result = page.ast(None);
-        assert!(
-            result.is_ok(),
-            "Unable to render page. Got error: {:#?}",
-            result
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Couldn't find Vale configuration file.");
+        assert_eq!(
+            errors[0].description,
+            "Expected a Vale configuration file at \".vale.ini\""
         );
-        assert_eq!(project.verify(None, None), Ok(()));
     }
 
     #[test]
-    fn v2_custom_components_with_syntax_errors_can_be_verified() {
+    fn verifies_vale_config_path() {
         let files = vec![
             InputFile {
-                path: PathBuf::from("README.md"),
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
                 content: InputContent::Text(
                     indoc! { r#"
-                    <Component.LandingPageCard heading="Foo">
-                        Foo bar
-                    </Component.LandingPageCard>
-                "# }
-                    .to_string(),
+              ---
+              title: An Project
+
+              vale:
+                version: 2.30.0
+                config_file_path: .vale.inia
+              "# }
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                path: PathBuf::from("README.md"),
                 content: InputContent::Text(
                     indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
+                  # Hi
+                  "#}
+                    .to_owned(),
                 ),
             },
             InputFile {
-                path: PathBuf::from("_components/landing-page-card.md"),
+                path: PathBuf::from(".vale.inia"),
                 content: InputContent::Text(
                     indoc! {r#"
-                    ---
-                    attributes:
-                      - title: heading
-                        required: true
-                    ---
-
-                    <div></BOB>
-                "#}
-                    .to_string(),
+                  # Hi
+                  "#}
+                    .to_owned(),
                 ),
             },
+        ];
+
+        let project = Project::from_file_list(files).unwrap();
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].message,
+            "A hidden vale configuration file must be named \".vale.ini\"."
+        );
+        assert_eq!(
+            errors[0].description,
+            "Use \".vale.ini\", or remove the \".\" from the start of the config file name \".vale.inia\"."
+        );
+    }
+
+    #[test]
+    fn creates_an_elasticlunr_search_index() {
+        let files = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                "#}
-                    .to_string(),
-                ),
+                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# A heading\n\nA paragraph".to_string()),
             },
         ];
 
         let project = Project::from_file_list(files).unwrap();
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let result = page.ast(None);
-        assert!(
-            result.is_err(),
-            "Component error not found. Got page: {:#?}",
-            result
-        );
-        let _errors = project.verify(None, None).unwrap_err();
+        let index = project.search_index().unwrap();
+
+        assert!(index.to_json().contains("A heading"));
+        assert!(index.to_json().contains("A paragraph"));
+    }
+
+    mod localization {
+        use super::*;
+
+        fn files() -> Vec<InputFile> {
+            vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("- heading: Something\n".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(String::from(
+                        "---\ntitle: An Project\nlocales: [en, fr]\n",
+                    )),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Welcome".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("fr/README.md"),
+                    content: InputContent::Text("# Bienvenue\n\n[Guide](/guide.md)".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("guide.md"),
+                    content: InputContent::Text("# Guide".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("translated.md"),
+                    content: InputContent::Text("# Translated".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("fr/translated.md"),
+                    content: InputContent::Text("# Traduit".to_string()),
+                },
+            ]
+        }
+
+        #[test]
+        fn prefers_translated_page_for_locale() {
+            let project = Project::from_file_list(files()).unwrap();
+
+            let opts = RenderOptions {
+                locale: Some("fr".to_string()),
+                ..Default::default()
+            };
+
+            let page = project
+                .get_localized_page_by_uri_path("/translated", Some(&opts))
+                .unwrap();
+            assert_eq!(page.uri_path(), "/fr/translated");
+
+            let page = project
+                .get_localized_page_by_uri_path("/translated", None)
+                .unwrap();
+            assert_eq!(page.uri_path(), "/translated");
+        }
+
+        #[test]
+        fn falls_back_to_default_locale_for_untranslated_pages() {
+            let project = Project::from_file_list(files()).unwrap();
+
+            let page = project
+                .get_localized_page_by_uri_path("/fr/guide", None)
+                .unwrap();
+            assert_eq!(page.uri_path(), "/guide");
+
+            let page = project.get_localized_page_by_uri_path("/fr", None).unwrap();
+            assert_eq!(page.uri_path(), "/fr");
+        }
+
+        #[test]
+        fn builds_search_index_per_locale() {
+            let project = Project::from_file_list(files()).unwrap();
+
+            let index = project.search_index_for_locale("fr").unwrap().to_json();
+            assert!(index.contains("Bienvenue"));
+            assert!(index.contains("Traduit"));
+            assert!(index.contains("Guide"));
+            assert!(!index.contains("Welcome"));
+
+            let index = project.search_index_for_locale("en").unwrap().to_json();
+            assert!(index.contains("Welcome"));
+            assert!(index.contains("Translated"));
+            assert!(!index.contains("Traduit"));
+        }
+
+        #[test]
+        fn links_in_translated_pages_point_at_translations() {
+            let mut files = files();
+            files.push(InputFile {
+                path: PathBuf::from("fr/links.md"),
+                content: InputContent::Text(
+                    "[Traduit](/translated.md) [Guide](/guide.md) [Accueil](/fr/README.md)"
+                        .to_string(),
+                ),
+            });
+
+            let project = Project::from_file_list(files).unwrap();
+
+            let opts = RenderOptions {
+                webbify_internal_urls: true,
+                prefix_link_urls: Some("/v2".to_string()),
+                ..Default::default()
+            };
+
+            let page = project.get_page_by_uri_path("/fr/links").unwrap();
+            let root = page.ast(Some(&opts)).unwrap();
+
+            let hrefs = root.as_markdown().unwrap().children[0]
+                .children
+                .iter()
+                .filter_map(|node| match &node.kind {
+                    crate::NodeKind::Link { url, .. } => Some(url.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(hrefs, vec!["/v2/fr/translated", "/v2/guide", "/v2/fr"]);
+        }
+
+        #[test]
+        fn verifies_translated_links_within_locale_first() {
+            let mut files = files();
+            files.push(InputFile {
+                path: PathBuf::from("fr/only-in-french.md"),
+                content: InputContent::Text("# Seulement en français".to_string()),
+            });
+            files.push(InputFile {
+                path: PathBuf::from("fr/links.md"),
+                content: InputContent::Text(
+                    "[Traduit](/only-in-french.md) [Guide](/guide.md)".to_string(),
+                ),
+            });
+
+            let project = Project::from_file_list(files.clone()).unwrap();
+            assert_eq!(project.verify(None, None), Ok(()));
+
+            files.push(InputFile {
+                path: PathBuf::from("links.md"),
+                content: InputContent::Text("[Broken](/only-in-french.md)".to_string()),
+            });
+
+            let project = Project::from_file_list(files).unwrap();
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].code, Error::BROKEN_INTERNAL_LINK);
+            assert_eq!(errors[0].file, Some(PathBuf::from("links.md")));
+        }
+    }
+
+    mod openapi_schema_depth {
+        use super::*;
+        use crate::open_api::ast::CollapsedSchema;
+
+        const SPEC: &str = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        paths:
+          /tree:
+            get:
+              summary: Get the tree
+              tags: [Trees]
+              responses:
+                '200':
+                  description: A tree
+                  content:
+                    application/json:
+                      schema:
+                        type: object
+                        properties:
+                          level1:
+                            type: object
+                            properties:
+                              level2:
+                                type: object
+                                properties:
+                                  level3:
+                                    type: object
+                                    properties:
+                                      leaf:
+                                        type: string
+        components:
+          schemas:
+            Node:
+              type: object
+              properties:
+                name:
+                  type: string
+                parent:
+                  $ref: '#/components/schemas/Node'
+        "#};
+
+        fn project() -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: openapi.yaml
+                            uri_prefix: /api
+                            max_schema_depth: 2
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.yaml"),
+                    content: InputContent::Text(SPEC.to_string()),
+                },
+            ])
+            .unwrap()
+        }
+
+        fn tree_schema(project: &Project) -> SchemaAst {
+            let ast = project
+                .pages()
+                .into_iter()
+                .find_map(|p| match p.ast(None) {
+                    Ok(Ast::OpenApi(ast)) => Some(ast),
+                    _ => None,
+                })
+                .unwrap();
+
+            ast.operations[0].responses[0].media_types[0].schemas[0].clone()
+        }
+
+        #[test]
+        fn collapses_schemas_nested_deeper_than_the_max_depth() {
+            let project = project();
+            let schema = tree_schema(&project);
+
+            let level1 = &schema.schemas[0];
+            assert_eq!(level1.collapsed, None);
+
+            let level2 = &level1.schemas[0];
+            assert!(level2.schemas.is_empty());
+
+            match &level2.collapsed {
+                Some(CollapsedSchema::MaxDepth { spec, pointer }) => {
+                    assert_eq!(spec, "openapi.yaml");
+                    assert!(pointer.starts_with("/operations/"), "{}", pointer);
+                    assert!(
+                        pointer.ends_with("/responses/200/application~1json/0/0/0"),
+                        "{}",
+                        pointer
+                    );
+                }
+                other => panic!("Expected a collapsed schema, got {:?}", other),
+            }
+        }
+
+        #[test]
+        fn fetches_collapsed_schemas_by_pointer() {
+            let project = project();
+            let schema = tree_schema(&project);
+
+            let pointer = match &schema.schemas[0].schemas[0].collapsed {
+                Some(CollapsedSchema::MaxDepth { pointer, .. }) => pointer.clone(),
+                other => panic!("Expected a collapsed schema, got {:?}", other),
+            };
+
+            let fragment = project
+                .openapi_schema_fragment(Path::new("openapi.yaml"), &pointer, None)
+                .unwrap();
+
+            let level3 = &fragment.schemas[0];
+            assert_eq!(level3.title.as_deref(), Some("level3"));
+            assert_eq!(level3.collapsed, None);
+            assert_eq!(level3.schemas[0].title.as_deref(), Some("leaf"));
+
+            let error = project
+                .openapi_schema_fragment(Path::new("openapi.yaml"), "/operations/nope/0", None)
+                .unwrap_err();
+            assert_eq!(error.code, Error::INVALID_OPENAPI_SCHEMA);
+        }
+
+        #[test]
+        fn marks_recursive_references() {
+            let project = project();
+
+            let parent = project
+                .openapi_schema_fragment(
+                    Path::new("openapi.yaml"),
+                    "/components/schemas/Node/1",
+                    None,
+                )
+                .unwrap();
+
+            assert_eq!(
+                parent.schemas[1].collapsed,
+                Some(CollapsedSchema::Recursive {
+                    component_name: "Node".to_string()
+                })
+            );
+        }
+
+        #[test]
+        fn reuses_specs_parsed_when_loading_the_project() {
+            let project = project();
+            let spec = &project.settings.open_api()[0];
+
+            let cached = project.cached_openapi_spec(spec, SPEC).unwrap();
+            let reparsed = project
+                .cached_openapi_spec(spec, &SPEC.replace("Trees", "Forests"))
+                .unwrap();
+
+            assert!(Arc::ptr_eq(
+                &cached,
+                &project.open_api_specs["openapi.yaml"].spec
+            ));
+            assert!(!Arc::ptr_eq(&cached, &reparsed));
+            assert_eq!(reparsed.info.title.as_str(), "Forests");
+        }
+    }
+
+    mod openapi_doctave_extensions {
+        use super::*;
+
+        const SPEC: &str = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        tags:
+          - name: Trees
+          - name: Leaves
+            x-doctave:
+              order: 1
+          - name: Internal
+            x-doctave:
+              hide: true
+        paths:
+          /tree:
+            get:
+              summary: Get the tree
+              operationId: getTree
+              tags: [Trees]
+              responses:
+                '200':
+                  description: A tree
+            post:
+              summary: Plant a tree
+              operationId: plantTree
+              tags: [Trees]
+              x-doctave:
+                order: 1
+              responses:
+                '200':
+                  description: A tree
+            delete:
+              summary: Cut down the tree
+              operationId: cutDownTree
+              tags: [Trees]
+              x-doctave:
+                hide: true
+              responses:
+                '200':
+                  description: Nothing
+          /tree/leaves:
+            get:
+              summary: Get the leaves
+              operationId: getLeaves
+              tags: [Trees]
+              x-doctave:
+                group: Leaves
+                summary_override: List leaves
+              responses:
+                '200':
+                  description: Some leaves
+          /secrets:
+            get:
+              summary: Get the secrets
+              operationId: getSecrets
+              tags: [Internal]
+              responses:
+                '200':
+                  description: Secrets
+        "#};
+
+        fn project(spec: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: openapi.yaml
+                            uri_prefix: /api
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.yaml"),
+                    content: InputContent::Text(spec.to_string()),
+                },
+            ])
+            .unwrap()
+        }
+
+        /// Tag name and the operations on its page, in page order
+        fn tag_pages(project: &Project) -> Vec<(String, Vec<(String, Option<String>)>)> {
+            project
+                .pages()
+                .into_iter()
+                .filter_map(|p| match &p.page {
+                    PageKind::OpenApi(page) => page.tag().map(|tag| {
+                        (
+                            tag.to_string(),
+                            page.operations()
+                                .iter()
+                                .map(|op| {
+                                    (
+                                        format!("{} {}", op.method, op.route_pattern),
+                                        op.summary.clone(),
+                                    )
+                                })
+                                .collect(),
+                        )
+                    }),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        #[test]
+        fn applies_overrides() {
+            let project = project(SPEC);
+
+            assert_eq!(
+                tag_pages(&project),
+                vec![
+                    (
+                        "Leaves".to_string(),
+                        vec![(
+                            "get /tree/leaves".to_string(),
+                            Some("List leaves".to_string())
+                        )]
+                    ),
+                    (
+                        "Trees".to_string(),
+                        vec![
+                            ("post /tree".to_string(), Some("Plant a tree".to_string())),
+                            ("get /tree".to_string(), Some("Get the tree".to_string())),
+                        ]
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn hidden_tags_have_no_page() {
+            let project = project(SPEC);
+
+            assert!(project.get_page_by_uri_path("/api/internal").is_none());
+            assert!(project.get_page_by_uri_path("/api/trees").is_some());
+        }
+
+        #[test]
+        fn warns_about_unknown_groups() {
+            let project = project(&SPEC.replace("group: Leaves", "group: Leafs"));
+
+            let warnings = project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.code == Error::INVALID_OPENAPI_SPEC)
+                .collect::<Vec<_>>();
+
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(
+                warnings[0].message,
+                r#"Unknown tag "Leafs" in x-doctave.group"#
+            );
+            assert_eq!(warnings[0].file, Some(PathBuf::from("openapi.yaml")));
+            assert!(
+                warnings[0].description.contains("GET /tree/leaves"),
+                "{}",
+                warnings[0].description
+            );
+        }
+
+        #[test]
+        fn known_groups_do_not_warn() {
+            let project = project(SPEC);
+
+            assert!(!project
+                .warnings()
+                .iter()
+                .any(|w| w.code == Error::INVALID_OPENAPI_SPEC));
+        }
+    }
+
+    mod markdown_fragments {
+        use super::*;
+
+        fn project() -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("_components/note.md"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        attributes:
+                          - title: label
+                            required: true
+                        ---
+
+                        **{@label}**
+                        "#}
+                        .to_owned(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("_assets/diagram.png"),
+                    content: InputContent::Binary {
+                        signature: "1".to_string(),
+                        size_bytes: 100,
+                    },
+                },
+            ])
+            .unwrap()
+        }
+
+        #[test]
+        fn renders_custom_components() {
+            let project = project();
+
+            let root = project
+                .render_markdown_fragment(
+                    r#"<Component.Note label="Careful" />"#,
+                    Path::new("api/openapi.yaml"),
+                    None,
+                )
+                .unwrap();
+
+            let debug = root.debug_string().unwrap();
+            assert!(debug.contains("Careful"), "{}", debug);
+            assert!(!debug.contains("Component.Note"), "{}", debug);
+        }
+
+        #[test]
+        fn resolves_relative_images_and_links_from_the_base_path() {
+            let project = project();
+
+            let opts = RenderOptions {
+                prefix_asset_urls: Some("https://cdn.example.com".to_string()),
+                ..Default::default()
+            };
+
+            let root = project
+                .render_markdown_fragment(
+                    "![Diagram](../_assets/diagram.png) [Guide](./guide.md)",
+                    Path::new("api/openapi.yaml"),
+                    Some(&opts),
+                )
+                .unwrap();
+
+            let urls = root.children[0]
+                .children
+                .iter()
+                .filter_map(|node| match &node.kind {
+                    crate::NodeKind::Image { url, .. } | crate::NodeKind::Link { url, .. } => {
+                        Some(url.as_str())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                urls,
+                vec![
+                    "https://cdn.example.com/_assets/diagram.png",
+                    "/api/guide.md"
+                ]
+            );
+        }
+
+        #[test]
+        fn errors_point_to_the_base_path() {
+            let project = project();
+
+            let error = project
+                .render_markdown_fragment("<Component.Note />", Path::new("api/openapi.yaml"), None)
+                .unwrap_err();
+
+            assert_eq!(error.file, Some(PathBuf::from("api/openapi.yaml")));
+        }
     }
 
-    #[test]
-    fn v2_custom_components_are_not_pages() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! { r#"
-                    <Component.LandingPageCard heading="Foo">
-                        Foo bar
-                    </Component.LandingPageCard>
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("_components/landing-page-card.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    attributes:
-                      - title: heading
-                        required: true
-                    ---
+    mod openapi_operation_ids {
+        use super::*;
+
+        fn project(paths: &str) -> Project {
+            let spec = formatdoc! {r#"
+                openapi: 3.0.0
+                info:
+                  title: Users
+                  version: 1.0.0
+                paths:
+                {}
+                "#,
+                indent(paths, "  ")
+            };
 
-                    <div></BOB>
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: openapi.yaml
+                            uri_prefix: /api
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.yaml"),
+                    content: InputContent::Text(spec),
+                },
+            ])
+            .unwrap()
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        fn indent(s: &str, prefix: &str) -> String {
+            s.lines()
+                .map(|l| format!("{}{}", prefix, l))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
 
-        assert!(
-            project
-                .get_page_by_uri_path("/_components/landing-page-card")
-                .is_none(),
-            "Custom component parsed as regular page"
-        );
-    }
+        fn operation_ids(project: &Project) -> Vec<(String, String, bool)> {
+            OpenApi::operation_ids(&project.open_api_specs["openapi.yaml"].spec)
+                .into_iter()
+                .map(|o| (o.location, o.id, o.generated))
+                .collect()
+        }
 
-    #[test]
-    fn v2_slot_can_only_be_used_in_components() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! { r#"
-                    <Slot />
-                "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                title: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+        #[test]
+        fn reports_duplicate_operation_ids_with_both_locations() {
+            let project = project(indoc! {r#"
+            /users:
+              get:
+                operationId: getUser
+                responses:
+                  '200':
+                    description: OK
+            /users/{id}:
+              get:
+                operationId: getUser
+                responses:
+                  '200':
+                    description: OK
+            "#});
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].code, Error::INVALID_OPENAPI_SPEC);
+            assert_eq!(errors[0].message, r#"Duplicate operationId "getUser""#);
+            assert_eq!(
+                errors[0].description,
+                "The operationId is used by GET /users and GET /users/{id}. Each operation needs a unique operationId."
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from("openapi.yaml")));
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        #[test]
+        fn warns_about_missing_operation_ids_with_the_generated_id() {
+            let project = project(indoc! {r#"
+            /users/{id}:
+              get:
+                operationId: getUser
+                responses:
+                  '200':
+                    description: OK
+              delete:
+                responses:
+                  '200':
+                    description: OK
+            "#});
+
+            assert_eq!(project.verify(None, None), Ok(()));
+
+            let warnings = project.warnings();
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(
+                warnings[0].message,
+                "Missing operationId for DELETE /users/{id}"
+            );
+            assert!(
+                warnings[0].description.contains(r#""delete-users-id""#),
+                "{}",
+                warnings[0].description
+            );
+        }
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let error = page.ast(None).unwrap_err();
+        #[test]
+        fn generated_operation_ids_do_not_collide() {
+            let project = project(indoc! {r#"
+            /users/{id}:
+              get:
+                operationId: get-users-id
+                responses:
+                  '200':
+                    description: OK
+            /users/id:
+              get:
+                responses:
+                  '200':
+                    description: OK
+            /users/{id}/:
+              get:
+                responses:
+                  '200':
+                    description: OK
+            "#});
+
+            assert_eq!(
+                operation_ids(&project),
+                vec![
+                    (
+                        "GET /users/{id}".to_string(),
+                        "get-users-id".to_string(),
+                        false
+                    ),
+                    (
+                        "GET /users/id".to_string(),
+                        "get-users-id-2".to_string(),
+                        true
+                    ),
+                    (
+                        "GET /users/{id}/".to_string(),
+                        "get-users-id-3".to_string(),
+                        true
+                    ),
+                ]
+            );
 
-        assert_eq!(error.message, "Invalid slot");
-        assert_eq!(
-            error.description,
-            indoc! { r#"
-            `<Slot />` can only be used in components and topics
+            // The same spec always generates the same IDs
+            assert_eq!(operation_ids(&project), operation_ids(&project));
+        }
+    }
 
-                1 │ <Slot />
-                    ▲▲▲▲▲▲▲▲
-                    └─ Invalid slot
+    mod openapi_limits {
+        use super::*;
+
+        #[test]
+        fn fails_specs_with_more_operations_than_the_limit() {
+            let errors = Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: openapi.yaml
+                            uri_prefix: /api
+                            max_operations: 1
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.yaml"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        openapi: 3.0.0
+                        info:
+                          title: Trees
+                          version: 1.0.0
+                        paths:
+                          /tree:
+                            get:
+                              responses:
+                                '200':
+                                  description: A tree
+                            delete:
+                              responses:
+                                '200':
+                                  description: Nothing
+                        "#}
+                        .to_string(),
+                    ),
+                },
+            ])
+            .unwrap_err();
 
-        "#}
-        );
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].code, Error::INVALID_OPENAPI_SPEC);
+            assert_eq!(errors[0].message, "OpenAPI spec is too large");
+            assert_eq!(
+                errors[0].description,
+                "The spec has 2 operations, which is more than the limit of 1. Split it into several specs, or raise `max_operations` for the spec."
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from("openapi.yaml")));
+        }
     }
 
-    #[test]
-    fn v2_verify_returns_an_error_if_the_openapi_spec_contains_unknown_components_in_tag_description(
-    ) {
-        // BUG: DOC-1170
-        let files = vec![
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# Hi".to_string()),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.yaml"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    openapi: "3.0.0"
-                    info:
-                      version: 1.0.0
-                      title: Swagger Petstore
-                      license:
-                        name: MIT
-                    servers:
-                      - url: http://petstore.swagger.io/v1
-                    tags:
-                      - name: pets
-                        description: THIS IS BROKEN <Nope>Hi</Nope>
-                    paths:
-                      /pets:
-                        get:
-                          summary: List all pets
-                          operationId: listPets
-                          tags:
-                            - pets
-                          parameters:
-                            - name: limit
-                              in: query
-                              description: How many items to return at one time (max 100)
-                              required: false
-                              schema:
-                                type: integer
-                                maximum: 100
-                                format: int32
-                          responses:
-                            '200':
-                              description: A paged array of pets
-                              headers:
-                                x-next:
-                                  description: A link to the next page of responses
-                                  schema:
-                                    type: string
-                              content:
-                                application/json:
-                                  schema:
-                                    $ref: '#/components/schemas/Pets'
-                            default:
-                              description: unexpected error
-                              content:
-                                application/json:
-                                  schema:
-                                    $ref: '#/components/schemas/Error'
-                    components:
-                      schemas:
-                        Pet:
-                          type: object
-                          required:
-                            - id
-                            - name
-                          properties:
-                            id:
-                              type: integer
-                              format: int64
-                            name:
-                              type: string
-                            tag:
-                              type: string
-                        Pets:
-                          type: array
-                          maxItems: 100
-                          items:
-                            $ref: '#/components/schemas/Pet'
-                        Error:
-                          type: object
-                          required:
-                            - code
-                            - message
-                          properties:
-                            code:
-                              type: integer
-                              format: int32
-                            message:
-                              type: string
-                "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                ---
-                - heading: Something
-                "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
+    mod undefined_variables {
+        use super::*;
+
+        fn project() -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        # Hi
+
+                        <Component.Greeting name="Alice" />
+                        "#}
+                        .to_owned(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("_components/greeting.md"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        attributes:
+                          - title: name
+                            required: true
+                        ---
+
+                        Hello {@nmae}!
+                        "#}
+                        .to_owned(),
+                    ),
+                },
+            ])
+            .unwrap()
+        }
+
+        #[test]
+        fn fail_verification_by_default() {
+            let project = project();
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].code, Error::INVALID_EXPRESSION);
+            assert!(
+                errors[0]
+                    .description
+                    .contains("Variable `@nmae` not found. Did you mean `@name`?"),
+                "{}",
+                errors[0].description
+            );
+            assert!(project.expression_warnings(None).is_empty());
+        }
+
+        #[test]
+        fn warn_renders_null_and_reports_the_lookup() {
+            let project = project();
+            let opts = RenderOptions {
+                undefined_variables: UndefinedVariables::Warn,
+                ..Default::default()
+            };
+
+            assert!(project.verify(Some(&opts), None).is_ok());
+
+            let warnings = project.expression_warnings(Some(&opts));
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].code, Error::INVALID_EXPRESSION);
+            assert_eq!(warnings[0].message, "Undefined variable in expression");
+            assert_eq!(
+                warnings[0].file,
+                Some(PathBuf::from("_components/greeting.md"))
+            );
+            assert!(warnings[0].description.contains("Did you mean `@name`?"));
+        }
+
+        #[test]
+        fn ignore_renders_null_silently() {
+            let project = project();
+            let opts = RenderOptions {
+                undefined_variables: UndefinedVariables::Ignore,
+                ..Default::default()
+            };
+
+            assert!(project.verify(Some(&opts), None).is_ok());
+            assert!(project.expression_warnings(Some(&opts)).is_empty());
+
+            let page = project.get_page_by_uri_path("/").unwrap();
+            let ast = page.ast(Some(&opts)).unwrap();
+            let text = ast.as_markdown().unwrap().inner_text();
+            assert!(text.contains("Hello") && text.contains('!'), "{}", text);
+        }
+    }
+
+    mod html_policy {
+        use super::*;
+
+        fn project(html_policy: &str, assets: &[&str]) -> Project {
+            let mut files = vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(format!(
+                        "---\ntitle: An Project\nhtml_policy: {}\n",
+                        html_policy
+                    )),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        # Hi
+
+                        <button onclick="steal()">Click me</button>
+
+                        <a download href="/_assets/guide.pdf">Download</a>
+                        "#}
+                        .to_owned(),
+                    ),
+                },
+            ];
+
+            for asset in assets {
+                files.push(InputFile {
+                    path: PathBuf::from(asset),
+                    content: InputContent::Binary {
+                        signature: String::new(),
+                        size_bytes: 0,
+                    },
+                });
+            }
+
+            Project::from_file_list(files).unwrap()
+        }
+
+        #[test]
+        fn sanitize_reports_removed_html_with_its_location() {
+            let project = project("sanitize", &["_assets/guide.pdf"]);
+
+            assert!(project.verify(None, None).is_ok());
+
+            let warnings = project.html_warnings(None);
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].code, Error::UNSAFE_HTML);
+            assert_eq!(warnings[0].file, Some(PathBuf::from("README.md")));
+            assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 3);
+            assert!(warnings[0]
+                .description
+                .contains("Removed `onclick` attribute from `<button>`"));
+        }
+
+        #[test]
+        fn sanitize_keeps_verifying_download_links() {
+            let errors = project("sanitize", &[]).verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].message, "Broken asset link detected");
+            assert!(errors[0].description.contains("/_assets/guide.pdf"));
+        }
+
+        #[test]
+        fn other_policies_do_not_warn() {
+            assert!(project("allow", &["_assets/guide.pdf"])
+                .html_warnings(None)
+                .is_empty());
+            assert!(project("strip", &["_assets/guide.pdf"])
+                .html_warnings(None)
+                .is_empty());
+        }
+    }
+
+    mod footnotes {
+        use super::*;
+
+        #[test]
+        fn warns_about_undefined_footnotes_with_their_location() {
+            let project = Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(
+                        "---\ntitle: An Project\nhtml_policy: allow\n".to_owned(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        # Hi
+
+                        See [^missing] and [^note].
+
+                        [^note]: Defined
+                        "#}
+                        .to_owned(),
+                    ),
+                },
+            ])
+            .unwrap();
+
+            assert!(project.verify(None, None).is_ok());
+
+            let warnings = project.render_warnings(None);
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].code, Error::BROKEN_INTERNAL_LINK);
+            assert_eq!(warnings[0].message, "Undefined footnote");
+            assert_eq!(warnings[0].file, Some(PathBuf::from("README.md")));
+
+            let position = warnings[0].position.as_ref().unwrap();
+            assert_eq!(position.start.row, 3);
+            assert_eq!(position.start.col, 5);
+            assert!(warnings[0]
+                .description
+                .contains("Footnote `[^missing]` is referenced but never defined"));
+        }
+    }
+
+    mod inline_svgs {
+        use crate::{inline_svg::MAX_INLINE_SVG_BYTES, markdown::Node, NodeKind};
+
+        use super::*;
+
+        fn project(readme: &str, svg: InputContent) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(readme.to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("_assets/arch.svg"),
+                    content: svg,
+                },
+            ])
+            .unwrap()
+        }
+
+        fn svg(body: &str) -> InputContent {
+            InputContent::Text(format!(
+                "<svg viewBox=\"0 0 10 10\"><title>Architecture</title>{}</svg>",
+                body
+            ))
+        }
+
+        fn nodes(project: &Project) -> Vec<Node> {
+            let Ast::Markdown(root) = project
+                .get_page_by_uri_path("/")
+                .unwrap()
+                .ast(None)
+                .unwrap()
+            else {
+                panic!("Expected a Markdown page");
+            };
+
+            root.walk().cloned().collect()
+        }
+
+        #[test]
+        fn inlines_sanitized_svgs() {
+            let project = project(
+                "# Hi\n\n![Architecture](/_assets/arch.svg?inline)\n",
+                svg(r#"<script>alert(1)</script><a href="https://example.com"><rect/></a>"#),
+            );
+
+            let html = nodes(&project)
+                .into_iter()
+                .find_map(|node| match node.kind {
+                    NodeKind::InlineSvg { src, alt, html } => {
+                        assert_eq!(src, "/_assets/arch.svg");
+                        assert_eq!(alt, "Architecture");
+                        Some(html)
+                    }
+                    _ => None,
+                })
+                .expect("SVG was not inlined");
+
+            assert_eq!(
+                html,
+                r#"<svg viewBox="0 0 10 10"><title>Architecture</title><a><rect/></a></svg>"#
+            );
+            assert!(project.render_warnings(None).is_empty());
+        }
+
+        #[test]
+        fn inlines_svgs_from_the_image_component() {
+            let project = project(
+                r#"<Image src="/_assets/arch.svg" alt="Architecture" inline={true} />"#,
+                svg("<rect/>"),
+            );
+
+            assert!(nodes(&project)
+                .iter()
+                .any(|node| matches!(node.kind, NodeKind::InlineSvg { .. })));
+        }
+
+        #[test]
+        fn shows_large_svgs_as_images_with_a_warning() {
+            let project = project(
+                "# Hi\n\n![Architecture](/_assets/arch.svg?inline)\n",
+                svg(&"<rect/>".repeat(MAX_INLINE_SVG_BYTES / 7 + 1)),
+            );
+
+            let nodes = nodes(&project);
+            assert!(!nodes
+                .iter()
+                .any(|node| matches!(node.kind, NodeKind::InlineSvg { .. })));
+            assert!(nodes.iter().any(|node| matches!(
+                &node.kind,
+                NodeKind::Image { url, .. } if url == "/_assets/arch.svg"
+            )));
+
+            let warnings = project.render_warnings(None);
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].code, Error::INVALID_INLINE_SVG);
+            assert_eq!(warnings[0].file, Some(PathBuf::from("README.md")));
+            assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 3);
+            assert!(warnings[0]
+                .description
+                .contains("over the 64.0 KB limit for inlining"));
+        }
+
+        #[test]
+        fn warns_about_svgs_given_as_binary() {
+            let project = project(
+                "![Architecture](/_assets/arch.svg?inline)",
+                InputContent::Binary {
+                    signature: String::new(),
+                    size_bytes: 0,
+                },
+            );
+
+            let warnings = project.render_warnings(None);
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert!(warnings[0]
+                .description
+                .contains("The contents of the SVG weren't available to inline."));
+        }
+    }
+
+    mod deprecated_operations {
+        use super::*;
+
+        const SPEC: &str = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        paths:
+          /trees:
+            get:
+              summary: List trees
+              tags: [trees]
+              deprecated: true
+              x-retired-on: "2025-06-30"
+              responses:
+                "200":
+                  description: OK
+            post:
+              summary: Plant a tree
+              tags: [trees]
+              responses:
+                "200":
+                  description: OK
+        "#};
+
+        fn project(settings: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(settings.to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(
+                        "[List](/api/trees#list-trees) [Plant](/api/trees#plant-a-tree)".to_owned(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.yaml"),
+                    content: InputContent::Text(SPEC.to_owned()),
+                },
+            ])
+            .unwrap()
+        }
+
+        #[test]
+        fn warns_about_links_to_deprecated_operations() {
+            let project = project(indoc! {r#"
+            ---
+            title: Trees
+            open_api:
+              - spec_file: openapi.yaml
+                uri_prefix: /api
+                sunset_header_field: x-retired-on
+                warn_deprecated_links: true
+            "#});
+
+            let warnings = project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.code == Error::DEPRECATED_OPERATION_LINK)
+                .collect::<Vec<_>>();
+
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].file, Some(PathBuf::from("README.md")));
+            assert_eq!(
+                warnings[0].description,
+                "Link /api/trees#list-trees points to the GET /trees operation, which is deprecated and will be removed on 2025-06-30."
+            );
+        }
+
+        #[test]
+        fn links_to_deprecated_operations_are_fine_by_default() {
+            let project = project(indoc! {r#"
+            ---
+            title: Trees
+            open_api:
+              - spec_file: openapi.yaml
+                uri_prefix: /api
+            "#});
+
+            assert!(!project
+                .warnings()
+                .iter()
+                .any(|w| w.code == Error::DEPRECATED_OPERATION_LINK));
+        }
+    }
+
+    mod operation_uris {
+        use super::*;
+        use crate::test_support;
+
+        const SPEC: &str = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Pets
+          version: 1.0.0
+        paths:
+          /v2/pets:
+            get:
+              operationId: listPets
+              summary: List pets
+              tags: [pets]
+              responses: { "200": { description: OK } }
+          /v2/pets/{id}/status:
+            get:
+              operationId: pet status
+              summary: Get status
+              tags: [pets]
+              responses: { "200": { description: OK } }
+            put:
+              operationId: pet-status
+              summary: Set status
+              tags: [pets]
+              responses: { "200": { description: OK } }
+        "#};
+
+        fn project(uri_strategy: &str) -> Project {
+            let settings = format!(
+                "---\ntitle: Pets\nopen_api:\n  - spec_file: openapi.yaml\n    uri_prefix: /api\n    uri_strategy: {}\n",
+                uri_strategy
+            );
+
+            test_support::project(&[
+                (SETTINGS_FILE_NAME, &settings),
+                ("README.md", "# Pets"),
+                ("openapi.yaml", SPEC),
+            ])
+        }
+
+        fn uris(uris: Vec<OperationUri>) -> Vec<(String, Option<String>)> {
+            let mut uris = uris
+                .into_iter()
+                .map(|uri| (uri.uri, uri.previous_uri))
+                .collect::<Vec<_>>();
+            uris.sort();
+            uris
+        }
+
+        #[test]
+        fn derives_anchors_from_operation_ids() {
+            let project = project("operation_id");
+
+            assert_eq!(
+                uris(project.operation_uris(None)),
+                vec![
+                    (
+                        "/api/pets#listPets".to_owned(),
+                        Some("/api/pets#list-pets".to_owned())
+                    ),
+                    (
+                        "/api/pets#pet-status".to_owned(),
+                        Some("/api/pets#get-status".to_owned())
+                    ),
+                    (
+                        "/api/pets#pet-status-2".to_owned(),
+                        Some("/api/pets#set-status".to_owned())
+                    ),
+                ]
+            );
+        }
+
+        #[test]
+        fn plans_the_switch_to_operation_ids() {
+            let project = project("summary");
+
+            assert!(project
+                .operation_uris(None)
+                .iter()
+                .all(|uri| uri.previous_uri.is_none()));
+            assert_eq!(project.operation_uris(None)[0].uri, "/api/pets#list-pets");
+            assert_eq!(project.operation_uris(None)[0].location, "GET /v2/pets");
+
+            assert_eq!(
+                uris(project.operation_uris(Some(UriStrategy::OperationId))),
+                uris(self::project("operation_id").operation_uris(None))
+            );
+        }
+
+        #[test]
+        fn drops_previous_anchors_that_another_operation_now_uses() {
+            let settings = "---\ntitle: Pets\nopen_api:\n  - spec_file: openapi.yaml\n    uri_prefix: /api\n    uri_strategy: operation_id\n";
+            let spec = indoc! {r#"
+            openapi: 3.0.0
+            info:
+              title: Pets
+              version: 1.0.0
+            paths:
+              /v2/pets:
+                get:
+                  operationId: listAll
+                  summary: List pets
+                  tags: [pets]
+                  responses: { "200": { description: OK } }
+                post:
+                  operationId: list-pets
+                  summary: Fetch pets
+                  tags: [pets]
+                  responses: { "200": { description: OK } }
+            "#};
+
+            let project = test_support::project(&[
+                (SETTINGS_FILE_NAME, settings),
+                ("README.md", "# Pets"),
+                ("openapi.yaml", spec),
+            ]);
+
+            assert_eq!(
+                uris(project.operation_uris(None)),
+                vec![
+                    (
+                        "/api/pets#list-pets".to_owned(),
+                        Some("/api/pets#fetch-pets".to_owned())
+                    ),
+                    ("/api/pets#listAll".to_owned(), None),
+                ]
+            );
+        }
+    }
+
+    mod default_responses {
+        use super::*;
+
+        const SPEC: &str = indoc! {r#"
+        openapi: 3.0.0
+        info: { title: Pets, version: 1.0.0 }
+        paths:
+          /pets:
+            get:
+              summary: List pets
+              tags: [pets]
+              responses:
+                "200": { description: OK }
+                "404": { description: No pets here }
+        components:
+          schemas:
+            Error:
+              type: object
+              required: [message]
+              properties:
+                message: { type: string }
+        "#};
+
+        fn project(default_responses: &str) -> Project {
+            let settings = formatdoc! {r#"
                 ---
-                title: Something
+                title: Pets
                 open_api:
                   - spec_file: openapi.yaml
                     uri_prefix: /api
-                "#}
-                    .to_string(),
-                ),
-            },
-        ];
+                    default_responses:
+                {}
+                "#,
+                default_responses
+                    .lines()
+                    .map(|line| format!("      {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
 
-        let project = &Project::from_file_list(files).unwrap();
+            test_support::project(&[
+                (SETTINGS_FILE_NAME, &settings),
+                ("README.md", "# Pets"),
+                ("openapi.yaml", SPEC),
+            ])
+        }
 
-        assert!(
-            project
-                .get_page_by_uri_path("/api/pets")
-                .unwrap()
-                .ast(None)
-                .is_err(),
-            "Did not get error on broken OpenAPI spec"
-        );
+        fn responses(project: &Project) -> serde_json::Value {
+            let snapshot = test_support::page_snapshot(project, "/api/pets");
 
-        let errors = project.verify(None, None).unwrap_err();
+            snapshot["page"]["ast"]["root"]["operations"][0]["responses"].clone()
+        }
 
-        assert_eq!(
-            errors[0].description,
-            indoc! { r#"
-            Unknown element "Nope"
+        #[test]
+        fn adds_default_responses_the_operation_does_not_document() {
+            let project = project(indoc! {r##"
+            404:
+              description: Not found
+            429:
+              description: Too many requests
+              schema: "#/components/schemas/Error"
+              example: { message: Slow down }
+              headers:
+                Retry-After:
+                  description: Seconds to wait
+                  example: "30"
+            "##});
+
+            let responses = responses(&project);
+            let responses = responses.as_array().unwrap();
+
+            assert_eq!(
+                responses
+                    .iter()
+                    .map(|r| (
+                        r["code"].as_str().unwrap(),
+                        r["api_default"].as_bool().unwrap()
+                    ))
+                    .collect::<Vec<_>>(),
+                vec![("200", false), ("404", false), ("429", true)]
+            );
 
-                1 │ THIS IS BROKEN <Nope>Hi</Nope>
-                                    ▲▲▲▲
+            let too_many = &responses[2];
+            assert_eq!(too_many["headers"][0]["name"], "Retry-After");
+            assert_eq!(
+                serde_json::from_str::<serde_json::Value>(
+                    too_many["media_types"][0]["examples"][0]["value"]
+                        .as_str()
+                        .unwrap()
+                )
+                .unwrap(),
+                serde_json::json!({ "message": "Slow down" })
+            );
+            assert!(project.verify(None, None).is_ok());
+        }
 
-        "#}
-        );
+        #[test]
+        fn verify_reports_unknown_schemas_and_invalid_examples() {
+            let project = project(indoc! {r##"
+            401:
+              description: Unauthorized
+              schema: "#/components/schemas/Eror"
+            429:
+              description: Too many requests
+              schema: "#/components/schemas/Error"
+              example: { reason: Slow down }
+            "##});
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 2, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                r##"Unknown schema "#/components/schemas/Eror" in default response 401"##
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from(SETTINGS_FILE_NAME)));
+            assert_eq!(errors[0].position.as_ref().unwrap().start.row, 9);
+            assert_eq!(errors[1].message, "Example does not match its schema");
+            assert!(errors[1]
+                .description
+                .starts_with("Missing required property `message`"));
+            assert_eq!(errors[1].position.as_ref().unwrap().start.row, 13);
+        }
     }
 
-    #[test]
-    fn checks_features_dont_exist() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    - heading: Something
-                    "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
-                    ---
-                    title: An Project
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("Hello".to_string()),
-            },
-        ];
+    mod templates {
+        use super::*;
+
+        fn project(settings: &str, readme: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(settings.to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(readme.to_owned()),
+                },
+            ])
+            .unwrap()
+        }
 
-        let project = Project::from_file_list(files).unwrap();
-        let expected: Vec<String> = vec![];
-        assert_eq!(project.check_features(), expected);
-    }
+        #[test]
+        fn errors_on_unknown_templates_in_frontmatter() {
+            let project = project(
+                indoc! {r#"
+                ---
+                title: Templates
+                templates:
+                  allowed: [landing, guide]
+                "#},
+                "---\ntemplate: landng\n---\n\n# Home",
+            );
 
-    #[test]
-    fn checks_features_custom_css_exists() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    - heading: Something
-                    "#}
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
-                    ---
-                    title: An Project
-                    styles:
-                      - _assets/custom.css
+            let errors = project.verify(None, None).unwrap_err();
 
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("Hello".to_string()),
-            },
-        ];
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].code, Error::INVALID_FRONTMATTER);
+            assert_eq!(errors[0].file, Some(PathBuf::from("README.md")));
+            assert_eq!(
+                errors[0].description,
+                "Expected one of: landing, guide.\nFound \"landng\"."
+            );
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        #[test]
+        fn templates_have_to_be_declared() {
+            let project = project(
+                "---\ntitle: Templates",
+                "---\ntemplate: landing\n---\n\n# Home",
+            );
 
-        assert!(project.check_features().contains(&"custom_css".to_string()))
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].code, Error::INVALID_FRONTMATTER);
+        }
+
+        #[test]
+        fn errors_on_unknown_templates_in_settings() {
+            let project = project(
+                indoc! {r#"
+                ---
+                title: Templates
+                templates:
+                  allowed: [guide]
+                  default: landing
+                  rules:
+                    - path: changelog/**
+                      template: changelog
+                "#},
+                "# Home",
+            );
+
+            let errors = project.verify(None, None).unwrap_err();
+            let messages = errors
+                .iter()
+                .map(|e| e.message.as_str())
+                .collect::<Vec<_>>();
+
+            assert_eq!(
+                messages,
+                vec![
+                    "Unknown template in `templates.default`",
+                    "Unknown template in `templates.rules`",
+                ]
+            );
+            assert!(errors.iter().all(|e| e.code == Error::INVALID_DOCTAVE_YAML));
+        }
     }
 
-    #[test]
-    fn download_link_works_with_openapi_non_assets_folder() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    open_api:
-                      - spec_file: openapi.json
-                        uri_prefix: /api
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <Link download href="openapi.json">Download</Link>
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
+    mod drafts {
+        use super::*;
+        use crate::page_handle::DraftStatus;
+
+        fn files(settings: &str) -> Vec<InputFile> {
+            vec![
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(settings.to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        - heading: Guides
+                          items:
+                            - href: /guide.md
+                            - href: /wip.md
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Welcome".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("guide.md"),
+                    content: InputContent::Text("# Guide".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("wip.md"),
+                    content: InputContent::Text(
+                        "---\ndraft: true\n---\n# Work in progress".to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("internal/notes.md"),
+                    content: InputContent::Text("# Internal notes".to_string()),
+                },
+            ]
+        }
+
+        fn project() -> Project {
+            Project::from_file_list(files(indoc! {r#"
+            ---
+            title: Drafts
+            exclude: ["internal/**"]
+            "#}))
+            .unwrap()
+        }
+
+        #[test]
+        fn hides_drafts_and_excluded_pages() {
+            let project = project();
+
+            assert!(project.get_page_by_uri_path("/guide").is_some());
+            assert!(project.get_page_by_uri_path("/wip").is_none());
+            assert!(project.get_page_by_uri_path("/internal/notes").is_none());
+
+            let published = project
+                .published_pages(None)
+                .iter()
+                .map(|p| p.uri_path().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(published, vec!["/", "/guide"]);
+
+            let index = project.search_index().unwrap().to_json();
+            assert!(index.contains("Guide"));
+            assert!(!index.contains("Work in progress"));
+            assert!(!index.contains("Internal notes"));
+        }
+
+        #[test]
+        fn publishes_drafts_when_asked() {
+            let project = project();
+            let opts = RenderOptions {
+                publish_drafts: true,
+                ..Default::default()
+            };
+
+            let page = project
+                .get_localized_page_by_uri_path("/wip", Some(&opts))
+                .unwrap();
+            assert!(matches!(page.draft_status(), Some(DraftStatus::Draft)));
+
+            let page = project
+                .get_localized_page_by_uri_path("/internal/notes", Some(&opts))
+                .unwrap();
+            assert_eq!(
+                page.draft_status(),
+                Some(DraftStatus::Excluded("internal/**".to_string()))
+            );
+
+            assert!(project
+                .get_localized_page_by_uri_path("/wip", None)
+                .is_none());
+        }
+
+        #[test]
+        fn leaves_drafts_out_of_the_navigation() {
+            let project = project();
+
+            let hrefs = |opts: &RenderOptions| {
+                project.navigation(Some(opts), "/").unwrap().sections[0]
+                    .items
+                    .iter()
+                    .map(|item| item.href().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(hrefs(&RenderOptions::default()), vec!["/guide"]);
+            assert_eq!(
+                hrefs(&RenderOptions {
+                    publish_drafts: true,
+                    ..Default::default()
+                }),
+                vec!["/guide", "/wip"]
+            );
+        }
+
+        #[test]
+        fn reports_links_from_published_pages_to_drafts() {
+            let mut files = files("---\ntitle: Drafts\nexclude: [\"internal/**\"]\n");
+            files.push(InputFile {
+                path: PathBuf::from("links.md"),
                 content: InputContent::Text(
-                    indoc! {r#"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        }
-                      }
-                "#}
-                    .to_string(),
+                    "[WIP](/wip.md) [Notes](/internal/notes.md)".to_string(),
                 ),
-            },
-        ];
+            });
+            files.push(InputFile {
+                path: PathBuf::from("internal/more.md"),
+                content: InputContent::Text("[WIP](/wip.md)".to_string()),
+            });
 
-        let project = Project::from_file_list(files).unwrap();
+            let project = Project::from_file_list(files).unwrap();
+            let errors = project.verify(None, None).unwrap_err();
 
-        assert!(project
-            .verify(Some(&RenderOptions::default()), None)
-            .is_ok());
+            assert_eq!(errors.len(), 2, "{:#?}", errors);
+            assert!(errors.iter().all(|e| e.code == Error::BROKEN_INTERNAL_LINK
+                && e.file == Some(PathBuf::from("links.md"))));
+            assert_eq!(
+                errors[0].description,
+                "Link /wip.md points to a page that is a draft (`draft: true` in its frontmatter)."
+            );
+            assert_eq!(
+                errors[1].description,
+                "Link /internal/notes.md points to a page that is excluded by the pattern \"internal/**\" in docapella.yaml."
+            );
+        }
     }
 
-    #[test]
-    fn parses_openapi_schema_node() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    open_api:
-                      - spec_file: openapi.json
-                        uri_prefix: /api
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <OpenAPISchema title="User" openapi_path="openapi.json" />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text(
-                    indoc! {r##"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        },
-                        "components": {
-                          "schemas": {
-                              "User": {
-                                  "title": "User schema",
-                                  "description": "# Hello, this is a title",
-                                  "type": "object",
-                                  "properties": {
-                                      "id": {
-                                          "type": "integer",
-                                          "format": "int64"
-                                      },
-                                      "username": {
-                                          "type": "string"
-                                      }
-                                  }
-                              }
-                          }
-                        }
-                      }
-                "##}
-                    .to_string(),
-                ),
-            },
-        ];
+    mod duplicate_uri_paths {
+        use super::*;
+
+        fn project(files: &[(&str, &str)]) -> Project {
+            let mut list = vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: openapi.yaml
+                            uri_prefix: /api
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("openapi.yaml"),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        openapi: 3.0.0
+                        info:
+                          title: Trees
+                          version: 1.0.0
+                        paths:
+                          /tree:
+                            get:
+                              summary: Get the tree
+                              tags: [Trees]
+                              responses:
+                                '200':
+                                  description: A tree
+                        "#}
+                        .to_string(),
+                    ),
+                },
+            ];
+
+            for (path, content) in files {
+                list.push(InputFile {
+                    path: PathBuf::from(path),
+                    content: InputContent::Text(content.to_string()),
+                });
+            }
 
-        let project = Project::from_file_list(files).unwrap();
+            Project::from_file_list(list).unwrap()
+        }
 
-        let opts = RenderOptions::default();
+        fn duplicate_errors(project: &Project) -> Vec<Error> {
+            project
+                .verify(None, None)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.code == Error::DUPLICATE_URI_PATH)
+                .collect()
+        }
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let ast = page.ast(Some(&opts)).unwrap();
+        #[test]
+        fn detects_a_page_and_a_readme_at_the_same_uri_path() {
+            let project = project(&[("Foo.md", "# File"), ("foo/README.md", "# Folder")]);
 
-        if let Ast::Markdown(root) = ast {
-            assert_str_eq!(
-                root.debug_string().unwrap(),
-                indoc! { r#"
-                <OpenAPISchema title={User schema} expanded={true}>
-                    <Heading1>
-                        <Text>
-                            Hello, this is a title
-                        </Text>
-                    </Heading1>
-                </OpenAPISchema>
-                "#}
-                .to_string()
+            let errors = duplicate_errors(&project);
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                r#"Duplicate URI path "/foo" for "Foo.md", "foo/README.md""#
             );
-        } else {
-            panic!("Expected markdown AST");
+            assert_eq!(errors[0].file, Some(PathBuf::from("foo/README.md")));
+            assert!(
+                errors[0]
+                    .description
+                    .contains(r#""Foo.md" takes precedence"#),
+                "{}",
+                errors[0].description
+            );
+        }
+
+        #[test]
+        fn detects_pages_differing_only_by_case() {
+            let project = project(&[("bar.md", "# Lower"), ("Bar.md", "# Upper")]);
+
+            let errors = duplicate_errors(&project);
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                r#"Duplicate URI path "/bar" for "Bar.md", "bar.md""#
+            );
+        }
+
+        #[test]
+        fn serves_the_same_page_regardless_of_input_order() {
+            let first = project(&[("bar.md", "# Lower"), ("Bar.md", "# Upper")]);
+            let second = project(&[("Bar.md", "# Upper"), ("bar.md", "# Lower")]);
+
+            for project in [first, second] {
+                let page = project.get_page_by_uri_path("/bar").unwrap();
+                assert_eq!(page.fs_path(), Path::new("Bar.md"));
+            }
+        }
+
+        #[test]
+        fn detects_markdown_pages_shadowed_by_openapi_pages() {
+            let project = project(&[("api/trees.md", "# Trees")]);
+
+            let errors = duplicate_errors(&project);
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                r#"Duplicate URI path "/api/trees" for "openapi.yaml" (OpenAPI tag "Trees"), "api/trees.md""#
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from("api/trees.md")));
+
+            let page = project.get_page_by_uri_path("/api/trees").unwrap();
+            assert_eq!(page.fs_path(), Path::new("openapi.yaml"));
+        }
+
+        #[test]
+        fn unique_uri_paths_are_fine() {
+            let project = project(&[("foo.md", "# Foo"), ("foo/bar.md", "# Bar")]);
+
+            assert!(duplicate_errors(&project).is_empty());
         }
     }
 
-    #[test]
-    fn openapi_schemas_can_be_toggled_to_not_expanded() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    open_api:
-                      - spec_file: openapi.json
-                        uri_prefix: /api
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <OpenAPISchema title="User" openapi_path="openapi.json" expanded={false} />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text(
-                    indoc! {r##"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        },
-                        "components": {
-                          "schemas": {
-                              "User": {
-                                  "title": "User schema",
-                                  "description": "# Hello, this is a title",
-                                  "type": "object",
-                                  "properties": {
-                                      "id": {
-                                          "type": "integer",
-                                          "format": "int64"
-                                      },
-                                      "username": {
-                                          "type": "string"
-                                      }
-                                  }
-                              }
-                          }
-                        }
-                      }
-                "##}
-                    .to_string(),
-                ),
-            },
-        ];
+    mod open_api_prefixes {
+        use super::*;
+
+        fn spec(title: &str) -> String {
+            format!(
+                indoc! {r#"
+                openapi: 3.0.0
+                info:
+                  title: {}
+                  version: 1.0.0
+                paths:
+                  /tree:
+                    get:
+                      summary: Get the tree
+                      tags: [Trees]
+                      responses:
+                        '200':
+                          description: A tree
+                "#},
+                title
+            )
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        fn project(first_prefix: &str, second_prefix: &str, files: &[(&str, &str)]) -> Project {
+            let mut list = vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(format!(
+                        indoc! {r#"
+                        ---
+                        title: An Project
+                        open_api:
+                          - spec_file: first.yaml
+                            uri_prefix: {}
+                          - spec_file: second.yaml
+                            uri_prefix: {}
+                        "#},
+                        first_prefix, second_prefix
+                    )),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("first.yaml"),
+                    content: InputContent::Text(spec("First")),
+                },
+                InputFile {
+                    path: PathBuf::from("second.yaml"),
+                    content: InputContent::Text(spec("Second")),
+                },
+            ];
+
+            for (path, content) in files {
+                list.push(InputFile {
+                    path: PathBuf::from(path),
+                    content: InputContent::Text(content.to_string()),
+                });
+            }
 
-        let opts = RenderOptions::default();
+            Project::from_file_list(list).unwrap()
+        }
+
+        fn settings_errors(project: &Project) -> Vec<Error> {
+            project
+                .verify(None, None)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.code == Error::INVALID_DOCTAVE_YAML)
+                .collect()
+        }
+
+        #[test]
+        fn refuses_duplicate_prefixes() {
+            let project = project("/api", "api/", &[]);
+
+            let errors = settings_errors(&project);
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].message, r#"Duplicate OpenAPI URI prefix "/api""#);
+            assert!(
+                errors[0]
+                    .description
+                    .contains(r#"The OpenAPI specs "first.yaml" and "second.yaml" both use the uri_prefix "/api""#),
+                "{}",
+                errors[0].description
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from(SETTINGS_FILE_NAME)));
+            assert_eq!(errors[0].position.as_ref().unwrap().start.row, 7);
+
+            assert!(project.get_page_by_uri_path("/api").is_none());
+            assert!(project.get_page_by_uri_path("/api/trees").is_none());
+        }
+
+        #[test]
+        fn refuses_a_root_prefix() {
+            let project = project("/", "/api", &[]);
+
+            let errors = settings_errors(&project);
+
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                "OpenAPI URI prefix should contain a path."
+            );
+            assert_eq!(errors[0].position.as_ref().unwrap().start.row, 5);
+        }
+
+        #[test]
+        fn lists_markdown_pages_colliding_with_the_specs_pages() {
+            let project = project(
+                "/api",
+                "/other",
+                &[
+                    ("api/README.md", "# API"),
+                    ("api/trees.md", "# Trees"),
+                    ("api/guide.md", "# Guide"),
+                ],
+            );
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let ast = page.ast(Some(&opts)).unwrap();
+            let errors = settings_errors(&project);
 
-        if let Ast::Markdown(root) = ast {
-            assert_str_eq!(
-                root.debug_string().unwrap(),
-                indoc! { r#"
-                <OpenAPISchema title={User schema} expanded={false}>
-                    <Heading1>
-                        <Text>
-                            Hello, this is a title
-                        </Text>
-                    </Heading1>
-                </OpenAPISchema>
-                "#}
-                .to_string()
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                r#"OpenAPI URI prefix "/api" collides with Markdown pages"#
             );
-        } else {
-            panic!("Expected markdown AST");
+            assert!(
+                errors[0]
+                    .description
+                    .contains("* api/README.md (/api)\n* api/trees.md (/api/trees)\n\n"),
+                "{}",
+                errors[0].description
+            );
+            assert!(!errors[0].description.contains("api/guide.md"));
+            assert_eq!(errors[0].position.as_ref().unwrap().start.row, 5);
         }
-    }
 
-    #[test]
-    fn openapi_schema_node_error_when_schema_not_found() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    open_api:
-                      - spec_file: openapi.json
-                        uri_prefix: /api
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <OpenAPISchema title="Foo" openapi_path="openapi.json" />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text(
-                    indoc! {r##"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        },
-                        "components": {
-                          "schemas": {
-                              "User": {
-                                  "title": "User schema",
-                                  "description": "# Hello, this is a title",
-                                  "type": "object",
-                                  "properties": {
-                                      "id": {
-                                          "type": "integer",
-                                          "format": "int64"
-                                      },
-                                      "username": {
-                                          "type": "string"
-                                      }
-                                  }
-                              }
-                          }
-                        }
-                      }
-                "##}
-                    .to_string(),
-                ),
-            },
-        ];
+        #[test]
+        fn warns_about_nested_prefixes() {
+            let project = project("/api", "/api/v2", &[]);
 
-        let project = Project::from_file_list(files).unwrap();
-        let opts = RenderOptions::default();
+            assert!(settings_errors(&project).is_empty());
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let error = page.ast(Some(&opts)).unwrap_err();
+            let warnings = project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.code == Error::INVALID_DOCTAVE_YAML)
+                .collect::<Vec<_>>();
 
-        assert_str_eq!(
-            error.description,
-            indoc! { r#"
-            OpenAPI schema with title "Foo" not found
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(
+                warnings[0].message,
+                r#"OpenAPI URI prefix "/api/v2" is nested under "/api""#
+            );
+            assert!(
+                warnings[0]
+                    .description
+                    .contains(r#"for example a tag named "v2""#),
+                "{}",
+                warnings[0].description
+            );
+            assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 7);
 
-                1 │ <OpenAPISchema title="Foo" openapi_path="openapi.json" />
-                                          ▲▲▲
+            assert!(project.get_page_by_uri_path("/api/trees").is_some());
+            assert!(project.get_page_by_uri_path("/api/v2/trees").is_some());
+        }
 
-            "# }
-            .to_string()
-        );
+        #[test]
+        fn sibling_prefixes_are_fine() {
+            let project = project("/api/v1", "/api/v2", &[("api/guide.md", "# Guide")]);
+
+            assert!(settings_errors(&project).is_empty());
+            assert!(project
+                .warnings()
+                .iter()
+                .all(|w| w.code != Error::INVALID_DOCTAVE_YAML));
+        }
     }
 
-    #[test]
-    fn openapi_schema_node_error_when_openapi_not_found() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <OpenAPISchema title="User" openapi_path="openapi.json" />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+    mod shadowed_redirects {
+        use super::*;
+
+        fn project(redirects: &str) -> Project {
+            let spec = indoc! {r#"
+            openapi: 3.0.0
+            info:
+              title: Trees
+              version: 1.0.0
+            paths:
+              /tree:
+                get:
+                  summary: Get the tree
+                  tags: [Trees]
+                  responses:
+                    '200':
+                      description: A tree
+            "#};
+
+            let files = [
+                (NAVIGATION_FILE_NAME, "---".to_owned()),
+                (
+                    SETTINGS_FILE_NAME,
+                    format!(
+                        "---\ntitle: Redirects\nopen_api:\n  - spec_file: openapi.yaml\n    uri_prefix: /api\nredirects:\n{}",
+                        redirects
+                    ),
+                ),
+                ("README.md", "# Hi".to_owned()),
+                ("guides/setup.md", "# Setup".to_owned()),
+                ("guides/old/install.md", "# Install".to_owned()),
+                ("openapi.yaml", spec.to_owned()),
+            ];
+
+            Project::from_file_list(
+                files
+                    .into_iter()
+                    .map(|(path, content)| InputFile {
+                        path: PathBuf::from(path),
+                        content: InputContent::Text(content),
+                    })
+                    .collect(),
+            )
+            .unwrap()
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        fn redirect_errors(project: &Project) -> Vec<Error> {
+            project
+                .verify(None, None)
+                .err()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.code == Error::INVALID_REDIRECT)
+                .collect()
+        }
 
-        let opts = RenderOptions::default();
+        #[test]
+        fn reports_redirects_from_markdown_and_openapi_pages() {
+            let project = project(indoc! {r#"
+              - from: /guides/setup/
+                to: /
+              - from: /somewhere
+                to: /
+              - from: /api/trees
+                to: /
+            "#});
+
+            let errors = redirect_errors(&project);
+            assert_eq!(errors.len(), 2, "{:#?}", errors);
+
+            assert!(
+                errors[0].description.starts_with(
+                    r#"Redirect source "/guides/setup" already exists as the page guides/setup.md."#
+                ),
+                "{}",
+                errors[0].description
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from(SETTINGS_FILE_NAME)));
+            assert_eq!(errors[0].position.as_ref().unwrap().start.row, 7);
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let error = page.ast(Some(&opts)).unwrap_err();
+            assert!(
+                errors[1].description.starts_with(
+                    r#"Redirect source "/api/trees" already exists as the page openapi.yaml."#
+                ),
+                "{}",
+                errors[1].description
+            );
+            assert_eq!(errors[1].position.as_ref().unwrap().start.row, 11);
+        }
 
-        assert_str_eq!(
-            error.description,
-            indoc! { r#"
-            Can't find an OpenAPI file with path "openapi.json"
+        #[test]
+        fn pages_take_precedence_over_redirects() {
+            let project = project(indoc! {r#"
+              - from: /guides/setup
+                to: /
+              - from: /somewhere
+                to: /guides/setup
+            "#});
+
+            assert_eq!(project.resolve_redirect("/guides/setup"), None);
+            assert_eq!(
+                project.resolve_redirect("/somewhere/"),
+                Some("/guides/setup".to_owned())
+            );
+            assert!(project.get_page_by_uri_path("/guides/setup").is_some());
 
-                1 │ <OpenAPISchema title="User" openapi_path="openapi.json" />
-                                                              ▲▲▲▲▲▲▲▲▲▲▲▲
+            assert_eq!(
+                project.redirects(),
+                vec![("/somewhere".to_owned(), "/guides/setup".to_owned())]
+            );
+        }
 
-            "# }
-            .to_string()
-        );
+        #[test]
+        fn pages_under_wildcard_redirects_are_served() {
+            let project = project(indoc! {r#"
+              - from: /guides/old/**
+                to: /guides/setup
+              - from: /legacy/*
+                to: /guides/:path
+            "#});
+
+            assert!(redirect_errors(&project).is_empty());
+
+            assert_eq!(project.resolve_redirect("/guides/old/install"), None);
+            assert_eq!(
+                project.resolve_redirect("/guides/old/upgrade/v2"),
+                Some("/guides/setup".to_owned())
+            );
+            assert_eq!(
+                project.resolve_redirect("/legacy/setup"),
+                Some("/guides/setup".to_owned())
+            );
+            assert_eq!(project.resolve_redirect("/legacy/setup/more"), None);
+
+            // Wildcards still apply to the paths without a page
+            assert_eq!(project.redirects().len(), 2);
+
+            let warnings = project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.code == Error::INVALID_REDIRECT)
+                .collect::<Vec<_>>();
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(
+                warnings[0].message,
+                r#"Redirect "/guides/old/**" covers existing pages"#
+            );
+            assert!(
+                warnings[0]
+                    .description
+                    .contains("* guides/old/install.md (/guides/old/install)\n\n"),
+                "{}",
+                warnings[0].description
+            );
+            assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 7);
+        }
     }
 
-    #[test]
-    #[ignore]
-    fn parses_openapi_schema_node_with_expanded() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    open_api:
-                      - spec_file: openapi.json
-                        uri_prefix: /api
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <OpenAPISchema title="User" openapi_path="openapi.json" expanded={true} />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text(
-                    indoc! {r##"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        },
-                        "components": {
-                          "schemas": {
-                              "User": {
-                                  "title": "User schema",
-                                  "description": "# Hello, this is a title",
-                                  "type": "object",
-                                  "properties": {
-                                      "id": {
-                                          "type": "integer",
-                                          "format": "int64"
-                                      },
-                                      "username": {
-                                          "type": "string"
-                                      }
-                                  }
-                              }
-                          }
-                        }
-                      }
-                "##}
-                    .to_string(),
-                ),
-            },
-        ];
+    mod url_style {
+        use super::*;
+
+        fn project(url_style: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(format!(
+                        indoc! {r#"
+                        ---
+                        title: Styles
+                        url_style: {}
+                        redirects:
+                          - from: /old/
+                            to: /guides/
+                        "#},
+                        url_style
+                    )),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        - heading: Guides
+                          items:
+                            - label: Guides
+                              href: /guides
+                            - label: Install
+                              href: /guides/install
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Welcome\n\n[Old](/old)".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("guides/README.md"),
+                    content: InputContent::Text(
+                        "# Guides\n\n[Install](/guides/install/) and [again](/guides/install.html)"
+                            .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("guides/install.md"),
+                    content: InputContent::Text("# Install".to_string()),
+                },
+            ])
+            .unwrap()
+        }
+
+        fn nav_links(project: &Project) -> Vec<String> {
+            project.navigation(None, "/").unwrap().gather_links()
+        }
+
+        #[test]
+        fn no_trailing_slash_is_the_default() {
+            let project = project("no_trailing_slash");
+
+            assert_eq!(nav_links(&project), vec!["/guides", "/guides/install"]);
+            assert_eq!(
+                project.get_page_by_uri_path("/guides").unwrap().out_path(),
+                PathBuf::from("guides/index.html")
+            );
+            assert_eq!(
+                project
+                    .get_page_by_uri_path("/guides/install")
+                    .unwrap()
+                    .out_path(),
+                PathBuf::from("guides/install.html")
+            );
+        }
+
+        #[test]
+        fn trailing_slash() {
+            let project = project("trailing_slash");
 
-        let project = Project::from_file_list(files).unwrap();
+            assert_eq!(nav_links(&project), vec!["/guides/", "/guides/install/"]);
 
-        let opts = RenderOptions::default();
+            let guides = project.get_page_by_uri_path("/guides/").unwrap();
+            assert_eq!(guides.uri_path(), "/guides");
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let ast = page.ast(Some(&opts)).unwrap();
+            let (prev, next) = guides.previous_and_next(None);
+            assert!(prev.is_none());
+            assert_eq!(next.unwrap().href, "/guides/install/");
 
-        if let Ast::Markdown(root) = ast {
-            assert_str_eq!(
-                root.debug_string().unwrap(),
-                indoc! { r#"
-                <OpenAPISchema title={User schema} openapi_path="openapi.json" expanded={true}>
-                    <Heading1>
-                        <Text>
-                            Hello, this is a title
-                        </Text>
-                    </Heading1>
-                </OpenAPISchema>
-                "#}
-                .to_string()
+            let install = project.get_page_by_uri_path("/guides/install").unwrap();
+            assert_eq!(
+                install.out_path(),
+                PathBuf::from("guides/install/index.html")
             );
-        } else {
-            panic!("Expected markdown AST");
+            assert_eq!(
+                project.get_page_by_uri_path("/").unwrap().out_path(),
+                PathBuf::from("index.html")
+            );
+        }
+
+        #[test]
+        fn html_extension() {
+            let project = project("html_extension");
+
+            assert_eq!(
+                nav_links(&project),
+                vec!["/guides.html", "/guides/install.html"]
+            );
+
+            let guides = project.get_page_by_uri_path("/guides.html").unwrap();
+            assert_eq!(guides.out_path(), PathBuf::from("guides.html"));
+
+            let (_, next) = guides.previous_and_next(None);
+            assert_eq!(next.unwrap().href, "/guides/install.html");
+        }
+
+        #[test]
+        fn render_options_override_the_settings() {
+            let project = project("trailing_slash");
+            let opts = RenderOptions {
+                url_style: Some(crate::UrlStyle::NoTrailingSlash),
+                ..Default::default()
+            };
+
+            let nav = project.navigation(Some(&opts), "/").unwrap();
+            assert_eq!(nav.gather_links(), vec!["/guides", "/guides/install"]);
+        }
+
+        #[test]
+        fn links_and_redirects_in_any_style_are_not_broken() {
+            for style in ["no_trailing_slash", "trailing_slash", "html_extension"] {
+                let project = project(style);
+
+                assert!(
+                    project.verify(None, None).is_ok(),
+                    "{}: {:#?}",
+                    style,
+                    project.verify(None, None)
+                );
+            }
         }
     }
 
-    #[test]
-    #[ignore]
-    fn parses_openapi_schema_node_with_expanded_stringified() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
+    mod windows_paths {
+        use super::*;
+
+        fn settings(content: &str) -> InputFile {
             InputFile {
                 path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    ---
-                    title: An Project
-                    open_api:
-                      - spec_file: openapi.json
-                        uri_prefix: /api
-                    "# }
-                    .to_string(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                      <OpenAPISchema title="User" openapi_path="openapi.json" expanded="true" />
-                    "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("openapi.json"),
-                content: InputContent::Text(
-                    indoc! {r##"
-                    {
-                        "openapi": "3.0.0",
-                        "info": {
-                          "version": "1.0.0",
-                          "title": "Example code sample extensions case"
-                        },
-                        "paths": {
-                          "/foo": {
-                            "get": {
-                              "description": "Foo GET",
-                              "tags": [
-                                "test"
-                              ],
-                              "parameters": [],
-                              "responses": {},
-                            }
-                          }
-                        },
-                        "components": {
-                          "schemas": {
-                              "User": {
-                                  "title": "User schema",
-                                  "description": "# Hello, this is a title",
-                                  "type": "object",
-                                  "properties": {
-                                      "id": {
-                                          "type": "integer",
-                                          "format": "int64"
-                                      },
-                                      "username": {
-                                          "type": "string"
-                                      }
-                                  }
-                              }
-                          }
-                        }
-                      }
-                "##}
-                    .to_string(),
-                ),
-            },
-        ];
+                content: InputContent::Text(content.to_string()),
+            }
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        #[test]
+        fn pages_in_nested_folders() {
+            let files = vec![
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("guides\\getting-started\\intro.md"),
+                    content: InputContent::Text("# Intro".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---\n".to_string()),
+                },
+                settings("---\ntitle: Something\n"),
+            ];
+
+            let project = Project::from_file_list(files).unwrap();
+
+            let page = project
+                .get_page_by_uri_path("/guides/getting-started/intro")
+                .unwrap();
+            assert_eq!(page.fs_path(), Path::new("guides/getting-started/intro.md"));
+
+            assert!(project
+                .get_page_by_fs_path(Path::new("guides\\getting-started\\intro.md"))
+                .is_some());
+            assert_eq!(
+                project.fs_path_to_uri_path(Path::new("guides\\getting-started\\intro.md"), None),
+                "/guides/getting-started/intro"
+            );
+        }
 
-        let opts = RenderOptions::default();
+        #[test]
+        fn fs_to_uri_path_with_backslashes() {
+            assert_eq!(crate::fs_to_uri_path(Path::new("foo\\bar.md")), "/foo/bar");
+            assert_eq!(crate::fs_to_uri_path(Path::new("foo\\README.md")), "/foo");
+        }
 
-        let page = project.get_page_by_uri_path("/").unwrap();
-        let ast = page.ast(Some(&opts)).unwrap();
+        #[test]
+        fn components_in_nested_folders() {
+            let files = vec![
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(
+                        "<Component.Cards.Card>Hello</Component.Cards.Card>".to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("_components\\cards\\card.md"),
+                    content: InputContent::Text("<Slot />".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---\n".to_string()),
+                },
+                settings("---\ntitle: Something\n"),
+            ];
+
+            let project = Project::from_file_list(files).unwrap();
+
+            assert!(project
+                .get_page_by_uri_path("/_components/cards/card")
+                .is_none());
+            assert_eq!(project.verify(None, None), Ok(()));
+        }
 
-        if let Ast::Markdown(root) = ast {
-            assert_str_eq!(
-                root.debug_string().unwrap(),
-                indoc! { r#"
-                <OpenAPISchema title={User schema} expanded={true}>
-                    <Heading1>
-                        <Text>
-                            Hello, this is a title
-                        </Text>
-                    </Heading1>
-                </OpenAPISchema>
-                "#}
-                .to_string()
+        #[test]
+        fn subtab_navigations() {
+            let files = vec![
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Hi".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("tab1\\README.md"),
+                    content: InputContent::Text("# Tab 1".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("tab1\\page.md"),
+                    content: InputContent::Text("# Page".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---\n".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(format!("tab1\\{}", NAVIGATION_FILE_NAME)),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        - heading: Tab 1
+                          items:
+                            - label: Page
+                              href: /tab1/page.md
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                settings(indoc! {r#"
+                ---
+                title: Something
+
+                tabs:
+                  - label: Default
+                    path: /
+                  - label: Tab 1
+                    path: /tab1/
+                "#}),
+            ];
+
+            let project = Project::from_file_list(files).unwrap();
+
+            let nav = project.navigation(None, "/tab1").unwrap();
+            assert!(nav.has_link_to("/tab1/page"));
+            assert_eq!(project.verify(None, None), Ok(()));
+        }
+
+        #[test]
+        fn link_checker_and_assets() {
+            let files = vec![
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(
+                        "[Intro](/guides/intro.md)\n\n![Logo](/_assets/images/logo.png)"
+                            .to_string(),
+                    ),
+                },
+                InputFile {
+                    path: PathBuf::from("guides\\intro.md"),
+                    content: InputContent::Text("[Home](../README.md)".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("_assets\\images\\logo.png"),
+                    content: InputContent::Binary {
+                        signature: String::new(),
+                        size_bytes: 0,
+                    },
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text(
+                        indoc! {r#"
+                        ---
+                        - heading: Guides
+                          items:
+                            - label: Intro
+                              href: guides/intro.md
+                        "#}
+                        .to_string(),
+                    ),
+                },
+                settings("---\ntitle: Something\n"),
+            ];
+
+            let project = Project::from_file_list(files).unwrap();
+
+            assert!(project
+                .get_asset_by_fs_path(Path::new("_assets\\images\\logo.png"))
+                .is_some());
+            assert_eq!(project.verify(None, None), Ok(()));
+        }
+    }
+
+    mod suggestions {
+        use super::*;
+
+        fn project(readme: &str, nav: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: Suggestions\n".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text(nav.to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(readme.to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("guides/install.md"),
+                    content: InputContent::Text("# Install".to_string()),
+                },
+                InputFile {
+                    path: PathBuf::from("start/setup.md"),
+                    content: InputContent::Text("# Setup".to_string()),
+                },
+            ])
+            .unwrap()
+        }
+
+        fn suggestion(file: &str, source: &str, written: &str, replacement: &str) -> Suggestion {
+            let start = source.find(written).unwrap();
+
+            Suggestion {
+                file: PathBuf::from(file),
+                start,
+                end: start + written.len(),
+                replacement: replacement.to_string(),
+            }
+        }
+
+        #[test]
+        fn suggests_pages_a_typo_away() {
+            let readme = "---\ntitle: Home\n---\n# Home\n\n[Install](/guides/instal)";
+            let project = project(readme, "---");
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 1);
+            assert_eq!(
+                errors[0].suggestions,
+                vec![suggestion(
+                    "README.md",
+                    readme,
+                    "/guides/instal",
+                    "/guides/install"
+                )]
             );
-        } else {
-            panic!("Expected markdown AST");
+
+            let position = errors[0].position.as_ref().unwrap();
+            assert_eq!(position.start.row, 6);
+            assert_eq!(position.start.col, 11);
+        }
+
+        #[test]
+        fn suggests_pages_moved_to_another_directory() {
+            let readme = "# Home\n\n[Setup](guides/setup.md#usage)";
+            let project = project(readme, "---");
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(
+                errors[0].suggestions,
+                vec![suggestion(
+                    "README.md",
+                    readme,
+                    "guides/setup.md",
+                    "/start/setup.md"
+                )]
+            );
+        }
+
+        #[test]
+        fn keeps_relative_links_relative() {
+            let readme = "# Home\n\n<a href=\"./guides/instll.md\">Install</a>";
+            let project = project(readme, "---");
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(
+                errors[0].suggestions,
+                vec![suggestion(
+                    "README.md",
+                    readme,
+                    "./guides/instll.md",
+                    "./guides/install.md"
+                )]
+            );
+        }
+
+        #[test]
+        fn suggests_nothing_when_no_page_is_close() {
+            let project = project("# Home\n\n[Nope](/something/else)", "---");
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors[0].suggestions, vec![]);
+        }
+
+        #[test]
+        fn suggests_markdown_paths_in_navigation() {
+            let nav = indoc! {r#"
+            - heading: Guides
+              items:
+                - label: Install
+                  href: /guides/Install#usage
+                - label: Setup
+                  href: "/setup.md"
+            "#};
+            let project = project("# Home", nav);
+
+            let errors = project.verify(None, None).unwrap_err();
+
+            assert_eq!(errors.len(), 2);
+            assert_eq!(
+                errors[0].suggestions,
+                vec![suggestion(
+                    "/navigation.yaml",
+                    nav,
+                    "/guides/Install#usage",
+                    "/guides/install.md#usage"
+                )]
+            );
+            assert_eq!(
+                errors[1].suggestions,
+                vec![suggestion(
+                    "/navigation.yaml",
+                    nav,
+                    "/setup.md",
+                    "/start/setup.md"
+                )]
+            );
+            assert_eq!(errors[1].position.as_ref().unwrap().start.row, 6);
+        }
+
+        #[test]
+        fn leaves_suggestions_out_of_serialized_errors_without_any() {
+            let project = project("# Home\n\n[Nope](/something/else)", "---");
+
+            let errors = project.verify(None, None).unwrap_err();
+            let json = serde_json::to_value(&errors[0]).unwrap();
+
+            assert!(json.get("suggestions").is_none());
         }
     }
 
-    #[test]
-    fn can_get_the_ast_for_a_virtual_page_in_a_project() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
-                ---
-                title: An Project
-                "# }
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    # Hi
-                    "#}
-                    .to_owned(),
+    mod titles {
+        use super::*;
+
+        fn project(navigation: &str, files: &[(&str, &str)]) -> Project {
+            let mut list = vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text(navigation.to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("Welcome".to_owned()),
+                },
+            ];
+
+            for (path, content) in files {
+                list.push(InputFile {
+                    path: PathBuf::from(path),
+                    content: InputContent::Text(content.to_string()),
+                });
+            }
+
+            Project::from_file_list(list).unwrap()
+        }
+
+        fn warnings(project: &Project, code: usize) -> Vec<Error> {
+            project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.code == code)
+                .collect()
+        }
+
+        #[test]
+        fn warns_about_pages_titled_after_their_file_name() {
+            let project = project(
+                "---",
+                &[
+                    ("with-frontmatter.md", "---\ntitle: Set\n---\nText"),
+                    ("with-heading.md", "# Set"),
+                    ("without-title.md", "Just text"),
+                ],
+            );
+
+            let warnings = warnings(&project, Error::MISSING_TITLE);
+
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].file, Some(PathBuf::from("without-title.md")));
+            assert_eq!(
+                warnings[0].description,
+                "The page is titled \"Without title\" after its file name. Add a `title` to the frontmatter, or start the page with a heading."
+            );
+        }
+
+        #[test]
+        fn warns_about_duplicate_titles_in_a_navigation_section() {
+            let project = project(
+                indoc! {r#"
+                - heading: Guides
+                  items:
+                    - href: /guides/install.md
+                    - label: Also installing
+                      href: /guides/setup.md
+                    - href: /guides/install.md#upgrading
+                - heading: Reference
+                  items:
+                    - href: /reference/install.md
+                "#},
+                &[
+                    ("guides/install.md", "# Installing\n\n## Upgrading"),
+                    ("guides/setup.md", "---\ntitle: Installing\n---\n"),
+                    ("reference/install.md", "# Installing"),
+                ],
+            );
+
+            let warnings = warnings(&project, Error::DUPLICATE_TITLE);
+
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].message, r#"Duplicate page title "Installing""#);
+            assert_eq!(warnings[0].file, Some(PathBuf::from("guides/setup.md")));
+            assert_eq!(
+                warnings[0].description,
+                "\"guides/install.md\" and \"guides/setup.md\" are both titled \"Installing\" in the \"Guides\" navigation section. Give one of them a different title, so that they can be told apart."
+            );
+        }
+
+        #[test]
+        fn uses_the_same_title_everywhere() {
+            let project = project(
+                indoc! {r#"
+                - heading: Guides
+                  items:
+                    - href: /guides/README.md
+                      items:
+                        - href: /guides/install.md
+                        - href: /guides/usage.md
+                "#},
+                &[
+                    ("guides/README.md", "Intro\n\n# All about guides"),
+                    ("guides/install.md", "# Installing"),
+                    ("guides/usage.md", "Some usage"),
+                ],
+            );
+
+            let page = project.get_page_by_uri_path("/guides/install").unwrap();
+            assert_eq!(page.title(), Ok("Installing".to_string()));
+
+            let breadcrumbs = page.breadcrumbs(None);
+            assert!(
+                matches!(
+                    &breadcrumbs[1],
+                    crate::breadcrumb::Breadcrumb::Link { label, .. } if label == "All about guides"
                 ),
-            },
-        ];
+                "{:#?}",
+                breadcrumbs
+            );
 
-        let project = Project::from_file_list(files).unwrap();
+            let (previous, next) = page.previous_and_next(None);
+            assert_eq!(previous.unwrap().title, "All about guides");
+            assert_eq!(next.unwrap().title, "Usage");
 
-        let opts = RenderOptions::default();
-        let ast = project
-            .get_ast_mdx_fault_tolerant("hi", Path::new("example.md"), &opts)
-            .unwrap();
+            let root = project.get_page_by_uri_path("/").unwrap();
+            assert_eq!(root.title(), Ok("An Project".to_string()));
+        }
+    }
+
+    mod page_styles {
+        use super::*;
+
+        fn project(page: &str, files: &[(&str, &str)]) -> Project {
+            let mut list = vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Welcome".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("landing.md"),
+                    content: InputContent::Text(page.to_owned()),
+                },
+            ];
+
+            for (path, content) in files {
+                list.push(InputFile {
+                    path: PathBuf::from(path),
+                    content: InputContent::Text(content.to_string()),
+                });
+            }
+
+            Project::from_file_list(list).unwrap()
+        }
+
+        #[test]
+        fn includes_the_style_sheets_in_the_frontmatter() {
+            let project = project(
+                indoc! {"
+                ---
+                css:
+                  - _assets/css/landing.css
+                ---
+                # Landing
+                "},
+                &[("_assets/css/landing.css", ".hero { color: red }")],
+            );
+
+            let page = project.get_page_by_uri_path("/landing").unwrap();
+
+            assert_eq!(page.custom_css(), vec![".hero { color: red }".to_string()]);
+            assert_eq!(page.css_scope(), None);
+            assert!(project.verify(None, None).is_ok());
+        }
+
+        #[test]
+        fn scopes_the_style_sheets_to_the_page() {
+            let project = project(
+                indoc! {"
+                ---
+                css:
+                  - /_assets/css/landing.css
+                css_scope: true
+                ---
+                # Landing
+                "},
+                &[("_assets/css/landing.css", ".hero { color: red }")],
+            );
+
+            let page = project.get_page_by_uri_path("/landing").unwrap();
+
+            assert_eq!(page.css_scope(), Some("css-scope-landing".to_string()));
+            assert!(
+                page.custom_css()[0].contains(".css-scope-landing .hero"),
+                "{:#?}",
+                page.custom_css()
+            );
 
-        assert_str_eq!(
-            ast.debug_string().unwrap(),
-            indoc! { r#"
-            <Paragraph>
-                <Text>
-                    hi
-                </Text>
-            </Paragraph>
-            "# }
-            .to_string()
-        );
-    }
+            match project.get_content_response_by_uri_path("/landing", ResponseContext::default()) {
+                ContentApiResponse::Content {
+                    page:
+                        crate::content_api::CurrentPage::Page {
+                            custom_css,
+                            css_scope,
+                            ..
+                        },
+                    ..
+                } => {
+                    assert_eq!(custom_css.len(), 1);
+                    assert_eq!(css_scope, Some("css-scope-landing".to_string()));
+                }
+                other => panic!("Unexpected response: {:#?}", other),
+            }
+        }
 
-    #[test]
-    fn can_get_errors_for_a_virtual_page_in_a_project() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
+        #[test]
+        fn errors_on_missing_style_sheets() {
+            let project = project(
+                indoc! {"
                 ---
-                title: An Project
-                "# }
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    # Hi
-                    "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+                css:
+                  - _assets/css/nope.css
+                ---
+                # Landing
+                "},
+                &[("_assets/css/landing.css", ".hero { color: red }")],
+            );
 
-        let project = Project::from_file_list(files).unwrap();
+            let errors = project.verify(None, None).unwrap_err();
 
-        let opts = RenderOptions::default();
-        let result = project
-            .get_ast_mdx_fault_tolerant("hi\n</>", Path::new("example.md"), &opts)
-            .unwrap_err();
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                "Could not find style sheet file at \"_assets/css/nope.css\"."
+            );
+            assert_eq!(errors[0].file, Some(PathBuf::from("landing.md")));
+            assert!(project
+                .get_page_by_uri_path("/landing")
+                .unwrap()
+                .custom_css()
+                .is_empty());
+        }
 
-        let ast = result.0.unwrap();
-        assert_eq!(ast.children.len(), 1);
+        #[test]
+        fn errors_on_style_sheets_that_cant_be_scoped() {
+            let project = project(
+                indoc! {"
+                ---
+                css:
+                  - _assets/css/landing.css
+                css_scope: true
+                ---
+                # Landing
+                "},
+                &[("_assets/css/landing.css", "..hero { color: red }")],
+            );
 
-        let errors = result.1;
-        assert_eq!(errors.len(), 1);
-    }
+            let errors = project.verify(None, None).unwrap_err();
 
-    #[test]
-    fn can_offset_virtual_file_error_offsets_by_frontmatter() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(
+                errors[0].message,
+                "Could not scope style sheet \"_assets/css/landing.css\""
+            );
+        }
+
+        #[test]
+        fn warns_about_style_sheets_outside_the_css_folder() {
+            let project = project(
+                indoc! {"
                 ---
-                title: An Project
-                "# }
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                    # Hi
-                    "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+                css:
+                  - _assets/landing.css
+                  - _assets/css/other.css
+                ---
+                # Landing
+                "},
+                &[
+                    ("_assets/landing.css", ".hero { color: red }"),
+                    ("_assets/css/other.css", ".other { color: blue }"),
+                ],
+            );
 
-        let project = Project::from_file_list(files).unwrap();
+            let warnings = project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.code == Error::PAGE_CSS_LOCATION)
+                .collect::<Vec<_>>();
 
-        let markdown = indoc! {r#"
-        ---
-        title: An Project
-        ---
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(
+                warnings[0].message,
+                "Style sheet \"_assets/landing.css\" is outside of \"_assets/css\""
+            );
+            assert_eq!(warnings[0].file, Some(PathBuf::from("landing.md")));
+        }
+    }
 
-        # Hi
+    mod unclosed_frontmatter {
+        use super::*;
+
+        fn project(page: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text(page.to_owned()),
+                },
+            ])
+            .unwrap()
+        }
 
-        </>
-        "#
-        };
+        fn unclosed_warnings(project: &Project) -> Vec<Error> {
+            project
+                .warnings()
+                .into_iter()
+                .filter(|w| w.message == "Unclosed frontmatter")
+                .collect()
+        }
 
-        let opts = RenderOptions::default();
-        let result = project
-            .get_ast_mdx_fault_tolerant(markdown, Path::new("example.md"), &opts)
-            .unwrap_err();
+        #[test]
+        fn warns_about_frontmatter_that_is_never_closed() {
+            let project = project(indoc! {"
+            ---
+            # Welcome
 
-        let errors = result.1;
-        assert_eq!(errors.len(), 1);
+            Some text
 
-        let error = &errors[0];
-        assert_eq!(error.position.as_ref().unwrap().start.row, 7);
-        assert_eq!(error.position.as_ref().unwrap().start.col, 1);
-        assert_eq!(error.position.as_ref().unwrap().start.byte_offset, 33);
-        assert_eq!(error.position.as_ref().unwrap().end.row, 7);
-        assert_eq!(error.position.as_ref().unwrap().end.col, 4);
-        assert_eq!(error.position.as_ref().unwrap().end.byte_offset, 36);
+            -----
 
-        assert_eq!(
-            "</>",
-            &markdown[error.position.as_ref().unwrap().start.byte_offset
-                ..error.position.as_ref().unwrap().end.byte_offset]
-        );
-    }
+            More text
+            "});
 
-    #[test]
-    fn verifies_vale_config_file_exists() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
-              ---
-              title: An Project
+            let warnings = unclosed_warnings(&project);
 
-              vale:
-                version: 2.30.0
-                config_file_path: .vale.ini
-              "# }
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                  # Hi
-                  "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+            assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+            assert_eq!(warnings[0].file, Some(PathBuf::from("README.md")));
+            assert_eq!(warnings[0].position.as_ref().unwrap().start.row, 1);
 
-        let project = Project::from_file_list(files).unwrap();
+            // The whole page is content
+            let page = project.get_page_by_uri_path("/").unwrap();
+            assert_eq!(page.title().unwrap(), "Welcome");
+        }
 
-        let errors = project.verify(None, None).unwrap_err();
+        #[test]
+        fn does_not_warn_about_closed_frontmatter() {
+            let project = project("---\ntitle: Welcome\n...\n\nSome text\n");
 
-        assert_eq!(errors.len(), 1);
-        assert_eq!(errors[0].message, "Couldn't find Vale configuration file.");
-        assert_eq!(
-            errors[0].description,
-            "Expected a Vale configuration file at \".vale.ini\""
-        );
+            assert!(unclosed_warnings(&project).is_empty());
+        }
     }
 
-    #[test]
-    fn verifies_vale_config_path() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(
-                    indoc! { r#"
-              ---
-              title: An Project
+    mod canonical_urls {
+        use super::*;
+
+        fn project(settings: &str, page: &str) -> Project {
+            Project::from_file_list(vec![
+                InputFile {
+                    path: PathBuf::from(NAVIGATION_FILE_NAME),
+                    content: InputContent::Text("---".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from(SETTINGS_FILE_NAME),
+                    content: InputContent::Text(settings.to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("README.md"),
+                    content: InputContent::Text("# Welcome".to_owned()),
+                },
+                InputFile {
+                    path: PathBuf::from("partner/setup.md"),
+                    content: InputContent::Text(page.to_owned()),
+                },
+            ])
+            .unwrap()
+        }
 
-              vale:
-                version: 2.30.0
-                config_file_path: .vale.inia
-              "# }
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                  # Hi
-                  "#}
-                    .to_owned(),
-                ),
-            },
-            InputFile {
-                path: PathBuf::from(".vale.inia"),
-                content: InputContent::Text(
-                    indoc! {r#"
-                  # Hi
-                  "#}
-                    .to_owned(),
-                ),
-            },
-        ];
+        fn page_response(project: &Project) -> (Option<String>, bool) {
+            match project
+                .get_content_response_by_uri_path("/partner/setup", ResponseContext::default())
+            {
+                ContentApiResponse::Content {
+                    page:
+                        crate::content_api::CurrentPage::Page {
+                            canonical_url,
+                            page_options,
+                            ..
+                        },
+                    ..
+                } => (canonical_url, page_options.noindex),
+                other => panic!("Unexpected response: {:#?}", other),
+            }
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        #[test]
+        fn exposes_the_canonical_url_and_noindex() {
+            let project = project(
+                "---\ntitle: An Project\n",
+                indoc! {"
+                ---
+                canonical_url: https://partner.example.com/setup
+                noindex: true
+                ---
+                # Setup
+                "},
+            );
 
-        let errors = project.verify(None, None).unwrap_err();
+            assert_eq!(
+                page_response(&project),
+                (Some("https://partner.example.com/setup".to_string()), true)
+            );
+            assert!(project.verify(None, None).is_ok());
+        }
 
-        assert_eq!(errors.len(), 1);
-        assert_eq!(
-            errors[0].message,
-            "A hidden vale configuration file must be named \".vale.ini\"."
-        );
-        assert_eq!(
-            errors[0].description,
-            "Use \".vale.ini\", or remove the \".\" from the start of the config file name \".vale.inia\"."
-        );
-    }
+        #[test]
+        fn frontmatter_overrides_the_settings() {
+            let settings = indoc! {"
+            ---
+            title: An Project
+            canonical_urls:
+              - path: partner/**
+                url: https://partner.example.com/docs
+            "};
+
+            let from_settings = project(settings, "# Setup");
+            assert_eq!(
+                page_response(&from_settings),
+                (
+                    Some("https://partner.example.com/docs/setup".to_string()),
+                    false
+                )
+            );
 
-    #[test]
-    fn creates_an_elasticlunr_search_index() {
-        let files = vec![
-            InputFile {
-                path: PathBuf::from(NAVIGATION_FILE_NAME),
-                content: InputContent::Text("---".to_owned()),
-            },
-            InputFile {
-                path: PathBuf::from(SETTINGS_FILE_NAME),
-                content: InputContent::Text(String::from("---\ntitle: An Project\n")),
-            },
-            InputFile {
-                path: PathBuf::from("README.md"),
-                content: InputContent::Text("# A heading\n\nA paragraph".to_string()),
-            },
-        ];
+            let from_frontmatter = project(
+                settings,
+                "---\ncanonical_url: https://elsewhere.example.com\n---\n# Setup",
+            );
+            assert_eq!(
+                page_response(&from_frontmatter).0,
+                Some("https://elsewhere.example.com".to_string())
+            );
+        }
 
-        let project = Project::from_file_list(files).unwrap();
+        #[test]
+        fn relative_canonical_urls_are_errors() {
+            let project = project(
+                "---\ntitle: An Project\n",
+                indoc! {"
+                ---
+                title: Setup
+                canonical_url: /setup
+                ---
+                # Setup
+                "},
+            );
 
-        let index = project.search_index().unwrap();
+            let errors = project.verify(None, None).unwrap_err();
 
-        assert!(index.to_json().contains("A heading"));
-        assert!(index.to_json().contains("A paragraph"));
+            assert_eq!(errors.len(), 1, "{:#?}", errors);
+            assert_eq!(errors[0].code, Error::INVALID_FRONTMATTER);
+            assert_eq!(errors[0].message, "Invalid `canonical_url` in frontmatter");
+            assert_eq!(errors[0].file, Some(PathBuf::from("partner/setup.md")));
+
+            let position = errors[0].position.as_ref().unwrap();
+            assert_eq!((position.start.row, position.start.col), (3, 16));
+        }
     }
 }
@@ -0,0 +1,376 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hasher;
+
+use regex::Regex;
+
+use crate::page_kind::PageKind;
+use crate::{CustomComponentHandle, Project};
+
+lazy_static! {
+    static ref COMPONENT_TAG: Regex =
+        Regex::new(r"<((?:Component|Topic)(?:\.[A-Za-z0-9_]+)+)").unwrap();
+}
+
+/// What changed between two versions of a project, so that the dev server
+/// can reload only the pages affected by a change.
+///
+/// Pages are identified by their URI paths, and are listed in sorted order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectDiff {
+    /// Pages that only exist in the newer project
+    pub added: Vec<String>,
+    /// Pages that only exist in the older project
+    pub removed: Vec<String>,
+    /// Pages whose content changed, or that use a custom component that changed
+    pub changed: Vec<String>,
+    pub navigation_changed: bool,
+    pub settings_changed: bool,
+    pub custom_css_changed: bool,
+    pub assets_changed: bool,
+    pub components_changed: bool,
+}
+
+impl ProjectDiff {
+    pub(crate) fn between(old: &Project, new: &Project) -> Self {
+        let old_pages = page_signatures(&old.pages);
+        let new_pages = page_signatures(&new.pages);
+
+        let mut added = new_pages
+            .keys()
+            .filter(|uri| !old_pages.contains_key(*uri))
+            .map(|uri| uri.to_string())
+            .collect::<Vec<_>>();
+
+        let mut removed = old_pages
+            .keys()
+            .filter(|uri| !new_pages.contains_key(*uri))
+            .map(|uri| uri.to_string())
+            .collect::<Vec<_>>();
+
+        let changed_components = changed_components(&old.custom_components, &new.custom_components);
+
+        let mut changed = new_pages
+            .iter()
+            .filter(|(uri, signature)| match old_pages.get(*uri) {
+                Some(old_signature) => {
+                    old_signature != *signature
+                        || new.component_usage.uses_any(uri, &changed_components)
+                }
+                None => false,
+            })
+            .map(|(uri, _)| uri.to_string())
+            .collect::<Vec<_>>();
+
+        added.sort();
+        removed.sort();
+        changed.sort();
+
+        ProjectDiff {
+            added,
+            removed,
+            changed,
+            navigation_changed: navigation_contents(old) != navigation_contents(new),
+            settings_changed: old.settings != new.settings,
+            custom_css_changed: old.custom_css != new.custom_css,
+            assets_changed: asset_signatures(old) != asset_signatures(new),
+            components_changed: !changed_components.is_empty(),
+        }
+    }
+
+    /// Whether nothing that affects the rendered pages changed.
+    pub fn is_empty(&self) -> bool {
+        *self == ProjectDiff::default()
+    }
+
+    /// Whether the change shows up on every page, like the navigation or the
+    /// theme, and so every page has to be reloaded.
+    pub fn affects_all_pages(&self) -> bool {
+        self.navigation_changed
+            || self.settings_changed
+            || self.custom_css_changed
+            || self.assets_changed
+    }
+
+    /// All pages that were added, removed, or changed.
+    pub fn affected_pages(&self) -> Vec<&str> {
+        let mut pages = self
+            .added
+            .iter()
+            .chain(&self.removed)
+            .chain(&self.changed)
+            .map(|uri| uri.as_str())
+            .collect::<Vec<_>>();
+        pages.sort();
+        pages
+    }
+}
+
+/// Which custom components each page uses, either directly or through
+/// other components. Built when the project is loaded, so that a change to
+/// a component can be traced back to the pages it shows up on.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ComponentUsage {
+    by_page: HashMap<String, HashSet<String>>,
+}
+
+impl ComponentUsage {
+    pub(crate) fn build(pages: &[PageKind], components: &[CustomComponentHandle]) -> Self {
+        let by_component = components
+            .iter()
+            .filter_map(|c| {
+                c.title()
+                    .ok()
+                    .map(|title| (title, used_components(&c.content)))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let by_page = pages
+            .iter()
+            .filter_map(|page| match page {
+                PageKind::Markdown(md) => Some((md.uri_path.clone(), &md.content)),
+                PageKind::OpenApi(_) => None,
+            })
+            .map(|(uri, content)| {
+                let mut used = HashSet::new();
+                let mut queue = used_components(content).into_iter().collect::<Vec<_>>();
+
+                while let Some(title) = queue.pop() {
+                    if let Some(nested) = by_component.get(&title) {
+                        queue.extend(nested.iter().filter(|n| !used.contains(*n)).cloned());
+                    }
+                    used.insert(title);
+                }
+
+                (uri, used)
+            })
+            .collect();
+
+        ComponentUsage { by_page }
+    }
+
+    fn uses_any(&self, uri_path: &str, components: &HashSet<String>) -> bool {
+        self.by_page
+            .get(uri_path)
+            .map(|used| !used.is_disjoint(components))
+            .unwrap_or(false)
+    }
+}
+
+fn used_components(content: &str) -> HashSet<String> {
+    COMPONENT_TAG
+        .captures_iter(content)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// Signatures keyed by URI path. When pages share a URI path, the first one
+/// is the one that gets served, so it's the one that counts.
+fn page_signatures(pages: &[PageKind]) -> HashMap<&str, u64> {
+    let mut signatures = HashMap::new();
+    for page in pages {
+        signatures
+            .entry(page.uri_path())
+            .or_insert_with(|| page.content_signature());
+    }
+    signatures
+}
+
+fn changed_components(
+    old: &[CustomComponentHandle],
+    new: &[CustomComponentHandle],
+) -> HashSet<String> {
+    let signatures = |components: &[CustomComponentHandle]| {
+        components
+            .iter()
+            .filter_map(|c| {
+                let mut hasher = DefaultHasher::new();
+                hasher.write(c.content.as_bytes());
+                c.title().ok().map(|title| (title, hasher.finish()))
+            })
+            .collect::<HashMap<_, _>>()
+    };
+
+    let old = signatures(old);
+    let new = signatures(new);
+
+    old.keys()
+        .chain(new.keys())
+        .filter(|title| old.get(*title) != new.get(*title))
+        .cloned()
+        .collect()
+}
+
+fn navigation_contents(project: &Project) -> Option<Vec<(&str, Option<&str>)>> {
+    project.navigations.as_ref().map(|navigations| {
+        let mut contents = navigations
+            .iter()
+            .map(|(path, handle)| (path.as_str(), handle.as_ref().map(|h| h.0.as_str())))
+            .collect::<Vec<_>>();
+        contents.sort();
+        contents
+    })
+}
+
+fn asset_signatures(project: &Project) -> Vec<(&std::path::Path, u64)> {
+    let mut signatures = project
+        .assets
+        .iter()
+        .map(|a| (a.path.as_path(), a.signature))
+        .collect::<Vec<_>>();
+    signatures.sort();
+    signatures
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use std::path::PathBuf;
+
+    fn project(files: &[(&str, &str)]) -> Project {
+        let mut list = vec![
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text("---\ntitle: An Project\n".to_owned()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_owned()),
+            },
+        ];
+
+        for (path, content) in files {
+            list.retain(|f| f.path != PathBuf::from(path));
+            list.push(InputFile {
+                path: PathBuf::from(path),
+                content: InputContent::Text(content.to_string()),
+            });
+        }
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    #[test]
+    fn identical_projects_have_no_diff() {
+        let files = [("README.md", "# Hi"), ("foo.md", "# Foo")];
+
+        assert!(project(&files).diff_pages(&project(&files)).is_empty());
+    }
+
+    #[test]
+    fn reports_added_removed_and_changed_pages() {
+        let old = project(&[
+            ("README.md", "# Hi"),
+            ("foo.md", "# Foo"),
+            ("bar.md", "# Bar"),
+        ]);
+        let new = project(&[
+            ("README.md", "# Hi"),
+            ("foo.md", "# Foo, again"),
+            ("baz.md", "# Baz"),
+        ]);
+
+        let diff = old.diff_pages(&new);
+
+        assert_eq!(diff.added, vec!["/baz".to_string()]);
+        assert_eq!(diff.removed, vec!["/bar".to_string()]);
+        assert_eq!(diff.changed, vec!["/foo".to_string()]);
+        assert!(!diff.affects_all_pages());
+    }
+
+    #[test]
+    fn component_changes_mark_pages_using_them_as_changed() {
+        let old = project(&[
+            ("README.md", "# Hi"),
+            ("foo.md", "<Component.Greeting />"),
+            ("bar.md", "<Component.Wrapper />"),
+            ("_components/greeting.md", "Hello"),
+            ("_components/wrapper.md", "<Component.Greeting />"),
+        ]);
+        let new = project(&[
+            ("README.md", "# Hi"),
+            ("foo.md", "<Component.Greeting />"),
+            ("bar.md", "<Component.Wrapper />"),
+            ("_components/greeting.md", "Hello there"),
+            ("_components/wrapper.md", "<Component.Greeting />"),
+        ]);
+
+        let diff = old.diff_pages(&new);
+
+        assert!(diff.components_changed);
+        assert_eq!(diff.changed, vec!["/bar".to_string(), "/foo".to_string()]);
+        assert!(!diff.affects_all_pages());
+    }
+
+    #[test]
+    fn navigation_and_settings_changes_affect_all_pages() {
+        let old = project(&[("README.md", "# Hi")]);
+
+        let diff = old.diff_pages(&project(&[
+            ("README.md", "# Hi"),
+            (NAVIGATION_FILE_NAME, "- heading: Guides"),
+        ]));
+        assert!(diff.navigation_changed);
+        assert!(diff.affects_all_pages());
+        assert!(diff.changed.is_empty());
+
+        let diff = old.diff_pages(&project(&[
+            ("README.md", "# Hi"),
+            (SETTINGS_FILE_NAME, "---\ntitle: Another Project\n"),
+        ]));
+        assert!(diff.settings_changed);
+        assert!(diff.affects_all_pages());
+    }
+
+    #[test]
+    fn openapi_changes_mark_affected_tag_pages() {
+        let spec = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        paths:
+          /tree:
+            get:
+              summary: Get the tree
+              tags: [Trees]
+              responses:
+                '200':
+                  description: A tree
+          /leaves:
+            get:
+              summary: Get the leaves
+              tags: [Leaves]
+              responses:
+                '200':
+                  description: Some leaves
+        "#};
+        let settings = indoc! {r#"
+        ---
+        title: An Project
+        open_api:
+          - spec_file: openapi.yaml
+            uri_prefix: /api
+        "#};
+
+        let old = project(&[
+            ("README.md", "# Hi"),
+            (SETTINGS_FILE_NAME, settings),
+            ("openapi.yaml", spec),
+        ]);
+        let new = project(&[
+            ("README.md", "# Hi"),
+            (SETTINGS_FILE_NAME, settings),
+            (
+                "openapi.yaml",
+                &spec.replace("Get the leaves", "List leaves"),
+            ),
+        ]);
+
+        let diff = old.diff_pages(&new);
+
+        assert_eq!(diff.changed, vec!["/api/leaves".to_string()]);
+        assert!(!diff.affects_all_pages());
+    }
+}
@@ -0,0 +1,596 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::{
+    breadcrumb::Breadcrumb,
+    markdown, navigation,
+    page_kind::PageKind,
+    render_context::RenderContext,
+    tutorial::{self, Tutorial},
+    PageHandle, Project, RenderOptions,
+};
+
+/// Where the manifest is written in a static build, when `search.quick_nav`
+/// is turned on.
+pub const QUICK_NAV_FILE_NAME: &str = "quick-nav.json";
+
+/// Something a command palette can jump to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuickNavEntry {
+    pub kind: QuickNavKind,
+    pub title: String,
+    /// Where the entry is, e.g. the breadcrumbs of a page, or the title of
+    /// the page a heading is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    /// Link to the entry, with the anchor for headings and operations
+    pub uri: String,
+    /// Path of the tab or subtab the entry belongs to. Empty without tabs.
+    pub tab: String,
+    /// The badge of the page in the navigation, or the method of an operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub badge: Option<navigation::Badge>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickNavKind {
+    Page,
+    Heading,
+    Operation,
+}
+
+/// Lists the pages of the project in the order they appear in the
+/// navigations, each followed by its headings or OpenAPI operations. Pages
+/// that aren't in any navigation come last. The steps of a tutorial are
+/// listed together, under the tutorial's title.
+///
+/// Pages left out of the search, with `search: false` or `noindex: true`, are
+/// left out here too, as are drafts unless `RenderOptions.publish_drafts` is
+/// set.
+pub(crate) fn compute(project: &Project, opts: Option<&RenderOptions>) -> Vec<QuickNavEntry> {
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+    ctx.with_maybe_options(opts);
+
+    let include_noindex = project.settings.search().include_noindex;
+
+    let pages = project
+        .published_pages(opts)
+        .into_iter()
+        .filter(|p| !p.hidden_from_search().unwrap_or(false))
+        .filter(|p| include_noindex || !p.noindex())
+        .collect::<Vec<_>>();
+
+    // Operations listed under multiple tags are only listed under the first
+    // one, like in the API reference sidebar
+    let mut canonical_pages: HashMap<(PathBuf, String, String), String> = HashMap::new();
+
+    for page in &pages {
+        if let PageKind::OpenApi(openapi) = page.page {
+            for op in openapi.operations() {
+                canonical_pages
+                    .entry((
+                        page.fs_path().to_path_buf(),
+                        op.method.clone(),
+                        op.route_pattern.clone(),
+                    ))
+                    .or_insert_with(|| page.uri_path().to_owned());
+            }
+        }
+    }
+
+    let badges = navigation::page_badges(project, opts);
+    let tutorials = tutorial::all(project);
+
+    let mut entries = vec![];
+    let mut seen_uris = HashSet::new();
+
+    let pages = in_navigation_order(project, pages, &ctx, opts);
+
+    for page in with_tutorials_grouped(&tutorials, pages) {
+        let uri = markdown::parser::to_final_link(page.uri_path(), &ctx);
+
+        if !seen_uris.insert(uri.clone()) {
+            continue;
+        }
+
+        let title = page.title().unwrap_or_else(|_| page.uri_path().to_owned());
+        let tab = project
+            .tab_scope(page.uri_path())
+            .map(|scope| scope.path)
+            .unwrap_or_default();
+
+        let trail = page
+            .breadcrumbs(opts)
+            .into_iter()
+            .map(|crumb| match crumb {
+                Breadcrumb::Label { text } => text,
+                Breadcrumb::Link { label, .. } => label,
+            })
+            .collect::<Vec<_>>();
+
+        let subtitle = match tutorial_of(&tutorials, &page) {
+            Some(tutorial) => Some(tutorial.title.clone()),
+            None => (!trail.is_empty()).then(|| trail.join(" / ")),
+        };
+
+        entries.push(QuickNavEntry {
+            kind: QuickNavKind::Page,
+            title: title.clone(),
+            subtitle,
+            uri: uri.clone(),
+            tab: tab.clone(),
+            badge: badges.get(&navigation::link_identity(&uri)).cloned(),
+        });
+
+        let children = match page.page {
+            PageKind::Markdown(_) => page
+                .on_this_page_headings(opts)
+                .into_iter()
+                .map(|heading| (QuickNavKind::Heading, heading.title, heading.anchor, None))
+                .collect::<Vec<_>>(),
+            PageKind::OpenApi(openapi) => openapi
+                .operations()
+                .iter()
+                .filter(|op| {
+                    canonical_pages
+                        .get(&(
+                            page.fs_path().to_path_buf(),
+                            op.method.clone(),
+                            op.route_pattern.clone(),
+                        ))
+                        .map(String::as_str)
+                        == Some(page.uri_path())
+                })
+                .map(|op| {
+                    let title = op.summary.clone().unwrap_or_else(|| {
+                        format!("{} {}", op.method.to_uppercase(), op.route_pattern)
+                    });
+
+                    let badge = navigation::HttpMethod::from_str(&op.method)
+                        .map(navigation::Badge::for_method);
+
+                    (QuickNavKind::Operation, title, op.anchor_tag.clone(), badge)
+                })
+                .collect(),
+        };
+
+        for (kind, child_title, anchor, badge) in children {
+            let uri = format!("{}#{}", uri, anchor);
+
+            if !seen_uris.insert(uri.clone()) {
+                continue;
+            }
+
+            entries.push(QuickNavEntry {
+                kind,
+                title: child_title,
+                subtitle: Some(title.clone()),
+                uri,
+                tab: tab.clone(),
+                badge,
+            });
+        }
+    }
+
+    entries
+}
+
+/// Sorts the pages by where they first appear in the navigations, starting
+/// from the root one. The rest are sorted by their URI path.
+fn in_navigation_order<'a>(
+    project: &Project,
+    pages: Vec<PageHandle<'a>>,
+    ctx: &RenderContext,
+    opts: Option<&RenderOptions>,
+) -> Vec<PageHandle<'a>> {
+    let nav_paths = project
+        .tabs()
+        .map(|tabs| tabs.nav_paths())
+        .unwrap_or_else(|| vec![String::from("/")]);
+
+    // NOTE: The navigation links have any prefixes applied to them, so we
+    // compare against the final version of the page links.
+    let mut order: HashMap<PathBuf, usize> = HashMap::new();
+
+    for navigation in nav_paths
+        .iter()
+        .filter_map(|path| project.navigation(opts, path).ok())
+    {
+        for item in navigation.flattened_links() {
+            let href = item.href().unwrap();
+
+            // Anchors point inside a page that's listed on its own
+            if href.contains('#') {
+                continue;
+            }
+
+            let next = order.len();
+            order.entry(navigation::link_identity(href)).or_insert(next);
+        }
+    }
+
+    let mut pages = pages
+        .into_iter()
+        .map(|page| {
+            let identity =
+                navigation::link_identity(&markdown::parser::to_final_link(page.uri_path(), ctx));
+
+            (order.get(&identity).copied(), page)
+        })
+        .collect::<Vec<_>>();
+
+    pages.sort_by(|(a_order, a), (b_order, b)| match (a_order, b_order) {
+        (Some(a_order), Some(b_order)) => a_order.cmp(b_order),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.uri_path().cmp(b.uri_path()),
+    });
+
+    pages.into_iter().map(|(_, page)| page).collect()
+}
+
+fn tutorial_of<'t>(tutorials: &'t [Tutorial], page: &PageHandle) -> Option<&'t Tutorial> {
+    tutorials
+        .iter()
+        .find(|t| t.steps.iter().any(|step| step == page.fs_path()))
+}
+
+/// Moves the steps of each tutorial to where its first listed step is, in
+/// the tutorial's order.
+fn with_tutorials_grouped<'a>(
+    tutorials: &[Tutorial],
+    pages: Vec<PageHandle<'a>>,
+) -> Vec<PageHandle<'a>> {
+    let mut out = vec![];
+    let mut grouped = HashSet::new();
+
+    for page in &pages {
+        if grouped.contains(page.fs_path()) {
+            continue;
+        }
+
+        let Some(tutorial) = tutorial_of(tutorials, page) else {
+            out.push(page.clone());
+            continue;
+        };
+
+        for step in &tutorial.steps {
+            if let Some(step_page) = pages.iter().find(|p| p.fs_path() == step) {
+                if grouped.insert(step_page.fs_path().to_path_buf()) {
+                    out.push(step_page.clone());
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    use super::*;
+
+    const SPEC: &str = indoc! {r#"
+    openapi: 3.0.0
+    info:
+      title: Trees
+      version: 1.0.0
+    tags:
+      - name: Trees
+      - name: Leaves
+    paths:
+      /tree:
+        get:
+          summary: Get the tree
+          tags: [Trees, Leaves]
+          responses:
+            '200':
+              description: A tree
+      /tree/leaves:
+        get:
+          tags: [Leaves]
+          responses:
+            '200':
+              description: Some leaves
+    "#};
+
+    fn project(files: Vec<(&str, &str)>) -> Project {
+        let mut input = vec![
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text("---\ntitle: Something\n".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("---".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from("README.md"),
+                content: InputContent::Text("# Home".to_string()),
+            },
+        ];
+        input.retain(|default| {
+            !files
+                .iter()
+                .any(|(path, _)| default.path == Path::new(path))
+        });
+        input.extend(files.into_iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        Project::from_file_list(input).unwrap()
+    }
+
+    fn uris(entries: &[QuickNavEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.uri.as_str()).collect()
+    }
+
+    #[test]
+    fn lists_pages_in_navigation_order() {
+        let project = project(vec![
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {"
+                - heading: Guides
+                  items:
+                    - label: Second
+                      href: /second.md
+                    - label: First
+                      href: /first.md
+                "},
+            ),
+            ("first.md", "# First"),
+            ("second.md", "# Second"),
+            ("orphan.md", "# Orphan"),
+        ]);
+
+        let entries = project.quick_nav_manifest(None);
+
+        assert_eq!(
+            uris(&entries),
+            vec!["/second", "/first", "/", "/orphan"],
+            "{:#?}",
+            entries
+        );
+        assert_eq!(entries[0].subtitle.as_deref(), Some("Guides"));
+        assert_eq!(entries[0].kind, QuickNavKind::Page);
+    }
+
+    #[test]
+    fn lists_headings_after_their_page() {
+        let project = project(vec![(
+            "README.md",
+            indoc! {"
+            # Home
+
+            ## Installing
+
+            ### On Mac
+            "},
+        )]);
+
+        assert_eq!(
+            project.quick_nav_manifest(None),
+            vec![
+                QuickNavEntry {
+                    kind: QuickNavKind::Page,
+                    title: "Home".to_string(),
+                    subtitle: None,
+                    uri: "/".to_string(),
+                    tab: String::new(),
+                    badge: None,
+                },
+                QuickNavEntry {
+                    kind: QuickNavKind::Heading,
+                    title: "Installing".to_string(),
+                    subtitle: Some("Home".to_string()),
+                    uri: "/#installing".to_string(),
+                    tab: String::new(),
+                    badge: None,
+                },
+                QuickNavEntry {
+                    kind: QuickNavKind::Heading,
+                    title: "On Mac".to_string(),
+                    subtitle: Some("Home".to_string()),
+                    uri: "/#on-mac".to_string(),
+                    tab: String::new(),
+                    badge: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_operations_once() {
+        let project = project(vec![
+            (
+                SETTINGS_FILE_NAME,
+                indoc! {"
+                ---
+                title: Something
+                open_api:
+                  - spec_file: openapi.yaml
+                    uri_prefix: /api
+                "},
+            ),
+            ("openapi.yaml", SPEC),
+        ]);
+
+        let operations = project
+            .quick_nav_manifest(None)
+            .into_iter()
+            .filter(|e| e.kind == QuickNavKind::Operation)
+            .collect::<Vec<_>>();
+
+        // `Get the tree` is under both tags, but only listed under the
+        // first one. Without a summary, operations are named by their method
+        // and path.
+        assert_eq!(operations.len(), 2, "{:#?}", operations);
+        assert_eq!(operations[0].title, "GET /tree/leaves");
+        assert!(operations[0].uri.starts_with("/api/leaves#"));
+        assert_eq!(operations[0].subtitle.as_deref(), Some("Leaves"));
+        assert_eq!(operations[1].title, "Get the tree");
+        assert_eq!(operations[1].uri, "/api/trees#get-the-tree");
+    }
+
+    #[test]
+    fn carries_badges_from_the_navigation() {
+        let project = project(vec![
+            (
+                SETTINGS_FILE_NAME,
+                indoc! {"
+                ---
+                title: Something
+                open_api:
+                  - spec_file: openapi.yaml
+                    uri_prefix: /api
+                "},
+            ),
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {"
+                - heading: Guides
+                  items:
+                    - href: /first.md
+                      badge:
+                        text: Beta
+                        color: orange
+                "},
+            ),
+            ("first.md", "# First"),
+            ("openapi.yaml", SPEC),
+        ]);
+
+        let entries = project.quick_nav_manifest(None);
+
+        assert_eq!(
+            entries[0].badge,
+            Some(navigation::Badge {
+                text: "Beta".to_string(),
+                color: "orange".to_string(),
+            })
+        );
+
+        let operation = entries
+            .iter()
+            .find(|e| e.kind == QuickNavKind::Operation)
+            .unwrap();
+        assert_eq!(
+            operation.badge.as_ref().map(|b| b.text.as_str()),
+            Some("GET")
+        );
+    }
+
+    #[test]
+    fn leaves_out_drafts_and_unindexed_pages() {
+        let project = project(vec![
+            ("draft.md", "---\ndraft: true\n---\n# Draft"),
+            ("hidden.md", "---\nsearch: false\n---\n# Hidden"),
+            ("unindexed.md", "---\nnoindex: true\n---\n# Unindexed"),
+        ]);
+
+        assert_eq!(uris(&project.quick_nav_manifest(None)), vec!["/"]);
+
+        let opts = RenderOptions {
+            publish_drafts: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            uris(&project.quick_nav_manifest(Some(&opts))),
+            vec!["/", "/draft"]
+        );
+    }
+
+    #[test]
+    fn scopes_entries_to_their_tab() {
+        let project = project(vec![
+            (
+                SETTINGS_FILE_NAME,
+                indoc! {"
+                ---
+                title: Something
+                tabs:
+                  - label: Guides
+                    path: /
+                  - label: API
+                    path: /api
+                "},
+            ),
+            ("api/navigation.yaml", "- heading: Something\n"),
+            ("api/users.md", "# Users\n\n## Listing users"),
+        ]);
+
+        let tabs = project
+            .quick_nav_manifest(None)
+            .into_iter()
+            .map(|e| (e.uri, e.tab))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            tabs,
+            vec![
+                ("/".to_string(), "/".to_string()),
+                ("/api/users".to_string(), "/api".to_string()),
+                ("/api/users#listing-users".to_string(), "/api".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_tutorial_steps_under_their_title() {
+        let project = project(vec![
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {"
+                - heading: Guides
+                  items:
+                    - href: /start/configure.md
+                      label: Configure
+                    - href: /about.md
+                      label: About
+                    - href: /start/install.md
+                      label: Install
+                "},
+            ),
+            (
+                "start/_tutorial.yaml",
+                "title: Getting started\nsteps:\n  - install.md\n  - configure.md\n",
+            ),
+            ("start/install.md", "# Install"),
+            ("start/configure.md", "# Configure"),
+            ("about.md", "# About"),
+        ]);
+
+        let pages = project
+            .quick_nav_manifest(None)
+            .into_iter()
+            .filter(|e| e.kind == QuickNavKind::Page)
+            .map(|e| (e.uri, e.subtitle))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            pages,
+            vec![
+                (
+                    "/start/install".to_string(),
+                    Some("Getting started".to_string())
+                ),
+                (
+                    "/start/configure".to_string(),
+                    Some("Getting started".to_string())
+                ),
+                ("/about".to_string(), Some("Guides".to_string())),
+                ("/".to_string(), None),
+            ]
+        );
+    }
+}
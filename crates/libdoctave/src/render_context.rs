@@ -1,13 +1,16 @@
 use std::collections::HashMap;
 use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{Arc, Mutex};
 use std::{ffi::OsStr, path::PathBuf};
 
+use crate::expressions::Value;
 use crate::open_api::model::Components;
 use crate::page_kind::PageKind;
 use crate::project::Asset;
-use crate::Project;
-use crate::{markdown::CustomComponentHandle, settings::Settings, RenderOptions, BAKED_COMPONENTS};
+use crate::{
+    markdown::CustomComponentHandle, settings::Settings, RenderOptions, UrlStyle, BAKED_COMPONENTS,
+};
+use crate::{Error, Project};
 
 /// This struct represents the context for rendering a page.
 /// The RenderOptions struct is provided from outside of
@@ -20,12 +23,32 @@ pub(crate) struct RenderContext<'a> {
     pub relative_url_base: Option<String>,
     pub file_context: Option<FileContext>,
     pub pages: &'a [PageKind],
+    /// The project being rendered, if the context was set up with
+    /// [`Self::with_project`].
+    pub project: Option<&'a Project>,
     /// Ballad custom components defined in `_components`.
     pub custom_components: &'a [CustomComponentHandle],
+    /// Components that can't be used on the page being rendered, from its
+    /// `components_disabled` frontmatter.
+    pub disabled_components: Vec<String>,
     pub assets: &'a [Asset],
     pub openapi_components: &'a HashMap<String, Components>,
     /// Global timestamp for cache busting image URLs
     pub cache_bust_timestamp: String,
+    /// Variables available to expressions on the page being rendered, e.g.
+    /// the spec details in an OpenAPI overview template.
+    pub expression_globals: Vec<(String, Value)>,
+    /// Issues found while rendering that don't stop the page from rendering.
+    /// Shared between clones, so that components rendered with a copy of the
+    /// context report to the same list.
+    pub warnings: Arc<Mutex<Vec<Error>>>,
+    /// Whether a built-in component, like `<Button>`, is being rendered. Its
+    /// markup is trusted, so the project's HTML policy doesn't apply to it.
+    pub in_built_in_component: bool,
+    /// Whether relative image URLs are expanded from the relative URL base.
+    /// Needed for Markdown fragments, which are shown on some other page than
+    /// the file they come from.
+    pub expand_image_urls: bool,
 }
 
 lazy_static! {
@@ -36,23 +59,24 @@ lazy_static! {
 
 impl Default for RenderContext<'_> {
     fn default() -> Self {
-        let now = SystemTime::now();
-        let cache_bust_timestamp = now
-            .duration_since(UNIX_EPOCH)
-            .expect("Time went backwards")
-            .as_millis()
-            .to_string();
+        let cache_bust_timestamp = crate::utils::now_millis().to_string();
 
         RenderContext {
             options: &DEFAULT_OPTS,
             settings: &DEFAULT_SETTINGS,
             pages: &[],
+            project: None,
             relative_url_base: None,
             file_context: None,
             custom_components: &BAKED_COMPONENTS,
+            disabled_components: vec![],
             assets: &[],
             openapi_components: &DEFAULT_OPENAPI_COMPONENTS,
             cache_bust_timestamp,
+            expression_globals: vec![],
+            warnings: Arc::new(Mutex::new(vec![])),
+            in_built_in_component: false,
+            expand_image_urls: false,
         }
     }
 }
@@ -66,6 +90,7 @@ impl<'a> RenderContext<'a> {
     ///
     /// Calls the various setters for the context.
     pub fn with_project(&mut self, project: &'a Project) {
+        self.project = Some(project);
         self.with_pages(&project.pages);
         self.with_settings(&project.settings);
         self.with_custom_components(&project.custom_components);
@@ -85,6 +110,33 @@ impl<'a> RenderContext<'a> {
         self.custom_components = components;
     }
 
+    /// Disables the components in the `components_disabled` frontmatter of
+    /// the page being rendered. Names without a prefix are custom components,
+    /// like `Fancy` for `Component.Fancy`, unless they ship with Docapella,
+    /// like `Card`.
+    pub fn with_disabled_components(&mut self, names: Vec<String>) {
+        self.disabled_components = names
+            .into_iter()
+            .map(|name| {
+                if name.starts_with("Component.")
+                    || name.starts_with("Topic.")
+                    || BAKED_COMPONENTS.iter().any(|c| c.matches_title(&name))
+                {
+                    name
+                } else {
+                    format!("Component.{}", name)
+                }
+            })
+            .collect();
+    }
+
+    /// The components that can be used on the page being rendered.
+    pub fn enabled_components(&self) -> impl Iterator<Item = &'a CustomComponentHandle> + '_ {
+        self.custom_components.iter().filter(|component| {
+            !matches!(component.title(), Ok(title) if self.disabled_components.contains(&title))
+        })
+    }
+
     pub fn with_assets(&mut self, assets: &'a [Asset]) {
         self.assets = assets;
     }
@@ -93,6 +145,10 @@ impl<'a> RenderContext<'a> {
         self.openapi_components = components;
     }
 
+    pub fn with_expression_globals(&mut self, globals: Vec<(String, Value)>) {
+        self.expression_globals = globals;
+    }
+
     pub fn with_file_context(&mut self, file_context: FileContext) {
         self.file_context = Some(file_context);
     }
@@ -155,6 +211,13 @@ impl<'a> RenderContext<'a> {
     pub fn should_expand_relative_uris(&self) -> bool {
         self.relative_url_base.is_some()
     }
+
+    /// The URL style from the render options, falling back to the settings.
+    pub fn url_style(&self) -> UrlStyle {
+        self.options
+            .url_style
+            .unwrap_or_else(|| self.settings.url_style())
+    }
 }
 
 impl std::fmt::Debug for RenderContext<'_> {
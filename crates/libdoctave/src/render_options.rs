@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Rules for rendering a Doctave page, for rewriting links,
@@ -8,8 +11,13 @@ use std::collections::HashMap;
 /// Note that bool fields are `false` by default according to
 /// Rust's default rules
 #[derive(Default)]
+#[serde(default)]
 pub struct RenderOptions {
     pub bust_image_caches: bool,
+    /// The value images are cache busted with, e.g. a hash of the project's
+    /// files. Defaults to the time of rendering, which changes the output
+    /// every time it's rendered.
+    pub cache_bust_value: Option<String>,
     /// Convert any .md links to their web equivalent
     ///
     /// NOTE:: Cannot be used with `fsify_internal_urls`
@@ -20,8 +28,228 @@ pub struct RenderOptions {
     /// NOTE:: Cannot be used with `webbify_internal_urls`
     pub fsify_internal_urls: bool,
     pub disable_syntax_highlighting: bool,
+    /// Highlight code blocks at build time, attaching the highlighted tokens
+    /// to the code nodes in the AST.
+    pub highlight_code: bool,
     pub link_rewrites: HashMap<String, String>,
     pub prefix_asset_urls: Option<String>,
     pub prefix_link_urls: Option<String>,
     pub download_url_prefix: Option<String>,
+    /// The locale to render for. Pages with a translation in this locale are
+    /// preferred over the default locale, and navigation labels use their
+    /// translations when available.
+    pub locale: Option<String>,
+    /// Render drafts and pages matched by `exclude` in the settings as if they
+    /// were published. Used by the dev server.
+    pub publish_drafts: bool,
+    /// How expressions referencing variables or fields that don't exist are
+    /// handled, e.g. `@page.titel` or a typo in a conditional.
+    pub undefined_variables: UndefinedVariables,
+    /// Overrides the `url_style` in the project settings.
+    pub url_style: Option<UrlStyle>,
+    /// Resized versions of images under `_assets`, keyed by the path of the
+    /// original, e.g. `_assets/screenshot.png`. Images with variants are
+    /// rendered with a `srcset`.
+    pub image_variants: HashMap<PathBuf, ImageVariants>,
+    /// Keep the content placed in component slots in `Slot` nodes, instead
+    /// of replacing the slots with it. For debugging components.
+    pub debug_slots: bool,
+}
+
+/// The resized and converted versions generated for an image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageVariants {
+    /// The `sizes` attribute, e.g. `(max-width: 1280px) 100vw, 1280px`
+    pub sizes: String,
+    pub variants: Vec<ImageVariant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageVariant {
+    /// Path of the variant in the build, e.g. `_assets/screenshot-640w.webp`
+    pub path: PathBuf,
+    pub width: u32,
+    /// E.g. `image/webp`
+    pub mime_type: String,
+}
+
+/// How to handle lookups of variables and object fields that don't exist in
+/// expressions, conditionals, and component attributes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UndefinedVariables {
+    /// Fail rendering the page with an `INVALID_EXPRESSION` error
+    #[default]
+    Error,
+    /// Evaluate to `null`, and report the lookup from `Project::expression_warnings`
+    Warn,
+    /// Evaluate to `null`
+    Ignore,
+}
+
+/// How page URLs are written in links, and how pages are laid out in a
+/// static build. Pages are identified by their URI path without a trailing
+/// slash internally, e.g. `/guides`, whatever the style.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UrlStyle {
+    /// `/guides`, built as `guides.html` or `guides/index.html`
+    #[default]
+    NoTrailingSlash,
+    /// `/guides/`, built as `guides/index.html`
+    TrailingSlash,
+    /// `/guides.html`, built as `guides.html`
+    HtmlExtension,
+}
+
+impl UrlStyle {
+    /// Writes an absolute link to a page in this style. Links to assets,
+    /// e.g. `/_assets/logo.png`, and external links are left as they are.
+    pub fn apply(&self, link: &str) -> String {
+        // Links are left as they were written in the default style
+        if *self == UrlStyle::NoTrailingSlash || !link.starts_with('/') || link.starts_with("//") {
+            return link.to_string();
+        }
+
+        let (path, query) = match link.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (link, None),
+        };
+
+        let path = crate::strip_url_style(path);
+
+        if path == "/" || path.rsplit('/').next().unwrap_or("").contains('.') {
+            return link.to_string();
+        }
+
+        let mut out = match self {
+            UrlStyle::NoTrailingSlash => path.to_string(),
+            UrlStyle::TrailingSlash => format!("{}/", path),
+            UrlStyle::HtmlExtension => format!("{}.html", path),
+        };
+
+        if let Some(query) = query {
+            out.push('?');
+            out.push_str(query);
+        }
+
+        out
+    }
+
+    /// The file a page with the given URI path is written to in a static
+    /// build, relative to the output directory. Returns `None` when the
+    /// style doesn't dictate a layout.
+    pub(crate) fn out_path(&self, uri_path: &str) -> Option<PathBuf> {
+        let path = uri_path.trim_matches('/');
+
+        match self {
+            UrlStyle::NoTrailingSlash => None,
+            _ if path.is_empty() => Some(PathBuf::from("index.html")),
+            UrlStyle::TrailingSlash => Some(PathBuf::from(path).join("index.html")),
+            UrlStyle::HtmlExtension => Some(PathBuf::from(format!("{}.html", path))),
+        }
+    }
+}
+
+impl RenderOptions {
+    /// The same options, but with links left as they were written, so that
+    /// they can be checked against the files in the project.
+    pub(crate) fn without_link_rewrites(&self) -> Self {
+        RenderOptions {
+            bust_image_caches: false,
+            webbify_internal_urls: false,
+            fsify_internal_urls: false,
+            link_rewrites: HashMap::new(),
+            prefix_asset_urls: None,
+            prefix_link_urls: None,
+            image_variants: HashMap::new(),
+            ..self.clone()
+        }
+    }
+
+    /// A hash of the options that change how a page is rendered, for caching
+    /// rendered pages. Options that don't change the AST, like
+    /// `publish_drafts` which only decides which pages can be found, are
+    /// left out. The hash is stable for a given build of the library.
+    pub fn cache_key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.bust_image_caches.hash(&mut hasher);
+        self.cache_bust_value.hash(&mut hasher);
+        self.webbify_internal_urls.hash(&mut hasher);
+        self.fsify_internal_urls.hash(&mut hasher);
+        self.highlight_code.hash(&mut hasher);
+        self.prefix_asset_urls.hash(&mut hasher);
+        self.prefix_link_urls.hash(&mut hasher);
+        self.download_url_prefix.hash(&mut hasher);
+        self.locale.hash(&mut hasher);
+        self.undefined_variables.hash(&mut hasher);
+        self.url_style.hash(&mut hasher);
+        self.debug_slots.hash(&mut hasher);
+
+        // Maps are hashed in a fixed order, so equal maps hash the same
+        let mut link_rewrites = self.link_rewrites.iter().collect::<Vec<_>>();
+        link_rewrites.sort();
+        link_rewrites.hash(&mut hasher);
+
+        let mut image_variants = self.image_variants.iter().collect::<Vec<_>>();
+        image_variants.sort_by_key(|(path, _)| *path);
+        for (path, variants) in image_variants {
+            path.hash(&mut hasher);
+            variants.sizes.hash(&mut hasher);
+            for variant in &variants.variants {
+                variant.path.hash(&mut hasher);
+                variant.width.hash(&mut hasher);
+                variant.mime_type.hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cache_key_changes_with_link_rewrites() {
+        let opts = RenderOptions::default();
+        let rewritten = RenderOptions {
+            link_rewrites: HashMap::from([("/a".to_string(), "/b".to_string())]),
+            ..Default::default()
+        };
+
+        assert_ne!(opts.cache_key(), rewritten.cache_key());
+    }
+
+    #[test]
+    fn cache_key_ignores_unrelated_options() {
+        let opts = RenderOptions::default();
+        let drafts = RenderOptions {
+            publish_drafts: true,
+            disable_syntax_highlighting: true,
+            ..Default::default()
+        };
+
+        assert_eq!(opts.cache_key(), drafts.cache_key());
+    }
+
+    #[test]
+    fn cache_key_does_not_depend_on_map_order() {
+        let rewrites = (0..20)
+            .map(|i| (format!("/{}", i), format!("/new/{}", i)))
+            .collect::<Vec<_>>();
+
+        let a = RenderOptions {
+            link_rewrites: rewrites.iter().cloned().collect(),
+            ..Default::default()
+        };
+        let b = RenderOptions {
+            link_rewrites: rewrites.into_iter().rev().collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(a.cache_key(), b.cache_key());
+    }
 }
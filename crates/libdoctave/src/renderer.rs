@@ -1,5 +1,6 @@
-use crate::{ContentApiResponse, Result};
-use color_generator::{Appearance, ColorGenerator};
+use crate::settings::ColorsV2;
+use crate::single_page::SinglePage;
+use crate::{AstNode, ContentApiResponse, Project, Result};
 use minijinja::{self, context, Environment, Error, Value};
 use serde_json;
 
@@ -58,6 +59,60 @@ impl Renderer {
             _ => todo!(),
         }
     }
+
+    /// Renders a section produced by `Project::render_section` into a
+    /// standalone HTML document.
+    pub fn render_single_page(&self, page: &SinglePage, project: &Project) -> Result<String> {
+        let page_value = serde_json::to_value(page).expect("Failed to serialize page");
+        let project_value = serde_json::json!({
+            "settings": &*project.settings,
+            "custom_css": &project.custom_css,
+        });
+
+        let template = self
+            .env
+            .get_template("layouts/single_page.html.jinja")
+            .expect("Failed to get template");
+
+        let rendered = template
+            .render(context! {
+                page => page_value,
+                project => project_value,
+            })
+            .expect("Failed to render template");
+
+        Ok(rendered)
+    }
+
+    /// Renders the AST of a page into HTML, with the same markup as the
+    /// content of the page in `render_page`, without the rest of the layout.
+    ///
+    /// Nodes that don't have a template are rendered as an empty element
+    /// with their kind and props, for the frontend to fill in. Nodes that the
+    /// templates can't render return an error instead of panicking, so that
+    /// servers rendering pages on request can recover from them.
+    pub fn render_ast(&self, root: &AstNode) -> Result<String> {
+        self.env
+            .get_template("layouts/fragment.html.jinja")
+            .and_then(|template| {
+                template.render(context! {
+                    root => Value::from_serialize(root),
+                })
+            })
+            .map_err(render_error)
+    }
+}
+
+fn render_error(error: Error) -> crate::Error {
+    crate::Error {
+        code: crate::Error::RENDER_ERROR,
+        message: "Could not render the page".to_owned(),
+        description: error.to_string(),
+        file: None,
+        position: None,
+        owner: None,
+        suggestions: vec![],
+    }
 }
 
 /// Returns the index of the initial openapi operation tab
@@ -146,29 +201,17 @@ fn actual_type_name(schema: &Value) -> std::result::Result<String, Error> {
     Ok(result_type_name)
 }
 
-fn color_scale_css(accent: &Value, gray_scale: &Value) -> std::result::Result<String, Error> {
-    let accent = accent.as_str().ok_or_else(|| {
-        Error::new(
-            minijinja::ErrorKind::InvalidOperation,
-            "color_scale_css first argument must be a string",
-        )
-    })?;
-    let gray_scale = gray_scale.as_str().ok_or_else(|| {
-        Error::new(
-            minijinja::ErrorKind::InvalidOperation,
-            "color_scale_css second argument must be a string",
-        )
-    })?;
-
-    let generator = ColorGenerator::new();
-
-    let light_palette = generator.generate_scale(Appearance::Light, accent, gray_scale, "#ffffff");
-    let dark_palette = generator.generate_scale(Appearance::Dark, accent, gray_scale, "#000000");
-
-    let light_css = light_palette.generate_css("accent", ":root, .light, .light-theme");
-    let dark_css = dark_palette.generate_css("accent", ".dark, .dark-theme");
-
-    Ok(format!("{}\n\n{}", light_css, dark_css))
+fn color_scale_css(colors: &Value) -> std::result::Result<String, Error> {
+    let colors: ColorsV2 = serde_json::to_value(colors)
+        .and_then(serde_json::from_value)
+        .map_err(|e| {
+            Error::new(
+                minijinja::ErrorKind::InvalidOperation,
+                format!("color_scale_css argument must be the theme colors: {}", e),
+            )
+        })?;
+
+    Ok(colors.css())
 }
 
 /// Returns whether a string starts with a given prefix
@@ -258,3 +301,681 @@ fn flatten_response_examples(response: &Value) -> std::result::Result<Value, Err
 
     Ok(Value::from_serialize(all_examples))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::markdown::console::{ConsoleLine, ConsoleLineKind, ConsoleSession};
+    use crate::markdown::highlight::HighlightToken;
+    use crate::open_api::ast::{CollapsedSchema, SchemaAst};
+    use crate::primitive_components::{
+        callout::CalloutType, download::FileInfo, CBox, Callout, CodeSelect, Download, Embed, Flex,
+        Grid, Responsive, Step, Tab, Tabs,
+    };
+    use crate::renderable_ast::{
+        Attribute, AttributeValue, ImageAlign, ImageAttributes, LinkAttributes, NodeKind,
+        ResponsiveImage, TableAlignment,
+    };
+    use crate::test_support::assert_snapshot;
+    use crate::Position;
+
+    fn node(kind: NodeKind, children: Vec<AstNode>) -> AstNode {
+        AstNode {
+            kind,
+            pos: Position::default(),
+            children,
+        }
+    }
+
+    fn text(value: &str) -> AstNode {
+        node(
+            NodeKind::Text {
+                value: value.to_string(),
+            },
+            vec![],
+        )
+    }
+
+    fn paragraph(value: &str) -> AstNode {
+        node(NodeKind::Paragraph, vec![text(value)])
+    }
+
+    fn code(value: &str, language: &str, label: Option<&str>, title: Option<&str>) -> AstNode {
+        node(
+            NodeKind::Code {
+                value: value.to_string(),
+                rendered_value: None,
+                highlighted: None,
+                language: Some(language.to_string()),
+                title: title.map(String::from),
+                label: label.map(String::from),
+                raw: false,
+                show_whitespace: false,
+                console: None,
+            },
+            vec![],
+        )
+    }
+
+    fn token(text: &str, class: Option<&str>) -> HighlightToken {
+        HighlightToken {
+            text: text.to_string(),
+            class: class.map(String::from),
+        }
+    }
+
+    fn tab(title: &str, id: &str, selected: bool, content: &str) -> AstNode {
+        node(
+            NodeKind::Tab(Tab {
+                title: title.to_string(),
+                id: id.to_string(),
+                label_id: format!("{}--label", id),
+                selected,
+            }),
+            vec![paragraph(content)],
+        )
+    }
+
+    fn step(title: &str, index: usize, content: &str) -> AstNode {
+        node(
+            NodeKind::Step(Step {
+                title: title.to_string(),
+                number: index.to_string(),
+                id: title.to_lowercase(),
+                index,
+                total: 2,
+            }),
+            vec![paragraph(content)],
+        )
+    }
+
+    /// A node of every kind that has a template, named after the snapshot
+    /// it's compared against.
+    fn nodes() -> Vec<(&'static str, AstNode)> {
+        vec![
+            ("text", text("Fish & chips")),
+            ("paragraph", paragraph("Hello")),
+            ("emphasis", node(NodeKind::Emphasis, vec![text("emphasis")])),
+            ("strong", node(NodeKind::Strong, vec![text("strong")])),
+            ("delete", node(NodeKind::Delete, vec![text("gone")])),
+            ("break", node(NodeKind::Break, vec![])),
+            (
+                "heading",
+                node(
+                    NodeKind::Heading {
+                        level: 2,
+                        slug: "getting-started".to_string(),
+                        anchor_link: true,
+                    },
+                    vec![text("Getting started")],
+                ),
+            ),
+            (
+                "slot",
+                node(
+                    NodeKind::Slot {
+                        name: Some("footer".to_string()),
+                        filled: true,
+                    },
+                    vec![paragraph("Filled in")],
+                ),
+            ),
+            (
+                "html_block",
+                node(
+                    NodeKind::HtmlBlock {
+                        name: "div".to_string(),
+                        attributes: vec![Attribute {
+                            key: "class".to_string(),
+                            value: Some(AttributeValue::Literal("custom".to_string())),
+                        }],
+                    },
+                    vec![paragraph("In a div")],
+                ),
+            ),
+            (
+                "html_tag",
+                node(
+                    NodeKind::HtmlTag {
+                        value: "<kbd>".to_string(),
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "link",
+                node(
+                    NodeKind::Link {
+                        url: "guide?page=2&sort=asc".to_string(),
+                        title: None,
+                        file: None,
+                        attributes: LinkAttributes {
+                            target: Some("_blank".to_string()),
+                            rel: Some("noopener".to_string()),
+                            class: None,
+                        },
+                    },
+                    vec![text("Guide")],
+                ),
+            ),
+            (
+                "link_to_file",
+                node(
+                    NodeKind::Link {
+                        url: "report.csv".to_string(),
+                        title: None,
+                        file: Some(FileInfo {
+                            extension: Some("csv".to_string()),
+                            size_bytes: Some(2300),
+                            size: Some("2.3 kB".to_string()),
+                            download: true,
+                        }),
+                        attributes: LinkAttributes::default(),
+                    },
+                    vec![text("Report")],
+                ),
+            ),
+            (
+                "list",
+                node(
+                    NodeKind::List {
+                        ordered: false,
+                        start: None,
+                        spread: false,
+                    },
+                    vec![
+                        node(NodeKind::ListItem { spread: false }, vec![text("One")]),
+                        node(NodeKind::ListItem { spread: false }, vec![text("Two")]),
+                    ],
+                ),
+            ),
+            (
+                "list_ordered",
+                node(
+                    NodeKind::List {
+                        ordered: true,
+                        start: Some(1),
+                        spread: false,
+                    },
+                    vec![node(
+                        NodeKind::ListItem { spread: false },
+                        vec![text("First")],
+                    )],
+                ),
+            ),
+            (
+                "task_list_item",
+                node(
+                    NodeKind::List {
+                        ordered: false,
+                        start: None,
+                        spread: false,
+                    },
+                    vec![
+                        node(
+                            NodeKind::TaskListItem {
+                                checked: true,
+                                spread: false,
+                            },
+                            vec![text("Done")],
+                        ),
+                        node(
+                            NodeKind::TaskListItem {
+                                checked: false,
+                                spread: false,
+                            },
+                            vec![text("To do")],
+                        ),
+                    ],
+                ),
+            ),
+            (
+                "footnote_reference",
+                node(
+                    NodeKind::FootnoteReference {
+                        number: 1,
+                        id: "fnref-1".to_string(),
+                        definition_id: "fn-1".to_string(),
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "footnotes",
+                node(
+                    NodeKind::Footnotes,
+                    vec![node(
+                        NodeKind::FootnoteDefinition {
+                            number: 1,
+                            id: "fn-1".to_string(),
+                            reference_ids: vec!["fnref-1".to_string()],
+                        },
+                        vec![paragraph("A footnote")],
+                    )],
+                ),
+            ),
+            (
+                "definition_list",
+                node(
+                    NodeKind::DefinitionList,
+                    vec![
+                        node(NodeKind::DefinitionTerm, vec![text("Term")]),
+                        node(
+                            NodeKind::DefinitionDescription,
+                            vec![paragraph("Definition")],
+                        ),
+                    ],
+                ),
+            ),
+            ("code", code("if a < b {}", "rust", None, Some("main.rs"))),
+            (
+                "code_highlighted",
+                node(
+                    NodeKind::Code {
+                        value: "let a = 1;".to_string(),
+                        rendered_value: None,
+                        highlighted: Some(vec![
+                            token("let", Some("storage type rust")),
+                            token(" a ", None),
+                            token("=", Some("keyword operator rust")),
+                            token(" ", None),
+                            token("1", Some("constant numeric rust")),
+                            token(";", Some("punctuation terminator rust")),
+                        ]),
+                        language: Some("rust".to_string()),
+                        title: None,
+                        label: None,
+                        raw: false,
+                        show_whitespace: false,
+                        console: None,
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "code_console",
+                node(
+                    NodeKind::Code {
+                        value: "cargo build\nFinished".to_string(),
+                        rendered_value: None,
+                        highlighted: None,
+                        language: Some("console".to_string()),
+                        title: None,
+                        label: None,
+                        raw: false,
+                        show_whitespace: false,
+                        console: Some(ConsoleSession {
+                            lines: vec![
+                                ConsoleLine {
+                                    kind: ConsoleLineKind::Command,
+                                    prompt: Some("$ ".to_string()),
+                                    text: "cargo build".to_string(),
+                                },
+                                ConsoleLine {
+                                    kind: ConsoleLineKind::Output,
+                                    prompt: None,
+                                    text: "Finished".to_string(),
+                                },
+                            ],
+                            commands: "cargo build".to_string(),
+                        }),
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "inline_code",
+                node(
+                    NodeKind::InlineCode {
+                        value: "a < b".to_string(),
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "math",
+                node(
+                    NodeKind::Math {
+                        value: "e = mc^2".to_string(),
+                        display_mode: true,
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "inline_math",
+                node(
+                    NodeKind::InlineMath {
+                        value: "x^2".to_string(),
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "code_select",
+                node(
+                    NodeKind::CodeSelect(CodeSelect {
+                        id: "usage".to_string(),
+                        option_ids: vec![
+                            "usage--code-rust".to_string(),
+                            "usage--code-python".to_string(),
+                        ],
+                    }),
+                    vec![
+                        code("run()", "rust", Some("Rust"), Some("Usage")),
+                        code("run()", "python", Some("Python"), Some("Usage")),
+                    ],
+                ),
+            ),
+            (
+                "image",
+                node(
+                    NodeKind::Image {
+                        url: "cat.png".to_string(),
+                        alt: "A cat".to_string(),
+                        title: Some("Cat".to_string()),
+                        responsive: None,
+                        attributes: ImageAttributes::default(),
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "image_with_caption",
+                node(
+                    NodeKind::Image {
+                        url: "cat.png".to_string(),
+                        alt: "A cat".to_string(),
+                        title: Some("Cat".to_string()),
+                        responsive: None,
+                        attributes: ImageAttributes {
+                            width: Some("600".to_string()),
+                            height: None,
+                            align: Some(ImageAlign::Center),
+                            caption: Some("Our cat".to_string()),
+                        },
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "image_responsive",
+                node(
+                    NodeKind::Image {
+                        url: "cat.png".to_string(),
+                        alt: "A cat".to_string(),
+                        title: Some("Cat".to_string()),
+                        responsive: Some(ResponsiveImage {
+                            srcset: "cat-640w.png 640w, cat.png 1280w".to_string(),
+                            webp_srcset: Some("cat-640w.webp 640w".to_string()),
+                            sizes: "100vw".to_string(),
+                        }),
+                        attributes: ImageAttributes {
+                            width: None,
+                            height: None,
+                            align: Some(ImageAlign::Right),
+                            caption: None,
+                        },
+                    },
+                    vec![],
+                ),
+            ),
+            (
+                "inline_svg",
+                node(
+                    NodeKind::InlineSvg {
+                        src: "arch.svg".to_string(),
+                        alt: "Diagram".to_string(),
+                        html: "<svg><rect></rect></svg>".to_string(),
+                    },
+                    vec![],
+                ),
+            ),
+            ("thematic_break", node(NodeKind::ThematicBreak, vec![])),
+            (
+                "blockquote",
+                node(NodeKind::BlockQuote, vec![paragraph("Quoted")]),
+            ),
+            (
+                "table",
+                node(
+                    NodeKind::Table {
+                        alignment: vec![TableAlignment::Left, TableAlignment::None],
+                    },
+                    vec![
+                        node(
+                            NodeKind::TableRow,
+                            vec![
+                                node(NodeKind::TableCell, vec![text("Name")]),
+                                node(NodeKind::TableCell, vec![text("Value")]),
+                            ],
+                        ),
+                        node(
+                            NodeKind::TableRow,
+                            vec![
+                                node(NodeKind::TableCell, vec![text("a")]),
+                                node(NodeKind::TableCell, vec![text("b")]),
+                            ],
+                        ),
+                    ],
+                ),
+            ),
+            (
+                "md_tabs",
+                node(
+                    NodeKind::Tabs(Tabs {
+                        sync_key: None,
+                        id: "setup".to_string(),
+                        selected: 0,
+                    }),
+                    vec![
+                        tab("macOS", "setup--tab-macos", true, "Use brew"),
+                        tab("Linux", "setup--tab-linux", false, "Use apt"),
+                    ],
+                ),
+            ),
+            (
+                "md_tabs_synced",
+                node(
+                    NodeKind::Tabs(Tabs {
+                        sync_key: Some("os".to_string()),
+                        id: "setup".to_string(),
+                        selected: 1,
+                    }),
+                    vec![
+                        tab("macOS", "setup--tab-macos", false, "Use brew"),
+                        tab("Linux", "setup--tab-linux", true, "Use apt"),
+                    ],
+                ),
+            ),
+            (
+                "steps",
+                node(
+                    NodeKind::Steps,
+                    vec![
+                        step("Install", 1, "Install the CLI"),
+                        step("Run", 2, "Run the build"),
+                    ],
+                ),
+            ),
+            (
+                "box",
+                node(NodeKind::Box(CBox::default()), vec![paragraph("In a box")]),
+            ),
+            (
+                "callout",
+                node(
+                    NodeKind::Callout(Callout {
+                        kind: CalloutType::Warning,
+                        title: Some("Careful".to_string()),
+                        ..Default::default()
+                    }),
+                    vec![paragraph("Mind the gap")],
+                ),
+            ),
+            (
+                "callout_collapsible",
+                node(
+                    NodeKind::Callout(Callout {
+                        collapsible: true,
+                        default_open: true,
+                        id: Some("details".to_string()),
+                        ..Default::default()
+                    }),
+                    vec![paragraph("More details")],
+                ),
+            ),
+            (
+                "flex",
+                node(
+                    NodeKind::Flex(Flex {
+                        gap: Responsive {
+                            md: Some(2),
+                            ..Responsive::new(1)
+                        },
+                        ..Default::default()
+                    }),
+                    vec![paragraph("In a flex")],
+                ),
+            ),
+            (
+                "grid",
+                node(
+                    NodeKind::Grid(Grid {
+                        columns: Responsive {
+                            sm: Some(2),
+                            lg: Some(3),
+                            ..Responsive::new(1)
+                        },
+                        ..Default::default()
+                    }),
+                    vec![paragraph("In a grid")],
+                ),
+            ),
+            (
+                "download",
+                node(
+                    NodeKind::Download(Download {
+                        src: "/_assets/report.csv".to_string(),
+                        label: "Q3 report".to_string(),
+                        file: FileInfo {
+                            extension: Some("csv".to_string()),
+                            size_bytes: Some(2300),
+                            size: Some("2.3 kB".to_string()),
+                            download: true,
+                        },
+                    }),
+                    vec![],
+                ),
+            ),
+            (
+                "embed",
+                node(
+                    NodeKind::Embed(Embed {
+                        provider: "youtube".to_string(),
+                        id: "abc123".to_string(),
+                        url: "https://www.youtube-nocookie.com/embed/abc123".to_string(),
+                        domain: "www.youtube-nocookie.com".to_string(),
+                        title: "Product tour".to_string(),
+                        aspect_ratio: "16:9".to_string(),
+                        privacy_mode: true,
+                    }),
+                    vec![],
+                ),
+            ),
+            (
+                "open_api_schema",
+                node(
+                    NodeKind::OpenAPISchema(SchemaAst {
+                        schemas: vec![],
+                        required: None,
+                        description_ast: None,
+                        title: None,
+                        type_name: "object".to_string(),
+                        format: None,
+                        pattern: None,
+                        default: None,
+                        deprecated: false,
+                        minimum: None,
+                        maximum: None,
+                        multiple_of: None,
+                        min_length: None,
+                        max_length: None,
+                        enumeration: None,
+                        example_string: None,
+                        combination_explanation: None,
+                        media_type: None,
+                        metadata: None,
+                        expanded: false,
+                        collapsed: Some(CollapsedSchema::Recursive {
+                            component_name: "Pet".to_string(),
+                        }),
+                    }),
+                    vec![],
+                ),
+            ),
+        ]
+    }
+
+    /// The lines of the HTML without their indentation or blank lines, so
+    /// that snapshots don't change with the whitespace of the templates.
+    fn lines(html: &str) -> serde_json::Value {
+        html.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .into()
+    }
+
+    #[test]
+    fn renders_every_kind_of_node() {
+        let renderer = Renderer::new().unwrap();
+
+        for (name, child) in nodes() {
+            let html = renderer
+                .render_ast(&node(NodeKind::Root, vec![child]))
+                .unwrap();
+
+            assert_snapshot(&format!("renderer/{}", name), &lines(&html));
+        }
+    }
+
+    #[test]
+    fn returns_an_error_for_nodes_the_templates_cannot_render() {
+        // Tables are rendered from their header row, which this one doesn't have
+        let root = node(
+            NodeKind::Root,
+            vec![node(NodeKind::Table { alignment: vec![] }, vec![])],
+        );
+
+        let error = Renderer::new().unwrap().render_ast(&root).unwrap_err();
+
+        assert_eq!(error.code, crate::Error::RENDER_ERROR);
+        assert_eq!(error.message, "Could not render the page");
+    }
+
+    #[test]
+    fn leaves_nodes_without_a_template_to_the_frontend() {
+        let root = node(
+            NodeKind::Root,
+            vec![node(
+                NodeKind::FootnoteDefinition {
+                    number: 1,
+                    id: "fn-1".to_string(),
+                    reference_ids: vec![],
+                },
+                vec![],
+            )],
+        );
+
+        let html = Renderer::new().unwrap().render_ast(&root).unwrap();
+
+        assert!(
+            html.contains(r#"data-type="footnote_definition""#),
+            "{}",
+            html
+        );
+        assert!(html.contains(r#""id":"fn-1""#), "{}", html);
+        assert!(html.contains(r#""number":1"#), "{}", html);
+    }
+}
@@ -1,15 +1,64 @@
+use crate::markdown;
+use crate::navigation::{self, Badge, HttpMethod};
 use crate::open_api::ast::PageAst;
+use crate::page_handle::PageHandle;
+use crate::render_context::RenderContext;
+use crate::settings::SearchBoosts;
 use crate::NodeKind;
 use crate::Project;
 use crate::Result;
 
+use std::collections::HashMap;
+
+/// Version of the `search.json` format. Bump this when the shape of the
+/// records changes, so that search UIs can tell the formats apart.
+///
+/// * 1: One record per page, the bare elasticlunr index
+/// * 2: One record per heading section, wrapped in `{ version, index }`
+/// * 3: Records carry the `scope` of the tab or subtab they belong to
+/// * 4: Records carry the `badge` and `badge_color` of their page's
+///   navigation item, or of their operation's method
+pub const SEARCH_INDEX_VERSION: u32 = 4;
+
 pub struct SearchIndex {
     index: elasticlunr::Index,
     doc_id: u64,
 }
 
+/// Boost weights for a single page: the project-wide weights multiplied by
+/// the page's `search_boost`.
+#[derive(Debug, Clone, Copy)]
+struct Boosts {
+    page: f32,
+    title: f32,
+    headings: f32,
+    body: f32,
+}
+
+impl Boosts {
+    fn new(weights: &SearchBoosts, page: f32) -> Self {
+        Boosts {
+            page,
+            title: weights.title * page,
+            headings: weights.headings * page,
+            body: weights.body * page,
+        }
+    }
+
+    fn apply(&self, doc: &mut DocumentBuilder) {
+        doc.boost = self.page.to_string();
+        doc.title_boost = self.title.to_string();
+        doc.headings_boost = self.headings.to_string();
+        doc.body_boost = self.body.to_string();
+    }
+}
+
 impl SearchIndex {
     pub fn new(project: &Project) -> Result<Self> {
+        Self::from_pages(project, project.published_pages(None))
+    }
+
+    pub(crate) fn from_pages(project: &Project, pages: Vec<PageHandle<'_>>) -> Result<Self> {
         let eindex = elasticlunr::IndexBuilder::new()
             .add_fields(&[
                 "title",
@@ -29,6 +78,14 @@ impl SearchIndex {
                 "openapi_path",
                 "openapi_method",
                 "kind",
+                "anchor",
+                "scope",
+                "badge",
+                "badge_color",
+                "boost",
+                "title_boost",
+                "headings_boost",
+                "body_boost",
             ])
             .save_docs(true)
             .build();
@@ -38,7 +95,30 @@ impl SearchIndex {
             doc_id: 0,
         };
 
-        for page in project.pages() {
+        let weights = &project.settings().search().boosts;
+
+        let mut ctx = RenderContext::default();
+        ctx.with_settings(&project.settings);
+        let badges = navigation::page_badges(project, None);
+
+        for page in pages {
+            if page.hidden_from_search().unwrap_or(false) {
+                continue;
+            }
+
+            if page.noindex() && !project.settings().search().include_noindex {
+                continue;
+            }
+
+            let boosts = Boosts::new(weights, page.search_boost().unwrap_or(1.0));
+            let scope = project
+                .tab_scope(page.uri_path())
+                .map(|scope| scope.path)
+                .unwrap_or_default();
+            let badge = badges.get(&navigation::link_identity(
+                &markdown::parser::to_final_link(page.uri_path(), &ctx),
+            ));
+
             match page.ast(None) {
                 Ok(ast) => {
                     match ast {
@@ -46,24 +126,21 @@ impl SearchIndex {
                             index_markdown(
                                 &mut index,
                                 ast,
-                                &page
-                                    .title()
-                                    .map(|t| {
-                                        t.unwrap_or_else(|| project.settings().title().to_owned())
-                                    })
-                                    .unwrap_or_default(),
+                                &page.title().unwrap_or_default(),
                                 page.uri_path(),
+                                &scope,
+                                badge,
+                                boosts,
                             );
                         }
                         crate::Ast::OpenApi(ast) => {
                             index_openapi(
                                 &mut index,
                                 ast,
-                                &page
-                                    .title()
-                                    .map(|t| t.unwrap_or_default())
-                                    .unwrap_or_default(),
+                                &page.title().unwrap_or_default(),
                                 page.uri_path(),
+                                &scope,
+                                boosts,
                             );
                         }
                     };
@@ -82,8 +159,14 @@ impl SearchIndex {
         self.doc_id += 1;
     }
 
+    /// Serializes the index as `{ "version": .., "index": .. }`, where
+    /// `index` is the elasticlunr index.
     pub fn to_json(&self) -> String {
-        self.index.to_json()
+        serde_json::json!({
+            "version": SEARCH_INDEX_VERSION,
+            "index": self.index,
+        })
+        .to_string()
     }
 }
 
@@ -106,6 +189,16 @@ struct DocumentBuilder {
     openapi_path: String,
     openapi_method: String,
     kind: String,
+    /// Slug of the heading the section starts at, if any. Also included in `page_url`.
+    anchor: String,
+    /// Path of the tab or subtab the page belongs to. Empty without tabs.
+    scope: String,
+    badge: String,
+    badge_color: String,
+    boost: String,
+    title_boost: String,
+    headings_boost: String,
+    body_boost: String,
 }
 
 impl DocumentBuilder {
@@ -128,6 +221,14 @@ impl DocumentBuilder {
             openapi_path: String::new(),
             openapi_method: String::new(),
             kind: "markdown".to_string(),
+            anchor: String::new(),
+            scope: String::new(),
+            badge: String::new(),
+            badge_color: String::new(),
+            boost: String::new(),
+            title_boost: String::new(),
+            headings_boost: String::new(),
+            body_boost: String::new(),
         }
     }
 
@@ -150,9 +251,22 @@ impl DocumentBuilder {
             openapi_path: String::new(),
             openapi_method: String::new(),
             kind: "openapi".to_string(),
+            anchor: String::new(),
+            scope: String::new(),
+            badge: String::new(),
+            badge_color: String::new(),
+            boost: String::new(),
+            title_boost: String::new(),
+            headings_boost: String::new(),
+            body_boost: String::new(),
         }
     }
 
+    fn set_badge(&mut self, badge: &Badge) {
+        self.badge = badge.text.clone();
+        self.badge_color = badge.color.clone();
+    }
+
     fn as_elasticlunr_document(&self) -> Vec<&str> {
         vec![
             &self.title,
@@ -172,82 +286,186 @@ impl DocumentBuilder {
             &self.openapi_path,
             &self.openapi_method,
             &self.kind,
+            &self.anchor,
+            &self.scope,
+            &self.badge,
+            &self.badge_color,
+            &self.boost,
+            &self.title_boost,
+            &self.headings_boost,
+            &self.body_boost,
         ]
     }
 }
 
+/// Indexes a Markdown page as one record per heading section, so that
+/// results can link straight to the relevant part of the page.
+///
+/// Each record carries the trail of headings leading to it in `lvl0`-`lvl5`,
+/// and the text up until the next heading.
 fn index_markdown(
     index: &mut SearchIndex,
     ast: crate::markdown::Node,
     title: &str,
     page_url: &str,
+    scope: &str,
+    badge: Option<&Badge>,
+    boosts: Boosts,
 ) {
-    fn index_node(node: &crate::markdown::Node, doc: &mut DocumentBuilder) {
-        match &node.kind {
-            NodeKind::Heading { level, .. } => match level {
-                1 => {
-                    doc.lvl0.push_str(&node.inner_text());
-                    doc.lvl0.push(' ');
-                }
-                2 => {
-                    doc.lvl1.push_str(&node.inner_text());
-                    doc.lvl1.push(' ');
-                }
-                3 => {
-                    doc.lvl2.push_str(&node.inner_text());
-                    doc.lvl2.push(' ');
+    struct Sections<'a> {
+        title: &'a str,
+        page_url: &'a str,
+        scope: &'a str,
+        boosts: Boosts,
+        trail: [String; 6],
+        /// Text of the footnotes by anchor, indexed with the section that
+        /// first references them
+        footnotes: HashMap<String, String>,
+        current: DocumentBuilder,
+        has_content: bool,
+        finished: Vec<DocumentBuilder>,
+    }
+
+    impl Sections<'_> {
+        fn new_section(&self, anchor: &str) -> DocumentBuilder {
+            let mut doc = DocumentBuilder::markdown();
+            doc.title = self.title.to_string();
+            doc.anchor = anchor.to_string();
+            doc.scope = self.scope.to_string();
+            doc.page_url = if anchor.is_empty() {
+                self.page_url.to_string()
+            } else {
+                format!("{}#{}", self.page_url, anchor)
+            };
+            [doc.lvl0, doc.lvl1, doc.lvl2, doc.lvl3, doc.lvl4, doc.lvl5] = self.trail.clone();
+            self.boosts.apply(&mut doc);
+            doc
+        }
+
+        fn start_section(&mut self, level: u8, heading: String, anchor: &str) {
+            let level = (level.clamp(1, 6) - 1) as usize;
+
+            self.trail[level] = heading;
+            for deeper in &mut self.trail[level + 1..] {
+                deeper.clear();
+            }
+
+            let next = self.new_section(anchor);
+            self.finish_section();
+            self.current = next;
+            // A heading is worth finding even if the section is empty
+            self.has_content = true;
+        }
+
+        fn finish_section(&mut self) {
+            let doc = std::mem::replace(&mut self.current, DocumentBuilder::markdown());
+
+            if self.has_content {
+                self.finished.push(doc);
+            }
+            self.has_content = false;
+        }
+
+        fn push_text(&mut self, text: &str) {
+            self.current.text.push_str(text);
+            self.current.text.push(' ');
+            self.has_content = true;
+        }
+
+        fn index_node(&mut self, node: &crate::markdown::Node) {
+            match &node.kind {
+                NodeKind::Heading { level, slug, .. } => {
+                    self.start_section(*level, node.inner_text(), slug);
                 }
-                4 => {
-                    doc.lvl3.push_str(&node.inner_text());
-                    doc.lvl3.push(' ');
+                NodeKind::Text { value } => self.push_text(value),
+                NodeKind::Image { alt, .. } | NodeKind::InlineSvg { alt, .. } => {
+                    self.current.alt.push_str(alt);
+                    self.current.alt.push(' ');
+                    self.has_content = true;
                 }
-                5 => {
-                    doc.lvl4.push_str(&node.inner_text());
-                    doc.lvl4.push(' ');
+                NodeKind::Code { value, .. } => self.push_text(value),
+                NodeKind::FootnoteReference { definition_id, .. } => {
+                    if let Some(text) = self.footnotes.remove(definition_id) {
+                        self.push_text(&text);
+                    }
                 }
-                6 => {
-                    doc.lvl5.push_str(&node.inner_text());
-                    doc.lvl5.push(' ');
+                NodeKind::Footnotes => {}
+                NodeKind::Callout(callout) => {
+                    if let Some(title) = &callout.title {
+                        self.push_text(title);
+                    }
+                    for child in &node.children {
+                        self.index_node(child);
+                    }
                 }
-                _ => {}
-            },
-            NodeKind::Text { value } => {
-                doc.text.push_str(value);
-                doc.text.push(' ');
-            }
-            NodeKind::Image { alt, .. } => {
-                doc.alt.push_str(alt);
-                doc.alt.push(' ');
-            }
-            NodeKind::Code { value, .. } => {
-                doc.text.push_str(value);
-                doc.text.push(' ');
-            }
-            _ => {
-                for child in &node.children {
-                    index_node(child, doc);
+                _ => {
+                    for child in &node.children {
+                        self.index_node(child);
+                    }
                 }
             }
         }
     }
 
-    let mut doc = DocumentBuilder::markdown();
-    doc.title = title.to_string();
-    doc.page_url = page_url.to_string();
+    let footnotes = ast
+        .walk()
+        .filter_map(|node| match &node.kind {
+            NodeKind::FootnoteDefinition { id, .. } => Some((id.clone(), node.inner_text())),
+            _ => None,
+        })
+        .collect();
 
-    index_node(&ast, &mut doc);
+    let mut sections = Sections {
+        title,
+        page_url,
+        scope,
+        boosts,
+        trail: Default::default(),
+        footnotes,
+        current: DocumentBuilder::markdown(),
+        has_content: false,
+        finished: vec![],
+    };
+    sections.current = sections.new_section("");
+
+    sections.index_node(&ast);
+    sections.finish_section();
+
+    // Pages without any content are still findable by their title
+    if sections.finished.is_empty() {
+        sections.finished.push(sections.new_section(""));
+    }
 
-    index.add_doc(&doc.as_elasticlunr_document());
+    for mut doc in sections.finished {
+        if let Some(badge) = badge {
+            doc.set_badge(badge);
+        }
+
+        index.add_doc(&doc.as_elasticlunr_document());
+    }
 }
 
-fn index_openapi(index: &mut SearchIndex, ast: PageAst, title: &str, page_url: &str) {
+fn index_openapi(
+    index: &mut SearchIndex,
+    ast: PageAst,
+    title: &str,
+    page_url: &str,
+    scope: &str,
+    boosts: Boosts,
+) {
     for operation in &ast.operations {
         let mut doc = DocumentBuilder::openapi();
         doc.title = title.to_string();
+        doc.anchor = operation.anchor_tag.clone();
         doc.page_url = format!("{}#{}", page_url, operation.anchor_tag);
+        doc.scope = scope.to_string();
+        boosts.apply(&mut doc);
         doc.openapi_tag = ast.tag.name.clone();
         doc.openapi_path = operation.route_pattern.clone();
         doc.openapi_method = operation.method.clone();
+        if let Some(method) = HttpMethod::from_str(&operation.method) {
+            doc.set_badge(&Badge::for_method(method));
+        }
         doc.openapi_summary = operation.summary.clone().unwrap_or_default();
         if let Some(description) = &operation.description_ast {
             doc.openapi_description = description.inner_text();
@@ -256,3 +474,281 @@ fn index_openapi(index: &mut SearchIndex, ast: PageAst, title: &str, page_url: &
         index.add_doc(&doc.as_elasticlunr_document());
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use std::path::{Path, PathBuf};
+
+    fn records(files: Vec<(&str, &str)>) -> (serde_json::Value, Vec<serde_json::Value>) {
+        let mut input = vec![
+            InputFile {
+                path: PathBuf::from(NAVIGATION_FILE_NAME),
+                content: InputContent::Text("- heading: Something\n".to_string()),
+            },
+            InputFile {
+                path: PathBuf::from(SETTINGS_FILE_NAME),
+                content: InputContent::Text("---\ntitle: An Project\n".to_string()),
+            },
+        ];
+        input.retain(|default| {
+            !files
+                .iter()
+                .any(|(path, _)| default.path == Path::new(path))
+        });
+        input.extend(files.into_iter().map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }));
+
+        let project = Project::from_file_list(input).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&project.search_index().unwrap().to_json()).unwrap();
+
+        let docs = json["index"]["documentStore"]["docs"]
+            .as_object()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect();
+
+        (json, docs)
+    }
+
+    fn find<'a>(docs: &'a [serde_json::Value], page_url: &str) -> &'a serde_json::Value {
+        docs.iter()
+            .find(|d| d["page_url"] == page_url)
+            .unwrap_or_else(|| panic!("No record for {}: {:#?}", page_url, docs))
+    }
+
+    #[test]
+    fn output_is_versioned() {
+        let (json, _) = records(vec![("README.md", "# Hello")]);
+
+        assert_eq!(json["version"], SEARCH_INDEX_VERSION);
+    }
+
+    #[test]
+    fn indexes_each_heading_section_separately() {
+        let (_, docs) = records(vec![(
+            "guide.md",
+            indoc! {"
+            Some intro text
+
+            # Getting started
+
+            Read this first
+
+            ## Installing
+
+            Run the installer
+
+            ### On Mac
+
+            Use brew
+
+            ## Configuring
+
+            Edit the settings
+            "},
+        )]);
+
+        assert_eq!(docs.len(), 5);
+
+        let intro = find(&docs, "/guide");
+        assert_eq!(intro["anchor"], "");
+        assert_eq!(intro["text"], "Some intro text ");
+
+        let mac = docs.iter().find(|d| d["text"] == "Use brew ").unwrap();
+        assert_eq!(mac["lvl0"], "Getting started");
+        assert_eq!(mac["lvl1"], "Installing");
+        assert_eq!(mac["lvl2"], "On Mac");
+        assert_eq!(
+            mac["page_url"],
+            format!("/guide#{}", mac["anchor"].as_str().unwrap())
+        );
+
+        // The trail is reset when moving to a sibling heading
+        let configuring = docs
+            .iter()
+            .find(|d| d["text"] == "Edit the settings ")
+            .unwrap();
+        assert_eq!(configuring["lvl1"], "Configuring");
+        assert_eq!(configuring["lvl2"], "");
+        // Titled by the first h1, rather than the file name
+        assert_eq!(configuring["title"], "Getting started");
+    }
+
+    #[test]
+    fn stores_boosts_in_each_record() {
+        let (_, docs) = records(vec![
+            ("README.md", "# Hello\n\nWorld"),
+            ("boosted.md", "---\nsearch_boost: 2\n---\n\nImportant"),
+        ]);
+
+        let normal = find(&docs, "/#hello");
+        assert_eq!(normal["boost"], "1");
+        assert_eq!(normal["title_boost"], "3");
+        assert_eq!(normal["headings_boost"], "2");
+        assert_eq!(normal["body_boost"], "1");
+
+        let boosted = find(&docs, "/boosted");
+        assert_eq!(boosted["boost"], "2");
+        assert_eq!(boosted["title_boost"], "6");
+        assert_eq!(boosted["headings_boost"], "4");
+        assert_eq!(boosted["body_boost"], "2");
+    }
+
+    #[test]
+    fn indexes_text_inside_callouts() {
+        let (_, docs) = records(vec![(
+            "README.md",
+            indoc! {"
+            :::warning Heads up
+            Back up your data
+            :::
+            "},
+        )]);
+
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0]["text"], "Heads up Back up your data ");
+    }
+
+    #[test]
+    fn indexes_footnotes_with_the_section_that_references_them() {
+        let (_, docs) = records(vec![(
+            "README.md",
+            indoc! {"
+            # Intro
+
+            Some claim[^source].
+
+            # Other
+
+            Unrelated
+
+            [^source]: A reliable source
+            "},
+        )]);
+
+        let section = |heading: &str| {
+            docs.iter()
+                .find(|d| d["lvl0"] == heading)
+                .unwrap_or_else(|| panic!("No record for {}: {:#?}", heading, docs))
+        };
+
+        assert_eq!(section("Intro")["text"], "Some claim A reliable source . ");
+        assert_eq!(section("Other")["text"], "Unrelated ");
+    }
+
+    #[test]
+    fn skips_pages_that_opt_out() {
+        let (_, docs) = records(vec![
+            ("README.md", "Hello"),
+            ("hidden.md", "---\nsearch: false\n---\n\nSecret"),
+            (
+                "also-hidden.md",
+                "---\nsearch:\n  hidden: true\n---\n\nSecret",
+            ),
+        ]);
+
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0]["page_url"], "/");
+    }
+
+    #[test]
+    fn records_the_tab_scope_of_each_page() {
+        let (_, docs) = records(vec![
+            (
+                SETTINGS_FILE_NAME,
+                indoc! {r#"
+                ---
+                title: An Project
+                tabs:
+                  - label: Guides
+                    path: /
+                  - label: API
+                    path: /api
+                "#},
+            ),
+            ("api/navigation.yaml", "- heading: Something\n"),
+            ("README.md", "Hello"),
+            ("api/users.md", "Users"),
+            ("api-guide.md", "Guide"),
+        ]);
+
+        assert_eq!(find(&docs, "/")["scope"], "/");
+        assert_eq!(find(&docs, "/api/users")["scope"], "/api");
+        assert_eq!(find(&docs, "/api-guide")["scope"], "/");
+    }
+
+    #[test]
+    fn records_the_badge_of_each_page() {
+        let (_, docs) = records(vec![
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {"
+                - heading: Guides
+                  items:
+                    - href: /new.md
+                      badge:
+                        text: New
+                        color: green
+                    - href: /old.md
+                "},
+            ),
+            ("new.md", "Intro\n\n## Setup\n\nSteps"),
+            ("old.md", "Old"),
+        ]);
+
+        let new = docs
+            .iter()
+            .filter(|d| d["page_url"].as_str().unwrap().starts_with("/new"))
+            .collect::<Vec<_>>();
+
+        // Every section of the page carries the badge
+        assert_eq!(new.len(), 2, "{:#?}", docs);
+        for doc in new {
+            assert_eq!(doc["badge"], "New");
+            assert_eq!(doc["badge_color"], "green");
+        }
+        assert_eq!(find(&docs, "/old")["badge"], "");
+    }
+
+    #[test]
+    fn scope_is_empty_without_tabs() {
+        let (_, docs) = records(vec![("README.md", "Hello")]);
+
+        assert_eq!(docs[0]["scope"], "");
+    }
+
+    #[test]
+    fn leaves_out_noindex_pages() {
+        let (_, docs) = records(vec![
+            ("README.md", "# Home"),
+            ("mirrored.md", "---\nnoindex: true\n---\n# Mirrored"),
+        ]);
+
+        assert!(
+            docs.iter().all(|d| d["page_url"] != "/mirrored"),
+            "{:#?}",
+            docs
+        );
+        find(&docs, "/");
+    }
+
+    #[test]
+    fn keeps_noindex_pages_when_asked_to() {
+        let (_, docs) = records(vec![
+            (
+                SETTINGS_FILE_NAME,
+                "---\ntitle: An Project\nsearch:\n  include_noindex: true\n",
+            ),
+            ("README.md", "# Home"),
+            ("mirrored.md", "---\nnoindex: true\n---\n# Mirrored"),
+        ]);
+
+        find(&docs, "/mirrored");
+    }
+}
@@ -1,12 +1,19 @@
 use regex::Regex;
 
+use crate::accessibility::Rule;
+use crate::page_kind::PageKind;
 use crate::parser::{is_external_link, rewrite_image_src, to_final_link};
 use crate::render_context::RenderContext;
 use crate::tabs::{TabDescription, TabsList};
 /// Settings for a given site backed by a `docapella.yaml` file.
-use crate::{Error, Project, RenderOptions, Result, SETTINGS_FILE_NAME};
+use crate::{Error, Project, RenderOptions, Result, UrlStyle, SETTINGS_FILE_NAME};
+use color_generator::{Appearance, ColorGenerator, Scale, ScaleOverrides};
+use globset::{Glob, GlobBuilder};
+use openapi_parser::openapi30::parser::Limits;
 /// Settings for a given site backed by a `docapella.yaml` file.
 use serde::{Deserialize, Deserializer, Serialize};
+use serde_path_to_error::Segment;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::{self, Display};
 use std::path::{Path, PathBuf};
 use url::Url;
@@ -42,13 +49,31 @@ impl Settings {
     /// https://github.com/serde-rs/serde/issues/912#issuecomment-423643892
     ///
     pub fn parse(input: &str) -> Result<Self> {
-        serde_yaml::from_str::<Settings>(input).map_err(|e| Error {
+        let mut settings = crate::yaml::from_str::<Settings>(input).map_err(|e| Error {
             code: Error::INVALID_DOCTAVE_YAML,
             message: "Invalid docapella.yaml".to_owned(),
             description: format!("There was an error parsing your docapella.yaml:\n\n{}", e),
             file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-            position: None,
-        })
+            position: e.position,
+            owner: None,
+            suggestions: vec![],
+        })?;
+
+        for spec in &mut settings.open_api {
+            spec.resolve_spec_file()?;
+        }
+
+        settings.integrations.verify(input)?;
+
+        if let Some(secret_scanning) = &settings.secret_scanning {
+            secret_scanning.verify(input)?;
+        }
+
+        for (name, provider) in &settings.embeds {
+            provider.verify(name, input)?;
+        }
+
+        Ok(settings)
     }
 
     /// Rewrite links based on a list of link rewrites
@@ -175,10 +200,43 @@ impl Settings {
         Some(&self.theme)
     }
 
+    pub fn search(&self) -> &SearchSettings {
+        &self.search
+    }
+
+    /// The `exclude` pattern that matches the file, if any.
+    pub(crate) fn excluded_by(&self, fs_path: &Path) -> Option<&str> {
+        let path = fs_path.strip_prefix("/").unwrap_or(fs_path);
+
+        self.exclude
+            .iter()
+            .find(|pattern| {
+                exclude_glob(pattern)
+                    .map(|glob| glob.compile_matcher().is_match(path))
+                    .unwrap_or(false)
+            })
+            .map(|pattern| pattern.as_str())
+    }
+
     pub fn open_api(&self) -> &[OpenApi] {
         self.open_api.as_slice()
     }
 
+    /// How deep schemas in the given OpenAPI spec are rendered before they
+    /// are collapsed.
+    pub(crate) fn max_schema_depth(&self, spec_file: &Path) -> usize {
+        self.open_api
+            .iter()
+            .find(|o| o.spec_file == spec_file)
+            .and_then(|o| o.max_schema_depth)
+            .unwrap_or(DEFAULT_MAX_SCHEMA_DEPTH)
+    }
+
+    /// The settings of the given OpenAPI spec.
+    pub(crate) fn open_api_spec(&self, spec_file: &Path) -> Option<&OpenApi> {
+        self.open_api.iter().find(|o| o.spec_file == spec_file)
+    }
+
     pub fn footer(&self) -> Option<&Footer> {
         Some(&self.footer)
     }
@@ -195,6 +253,134 @@ impl Settings {
         self.styles.as_slice()
     }
 
+    pub fn html_policy(&self) -> HtmlPolicy {
+        self.html_policy
+    }
+
+    pub fn url_style(&self) -> UrlStyle {
+        self.url_style
+    }
+
+    pub fn locales(&self) -> &[String] {
+        self.locales.as_slice()
+    }
+
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    pub fn feeds(&self) -> &[FeedSettings] {
+        self.feeds.as_slice()
+    }
+
+    pub fn whats_new(&self) -> Option<&WhatsNewSettings> {
+        self.whats_new.as_ref()
+    }
+
+    pub fn external_links(&self) -> &ExternalLinkSettings {
+        &self.external_links
+    }
+
+    pub fn templates(&self) -> &TemplateSettings {
+        &self.templates
+    }
+
+    pub fn lints(&self) -> &LintSettings {
+        &self.lints
+    }
+
+    pub fn health(&self) -> &HealthSettings {
+        &self.health
+    }
+
+    pub fn owners(&self) -> &[OwnerRule] {
+        self.owners.as_slice()
+    }
+
+    pub fn secret_scanning(&self) -> Option<&SecretScanning> {
+        self.secret_scanning.as_ref()
+    }
+
+    pub fn embeds(&self) -> &BTreeMap<String, EmbedProvider> {
+        &self.embeds
+    }
+
+    /// The `variables`, with the overrides for `locale` applied.
+    pub fn variables(&self, locale: Option<&str>) -> BTreeMap<String, Variable> {
+        let mut variables = Variable::Map(self.variables.clone());
+
+        if let Some(overrides) = locale.and_then(|l| self.variable_translations.get(l)) {
+            variables.merge(&Variable::Map(overrides.clone()));
+        }
+
+        match variables {
+            Variable::Map(map) => map,
+            _ => unreachable!("BUG: Variables weren't a map"),
+        }
+    }
+
+    pub fn markdown(&self) -> &MarkdownSettings {
+        &self.markdown
+    }
+
+    pub fn navigation(&self) -> &NavigationSettings {
+        &self.navigation
+    }
+
+    /// The canonical URL the `canonical_urls` rules give a page, if any.
+    pub(crate) fn canonical_url_for(&self, fs_path: &Path, uri_path: &str) -> Option<String> {
+        let path = fs_path.strip_prefix("/").unwrap_or(fs_path);
+
+        self.canonical_urls
+            .iter()
+            .find(|rule| {
+                exclude_glob(&rule.path)
+                    .map(|glob| glob.compile_matcher().is_match(path))
+                    .unwrap_or(false)
+            })
+            .map(|rule| rule.canonical_url(uri_path))
+    }
+
+    pub fn default_locale(&self) -> Option<&str> {
+        self.default_locale
+            .as_deref()
+            .or_else(|| self.locales.first().map(|l| l.as_str()))
+    }
+
+    /// Splits a URI path into the locale it belongs to, and the path of the
+    /// page within that locale.
+    ///
+    ///   /fr/foo/bar => (Some("fr"), "/foo/bar")
+    ///   /foo/bar    => (None, "/foo/bar")
+    ///
+    /// Only locales other than the default one have a URI prefix.
+    pub(crate) fn split_locale<'a>(&self, uri_path: &'a str) -> (Option<&str>, &'a str) {
+        let trimmed = uri_path.trim_start_matches('/');
+        let segment_end = trimmed.find(['/', '#']).unwrap_or(trimmed.len());
+        let first_segment = &trimmed[..segment_end];
+
+        match self
+            .locales
+            .iter()
+            .find(|l| l.as_str() == first_segment && Some(l.as_str()) != self.default_locale())
+        {
+            Some(locale) => {
+                let rest = &trimmed[segment_end..];
+
+                if rest.is_empty() || rest.starts_with('#') {
+                    (Some(locale.as_str()), "/")
+                } else {
+                    (Some(locale.as_str()), rest)
+                }
+            }
+            None => (None, uri_path),
+        }
+    }
+
     pub fn verify(&self, project: &Project, errors: &mut Vec<Error>) {
         // Shared verifications
         self.verify_openapi_specs(project, errors);
@@ -205,6 +391,14 @@ impl Settings {
         self.verify_header(project, errors);
         self.verify_footer(project, errors);
         self.verify_vale(project, errors);
+        self.verify_locales(errors);
+        self.verify_variable_translations(errors);
+        self.verify_search(errors);
+        self.verify_exclude(errors);
+        self.verify_feeds(errors);
+        self.verify_templates(errors);
+        self.verify_canonical_urls(errors);
+        self.verify_owners(errors);
 
         // Theme verifications
         self.verify_v2_theme(errors);
@@ -222,6 +416,8 @@ impl Settings {
                         description: format!("Use \".vale.ini\", or remove the \".\" from the start of the config file name \"{}\".", file_name),
                         file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
             }
@@ -233,11 +429,97 @@ impl Settings {
                     description: format!("Expected a Vale configuration file at \"{config_path}\""),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })
             }
         }
     }
 
+    fn verify_locales(&self, errors: &mut Vec<Error>) {
+        for locale in &self.locales {
+            if locale.is_empty()
+                || !locale
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid locale"),
+                    description: format!(
+                        r#"Locale "{}" can only include alphanumerics, underscores, and hyphens."#,
+                        locale
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        if let Some(default_locale) = &self.default_locale {
+            if !self.locales.contains(default_locale) {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Unknown default locale"),
+                    description: format!(
+                        r#"Default locale "{}" must be one of the locales listed under `locales`. Found [{}]."#,
+                        default_locale,
+                        self.locales
+                            .iter()
+                            .map(|l| format!("\"{}\"", l))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+    }
+
+    fn verify_variable_translations(&self, errors: &mut Vec<Error>) {
+        let defaults = Variable::Map(self.variables.clone());
+
+        for (locale, overrides) in &self.variable_translations {
+            if !self.locales.contains(locale) {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Unknown locale in variable_translations"),
+                    description: format!(
+                        r#"Expected one of the locales listed under `locales`. Found "{}"."#,
+                        locale
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            let mut unknown = vec![];
+            defaults.unknown_overrides(&Variable::Map(overrides.clone()), "", &mut unknown);
+
+            for name in unknown {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Unknown variable in variable_translations"),
+                    description: format!(
+                        r#"The "{}" translation overrides "{}", which isn't under `variables`. Add a default value for it under `variables`."#,
+                        locale, name
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+    }
+
     fn verify_header(&self, project: &Project, errors: &mut Vec<Error>) {
         if let Some(header) = self.header() {
             if let Some(cta) = &header.cta {
@@ -258,319 +540,1820 @@ impl Settings {
         }
     }
 
-    fn verify_v2_theme(&self, errors: &mut Vec<Error>) {
-        if let Some(false) = self
-            .theme()
-            .map(|t| t.colors.original_accent.clone())
-            .map(|s| is_hex_color(&s))
-        {
-            errors.push(Error {
-                code: Error::INVALID_DOCTAVE_YAML,
-                message: String::from("Invalid accent color in theme"),
-                description: format!("Expected a HEX color code, or a valid CSS color name."),
-                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-                position: None,
-            });
-        }
-    }
+    fn verify_search(&self, errors: &mut Vec<Error>) {
+        let boosts = &self.search.boosts;
 
-    fn verify_openapi_specs(&self, project: &Project, errors: &mut Vec<Error>) {
-        for o in self.open_api() {
-            if o.uri_prefix == "/" {
+        for (key, value) in [
+            ("title", boosts.title),
+            ("headings", boosts.headings),
+            ("body", boosts.body),
+        ] {
+            if value <= 0.0 || !value.is_finite() {
                 errors.push(Error {
                     code: Error::INVALID_DOCTAVE_YAML,
-                    message: String::from("OpenAPI URI prefix should contain a path."),
-                    description: format!(
-                        "Define a uri_prefix for the OpenAPI spec \"{}\" in docapella.yaml. For example, uri_prefix: /api.",
-                        &o.spec_file.display()
-                    ),
+                    message: format!("Invalid search boost for `search.boosts.{}`", key),
+                    description: format!("Expected a positive number.\nFound {}.", value),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-            position: None,
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
+        }
+    }
 
-            if !project.input_paths.contains(&o.spec_file) {
+    fn verify_exclude(&self, errors: &mut Vec<Error>) {
+        for pattern in &self.exclude {
+            if let Err(e) = exclude_glob(pattern) {
                 errors.push(Error {
                     code: Error::INVALID_DOCTAVE_YAML,
-                    message: String::from("Could not find OpenAPI spec."),
-                    description: format!(
-                        "OpenAPI spec at \"{}\" not found. Is it in the correct location?",
-                        &o.spec_file.display()
-                    ),
+                    message: String::from("Invalid pattern in `exclude`"),
+                    description: format!("Could not parse \"{}\": {}", pattern, e.kind()),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
         }
     }
 
-    fn verify_logo(&self, project: &Project, errors: &mut Vec<Error>) {
-        if let Some(logo) = &self.logo() {
-            logo.verify(project, errors);
+    fn verify_templates(&self, errors: &mut Vec<Error>) {
+        let templates = &self.templates;
+
+        let mut used = vec![];
+        if let Some(default) = &templates.default {
+            used.push(("templates.default", default));
         }
-    }
+        for rule in &templates.rules {
+            used.push(("templates.rules", &rule.template));
 
-    fn verify_favicon(&self, project: &Project, errors: &mut Vec<Error>) {
-        if let Some(favicon) = self.favicon() {
-            if !project.assets.iter().any(|asset| asset == &favicon.src) {
+            if let Err(e) = exclude_glob(&rule.path) {
                 errors.push(Error {
                     code: Error::INVALID_DOCTAVE_YAML,
-                    message: format!(
-                        "Could not find favicon at \"{}\".",
-                        favicon.src.display()
-                    ),
-                    description: format!(
-                        "Found following possible favicons: [{}].\nMake sure the file name is correct and located under the \"_assets\" directory.",
-                        project.assets
-                            .iter()
-                            .filter(|a| {
-                                let ext = a.path.extension().and_then(|s| s.to_str());
-                                ext == Some("png")  || ext == Some("svg")  || ext == Some("ico")
-                            })
-                            .map(|s| format!("\"{}\"", s.path.display()))
-                            .collect::<Vec<_>>()
-                            .join(", "),
-                    ),
-                    file: Some(PathBuf::from(crate::SETTINGS_FILE_NAME)),
-            position: None,
+                    message: String::from("Invalid pattern in `templates.rules`"),
+                    description: format!("Could not parse \"{}\": {}", rule.path, e.kind()),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
         }
-    }
 
-    fn verify_styles(&self, project: &Project, errors: &mut Vec<Error>) {
-        for path in self.styles() {
-            if !project.assets.iter().any(|asset| asset == path) {
+        for (key, name) in used {
+            if !templates.is_allowed(name) {
                 errors.push(Error {
                     code: Error::INVALID_DOCTAVE_YAML,
-                    message: format!(
-                        "Could not find style sheet file at \"{}\".",
-                        path.display()
-                    ),
-                    description: format!(
-                        "Found [{}].\nMake sure the file name is correct and located under the \"_assets\" directory.",
-                        project.assets
-                            .iter()
-                            .filter(|a| a.path.extension().and_then(|s| s.to_str()) == Some("css"))
-                            .map(|s| format!("\"{}\"", s.path.display()))
-                            .collect::<Vec<_>>()
-                            .join(", "),
-                    ),
-                    file: Some(PathBuf::from(crate::SETTINGS_FILE_NAME)),
-            position: None,
+                    message: format!("Unknown template in `{}`", key),
+                    description: templates.unknown_template_description(name),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
         }
     }
 
-    fn verify_redirects(&self, project: &Project, errors: &mut Vec<Error>) {
-        let pages = project.pages();
-
-        for (from, to) in self.redirects().iter().map(|r| r.as_tuple()) {
-            let from_without_anchor = from.split('#').collect::<Vec<_>>()[0];
-            let to_without_anchor = to.split('#').collect::<Vec<_>>()[0];
-
-            if from_without_anchor.contains(':') {
+    fn verify_owners(&self, errors: &mut Vec<Error>) {
+        for rule in &self.owners {
+            if let Err(e) = crate::owners::owner_glob(&rule.path) {
                 errors.push(Error {
-                    code: Error::INVALID_REDIRECT,
-                    message: String::from("Invalid redirect source"),
-                    description: format!(
-                        r#"Redirect source "{}" can't include path parameters."#,
-                        from_without_anchor
-                    ),
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid pattern in `owners`"),
+                    description: format!("Could not parse \"{}\": {}", rule.path, e.kind()),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
+        }
+    }
 
-            if from_without_anchor.contains('*') && !WILDCARD_REGEX.is_match(from_without_anchor) {
-                errors.push(Error {
-                      code: Error::INVALID_REDIRECT,
-                      message: String::from("Invalid redirect source"),
-                      description: format!(r#"Redirect source "{}" with a wildcard should end with `.../*` or `.../**`."#, from),
-                      file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-            position: None,
-                  });
-            }
-
-            if pages.iter().any(|p| p.uri_path() == from_without_anchor) {
+    fn verify_canonical_urls(&self, errors: &mut Vec<Error>) {
+        for rule in &self.canonical_urls {
+            if let Err(e) = exclude_glob(&rule.path) {
                 errors.push(Error {
-                    code: Error::INVALID_REDIRECT,
-                    message: String::from("Redirect overlaps with existing page"),
-                    description: format!(r#"Redirect source "{}" already exists as a page. Delete or rename the page, or change the redirect source."#, from_without_anchor),
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid pattern in `canonical_urls`"),
+                    description: format!("Could not parse \"{}\": {}", rule.path, e.kind()),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-            position: None,
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
 
-            if !from_without_anchor.starts_with('/') {
+            if !is_absolute_url(&rule.url) {
                 errors.push(Error {
-                    code: Error::INVALID_REDIRECT,
-                    message: String::from("Invalid redirect detected"),
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid URL in `canonical_urls`"),
                     description: format!(
-                        r#"Redirect source "{}" must start with a forward slash."#,
-                        from_without_anchor
+                        "Expected an absolute URL, like \"https://docs.example.com/guides\".\nFound \"{}\".",
+                        rule.url
                     ),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
+        }
+    }
 
-            if from_without_anchor == to_without_anchor {
+    fn verify_feeds(&self, errors: &mut Vec<Error>) {
+        if let Some(base_url) = &self.base_url {
+            if !is_absolute_url(base_url) {
                 errors.push(Error {
-                    code: Error::INVALID_REDIRECT,
-                    message: String::from("Invalid redirect detected"),
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid `base_url`"),
                     description: format!(
-                        r#"Redirect source "{}" and destination "{}" must be different."#,
-                        from_without_anchor, to_without_anchor
+                        "Expected an absolute URL, like \"https://docs.example.com\".\nFound \"{}\".",
+                        base_url
                     ),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
+        }
 
-            if from_without_anchor.ends_with(".md") {
+        if !self.feeds.is_empty() && self.base_url.is_none() {
+            errors.push(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: String::from("Feeds require a `base_url`"),
+                description: String::from("Feeds link to pages with absolute URLs. Set `base_url` to where your site is published, like \"https://docs.example.com\"."),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        let mut seen = HashSet::new();
+
+        for feed in &self.feeds {
+            if !seen.insert(feed.uri_path()) {
                 errors.push(Error {
-                    code: Error::INVALID_REDIRECT,
-                    message: String::from("Invalid redirect detected"),
-                    description: format!(
-                        r#"Redirect source "{}" must not end with .md."#,
-                        from_without_anchor
-                    ),
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Feeds can not share paths"),
+                    description: format!("Multiple feeds have the path \"{}\".", feed.uri_path()),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
 
-            let is_external = Url::parse(to_without_anchor).is_ok();
+            if feed.limit == 0 {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid feed limit"),
+                    description: format!(
+                        "Expected the feed \"{}\" to include at least one page.\nFound a limit of 0.",
+                        feed.title
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        if let Some(since) = self.whats_new().and_then(|w| w.since.as_ref()) {
+            if !crate::feed::is_valid_date(since) {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid date in whats_new"),
+                    description: format!(
+                        "Expected `whats_new.since` to be a date like `2024-05-01`.\nFound \"{}\".",
+                        since
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+    }
+
+    fn verify_v2_theme(&self, errors: &mut Vec<Error>) {
+        if let Some(false) = self
+            .theme()
+            .map(|t| t.colors.original_accent.clone())
+            .map(|s| is_hex_color(&s))
+        {
+            errors.push(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: String::from("Invalid accent color in theme"),
+                description: format!("Expected a HEX color code, or a valid CSS color name."),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        if let Some(theme) = self.theme() {
+            theme.colors.verify(errors);
+        }
+    }
+
+    fn verify_openapi_specs(&self, project: &Project, errors: &mut Vec<Error>) {
+        for o in self.open_api() {
+            if !project
+                .input_paths
+                .contains(&crate::canonical_path::canonicalize(&o.spec_file))
+            {
+                let (message, description) = match &o.spec_url {
+                    Some(url) => (
+                        String::from("Could not fetch OpenAPI spec."),
+                        format!(
+                            "OpenAPI spec at \"{}\" could not be fetched, and there is no cached copy of it. Check the URL and your connection, and try again.",
+                            url
+                        ),
+                    ),
+                    None => (
+                        String::from("Could not find OpenAPI spec."),
+                        format!(
+                            "OpenAPI spec at \"{}\" not found. Is it in the correct location?",
+                            &o.spec_file.display()
+                        ),
+                    ),
+                };
+
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message,
+                    description,
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            if let Some(template) = &o.overview_template {
+                if !project
+                    .input_paths
+                    .contains(&crate::canonical_path::canonicalize(template))
+                {
+                    errors.push(Error {
+                        code: Error::OPENAPI_TEMPLATE_ERROR,
+                        message: String::from("Could not find OpenAPI overview template."),
+                        description: format!(
+                            "Overview template at \"{}\" for the OpenAPI spec \"{}\" not found. Is it in the correct location?",
+                            template.display(),
+                            &o.spec_file.display()
+                        ),
+                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+            }
+        }
+    }
+
+    /// URI prefixes shared by more than one OpenAPI spec. Pages aren't
+    /// generated for these specs, since which of them would be served at a
+    /// given URI path would be arbitrary.
+    pub(crate) fn duplicate_open_api_prefixes(&self) -> HashSet<&str> {
+        let mut seen = HashSet::new();
+
+        self.open_api()
+            .iter()
+            .map(|o| o.uri_prefix.as_str())
+            .filter(|prefix| !seen.insert(*prefix))
+            .collect()
+    }
+
+    /// Reports OpenAPI specs without a URI prefix, specs sharing a prefix,
+    /// and specs whose pages collide with Markdown pages in the folder of
+    /// their prefix. Positions point to the `uri_prefix` of the spec in
+    /// `input`.
+    pub(crate) fn open_api_prefix_errors(&self, input: &str, pages: &[PageKind]) -> Vec<Error> {
+        let mut errors = vec![];
+
+        for (index, o) in self.open_api().iter().enumerate() {
+            let position = crate::yaml::position_of(input, &uri_prefix_path(index));
+
+            if o.uri_prefix == "/" {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("OpenAPI URI prefix should contain a path."),
+                    description: format!(
+                        "Define a uri_prefix for the OpenAPI spec \"{}\" in docapella.yaml. For example, uri_prefix: /api.\n\n\
+                        The pages of the spec can't be served from the root of the site, where they would collide with your Markdown pages.",
+                        &o.spec_file.display()
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position,
+                    owner: None,
+                    suggestions: vec![],
+                });
+
+                continue;
+            }
+
+            let first = self
+                .open_api()
+                .iter()
+                .position(|other| other.uri_prefix == o.uri_prefix)
+                .unwrap_or(index);
+
+            if first < index {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: format!(r#"Duplicate OpenAPI URI prefix "{}""#, o.uri_prefix),
+                    description: format!(
+                        "The OpenAPI specs \"{}\" and \"{}\" both use the uri_prefix \"{}\", so their pages would be mixed together. \
+                        Pages aren't generated for either spec until each has a uri_prefix of its own, for example {}/v2.",
+                        self.open_api()[first].spec_file.display(),
+                        o.spec_file.display(),
+                        o.uri_prefix,
+                        o.uri_prefix
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position,
+                    owner: None,
+                    suggestions: vec![],
+                });
+
+                continue;
+            }
+
+            let spec_uri_paths = pages
+                .iter()
+                .filter_map(|page| match page {
+                    PageKind::OpenApi(oapi) if oapi.fs_path == o.spec_file => {
+                        Some(oapi.uri_path.as_str())
+                    }
+                    _ => None,
+                })
+                .collect::<HashSet<_>>();
+
+            let conflicting = pages
+                .iter()
+                .filter_map(|page| match page {
+                    PageKind::Markdown(md) if spec_uri_paths.contains(md.uri_path.as_str()) => {
+                        Some(format!("* {} ({})", md.path.display(), md.uri_path))
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            if !conflicting.is_empty() {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: format!(
+                        r#"OpenAPI URI prefix "{}" collides with Markdown pages"#,
+                        o.uri_prefix
+                    ),
+                    description: format!(
+                        "The OpenAPI spec \"{}\" generates pages under \"{}\" at the same URI paths as these Markdown pages:\n\n{}\n\n\
+                        The OpenAPI pages are served, and the Markdown pages are unreachable. Markdown pages can live in the same folder \
+                        as the spec's pages, as long as they don't share a URI path with the overview or a tag. \
+                        Rename or move the Markdown pages, or pick another uri_prefix for the spec.",
+                        o.spec_file.display(),
+                        o.uri_prefix,
+                        conflicting.join("\n")
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// Warns about OpenAPI specs whose URI prefix is nested under the prefix
+    /// of another spec, e.g. `/api/v2` under `/api`.
+    pub(crate) fn open_api_prefix_warnings(&self, input: &str) -> Vec<Error> {
+        let mut warnings = vec![];
+
+        for (index, o) in self.open_api().iter().enumerate() {
+            let Some(parent) = self.open_api().iter().find(|parent| {
+                parent.uri_prefix != "/"
+                    && parent.uri_prefix != o.uri_prefix
+                    && is_under_prefix(&o.uri_prefix, &parent.uri_prefix)
+            }) else {
+                continue;
+            };
+
+            warnings.push(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: format!(
+                    r#"OpenAPI URI prefix "{}" is nested under "{}""#,
+                    o.uri_prefix, parent.uri_prefix
+                ),
+                description: format!(
+                    "The pages of the OpenAPI spec \"{}\" are served under the uri_prefix of the spec \"{}\". \
+                    If a tag of \"{}\" ends up at the same URI path as a page of \"{}\", for example a tag named \"{}\", \
+                    only the page of the spec listed first in docapella.yaml is served.\n\n\
+                    Give the specs sibling prefixes, like {}/v1 and {}/v2, to keep their pages apart.",
+                    o.spec_file.display(),
+                    parent.spec_file.display(),
+                    parent.spec_file.display(),
+                    o.spec_file.display(),
+                    o.uri_prefix
+                        .trim_start_matches(&parent.uri_prefix)
+                        .trim_start_matches('/'),
+                    parent.uri_prefix,
+                    parent.uri_prefix,
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: crate::yaml::position_of(input, &uri_prefix_path(index)),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        warnings
+    }
+
+    /// Finds redirects from the URI path of a page. The page is served, and
+    /// the redirect is never used.
+    pub(crate) fn shadowed_redirect_errors(&self, input: &str, pages: &[PageKind]) -> Vec<Error> {
+        let mut errors = vec![];
+
+        for (index, redirect) in self.redirects().iter().enumerate() {
+            if redirect.is_wildcard() {
+                continue;
+            }
+
+            let Some(page) = pages
+                .iter()
+                .find(|page| page.uri_path() == redirect.source())
+            else {
+                continue;
+            };
+
+            errors.push(Error {
+                code: Error::INVALID_REDIRECT,
+                message: String::from("Redirect overlaps with existing page"),
+                description: format!(
+                    "Redirect source \"{}\" already exists as the page {}. Pages take precedence over redirects, so the redirect is never used.\n\n\
+                    Delete or rename the page, or remove the redirect.",
+                    redirect.source(),
+                    page.fs_path().display()
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: crate::yaml::position_of(input, &redirect_from_path(index)),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        errors
+    }
+
+    /// Warns about wildcard redirects that cover pages. The pages are still
+    /// served, and only the other paths under the wildcard are redirected.
+    pub(crate) fn shadowed_redirect_warnings(&self, input: &str, pages: &[PageKind]) -> Vec<Error> {
+        let mut warnings = vec![];
+
+        for (index, redirect) in self.redirects().iter().enumerate() {
+            if !redirect.is_wildcard() {
+                continue;
+            }
+
+            let covered = pages
+                .iter()
+                .filter(|page| redirect.destination_for(page.uri_path()).is_some())
+                .map(|page| format!("* {} ({})", page.fs_path().display(), page.uri_path()))
+                .collect::<Vec<_>>();
+
+            if covered.is_empty() {
+                continue;
+            }
+
+            warnings.push(Error {
+                code: Error::INVALID_REDIRECT,
+                message: format!(r#"Redirect "{}" covers existing pages"#, redirect.source()),
+                description: format!(
+                    "These pages are under the wildcard redirect \"{}\":\n\n{}\n\n\
+                    Pages take precedence over redirects, so they are still served, and only the other paths under the wildcard are redirected.",
+                    redirect.source(),
+                    covered.join("\n")
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: crate::yaml::position_of(input, &redirect_from_path(index)),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        warnings
+    }
+
+    fn verify_logo(&self, project: &Project, errors: &mut Vec<Error>) {
+        if let Some(logo) = &self.logo() {
+            logo.verify(project, errors);
+        }
+    }
+
+    fn verify_favicon(&self, project: &Project, errors: &mut Vec<Error>) {
+        if let Some(favicon) = self.favicon() {
+            if !project.assets.iter().any(|asset| asset == &favicon.src) {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: format!(
+                        "Could not find favicon at \"{}\".",
+                        favicon.src.display()
+                    ),
+                    description: format!(
+                        "Found following possible favicons: [{}].\nMake sure the file name is correct and located under the \"_assets\" directory.",
+                        project.assets
+                            .iter()
+                            .filter(|a| {
+                                let ext = a.path.extension().and_then(|s| s.to_str());
+                                ext == Some("png")  || ext == Some("svg")  || ext == Some("ico")
+                            })
+                            .map(|s| format!("\"{}\"", s.path.display()))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                    file: Some(PathBuf::from(crate::SETTINGS_FILE_NAME)),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+                });
+            }
+        }
+    }
+
+    fn verify_styles(&self, project: &Project, errors: &mut Vec<Error>) {
+        for path in self.styles() {
+            if !project.assets.iter().any(|asset| asset == path) {
+                errors.push(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: format!(
+                        "Could not find style sheet file at \"{}\".",
+                        path.display()
+                    ),
+                    description: format!(
+                        "Found [{}].\nMake sure the file name is correct and located under the \"_assets\" directory.",
+                        project.assets
+                            .iter()
+                            .filter(|a| a.path.extension().and_then(|s| s.to_str()) == Some("css"))
+                            .map(|s| format!("\"{}\"", s.path.display()))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
+                    file: Some(PathBuf::from(crate::SETTINGS_FILE_NAME)),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+                });
+            }
+        }
+    }
+
+    fn verify_redirects(&self, project: &Project, errors: &mut Vec<Error>) {
+        let pages = project.pages();
+
+        for (from, to) in self.redirects().iter().map(|r| r.as_tuple()) {
+            let from_without_anchor = from.split('#').collect::<Vec<_>>()[0];
+            let to_without_anchor = to.split('#').collect::<Vec<_>>()[0];
+            let to_page = crate::strip_url_style(to_without_anchor);
+
+            if from_without_anchor.contains(':') {
+                errors.push(Error {
+                    code: Error::INVALID_REDIRECT,
+                    message: String::from("Invalid redirect source"),
+                    description: format!(
+                        r#"Redirect source "{}" can't include path parameters."#,
+                        from_without_anchor
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            if from_without_anchor.contains('*') && !WILDCARD_REGEX.is_match(from_without_anchor) {
+                errors.push(Error {
+                      code: Error::INVALID_REDIRECT,
+                      message: String::from("Invalid redirect source"),
+                      description: format!(r#"Redirect source "{}" with a wildcard should end with `.../*` or `.../**`."#, from),
+                      file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+                  });
+            }
+
+            if !from_without_anchor.starts_with('/') {
+                errors.push(Error {
+                    code: Error::INVALID_REDIRECT,
+                    message: String::from("Invalid redirect detected"),
+                    description: format!(
+                        r#"Redirect source "{}" must start with a forward slash."#,
+                        from_without_anchor
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            if from_without_anchor == to_without_anchor {
+                errors.push(Error {
+                    code: Error::INVALID_REDIRECT,
+                    message: String::from("Invalid redirect detected"),
+                    description: format!(
+                        r#"Redirect source "{}" and destination "{}" must be different."#,
+                        from_without_anchor, to_without_anchor
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            if from_without_anchor.ends_with(".md") {
+                errors.push(Error {
+                    code: Error::INVALID_REDIRECT,
+                    message: String::from("Invalid redirect detected"),
+                    description: format!(
+                        r#"Redirect source "{}" must not end with .md."#,
+                        from_without_anchor
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: None,
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+
+            let is_external = Url::parse(to_without_anchor).is_ok();
+
+            if !is_external {
+                if to_without_anchor.contains(':') {
+                    if !PATH_REGEX.is_match(to_without_anchor) {
+                        errors.push(Error {
+                        code: Error::INVALID_REDIRECT,
+                        message: String::from("Invalid redirect destination"),
+                        description: format!(
+                            r#"Redirect destination "{}" path parameters can only contain alphanumerics and underscores."#,
+                            to
+                        ),
+                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+                    });
+                    }
+
+                    if !from_without_anchor.contains('*') {
+                        errors.push(Error {
+                          code: Error::INVALID_REDIRECT,
+                          message: String::from("Invalid redirect source"),
+                          description: format!(r#"Redirect source "{}" should include a wildcard when `to` has path parameters."#, from),
+                          file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+            position: None,
+            owner: None,
+            suggestions: vec![],
+                      });
+                    }
+                }
+
+                if !to.contains(':') && !pages.iter().any(|p| p.uri_path() == to_page) {
+                    errors.push(Error {
+                        code: Error::INVALID_REDIRECT,
+                        message: String::from("Broken redirect detected"),
+                        description: format!(
+                            r#"Redirect destination "{}" does not exist."#,
+                            to_without_anchor
+                        ),
+                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+
+                if !to_without_anchor.starts_with('/') {
+                    errors.push(Error {
+                        code: Error::INVALID_REDIRECT,
+                        message: String::from("Invalid redirect detected"),
+                        description: format!(
+                            r#"Redirect destination "{}" must start with a forward slash, or be an external URL."#,
+                            to_without_anchor
+                        ),
+                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+
+                if to_without_anchor.ends_with(".md") {
+                    errors.push(Error {
+                        code: Error::INVALID_REDIRECT,
+                        message: String::from("Invalid redirect detected"),
+                        description: format!(
+                            r#"Redirect destination "{}" must not end with .md."#,
+                            to_without_anchor
+                        ),
+                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                        position: None,
+                        owner: None,
+                        suggestions: vec![],
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// How HTML elements written in Markdown are handled. Built-in components
+/// are always rendered, whatever the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HtmlPolicy {
+    /// Render every element and attribute as written
+    Allow,
+    /// Remove elements and attributes that aren't known to be safe, like
+    /// `<script>` or `onclick`, and warn about them
+    #[default]
+    Sanitize,
+    /// Remove all elements, keeping the text inside them
+    Strip,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Radius {
+    None,
+    Small,
+    #[default]
+    Medium,
+    Large,
+    Full,
+}
+
+impl Display for Radius {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Radius::*;
+        match self {
+            None => write!(f, "none"),
+            Small => write!(f, "small"),
+            Medium => write!(f, "medium"),
+            Large => write!(f, "large"),
+            Full => write!(f, "full"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub header: Option<HeaderSettings>,
+    #[serde(default = "Theme::default")]
+    pub theme: Theme,
+    #[serde(default)]
+    pub open_api: Vec<OpenApi>,
+    #[serde(default)]
+    pub styles: Vec<PathBuf>,
+    #[serde(default)]
+    pub redirects: Vec<Redirect>,
+    #[serde(default, rename(deserialize = "tabs"))]
+    pub tab_descriptions: Vec<TabDescription>,
+    #[serde(default)]
+    pub footer: Footer,
+    #[serde(default)]
+    pub vale: Option<ValeSettings>,
+    /// Locales the documentation is available in, e.g. `[en, fr]`.
+    ///
+    /// Translations live in a folder named after the locale, mirroring the
+    /// structure of the default locale.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locales: Vec<String>,
+    /// The locale of pages outside the locale folders. Defaults to the first
+    /// entry in `locales`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_locale: Option<String>,
+    #[serde(default)]
+    pub search: SearchSettings,
+    /// Glob patterns for files that are left out of the published site, the
+    /// same way as drafts, e.g. `internal/**`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
+    /// What to do with HTML elements written in Markdown.
+    #[serde(default)]
+    pub html_policy: HtmlPolicy,
+    /// How page URLs are written, e.g. `/guides` or `/guides/`.
+    #[serde(default)]
+    pub url_style: UrlStyle,
+    /// Where the site is published, e.g. `https://docs.example.com`. Used
+    /// for links that have to work outside the site, like in feeds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// The version of the documented product, e.g. `2.1`. Pages can show it
+    /// with `{@project.version}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Feeds generated from the pages in a folder, like a changelog.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub feeds: Vec<FeedSettings>,
+    /// A generated page listing the `changelog` entries of every page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whats_new: Option<WhatsNewSettings>,
+    /// Which links to other sites `docapella check --external-links` checks.
+    #[serde(default)]
+    pub external_links: ExternalLinkSettings,
+    /// Layouts pages can be rendered with, and which pages use them.
+    #[serde(default)]
+    pub templates: TemplateSettings,
+    /// Checks for common accessibility problems in pages, and how they're reported.
+    #[serde(default)]
+    pub lints: LintSettings,
+    /// Markdown features that can be turned on or off for the whole project.
+    #[serde(default)]
+    pub markdown: MarkdownSettings,
+    /// Defaults for the groups in the navigation of every tab.
+    #[serde(default)]
+    pub navigation: NavigationSettings,
+    /// Canonical URLs for the pages in a folder, e.g. for pages mirrored from
+    /// another site. The first matching rule wins, and a `canonical_url` in a
+    /// page's frontmatter overrides them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub canonical_urls: Vec<CanonicalUrlRule>,
+    /// Analytics providers and scripts from other sites, added to every page.
+    #[serde(default)]
+    pub integrations: Integrations,
+    /// Looks for API keys and tokens in pages and OpenAPI specs. Off unless set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_scanning: Option<SecretScanning>,
+    /// Sites whose content can be embedded with `<Embed>`, by the name used
+    /// in its `provider` attribute.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub embeds: BTreeMap<String, EmbedProvider>,
+    /// Values pages, components, and navigation labels can insert with
+    /// `{@vars.name}`, e.g. the product name or the latest version.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, Variable>,
+    /// Per-locale overrides for `variables`, keyed by the locale.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variable_translations: BTreeMap<String, BTreeMap<String, Variable>>,
+    /// How the parts of the project's health score are weighed.
+    #[serde(default)]
+    pub health: HealthSettings,
+    /// Who owns the files in a folder, for routing their errors. Added after
+    /// the patterns in `.docapella-owners`, and the last matching pattern wins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<OwnerRule>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            title: String::from("unknown project"),
+            header: None,
+            theme: Theme::default(),
+            open_api: Vec::new(),
+            styles: Vec::new(),
+            redirects: Vec::new(),
+            tab_descriptions: Vec::new(),
+            footer: Footer::default(),
+            vale: None,
+            locales: Vec::new(),
+            default_locale: None,
+            search: SearchSettings::default(),
+            exclude: Vec::new(),
+            html_policy: HtmlPolicy::default(),
+            url_style: UrlStyle::default(),
+            base_url: None,
+            version: None,
+            feeds: Vec::new(),
+            whats_new: None,
+            external_links: ExternalLinkSettings::default(),
+            templates: TemplateSettings::default(),
+            lints: LintSettings::default(),
+            markdown: MarkdownSettings::default(),
+            navigation: NavigationSettings::default(),
+            canonical_urls: Vec::new(),
+            integrations: Integrations::default(),
+            secret_scanning: None,
+            embeds: BTreeMap::new(),
+            variables: BTreeMap::new(),
+            variable_translations: BTreeMap::new(),
+            health: HealthSettings::default(),
+            owners: Vec::new(),
+        }
+    }
+}
+
+/// A value under `variables`: text, a number, a boolean, or a map of more
+/// variables.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Variable {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Map(BTreeMap<String, Variable>),
+}
+
+impl Variable {
+    /// Applies the overrides of a locale, keeping the variables they don't
+    /// mention.
+    fn merge(&mut self, overrides: &Variable) {
+        match (self, overrides) {
+            (Variable::Map(map), Variable::Map(overrides)) => {
+                for (name, value) in overrides {
+                    match map.get_mut(name) {
+                        Some(existing) => existing.merge(value),
+                        None => {
+                            map.insert(name.clone(), value.clone());
+                        }
+                    }
+                }
+            }
+            (this, overrides) => *this = overrides.clone(),
+        }
+    }
+
+    /// Overrides in `overrides` that aren't in `self`, as dotted paths,
+    /// e.g. `support.emial`.
+    fn unknown_overrides(&self, overrides: &Variable, prefix: &str, out: &mut Vec<String>) {
+        if let (Variable::Map(map), Variable::Map(overrides)) = (self, overrides) {
+            for (name, value) in overrides {
+                let path = format!("{}{}", prefix, name);
+
+                match map.get(name) {
+                    Some(existing) => existing.unknown_overrides(value, &format!("{}.", path), out),
+                    None => out.push(path),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeedSettings {
+    /// Folder whose pages are the items of the feed, e.g. `/changelog`
+    pub path: String,
+    pub title: String,
+    /// How many of the latest pages are included
+    #[serde(default = "FeedSettings::default_limit")]
+    pub limit: usize,
+    #[serde(default)]
+    pub source: FeedSource,
+}
+
+/// What the items of a feed are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedSource {
+    /// The pages in the feed's folder, by their `date`
+    #[default]
+    Pages,
+    /// The `changelog` entries of every page, like on the `whats_new` page
+    Changelog,
+}
+
+/// The page listing the `changelog` entries in the frontmatter of pages,
+/// newest first.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WhatsNewSettings {
+    /// URI path of the page
+    #[serde(default = "WhatsNewSettings::default_path")]
+    pub path: String,
+    #[serde(default = "WhatsNewSettings::default_title")]
+    pub title: String,
+    /// Entries from before this date are left out, e.g. `2024-01-01`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// How many of the latest entries are listed
+    #[serde(default = "WhatsNewSettings::default_limit")]
+    pub limit: usize,
+    /// Add a link to the page at the end of the navigation it's in, unless
+    /// the navigation already links to it
+    #[serde(default)]
+    pub navigation: bool,
+}
+
+impl WhatsNewSettings {
+    fn default_path() -> String {
+        String::from("/whats-new")
+    }
+
+    fn default_title() -> String {
+        String::from("Documentation updates")
+    }
+
+    fn default_limit() -> usize {
+        100
+    }
+
+    /// The path of the page, without a trailing slash
+    pub fn uri_path(&self) -> String {
+        format!("/{}", self.path.trim_matches('/'))
+    }
+}
+
+impl FeedSettings {
+    fn default_limit() -> usize {
+        20
+    }
+
+    /// The path of the folder, without a trailing slash
+    pub fn uri_path(&self) -> String {
+        format!("/{}", self.path.trim_matches('/'))
+    }
+}
+
+/// URL prefixes, like `https://example.com/internal`, deciding which links
+/// to other sites are checked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalLinkSettings {
+    /// When set, only links matching one of these are checked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    /// Links that are never checked, e.g. sites that block bots
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+}
+
+impl ExternalLinkSettings {
+    pub fn should_check(&self, url: &str) -> bool {
+        let matches = |prefix: &String| url.starts_with(prefix.as_str());
+
+        (self.allow.is_empty() || self.allow.iter().any(matches))
+            && !self.ignore.iter().any(matches)
+    }
+}
+
+/// Named layouts the frontend can render pages with, like `landing` or
+/// `changelog`. Pages pick one with `template` in their frontmatter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateSettings {
+    /// The template names pages may use
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed: Vec<String>,
+    /// Used by pages that don't match any of the `rules`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Templates for the pages in a folder. The first matching rule wins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<TemplateRule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateRule {
+    /// Glob pattern for the files, like `changelog/**`
+    pub path: String,
+    pub template: String,
+}
+
+/// Gives the pages matching `path` canonical URLs under `url`. The folder the
+/// pattern starts with is replaced by the URL, so with `path: partner/**` and
+/// `url: https://partner.example.com/docs`, `/partner/setup` becomes
+/// `https://partner.example.com/docs/setup`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CanonicalUrlRule {
+    /// Glob pattern for the files, like `partner/**`
+    pub path: String,
+    pub url: String,
+}
+
+/// The owner of the files matching `path`, e.g. a team name or an email
+/// address.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OwnerRule {
+    /// Glob pattern for the files, like `guides/**`
+    pub path: String,
+    pub owner: String,
+}
+
+impl CanonicalUrlRule {
+    fn canonical_url(&self, uri_path: &str) -> String {
+        // The components of the pattern before the first wildcard
+        let folder = self
+            .path
+            .trim_start_matches('/')
+            .split('/')
+            .take_while(|part| !part.contains(['*', '?', '[', '{']))
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let path = uri_path.trim_matches('/');
+        let rest = if path == folder {
+            ""
+        } else if folder.is_empty() {
+            path
+        } else {
+            path.strip_prefix(&format!("{}/", folder)).unwrap_or(path)
+        };
+
+        if rest.is_empty() {
+            self.url.clone()
+        } else {
+            format!("{}/{}", self.url.trim_end_matches('/'), rest)
+        }
+    }
+}
+
+impl TemplateSettings {
+    pub fn is_allowed(&self, name: &str) -> bool {
+        self.allowed.iter().any(|t| t == name)
+    }
+
+    /// The template of a page that doesn't set one in its frontmatter.
+    pub(crate) fn default_for(&self, fs_path: &Path) -> Option<&str> {
+        let path = fs_path.strip_prefix("/").unwrap_or(fs_path);
+
+        self.rules
+            .iter()
+            .find(|rule| {
+                exclude_glob(&rule.path)
+                    .map(|glob| glob.compile_matcher().is_match(path))
+                    .unwrap_or(false)
+            })
+            .map(|rule| rule.template.as_str())
+            .or(self.default.as_deref())
+    }
+
+    pub(crate) fn unknown_template_description(&self, name: &str) -> String {
+        if self.allowed.is_empty() {
+            format!(
+                "Found template \"{}\", but no templates are declared.\nList the templates pages can use under `templates.allowed` in {}.",
+                name, SETTINGS_FILE_NAME
+            )
+        } else {
+            format!(
+                "Expected one of: {}.\nFound \"{}\".",
+                self.allowed.join(", "),
+                name
+            )
+        }
+    }
+}
+
+/// How a lint reports the problems it finds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    /// Fail verification
+    Error,
+    /// Report a warning
+    Warn,
+    #[default]
+    Off,
+}
+
+/// Levels of the accessibility lints. `accessibility` sets the level of every
+/// rule that isn't set on its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintSettings {
+    #[serde(default)]
+    pub accessibility: LintLevel,
+    /// Images without alt text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_alt: Option<LintLevel>,
+    /// Headings that skip a level, like an h3 right after an h1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading_increment: Option<LintLevel>,
+    /// Pages with more than one h1
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_h1: Option<LintLevel>,
+    /// Links whose only text is "here" or "click here"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_text: Option<LintLevel>,
+    /// Tables without a header row
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_header: Option<LintLevel>,
+    /// Pages that aren't in any navigation, and that no other page links to.
+    /// Not affected by `accessibility`.
+    #[serde(default = "LintSettings::default_orphan_pages")]
+    pub orphan_pages: LintLevel,
+}
 
-            if !is_external {
-                if to_without_anchor.contains(':') {
-                    if !PATH_REGEX.is_match(to_without_anchor) {
-                        errors.push(Error {
-                        code: Error::INVALID_REDIRECT,
-                        message: String::from("Invalid redirect destination"),
-                        description: format!(
-                            r#"Redirect destination "{}" path parameters can only contain alphanumerics and underscores."#,
-                            to
-                        ),
-                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-            position: None,
-                    });
-                    }
+impl Default for LintSettings {
+    fn default() -> Self {
+        LintSettings {
+            accessibility: LintLevel::default(),
+            image_alt: None,
+            heading_increment: None,
+            single_h1: None,
+            link_text: None,
+            table_header: None,
+            orphan_pages: LintSettings::default_orphan_pages(),
+        }
+    }
+}
 
-                    if !from_without_anchor.contains('*') {
-                        errors.push(Error {
-                          code: Error::INVALID_REDIRECT,
-                          message: String::from("Invalid redirect source"),
-                          description: format!(r#"Redirect source "{}" should include a wildcard when `to` has path parameters."#, from),
-                          file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-            position: None,
-                      });
-                    }
-                }
+impl LintSettings {
+    fn default_orphan_pages() -> LintLevel {
+        LintLevel::Warn
+    }
 
-                if !to.contains(':') && !pages.iter().any(|p| p.uri_path() == to_without_anchor) {
-                    errors.push(Error {
-                        code: Error::INVALID_REDIRECT,
-                        message: String::from("Broken redirect detected"),
-                        description: format!(
-                            r#"Redirect destination "{}" does not exist."#,
-                            to_without_anchor
+    pub(crate) fn level(&self, rule: Rule) -> LintLevel {
+        let own = match rule {
+            Rule::ImageAlt => self.image_alt,
+            Rule::HeadingIncrement => self.heading_increment,
+            Rule::SingleH1 => self.single_h1,
+            Rule::LinkText => self.link_text,
+            Rule::TableHeader => self.table_header,
+        };
+
+        own.unwrap_or(self.accessibility)
+    }
+}
+
+/// Settings for the health report of `docapella check` and
+/// `docapella build --health`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthSettings {
+    #[serde(default)]
+    pub weights: HealthWeights,
+}
+
+/// How much each part of the health score counts, relative to the others.
+/// A weight of 0 leaves the part out of the score.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthWeights {
+    #[serde(default = "HealthWeights::default_errors")]
+    pub errors: u32,
+    #[serde(default = "HealthWeights::default_weight")]
+    pub warnings: u32,
+    #[serde(default = "HealthWeights::default_weight")]
+    pub orphan_pages: u32,
+    /// Pages with a description
+    #[serde(default = "HealthWeights::default_weight")]
+    pub search_coverage: u32,
+    /// Operations and parameters with a description
+    #[serde(default = "HealthWeights::default_weight")]
+    pub open_api_coverage: u32,
+    #[serde(default = "HealthWeights::default_weight")]
+    pub accessibility: u32,
+}
+
+impl HealthWeights {
+    fn default_errors() -> u32 {
+        3
+    }
+
+    fn default_weight() -> u32 {
+        1
+    }
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        HealthWeights {
+            errors: HealthWeights::default_errors(),
+            warnings: HealthWeights::default_weight(),
+            orphan_pages: HealthWeights::default_weight(),
+            search_coverage: HealthWeights::default_weight(),
+            open_api_coverage: HealthWeights::default_weight(),
+            accessibility: HealthWeights::default_weight(),
+        }
+    }
+}
+
+/// Markdown features that can be turned on or off. The defaults match how
+/// Markdown has always been parsed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarkdownSettings {
+    /// Curly quotes, dashes and ellipses in place of `"`, `--` and `...`
+    #[serde(default)]
+    pub smart_punctuation: bool,
+    /// Line breaks inside paragraphs are kept, instead of being joined into
+    /// one line
+    #[serde(default)]
+    pub hard_breaks: bool,
+    /// Math between `$` and `$$` delimiters
+    #[serde(default = "MarkdownSettings::default_math")]
+    pub math: bool,
+    /// Headings get a link to themselves, shown when hovered
+    #[serde(default)]
+    pub heading_anchor_links: bool,
+}
+
+impl MarkdownSettings {
+    fn default_math() -> bool {
+        true
+    }
+}
+
+impl Default for MarkdownSettings {
+    fn default() -> Self {
+        MarkdownSettings {
+            smart_punctuation: false,
+            hard_breaks: false,
+            math: MarkdownSettings::default_math(),
+            heading_anchor_links: false,
+        }
+    }
+}
+
+/// Defaults for the navigation, for groups that don't set them in
+/// `navigation.yaml`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NavigationSettings {
+    /// Sections with a heading, and items with nested items, start collapsed
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+/// Analytics providers and scripts from other sites, added to the `<head>`
+/// of every page. Scripts are only loaded from `https://` URLs; inline
+/// scripts aren't supported.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Integrations {
+    /// The domain of the site in Plausible, e.g. `docs.example.com`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plausible: Option<String>,
+    /// The site ID in Fathom, e.g. `ABCDEFGH`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fathom: Option<String>,
+    /// The Google Analytics measurement ID, e.g. `G-XXXXXXXXXX`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gtag: Option<String>,
+    /// Every script in `scripts` has to have an `integrity` hash
+    #[serde(default)]
+    pub require_sri: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scripts: Vec<Script>,
+}
+
+/// A script loaded from another site, like a support widget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    pub src: String,
+    #[serde(default)]
+    pub defer: bool,
+    #[serde(default, rename = "async")]
+    pub is_async: bool,
+    /// Subresource integrity hash, e.g. `sha384-oqVuAfXR...`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub integrity: Option<String>,
+}
+
+impl Integrations {
+    /// The values end up in the HTML of every page, so they're checked when
+    /// the settings are parsed, with errors pointing into `input`.
+    fn verify(&self, input: &str) -> Result<()> {
+        let providers = [
+            ("plausible", &self.plausible, "docs.example.com"),
+            ("fathom", &self.fathom, "ABCDEFGH"),
+            ("gtag", &self.gtag, "G-XXXXXXXXXX"),
+        ];
+
+        for (provider, id, example) in providers {
+            if let Some(id) = id.as_ref().filter(|id| !is_integration_id(id)) {
+                return Err(invalid_integration(
+                    input,
+                    &[integration_key(provider)],
+                    format!("Invalid `{}` integration", provider),
+                    format!(
+                        "Expected the site or measurement ID, like \"{}\".\nFound \"{}\".",
+                        example, id
+                    ),
+                ));
+            }
+        }
+
+        for (index, script) in self.scripts.iter().enumerate() {
+            let at = |key: &str| {
+                [
+                    integration_key("scripts"),
+                    Segment::Seq { index },
+                    integration_key(key),
+                ]
+            };
+
+            match Url::parse(&script.src) {
+                Ok(url) if url.scheme() == "https" => {}
+                Ok(_) => {
+                    return Err(invalid_integration(
+                        input,
+                        &at("src"),
+                        String::from("Scripts must be loaded over HTTPS"),
+                        format!(
+                            "Found \"{}\". Use an `https://` URL for the script.",
+                            script.src
                         ),
-                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-                        position: None,
-                    });
+                    ))
                 }
-
-                if !to_without_anchor.starts_with('/') {
-                    errors.push(Error {
-                        code: Error::INVALID_REDIRECT,
-                        message: String::from("Invalid redirect detected"),
-                        description: format!(
-                            r#"Redirect destination "{}" must start with a forward slash, or be an external URL."#,
-                            to_without_anchor
+                Err(_) => {
+                    return Err(invalid_integration(
+                        input,
+                        &at("src"),
+                        String::from("Invalid script URL"),
+                        format!(
+                            "Expected an absolute `https://` URL, like \"https://cdn.example.com/widget.js\". Inline scripts aren't supported.\nFound \"{}\".",
+                            script.src
                         ),
-                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-                        position: None,
-                    });
+                    ))
                 }
+            }
 
-                if to_without_anchor.ends_with(".md") {
-                    errors.push(Error {
-                        code: Error::INVALID_REDIRECT,
-                        message: String::from("Invalid redirect detected"),
-                        description: format!(
-                            r#"Redirect destination "{}" must not end with .md."#,
-                            to_without_anchor
+            match &script.integrity {
+                Some(integrity) if !is_integrity_hash(integrity) => {
+                    return Err(invalid_integration(
+                        input,
+                        &at("integrity"),
+                        String::from("Invalid script integrity hash"),
+                        format!(
+                            "Expected a `sha256-`, `sha384-` or `sha512-` hash, like the ones CDNs list next to their scripts.\nFound \"{}\".",
+                            integrity
                         ),
-                        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-                        position: None,
-                    });
+                    ));
+                }
+                None if self.require_sri => {
+                    return Err(invalid_integration(
+                        input,
+                        &at("src"),
+                        String::from("Missing script integrity hash"),
+                        format!(
+                            "`require_sri` is set, but the script \"{}\" has no `integrity` hash.",
+                            script.src
+                        ),
+                    ));
                 }
+                _ => {}
             }
         }
+
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "snake_case")]
-pub enum Radius {
-    None,
-    Small,
-    #[default]
-    Medium,
-    Large,
-    Full,
+fn integration_key(key: &str) -> Segment {
+    Segment::Map {
+        key: key.to_owned(),
+    }
 }
 
-impl Display for Radius {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use Radius::*;
-        match self {
-            None => write!(f, "none"),
-            Small => write!(f, "small"),
-            Medium => write!(f, "medium"),
-            Large => write!(f, "large"),
-            Full => write!(f, "full"),
+fn invalid_integration(
+    input: &str,
+    path: &[Segment],
+    message: String,
+    description: String,
+) -> Error {
+    let path = [&[integration_key("integrations")], path].concat();
+
+    Error {
+        code: Error::INVALID_DOCTAVE_YAML,
+        message,
+        description,
+        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+        position: crate::yaml::position_of(input, &path),
+        owner: None,
+        suggestions: vec![],
+    }
+}
+
+/// IDs are written into scripts as they are, so only the characters the
+/// providers use are allowed
+fn is_integration_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+fn is_integrity_hash(integrity: &str) -> bool {
+    ["sha256-", "sha384-", "sha512-"].iter().any(|prefix| {
+        integrity.strip_prefix(prefix).is_some_and(|hash| {
+            !hash.is_empty()
+                && hash
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/' || c == '=')
+        })
+    })
+}
+
+/// Patterns for API keys and tokens that shouldn't be published, on top of
+/// the built in ones, and the matches that are fine to keep.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecretScanning {
+    /// How matches are reported. Fails verification unless set otherwise.
+    #[serde(default = "SecretScanning::default_level")]
+    pub level: LintLevel,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patterns: Vec<SecretPattern>,
+    /// Regexes for values that only look like secrets, like `YOUR_API_KEY`.
+    /// Matched case-insensitively, in addition to the built in ones.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub placeholders: Vec<String>,
+    /// SHA-256 hashes of matches that aren't secrets, as shown in the
+    /// error about them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecretPattern {
+    pub name: String,
+    pub regex: String,
+}
+
+impl SecretScanning {
+    fn default_level() -> LintLevel {
+        LintLevel::Error
+    }
+
+    fn verify(&self, input: &str) -> Result<()> {
+        let key = |key: &str| Segment::Map {
+            key: key.to_owned(),
+        };
+
+        let regexes = self
+            .patterns
+            .iter()
+            .enumerate()
+            .map(|(index, pattern)| {
+                (
+                    vec![key("patterns"), Segment::Seq { index }, key("regex")],
+                    pattern.regex.as_str(),
+                )
+            })
+            .chain(self.placeholders.iter().enumerate().map(|(index, regex)| {
+                (
+                    vec![key("placeholders"), Segment::Seq { index }],
+                    regex.as_str(),
+                )
+            }));
+
+        for (path, regex) in regexes {
+            if let Err(e) = Regex::new(regex) {
+                let path = [&[key("secret_scanning")], path.as_slice()].concat();
+
+                return Err(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid secret scanning regex"),
+                    description: format!("Could not parse \"{}\":\n\n{}", regex, e),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: crate::yaml::position_of(input, &path),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
+        for (index, hash) in self.allow.iter().enumerate() {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                let path = [key("secret_scanning"), key("allow"), Segment::Seq { index }];
+
+                return Err(Error {
+                    code: Error::INVALID_DOCTAVE_YAML,
+                    message: String::from("Invalid allowed secret"),
+                    description: format!(
+                        "Expected the SHA-256 hash of the match, as shown in the error about it.\nFound \"{}\".",
+                        hash
+                    ),
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: crate::yaml::position_of(input, &path),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
         }
+
+        Ok(())
     }
 }
 
+/// A site whose content can be embedded with `<Embed>`, like videos or code
+/// sandboxes. Embeds are loaded in a sandboxed frame, and only once the
+/// reader clicks on them.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
-pub struct Settings {
-    pub title: String,
-    #[serde(skip_serializing_if = "Option::is_none", default)]
-    pub header: Option<HeaderSettings>,
-    #[serde(default = "Theme::default")]
-    pub theme: Theme,
-    #[serde(default)]
-    pub open_api: Vec<OpenApi>,
+pub struct EmbedProvider {
+    /// Where the content is loaded from, with `{id}` where the ID goes, e.g.
+    /// `https://www.youtube.com/embed/{id}`
+    pub url: String,
+    /// Used instead of `url` with `privacy_mode`, e.g.
+    /// `https://www.youtube-nocookie.com/embed/{id}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub privacy_url: Option<String>,
     #[serde(default)]
-    pub styles: Vec<PathBuf>,
+    pub privacy_mode: bool,
+    /// Regex the whole ID has to match
+    #[serde(default = "EmbedProvider::default_id_pattern")]
+    pub id_pattern: String,
+    /// Attributes of `<Embed>` that are passed on as query parameters, e.g.
+    /// `start` for where a video starts
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<String>,
+    /// Width to height, e.g. `16:9`. Can be set for each embed too.
+    #[serde(default = "EmbedProvider::default_aspect_ratio")]
+    pub aspect_ratio: String,
+}
+
+/// Attributes of `<Embed>` itself, which can't be used as parameters
+pub(crate) const EMBED_ATTRIBUTES: &[&str] = &["provider", "id", "title", "aspect_ratio"];
+
+impl EmbedProvider {
+    fn default_id_pattern() -> String {
+        String::from("[A-Za-z0-9_-]+")
+    }
+
+    fn default_aspect_ratio() -> String {
+        String::from("16:9")
+    }
+
+    /// The URL template for the provider's mode
+    pub fn url_template(&self) -> &str {
+        match &self.privacy_url {
+            Some(url) if self.privacy_mode => url,
+            _ => &self.url,
+        }
+    }
+
+    /// Matches whole IDs. Checked when the settings are parsed.
+    pub(crate) fn id_regex(&self) -> Option<Regex> {
+        Regex::new(&format!("^(?:{})$", self.id_pattern)).ok()
+    }
+
+    /// Parses an aspect ratio like `16:9` into its width and height.
+    pub(crate) fn parse_aspect_ratio(ratio: &str) -> Option<(f32, f32)> {
+        let (width, height) = ratio.split_once(':')?;
+        let width = width.trim().parse::<f32>().ok()?;
+        let height = height.trim().parse::<f32>().ok()?;
+
+        (width > 0.0 && height > 0.0).then_some((width, height))
+    }
+
+    fn verify(&self, name: &str, input: &str) -> Result<()> {
+        let error = |field: &str, message: &str, description: String| {
+            let path = [
+                Segment::Map {
+                    key: String::from("embeds"),
+                },
+                Segment::Map {
+                    key: name.to_owned(),
+                },
+                Segment::Map {
+                    key: field.to_owned(),
+                },
+            ];
+
+            Err(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: message.to_owned(),
+                description,
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: crate::yaml::position_of(input, &path),
+                owner: None,
+                suggestions: vec![],
+            })
+        };
+
+        let templates = std::iter::once(("url", &self.url))
+            .chain(self.privacy_url.as_ref().map(|url| ("privacy_url", url)));
+
+        for (field, template) in templates {
+            let valid = template.starts_with("https://")
+                && template.contains("{id}")
+                && Url::parse(&template.replace("{id}", "id")).is_ok();
+
+            if !valid {
+                return error(
+                    field,
+                    "Invalid embed URL",
+                    format!(
+                        "Expected an https:// URL with `{{id}}` where the ID goes, like \"https://www.youtube.com/embed/{{id}}\".\nFound \"{}\".",
+                        template
+                    ),
+                );
+            }
+        }
+
+        if self.privacy_mode && self.privacy_url.is_none() {
+            return error(
+                "privacy_mode",
+                "Missing embed privacy URL",
+                format!(
+                    "The embed provider \"{}\" has `privacy_mode` set, but no `privacy_url` to load embeds from.",
+                    name
+                ),
+            );
+        }
+
+        if let Err(e) = Regex::new(&self.id_pattern) {
+            return error(
+                "id_pattern",
+                "Invalid embed ID pattern",
+                format!("Could not parse \"{}\":\n\n{}", self.id_pattern, e),
+            );
+        }
+
+        if let Some(param) = self
+            .params
+            .iter()
+            .find(|p| EMBED_ATTRIBUTES.contains(&p.as_str()))
+        {
+            return error(
+                "params",
+                "Invalid embed parameter",
+                format!(
+                    "`{}` is an attribute of `<Embed>` itself, so it can't be passed on as a parameter.",
+                    param
+                ),
+            );
+        }
+
+        if Self::parse_aspect_ratio(&self.aspect_ratio).is_none() {
+            return error(
+                "aspect_ratio",
+                "Invalid embed aspect ratio",
+                format!(
+                    "Expected a width and height like \"16:9\".\nFound \"{}\".",
+                    self.aspect_ratio
+                ),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SearchSettings {
     #[serde(default)]
-    pub redirects: Vec<Redirect>,
-    #[serde(default, rename(deserialize = "tabs"))]
-    pub tab_descriptions: Vec<TabDescription>,
+    pub boosts: SearchBoosts,
+    /// Keep pages with `noindex: true` in the site's own search, while still
+    /// asking search engines not to index them.
     #[serde(default)]
-    pub footer: Footer,
+    pub include_noindex: bool,
+    /// Write a list of every page, heading, and operation next to the built
+    /// site, for a command palette.
     #[serde(default)]
-    pub vale: Option<ValeSettings>,
+    pub quick_nav: bool,
 }
 
-impl Default for Settings {
-    fn default() -> Self {
-        Settings {
-            title: String::from("unknown project"),
-            header: None,
-            theme: Theme::default(),
-            open_api: Vec::new(),
-            styles: Vec::new(),
-            redirects: Vec::new(),
-            tab_descriptions: Vec::new(),
-            footer: Footer::default(),
-            vale: None,
+/// How much matches in different parts of a search record weigh. Stored in
+/// every record of the search index, multiplied by the page's `search_boost`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchBoosts {
+    #[serde(default = "SearchBoosts::default_title")]
+    pub title: f32,
+    #[serde(default = "SearchBoosts::default_headings")]
+    pub headings: f32,
+    #[serde(default = "SearchBoosts::default_body")]
+    pub body: f32,
+}
+
+impl SearchBoosts {
+    fn default_title() -> f32 {
+        3.0
+    }
+
+    fn default_headings() -> f32 {
+        2.0
+    }
+
+    fn default_body() -> f32 {
+        1.0
+    }
+}
+
+impl Default for SearchBoosts {
+    fn default() -> Self {
+        SearchBoosts {
+            title: Self::default_title(),
+            headings: Self::default_headings(),
+            body: Self::default_body(),
         }
     }
 }
@@ -621,6 +2404,12 @@ pub struct ColorsV2Description {
     accent: String,
     #[serde(default)]
     grayscale: Grayscale,
+    #[serde(default)]
+    light: AppearanceColors,
+    #[serde(default)]
+    dark: AppearanceColors,
+    #[serde(default)]
+    tokens: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -632,6 +2421,42 @@ pub struct ColorsV2 {
     original_accent: String,
     #[serde(default)]
     grayscale: Grayscale,
+    #[serde(default, skip_serializing_if = "AppearanceColors::is_empty")]
+    light: AppearanceColors,
+    #[serde(default, skip_serializing_if = "AppearanceColors::is_empty")]
+    dark: AppearanceColors,
+    /// Extra named colors, e.g. `warning`, emitted as `--warning` for both
+    /// light and dark mode.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tokens: BTreeMap<String, String>,
+}
+
+/// Color settings that only apply to either light or dark mode.
+///
+/// Anything not set here falls back to the shared color settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AppearanceColors {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    accent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    grayscale: Option<Grayscale>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    background: Option<String>,
+    /// Overrides for individual steps of the accent scale, keyed by step number
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    accent_steps: BTreeMap<usize, String>,
+    /// Overrides for individual steps of the gray scale, keyed by step number
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    gray_steps: BTreeMap<usize, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tokens: BTreeMap<String, String>,
+}
+
+impl AppearanceColors {
+    fn is_empty(&self) -> bool {
+        self == &AppearanceColors::default()
+    }
 }
 
 impl From<ColorsV2Description> for ColorsV2 {
@@ -646,6 +2471,9 @@ impl From<ColorsV2Description> for ColorsV2 {
             accent,
             original_accent: value.accent.clone(),
             grayscale: value.grayscale,
+            light: value.light,
+            dark: value.dark,
+            tokens: value.tokens,
         }
     }
 }
@@ -656,10 +2484,219 @@ impl Default for ColorsV2 {
             accent: "#5B5BD6".to_string(), // Radix Iris 9
             original_accent: "#5B5BD6".to_string(),
             grayscale: Grayscale::default(),
+            light: AppearanceColors::default(),
+            dark: AppearanceColors::default(),
+            tokens: BTreeMap::new(),
+        }
+    }
+}
+
+impl ColorsV2 {
+    /// Whether `name` is one of the named colors in `tokens`, for either
+    /// light or dark mode.
+    pub(crate) fn has_token(&self, name: &str) -> bool {
+        self.tokens.contains_key(name)
+            || self.light.tokens.contains_key(name)
+            || self.dark.tokens.contains_key(name)
+    }
+
+    /// Generates the CSS variables for both light and dark mode.
+    ///
+    /// Invalid colors are skipped here, and reported as errors when verifying
+    /// the settings.
+    pub(crate) fn css(&self) -> String {
+        let generator = ColorGenerator::new();
+
+        let light_css = self
+            .scale(&generator, Appearance::Light, &self.light, "#ffffff")
+            .generate_css("accent", ":root, .light, .light-theme");
+        let dark_css = self
+            .scale(&generator, Appearance::Dark, &self.dark, "#000000")
+            .generate_css("accent", ".dark, .dark-theme");
+
+        format!("{}\n\n{}", light_css, dark_css)
+    }
+
+    /// Generates the CSS variables for another accent color, scoped to the
+    /// given selector. Used by tabs with their own accent color.
+    ///
+    /// Returns `None` for invalid colors, which are reported when verifying
+    /// the tabs.
+    pub(crate) fn accent_css(&self, accent: &str, selector: &str) -> Option<String> {
+        if !is_full_hex_color(accent) {
+            return None;
+        }
+
+        // The accent overrides of the theme would win over the tab's accent
+        let colors = ColorsV2 {
+            accent: accent.to_string(),
+            light: AppearanceColors {
+                accent: None,
+                accent_steps: BTreeMap::new(),
+                ..self.light.clone()
+            },
+            dark: AppearanceColors {
+                accent: None,
+                accent_steps: BTreeMap::new(),
+                ..self.dark.clone()
+            },
+            ..self.clone()
+        };
+
+        let generator = ColorGenerator::new();
+
+        let light_css = colors
+            .scale(&generator, Appearance::Light, &colors.light, "#ffffff")
+            .generate_css("accent", selector);
+        let dark_css = colors
+            .scale(&generator, Appearance::Dark, &colors.dark, "#000000")
+            .generate_css(
+                "accent",
+                &format!(".dark {}, .dark-theme {}", selector, selector),
+            );
+
+        Some(format!("{}\n\n{}", light_css, dark_css))
+    }
+
+    fn scale(
+        &self,
+        generator: &ColorGenerator,
+        appearance: Appearance,
+        colors: &AppearanceColors,
+        default_background: &str,
+    ) -> Scale {
+        let valid = |color: &&String| is_full_hex_color(color);
+
+        let accent = colors.accent.as_ref().filter(valid).unwrap_or(&self.accent);
+        let grayscale = colors.grayscale.as_ref().unwrap_or(&self.grayscale);
+        let background = colors
+            .background
+            .as_ref()
+            .filter(valid)
+            .map(String::as_str)
+            .unwrap_or(default_background);
+
+        let valid_steps = |steps: &BTreeMap<usize, String>| {
+            steps
+                .iter()
+                .filter(|(step, color)| (1..=12).contains(*step) && is_full_hex_color(color))
+                .map(|(step, color)| (*step, color.clone()))
+                .collect()
+        };
+
+        let overrides = ScaleOverrides {
+            accent_steps: valid_steps(&colors.accent_steps),
+            gray_steps: valid_steps(&colors.gray_steps),
+            named_colors: self
+                .tokens
+                .iter()
+                .chain(colors.tokens.iter())
+                .filter(|(name, color)| is_token_name(name) && is_full_hex_color(color))
+                .map(|(name, color)| (name.clone(), color.clone()))
+                .collect(),
+        };
+
+        // Invalid overrides were filtered out above, so this only falls back
+        // to the plain scale if the generator is stricter than the settings.
+        generator
+            .generate_scale_with_overrides(
+                appearance,
+                accent,
+                grayscale.as_str(),
+                background,
+                &overrides,
+            )
+            .unwrap_or_else(|_| {
+                generator.generate_scale(appearance, accent, grayscale.as_str(), background)
+            })
+    }
+
+    /// Checks the colors that the user defined, naming the exact key of any
+    /// invalid value, e.g. `theme.colors.dark.accent_steps.9`.
+    fn verify(&self, errors: &mut Vec<Error>) {
+        verify_color_tokens("theme.colors.tokens", &self.tokens, errors);
+
+        for (appearance, colors) in [("light", &self.light), ("dark", &self.dark)] {
+            let prefix = format!("theme.colors.{}", appearance);
+
+            for (key, color) in [
+                ("accent", &colors.accent),
+                ("background", &colors.background),
+            ] {
+                if let Some(color) = color.as_ref().filter(|c| !is_full_hex_color(c)) {
+                    errors.push(invalid_theme_color(&format!("{}.{}", prefix, key), color));
+                }
+            }
+
+            for (key, steps) in [
+                ("accent_steps", &colors.accent_steps),
+                ("gray_steps", &colors.gray_steps),
+            ] {
+                for (step, color) in steps {
+                    let key = format!("{}.{}.{}", prefix, key, step);
+
+                    if !(1..=12).contains(step) {
+                        errors.push(Error {
+                            code: Error::INVALID_DOCTAVE_YAML,
+                            message: format!("Invalid color step `{}` in theme", key),
+                            description: format!(
+                                "Expected a step between 1 and 12.\nFound {}.",
+                                step
+                            ),
+                            file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                            position: None,
+                            owner: None,
+                            suggestions: vec![],
+                        });
+                    } else if !is_full_hex_color(color) {
+                        errors.push(invalid_theme_color(&key, color));
+                    }
+                }
+            }
+
+            verify_color_tokens(&format!("{}.tokens", prefix), &colors.tokens, errors);
+        }
+    }
+}
+
+fn verify_color_tokens(prefix: &str, tokens: &BTreeMap<String, String>, errors: &mut Vec<Error>) {
+    for (name, color) in tokens {
+        let key = format!("{}.{}", prefix, name);
+
+        if !is_token_name(name) {
+            errors.push(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: format!("Invalid color token name `{}` in theme", key),
+                description: format!(
+                    "Token names may only contain lowercase letters, numbers, and dashes.\nFound \"{}\".",
+                    name
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        } else if !is_full_hex_color(color) {
+            errors.push(invalid_theme_color(&key, color));
         }
     }
 }
 
+fn invalid_theme_color(key: &str, color: &str) -> Error {
+    Error {
+        code: Error::INVALID_DOCTAVE_YAML,
+        message: format!("Invalid color for `{}` in theme", key),
+        description: format!(
+            "Expected a HEX color code, like \"#ffb224\".\nFound \"{}\".",
+            color
+        ),
+        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+        position: None,
+        owner: None,
+        suggestions: vec![],
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub enum Grayscale {
     #[default]
@@ -677,6 +2714,19 @@ pub enum Grayscale {
     Sand,
 }
 
+impl Grayscale {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Grayscale::Gray => "gray",
+            Grayscale::Mauve => "mauve",
+            Grayscale::Slate => "slate",
+            Grayscale::Sage => "sage",
+            Grayscale::Olive => "olive",
+            Grayscale::Sand => "sand",
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ColorMode {
     #[serde(rename = "auto")]
@@ -1163,6 +3213,8 @@ impl Logo {
                     ),
                     file: Some(PathBuf::from(crate::SETTINGS_FILE_NAME)),
             position: None,
+            owner: None,
+            suggestions: vec![],
                 });
         }
 
@@ -1188,6 +3240,8 @@ impl Logo {
                     ),
                     file: Some(PathBuf::from(crate::SETTINGS_FILE_NAME)),
             position: None,
+            owner: None,
+            suggestions: vec![],
                 });
             }
         }
@@ -1204,6 +3258,57 @@ impl Redirect {
     pub fn as_tuple(&self) -> (String, String) {
         (self.from.clone(), self.to.clone())
     }
+
+    /// The URI path the redirect is from, without an anchor or URL style.
+    pub fn source(&self) -> &str {
+        crate::strip_url_style(self.from.split('#').next().unwrap_or_default())
+    }
+
+    /// Whether the redirect is for every path under a prefix, like `/blog/*`
+    /// or `/blog/**`.
+    pub fn is_wildcard(&self) -> bool {
+        self.source().ends_with("/*") || self.source().ends_with("/**")
+    }
+
+    /// Where a request for `uri_path` is redirected to, if the redirect
+    /// matches it. A `*` matches a single path segment and a `**` any number
+    /// of them, which replace the path parameters of the destination.
+    ///
+    /// Doesn't take pages into account, see [`Project::resolve_redirect`].
+    ///
+    /// [`Project::resolve_redirect`]: crate::Project::resolve_redirect
+    pub fn destination_for(&self, uri_path: &str) -> Option<String> {
+        let source = self.source();
+        let path = crate::strip_url_style(uri_path.split('#').next().unwrap_or_default());
+
+        let rest = if let Some(prefix) = source.strip_suffix("/**") {
+            path.strip_prefix(prefix)?.strip_prefix('/')?
+        } else if let Some(prefix) = source.strip_suffix("/*") {
+            path.strip_prefix(prefix)?
+                .strip_prefix('/')
+                .filter(|rest| !rest.contains('/'))?
+        } else {
+            return (path == source).then(|| self.to.clone());
+        };
+
+        if rest.is_empty() {
+            return None;
+        }
+
+        Some(
+            self.to
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with(':') {
+                        rest
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1240,6 +3345,8 @@ impl HeaderLink {
                             ),
                             file: Some(SETTINGS_FILE_NAME.into()),
                             position: None,
+                            owner: None,
+                            suggestions: vec![],
                         };
 
                         errors.push(error);
@@ -1249,7 +3356,7 @@ impl HeaderLink {
                     let uri = crate::fs_to_uri_path(&path);
 
                     if project.get_page_by_uri_path(&uri).is_none()
-                        && !project.redirects().iter().any(|r| r.0 == uri)
+                        && !project.has_redirect_from(&uri)
                     {
                         let thing = match verify_as {
                             "footer" => "Footer",
@@ -1264,6 +3371,8 @@ impl HeaderLink {
                             ),
                             file: Some(SETTINGS_FILE_NAME.into()),
                             position: None,
+                            owner: None,
+                            suggestions: vec![],
                         };
 
                         errors.push(error);
@@ -1285,6 +3394,8 @@ impl HeaderLink {
                         ),
                         file: Some(SETTINGS_FILE_NAME.into()),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     })
                 }
             }
@@ -1324,6 +3435,64 @@ pub struct ExternalLink {
     pub external: String,
 }
 
+/// Status codes are usually written as numbers, e.g. `429:`, which aren't
+/// strings once merge keys have been applied.
+fn status_code_keys<'de, D>(
+    deserializer: D,
+) -> std::result::Result<BTreeMap<String, DefaultResponse>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    #[serde(untagged)]
+    enum StatusCode {
+        Number(u16),
+        Text(String),
+    }
+
+    let responses: BTreeMap<StatusCode, DefaultResponse> = Deserialize::deserialize(deserializer)?;
+
+    Ok(responses
+        .into_iter()
+        .map(|(status, response)| match status {
+            StatusCode::Number(code) => (code.to_string(), response),
+            StatusCode::Text(text) => (text, response),
+        })
+        .collect())
+}
+
+fn uri_prefix_path(index: usize) -> [Segment; 3] {
+    [
+        Segment::Map {
+            key: String::from("open_api"),
+        },
+        Segment::Seq { index },
+        Segment::Map {
+            key: String::from("uri_prefix"),
+        },
+    ]
+}
+
+fn redirect_from_path(index: usize) -> [Segment; 3] {
+    [
+        Segment::Map {
+            key: String::from("redirects"),
+        },
+        Segment::Seq { index },
+        Segment::Map {
+            key: String::from("from"),
+        },
+    ]
+}
+
+/// Whether `uri_path` is `prefix` or a path under it
+fn is_under_prefix(uri_path: &str, prefix: &str) -> bool {
+    uri_path
+        .strip_prefix(prefix)
+        .map(|rest| rest.is_empty() || rest.starts_with('/'))
+        .unwrap_or(false)
+}
+
 fn normalize_uri_path<'de, D>(deserializer: D) -> std::result::Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -1334,11 +3503,207 @@ where
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OpenApi {
+    /// Where the spec is in the project. For specs fetched from `spec_url`,
+    /// where the fetched copy is placed, which defaults to a path based on
+    /// the URL.
+    #[serde(default)]
     pub spec_file: PathBuf,
+    /// A URL the spec is fetched from, instead of being part of the project.
+    /// Fetching is left to the caller, which gives the spec to the project
+    /// as the file at `spec_file`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spec_url: Option<String>,
     #[serde(deserialize_with = "normalize_uri_path")]
     pub uri_prefix: String,
     #[serde(default)]
     pub experimental: bool,
+    /// Validate the examples in the spec against their schemas.
+    #[serde(default)]
+    pub validate_examples: bool,
+    /// Markdown file that replaces the generated overview page.
+    #[serde(default)]
+    pub overview_template: Option<PathBuf>,
+    /// Folder of Markdown files added to the pages of operations, each named
+    /// after an operationId. Defaults to `DEFAULT_AUGMENTATIONS_DIR`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub augmentations_dir: Option<PathBuf>,
+    /// Schemas nested deeper than this are collapsed, and loaded on demand.
+    /// Defaults to `DEFAULT_MAX_SCHEMA_DEPTH`.
+    #[serde(default)]
+    pub max_schema_depth: Option<usize>,
+    /// Fail the build if the spec has more operations than this.
+    #[serde(default)]
+    pub max_operations: Option<usize>,
+    /// Fail the build if parsing the spec takes more schemas than this.
+    #[serde(default)]
+    pub max_schema_nodes: Option<usize>,
+    /// Fail the build if a single schema expands to more schemas than this.
+    #[serde(default)]
+    pub max_schema_expansion: Option<usize>,
+    /// Show deprecated operations in a banner at the top of their pages, and
+    /// list them last in the navigation.
+    #[serde(default)]
+    pub deprecation_banner: bool,
+    /// The extension on operations with the date they will be removed.
+    /// Defaults to `DEFAULT_SUNSET_FIELD`.
+    #[serde(default)]
+    pub sunset_header_field: Option<String>,
+    /// Warn about links from other pages to deprecated operations.
+    #[serde(default)]
+    pub warn_deprecated_links: bool,
+    /// Link inline code naming an operation or schema of the spec, like
+    /// `` `POST /pets` ``, to the API reference.
+    #[serde(default)]
+    pub auto_link_code: bool,
+    /// What the anchors of operations are derived from.
+    #[serde(default)]
+    pub uri_strategy: UriStrategy,
+    /// Responses shown on every operation that doesn't document the status
+    /// code itself, keyed by the status code, e.g. a `429` for rate limits.
+    #[serde(
+        default,
+        deserialize_with = "status_code_keys",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub default_responses: BTreeMap<String, DefaultResponse>,
+}
+
+/// A response from the `default_responses` of a spec. Shown on operations
+/// alongside the responses from the spec, marked as an API-wide default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefaultResponse {
+    pub description: String,
+    /// The body of the response, shown as its JSON example
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<openapi_parser::Value>,
+    /// A schema of the spec the body follows, e.g.
+    /// `#/components/schemas/Error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, DefaultResponseHeader>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefaultResponseHeader {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub example: Option<String>,
+}
+
+/// What the anchors of operations, like `/api/pets#list-pets`, are derived
+/// from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UriStrategy {
+    /// The summary of the operation, or its method and path if it has no
+    /// summary
+    #[default]
+    Summary,
+    /// The operationId, so that anchors stay the same when paths change
+    OperationId,
+}
+
+pub const DEFAULT_MAX_SCHEMA_DEPTH: usize = 8;
+/// Where specs fetched from a `spec_url` are placed in the project, unless
+/// they set a `spec_file`.
+pub const REMOTE_SPEC_DIR: &str = "_openapi";
+pub const DEFAULT_SUNSET_FIELD: &str = "x-sunset";
+pub const DEFAULT_AUGMENTATIONS_DIR: &str = "_openapi";
+
+impl OpenApi {
+    /// The guardrails the spec is parsed with, using the parser's defaults
+    /// for anything that isn't set.
+    pub fn parser_limits(&self) -> Limits {
+        let defaults = Limits::default();
+
+        Limits {
+            max_operations: self.max_operations.unwrap_or(defaults.max_operations),
+            max_schema_nodes: self.max_schema_nodes.unwrap_or(defaults.max_schema_nodes),
+            max_schema_expansion: self
+                .max_schema_expansion
+                .unwrap_or(defaults.max_schema_expansion),
+        }
+    }
+
+    /// Where the Markdown files added to the pages of operations are.
+    pub fn augmentations_dir(&self) -> PathBuf {
+        self.augmentations_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_AUGMENTATIONS_DIR))
+    }
+
+    /// The extension on operations with the date they will be removed.
+    pub fn sunset_field(&self) -> &str {
+        self.sunset_header_field
+            .as_deref()
+            .unwrap_or(DEFAULT_SUNSET_FIELD)
+    }
+
+    /// The path a spec fetched from the URL is placed at when it doesn't set
+    /// a `spec_file`, e.g. `https://example.com/v1/openapi.yaml` becomes
+    /// `_openapi/example-com-v1-openapi.yaml`. URLs that don't end in
+    /// `.yaml` or `.yml` are expected to return JSON.
+    pub fn remote_spec_file(url: &str) -> PathBuf {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let path = path.split_once("://").map(|(_, rest)| rest).unwrap_or(path);
+
+        let (stem, extension) = match path.rsplit_once('.') {
+            Some((stem, "yaml" | "yml")) => (stem, "yaml"),
+            Some((stem, "json")) => (stem, "json"),
+            _ => (path, "json"),
+        };
+
+        let slug = stem
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+            .to_lowercase();
+
+        PathBuf::from(format!("{}/{}.{}", REMOTE_SPEC_DIR, slug, extension))
+    }
+
+    fn resolve_spec_file(&mut self) -> Result<()> {
+        if !self.spec_file.as_os_str().is_empty() {
+            return Ok(());
+        }
+
+        match &self.spec_url {
+            Some(url) => {
+                self.spec_file = Self::remote_spec_file(url);
+                Ok(())
+            }
+            None => Err(Error {
+                code: Error::INVALID_DOCTAVE_YAML,
+                message: "Missing OpenAPI spec".to_owned(),
+                description: format!(
+                    "Set either `spec_file` or `spec_url` for the OpenAPI spec at \"{}\".",
+                    self.uri_prefix
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            }),
+        }
+    }
+}
+
+/// Patterns are relative to the project root, and `*` doesn't match across
+/// folders, so `internal/*` only matches files directly in `internal`.
+fn exclude_glob(pattern: &str) -> std::result::Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern.trim_start_matches('/'))
+        .literal_separator(true)
+        .build()
+}
+
+/// An `http` or `https` URL with a host, like `https://docs.example.com`.
+pub(crate) fn is_absolute_url(url: &str) -> bool {
+    Url::parse(url)
+        .map(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+        .unwrap_or(false)
 }
 
 /// Check if a string is a valid hex color.
@@ -1348,6 +3713,17 @@ fn is_hex_color(s: &str) -> bool {
     s.starts_with('#') && s.chars().skip(1).all(|c| c.is_ascii_hexdigit())
 }
 
+/// An opaque hex color with either 3 or 6 digits, e.g. `#fb2` or `#ffb224`.
+pub(crate) fn is_full_hex_color(s: &str) -> bool {
+    is_hex_color(s) && matches!(s.len(), 4 | 7)
+}
+
+fn is_token_name(s: &str) -> bool {
+    !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
 #[cfg(test)]
 mod test {
 
@@ -1419,46 +3795,204 @@ mod test {
 
         let mut settings = Settings::parse(input).unwrap();
 
-        let opts = RenderOptions {
-            link_rewrites: [
-                // NOTE: Jaleo always adds the "/" prefix
-                ("/_assets/logo.png".to_string(), "new_logo.png".to_string()),
-                (
-                    "/_assets/logo-dark.png".to_string(),
-                    "new_logo_dark.png".to_string(),
-                ),
-                (
-                    "/_assets/favicon.svg".to_string(),
-                    "new_favicon.svg".to_string(),
-                ),
-            ]
-            .into(),
-            ..Default::default()
-        };
-        let mut ctx = RenderContext::new();
-        ctx.with_maybe_options(Some(&opts));
-        settings.rewrite_links(&ctx);
+        let opts = RenderOptions {
+            link_rewrites: [
+                // NOTE: Jaleo always adds the "/" prefix
+                ("/_assets/logo.png".to_string(), "new_logo.png".to_string()),
+                (
+                    "/_assets/logo-dark.png".to_string(),
+                    "new_logo_dark.png".to_string(),
+                ),
+                (
+                    "/_assets/favicon.svg".to_string(),
+                    "new_favicon.svg".to_string(),
+                ),
+            ]
+            .into(),
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new();
+        ctx.with_maybe_options(Some(&opts));
+        settings.rewrite_links(&ctx);
+
+        assert_eq!(&settings.logo().unwrap().src, &Path::new("new_logo.png"));
+        assert_eq!(
+            settings.logo().unwrap().src_dark.as_deref(),
+            Some(Path::new("new_logo_dark.png"))
+        );
+        assert_eq!(
+            &settings.favicon().unwrap().src,
+            &Path::new("new_favicon.svg")
+        );
+    }
+
+    #[test]
+    fn v2_has_a_default_color() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+        assert_eq!(settings.theme().unwrap().colors.accent, "#5B5BD6");
+    }
+
+    #[test]
+    fn default_locale_defaults_to_first_locale() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+        locales: [en, fr]
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+        assert_eq!(settings.locales(), &["en".to_string(), "fr".to_string()]);
+        assert_eq!(settings.default_locale(), Some("en"));
+    }
+
+    #[test]
+    fn splits_locale_from_uri_path() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+        locales: [en, fr]
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+        assert_eq!(
+            settings.split_locale("/fr/foo/bar"),
+            (Some("fr"), "/foo/bar")
+        );
+        assert_eq!(settings.split_locale("/fr"), (Some("fr"), "/"));
+        assert_eq!(settings.split_locale("/en/foo"), (None, "/en/foo"));
+        assert_eq!(settings.split_locale("/french"), (None, "/french"));
+    }
+
+    #[test]
+    fn parses_variables_with_translations() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+        locales: [en, fr]
+        variables:
+          product: Acme
+          max_retries: 3
+          beta: true
+          support:
+            email: help@example.com
+            url: https://example.com/support
+        variable_translations:
+          fr:
+            support:
+              url: https://example.com/fr/support
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+
+        let support = |locale: Option<&str>| match settings.variables(locale).remove("support") {
+            Some(Variable::Map(support)) => support,
+            other => panic!("Not a map: {:?}", other),
+        };
+
+        assert_eq!(
+            support(None).get("url"),
+            Some(&Variable::String("https://example.com/support".to_string()))
+        );
+        assert_eq!(
+            support(Some("fr")).get("url"),
+            Some(&Variable::String(
+                "https://example.com/fr/support".to_string()
+            ))
+        );
+        assert_eq!(
+            support(Some("fr")).get("email"),
+            Some(&Variable::String("help@example.com".to_string()))
+        );
+        assert_eq!(
+            settings.variables(None).get("max_retries"),
+            Some(&Variable::Integer(3))
+        );
+        assert_eq!(
+            settings.variables(None).get("beta"),
+            Some(&Variable::Bool(true))
+        );
+
+        let mut errors = vec![];
+        settings.verify_variable_translations(&mut errors);
+        assert!(errors.is_empty(), "{:#?}", errors);
+    }
+
+    #[test]
+    fn verifies_variable_translations() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+        locales: [en, fr]
+        variables:
+          support:
+            url: https://example.com/support
+        variable_translations:
+          de:
+            support:
+              url: https://example.com/de/support
+          fr:
+            support:
+              ulr: https://example.com/fr/support
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+
+        let mut errors = vec![];
+        settings.verify_variable_translations(&mut errors);
+
+        assert_eq!(errors.len(), 2, "{:#?}", errors);
+        assert_eq!(errors[0].message, "Unknown locale in variable_translations");
+        assert_eq!(
+            errors[1].message,
+            "Unknown variable in variable_translations"
+        );
+        assert_eq!(
+            errors[1].description,
+            r#"The "fr" translation overrides "support.ulr", which isn't under `variables`. Add a default value for it under `variables`."#
+        );
+    }
+
+    #[test]
+    fn matches_exclude_patterns() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+        exclude: ["internal/**", "/notes/*.md"]
+        "##};
 
-        assert_eq!(&settings.logo().unwrap().src, &Path::new("new_logo.png"));
+        let settings = Settings::parse(input).unwrap();
         assert_eq!(
-            settings.logo().unwrap().src_dark.as_deref(),
-            Some(Path::new("new_logo_dark.png"))
+            settings.excluded_by(Path::new("internal/deep/page.md")),
+            Some("internal/**")
         );
         assert_eq!(
-            &settings.favicon().unwrap().src,
-            &Path::new("new_favicon.svg")
+            settings.excluded_by(Path::new("notes/todo.md")),
+            Some("/notes/*.md")
         );
+        assert_eq!(settings.excluded_by(Path::new("notes/old/todo.md")), None);
+        assert_eq!(settings.excluded_by(Path::new("guides/internal.md")), None);
     }
 
     #[test]
-    fn v2_has_a_default_color() {
+    fn verifies_exclude_patterns() {
         let input = indoc! {r##"
         ---
         title: Acme Inc
+        exclude: ["internal/[oops"]
         "##};
 
         let settings = Settings::parse(input).unwrap();
-        assert_eq!(settings.theme().unwrap().colors.accent, "#5B5BD6");
+        let mut errors = vec![];
+        settings.verify_exclude(&mut errors);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, Error::INVALID_DOCTAVE_YAML);
+        assert_eq!(errors[0].message, "Invalid pattern in `exclude`");
     }
 
     #[test]
@@ -1488,6 +4022,111 @@ mod test {
         assert_eq!(settings.theme().unwrap().colors.accent, "#5B5BD6");
     }
 
+    #[test]
+    fn v2_theme_overrides_colors_per_appearance() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+
+        theme:
+          colors:
+            accent: "#5B5BD6"
+            tokens:
+              success: "#30A46C"
+            dark:
+              background: "#111111"
+              accent_steps:
+                9: "#8D8DF0"
+              tokens:
+                warning: "#FFB224"
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+        let mut errors = vec![];
+        settings.verify_v2_theme(&mut errors);
+        assert_eq!(errors, vec![]);
+
+        let css = settings.theme().unwrap().colors.css();
+        let (light, dark) = css.split_once(".dark, .dark-theme").unwrap();
+
+        assert!(light.contains("--success: #30a46c;"));
+        assert!(!light.contains("--warning"));
+        assert!(!light.contains("--accent-9: #8d8df0;"));
+
+        assert!(dark.contains("--success: #30a46c;"));
+        assert!(dark.contains("--warning: #ffb224;"));
+        assert!(dark.contains("--accent-9: #8d8df0;"));
+        assert!(dark.contains("--background: #111111;"));
+    }
+
+    #[test]
+    fn v2_theme_colors_survive_serialization() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+
+        theme:
+          colors:
+            accent: "#5B5BD6"
+            light:
+              grayscale: slate
+              gray_steps:
+                1: "#fcfcfd"
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+        let colors = &settings.theme().unwrap().colors;
+
+        let json = serde_json::to_value(colors).unwrap();
+        let roundtrip: ColorsV2 = serde_json::from_value(json).unwrap();
+
+        assert_eq!(&roundtrip, colors);
+    }
+
+    #[test]
+    fn v2_theme_reports_invalid_colors_by_key() {
+        let input = indoc! {r##"
+        ---
+        title: Acme Inc
+
+        theme:
+          colors:
+            tokens:
+              warning: "orange"
+            dark:
+              accent_steps:
+                9: "#12"
+                13: "#ffffff"
+        "##};
+
+        let settings = Settings::parse(input).unwrap();
+        let mut errors = vec![];
+        settings.verify_v2_theme(&mut errors);
+
+        let messages = errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                "Invalid color for `theme.colors.tokens.warning` in theme",
+                "Invalid color for `theme.colors.dark.accent_steps.9` in theme",
+                "Invalid color step `theme.colors.dark.accent_steps.13` in theme",
+            ]
+        );
+        assert!(errors.iter().all(|e| e.code == Error::INVALID_DOCTAVE_YAML));
+        assert_eq!(
+            errors[1].description,
+            "Expected a HEX color code, like \"#ffb224\".\nFound \"#12\"."
+        );
+
+        // Invalid colors are skipped when generating the CSS
+        let css = settings.theme().unwrap().colors.css();
+        assert!(!css.contains("--warning"));
+        assert!(!css.contains("#12;"));
+    }
+
     #[test]
     fn rewrite_logo_and_favicon_links_that_are_prefixed_with_slashes() {
         let input = indoc! {r##"
@@ -1552,6 +4191,54 @@ mod test {
         );
     }
 
+    #[test]
+    fn markdown_defaults() {
+        let settings = Settings::parse("title: Acme Inc").unwrap();
+
+        assert_eq!(settings.markdown(), &MarkdownSettings::default());
+        assert!(settings.markdown().math);
+        assert!(!settings.markdown().smart_punctuation);
+        assert!(!settings.markdown().hard_breaks);
+        assert!(!settings.markdown().heading_anchor_links);
+    }
+
+    #[test]
+    fn parses_markdown_toggles() {
+        let input = indoc! {"
+        title: Acme Inc
+        markdown:
+          smart_punctuation: true
+          math: false
+        "};
+
+        let settings = Settings::parse(input).unwrap();
+
+        assert!(settings.markdown().smart_punctuation);
+        assert!(!settings.markdown().math);
+        assert!(!settings.markdown().hard_breaks);
+    }
+
+    #[test]
+    fn rejects_unknown_markdown_toggles() {
+        let input = indoc! {"
+        title: Acme Inc
+        markdown:
+          smart_quotes: true
+        "};
+
+        let error = Settings::parse(input).unwrap_err();
+
+        assert!(
+            error.description.contains("unknown field `smart_quotes`"),
+            "{}",
+            error.description
+        );
+
+        let position = error.position.unwrap();
+        assert_eq!(position.start.row, 3);
+        assert_eq!(position.start.col, 3);
+    }
+
     #[test]
     fn from_serde_all_attributes() {
         let input = indoc! {"
@@ -1651,6 +4338,361 @@ mod test {
         assert_eq!(settings.open_api[0].uri_prefix, "/bobby");
     }
 
+    #[test]
+    fn canonical_url_rules_replace_the_folder_with_the_url() {
+        let settings = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        canonical_urls:
+          - path: partner/**
+            url: https://partner.example.com/docs/
+          - path: \"**/mirrored.md\"
+            url: https://mirror.example.com
+        "})
+        .unwrap();
+
+        assert_eq!(
+            settings.canonical_url_for(Path::new("partner/setup.md"), "/partner/setup"),
+            Some("https://partner.example.com/docs/setup".to_string())
+        );
+        assert_eq!(
+            settings.canonical_url_for(Path::new("partner/README.md"), "/partner"),
+            Some("https://partner.example.com/docs/".to_string())
+        );
+        assert_eq!(
+            settings.canonical_url_for(Path::new("guides/mirrored.md"), "/guides/mirrored"),
+            Some("https://mirror.example.com/guides/mirrored".to_string())
+        );
+        assert_eq!(
+            settings.canonical_url_for(Path::new("guides/setup.md"), "/guides/setup"),
+            None
+        );
+    }
+
+    #[test]
+    fn verifies_canonical_url_rules() {
+        let settings = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        canonical_urls:
+          - path: partner/**
+            url: /docs
+        "})
+        .unwrap();
+
+        let mut errors = vec![];
+        settings.verify_canonical_urls(&mut errors);
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].message, "Invalid URL in `canonical_urls`");
+    }
+
+    #[test]
+    fn parses_integrations() {
+        let settings = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        integrations:
+          plausible: docs.example.com
+          gtag: G-ABC123
+          require_sri: true
+          scripts:
+            - src: https://cdn.example.com/widget.js
+              defer: true
+              integrity: sha384-oqVuAfXRKap7fdgcCY5uykM6+R9GqQ8K/uxy9rx7HNQlGYl1kPzQho1wx4JwY8wC
+        "})
+        .unwrap();
+
+        assert_eq!(
+            settings.integrations,
+            Integrations {
+                plausible: Some("docs.example.com".to_string()),
+                fathom: None,
+                gtag: Some("G-ABC123".to_string()),
+                require_sri: true,
+                scripts: vec![Script {
+                    src: "https://cdn.example.com/widget.js".to_string(),
+                    defer: true,
+                    is_async: false,
+                    integrity: Some(
+                        "sha384-oqVuAfXRKap7fdgcCY5uykM6+R9GqQ8K/uxy9rx7HNQlGYl1kPzQho1wx4JwY8wC"
+                            .to_string()
+                    ),
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn points_invalid_scripts_to_their_settings() {
+        let error = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        integrations:
+          scripts:
+            - src: https://cdn.example.com/widget.js
+            - src: http://cdn.example.com/chat.js
+              async: true
+        "})
+        .unwrap_err();
+
+        assert_eq!(error.code, Error::INVALID_DOCTAVE_YAML);
+        assert_eq!(error.message, "Scripts must be loaded over HTTPS");
+        let position = error.position.unwrap();
+        assert_eq!((position.start.row, position.start.col), (6, 7));
+
+        let error = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        integrations:
+          require_sri: true
+          scripts:
+            - src: https://cdn.example.com/widget.js
+        "})
+        .unwrap_err();
+
+        assert_eq!(error.message, "Missing script integrity hash");
+        let position = error.position.unwrap();
+        assert_eq!((position.start.row, position.start.col), (6, 7));
+    }
+
+    #[test]
+    fn rejects_inline_scripts() {
+        let error = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        integrations:
+          scripts:
+            - src: \"alert('hi')\"
+        "})
+        .unwrap_err();
+
+        assert_eq!(error.message, "Invalid script URL");
+
+        assert!(Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        integrations:
+          scripts:
+            - content: alert('hi')
+        "})
+        .is_err());
+    }
+
+    #[test]
+    fn points_invalid_secret_patterns_to_their_settings() {
+        let error = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        secret_scanning:
+          patterns:
+            - name: Internal token
+              regex: \"int_[a-z\"
+        "})
+        .unwrap_err();
+
+        assert_eq!(error.code, Error::INVALID_DOCTAVE_YAML);
+        assert_eq!(error.message, "Invalid secret scanning regex");
+        let position = error.position.unwrap();
+        assert_eq!((position.start.row, position.start.col), (6, 7));
+
+        let settings = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        secret_scanning: {}
+        "})
+        .unwrap();
+
+        assert_eq!(
+            settings.secret_scanning().map(|s| s.level),
+            Some(LintLevel::Error)
+        );
+    }
+
+    #[test]
+    fn verifies_embed_providers() {
+        let settings = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        embeds:
+          youtube:
+            url: https://www.youtube.com/embed/{id}
+            privacy_url: https://www.youtube-nocookie.com/embed/{id}
+            privacy_mode: true
+            params: [start]
+        "})
+        .unwrap();
+
+        let youtube = &settings.embeds()["youtube"];
+        assert_eq!(
+            youtube.url_template(),
+            "https://www.youtube-nocookie.com/embed/{id}"
+        );
+        assert_eq!(youtube.aspect_ratio, "16:9");
+
+        let error = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        embeds:
+          loom:
+            url: http://www.loom.com/embed
+        "})
+        .unwrap_err();
+
+        assert_eq!(error.message, "Invalid embed URL");
+        assert_eq!(error.position.unwrap().start.row, 5);
+
+        let error = Settings::parse(indoc! {"
+        ---
+        title: Acme Inc
+        embeds:
+          loom:
+            url: https://www.loom.com/embed/{id}
+            privacy_mode: true
+        "})
+        .unwrap_err();
+
+        assert_eq!(error.message, "Missing embed privacy URL");
+    }
+
+    #[test]
+    fn places_remote_openapi_specs_by_their_url() {
+        let input = indoc! {"
+        ---
+        title: Acme Inc
+
+        open_api:
+            - spec_url: https://api.example.com/v1/openapi.yml?token=abc
+              uri_prefix: /api
+            - spec_url: https://api.example.com/spec
+              uri_prefix: /other
+            - spec_url: https://api.example.com/spec
+              spec_file: specs/other.yaml
+              uri_prefix: /third
+        "};
+
+        let settings = Settings::parse(input).unwrap();
+
+        assert_eq!(
+            settings.open_api[0].spec_file,
+            Path::new("_openapi/api-example-com-v1-openapi.yaml")
+        );
+        assert_eq!(
+            settings.open_api[1].spec_file,
+            Path::new("_openapi/api-example-com-spec.json")
+        );
+        assert_eq!(
+            settings.open_api[2].spec_file,
+            Path::new("specs/other.yaml")
+        );
+    }
+
+    #[test]
+    fn merges_shared_openapi_settings() {
+        let input = indoc! {"
+        ---
+        title: Acme Inc
+
+        open_api:
+            - &api
+              spec_file: v1.yaml
+              uri_prefix: /v1
+              validate_examples: true
+              auto_link_code: true
+            - <<: *api
+              spec_file: v2.yaml
+              uri_prefix: /v2
+        "};
+
+        let settings = Settings::parse(input).unwrap();
+
+        assert_eq!(settings.open_api[1].spec_file, Path::new("v2.yaml"));
+        assert_eq!(settings.open_api[1].uri_prefix, "/v2");
+        assert!(settings.open_api[1].validate_examples);
+        assert!(settings.open_api[1].auto_link_code);
+    }
+
+    #[test]
+    fn parses_default_responses_keyed_by_status_code() {
+        let input = indoc! {"
+        ---
+        title: Acme Inc
+
+        open_api:
+            - &api
+              spec_file: v1.yaml
+              uri_prefix: /v1
+              default_responses:
+                429:
+                  description: Too many requests
+                  headers:
+                    Retry-After:
+                      example: \"30\"
+                4XX:
+                  description: Client error
+            - <<: *api
+              spec_file: v2.yaml
+              uri_prefix: /v2
+        "};
+
+        let settings = Settings::parse(input).unwrap();
+
+        for spec in settings.open_api() {
+            assert_eq!(
+                spec.default_responses.keys().collect::<Vec<_>>(),
+                vec!["429", "4XX"]
+            );
+            assert_eq!(
+                spec.default_responses["429"].headers["Retry-After"].example,
+                Some("30".to_owned())
+            );
+        }
+    }
+
+    #[test]
+    fn points_errors_in_aliased_settings_to_the_alias() {
+        let input = indoc! {"
+        ---
+        title: &name Acme Inc
+
+        open_api:
+            - spec_file: v1.yaml
+              uri_prefix: /v1
+              validate_examples: *name
+        "};
+
+        let error = Settings::parse(input).unwrap_err();
+        let position = error.position.unwrap();
+
+        assert_eq!((position.start.row, position.start.col), (7, 26));
+        assert!(
+            error
+                .description
+                .contains("open_api[0].validate_examples: invalid type: string"),
+            "{}",
+            error.description
+        );
+    }
+
+    #[test]
+    fn openapi_specs_need_a_file_or_url() {
+        let input = indoc! {"
+        ---
+        title: Acme Inc
+
+        open_api:
+            - uri_prefix: /api
+        "};
+
+        let error = Settings::parse(input).unwrap_err();
+
+        assert_eq!(error.message, "Missing OpenAPI spec");
+        assert_eq!(
+            error.description,
+            "Set either `spec_file` or `spec_url` for the OpenAPI spec at \"/api\"."
+        );
+    }
+
     #[test]
     fn from_serde_header_label_alias() {
         let input = indoc! {"
@@ -1849,6 +4891,17 @@ mod test {
         assert_eq!(settings.theme.color_mode, ColorMode::Auto);
     }
 
+    #[test]
+    fn html_policy() {
+        let settings = Settings::parse("---\ntitle: Acme Inc\n").unwrap();
+        assert_eq!(settings.html_policy(), HtmlPolicy::Sanitize);
+
+        let settings = Settings::parse("---\ntitle: Acme Inc\nhtml_policy: strip\n").unwrap();
+        assert_eq!(settings.html_policy(), HtmlPolicy::Strip);
+
+        assert!(Settings::parse("---\ntitle: Acme Inc\nhtml_policy: escape\n").is_err());
+    }
+
     mod v2 {
         use super::*;
 
@@ -1932,8 +4985,22 @@ mod test {
                 settings.open_api(),
                 &[OpenApi {
                     spec_file: "/path/to/spec.json".into(),
+                    spec_url: None,
                     uri_prefix: "/bobby".to_string(),
-                    experimental: false
+                    experimental: false,
+                    validate_examples: false,
+                    overview_template: None,
+                    augmentations_dir: None,
+                    max_schema_depth: None,
+                    max_operations: None,
+                    max_schema_nodes: None,
+                    max_schema_expansion: None,
+                    deprecation_banner: false,
+                    sunset_header_field: None,
+                    warn_deprecated_links: false,
+                    auto_link_code: false,
+                    uri_strategy: UriStrategy::Summary,
+                    default_responses: BTreeMap::new(),
                 }]
             );
             assert_eq!(settings.styles(), &[PathBuf::from("_assets/style.css")]);
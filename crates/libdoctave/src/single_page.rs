@@ -0,0 +1,509 @@
+//! Renders a section of the project as a single document, for printing or
+//! exporting to PDF.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::markdown::parser::{is_internal_link, to_final_link, webbify_url};
+use crate::primitive_components::{Step, Tab};
+use crate::render_context::RenderContext;
+use crate::renderable_ast::{Attribute, AttributeValue, Node, NodeKind};
+use crate::{navigation, slug, Ast, Error, PageHandle, Project, RenderOptions};
+
+/// A section of the project rendered into one document.
+#[derive(Debug, Clone, Serialize)]
+pub struct SinglePage {
+    pub title: String,
+    /// The pages in the section, each wrapped in a `<section>` with an anchor
+    /// that links between the included pages point to.
+    pub root: Node,
+}
+
+/// A page included in the document, along with the anchor it is rendered under
+struct Entry<'a> {
+    page: PageHandle<'a>,
+    anchor: String,
+    depth: u8,
+}
+
+pub(crate) fn render(
+    project: &Project,
+    uri_prefix: &str,
+    opts: Option<&RenderOptions>,
+) -> std::result::Result<SinglePage, Vec<Error>> {
+    let prefix = format!("/{}", uri_prefix.trim_matches('/'));
+
+    let entries = section_pages(project, &prefix, opts)
+        .into_iter()
+        .map(|page| Entry {
+            anchor: page_anchor(page.uri_path()),
+            depth: depth(page.uri_path(), &prefix),
+            page,
+        })
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        return Err(vec![Error {
+            code: Error::NAVIGATION_ERROR,
+            message: String::from("No pages found for section"),
+            description: format!("Could not find any Markdown pages under \"{}\".", prefix),
+            file: None,
+            position: None,
+            owner: None,
+            suggestions: vec![],
+        }]);
+    }
+
+    let anchors = entries
+        .iter()
+        .map(|e| (e.page.uri_path().to_string(), e.anchor.clone()))
+        .collect::<HashMap<_, _>>();
+
+    // Links are resolved to pages by their filesystem paths, so the ASTs are
+    // built without any of the options that turn links into web URLs.
+    let mut ast_opts = opts.cloned().unwrap_or_default();
+    ast_opts.webbify_internal_urls = false;
+    ast_opts.fsify_internal_urls = false;
+    let link_prefix = ast_opts.prefix_link_urls.take();
+
+    let mut errors = vec![];
+    let mut root = Node {
+        kind: NodeKind::Root,
+        ..Default::default()
+    };
+
+    for entry in &entries {
+        let ast = match entry.page.ast(Some(&ast_opts)) {
+            Ok(Ast::Markdown(ast)) => ast,
+            Ok(_) => continue,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+
+        let rewriter = Rewriter {
+            anchor: &entry.anchor,
+            depth: entry.depth,
+            anchors: &anchors,
+            link_prefix: link_prefix.as_deref(),
+        };
+
+        root.children.push(Node {
+            kind: NodeKind::HtmlBlock {
+                name: String::from("section"),
+                attributes: vec![
+                    Attribute {
+                        key: String::from("id"),
+                        value: Some(AttributeValue::Literal(entry.anchor.clone())),
+                    },
+                    Attribute {
+                        key: String::from("class"),
+                        value: Some(AttributeValue::Literal(String::from("single-page-section"))),
+                    },
+                ],
+            },
+            pos: Default::default(),
+            children: rewriter.rewrite(ast.children),
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let title = project
+        .get_page_by_uri_path(&prefix)
+        .filter(|_| prefix != "/")
+        .and_then(|p| p.title().ok())
+        .unwrap_or_else(|| project.settings.title().to_string());
+
+    Ok(SinglePage { title, root })
+}
+
+/// Gathers the Markdown pages under the prefix, in the order they appear in
+/// the navigation. Pages missing from the navigation are appended at the end,
+/// sorted by their path.
+fn section_pages<'a>(
+    project: &'a Project,
+    prefix: &str,
+    opts: Option<&RenderOptions>,
+) -> Vec<PageHandle<'a>> {
+    let in_section = |uri_path: &str| {
+        prefix == "/" || uri_path == prefix || uri_path.starts_with(&format!("{}/", prefix))
+    };
+
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+    ctx.with_maybe_options(opts);
+
+    let pages = project
+        .published_pages(opts)
+        .into_iter()
+        .filter(|p| p.is_markdown() && in_section(p.uri_path()))
+        .map(|p| {
+            (
+                navigation::link_identity(&to_final_link(p.uri_path(), &ctx)),
+                p,
+            )
+        })
+        .collect::<HashMap<PathBuf, PageHandle>>();
+
+    let subtab_path = project
+        .get_subtab_path_by_uri_path(prefix)
+        .unwrap_or("/".to_string());
+
+    let mut ordered: Vec<PageHandle> = vec![];
+
+    if let Ok(navigation) = project.navigation(opts, &subtab_path) {
+        for item in navigation.flattened_links() {
+            let href = item.href().unwrap();
+
+            if href.contains('#') {
+                continue;
+            }
+
+            if let Some(page) = pages.get(&navigation::link_identity(href)) {
+                if !ordered.iter().any(|p| p.uri_path() == page.uri_path()) {
+                    ordered.push(page.clone());
+                }
+            }
+        }
+    }
+
+    let mut rest = pages
+        .into_values()
+        .filter(|p| !ordered.iter().any(|o| o.uri_path() == p.uri_path()))
+        .collect::<Vec<_>>();
+    rest.sort_by(|a, b| a.uri_path().cmp(b.uri_path()));
+
+    // The page at the prefix itself introduces the section, so it goes first
+    // if it's not in the navigation.
+    if let Some(idx) = rest.iter().position(|p| p.uri_path() == prefix) {
+        ordered.insert(0, rest.remove(idx));
+    }
+
+    ordered.extend(rest);
+    ordered
+}
+
+fn page_anchor(uri_path: &str) -> String {
+    match uri_path.trim_matches('/') {
+        "" => String::from("index"),
+        path => slug::slugify(&path.replace('/', "-")),
+    }
+}
+
+/// How many levels below the prefix the page is
+fn depth(uri_path: &str, prefix: &str) -> u8 {
+    let segments = |p: &str| p.split('/').filter(|s| !s.is_empty()).count();
+
+    segments(uri_path).saturating_sub(segments(prefix)) as u8
+}
+
+/// Rewrites a page's AST so it can be placed into the combined document
+struct Rewriter<'a> {
+    anchor: &'a str,
+    depth: u8,
+    anchors: &'a HashMap<String, String>,
+    link_prefix: Option<&'a str>,
+}
+
+impl Rewriter<'_> {
+    fn rewrite(&self, nodes: Vec<Node>) -> Vec<Node> {
+        let mut out = vec![];
+
+        for mut node in nodes {
+            match node.kind {
+                // Tabs and steps are interactive, so we lay out their contents
+                // one after another, each under a bolded title.
+                NodeKind::Tabs(_) | NodeKind::Steps | NodeKind::CodeSelect(_) => {
+                    for child in node.children {
+                        match &child.kind {
+                            NodeKind::Tab(Tab { title, .. }) => {
+                                out.push(bold_paragraph(title.clone()));
+                                out.extend(self.rewrite(child.children));
+                            }
+                            NodeKind::Step(Step { title, number, .. }) => {
+                                out.push(bold_paragraph(format!("{}. {}", number, title)));
+                                out.extend(self.rewrite(child.children));
+                            }
+                            _ => out.extend(self.rewrite(vec![child])),
+                        }
+                    }
+                }
+                _ => {
+                    self.rewrite_kind(&mut node.kind);
+                    node.children = self.rewrite(node.children);
+                    out.push(node);
+                }
+            }
+        }
+
+        out
+    }
+
+    fn rewrite_kind(&self, kind: &mut NodeKind) {
+        match kind {
+            NodeKind::Heading { level, slug, .. } => {
+                *level = (*level + self.depth).min(6);
+                *slug = format!("{}-{}", self.anchor, slug);
+            }
+            NodeKind::Link { url, .. } => {
+                *url = self.rewrite_link(url);
+            }
+            NodeKind::Callout(callout) => {
+                // The document is meant to be read in full, e.g. when printed
+                callout.default_open = true;
+            }
+            NodeKind::Image { url, .. } => {
+                // Assets are resolved relative to the document, which is
+                // placed at the root of the project.
+                if url.starts_with("/_assets/") {
+                    *url = url.trim_start_matches('/').to_string();
+                }
+            }
+            NodeKind::Download(download) => {
+                if download.src.starts_with("/_assets/") {
+                    download.src = download.src.trim_start_matches('/').to_string();
+                }
+            }
+            // Every page numbers its footnotes from one, so their anchors
+            // are kept apart like the headings'
+            NodeKind::FootnoteReference {
+                id, definition_id, ..
+            } => {
+                *id = format!("{}-{}", self.anchor, id);
+                *definition_id = format!("{}-{}", self.anchor, definition_id);
+            }
+            NodeKind::FootnoteDefinition {
+                id, reference_ids, ..
+            } => {
+                *id = format!("{}-{}", self.anchor, id);
+                for reference_id in reference_ids {
+                    *reference_id = format!("{}-{}", self.anchor, reference_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rewrite_link(&self, url: &str) -> String {
+        let (path, fragment) = match url.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (url, None),
+        };
+
+        if path.is_empty() {
+            return match fragment {
+                Some(fragment) => format!("#{}-{}", self.anchor, fragment),
+                None => url.to_string(),
+            };
+        }
+
+        if !is_internal_link(path) {
+            return url.to_string();
+        }
+
+        let uri = crate::fs_to_uri_path(Path::new(path));
+
+        match (self.anchors.get(&uri), fragment) {
+            (Some(anchor), Some(fragment)) => format!("#{}-{}", anchor, fragment),
+            (Some(anchor), None) => format!("#{}", anchor),
+            (None, _) => {
+                let mut absolute = webbify_url(path);
+
+                if !absolute.starts_with('/') {
+                    absolute.insert(0, '/');
+                }
+
+                if let Some(prefix) = self.link_prefix {
+                    absolute.insert_str(0, prefix.trim_end_matches('/'));
+                }
+
+                match fragment {
+                    Some(fragment) => format!("{}#{}", absolute, fragment),
+                    None => absolute,
+                }
+            }
+        }
+    }
+}
+
+fn bold_paragraph(text: String) -> Node {
+    Node {
+        kind: NodeKind::Paragraph,
+        pos: Default::default(),
+        children: vec![Node {
+            kind: NodeKind::Strong,
+            pos: Default::default(),
+            children: vec![Node {
+                kind: NodeKind::Text { value: text },
+                pos: Default::default(),
+                children: vec![],
+            }],
+        }],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    use super::*;
+
+    fn project(files: Vec<(&str, &str)>) -> Project {
+        let mut files = files
+            .into_iter()
+            .map(|(path, content)| InputFile {
+                path: PathBuf::from(path),
+                content: InputContent::Text(content.to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        files.push(InputFile {
+            path: PathBuf::from(SETTINGS_FILE_NAME),
+            content: InputContent::Text("---\ntitle: Acme Docs\n".to_string()),
+        });
+
+        Project::from_file_list(files).unwrap()
+    }
+
+    fn headings(node: &Node) -> Vec<(u8, String)> {
+        node.walk()
+            .filter_map(|n| match &n.kind {
+                NodeKind::Heading { level, slug, .. } => Some((*level, slug.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn links(node: &Node) -> Vec<String> {
+        node.walk()
+            .filter_map(|n| match &n.kind {
+                NodeKind::Link { url, .. } => Some(url.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn concatenates_pages_in_navigation_order() {
+        let project = project(vec![
+            ("README.md", "# Home"),
+            ("guide/README.md", "# Guide"),
+            ("guide/install.md", "# Install"),
+            ("guide/usage.md", "# Usage"),
+            (
+                NAVIGATION_FILE_NAME,
+                indoc! {r#"
+                - heading: Guide
+                  items:
+                    - label: Usage
+                      href: guide/usage.md
+                    - label: Install
+                      href: guide/install.md
+                "#},
+            ),
+        ]);
+
+        let page = project.render_section("/guide", None).unwrap();
+
+        assert_eq!(page.title, "Guide");
+        assert_eq!(
+            headings(&page.root),
+            vec![
+                (1, "guide-guide".to_string()),
+                (2, "guide-usage-usage".to_string()),
+                (2, "guide-install-install".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrites_links_between_included_pages_to_anchors() {
+        let project = project(vec![
+            ("README.md", "# Home"),
+            (
+                "guide/install.md",
+                indoc! {r#"
+                # Install
+
+                See [usage](./usage.md), [options](./usage.md#options),
+                [above](#install) and [home](/README.md).
+                "#},
+            ),
+            ("guide/usage.md", "# Usage\n\n## Options"),
+            (NAVIGATION_FILE_NAME, "- heading: Guide\n"),
+        ]);
+
+        let page = project.render_section("guide", None).unwrap();
+
+        assert_eq!(
+            links(&page.root),
+            vec![
+                "#guide-usage".to_string(),
+                "#guide-usage-options".to_string(),
+                "#guide-install-install".to_string(),
+                "/".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn flattens_tabs_and_steps() {
+        let project = project(vec![
+            ("README.md", "# Home"),
+            (
+                "guide.md",
+                indoc! {r#"
+                <Tabs>
+                  <Tab title="Mac">
+                    Use brew
+                  </Tab>
+                  <Tab title="Linux">
+                    Use apt
+                  </Tab>
+                </Tabs>
+
+                <Steps>
+                  <Step title="Download">
+                    Get it
+                  </Step>
+                </Steps>
+                "#},
+            ),
+            (NAVIGATION_FILE_NAME, "- heading: Guide\n"),
+        ]);
+
+        let page = project.render_section("/guide", None).unwrap();
+
+        let titles = page
+            .root
+            .walk()
+            .filter(|n| matches!(n.kind, NodeKind::Strong))
+            .map(|n| n.inner_text())
+            .collect::<Vec<_>>();
+
+        assert!(!page
+            .root
+            .walk()
+            .any(|n| matches!(n.kind, NodeKind::Tabs(_) | NodeKind::Steps)));
+        assert_eq!(titles, vec!["Mac", "Linux", "1. Download"]);
+    }
+
+    #[test]
+    fn errors_when_no_pages_match() {
+        let project = project(vec![
+            ("README.md", "# Home"),
+            (NAVIGATION_FILE_NAME, "- heading: Guide\n"),
+        ]);
+
+        let errors = project.render_section("/missing", None).unwrap_err();
+
+        assert_eq!(errors[0].code, Error::NAVIGATION_ERROR);
+    }
+}
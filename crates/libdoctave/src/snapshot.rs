@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use crate::{FileMetadata, InputFile};
+
+/// The files a [`Project`](crate::Project) was built from, for persisting a
+/// project and restoring it with [`Project::from_snapshot`](crate::Project::from_snapshot).
+///
+/// Derived state, like parsed specs and cached ASTs, isn't included and is
+/// rebuilt when the project is restored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectSnapshot {
+    /// The [`Project::fingerprint`](crate::Project::fingerprint) of the
+    /// project the snapshot was taken of
+    pub fingerprint: u64,
+    /// Sorted by path
+    pub(crate) files: Vec<InputFile>,
+    /// Sorted by path
+    pub(crate) metadata: Vec<(PathBuf, FileMetadata)>,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{InputContent, InputFile, Project, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn files(readme: &str) -> Vec<InputFile> {
+        let text = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        vec![
+            text(SETTINGS_FILE_NAME, "---\ntitle: An Project\n"),
+            text(
+                NAVIGATION_FILE_NAME,
+                "- heading: Pages\n  items:\n  - href: /foo.md\n",
+            ),
+            text("README.md", readme),
+            text("foo.md", "# Foo"),
+            text("bar/baz.md", "# Baz"),
+            InputFile {
+                path: PathBuf::from("_assets/logo.png"),
+                content: InputContent::Binary {
+                    signature: "1".to_string(),
+                    size_bytes: 10,
+                },
+            },
+        ]
+    }
+
+    fn page_fingerprints(project: &Project) -> HashMap<String, u64> {
+        project
+            .pages()
+            .iter()
+            .map(|p| (p.uri_path().to_string(), p.fingerprint()))
+            .collect()
+    }
+
+    #[test]
+    fn fingerprint_does_not_depend_on_file_order() {
+        let mut reversed = files("# Hi");
+        reversed.reverse();
+
+        let a = Project::from_file_list(files("# Hi")).unwrap();
+        let b = Project::from_file_list(reversed).unwrap();
+
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(page_fingerprints(&a), page_fingerprints(&b));
+    }
+
+    #[test]
+    fn changing_a_page_only_changes_its_fingerprint() {
+        let a = Project::from_file_list(files("# Hi")).unwrap();
+        let b = Project::from_file_list(files("# Hello")).unwrap();
+
+        assert_ne!(a.fingerprint(), b.fingerprint());
+
+        let a = page_fingerprints(&a);
+        let b = page_fingerprints(&b);
+
+        assert_eq!(a.len(), 3);
+        for (uri_path, fingerprint) in &a {
+            if uri_path == "/" {
+                assert_ne!(*fingerprint, b[uri_path]);
+            } else {
+                assert_eq!(*fingerprint, b[uri_path], "{}", uri_path);
+            }
+        }
+    }
+
+    #[test]
+    fn changing_an_asset_changes_the_fingerprint() {
+        let a = Project::from_file_list(files("# Hi")).unwrap();
+
+        let mut changed = files("# Hi");
+        changed[5].content = InputContent::Binary {
+            signature: "2".to_string(),
+            size_bytes: 10,
+        };
+        let b = Project::from_file_list(changed).unwrap();
+
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        assert_eq!(page_fingerprints(&a), page_fingerprints(&b));
+    }
+
+    #[test]
+    fn restores_a_project_from_a_snapshot() {
+        let project = Project::from_file_list(files("# Hi")).unwrap();
+
+        let json = serde_json::to_string(&project.snapshot()).unwrap();
+        let restored = Project::from_snapshot(serde_json::from_str(&json).unwrap()).unwrap();
+
+        assert_eq!(restored.fingerprint(), project.fingerprint());
+        assert_eq!(restored.snapshot(), project.snapshot());
+        assert_eq!(page_fingerprints(&restored), page_fingerprints(&project));
+    }
+}
@@ -0,0 +1,286 @@
+use std::path::{Path, PathBuf};
+
+use crate::canonical_path::canonicalize;
+use crate::page_kind::PageKind;
+use crate::Project;
+
+/// How many assets are listed in [`ProjectStatistics::largest_assets`].
+pub const LARGEST_ASSETS_COUNT: usize = 10;
+
+/// Sizes and counts of the content in a project, for keeping an eye on how
+/// large the published bundle is going to be.
+///
+/// Files are listed by their path in the project, in sorted order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectStatistics {
+    /// Same as [`Project::content_size_bytes`]
+    pub content_size_bytes: usize,
+    /// Combined size of everything under `_assets`
+    pub assets_size_bytes: usize,
+    /// All pages, including the ones generated from OpenAPI specs
+    pub page_count: usize,
+    pub partial_count: usize,
+    /// Custom components and topics. Built-in components aren't counted.
+    pub component_count: usize,
+    /// Markdown pages, with the size of their source file. Pages generated
+    /// from an OpenAPI spec are counted towards the spec instead.
+    pub pages: Vec<PageSize>,
+    pub assets: Vec<FileSize>,
+    /// The largest assets, largest first
+    pub largest_assets: Vec<FileSize>,
+    pub open_api_specs: Vec<FileSize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PageSize {
+    pub uri_path: String,
+    pub path: PathBuf,
+    pub size_bytes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileSize {
+    pub path: PathBuf,
+    pub size_bytes: usize,
+}
+
+impl ProjectStatistics {
+    pub(crate) fn gather(project: &Project) -> Self {
+        let size_of = |path: &Path| {
+            project
+                .file_sizes
+                .get(&canonicalize(path))
+                .copied()
+                .unwrap_or(0)
+        };
+
+        let files_under = |dirs: &[&str]| {
+            let mut files = project
+                .input_paths
+                .iter()
+                .filter(|path| dirs.iter().any(|dir| path.starts_with(dir)))
+                .collect::<Vec<_>>();
+            files.sort();
+            files
+        };
+
+        let mut pages = project
+            .pages
+            .iter()
+            .filter_map(|page| match page {
+                // Overview pages of OpenAPI specs have a synthetic path that
+                // isn't one of the input files, so they're skipped here too
+                PageKind::Markdown(md) => {
+                    project.file_sizes.get(&md.path).map(|size_bytes| PageSize {
+                        uri_path: md.uri_path.clone(),
+                        path: md.path.clone(),
+                        size_bytes: *size_bytes,
+                    })
+                }
+                PageKind::OpenApi(_) => None,
+            })
+            .collect::<Vec<_>>();
+        pages.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let assets = files_under(&["_assets"])
+            .into_iter()
+            .map(|path| FileSize {
+                path: path.clone(),
+                size_bytes: size_of(path),
+            })
+            .collect::<Vec<_>>();
+
+        let mut largest_assets = assets.clone();
+        largest_assets.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(&b.path)));
+        largest_assets.truncate(LARGEST_ASSETS_COUNT);
+
+        let mut open_api_specs = project
+            .settings
+            .open_api()
+            .iter()
+            .map(|spec| FileSize {
+                path: spec.spec_file.clone(),
+                size_bytes: size_of(&spec.spec_file),
+            })
+            .collect::<Vec<_>>();
+        open_api_specs.sort_by(|a, b| a.path.cmp(&b.path));
+        open_api_specs.dedup();
+
+        ProjectStatistics {
+            content_size_bytes: project.content_size_bytes,
+            assets_size_bytes: assets.iter().map(|a| a.size_bytes).sum(),
+            page_count: project.pages.len(),
+            partial_count: files_under(&["_partials"]).len(),
+            component_count: files_under(&["_components", "_topics"]).len(),
+            pages,
+            assets,
+            largest_assets,
+            open_api_specs,
+        }
+    }
+}
+
+/// Formats a size in bytes for humans, e.g. `2.3 MB`. Uses powers of 1024.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    fn text(path: &str, content: &str) -> InputFile {
+        InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        }
+    }
+
+    fn binary(path: &str, size_bytes: usize) -> InputFile {
+        InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Binary {
+                signature: String::from("1"),
+                size_bytes,
+            },
+        }
+    }
+
+    #[test]
+    fn counts_pages_partials_and_components() {
+        let project = Project::from_file_list(vec![
+            text(SETTINGS_FILE_NAME, "---\ntitle: An Project\n"),
+            text(NAVIGATION_FILE_NAME, "---"),
+            text("README.md", "# Hi"),
+            text("guides/setup.md", "# Setup"),
+            text("_partials/note.md", "A note"),
+            text("_components/card.md", "A card"),
+            text("_topics/intro.md", "An intro"),
+        ])
+        .unwrap();
+
+        let stats = project.statistics();
+
+        assert_eq!(stats.page_count, 2);
+        assert_eq!(stats.partial_count, 1);
+        assert_eq!(stats.component_count, 2);
+        assert_eq!(
+            stats.pages,
+            vec![
+                PageSize {
+                    uri_path: String::from("/"),
+                    path: PathBuf::from("README.md"),
+                    size_bytes: 4,
+                },
+                PageSize {
+                    uri_path: String::from("/guides/setup"),
+                    path: PathBuf::from("guides/setup.md"),
+                    size_bytes: 7,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_asset_sizes_from_binary_files() {
+        let mut files = vec![
+            text(SETTINGS_FILE_NAME, "---\ntitle: An Project\n"),
+            text(NAVIGATION_FILE_NAME, "---"),
+            text("README.md", "# Hi"),
+            text("_assets/style.css", "body {}"),
+        ];
+        for i in 0..12 {
+            files.push(binary(&format!("_assets/image-{:02}.png", i), i * 100));
+        }
+
+        let stats = Project::from_file_list(files).unwrap().statistics();
+
+        assert_eq!(stats.assets.len(), 13);
+        assert_eq!(stats.assets_size_bytes, 6600 + 7);
+        assert_eq!(stats.largest_assets.len(), LARGEST_ASSETS_COUNT);
+        assert_eq!(
+            stats.largest_assets[0],
+            FileSize {
+                path: PathBuf::from("_assets/image-11.png"),
+                size_bytes: 1100,
+            }
+        );
+        assert!(stats.content_size_bytes < stats.assets_size_bytes);
+    }
+
+    #[test]
+    fn reports_openapi_spec_sizes() {
+        let spec = indoc! {r#"
+        openapi: 3.0.0
+        info:
+          title: Trees
+          version: 1.0.0
+        paths:
+          /tree:
+            get:
+              summary: Get the tree
+              tags: [Trees]
+              responses:
+                '200':
+                  description: A tree
+        "#};
+
+        let stats = Project::from_file_list(vec![
+            text(
+                SETTINGS_FILE_NAME,
+                indoc! {r#"
+                ---
+                title: An Project
+                open_api:
+                  - spec_file: openapi.yaml
+                    uri_prefix: /api
+                "#},
+            ),
+            text(NAVIGATION_FILE_NAME, "---"),
+            text("README.md", "# Hi"),
+            text("openapi.yaml", spec),
+        ])
+        .unwrap()
+        .statistics();
+
+        assert_eq!(
+            stats.open_api_specs,
+            vec![FileSize {
+                path: PathBuf::from("openapi.yaml"),
+                size_bytes: spec.len(),
+            }]
+        );
+        assert!(stats.pages.iter().all(|p| !p.uri_path.starts_with("/api")));
+        assert!(stats.page_count > stats.pages.len());
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let stats = Project::from_file_list(vec![
+            text(SETTINGS_FILE_NAME, "---\ntitle: An Project\n"),
+            text("README.md", "# Hi"),
+        ])
+        .unwrap()
+        .statistics();
+
+        let json = serde_json::to_value(&stats).unwrap();
+
+        assert_eq!(json["page_count"], 1);
+        assert_eq!(json["pages"][0]["uri_path"], "/");
+        assert_eq!(json["pages"][0]["size_bytes"], 4);
+    }
+}
@@ -1,9 +1,12 @@
-use crate::icon::{Icon, IconDescription};
+use crate::icon::{CustomIcons, Icon, IconDescription};
+use crate::open_api::locate_pointer;
 use crate::parser::is_external_link;
+use crate::settings::is_full_hex_color;
 use crate::SETTINGS_FILE_NAME;
-use crate::{Error, Result};
+use crate::{Error, Position, Result};
+use located_yaml::{Yaml, YamlLoader};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use uriparse::{URIError, URI};
 
@@ -15,14 +18,42 @@ fn normalize_tab_path(path: &str) -> String {
     }
 }
 
+/// Checks if `path` is `prefix` or one of its descendants. External links
+/// never match.
+pub(crate) fn path_in_scope(path: &str, prefix: &str) -> bool {
+    if is_external_link(prefix) {
+        return false;
+    }
+
+    prefix == "/"
+        || path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// The tab, and possibly subtab, a page belongs to.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize)]
+pub struct TabScope {
+    /// Label of the tab
+    pub tab: String,
+    /// Label of the subtab, if the page is under one
+    pub subtab: Option<String>,
+    /// Path of the most specific tab or subtab that matched
+    pub path: String,
+    /// Position of the tab, followed by the position of the subtab
+    #[serde(skip)]
+    pub index: Vec<usize>,
+}
+
 #[derive(PartialEq, Clone, Debug, Serialize)]
 pub struct TabsList {
     pub tabs: Vec<Tab>,
 }
 
 pub fn parse_structure(input: &str) -> Result<TabsDescription> {
-    serde_yaml::from_str(input).map_err(|e| {
-        Error::from_serde_yaml(
+    crate::yaml::from_str(input).map_err(|e| {
+        Error::from_yaml(
             e,
             Error::INVALID_STRUCTURE,
             "Invalid tabs".to_owned(),
@@ -68,6 +99,45 @@ impl TabsList {
         Ok(TabsList { tabs })
     }
 
+    /// Fills in the markup of custom icons used by tabs and subtabs.
+    pub(crate) fn resolve_icons(&mut self, custom_icons: &CustomIcons) {
+        for tab in &mut self.tabs {
+            tab.icon = tab.icon.take().map(|i| custom_icons.resolve(i));
+
+            for subtab in &mut tab.subtabs {
+                subtab.icon = subtab.icon.take().map(|i| custom_icons.resolve(i));
+            }
+        }
+    }
+
+    /// Records where each tab and subtab is defined in the settings file, so
+    /// that errors about them can point there.
+    pub(crate) fn locate(&mut self, source: &str) {
+        let Some(root) = YamlLoader::load_from_str(source)
+            .ok()
+            .and_then(|mut loader| loader.docs.drain(..).next())
+        else {
+            return;
+        };
+
+        for (tab_index, tab) in self.tabs.iter_mut().enumerate() {
+            let pointer = format!("/tabs/{}", tab_index);
+            tab.locate_in(&root, &pointer);
+
+            for (subtab_index, subtab) in tab.subtabs.iter_mut().enumerate() {
+                subtab.locate_in(&root, &format!("{}/subtabs/{}", pointer, subtab_index));
+            }
+        }
+    }
+
+    /// All icons used by tabs and subtabs
+    pub(crate) fn icons(&self) -> impl Iterator<Item = &Icon> {
+        self.tabs
+            .iter()
+            .flat_map(|tab| std::iter::once(tab).chain(tab.subtabs.iter()))
+            .filter_map(|tab| tab.icon.as_ref())
+    }
+
     pub fn subtabs(&self) -> Vec<Tab> {
         self.tabs
             .iter()
@@ -75,18 +145,72 @@ impl TabsList {
             .collect::<Vec<_>>()
     }
 
+    /// Finds the tab, and possibly subtab, that the given URI path belongs to.
+    ///
+    /// Paths are matched segment by segment, so `/api-guide` does not belong
+    /// to `/api`. When several paths match, the longest one wins, which lets
+    /// the root `/` act as a fallback for everything not covered by a more
+    /// specific tab or subtab.
+    pub fn scope_for(&self, uri_path: &str) -> Option<TabScope> {
+        let uri_path = format!(
+            "/{}",
+            uri_path.trim_end_matches('/').trim_start_matches('/')
+        );
+
+        let mut best: Option<TabScope> = None;
+
+        for (tab_index, tab) in self.tabs.iter().enumerate() {
+            let candidates = tab
+                .subtabs
+                .iter()
+                .enumerate()
+                .map(|(subtab_index, subtab)| (Some((subtab_index, subtab)), &subtab.href))
+                .chain(std::iter::once((None, &tab.href)));
+
+            for (subtab, href) in candidates {
+                if !path_in_scope(&uri_path, href)
+                    || best.as_ref().is_some_and(|b| b.path.len() >= href.len())
+                {
+                    continue;
+                }
+
+                let mut index = vec![tab_index];
+                index.extend(subtab.map(|(i, _)| i));
+
+                best = Some(TabScope {
+                    tab: tab.label.clone(),
+                    subtab: subtab.map(|(_, s)| s.label.clone()),
+                    path: href.clone(),
+                    index,
+                });
+            }
+        }
+
+        best
+    }
+
     pub fn verify(&self) -> Vec<Error> {
         let mut errors = vec![];
 
-        // Ensure that there is at least one tab with path "/"
-        if !self.tabs.iter().any(|tab| tab.href == "/") {
+        let roots = self
+            .tabs
+            .iter()
+            .filter(|tab| tab.href == "/")
+            .collect::<Vec<_>>();
+
+        // Ensure that there is exactly one tab with path "/"
+        if roots.is_empty() {
             errors.push(Error {
                 code: Error::INVALID_STRUCTURE,
                 message: String::from("One of the tabs must be root."),
                 description: String::from("Expected a tab to have path \"/\". Found none."),
                 file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                 position: None,
+                owner: None,
+                suggestions: vec![],
             });
+        } else if roots.len() > 1 {
+            errors.push(ambiguous_root_error("tab", &roots));
         }
 
         let mut seen = HashSet::new();
@@ -94,37 +218,142 @@ impl TabsList {
         for tab in &self.tabs {
             errors.extend(tab.verify(None));
 
-            if !seen.insert(&tab.href) {
+            if !seen.insert(&tab.href) && tab.href != "/" {
                 errors.push(Error {
                         code: Error::INVALID_STRUCTURE,
                         message: String::from("Tabs can not share paths."),
                         description: format!("Multiple tabs share the path \"{}\".\nEach tab must have a unique path prefix.", tab.href),
                         file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-                        position: None,
+                        position: tab.position.clone(),
+                        owner: None,
+                        suggestions: vec![],
                     });
             }
 
+            let subtab_roots = tab
+                .subtabs
+                .iter()
+                .filter(|subtab| subtab.href == "/")
+                .collect::<Vec<_>>();
+
+            if subtab_roots.len() > 1 {
+                errors.push(ambiguous_root_error("subtab", &subtab_roots));
+            }
+
             let mut seen_for_tab = HashSet::new();
 
             for subtab in &tab.subtabs {
                 errors.extend(subtab.verify(Some(tab)));
 
-                if !seen_for_tab.insert(&subtab.href) {
+                if !seen_for_tab.insert(&subtab.href) && subtab.href != "/" {
                     errors.push(Error {
                             code: Error::INVALID_STRUCTURE,
                             message: String::from("Subtabs can not share paths."),
                             description: format!("Multiple subtabs share the path \"{}\".\nEach subtab must have a unique path prefix.", subtab.href),
                             file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
-                            position: None,
+                            position: subtab.position.clone(),
+                            owner: None,
+                            suggestions: vec![],
                         });
                 }
             }
         }
 
+        errors.extend(self.verify_subtabs_across_tabs());
+
+        errors
+    }
+
+    /// Subtabs of different tabs can't claim the same pages, since a page
+    /// can only be shown under one tab.
+    fn verify_subtabs_across_tabs(&self) -> Vec<Error> {
+        let mut errors = vec![];
+
+        let subtabs = self
+            .tabs
+            .iter()
+            .enumerate()
+            .flat_map(|(tab_index, tab)| {
+                tab.subtabs
+                    .iter()
+                    .filter(|subtab| !subtab.is_external && subtab.href != "/")
+                    .map(move |subtab| (tab_index, tab, subtab))
+            })
+            .collect::<Vec<_>>();
+
+        for (i, (tab_index, tab, subtab)) in subtabs.iter().enumerate() {
+            for (other_tab_index, other_tab, other) in &subtabs[..i] {
+                if tab_index == other_tab_index {
+                    continue;
+                }
+
+                let (message, description) = if subtab.href == other.href {
+                    (
+                        "Subtabs in different tabs can not share paths.",
+                        format!(
+                            "Subtab \"{}\" in tab \"{}\" and subtab \"{}\" in tab \"{}\" share the path \"{}\".\nEach subtab must have a unique path prefix.",
+                            other.label, other_tab.label, subtab.label, tab.label, subtab.href
+                        ),
+                    )
+                } else if path_in_scope(&subtab.href, &other.href)
+                    || path_in_scope(&other.href, &subtab.href)
+                {
+                    (
+                        "Subtabs in different tabs can not overlap.",
+                        format!(
+                            "Subtab \"{}\" in tab \"{}\" has the path \"{}\", and subtab \"{}\" in tab \"{}\" has the path \"{}\".\nPages under the longer path would belong to both tabs.",
+                            other.label, other_tab.label, other.href, subtab.label, tab.label, subtab.href
+                        ),
+                    )
+                } else {
+                    continue;
+                };
+
+                errors.push(Error {
+                    code: Error::INVALID_STRUCTURE,
+                    message: String::from(message),
+                    description,
+                    file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                    position: subtab.position.clone(),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+
         errors
     }
 }
 
+fn ambiguous_root_error(id: &str, roots: &[&Tab]) -> Error {
+    Error {
+        code: Error::INVALID_STRUCTURE,
+        message: format!("Only one {} can be root.", id),
+        description: format!(
+            "The {}s {} all have the path \"/\".\nPages not under any other path would belong to all of them. Give all but one of them a different path.",
+            id,
+            roots
+                .iter()
+                .map(|tab| format!("\"{}\"", tab.label))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+        position: roots.get(1).and_then(|tab| tab.position.clone()),
+        owner: None,
+        suggestions: vec![],
+    }
+}
+
+/// Points to the `path` or `external` of a tab, or the tab itself when it
+/// has neither.
+fn locate_tab(root: &Yaml, pointer: &str) -> Option<Position> {
+    ["path", "external"]
+        .iter()
+        .find_map(|key| locate_pointer(root, &format!("{}/{}", pointer, key)))
+        .or_else(|| locate_pointer(root, pointer))
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Tab {
     pub label: String,
@@ -132,15 +361,75 @@ pub struct Tab {
     pub href: String,
     pub is_external: bool,
     pub icon: Option<Icon>,
+    /// Hex color replacing the theme's accent color on the tab's pages
+    pub accent_color: Option<String>,
+    /// URI path of the page the tab links to, instead of its path
+    pub landing_page: Option<String>,
     #[serde(skip_serializing)]
     pub raw_path: Option<String>,
+    /// Where the tab is defined in the settings file
+    #[serde(skip)]
+    pub position: Option<Position>,
+    /// Where the tab's optional keys are defined, e.g. `accent_color`
+    #[serde(skip)]
+    pub key_positions: BTreeMap<&'static str, Position>,
 }
 
 impl Tab {
+    fn locate_in(&mut self, root: &Yaml, pointer: &str) {
+        self.position = locate_tab(root, pointer);
+
+        for key in ["accent_color", "landing_page"] {
+            if let Some(position) = locate_pointer(root, &format!("{}/{}", pointer, key)) {
+                self.key_positions.insert(key, position);
+            }
+        }
+    }
+
+    /// Where the given key of the tab is defined, or the tab itself
+    pub(crate) fn key_position(&self, key: &str) -> Option<Position> {
+        self.key_positions
+            .get(key)
+            .cloned()
+            .or_else(|| self.position.clone())
+    }
+
     fn verify(&self, parent: Option<&Tab>) -> Vec<Error> {
         let mut errors = vec![];
         let id = if parent.is_some() { "Subtab" } else { "Tab" };
 
+        if let Some(color) = self.accent_color.as_ref().filter(|c| !is_full_hex_color(c)) {
+            errors.push(Error {
+                code: Error::INVALID_STRUCTURE,
+                message: format!("Invalid accent color for {}", id.to_lowercase()),
+                description: format!(
+                    "Expected {} \"{}\" to have a hex color like \"#5B5BD6\".\nFound \"{}\".",
+                    id.to_lowercase(),
+                    self.label,
+                    color
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: self.key_position("accent_color"),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
+        if self.is_external && self.landing_page.is_some() {
+            errors.push(Error {
+                code: Error::INVALID_STRUCTURE,
+                message: format!("{} with external links cannot have a landing page", id),
+                description: format!(
+                    "{} \"{}\" has both an external link and a landing page.",
+                    id, self.label
+                ),
+                file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
+                position: self.key_position("landing_page"),
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+
         if let Some(icon) = &self.icon {
             if !icon.is_valid() {
                 errors.push(Error {
@@ -159,6 +448,8 @@ impl Tab {
                     ),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })
             }
 
@@ -172,6 +463,8 @@ impl Tab {
                     ),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 })
             }
         }
@@ -182,6 +475,10 @@ impl Tab {
             errors.extend(self.verify_internal(parent));
         }
 
+        for error in errors.iter_mut().filter(|e| e.position.is_none()) {
+            error.position = self.position.clone();
+        }
+
         errors
     }
 
@@ -207,6 +504,8 @@ impl Tab {
                     ),
                     file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                     position: None,
+                    owner: None,
+                    suggestions: vec![],
                 });
             }
 
@@ -226,6 +525,8 @@ impl Tab {
                         ),
                         file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
             }
@@ -241,6 +542,8 @@ impl Tab {
                 ),
                 file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                 position: None,
+                owner: None,
+                suggestions: vec![],
             });
         }
 
@@ -258,6 +561,8 @@ impl Tab {
                 description: format!("Tab \"{}\" has both subtabs and external URL.", self.label),
                 file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                 position: None,
+                owner: None,
+                suggestions: vec![],
             });
         }
 
@@ -278,6 +583,8 @@ impl Tab {
                         ),
                         file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
                 URIError::NotURI => {
@@ -294,6 +601,8 @@ impl Tab {
                         ),
                         file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                 position: None,
+                owner: None,
+                suggestions: vec![],
                     });
                 }
                 _ => {
@@ -306,6 +615,8 @@ impl Tab {
                         ),
                         file: Some(PathBuf::from(SETTINGS_FILE_NAME)),
                         position: None,
+                        owner: None,
+                        suggestions: vec![],
                     });
                 }
             }
@@ -325,8 +636,16 @@ impl Tab {
     #[allow(dead_code)]
     pub fn prefix(&mut self, prefix: &str) -> &Self {
         self.href = format!("{}{}", prefix, self.href);
+        self.landing_page = self
+            .landing_page
+            .take()
+            .map(|page| format!("{}{}", prefix, page));
         self.subtabs.iter_mut().for_each(|subtab| {
             subtab.href = format!("{}{}", prefix, subtab.href);
+            subtab.landing_page = subtab
+                .landing_page
+                .take()
+                .map(|page| format!("{}{}", prefix, page));
         });
 
         self
@@ -360,6 +679,11 @@ pub struct TabDescription {
     #[serde(default)]
     pub subtabs: Vec<SubTabDescription>,
     pub icon: Option<IconDescription>,
+    /// Hex color replacing the theme's accent color on the tab's pages
+    pub accent_color: Option<String>,
+    /// Path of the page the tab links to, e.g. `/api/overview`. Defaults to
+    /// the tab's path.
+    pub landing_page: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -369,6 +693,8 @@ pub struct SubTabDescription {
     external: Option<String>,
     label: String,
     icon: Option<IconDescription>,
+    accent_color: Option<String>,
+    landing_page: Option<String>,
 }
 
 impl From<TabDescription> for Tab {
@@ -379,7 +705,8 @@ impl From<TabDescription> for Tab {
             label,
             subtabs,
             icon,
-            ..
+            accent_color,
+            landing_page,
         } = desc;
 
         let icon = icon.map(|i| i.resolve());
@@ -400,7 +727,11 @@ impl From<TabDescription> for Tab {
             href,
             icon,
             is_external,
+            accent_color,
+            landing_page: landing_page.map(|p| normalize_tab_path(&p)),
             raw_path: path,
+            position: None,
+            key_positions: BTreeMap::new(),
         }
     }
 }
@@ -412,7 +743,9 @@ impl From<SubTabDescription> for TabDescription {
             label,
             path,
             icon,
-        } = desc.clone();
+            accent_color,
+            landing_page,
+        } = desc;
 
         TabDescription {
             path,
@@ -420,6 +753,8 @@ impl From<SubTabDescription> for TabDescription {
             label,
             subtabs: vec![],
             icon,
+            accent_color,
+            landing_page,
         }
     }
 }
@@ -896,12 +1231,12 @@ mod test {
         assert_eq!(tab_error.message, "Unknown icon set in tab");
         assert_eq!(
             tab_error.description,
-            "Tab \"Default\" has an unknown icon set: \"nope\". Expected one of [\"lucide\", \"devicon\"].",
+            "Tab \"Default\" has an unknown icon set: \"nope\". Expected one of [\"lucide\", \"devicon\", \"custom\"].",
         );
         assert_eq!(subtab_error.message, "Unknown icon set in subtab");
         assert_eq!(
             subtab_error.description,
-            "Subtab \"Second\" has an unknown icon set: \"nope\". Expected one of [\"lucide\", \"devicon\"].",
+            "Subtab \"Second\" has an unknown icon set: \"nope\". Expected one of [\"lucide\", \"devicon\", \"custom\"].",
         );
     }
 
@@ -936,4 +1271,442 @@ mod test {
             "Tab \"Other\" has both an external link and an icon."
         );
     }
+
+    #[test]
+    fn tabs_can_have_custom_icons() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+            icon:
+              set: custom
+              name: brand
+            subtabs:
+              - label: Second
+                path: /
+                icon:
+                  set: lucide
+                  name: box
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file(
+            "_assets/icons/brand.svg",
+            r#"<svg viewBox="0 0 24 24" onload="alert(1)"><script>alert(1)</script><path d="M0 0"/></svg>"#,
+        );
+        builder.with_file("_assets/icons/box.svg", "<svg><circle r=\"4\"/></svg>");
+
+        let project = builder.build().unwrap();
+
+        let result = project.verify(None, None);
+        assert!(result.is_ok(), "Failed to build project: {:#?}", result);
+
+        let tab = &project.tabs().unwrap().tabs[0];
+        let icon = tab.icon.as_ref().unwrap();
+        assert_eq!(icon.set(), "custom");
+        assert_eq!(
+            icon.html(),
+            r#"<svg viewBox="0 0 24 24"><path d="M0 0"/></svg>"#
+        );
+
+        // The custom icon with the same name replaces the built-in one
+        let subtab_icon = tab.subtabs[0].icon.as_ref().unwrap();
+        assert_eq!(subtab_icon.set(), "custom");
+        assert_eq!(subtab_icon.html(), "<svg><circle r=\"4\"/></svg>");
+
+        let warnings = project.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "Custom icon overrides a built-in icon");
+    }
+
+    #[test]
+    fn verifies_custom_icons() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+            icon:
+              set: custom
+              name: missing
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("_assets/icons/broken.svg", "<html></html>");
+
+        let project = builder.build().unwrap();
+
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 2, "{:#?}", errors);
+        assert!(errors.iter().any(|e| e.message == "Unknown custom icon"
+            && e.description
+                .starts_with("Could not find an icon named \"missing\"")
+            && e.description
+                .ends_with("Found following icons: [\"broken\"].")));
+        assert!(errors.iter().any(|e| e.message == "Invalid custom icon"
+            && e.file == Some(PathBuf::from("_assets/icons/broken.svg"))));
+    }
+
+    #[test]
+    fn scope_prefers_the_longest_matching_path() {
+        let tabs = TabsList::build(indoc! {r#"
+        tabs:
+          - label: Guides
+            path: /
+            subtabs:
+              - label: Basics
+                path: /
+              - label: Advanced
+                path: /advanced
+          - label: API
+            path: /api
+          - label: Blog
+            external: https://www.example.com/blog
+        "#})
+        .unwrap();
+
+        let scope = tabs.scope_for("/advanced/caching/").unwrap();
+        assert_eq!(scope.tab, "Guides");
+        assert_eq!(scope.subtab.as_deref(), Some("Advanced"));
+        assert_eq!(scope.path, "/advanced");
+        assert_eq!(scope.index, vec![0, 1]);
+
+        let scope = tabs.scope_for("/api").unwrap();
+        assert_eq!(scope.tab, "API");
+        assert_eq!(scope.subtab, None);
+        assert_eq!(scope.index, vec![1]);
+
+        let scope = tabs.scope_for("/").unwrap();
+        assert_eq!(scope.subtab.as_deref(), Some("Basics"));
+        assert_eq!(scope.index, vec![0, 0]);
+    }
+
+    #[test]
+    fn scope_matches_whole_path_segments() {
+        let tabs = TabsList::build(indoc! {r#"
+        tabs:
+          - label: Guides
+            path: /
+          - label: API
+            path: /api
+        "#})
+        .unwrap();
+
+        assert_eq!(tabs.scope_for("/api/users").unwrap().path, "/api");
+        assert_eq!(tabs.scope_for("/api-guide").unwrap().path, "/");
+        assert_eq!(tabs.scope_for("/docs/api").unwrap().path, "/");
+    }
+
+    #[test]
+    fn no_scope_without_a_matching_tab() {
+        let tabs = TabsList::build(indoc! {r#"
+        tabs:
+          - label: Guides
+            path: /guides
+        "#})
+        .unwrap();
+
+        assert_eq!(tabs.scope_for("/other"), None);
+    }
+
+    #[test]
+    fn verifies_only_one_tab_is_root() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+          - label: Another
+            path: /
+        "#};
+
+        let builder = ProjectBuilder::with_structure(structure);
+
+        let project = builder.build().unwrap();
+
+        let errors = project
+            .verify(None, None)
+            .expect_err("Expected verification to fail");
+
+        let error = errors
+            .iter()
+            .find(|e| e.message == "Only one tab can be root.")
+            .expect("No error for multiple root tabs");
+
+        assert_eq!(error.code, Error::INVALID_STRUCTURE);
+        assert_eq!(
+            error.description,
+            "The tabs \"Default\", \"Another\" all have the path \"/\".\nPages not under any other path would belong to all of them. Give all but one of them a different path."
+        );
+        assert_eq!(error.position.as_ref().unwrap().start.row, 8);
+
+        assert!(
+            !errors
+                .iter()
+                .any(|e| e.message == "Tabs can not share paths."),
+            "Root tabs reported twice"
+        );
+    }
+
+    #[test]
+    fn verifies_subtab_paths_are_unique_across_tabs() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+            subtabs:
+              - label: Guides
+                path: /guides
+          - label: Other
+            path: /guides
+            subtabs:
+              - label: Guides again
+                path: /guides
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("guides/README.md", "# Guides");
+
+        let project = builder.build().unwrap();
+
+        let errors = project
+            .verify(None, None)
+            .expect_err("Expected verification to fail");
+
+        let error = errors
+            .iter()
+            .find(|e| e.message == "Subtabs in different tabs can not share paths.")
+            .expect("No error for subtabs sharing paths across tabs");
+
+        assert_eq!(
+            error.description,
+            "Subtab \"Guides\" in tab \"Default\" and subtab \"Guides again\" in tab \"Other\" share the path \"/guides\".\nEach subtab must have a unique path prefix."
+        );
+        assert_eq!(error.position.as_ref().unwrap().start.row, 14);
+    }
+
+    #[test]
+    fn verifies_subtab_paths_do_not_overlap_across_tabs() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+          - label: API
+            path: /api
+            subtabs:
+              - label: Reference
+                path: /api
+          - label: Beta
+            path: /api/beta
+            subtabs:
+              - label: Beta reference
+                path: /api/beta
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("api/README.md", "# API");
+        builder.with_file("api/beta/README.md", "# Beta");
+
+        let project = builder.build().unwrap();
+
+        let errors = project
+            .verify(None, None)
+            .expect_err("Expected verification to fail");
+
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.message == "Subtabs in different tabs can not overlap."),
+            "No error for overlapping subtabs: {:#?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn verifies_tab_paths_have_content() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+            subtabs:
+              - label: Guides
+                path: /guidse/
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("guides/README.md", "# Guides");
+
+        let project = builder.build().unwrap();
+
+        let errors = project
+            .verify(None, None)
+            .expect_err("Expected verification to fail");
+
+        let error = errors
+            .iter()
+            .find(|e| e.message == "Subtab \"Guides\" has no content.")
+            .expect("No error for subtab without content");
+
+        assert_eq!(error.code, Error::INVALID_STRUCTURE);
+        assert_eq!(
+            error.description,
+            "No pages or navigation.yaml found under the path \"/guidse\". Did you mean \"/guides\"?"
+        );
+        assert_eq!(error.position.as_ref().unwrap().start.row, 9);
+    }
+
+    #[test]
+    fn warns_about_missing_subtab_navigation() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+            subtabs:
+              - label: Guides
+                path: /guides
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("guides/README.md", "# Guides");
+
+        let project = builder.build().unwrap();
+
+        project.verify(None, None).unwrap();
+
+        let warnings = project.warnings();
+        let warning = warnings
+            .iter()
+            .find(|w| w.code == Error::MISSING_NAVIGATION)
+            .expect("No warning for missing navigation");
+
+        assert_eq!(
+            warning.message,
+            "Missing navigation.yaml for subtab \"Guides\""
+        );
+        assert_eq!(
+            warning.description,
+            "Expected a navigation file at \"guides/navigation.yaml\". Pages under \"/guides\" are shown without a navigation."
+        );
+        assert_eq!(warning.position.as_ref().unwrap().start.row, 9);
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("guides/README.md", "# Guides");
+        builder.with_file("guides/navigation.yaml", "---\n- heading: Guides");
+
+        let project = builder.build().unwrap();
+
+        assert!(!project
+            .warnings()
+            .iter()
+            .any(|w| w.code == Error::MISSING_NAVIGATION));
+    }
+
+    #[test]
+    fn verifies_accent_colors() {
+        let structure = indoc! {r##"
+        tabs:
+          - label: Default
+            path: /
+            accent_color: blue
+            subtabs:
+              - label: Guides
+                path: /guides
+                accent_color: "#00A2C7"
+        "##};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("guides/README.md", "# Guides");
+
+        let project = builder.build().unwrap();
+        let errors = project.verify(None, None).unwrap_err();
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::INVALID_STRUCTURE);
+        assert_eq!(errors[0].message, "Invalid accent color for tab");
+        assert_eq!(
+            errors[0].description,
+            "Expected tab \"Default\" to have a hex color like \"#5B5BD6\".\nFound \"blue\"."
+        );
+        assert_eq!(errors[0].position.as_ref().unwrap().start.row, 7);
+    }
+
+    #[test]
+    fn verifies_landing_pages() {
+        let structure = indoc! {r#"
+        tabs:
+          - label: Default
+            path: /
+            landing_page: /welcome
+          - label: API
+            path: /api
+            landing_page: /guides/intro
+        "#};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("api/README.md", "# API");
+        builder.with_file("guides/intro.md", "# Intro");
+
+        let project = builder.build().unwrap();
+        let errors = project.verify(None, None).unwrap_err();
+
+        let descriptions = errors
+            .iter()
+            .filter(|e| e.message == "Invalid landing page for tab")
+            .map(|e| {
+                (
+                    e.description.as_str(),
+                    e.position.as_ref().unwrap().start.row,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            descriptions,
+            vec![
+                (
+                    "Could not find a page at \"/welcome\" for the landing page of tab \"Default\".",
+                    7
+                ),
+                (
+                    "Expected the landing page of tab \"API\" to be under \"/api\".\nFound \"/guides/intro\".",
+                    10
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn generates_css_for_tab_accent_colors() {
+        let structure = indoc! {r##"
+        tabs:
+          - label: Default
+            path: /
+            subtabs:
+              - label: Guides
+                path: /guides
+                accent_color: "#00A2C7"
+          - label: API
+            path: /api
+            accent_color: "#E54666"
+            landing_page: /api/overview
+        "##};
+
+        let mut builder = ProjectBuilder::with_structure(structure);
+        builder.with_file("guides/README.md", "# Guides");
+        builder.with_file("api/README.md", "# API");
+        builder.with_file("api/overview.md", "# Overview");
+
+        let project = builder.build().unwrap();
+        project.verify(None, None).unwrap();
+
+        let css = project.tab_accent_css();
+
+        assert!(css.contains("body[data-tab=\"1\"] {"), "{}", css);
+        assert!(css.contains(".dark body[data-tab=\"1\"], .dark-theme body[data-tab=\"1\"] {"));
+        assert!(css.contains("body[data-subtab=\"0-0\"] {"));
+        assert!(!css.contains("body[data-tab=\"0\"]"));
+
+        let tabs = &project.tabs().unwrap().tabs;
+        assert_eq!(tabs[1].accent_color.as_deref(), Some("#E54666"));
+        assert_eq!(tabs[1].landing_page.as_deref(), Some("/api/overview"));
+    }
 }
@@ -0,0 +1,208 @@
+//! Helpers for testing against rendered pages, for themes and other consumers
+//! of the Content API writing contract tests. Enabled with the
+//! `test-support` feature.
+//!
+//! ```ignore
+//! use libdoctave::test_support::{assert_snapshot, page_snapshot, project};
+//!
+//! let project = project(&[("README.md", "# Hello")]);
+//!
+//! assert_snapshot("readme", &page_snapshot(&project, "/"));
+//! ```
+//!
+//! Snapshots are stored in `tests/snapshots` of the crate running the tests.
+//! Set `DOCAPELLA_UPDATE_SNAPSHOTS=1` to write them instead of comparing
+//! against them.
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    InputContent, InputFile, Project, ResponseContext, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+};
+
+/// Set to write snapshots instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "DOCAPELLA_UPDATE_SNAPSHOTS";
+
+/// Keys left out of snapshots, because they change from build to build or
+/// between machines.
+const VOLATILE_KEYS: &[&str] = &["build", "debug_info", "last_updated", "contributors"];
+
+/// Builds a project from paths and their contents. The settings and the
+/// navigation are filled in if they're not given.
+///
+/// Panics if the project can't be built.
+pub fn project(files: &[(&str, &str)]) -> Project {
+    let mut list = files
+        .iter()
+        .map(|(path, content)| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        })
+        .collect::<Vec<_>>();
+
+    for (path, default) in [
+        (SETTINGS_FILE_NAME, "---\ntitle: An Project\n"),
+        (NAVIGATION_FILE_NAME, "---\n"),
+    ] {
+        if !list.iter().any(|file| file.path == Path::new(path)) {
+            list.push(InputFile {
+                path: PathBuf::from(path),
+                content: InputContent::Text(default.to_string()),
+            });
+        }
+    }
+
+    match Project::from_file_list(list) {
+        Ok(project) => project,
+        Err(errors) => panic!("Could not build the project: {:#?}", errors),
+    }
+}
+
+/// The Content API response for a page, with the keys sorted and the
+/// volatile fields left out.
+pub fn page_snapshot(project: &Project, uri_path: &str) -> Value {
+    let response = project.get_content_response_by_uri_path(uri_path, ResponseContext::default());
+
+    normalize(serde_json::to_value(response).expect("responses serialize to JSON"))
+}
+
+/// Sorts the keys of objects and leaves out the volatile fields, like
+/// timestamps, so that the value can be compared between runs.
+pub fn normalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map
+                .into_iter()
+                .filter(|(key, _)| !VOLATILE_KEYS.contains(&key.as_str()))
+                .collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, normalize(value)))
+                    .collect::<Map<_, _>>(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(normalize).collect()),
+        other => other,
+    }
+}
+
+/// Compares the value against the snapshot with the given name, showing a
+/// diff if they differ. Writes the snapshot instead if
+/// [`UPDATE_SNAPSHOTS_ENV`] is set.
+pub fn assert_snapshot(name: &str, value: &Value) {
+    let update = std::env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+
+    check_snapshot(&snapshot_path(name), value, update);
+}
+
+/// Where the snapshot with the given name is stored, under `tests/snapshots`
+/// of the crate running the tests.
+pub fn snapshot_path(name: &str) -> PathBuf {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+
+    root.join("tests")
+        .join("snapshots")
+        .join(format!("{}.json", name))
+}
+
+fn check_snapshot(path: &Path, value: &Value, update: bool) {
+    let mut actual = serde_json::to_string_pretty(value).expect("values serialize to JSON");
+    actual.push('\n');
+
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("could not create the snapshot folder");
+        }
+        std::fs::write(path, actual).expect("could not write the snapshot");
+        return;
+    }
+
+    let expected = match std::fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(_) => panic!(
+            "Missing snapshot {}. Run the tests with {}=1 to create it.",
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV
+        ),
+    };
+
+    pretty_assertions::assert_eq!(
+        expected,
+        actual,
+        "Snapshot {} doesn't match. Run the tests with {}=1 to update it.",
+        path.display(),
+        UPDATE_SNAPSHOTS_ENV
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_snapshot(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("libdoctave-snapshots-{}", std::process::id()))
+            .join(format!("{}.json", name))
+    }
+
+    #[test]
+    fn fills_in_the_settings_and_navigation() {
+        let project = project(&[("README.md", "# Hello")]);
+
+        assert_eq!(project.settings.title(), "An Project");
+        assert!(project.get_page_by_uri_path("/").is_some());
+    }
+
+    #[test]
+    fn sorts_keys_and_leaves_out_volatile_fields() {
+        let value = normalize(json!({
+            "page": { "title": "Hi", "last_updated": "2024-05-01", "ast": [{ "b": 1, "a": 2 }] },
+            "build": { "id": 0 },
+            "kind": "content",
+        }));
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"kind":"content","page":{"ast":[{"a":2,"b":1}],"title":"Hi"}}"#
+        );
+    }
+
+    #[test]
+    fn snapshots_a_page() {
+        let project = project(&[("README.md", "# Hello\n\nSome content")]);
+
+        let snapshot = page_snapshot(&project, "/");
+
+        assert_eq!(snapshot["page"]["description"], "Some content");
+        assert!(snapshot.get("build").is_none());
+        assert!(snapshot["page"].get("last_updated").is_none());
+    }
+
+    #[test]
+    fn writes_snapshots_in_update_mode_and_compares_otherwise() {
+        let path = temp_snapshot("update");
+
+        check_snapshot(&path, &json!({ "title": "Hello" }), true);
+        check_snapshot(&path, &json!({ "title": "Hello" }), false);
+
+        let mismatch = std::panic::catch_unwind(|| {
+            check_snapshot(&path, &json!({ "title": "Goodbye" }), false)
+        });
+        assert!(mismatch.is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing snapshot")]
+    fn fails_on_missing_snapshots() {
+        check_snapshot(&temp_snapshot("missing"), &json!({}), false);
+    }
+}
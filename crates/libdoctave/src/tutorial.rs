@@ -0,0 +1,501 @@
+//! Tutorials: pages meant to be read in order, like "Part 2 of 5". A
+//! `_tutorial.yaml` in a folder titles the tutorial and lists its steps:
+//!
+//! ```yaml
+//! title: Getting started
+//! steps:
+//!   - install.md
+//!   - configure.md
+//!   - /guides/deploy.md
+//! ```
+//!
+//! Steps are relative to the folder, unless they start with a `/`. The
+//! tutorial is identified by its folder, e.g. `tutorials/getting-started`,
+//! which pages can name with `tutorial` in their frontmatter to make sure
+//! they stay in it.
+//!
+//! Steps link to each other in the tutorial's order, instead of in the order
+//! of the navigation.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_path_to_error::Segment;
+
+use crate::{
+    frontmatter, markdown, page_kind::PageKind, page_links::PageLink,
+    render_context::RenderContext, Error, PageHandle, Project, RenderOptions, Result,
+};
+
+pub const TUTORIAL_FILE_NAME: &str = "_tutorial.yaml";
+
+/// A `_tutorial.yaml`, parsed when it's needed so that errors in it are
+/// reported by `verify` instead of failing the whole project.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TutorialFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TutorialDescription {
+    title: String,
+    steps: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tutorial {
+    /// Path of the tutorial's folder, e.g. `tutorials/getting-started`
+    pub id: String,
+    pub title: String,
+    pub file: PathBuf,
+    /// Paths of the pages of the steps, in order
+    pub steps: Vec<PathBuf>,
+}
+
+impl TutorialFile {
+    fn folder(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+
+    pub(crate) fn parse(&self) -> Result<Tutorial> {
+        let description: TutorialDescription =
+            crate::yaml::from_str(&self.content).map_err(|e| {
+                Error::from_yaml(
+                    e,
+                    Error::INVALID_TUTORIAL,
+                    "Invalid tutorial".to_owned(),
+                    Some(self.path.clone()),
+                )
+            })?;
+
+        Ok(Tutorial {
+            id: self.folder().to_string_lossy().to_string(),
+            title: description.title,
+            file: self.path.clone(),
+            steps: description
+                .steps
+                .iter()
+                .map(|step| self.step_path(step))
+                .collect(),
+        })
+    }
+
+    fn step_path(&self, step: &str) -> PathBuf {
+        match step.strip_prefix('/') {
+            Some(path) => crate::canonical_path::canonicalize(Path::new(path)),
+            None => crate::canonical_path::canonicalize(&self.folder().join(step)),
+        }
+    }
+}
+
+impl Tutorial {
+    /// A stable ID for a step, for remembering which steps a reader has
+    /// completed. Based on the step's file rather than its number, so it
+    /// stays the same when steps are added or reordered.
+    fn step_id(&self, step: &Path) -> String {
+        let path = step
+            .strip_prefix(&self.id)
+            .unwrap_or(step)
+            .with_extension("");
+
+        format!("{}/{}", self.id, path.to_string_lossy())
+            .trim_start_matches('/')
+            .to_string()
+    }
+}
+
+/// Where a page is in its tutorial, for the content API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TutorialProgress {
+    pub id: String,
+    pub title: String,
+    pub total_steps: usize,
+    /// Starts from 1
+    pub current_step: usize,
+    pub steps: Vec<TutorialStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TutorialStep {
+    /// Stays the same when steps are added or reordered, e.g.
+    /// `tutorials/getting-started/install`
+    pub id: String,
+    pub number: usize,
+    pub title: String,
+    pub href: String,
+}
+
+/// The tutorials of the project. Ones that fail to parse are reported by
+/// `verify`.
+pub(crate) fn all(project: &Project) -> Vec<Tutorial> {
+    project
+        .tutorials
+        .iter()
+        .filter_map(|file| file.parse().ok())
+        .collect()
+}
+
+/// The tutorial the page is a step of. The first one wins if the page is in
+/// several, which `verify` reports.
+pub(crate) fn of_page(project: &Project, fs_path: &Path) -> Option<Tutorial> {
+    all(project)
+        .into_iter()
+        .find(|tutorial| tutorial.steps.iter().any(|step| step == fs_path))
+}
+
+/// The published pages of the tutorial's steps, in order. Steps that are
+/// drafts, or missing, are skipped.
+fn published_steps<'a>(
+    tutorial: &Tutorial,
+    project: &'a Project,
+    opts: Option<&RenderOptions>,
+) -> Vec<(PathBuf, PageHandle<'a>)> {
+    let include_drafts = crate::project::publishes_drafts(opts);
+
+    tutorial
+        .steps
+        .iter()
+        .filter_map(|step| {
+            let page = project.get_page_by_fs_path(step)?;
+
+            (include_drafts || page.draft_status().is_none()).then(|| (step.clone(), page))
+        })
+        .collect()
+}
+
+/// Where the page is in its tutorial, if it's a step of one.
+pub(crate) fn progress(
+    page: &PageHandle,
+    project: &Project,
+    opts: Option<&RenderOptions>,
+) -> Option<TutorialProgress> {
+    let tutorial = of_page(project, page.fs_path())?;
+    let steps = published_steps(&tutorial, project, opts);
+    let current = steps
+        .iter()
+        .position(|(_, p)| p.fs_path() == page.fs_path())?;
+
+    let mut ctx = RenderContext::default();
+    ctx.with_settings(&project.settings);
+    ctx.with_maybe_options(opts);
+
+    Some(TutorialProgress {
+        id: tutorial.id.clone(),
+        title: tutorial.title.clone(),
+        total_steps: steps.len(),
+        current_step: current + 1,
+        steps: steps
+            .iter()
+            .enumerate()
+            .map(|(index, (step, page))| TutorialStep {
+                id: tutorial.step_id(step),
+                number: index + 1,
+                title: page.title().unwrap_or_else(|_| page.uri_path().to_owned()),
+                href: markdown::parser::to_final_link(page.uri_path(), &ctx),
+            })
+            .collect(),
+    })
+}
+
+/// The neighbouring steps of the page, if it's a step of a tutorial.
+pub(crate) fn previous_and_next(
+    uri_path: &str,
+    project: &Project,
+    opts: Option<&RenderOptions>,
+) -> Option<(Option<PageLink>, Option<PageLink>)> {
+    let page = project.find_page_by_uri_path(uri_path, crate::project::publishes_drafts(opts))?;
+    let progress = progress(&page, project, opts)?;
+
+    let to_link = |step: &TutorialStep| PageLink {
+        href: step.href.clone(),
+        title: step.title.clone(),
+    };
+
+    let index = progress.current_step - 1;
+
+    Some((
+        index
+            .checked_sub(1)
+            .and_then(|i| progress.steps.get(i))
+            .map(to_link),
+        progress.steps.get(index + 1).map(to_link),
+    ))
+}
+
+/// Checks that the steps of each tutorial exist and are listed once, and
+/// that pages naming a tutorial in their frontmatter are in it.
+pub(crate) fn verify(project: &Project) -> Vec<Error> {
+    let mut errors = vec![];
+    let mut tutorials = vec![];
+
+    for file in &project.tutorials {
+        match file.parse() {
+            Ok(tutorial) => tutorials.push((file, tutorial)),
+            Err(error) => errors.push(error),
+        }
+    }
+
+    // The tutorial each page was first listed in
+    let mut listed: HashMap<&Path, &Tutorial> = HashMap::new();
+
+    for (file, tutorial) in &tutorials {
+        for (index, step) in tutorial.steps.iter().enumerate() {
+            let path = [
+                Segment::Map {
+                    key: "steps".to_owned(),
+                },
+                Segment::Seq { index },
+            ];
+
+            let problem = if project.get_page_by_fs_path(step).is_none() {
+                Some((
+                    "Missing tutorial step",
+                    format!(
+                        "Step {} of \"{}\" is \"{}\", which isn't a page in the project.",
+                        index + 1,
+                        tutorial.title,
+                        step.display()
+                    ),
+                ))
+            } else if let Some(other) = listed.get(step.as_path()) {
+                if other.file == tutorial.file {
+                    Some((
+                        "Duplicate tutorial step",
+                        format!(
+                            "\"{}\" is listed more than once in \"{}\". Each page can be one step of a tutorial.",
+                            step.display(),
+                            tutorial.title
+                        ),
+                    ))
+                } else {
+                    Some((
+                        "Page in more than one tutorial",
+                        format!(
+                            "\"{}\" is already a step of \"{}\" in {}. Pages can only be in one tutorial.",
+                            step.display(),
+                            other.title,
+                            other.file.display()
+                        ),
+                    ))
+                }
+            } else {
+                listed.insert(step.as_path(), tutorial);
+                None
+            };
+
+            if let Some((message, description)) = problem {
+                errors.push(Error {
+                    code: Error::INVALID_TUTORIAL,
+                    message: message.to_owned(),
+                    description,
+                    file: Some(file.path.clone()),
+                    position: crate::yaml::position_of(&file.content, &path),
+                    owner: None,
+                    suggestions: vec![],
+                });
+            }
+        }
+    }
+
+    for page in project.pages() {
+        let PageKind::Markdown(md) = page.page else {
+            continue;
+        };
+
+        // Invalid frontmatters are reported on their own
+        let Some(claimed) = md.frontmatter().ok().and_then(|f| f.tutorial) else {
+            continue;
+        };
+
+        let claimed = claimed.trim_matches('/');
+
+        let description = match tutorials.iter().find(|(_, t)| t.id == claimed) {
+            None => format!(
+                "No tutorial is declared in \"{}\". Add a {} to the folder, or remove `tutorial` from the frontmatter.",
+                claimed, TUTORIAL_FILE_NAME
+            ),
+            Some((_, tutorial)) if !tutorial.steps.iter().any(|s| s == page.fs_path()) => format!(
+                "The page isn't one of the steps in {}. Add it to `steps`, or remove `tutorial` from the frontmatter.",
+                tutorial.file.display()
+            ),
+            Some(_) => continue,
+        };
+
+        errors.push(Error {
+            code: Error::INVALID_TUTORIAL,
+            message: format!("Page isn't a step of tutorial \"{}\"", claimed),
+            description,
+            file: Some(md.source_path().to_owned()),
+            position: frontmatter::value_position(&md.content, "tutorial"),
+            owner: None,
+            suggestions: vec![],
+        });
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+
+    fn project(files: Vec<(&str, &str)>) -> Project {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        let mut list = vec![
+            file("README.md", "# Home"),
+            file(NAVIGATION_FILE_NAME, "---"),
+            file(SETTINGS_FILE_NAME, "---\ntitle: Something\n"),
+        ];
+        list.extend(files.into_iter().map(|(path, content)| file(path, content)));
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    const MANIFEST: &str = indoc! {"
+    title: Getting started
+    steps:
+      - install.md
+      - configure.md
+      - /deploy.md
+    "};
+
+    #[test]
+    fn tracks_progress_through_the_tutorial() {
+        let project = project(vec![
+            ("start/_tutorial.yaml", MANIFEST),
+            ("start/install.md", "# Install"),
+            ("start/configure.md", "# Configure"),
+            ("deploy.md", "# Deploy"),
+        ]);
+
+        let page = project.get_page_by_uri_path("/start/configure").unwrap();
+        let progress = progress(&page, &project, None).unwrap();
+
+        assert_eq!(progress.id, "start");
+        assert_eq!(progress.title, "Getting started");
+        assert_eq!(progress.total_steps, 3);
+        assert_eq!(progress.current_step, 2);
+        assert_eq!(
+            progress.steps,
+            vec![
+                TutorialStep {
+                    id: "start/install".to_string(),
+                    number: 1,
+                    title: "Install".to_string(),
+                    href: "/start/install".to_string(),
+                },
+                TutorialStep {
+                    id: "start/configure".to_string(),
+                    number: 2,
+                    title: "Configure".to_string(),
+                    href: "/start/configure".to_string(),
+                },
+                TutorialStep {
+                    id: "start/deploy".to_string(),
+                    number: 3,
+                    title: "Deploy".to_string(),
+                    href: "/deploy".to_string(),
+                },
+            ]
+        );
+
+        let readme = project.get_page_by_uri_path("/").unwrap();
+        assert!(progress(&readme, &project, None).is_none());
+    }
+
+    #[test]
+    fn links_steps_in_tutorial_order() {
+        let project = project(vec![
+            ("start/_tutorial.yaml", MANIFEST),
+            ("start/install.md", "# Install"),
+            ("start/configure.md", "# Configure"),
+            ("deploy.md", "# Deploy"),
+        ]);
+
+        let (previous, next) = project
+            .get_page_by_uri_path("/start/configure")
+            .unwrap()
+            .previous_and_next(None);
+
+        assert_eq!(previous.unwrap().href, "/start/install");
+        assert_eq!(next.unwrap().href, "/deploy");
+
+        let (previous, next) = project
+            .get_page_by_uri_path("/deploy")
+            .unwrap()
+            .previous_and_next(None);
+
+        assert_eq!(previous.unwrap().title, "Configure");
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn reports_missing_and_duplicate_steps() {
+        let project = project(vec![
+            (
+                "start/_tutorial.yaml",
+                indoc! {"
+                title: Getting started
+                steps:
+                  - install.md
+                  - missing.md
+                  - install.md
+                "},
+            ),
+            ("start/install.md", "# Install"),
+        ]);
+
+        let errors = verify(&project);
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| e.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Missing tutorial step", "Duplicate tutorial step"],
+            "{:#?}",
+            errors
+        );
+        assert_eq!(errors[0].file, Some(PathBuf::from("start/_tutorial.yaml")));
+        assert_eq!(errors[0].position.as_ref().unwrap().start.row, 4);
+        assert_eq!(errors[1].position.as_ref().unwrap().start.row, 5);
+    }
+
+    #[test]
+    fn reports_pages_claiming_a_tutorial_that_does_not_list_them() {
+        let project = project(vec![
+            ("start/_tutorial.yaml", MANIFEST),
+            ("start/install.md", "---\ntutorial: start\n---\n# Install"),
+            ("start/extra.md", "---\ntutorial: start\n---\n# Extra"),
+            ("other.md", "---\ntutorial: nowhere\n---\n# Other"),
+        ]);
+
+        let errors = verify(&project)
+            .into_iter()
+            .filter(|e| e.message.starts_with("Page isn't a step"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(errors.len(), 2, "{:#?}", errors);
+        assert_eq!(errors[0].file, Some(PathBuf::from("other.md")));
+        assert_eq!(errors[1].file, Some(PathBuf::from("start/extra.md")));
+        assert_eq!(errors[1].position.as_ref().unwrap().start.row, 2);
+    }
+
+    #[test]
+    fn reports_invalid_manifests() {
+        let project = project(vec![("start/_tutorial.yaml", "title: Getting started\n")]);
+
+        let errors = verify(&project);
+
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::INVALID_TUTORIAL);
+        assert_eq!(errors[0].message, "Invalid tutorial");
+    }
+}
@@ -18,3 +18,79 @@ pub(crate) fn empty_map<K, V>(map: &std::collections::HashMap<K, V>) -> bool {
 pub(crate) fn is_false(val: &bool) -> bool {
     !(*val)
 }
+
+/// Finds the candidate closest to `name` by edit distance, for suggesting
+/// the intended name when there's a typo. Returns `None` if nothing is close.
+pub(crate) fn closest_match<'a, I>(name: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    // Allow one edit for every three characters, rounding up, so that short
+    // names don't match everything
+    let max_distance = name.chars().count().div_ceil(3);
+
+    candidates
+        .into_iter()
+        .filter(|c| *c != name)
+        .map(|c| (levenshtein(name, c), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, c)| c)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Milliseconds since the Unix epoch. `SystemTime` panics in WebAssembly
+/// outside of WASI, so the time comes from JavaScript there.
+pub(crate) fn now_millis() -> u128 {
+    #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+    {
+        js_sys::Date::now() as u128
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("Time went backwards")
+            .as_millis()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levenshtein_distance() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("abc", ""), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("theme", "thmee"), 2);
+    }
+
+    #[test]
+    fn closest_match_suggests_near_misses() {
+        let candidates = ["title", "version", "servers"];
+
+        assert_eq!(closest_match("titel", candidates), Some("title"));
+        assert_eq!(closest_match("server", candidates), Some("servers"));
+        assert_eq!(closest_match("description", candidates), None);
+    }
+}
@@ -68,6 +68,8 @@ pub fn vale_results_to_errors(project: &Project, vale_results: ValeResults) -> V
                 description: desc,
                 file: Some(PathBuf::from(file.clone())),
                 position: Some(position),
+                owner: None,
+                suggestions: vec![],
             });
         }
     }
@@ -88,6 +90,8 @@ pub fn vale_runtime_error_to_error(
         description: vale_runtime_error.text,
         file: Some(PathBuf::from(config_path)),
         position: None,
+        owner: None,
+        suggestions: vec![],
     }
 }
 
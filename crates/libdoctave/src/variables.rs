@@ -0,0 +1,355 @@
+//! The `variables` from the settings, available to expressions as `@vars`,
+//! e.g. `{@vars.latest_version}`. Locales can override them under
+//! `variable_translations`.
+//!
+//! Text outside of Markdown, like navigation labels, can use expressions
+//! that start with a variable, e.g. `Install {@vars.product}`.
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+use rust_decimal::Decimal;
+
+use crate::{
+    expressions::{self, Environment, Interpreter, Value},
+    settings::{Settings, Variable},
+};
+
+/// The name the variables are available under in expressions
+pub(crate) const GLOBAL: &str = "vars";
+
+/// The variables for `locale`, as the value of `@vars`.
+pub(crate) fn value(settings: &Settings, locale: Option<&str>) -> Value {
+    map_value(&settings.variables(locale))
+}
+
+fn map_value(map: &BTreeMap<String, Variable>) -> Value {
+    Value::Object(
+        map.iter()
+            .map(|(name, variable)| (name.clone(), variable_value(variable)))
+            .collect::<IndexMap<_, _>>(),
+    )
+}
+
+fn variable_value(variable: &Variable) -> Value {
+    match variable {
+        Variable::Bool(b) => Value::Bool(*b),
+        Variable::Integer(i) => Value::Number((*i).into()),
+        // Through the shortest representation, so that `2.4` isn't
+        // `2.4000000000000003`
+        Variable::Float(f) => Decimal::from_str(&f.to_string())
+            .map(Value::Number)
+            .unwrap_or_else(|_| Value::String(f.to_string())),
+        Variable::String(s) => Value::String(s.clone()),
+        Variable::Map(map) => map_value(map),
+    }
+}
+
+/// Text with its expressions replaced by their values, along with the
+/// lookups of variables and fields that don't exist, when those evaluate to
+/// `null` instead of failing.
+#[derive(Debug)]
+pub(crate) struct Interpolated {
+    pub text: String,
+    pub undefined: Vec<expressions::Error>,
+}
+
+/// Replaces the expressions in `text` that start with a variable, like
+/// `{@vars.product}`, with their values. Other braces, like in
+/// `GET /users/{id}`, are left as they are.
+pub(crate) fn interpolate(
+    text: &str,
+    globals: &[(String, Value)],
+    undefined_as_null: bool,
+) -> expressions::Result<Interpolated> {
+    let mut out = String::with_capacity(text.len());
+    let mut undefined = vec![];
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let expr = &rest[start + 1..start + len];
+
+        out.push_str(&rest[..start]);
+
+        if expr.trim_start().starts_with('@') {
+            let mut env = Environment::default();
+            for (name, value) in globals {
+                env.add_global(name, value.clone());
+            }
+
+            let mut interpreter = Interpreter::new(Some(env));
+            interpreter.undefined_as_null = undefined_as_null;
+
+            let value = interpreter.interpret(expressions::parse(expr)?)?;
+            out.push_str(&value.to_string());
+            undefined.append(&mut interpreter.undefined);
+        } else {
+            out.push_str(&rest[start..=start + len]);
+        }
+
+        rest = &rest[start + len + 1..];
+    }
+
+    out.push_str(rest);
+
+    Ok(Interpolated {
+        text: out,
+        undefined,
+    })
+}
+
+/// `text` with the variables it uses filled in for `locale`. Expressions
+/// that fail are left as they are, and variables that don't exist are
+/// empty, for text that is checked separately, like navigation labels.
+pub(crate) fn fill_in(text: String, settings: &Settings, locale: Option<&str>) -> String {
+    if !text.contains('{') {
+        return text;
+    }
+
+    let globals = [(GLOBAL.to_string(), value(settings, locale))];
+
+    interpolate(&text, &globals, true)
+        .map(|interpolated| interpolated.text)
+        .unwrap_or(text)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::{
+        Error, InputContent, InputFile, Project, RenderOptions, SearchIndex, UndefinedVariables,
+        NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME,
+    };
+
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings::parse(indoc! {r#"
+        ---
+        title: Variables
+        locales: [en, fr]
+        variables:
+          product: Acme
+          latest_version: 2.4
+          max_retries: 3
+          beta: false
+          support:
+            email: help@example.com
+            url: https://example.com/support
+        variable_translations:
+          fr:
+            support:
+              url: https://example.com/fr/support
+        "#})
+        .unwrap()
+    }
+
+    fn globals(locale: Option<&str>) -> Vec<(String, Value)> {
+        vec![(GLOBAL.to_string(), value(&settings(), locale))]
+    }
+
+    #[test]
+    fn converts_the_variables_to_values() {
+        assert_eq!(
+            value(&settings(), None).debug_string(),
+            r#"{ beta: false, latest_version: 2.4, max_retries: 3, product: "Acme", support: { email: "help@example.com", url: "https://example.com/support" } }"#
+        );
+    }
+
+    #[test]
+    fn applies_the_overrides_of_the_locale() {
+        let globals = globals(Some("fr"));
+
+        let text = interpolate("{@vars.support.url} {@vars.support.email}", &globals, false)
+            .unwrap()
+            .text;
+
+        assert_eq!(text, "https://example.com/fr/support help@example.com");
+    }
+
+    #[test]
+    fn interpolates_only_expressions_starting_with_a_variable() {
+        let globals = globals(None);
+
+        let text = interpolate(
+            r#"Install { @vars.product | append(" Cloud") } {@vars.latest_version} at GET /users/{id}"#,
+            &globals,
+            false,
+        )
+        .unwrap()
+        .text;
+
+        assert_eq!(text, "Install Acme Cloud 2.4 at GET /users/{id}");
+    }
+
+    #[test]
+    fn fails_on_unknown_variables_unless_they_evaluate_to_null() {
+        let globals = globals(None);
+
+        let error = interpolate("{@vars.produt}", &globals, false).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not find field `produt` on `object` `{ beta: false, latest_version: 2.4, max_retries: 3, product: \"Acme\", support: { email: \"help@example.com\", url: \"https://example.com/support\" } }`. Did you mean `product`?"
+        );
+
+        let interpolated = interpolate("Hi {@vars.produt}", &globals, true).unwrap();
+        assert_eq!(interpolated.text, "Hi ");
+        assert_eq!(interpolated.undefined.len(), 1);
+    }
+
+    fn project(navigation: &str) -> Project {
+        let files = [
+            (
+                SETTINGS_FILE_NAME,
+                indoc! {r#"
+                ---
+                title: Acme
+                locales: [en, fr]
+                variables:
+                  product: Acme Cloud
+                  latest_version: "2.4.1"
+                  support:
+                    url: https://example.com/support
+                variable_translations:
+                  fr:
+                    support:
+                      url: https://example.com/fr/support
+                "#},
+            ),
+            (NAVIGATION_FILE_NAME, navigation),
+            (
+                "README.md",
+                indoc! {r#"
+                # Install {@vars.product}
+
+                Version {@vars.latest_version}
+
+                <Component.Support />
+
+                <Topic.Upgrading />
+                "#},
+            ),
+            ("fr/README.md", "# Installer\n\n<Component.Support />"),
+            (
+                "upgrading.md",
+                "---\ntitle: Upgrading to {@vars.latest_version}\n---\n\nSteps",
+            ),
+            ("_components/support.md", "Contact {@vars.support.url}"),
+            ("_topics/upgrading.md", "Upgrade {@vars.product}"),
+        ];
+
+        Project::from_file_list(
+            files
+                .iter()
+                .map(|(path, content)| InputFile {
+                    path: PathBuf::from(path),
+                    content: InputContent::Text(content.to_string()),
+                })
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn text(project: &Project, uri_path: &str) -> String {
+        project
+            .get_page_by_uri_path(uri_path)
+            .unwrap()
+            .ast(None)
+            .unwrap()
+            .as_markdown()
+            .unwrap()
+            .inner_text()
+    }
+
+    #[test]
+    fn pages_components_and_topics_see_the_variables() {
+        let project = project("- heading: Guides\n");
+
+        let text = text(&project, "/");
+        assert!(text.contains("Install Acme Cloud"), "{}", text);
+        assert!(text.contains("Version 2.4.1"), "{}", text);
+        assert!(
+            text.contains("Contact https://example.com/support"),
+            "{}",
+            text
+        );
+        assert!(text.contains("Upgrade Acme Cloud"), "{}", text);
+    }
+
+    #[test]
+    fn translated_pages_see_the_variables_of_their_locale() {
+        let project = project("- heading: Guides\n");
+
+        let text = text(&project, "/fr");
+        assert!(
+            text.contains("Contact https://example.com/fr/support"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn navigation_labels_see_the_variables() {
+        let project = project(indoc! {r#"
+        - heading: "{@vars.product} guides"
+          items:
+            - href: /
+              label: "Install {@vars.product}"
+            - subheading: GET /users/{id}
+        "#});
+
+        project.verify(None, None).unwrap();
+
+        let navigation = project.navigation(None, "/").unwrap();
+        let section = &navigation.sections[0];
+        assert_eq!(section.heading.as_deref(), Some("Acme Cloud guides"));
+        assert_eq!(section.items[0].label(), "Install Acme Cloud");
+        assert_eq!(section.items[1].label(), "GET /users/{id}");
+    }
+
+    #[test]
+    fn the_search_index_has_the_values() {
+        let project = project("- heading: Guides\n");
+
+        let index = SearchIndex::new(&project).unwrap().to_json();
+        assert!(index.contains("Install Acme Cloud"), "{}", index);
+        assert!(index.contains("Upgrading to 2.4.1"), "{}", index);
+        assert!(!index.contains("@vars"), "{}", index);
+    }
+
+    #[test]
+    fn unknown_variables_in_labels_follow_undefined_variables() {
+        let project = project(indoc! {r#"
+        - heading: Guides
+          items:
+            - href: /
+              label: "Install {@vars.produt}"
+        "#});
+
+        let errors = project.verify(None, None).unwrap_err();
+        assert_eq!(errors.len(), 1, "{:#?}", errors);
+        assert_eq!(errors[0].code, Error::NAVIGATION_ERROR);
+        assert_eq!(errors[0].message, "Undefined variable in navigation.yaml");
+        assert!(
+            errors[0].description.contains("Did you mean `product`?"),
+            "{}",
+            errors[0].description
+        );
+        assert_eq!(errors[0].file, Some(PathBuf::from(NAVIGATION_FILE_NAME)));
+        assert_eq!(errors[0].position.as_ref().unwrap().start.row, 4);
+
+        let opts = RenderOptions {
+            undefined_variables: UndefinedVariables::Warn,
+            ..Default::default()
+        };
+        assert!(project.verify(Some(&opts), None).is_ok());
+
+        let warnings = project.render_warnings(Some(&opts));
+        assert_eq!(warnings.len(), 1, "{:#?}", warnings);
+        assert_eq!(warnings[0].message, "Undefined variable in navigation.yaml");
+    }
+}
@@ -0,0 +1,572 @@
+//! The "What's new" page: the `changelog` entries in the frontmatter of every
+//! page, newest first and grouped by month, each linking back to its page.
+//! Generated as a Markdown page when `whats_new` is set in the settings, so
+//! it's rendered, searched and listed like any other page.
+//!
+//! Entries of pages left out of the search, like drafts and pages with
+//! `search: false` or `noindex: true`, are left out, so that the page doesn't
+//! point readers to pages they aren't meant to find.
+use std::path::PathBuf;
+
+use crate::markdown::parser::to_final_link;
+use crate::markdown_page::MarkdownPage;
+use crate::navigation::{Item, Navigation, Section};
+use crate::page_kind::PageKind;
+use crate::render_context::RenderContext;
+use crate::{Error, Project, RenderOptions};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A `changelog` entry, with the page it's from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Update {
+    /// e.g. `2024-05-01`
+    pub date: String,
+    pub note: String,
+    pub tag: Option<String>,
+    /// Title of the page the entry is from
+    pub title: String,
+    pub uri_path: String,
+    pub fs_path: PathBuf,
+}
+
+/// The latest entries, newest first. Entries with invalid dates are
+/// reported by `verify`.
+pub(crate) fn entries(
+    project: &Project,
+    opts: Option<&RenderOptions>,
+    since: Option<&str>,
+    limit: usize,
+) -> Vec<Update> {
+    let include_noindex = project.settings.search().include_noindex;
+
+    let mut updates = project
+        .published_pages(opts)
+        .into_iter()
+        .filter(|p| !p.hidden_from_search().unwrap_or(false))
+        .filter(|p| include_noindex || !p.noindex())
+        .flat_map(|page| {
+            let PageKind::Markdown(markdown) = page.page else {
+                return vec![];
+            };
+
+            markdown
+                .changelog()
+                .into_iter()
+                .filter(|entry| crate::feed::is_valid_date(&entry.date))
+                .filter(|entry| since.map(|s| entry.date.as_str() >= s).unwrap_or(true))
+                .map(|entry| Update {
+                    date: entry.date,
+                    note: entry.note,
+                    tag: entry.tag,
+                    title: page.title().unwrap_or_else(|_| page.uri_path().to_owned()),
+                    uri_path: page.uri_path().to_owned(),
+                    fs_path: page.fs_path().to_owned(),
+                })
+                .collect()
+        })
+        .collect::<Vec<_>>();
+
+    updates.sort_by(|a, b| {
+        b.date
+            .cmp(&a.date)
+            .then_with(|| a.uri_path.cmp(&b.uri_path))
+    });
+    updates.truncate(limit);
+
+    updates
+}
+
+/// Adds the page to the project, unless a page already has its path. That
+/// page is reported by `verify`.
+pub(crate) fn add_page(project: &mut Project) {
+    let Some(settings) = project.settings.whats_new() else {
+        return;
+    };
+
+    let uri_path = settings.uri_path();
+
+    if project.get_page_by_uri_path(&uri_path).is_some() {
+        return;
+    }
+
+    let updates = entries(project, None, settings.since.as_deref(), settings.limit);
+    let content = markdown(&settings.title, &updates);
+
+    project.pages.push(PageKind::Markdown(MarkdownPage::new(
+        &crate::uri_to_fs_path(&uri_path),
+        content.into_bytes(),
+    )));
+}
+
+fn markdown(title: &str, updates: &[Update]) -> String {
+    let mut out = format!(
+        "---\ntitle: {}\norphan_ok: true\n---\n\n# {}\n",
+        serde_json::to_string(title).unwrap(),
+        escape(title)
+    );
+
+    if updates.is_empty() {
+        out.push_str("\nNo updates yet.\n");
+    }
+
+    let mut month = "";
+
+    for update in updates {
+        if &update.date[..7] != month {
+            month = &update.date[..7];
+            out.push_str(&format!("\n## {}\n\n", month_heading(month)));
+        }
+
+        out.push_str(&format!(
+            "- **{}** · [{}](/{}): {}",
+            update.date,
+            escape(&update.title),
+            update.fs_path.display(),
+            escape_note(&update.note)
+        ));
+
+        if let Some(tag) = &update.tag {
+            out.push_str(&format!(" `{}`", tag.replace('`', "")));
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+/// `2024-05` as `May 2024`
+fn month_heading(month: &str) -> String {
+    let (year, month) = month.split_at(4);
+    let name = month[1..]
+        .parse::<usize>()
+        .ok()
+        .and_then(|m| MONTHS.get(m.wrapping_sub(1)))
+        .unwrap_or(&"");
+
+    format!("{} {}", name, year)
+}
+
+/// Titles are shown as they are written
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '<' | '{' | '}' | '*' | '_' | '`') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+/// Notes can use inline Markdown, but not components or expressions, and
+/// have to fit in their list item.
+fn escape_note(note: &str) -> String {
+    let mut out = String::new();
+
+    for c in note.trim().chars() {
+        match c {
+            '\n' | '\r' => out.push(' '),
+            '<' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+/// Links to the page at the end of the navigation of the tab it's in, if
+/// `whats_new.navigation` is set and the navigation doesn't link to it yet.
+pub(crate) fn add_to_navigation(
+    navigation: &mut Navigation,
+    nav_path: &str,
+    project: &Project,
+    ctx: &RenderContext,
+) {
+    let Some(settings) = project.settings.whats_new() else {
+        return;
+    };
+
+    let uri_path = settings.uri_path();
+
+    if !settings.navigation
+        || navigation.has_link_to(&uri_path)
+        || project.get_page_by_uri_path(&uri_path).is_none()
+    {
+        return;
+    }
+
+    let page_nav_path = project
+        .get_subtab_path_by_uri_path(&uri_path)
+        .unwrap_or_else(|| String::from("/"));
+
+    if page_nav_path.trim_end_matches('/') != nav_path.trim_end_matches('/') {
+        return;
+    }
+
+    navigation.sections.push(Section {
+        heading: None,
+        collapsed: false,
+        collapsible: false,
+        expanded_for_current_page: false,
+        items: vec![Item::Link {
+            label: settings.title.clone(),
+            external_href: None,
+            href: Some(to_final_link(&uri_path, ctx)),
+            title: None,
+            collapsed: None,
+            collapsible: None,
+            http_method: None,
+            badge: None,
+            depth: 1,
+            expanded_for_current_page: false,
+            items: None,
+        }],
+    });
+}
+
+/// Checks the `changelog` entries of every page, and that the generated page
+/// doesn't take the place of a page in the project.
+pub(crate) fn verify(project: &Project) -> Vec<Error> {
+    let mut errors = vec![];
+
+    for page in project.pages() {
+        let PageKind::Markdown(markdown) = page.page else {
+            continue;
+        };
+
+        for entry in markdown.changelog() {
+            let (message, description) = if !crate::feed::is_valid_date(&entry.date) {
+                (
+                    "Invalid date in changelog",
+                    format!(
+                        "Expected a date like `2024-05-01` for a `changelog` entry.\nFound \"{}\".",
+                        entry.date
+                    ),
+                )
+            } else if entry.note.trim().is_empty() {
+                (
+                    "Empty note in changelog",
+                    format!(
+                        "The `changelog` entry for {} needs a `note` saying what changed.",
+                        entry.date
+                    ),
+                )
+            } else {
+                continue;
+            };
+
+            errors.push(Error {
+                code: Error::INVALID_FRONTMATTER,
+                message: message.to_owned(),
+                description,
+                file: Some(page.fs_path().to_path_buf()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+
+    let Some(settings) = project.settings.whats_new() else {
+        return errors;
+    };
+
+    // The generated page isn't one of the project's files
+    let uri_path = settings.uri_path();
+    if let Some(page) = project.get_page_by_uri_path(&uri_path) {
+        if project.text_source(page.fs_path()).is_some() {
+            errors.push(Error {
+                code: Error::DUPLICATE_URI_PATH,
+                message: String::from("Page conflicts with the \"What's new\" page"),
+                description: format!(
+                    "{} has the path {}, which is where the `whats_new` page is generated.\nSet `whats_new.path` to another path, or move the page.",
+                    page.fs_path().display(),
+                    uri_path
+                ),
+                file: Some(page.fs_path().to_path_buf()),
+                position: None,
+                owner: None,
+                suggestions: vec![],
+            });
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{InputContent, InputFile, NAVIGATION_FILE_NAME, SETTINGS_FILE_NAME};
+    use indoc::indoc;
+
+    fn project(settings: &str, navigation: &str, files: Vec<(&str, &str)>) -> Project {
+        let file = |path: &str, content: &str| InputFile {
+            path: PathBuf::from(path),
+            content: InputContent::Text(content.to_string()),
+        };
+
+        let mut list = vec![
+            file("README.md", "# Home"),
+            file(NAVIGATION_FILE_NAME, navigation),
+            file(
+                SETTINGS_FILE_NAME,
+                &format!(
+                    "---\ntitle: Something\nbase_url: https://docs.example.com\n{}",
+                    settings
+                ),
+            ),
+        ];
+        list.extend(files.into_iter().map(|(path, content)| file(path, content)));
+
+        Project::from_file_list(list).unwrap()
+    }
+
+    fn pages() -> Vec<(&'static str, &'static str)> {
+        vec![
+            (
+                "install.md",
+                indoc! {"
+                ---
+                title: Installing [beta]
+                changelog:
+                  - date: 2024-05-03
+                    note: Added the `--quiet` flag
+                    tag: cli
+                  - date: 2024-04-20
+                    note: Covers Windows
+                ---
+                # Install
+                "},
+            ),
+            (
+                "api.md",
+                indoc! {"
+                ---
+                changelog:
+                  - date: 2024-05-10
+                    note: New <rate limits>
+                ---
+                # API
+                "},
+            ),
+            (
+                "draft.md",
+                indoc! {"
+                ---
+                draft: true
+                changelog:
+                  - date: 2024-06-01
+                    note: Not yet
+                ---
+                # Draft
+                "},
+            ),
+            (
+                "hidden.md",
+                indoc! {"
+                ---
+                search: false
+                changelog:
+                  - date: 2024-06-02
+                    note: Hidden
+                ---
+                # Hidden
+                "},
+            ),
+        ]
+    }
+
+    #[test]
+    fn lists_changelog_entries_by_month() {
+        let project = project("whats_new: {}", "---", pages());
+
+        let page = project.get_page_by_uri_path("/whats-new").unwrap();
+        let PageKind::Markdown(markdown) = page.page else {
+            panic!("Not a Markdown page");
+        };
+
+        assert_eq!(
+            markdown.content,
+            indoc! {r#"
+            ---
+            title: "Documentation updates"
+            orphan_ok: true
+            ---
+
+            # Documentation updates
+
+            ## May 2024
+
+            - **2024-05-10** · [API](/api.md): New \<rate limits>
+            - **2024-05-03** · [Installing \[beta\]](/install.md): Added the `--quiet` flag `cli`
+
+            ## April 2024
+
+            - **2024-04-20** · [Installing \[beta\]](/install.md): Covers Windows
+            "#}
+        );
+        assert_eq!(page.title().unwrap(), "Documentation updates");
+        assert!(project.verify(None, None).is_ok());
+    }
+
+    #[test]
+    fn respects_since_and_limit() {
+        let project = project(
+            "whats_new:\n  since: 2024-05-01\n  limit: 1",
+            "---",
+            pages(),
+        );
+
+        let updates = entries(&project, None, Some("2024-05-01"), 1);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].uri_path, "/api");
+
+        let updates = entries(&project, None, Some("2024-05-01"), 10);
+        assert_eq!(
+            updates.iter().map(|u| u.date.as_str()).collect::<Vec<_>>(),
+            vec!["2024-05-10", "2024-05-03"]
+        );
+    }
+
+    #[test]
+    fn is_only_generated_when_configured() {
+        let project = project("", "---", pages());
+
+        assert!(project.get_page_by_uri_path("/whats-new").is_none());
+    }
+
+    #[test]
+    fn can_be_added_to_the_navigation() {
+        let navigation = indoc! {"
+        - heading: Guides
+          items:
+            - href: /install.md
+              label: Install
+        "};
+
+        let project = project(
+            "whats_new:\n  path: /updates\n  title: Updates\n  navigation: true",
+            navigation,
+            pages(),
+        );
+
+        let nav = project.navigation(None, "/").unwrap();
+        let last = nav.sections.last().unwrap();
+        assert_eq!(last.heading, None);
+        assert_eq!(last.items[0].label(), "Updates");
+        assert_eq!(last.items[0].href(), Some("/updates"));
+
+        let listed = project(
+            "whats_new:\n  navigation: true",
+            "- heading: News\n  items:\n    - href: /whats-new\n      label: News",
+            pages(),
+        );
+        assert_eq!(listed.navigation(None, "/").unwrap().sections.len(), 1);
+    }
+
+    #[test]
+    fn feeds_can_list_changelog_entries() {
+        let project = project(
+            indoc! {"
+            whats_new: {}
+            feeds:
+              - path: /whats-new
+                title: Updates
+                source: changelog
+            "},
+            "---",
+            vec![(
+                "install.md",
+                indoc! {"
+                ---
+                changelog:
+                  - date: 2024-05-03
+                    note: First
+                  - date: 2024-05-03
+                    note: Second
+                ---
+                # Install
+                "},
+            )],
+        );
+
+        let feed = project.feed("/whats-new", None).unwrap();
+
+        assert_eq!(
+            feed.items
+                .iter()
+                .map(|i| (i.id.as_str(), i.description.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "https://docs.example.com/install#changelog-2024-05-03",
+                    "First"
+                ),
+                (
+                    "https://docs.example.com/install#changelog-2024-05-03-2",
+                    "Second"
+                ),
+            ]
+        );
+        assert_eq!(feed.items[0].url, "https://docs.example.com/install");
+        assert!(project.verify(None, None).is_ok());
+    }
+
+    #[test]
+    fn verifies_changelog_entries() {
+        let project = project(
+            "whats_new: {}",
+            "---",
+            vec![
+                (
+                    "install.md",
+                    "---\nchangelog:\n  - date: May 3rd\n    note: Oops\n  - date: 2024-05-03\n    note: \" \"\n---\n# Install",
+                ),
+                ("whats-new.md", "# Mine"),
+            ],
+        );
+
+        let errors = project.verify(None, None).unwrap_err();
+        let messages = errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>();
+
+        assert!(
+            messages.contains(&"Invalid date in changelog"),
+            "{:#?}",
+            errors
+        );
+        assert!(
+            messages.contains(&"Empty note in changelog"),
+            "{:#?}",
+            errors
+        );
+        assert!(
+            messages.contains(&"Page conflicts with the \"What's new\" page"),
+            "{:#?}",
+            errors
+        );
+    }
+}
@@ -0,0 +1,387 @@
+//! Deserializing the YAML files of a project, with support for anchors,
+//! aliases and `<<` merge keys.
+//!
+//! serde_yaml follows aliases, but reports errors in aliased content where
+//! the anchor is, and doesn't apply merge keys. Errors are pointed to where
+//! the alias or merge key is used instead, since that's where the content
+//! turned out to be invalid.
+use std::fmt;
+
+use located_yaml::{Marker, Yaml, YamlElt, YamlLoader};
+use serde::de::DeserializeOwned;
+use serde_path_to_error::{Path, Segment};
+use serde_yaml::Value;
+
+use crate::{Point, Position};
+
+const MERGE_KEY: &str = "<<";
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct YamlError {
+    pub message: String,
+    pub position: Option<Position>,
+}
+
+impl fmt::Display for YamlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<serde_yaml::Error> for YamlError {
+    fn from(error: serde_yaml::Error) -> Self {
+        YamlError {
+            message: error.to_string(),
+            position: error.location().map(|location| {
+                let point = Point {
+                    row: location.line(),
+                    col: location.column(),
+                    byte_offset: location.index(),
+                };
+
+                Position {
+                    start: point.clone(),
+                    end: point,
+                }
+            }),
+        }
+    }
+}
+
+pub(crate) fn from_str<T: DeserializeOwned>(input: &str) -> Result<T, YamlError> {
+    let mut value = serde_yaml::from_str::<Value>(input)?;
+
+    if !has_merge_keys(&value) {
+        return serde_yaml::from_str::<T>(input)
+            .map_err(|error| alias_error::<T>(input, value).unwrap_or_else(|| error.into()));
+    }
+
+    value.apply_merge()?;
+
+    serde_path_to_error::deserialize::<_, T>(value).map_err(|error| {
+        let location = locate(input, error.path().iter());
+        describe(error.path(), error.inner(), location.map(|l| l.position))
+    })
+}
+
+/// Parses YAML into a value with the merge keys applied, for when it's
+/// changed before being deserialized.
+pub(crate) fn value_from_str(input: &str) -> Result<Value, YamlError> {
+    let mut value = serde_yaml::from_str::<Value>(input)?;
+    value.apply_merge()?;
+
+    Ok(value)
+}
+
+/// The error for content that's only invalid where it's aliased. Deserializing
+/// the parsed value gives the path of the invalid value, which leads to the
+/// alias.
+fn alias_error<T: DeserializeOwned>(input: &str, value: Value) -> Option<YamlError> {
+    let error = serde_path_to_error::deserialize::<_, T>(value).err()?;
+    let location = locate(input, error.path().iter())?;
+
+    location
+        .via_alias
+        .then(|| describe(error.path(), error.inner(), Some(location.position)))
+}
+
+/// Formats the error like serde_yaml does, e.g. `theme.color: invalid type
+/// at line 3 column 5`.
+fn describe(path: &Path, error: &serde_yaml::Error, position: Option<Position>) -> YamlError {
+    let path = path.to_string();
+    let mut message = if path == "." {
+        error.to_string()
+    } else if path.starts_with('[') {
+        format!(".{}: {}", path, error)
+    } else {
+        format!("{}: {}", path, error)
+    };
+
+    if let Some(position) = &position {
+        message.push_str(&format!(
+            " at line {} column {}",
+            position.start.row, position.start.col
+        ));
+    }
+
+    YamlError { message, position }
+}
+
+struct Location {
+    position: Position,
+    /// Whether an alias or merge key was followed to the value
+    via_alias: bool,
+}
+
+/// Where the value at `path` is written in `input`, for values that
+/// deserialized fine but turned out to be invalid, e.g. `integrations.scripts[0].src`.
+pub(crate) fn position_of(input: &str, path: &[Segment]) -> Option<Position> {
+    locate(input, path.iter()).map(|location| location.position)
+}
+
+/// Where the value at `path` is written in `input`. Aliased values are
+/// copies of the anchored ones, and merged keys aren't in the mapping they
+/// are merged into, so those point to the alias or merge key instead.
+fn locate<'a>(input: &str, path: impl Iterator<Item = &'a Segment>) -> Option<Location> {
+    let root = YamlLoader::load_from_str(input)
+        .ok()
+        .and_then(|mut loader| loader.docs.drain(..).next())?;
+
+    let mut current = &root;
+    let mut marker = root.marker;
+
+    for segment in path {
+        // Anchors come before their aliases, so a node from earlier in the
+        // file than its parent is a copy from an alias
+        let (node, node_marker) = match (segment, &current.yaml) {
+            (Segment::Seq { index }, YamlElt::Array(items)) => {
+                let Some(item) = items.get(*index) else {
+                    break;
+                };
+
+                if item.marker.index < marker.index {
+                    // Search after the previous item, in case it's an alias too
+                    let from = items[..*index]
+                        .iter()
+                        .map(|i| i.marker.index)
+                        .filter(|i| *i > marker.index)
+                        .max()
+                        .unwrap_or(marker.index);
+
+                    return Some(alias_location(input, item.marker, from, marker));
+                }
+
+                (item, item.marker)
+            }
+            (Segment::Map { key }, YamlElt::Hash(hash)) => match find_key(hash.iter(), key) {
+                Some((key, value)) => {
+                    if value.marker.index < key.marker.index {
+                        return Some(alias_location(
+                            input,
+                            value.marker,
+                            key.marker.index,
+                            key.marker,
+                        ));
+                    }
+
+                    (value, key.marker)
+                }
+                None => match find_key(hash.iter(), MERGE_KEY) {
+                    Some((merge, _)) => {
+                        return Some(Location {
+                            position: to_position(merge.marker),
+                            via_alias: true,
+                        })
+                    }
+                    None => break,
+                },
+            },
+            (Segment::Enum { .. }, _) => continue,
+            _ => break,
+        };
+
+        current = node;
+        marker = node_marker;
+    }
+
+    Some(Location {
+        position: to_position(marker),
+        via_alias: false,
+    })
+}
+
+/// Where the alias of the node anchored at `anchored` is used, looking from
+/// `from` onwards. Falls back to `fallback` when the alias can't be found.
+fn alias_location(input: &str, anchored: Marker, from: usize, fallback: Marker) -> Location {
+    let position = find_alias(
+        input,
+        byte_index(input, anchored.index),
+        byte_index(input, from),
+    )
+    .map(|index| Position::from_byte_range(input, index, index))
+    .unwrap_or_else(|| to_position(fallback));
+
+    Location {
+        position,
+        via_alias: true,
+    }
+}
+
+/// Finds the `*name` alias of the `&name` anchor right before `anchored`.
+fn find_alias(input: &str, anchored: usize, from: usize) -> Option<usize> {
+    let before = input.get(..anchored)?.trim_end();
+    let name = &before[before.rfind('&')? + 1..];
+
+    if name.is_empty() || name.contains(|c: char| c.is_whitespace()) {
+        return None;
+    }
+
+    let alias = format!("*{}", name);
+
+    input
+        .get(from..)?
+        .match_indices(&alias)
+        .map(|(index, _)| from + index)
+        .find(|index| {
+            // Not just the start of a longer name
+            let rest = &input[index + alias.len()..];
+            rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace() || ",]}".contains(c))
+        })
+}
+
+fn find_key<'a>(
+    mut hash: impl Iterator<Item = (&'a Yaml, &'a Yaml)>,
+    key: &str,
+) -> Option<(&'a Yaml, &'a Yaml)> {
+    // Keys written as numbers, like status codes, match their text
+    hash.find(|(k, _)| match &k.yaml {
+        YamlElt::String(k) => k == key,
+        YamlElt::Integer(k) => k.to_string() == key,
+        _ => false,
+    })
+}
+
+/// Markers count characters rather than bytes
+fn byte_index(input: &str, char_index: usize) -> usize {
+    input
+        .char_indices()
+        .nth(char_index)
+        .map(|(index, _)| index)
+        .unwrap_or(input.len())
+}
+
+fn to_position(marker: Marker) -> Position {
+    let point = Point {
+        row: marker.line,
+        col: marker.col + 1,
+        byte_offset: marker.index,
+    };
+
+    Position {
+        start: point.clone(),
+        end: point,
+    }
+}
+
+fn has_merge_keys(value: &Value) -> bool {
+    match value {
+        Value::Mapping(mapping) => mapping
+            .iter()
+            .any(|(key, value)| key.as_str() == Some(MERGE_KEY) || has_merge_keys(value)),
+        Value::Sequence(items) => items.iter().any(has_merge_keys),
+        Value::Tagged(tagged) => has_merge_keys(&tagged.value),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Spec {
+        file: String,
+        prefix: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct Specs {
+        #[serde(default)]
+        defaults: Option<Value>,
+        specs: Vec<Spec>,
+    }
+
+    #[test]
+    fn applies_merge_keys() {
+        let input = indoc! {r#"
+        defaults: &defaults
+          prefix: /api
+        specs:
+          - <<: *defaults
+            file: a.yaml
+          - <<: *defaults
+            file: b.yaml
+            prefix: /v2
+        "#};
+
+        let specs = from_str::<Specs>(input).unwrap();
+
+        assert_eq!(
+            specs.specs,
+            vec![
+                Spec {
+                    file: "a.yaml".to_owned(),
+                    prefix: "/api".to_owned(),
+                },
+                Spec {
+                    file: "b.yaml".to_owned(),
+                    prefix: "/v2".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn points_errors_in_merged_keys_to_the_merge() {
+        let input = indoc! {r#"
+        defaults: &defaults
+          prefix: [/api]
+        specs:
+          - file: a.yaml
+            prefix: /api
+          - <<: *defaults
+            file: b.yaml
+        "#};
+
+        let error = from_str::<Specs>(input).unwrap_err();
+
+        assert_eq!(error.position.as_ref().unwrap().start.row, 6);
+        assert!(
+            error.message.starts_with("specs[1].prefix: invalid type"),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn points_errors_in_aliased_content_to_the_alias() {
+        let input = indoc! {r#"
+        defaults: &spec
+          file: a.yaml
+          prefx: /api
+        specs:
+          - file: b.yaml
+            prefix: /api
+          - *spec
+        "#};
+
+        let error = from_str::<Specs>(input).unwrap_err();
+
+        let position = error.position.unwrap();
+        assert_eq!((position.start.row, position.start.col), (7, 5));
+        assert!(
+            error.message.contains("unknown field `prefx`"),
+            "{}",
+            error.message
+        );
+    }
+
+    #[test]
+    fn keeps_serde_yaml_errors_without_aliases() {
+        let input = "specs:\n  - file: a.yaml\n    prefix: [/api]\n";
+
+        let error = from_str::<Specs>(input).unwrap_err();
+
+        assert_eq!(
+            error.message,
+            serde_yaml::from_str::<Specs>(input)
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(error.position.as_ref().unwrap().start.row, 3);
+    }
+}
@@ -166,7 +166,7 @@ impl ProjectBuilder {
         if let Some(pos) = self.inputs.iter_mut().position(|i| i.path == path) {
             let file = self.inputs.remove(pos);
             let new_content = match file.content {
-                InputContent::Binary(_) => unimplemented!(),
+                InputContent::Binary { .. } => unimplemented!(),
                 InputContent::Text(mut content) => {
                     content.push_str(text);
                     content
@@ -40,9 +40,11 @@ pub use openapi30::schemas::{
     tag::Tag,
     webhook::Webhook,
 };
+pub use openapi30::validation::{validate_example, validate_examples, ExampleError};
 use serde::Deserialize;
 use serde::Serialize;
 use std::mem;
+use std::sync::Arc;
 use std::{
     collections::hash_map::DefaultHasher,
     fmt::Display,
@@ -191,7 +193,7 @@ impl From<Schema> for Value {
         }
 
         if let Some(not) = s.not {
-            map.insert("not".into(), (*not).into());
+            map.insert("not".into(), Arc::unwrap_or_clone(not).into());
         }
 
         let kind_val: Value = s.kind.into();
@@ -238,7 +240,7 @@ impl From<ArraySchema> for Value {
         object.insert("type".into(), Value::String("array".into()));
 
         if let Some(items) = a.items {
-            object.insert("items".into(), (*items).into());
+            object.insert("items".into(), Arc::unwrap_or_clone(items).into());
         }
 
         if let Some(max_items) = a.max_items {
@@ -419,7 +421,7 @@ impl From<ObjectSchema> for Value {
                 "properties".into(),
                 o.properties
                     .into_iter()
-                    .map(|(k, v)| (k, (*v).into()))
+                    .map(|(k, v)| (k, Arc::unwrap_or_clone(v).into()))
                     .collect::<Map<_, _>>()
                     .into(),
             );
@@ -428,7 +430,7 @@ impl From<ObjectSchema> for Value {
         if let Some(additional_properties) = o.additional_properties {
             object.insert(
                 "additionalProperties".into(),
-                (*additional_properties).into(),
+                Arc::unwrap_or_clone(additional_properties).into(),
             );
         }
 
@@ -686,8 +688,8 @@ impl From<Operation> for Value {
             object.insert("parameters".into(), parameters.into());
         }
 
-        if !o.security.is_empty() {
-            let security = o.security.into_iter().map(Value::from).collect::<Vec<_>>();
+        if let Some(security) = o.security {
+            let security = security.into_iter().map(Value::from).collect::<Vec<_>>();
             object.insert("security".into(), security.into());
         }
 
@@ -1190,6 +1192,8 @@ impl From<Tag> for Value {
             object.insert("externalDocs".into(), external_docs.into());
         }
 
+        object.extend(t.extensions);
+
         Value::Object(object)
     }
 }
@@ -1,3 +1,5 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::fmt::Display;
 
 use crate::{Map, Set};
@@ -34,6 +36,7 @@ pub enum Error {
     Contact(#[from] contact::Error),
     RequestBody(#[from] request_body::Error),
     Callback(#[from] callback::Error),
+    Limit(#[from] LimitError),
 }
 
 impl Display for Error {
@@ -61,18 +64,124 @@ impl Display for Error {
             Error::Contact(e) => write!(f, "{}", e),
             Error::RequestBody(e) => write!(f, "{}", e),
             Error::Callback(e) => write!(f, "{}", e),
+            Error::Limit(e) => write!(f, "{}", e),
         }
     }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+pub const DEFAULT_MAX_OPERATIONS: usize = 10_000;
+pub const DEFAULT_MAX_SCHEMA_NODES: usize = 1_000_000;
+pub const DEFAULT_MAX_SCHEMA_EXPANSION: usize = 100_000;
+
+/// Guardrails for specs that would take too long, or too much memory, to
+/// parse. Going over any of them fails the parse with a `LimitError`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Operations across all paths and webhooks.
+    pub max_operations: usize,
+    /// Schemas parsed for the whole spec. A component is only parsed once
+    /// however many times it's referenced, so references count as one each.
+    pub max_schema_nodes: usize,
+    /// Schemas in a single component once its references are expanded.
+    pub max_schema_expansion: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_operations: DEFAULT_MAX_OPERATIONS,
+            max_schema_nodes: DEFAULT_MAX_SCHEMA_NODES,
+            max_schema_expansion: DEFAULT_MAX_SCHEMA_EXPANSION,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum LimitError {
+    #[error(r#"The spec has {count} operations, which is more than the limit of {limit}. Split it into several specs, or raise `max_operations` for the spec."#)]
+    TooManyOperations { count: usize, limit: usize },
+    #[error(r#"The spec has more than {limit} schemas once its references are resolved. Move schemas that are repeated inline into `components` and use `$ref` instead, or raise `max_schema_nodes` for the spec."#)]
+    TooManySchemaNodes { limit: usize },
+    #[error(r#"The schema '{reference}' has more than {limit} schemas nested in it once its references are resolved. Split it into smaller schemas, or raise `max_schema_expansion` for the spec."#)]
+    SchemaTooLarge { reference: String, limit: usize },
+}
+
+impl LimitError {
+    /// The limit in `Limits` that was exceeded.
+    pub fn limit_name(&self) -> &'static str {
+        match self {
+            LimitError::TooManyOperations { .. } => "max_operations",
+            LimitError::TooManySchemaNodes { .. } => "max_schema_nodes",
+            LimitError::SchemaTooLarge { .. } => "max_schema_expansion",
+        }
+    }
+}
+
+/// Identifies a resolved `$ref`. Which references are already being
+/// resolved decides where recursive schemas are cut off, so they are part
+/// of the key. Overrides are never applied to references, so they aren't.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ResolvedKey {
+    ref_path: String,
+    visited_refs: Vec<String>,
+}
+
+impl ResolvedKey {
+    pub(crate) fn new(ref_path: &str, visited_refs: &Set<String>) -> Self {
+        let mut visited_refs = visited_refs.iter().cloned().collect::<Vec<_>>();
+        visited_refs.sort();
+
+        ResolvedKey {
+            ref_path: ref_path.into(),
+            visited_refs,
+        }
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct ParserContext {
     pub ref_cache: ReferenceCache,
+    pub limits: Limits,
+    /// Schemas parsed so far, see `Limits::max_schema_nodes`.
+    schema_nodes: Cell<usize>,
+    /// Schemas that `$ref`s resolve to. Schemas share their nested schemas
+    /// through `Arc`s, so handing out clones of these is cheap.
+    resolved_schemas: RefCell<HashMap<ResolvedKey, schema::Schema>>,
 }
 
 impl ParserContext {
+    pub fn with_limits(limits: Limits) -> Self {
+        ParserContext {
+            limits,
+            ..Default::default()
+        }
+    }
+
+    /// Counts a parsed schema against `Limits::max_schema_nodes`.
+    pub(crate) fn count_schema_node(&self) -> Result<()> {
+        let count = self.schema_nodes.get() + 1;
+        self.schema_nodes.set(count);
+
+        if count > self.limits.max_schema_nodes {
+            return Err(LimitError::TooManySchemaNodes {
+                limit: self.limits.max_schema_nodes,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn resolved_schema(&self, key: &ResolvedKey) -> Option<schema::Schema> {
+        self.resolved_schemas.borrow().get(key).cloned()
+    }
+
+    pub(crate) fn cache_resolved_schema(&self, key: ResolvedKey, schema: schema::Schema) {
+        self.resolved_schemas.borrow_mut().insert(key, schema);
+    }
+
     pub fn get(&self, ref_path: &str, visited_refs: &mut Set<String>) -> Option<Value> {
         match &self.ref_cache {
             ReferenceCache::Components(_components) => todo!(),
@@ -130,15 +239,23 @@ impl ReferenceCache {
 }
 
 pub fn parse_json(input: &str) -> Result<openapi::OpenAPI> {
+    parse_json_with_limits(input, Limits::default())
+}
+
+pub fn parse_yaml(input: &str) -> Result<openapi::OpenAPI> {
+    parse_yaml_with_limits(input, Limits::default())
+}
+
+pub fn parse_json_with_limits(input: &str, limits: Limits) -> Result<openapi::OpenAPI> {
     match serde_json_lenient::from_str::<Value>(input) {
-        Ok(val) => openapi::OpenAPI::try_parse(val),
+        Ok(val) => openapi::OpenAPI::try_parse_with_limits(val, limits),
         Err(_e) => Err(Error::OpenAPI(openapi::Error::InvalidOpenAPI)),
     }
 }
 
-pub fn parse_yaml(input: &str) -> Result<openapi::OpenAPI> {
+pub fn parse_yaml_with_limits(input: &str, limits: Limits) -> Result<openapi::OpenAPI> {
     match serde_yaml::from_str::<Value>(input) {
-        Ok(val) => openapi::OpenAPI::try_parse(val),
+        Ok(val) => openapi::OpenAPI::try_parse_with_limits(val, limits),
         Err(_e) => Err(Error::OpenAPI(openapi::Error::InvalidOpenAPI)),
     }
 }
@@ -5,7 +5,7 @@ use crate::Value;
 use crate::{Map, Operation};
 use thiserror::Error;
 
-use crate::openapi30::parser;
+use crate::openapi30::parser::{self, LimitError, Limits};
 
 use super::{
     components::Components, external_documentation::ExternalDocumentation, info::Info,
@@ -81,8 +81,21 @@ impl OpenAPI {
             .collect()
     }
 
-    pub fn try_parse(mut value: Value) -> parser::Result<Self> {
-        let mut ctx = ParserContext::default();
+    pub fn try_parse(value: Value) -> parser::Result<Self> {
+        Self::try_parse_with_limits(value, Limits::default())
+    }
+
+    pub fn try_parse_with_limits(mut value: Value, limits: Limits) -> parser::Result<Self> {
+        let operation_count = count_operations(&value);
+        if operation_count > limits.max_operations {
+            return Err(LimitError::TooManyOperations {
+                count: operation_count,
+                limit: limits.max_operations,
+            }
+            .into());
+        }
+
+        let mut ctx = ParserContext::with_limits(limits);
         let mut visited_refs: Set<String> = Set::new();
 
         let openapi = value
@@ -175,6 +188,39 @@ impl OpenAPI {
     }
 }
 
+/// Counts the operations before anything is parsed, so that specs with too
+/// many of them can be turned away early.
+fn count_operations(value: &Value) -> usize {
+    const METHODS: [&str; 8] = [
+        "get", "put", "post", "delete", "options", "head", "patch", "trace",
+    ];
+
+    let paths = value
+        .get("paths")
+        .and_then(Value::as_object)
+        .map(|paths| {
+            paths
+                .values()
+                .map(|item| METHODS.iter().filter(|m| item.get(m).is_some()).count())
+                .sum::<usize>()
+        })
+        .unwrap_or_default();
+
+    let webhooks = value
+        .get("x-webhooks")
+        .and_then(Value::as_object)
+        .map(|webhooks| {
+            webhooks
+                .values()
+                .filter_map(Value::as_object)
+                .map(|operations| operations.len())
+                .sum::<usize>()
+        })
+        .unwrap_or_default();
+
+    paths + webhooks
+}
+
 #[cfg(test)]
 mod test {
     use crate::json;
@@ -519,4 +565,137 @@ mod test {
             }"# }
         );
     }
+
+    mod limits {
+        use std::sync::Arc;
+
+        use crate::openapi30::parser::{self, parse_json_with_limits, LimitError, Limits};
+        use crate::{Schema, SchemaKind};
+
+        /// A spec like the ones generated for large APIs, where every
+        /// operation returns the same component.
+        fn generated_spec(operations: usize) -> std::string::String {
+            let paths = (0..operations)
+                .map(|i| {
+                    format!(
+                        r##""/things/{i}": {{ "get": {{ "responses": {{ "200": {{ "description": "OK", "content": {{ "application/json": {{ "schema": {{ "$ref": "#/components/schemas/Thing" }} }} }} }} }} }} }}"##
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+
+            let fields = (0..50)
+                .map(|i| format!(r#""field{i}": {{ "type": "string" }}"#))
+                .collect::<Vec<_>>()
+                .join(",\n");
+
+            format!(
+                r##"{{
+                  "openapi": "3.0.0",
+                  "info": {{ "title": "Generated", "version": "1.0.0" }},
+                  "paths": {{ {paths} }},
+                  "components": {{
+                    "schemas": {{
+                      "Owner": {{ "type": "object", "properties": {{ {fields} }} }},
+                      "Thing": {{
+                        "type": "object",
+                        "properties": {{
+                          "owner": {{ "$ref": "#/components/schemas/Owner" }},
+                          {fields}
+                        }}
+                      }}
+                    }}
+                  }}
+                }}"##
+            )
+        }
+
+        fn response_schema(spec: &crate::OpenAPI, index: usize) -> &Schema {
+            spec.operations()[index]
+                .responses
+                .as_ref()
+                .and_then(|r| r.0.get("200"))
+                .and_then(|r| r.content.get("application/json"))
+                .and_then(|m| m.schema.as_ref())
+                .unwrap()
+        }
+
+        #[test]
+        fn shares_referenced_schemas_between_operations() {
+            let operations = 12_000;
+            let spec = generated_spec(operations);
+
+            // Expanding `Thing` into every operation would take over 600k
+            // schemas, so this only passes if they're shared.
+            let limits = Limits {
+                max_schema_nodes: 20_000,
+                ..Default::default()
+            };
+
+            let start = std::time::Instant::now();
+            let parsed = parse_json_with_limits(&spec, limits).unwrap();
+            eprintln!("Parsed {} operations in {:?}", operations, start.elapsed());
+
+            assert_eq!(parsed.operations().len(), operations);
+
+            let owner = |schema: &Schema| match &schema.kind {
+                SchemaKind::Object(o) => o.properties["owner"].clone(),
+                other => panic!("Unexpected schema: {:?}", other),
+            };
+
+            let first = response_schema(&parsed, 0);
+            let last = response_schema(&parsed, operations - 1);
+
+            assert_eq!(first, last);
+            assert!(Arc::ptr_eq(&owner(first), &owner(last)));
+        }
+
+        #[test]
+        fn fails_specs_with_too_many_operations() {
+            let limits = Limits {
+                max_operations: 10,
+                ..Default::default()
+            };
+
+            let error = parse_json_with_limits(&generated_spec(11), limits).unwrap_err();
+
+            assert!(matches!(
+                error,
+                parser::Error::Limit(LimitError::TooManyOperations {
+                    count: 11,
+                    limit: 10
+                })
+            ));
+        }
+
+        #[test]
+        fn fails_specs_with_too_many_schemas() {
+            let limits = Limits {
+                max_schema_nodes: 100,
+                ..Default::default()
+            };
+
+            let error = parse_json_with_limits(&generated_spec(200), limits).unwrap_err();
+
+            assert!(matches!(
+                error,
+                parser::Error::Limit(LimitError::TooManySchemaNodes { limit: 100 })
+            ));
+        }
+
+        #[test]
+        fn fails_schemas_that_expand_past_the_limit() {
+            let limits = Limits {
+                max_schema_expansion: 60,
+                ..Default::default()
+            };
+
+            let error = parse_json_with_limits(&generated_spec(1), limits).unwrap_err();
+
+            assert_eq!(
+                error.to_string(),
+                "The schema '#/components/schemas/Thing' has more than 60 schemas nested in it once its references are resolved. Split it into smaller schemas, or raise `max_schema_expansion` for the spec."
+            );
+        }
+    }
 }
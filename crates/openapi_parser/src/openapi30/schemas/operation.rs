@@ -24,7 +24,9 @@ pub struct Operation {
     pub operation_id: Option<String>,
     pub responses: Option<Responses>,
     pub deprecated: Option<bool>,
-    pub security: Vec<SecurityRequirement>,
+    /// `None` if the operation doesn't override the security requirements
+    /// of the spec. An empty list means no security is required.
+    pub security: Option<Vec<SecurityRequirement>>,
     pub servers: Vec<Server>,
     pub extensions: Map<String, Value>,
 
@@ -119,8 +121,7 @@ impl Operation {
                     .map(SecurityRequirement::try_parse)
                     .collect::<parser::Result<Vec<_>>>()
             })
-            .transpose()?
-            .unwrap_or_default();
+            .transpose()?;
 
         let extensions = if let Some(obj) = value.take_object() {
             obj.into_iter()
@@ -629,6 +630,36 @@ mod test {
         );
     }
 
+    #[test]
+    fn keeps_empty_security_apart_from_missing_security() {
+        let parse = |value| {
+            Operation::try_parse(
+                value,
+                &ParserContext::default(),
+                &mut Set::new(),
+                "get".into(),
+                "/path".into(),
+                vec![],
+                &[],
+            )
+            .unwrap()
+        };
+
+        assert!(parse(json!({})).security.is_none());
+
+        let operation = parse(json!({ "security": [] }));
+        assert_eq!(operation.security.as_ref().map(Vec::len), Some(0));
+
+        assert_str_eq!(
+            operation.pretty_print().as_str(),
+            indoc! {r#"
+            {
+              "security": [],
+              "method": "get"
+            }"#}
+        );
+    }
+
     #[test]
     fn parses_extensions() {
         let value = json!({
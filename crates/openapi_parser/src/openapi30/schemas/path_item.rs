@@ -20,6 +20,9 @@ pub struct PathItem {
     pub patch: Option<Operation>,
     pub trace: Option<Operation>,
     pub servers: Vec<Server>,
+    /// Parameters shared by all operations. Each operation's `parameters`
+    /// starts with these, followed by the operation's own.
+    pub parameters: Vec<Parameter>,
 }
 
 impl PathItem {
@@ -188,12 +191,13 @@ impl PathItem {
                         visited_refs,
                         "trace".into(),
                         route_pattern.clone(),
-                        path_params,
+                        path_params.clone(),
                         &servers,
                     )
                 })
                 .transpose()?,
             servers,
+            parameters: path_params,
         })
     }
 }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::openapi30::parser::{ParserContext, Result};
 use crate::Set;
 use crate::{Number, String, Value};
@@ -9,7 +11,7 @@ pub struct ArraySchema {
     pub unique_items: Option<bool>,
     pub max_items: Option<Number>,
     pub min_items: Option<Number>,
-    pub items: Option<Box<Schema>>, // referable, we should resolve these out
+    pub items: Option<Arc<Schema>>, // referable, we should resolve these out
 }
 
 impl ArraySchema {
@@ -42,6 +44,7 @@ impl ArraySchema {
             other.items = None;
         } else if let Some(self_val) = &mut self.items {
             // Otherwise, do partial exclusion
+            let self_val = Arc::make_mut(self_val);
             self_val.not = other.items.take();
             self_val.exclude();
         }
@@ -72,7 +75,9 @@ impl ArraySchema {
 
         if let Some(items) = self.items {
             if let Some(other_items) = other.items {
-                self.items = Some(Box::new(items.merge(*other_items)));
+                self.items = Some(Arc::new(
+                    Arc::unwrap_or_clone(items).merge(Arc::unwrap_or_clone(other_items)),
+                ));
             } else {
                 self.items = Some(items);
             }
@@ -92,7 +97,7 @@ impl ArraySchema {
             .take("items")
             .map(|v| Schema::try_parse(v, ctx, visited_refs, None))
             .transpose()?
-            .map(Box::new);
+            .map(Arc::new);
 
         Ok(Self {
             unique_items: value.take("uniqueItems").and_then(Value::take_bool),
@@ -8,7 +8,10 @@ pub mod one_of;
 pub mod property;
 pub mod string;
 
+use std::sync::Arc;
+
 use crate::openapi30::parser;
+use crate::openapi30::parser::{LimitError, ResolvedKey};
 use crate::{openapi30::parser::ParserContext, String};
 
 use crate::{Set, Value};
@@ -167,7 +170,7 @@ pub struct Schema {
     pub title: Option<String>,
     pub description: Option<String>,
     pub nullable: Option<bool>,
-    pub not: Option<Box<Schema>>,
+    pub not: Option<Arc<Schema>>,
     pub example: Option<Value>,
     pub default: Option<Value>,
     pub deprecated: Option<bool>,
@@ -270,7 +273,9 @@ impl Schema {
 
         match (self.not, other.not) {
             (Some(a), Some(b)) => {
-                self.not = Some(Box::new(a.merge(*b)));
+                self.not = Some(Arc::new(
+                    Arc::unwrap_or_clone(a).merge(Arc::unwrap_or_clone(b)),
+                ));
             }
             (a, b) => {
                 self.not = b.or(a);
@@ -293,17 +298,17 @@ impl Schema {
                     s.exclude();
                 }
             } else {
-                self.kind.exclude(&mut not.kind);
+                self.kind.exclude(&mut Arc::make_mut(&mut not).kind);
             }
 
             if self.default == not.default {
                 self.default = None;
-                not.default = None;
+                Arc::make_mut(&mut not).default = None;
             }
 
             if self.example == not.example {
                 self.example = None;
-                not.example = None;
+                Arc::make_mut(&mut not).example = None;
             }
 
             if not.is_empty() {
@@ -314,12 +319,77 @@ impl Schema {
         }
     }
 
+    /// Counts the schemas in this one once its references are expanded,
+    /// stopping as soon as there are more than `limit`.
+    fn expanded_size(&self, limit: usize) -> usize {
+        let mut size = 0;
+        let mut stack = vec![self];
+
+        while let Some(schema) = stack.pop() {
+            size += 1;
+
+            if size > limit {
+                break;
+            }
+
+            match &schema.kind {
+                SchemaKind::Object(o) => {
+                    stack.extend(o.properties.values().map(|p| &p.schema));
+                    stack.extend(o.additional_properties.as_deref());
+                }
+                SchemaKind::Array(a) => stack.extend(a.items.as_deref()),
+                SchemaKind::OneOf(o) => stack.extend(o.one_of.iter()),
+                SchemaKind::AnyOf(a) => stack.extend(a.any_of.iter()),
+                _ => {}
+            }
+
+            stack.extend(schema.not.as_deref());
+        }
+
+        size
+    }
+
+    /// Resolves a `$ref`. Each component is parsed once for every place it
+    /// can be cut off as recursive, and shared by all references after that.
+    fn resolve(
+        ref_path: String,
+        ctx: &ParserContext,
+        visited_refs: &mut Set<String>,
+    ) -> parser::Result<Self> {
+        let key = ResolvedKey::new(&ref_path, visited_refs);
+
+        if let Some(schema) = ctx.resolved_schema(&key) {
+            return Ok(schema);
+        }
+
+        let value = ctx
+            .get(&ref_path, visited_refs)
+            .ok_or_else(|| Error::ReferenceNotFound(ref_path.clone()))?;
+
+        let schema = Schema::try_parse(value, ctx, visited_refs, None)?;
+
+        let limit = ctx.limits.max_schema_expansion;
+        if schema.expanded_size(limit) > limit {
+            return Err(LimitError::SchemaTooLarge {
+                reference: ref_path,
+                limit,
+            }
+            .into());
+        }
+
+        ctx.cache_resolved_schema(key, schema.clone());
+
+        Ok(schema)
+    }
+
     pub fn try_parse(
         mut value: Value,
         ctx: &ParserContext,
         visited_refs: &mut Set<String>,
         overrides: Option<Overrides>,
     ) -> parser::Result<Self> {
+        ctx.count_schema_node()?;
+
         let mut visited_refs = visited_refs.clone();
 
         let mut val = if let Some(all_of) = value.take("allOf").and_then(Value::take_array) {
@@ -354,8 +424,10 @@ impl Schema {
             let mut resolved_one_of = vec![];
 
             for v in one_of {
-                if let Ok(schema) = Schema::try_parse(v, ctx, &mut visited_refs, None) {
-                    resolved_one_of.push(schema);
+                match Schema::try_parse(v, ctx, &mut visited_refs, None) {
+                    Ok(schema) => resolved_one_of.push(schema),
+                    Err(e @ parser::Error::Limit(_)) => return Err(e),
+                    Err(_) => {}
                 }
             }
 
@@ -381,8 +453,10 @@ impl Schema {
             let mut resolved_any_of = vec![];
 
             for v in any_of {
-                if let Ok(schema) = Schema::try_parse(v, ctx, &mut visited_refs, None) {
-                    resolved_any_of.push(schema);
+                match Schema::try_parse(v, ctx, &mut visited_refs, None) {
+                    Ok(schema) => resolved_any_of.push(schema),
+                    Err(e @ parser::Error::Limit(_)) => return Err(e),
+                    Err(_) => {}
                 }
             }
 
@@ -405,14 +479,10 @@ impl Schema {
                 default: None,
             }
         } else if let Some(ref_path) = value.take("$ref").and_then(Value::take_string) {
-            let value = ctx
-                .get(&ref_path, &mut visited_refs)
-                .ok_or(Error::ReferenceNotFound(ref_path))?;
-
-            Schema::try_parse(value, ctx, &mut visited_refs, None)?
+            Schema::resolve(ref_path, ctx, &mut visited_refs)?
         } else {
             let not = if let Some(n) = value.take("not") {
-                Some(Box::new(Schema::try_parse(
+                Some(Arc::new(Schema::try_parse(
                     n,
                     ctx,
                     &mut visited_refs,
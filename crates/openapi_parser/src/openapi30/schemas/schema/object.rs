@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::{Map, Set};
 
 use crate::{
@@ -12,8 +14,8 @@ use super::Schema;
 pub struct ObjectSchema {
     pub max_properties: Option<Number>,
     pub min_properties: Option<Number>,
-    pub properties: Map<String, Box<Property>>, // referable
-    pub additional_properties: Option<Box<Schema>>, // referable
+    pub properties: Map<String, Arc<Property>>, // referable
+    pub additional_properties: Option<Arc<Schema>>, // referable
     pub required: Set<String>,
 }
 
@@ -54,7 +56,8 @@ impl ObjectSchema {
         // do partial exclusion
         for (self_key, self_val) in self.properties.iter_mut() {
             if let Some(other_val) = other.properties.shift_remove(self_key) {
-                self_val.schema.not = Some(Box::new(other_val.schema));
+                let self_val = Arc::make_mut(self_val);
+                self_val.schema.not = Some(Arc::new(Arc::unwrap_or_clone(other_val).schema));
                 self_val.schema.exclude();
             }
         }
@@ -68,7 +71,11 @@ impl ObjectSchema {
         other.required.retain(|r| !common_req.contains(r));
 
         for (k, prop) in self.properties.iter_mut() {
-            prop.required = self.required.contains(k);
+            let required = self.required.contains(k);
+
+            if prop.required != required {
+                Arc::make_mut(prop).required = required;
+            }
         }
     }
 
@@ -98,12 +105,18 @@ impl ObjectSchema {
         self.properties.extend(other.properties);
 
         for (k, prop) in self.properties.iter_mut() {
-            prop.required = self.required.contains(k);
+            let required = self.required.contains(k);
+
+            if prop.required != required {
+                Arc::make_mut(prop).required = required;
+            }
         }
 
         self.additional_properties = match (self.additional_properties, other.additional_properties)
         {
-            (Some(self_add), Some(other_add)) => Some(Box::new(self_add.merge(*other_add))),
+            (Some(self_add), Some(other_add)) => Some(Arc::new(
+                Arc::unwrap_or_clone(self_add).merge(Arc::unwrap_or_clone(other_add)),
+            )),
             (None, Some(other_add)) => Some(other_add),
             (Some(self_add), None) => Some(self_add),
             (None, None) => None,
@@ -135,7 +148,7 @@ impl ObjectSchema {
                 let mut prop = Property::try_parse(v, ctx, visited_refs, is_prop_required)?;
                 prop.schema.metadata.field_name = Some(k.clone());
 
-                properties.insert(k, Box::new(prop));
+                properties.insert(k, Arc::new(prop));
             }
         }
 
@@ -143,7 +156,7 @@ impl ObjectSchema {
             .take("additionalProperties")
             .map(|v| Schema::try_parse(v, ctx, visited_refs, None))
             .transpose()?
-            .map(Box::new);
+            .map(Arc::new);
 
         Ok(Self {
             max_properties: value.take("maxProperties").and_then(Value::take_number),
@@ -1,6 +1,6 @@
 use super::external_documentation::ExternalDocumentation;
 use crate::openapi30::parser;
-use crate::{String, Value};
+use crate::{Map, String, Value};
 
 #[cfg(test)]
 use serde_json::to_string_pretty;
@@ -19,6 +19,7 @@ pub struct Tag {
     pub name: String,
     pub description: Option<String>,
     pub external_docs: Option<ExternalDocumentation>,
+    pub extensions: Map<String, Value>,
 }
 
 impl Tag {
@@ -41,10 +42,19 @@ impl Tag {
             .map(ExternalDocumentation::try_parse)
             .transpose()?;
 
+        let extensions = if let Some(obj) = value.take_object() {
+            obj.into_iter()
+                .filter(|(k, _)| k.starts_with("x-"))
+                .collect::<Map<_, _>>()
+        } else {
+            Map::new()
+        };
+
         Ok(Tag {
             name,
             description,
             external_docs,
+            extensions,
         })
     }
 }
@@ -55,6 +65,7 @@ impl From<String> for Tag {
             name: value,
             description: None,
             external_docs: None,
+            extensions: Map::new(),
         }
     }
 }
@@ -146,6 +157,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn parses_extensions() {
+        let value = json!({
+            "name": "pet",
+            "x-doctave": {
+                "hide": true
+            },
+            "unknown": "ignored"
+        });
+        let tag = Tag::try_parse(value).unwrap();
+        assert_str_eq!(
+            tag.pretty_print(),
+            indoc! {r#"
+            {
+              "name": "pet",
+              "x-doctave": {
+                "hide": true
+              }
+            }"#}
+        );
+    }
+
     #[test]
     fn fails_on_missing_name() {
         let value = json!({
@@ -0,0 +1,826 @@
+//! Validates the examples in a parsed spec against the schemas they describe.
+//!
+//! The schemas have already been resolved by the parser, so the checks here
+//! only walk the parsed structures. Only a practical subset of JSON Schema is
+//! covered: types, required properties, enums and a handful of string formats.
+
+use super::schemas::{
+    example::Example,
+    media_type::MediaType,
+    openapi::OpenAPI,
+    parameter::{Parameter, SchemaOrContent},
+    request_body::RequestBody,
+    response::Response,
+    schema::{Schema, SchemaKind},
+};
+use crate::{Map, Number, Value};
+
+/// An example that does not match its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExampleError {
+    /// JSON pointer to the offending example inside the spec
+    pub pointer: std::string::String,
+    /// Why the example did not match the schema
+    pub reason: std::string::String,
+}
+
+/// Validates every `example` and `examples` value found in media types,
+/// parameters, headers and schemas.
+pub fn validate_examples(spec: &OpenAPI) -> Vec<ExampleError> {
+    let mut errors = vec![];
+
+    for (pattern, path_item) in spec.paths.iter() {
+        let path_pointer = format!("/paths/{}", escape(pattern));
+
+        for (idx, parameter) in path_item.parameters.iter().enumerate() {
+            validate_parameter(
+                parameter,
+                &format!("{}/parameters/{}", path_pointer, idx),
+                &mut errors,
+            );
+        }
+
+        for operation in path_item.operations() {
+            let pointer = format!("{}/{}", path_pointer, operation.method.to_lowercase());
+
+            // The path level parameters were merged in front of the
+            // operation's own, and are validated above
+            let own_parameters = operation.parameters.iter().skip(path_item.parameters.len());
+
+            for (idx, parameter) in own_parameters.enumerate() {
+                validate_parameter(
+                    parameter,
+                    &format!("{}/parameters/{}", pointer, idx),
+                    &mut errors,
+                );
+            }
+
+            if let Some(request_body) = &operation.request_body {
+                validate_request_body(
+                    request_body,
+                    &format!("{}/requestBody", pointer),
+                    &mut errors,
+                );
+            }
+
+            if let Some(responses) = &operation.responses {
+                for (status, response) in responses.0.iter() {
+                    validate_response(
+                        response,
+                        &format!("{}/responses/{}", pointer, escape(status)),
+                        &mut errors,
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(components) = &spec.components {
+        for (name, schema) in components.schemas.iter() {
+            validate_schema_examples(
+                schema,
+                &format!("/components/schemas/{}", escape(name)),
+                &mut errors,
+            );
+        }
+
+        for (name, parameter) in components.parameters.iter() {
+            validate_parameter(
+                parameter,
+                &format!("/components/parameters/{}", escape(name)),
+                &mut errors,
+            );
+        }
+
+        for (name, request_body) in components.request_bodies.iter() {
+            validate_request_body(
+                request_body,
+                &format!("/components/requestBodies/{}", escape(name)),
+                &mut errors,
+            );
+        }
+
+        for (name, response) in components.responses.iter() {
+            validate_response(
+                response,
+                &format!("/components/responses/{}", escape(name)),
+                &mut errors,
+            );
+        }
+
+        for (name, header) in components.headers.iter() {
+            validate_parameter(
+                &header.0,
+                &format!("/components/headers/{}", escape(name)),
+                &mut errors,
+            );
+        }
+    }
+
+    errors
+}
+
+/// Checks a single example against a schema of the spec, for examples kept
+/// outside of it. Returns why the example doesn't match.
+pub fn validate_example(schema: &Schema, example: &Value) -> Result<(), std::string::String> {
+    validate_value(schema, example, "")
+}
+
+fn validate_parameter(parameter: &Parameter, pointer: &str, errors: &mut Vec<ExampleError>) {
+    match &parameter.schema_or_content {
+        Some(SchemaOrContent::Schema(schema)) => {
+            validate_schema_examples(schema, &format!("{}/schema", pointer), errors);
+            validate_example_values(
+                schema,
+                parameter.example.as_ref(),
+                &parameter.examples,
+                pointer,
+                errors,
+            );
+        }
+        Some(SchemaOrContent::Content(content)) => {
+            validate_content(content, &format!("{}/content", pointer), errors);
+        }
+        None => {}
+    }
+}
+
+fn validate_request_body(
+    request_body: &RequestBody,
+    pointer: &str,
+    errors: &mut Vec<ExampleError>,
+) {
+    validate_content(
+        &request_body.content,
+        &format!("{}/content", pointer),
+        errors,
+    );
+}
+
+fn validate_response(response: &Response, pointer: &str, errors: &mut Vec<ExampleError>) {
+    for (name, header) in response.headers.iter() {
+        validate_parameter(
+            &header.0,
+            &format!("{}/headers/{}", pointer, escape(name)),
+            errors,
+        );
+    }
+
+    validate_content(&response.content, &format!("{}/content", pointer), errors);
+}
+
+fn validate_content(
+    content: &Map<crate::String, MediaType>,
+    pointer: &str,
+    errors: &mut Vec<ExampleError>,
+) {
+    for (mime, media_type) in content.iter() {
+        let pointer = format!("{}/{}", pointer, escape(mime));
+
+        if let Some(schema) = &media_type.schema {
+            validate_schema_examples(schema, &format!("{}/schema", pointer), errors);
+            validate_example_values(
+                schema,
+                media_type.example.as_ref(),
+                &media_type.examples,
+                &pointer,
+                errors,
+            );
+        }
+    }
+}
+
+fn validate_example_values(
+    schema: &Schema,
+    example: Option<&Value>,
+    examples: &Map<crate::String, Example>,
+    pointer: &str,
+    errors: &mut Vec<ExampleError>,
+) {
+    if let Some(example) = example {
+        push_error(schema, example, format!("{}/example", pointer), errors);
+    }
+
+    for (name, example) in examples.iter() {
+        if let Some(value) = &example.value {
+            push_error(
+                schema,
+                value,
+                format!("{}/examples/{}/value", pointer, escape(name)),
+                errors,
+            );
+        }
+    }
+}
+
+/// Validates the `example` of a schema and all of its subschemas.
+///
+/// Schemas that were resolved from a reference to a component schema are
+/// skipped, since they are validated under `/components/schemas` instead.
+fn validate_schema_examples(schema: &Schema, pointer: &str, errors: &mut Vec<ExampleError>) {
+    if schema.metadata.component_name.is_some() {
+        return;
+    }
+
+    if let Some(example) = &schema.example {
+        push_error(schema, example, format!("{}/example", pointer), errors);
+    }
+
+    match &schema.kind {
+        SchemaKind::Object(o) => {
+            for (name, property) in o.properties.iter() {
+                validate_schema_examples(
+                    &property.schema,
+                    &format!("{}/properties/{}", pointer, escape(name)),
+                    errors,
+                );
+            }
+
+            if let Some(additional) = &o.additional_properties {
+                validate_schema_examples(
+                    additional,
+                    &format!("{}/additionalProperties", pointer),
+                    errors,
+                );
+            }
+        }
+        SchemaKind::Array(a) => {
+            if let Some(items) = &a.items {
+                validate_schema_examples(items, &format!("{}/items", pointer), errors);
+            }
+        }
+        SchemaKind::OneOf(o) => {
+            for (idx, branch) in o.one_of.iter().enumerate() {
+                validate_schema_examples(branch, &format!("{}/oneOf/{}", pointer, idx), errors);
+            }
+        }
+        SchemaKind::AnyOf(a) => {
+            for (idx, branch) in a.any_of.iter().enumerate() {
+                validate_schema_examples(branch, &format!("{}/anyOf/{}", pointer, idx), errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn push_error(schema: &Schema, value: &Value, pointer: String, errors: &mut Vec<ExampleError>) {
+    if let Err(reason) = validate_value(schema, value, "") {
+        errors.push(ExampleError { pointer, reason });
+    }
+}
+
+/// Checks a single value against a schema. The `location` is the JSON pointer
+/// of the value inside the example, used to make the error easier to find.
+fn validate_value(schema: &Schema, value: &Value, location: &str) -> Result<(), String> {
+    let at = if location.is_empty() {
+        String::new()
+    } else {
+        format!(" at `{}`", location)
+    };
+
+    if let Value::Null = value {
+        return match schema.kind {
+            SchemaKind::Unknown => Ok(()),
+            _ if schema.nullable == Some(true) => Ok(()),
+            SchemaKind::OneOf(_) | SchemaKind::AnyOf(_) => check_branches(schema, value, location),
+            _ => Err(format!(
+                "Found `null`{}, but the schema is not nullable",
+                at
+            )),
+        };
+    }
+
+    match &schema.kind {
+        SchemaKind::String(s) => {
+            let string = value
+                .as_str()
+                .ok_or_else(|| type_mismatch("string", value, &at))?;
+
+            if !s.r#enum.is_empty() && !s.r#enum.iter().any(|e| e == string) {
+                return Err(format!(
+                    "Found \"{}\"{}, expected one of {}",
+                    string,
+                    at,
+                    list(s.r#enum.iter().map(|e| format!("\"{}\"", e)))
+                ));
+            }
+
+            if let Some(format) = s.format.as_deref() {
+                if !matches_format(format, string) {
+                    return Err(format!(
+                        "Found \"{}\"{}, which is not a valid `{}`",
+                        string, at, format
+                    ));
+                }
+            }
+
+            Ok(())
+        }
+        SchemaKind::Number(n) => {
+            let number = match value {
+                Value::Number(number) => number,
+                _ => return Err(type_mismatch("number", value, &at)),
+            };
+
+            check_number_enum(&n.r#enum, number, &at)
+        }
+        SchemaKind::Integer(i) => {
+            let number = match value {
+                Value::Number(number) if as_f64(number).fract() == 0.0 => number,
+                _ => return Err(type_mismatch("integer", value, &at)),
+            };
+
+            check_number_enum(&i.r#enum, number, &at)
+        }
+        SchemaKind::Boolean(b) => {
+            let boolean = match value {
+                Value::Bool(boolean) => *boolean,
+                _ => return Err(type_mismatch("boolean", value, &at)),
+            };
+
+            if !b.r#enum.is_empty() && !b.r#enum.contains(&boolean) {
+                return Err(format!(
+                    "Found `{}`{}, expected one of {}",
+                    boolean,
+                    at,
+                    list(b.r#enum.iter().map(|e| format!("`{}`", e)))
+                ));
+            }
+
+            Ok(())
+        }
+        SchemaKind::Array(a) => {
+            let items = match value {
+                Value::Array(items) => items,
+                _ => return Err(type_mismatch("array", value, &at)),
+            };
+
+            if let Some(schema) = &a.items {
+                for (idx, item) in items.iter().enumerate() {
+                    validate_value(schema, item, &format!("{}/{}", location, idx))?;
+                }
+            }
+
+            Ok(())
+        }
+        SchemaKind::Object(o) => {
+            let object = match value {
+                Value::Object(_) | Value::Mapping(_) => value.clone().take_object().unwrap(),
+                _ => return Err(type_mismatch("object", value, &at)),
+            };
+
+            for (name, property) in o.properties.iter() {
+                match object.get(name) {
+                    Some(value) => validate_value(
+                        &property.schema,
+                        value,
+                        &format!("{}/{}", location, escape(name)),
+                    )?,
+                    None if property.required || o.required.contains(name) => {
+                        return Err(format!("Missing required property `{}`{}", name, at));
+                    }
+                    None => {}
+                }
+            }
+
+            for name in o.required.iter() {
+                if !object.contains_key(name) {
+                    return Err(format!("Missing required property `{}`{}", name, at));
+                }
+            }
+
+            Ok(())
+        }
+        SchemaKind::OneOf(_) | SchemaKind::AnyOf(_) => check_branches(schema, value, location),
+        SchemaKind::Unknown => Ok(()),
+    }
+}
+
+/// `oneOf` and `anyOf` both pass if any of the branches validates. We don't
+/// enforce `oneOf` exclusivity, since branches commonly overlap in practice.
+fn check_branches(schema: &Schema, value: &Value, location: &str) -> Result<(), String> {
+    let (keyword, branches) = match &schema.kind {
+        SchemaKind::OneOf(o) => ("oneOf", &o.one_of),
+        SchemaKind::AnyOf(a) => ("anyOf", &a.any_of),
+        _ => unreachable!(),
+    };
+
+    if branches.is_empty()
+        || branches
+            .iter()
+            .any(|b| validate_value(b, value, location).is_ok())
+    {
+        return Ok(());
+    }
+
+    let at = if location.is_empty() {
+        String::new()
+    } else {
+        format!(" at `{}`", location)
+    };
+
+    Err(format!(
+        "Value{} does not match any of the `{}` schemas",
+        at, keyword
+    ))
+}
+
+fn check_number_enum(r#enum: &[Number], number: &Number, at: &str) -> Result<(), String> {
+    if !r#enum.is_empty() && !r#enum.iter().any(|e| as_f64(e) == as_f64(number)) {
+        return Err(format!(
+            "Found `{}`{}, expected one of {}",
+            number,
+            at,
+            list(r#enum.iter().map(|e| format!("`{}`", e)))
+        ));
+    }
+
+    Ok(())
+}
+
+fn type_mismatch(expected: &str, value: &Value, at: &str) -> String {
+    format!(
+        "Expected {} {}, found {}",
+        article(expected),
+        expected,
+        type_name(value)
+    ) + at
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::String(_) => "a string",
+        Value::Bool(_) => "a boolean",
+        Value::Number(Number::Int(_)) => "an integer",
+        Value::Number(Number::Float(_)) => "a number",
+        Value::Array(_) => "an array",
+        Value::Object(_) | Value::Mapping(_) => "an object",
+    }
+}
+
+fn article(word: &str) -> &'static str {
+    if word.starts_with(['a', 'e', 'i', 'o', 'u']) {
+        "an"
+    } else {
+        "a"
+    }
+}
+
+fn as_f64(number: &Number) -> f64 {
+    match number {
+        Number::Int(i) => *i as f64,
+        Number::Float(f) => *f,
+    }
+}
+
+fn list(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// Escapes a reference token according to RFC 6901.
+fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Basic checks for the most common string formats. Unknown formats always pass.
+fn matches_format(format: &str, value: &str) -> bool {
+    match format {
+        "date" => is_date(value),
+        "date-time" => is_date_time(value),
+        "email" => value
+            .split_once('@')
+            .map(|(local, domain)| !local.is_empty() && domain.contains('.'))
+            .unwrap_or(false),
+        "uuid" => is_uuid(value),
+        "uri" => value
+            .split_once(':')
+            .map(|(scheme, _)| {
+                !scheme.is_empty()
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+            })
+            .unwrap_or(false),
+        _ => true,
+    }
+}
+
+fn is_digits(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_date(value: &str) -> bool {
+    let parts = value.split('-').collect::<Vec<_>>();
+
+    match parts.as_slice() {
+        [year, month, day] => {
+            is_digits(year, 4)
+                && is_digits(month, 2)
+                && is_digits(day, 2)
+                && (1..=12).contains(&month.parse::<u8>().unwrap_or(0))
+                && (1..=31).contains(&day.parse::<u8>().unwrap_or(0))
+        }
+        _ => false,
+    }
+}
+
+fn is_date_time(value: &str) -> bool {
+    let (date, time) = match value.split_once(['T', 't']) {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    let time = time.trim_end_matches(['Z', 'z']);
+    let time = time
+        .rsplit_once(['+', '-'])
+        .filter(|(_, offset)| offset.len() == 5 && offset.as_bytes()[2] == b':')
+        .map(|(time, _)| time)
+        .unwrap_or(time);
+    let time = time.split_once('.').map(|(time, _)| time).unwrap_or(time);
+
+    let parts = time.split(':').collect::<Vec<_>>();
+
+    is_date(date)
+        && matches!(parts.as_slice(), [h, m, s] if is_digits(h, 2) && is_digits(m, 2) && is_digits(s, 2))
+}
+
+fn is_uuid(value: &str) -> bool {
+    let groups = value.split('-').map(str::len).collect::<Vec<_>>();
+
+    groups == [8, 4, 4, 4, 12] && value.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::openapi30::parser::parse_json;
+
+    fn errors_for(spec: serde_json::Value) -> Vec<ExampleError> {
+        let spec = parse_json(&spec.to_string()).unwrap();
+
+        validate_examples(&spec)
+    }
+
+    fn spec_with_body(media_type: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Test", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "content": { "application/json": media_type }
+                        },
+                        "responses": { "200": { "description": "OK" } }
+                    }
+                }
+            }
+        })
+    }
+
+    fn user_schema() -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "required": ["id", "email"],
+            "properties": {
+                "id": { "type": "integer" },
+                "email": { "type": "string", "format": "email" },
+                "role": { "type": "string", "enum": ["admin", "member"] }
+            }
+        })
+    }
+
+    #[test]
+    fn valid_example_passes() {
+        let spec = spec_with_body(serde_json::json!({
+            "schema": user_schema(),
+            "example": { "id": 1, "email": "ann@example.com", "role": "admin" }
+        }));
+
+        assert_eq!(errors_for(spec), vec![]);
+    }
+
+    #[test]
+    fn reports_missing_required_property() {
+        let spec = spec_with_body(serde_json::json!({
+            "schema": user_schema(),
+            "example": { "id": 1 }
+        }));
+
+        assert_eq!(
+            errors_for(spec),
+            vec![ExampleError {
+                pointer: "/paths/~1users/post/requestBody/content/application~1json/example"
+                    .to_string(),
+                reason: "Missing required property `email`".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_wrong_type_with_location() {
+        let spec = spec_with_body(serde_json::json!({
+            "schema": user_schema(),
+            "examples": {
+                "bad": { "value": { "id": "one", "email": "ann@example.com" } }
+            }
+        }));
+
+        assert_eq!(
+            errors_for(spec),
+            vec![ExampleError {
+                pointer:
+                    "/paths/~1users/post/requestBody/content/application~1json/examples/bad/value"
+                        .to_string(),
+                reason: "Expected an integer, found a string at `/id`".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_enum_and_format_violations() {
+        let spec = spec_with_body(serde_json::json!({
+            "schema": user_schema(),
+            "examples": {
+                "role": { "value": { "id": 1, "email": "ann@example.com", "role": "owner" } },
+                "email": { "value": { "id": 1, "email": "not-an-email" } }
+            }
+        }));
+
+        let errors = errors_for(spec);
+
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].reason,
+            "Found \"owner\" at `/role`, expected one of \"admin\", \"member\""
+        );
+        assert_eq!(
+            errors[1].reason,
+            "Found \"not-an-email\" at `/email`, which is not a valid `email`"
+        );
+    }
+
+    #[test]
+    fn one_of_passes_when_any_branch_validates() {
+        let spec = spec_with_body(serde_json::json!({
+            "schema": { "oneOf": [{ "type": "integer" }, { "type": "string" }] },
+            "examples": {
+                "number": { "value": 1 },
+                "string": { "value": "one" },
+                "bool": { "value": true }
+            }
+        }));
+
+        let errors = errors_for(spec);
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].pointer,
+            "/paths/~1users/post/requestBody/content/application~1json/examples/bool/value"
+        );
+        assert_eq!(
+            errors[0].reason,
+            "Value does not match any of the `oneOf` schemas"
+        );
+    }
+
+    #[test]
+    fn nullable_allows_null() {
+        let spec = spec_with_body(serde_json::json!({
+            "schema": { "type": "string", "nullable": true },
+            "example": null
+        }));
+
+        assert_eq!(errors_for(spec), vec![]);
+    }
+
+    #[test]
+    fn validates_parameter_and_schema_examples() {
+        let spec = serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Test", "version": "1.0.0" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [{
+                            "name": "limit",
+                            "in": "query",
+                            "schema": { "type": "integer" },
+                            "example": "ten"
+                        }],
+                        "responses": { "200": { "description": "OK" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "created": {
+                                "type": "string",
+                                "format": "date-time",
+                                "example": "2024-01-01T12:00:00Z"
+                            },
+                            "birthday": {
+                                "type": "string",
+                                "format": "date",
+                                "example": "2024-13-01"
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            errors_for(spec),
+            vec![
+                ExampleError {
+                    pointer: "/paths/~1users/get/parameters/0/example".to_string(),
+                    reason: "Expected an integer, found a string".to_string(),
+                },
+                ExampleError {
+                    pointer: "/components/schemas/User/properties/birthday/example".to_string(),
+                    reason: "Found \"2024-13-01\", which is not a valid `date`".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn points_to_path_and_operation_level_parameters() {
+        let spec = serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Test", "version": "1.0.0" },
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [{
+                        "name": "id",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "integer" },
+                        "example": "abc"
+                    }],
+                    "get": {
+                        "parameters": [{
+                            "name": "fields",
+                            "in": "query",
+                            "schema": { "type": "string" }
+                        }, {
+                            "name": "limit",
+                            "in": "query",
+                            "schema": { "type": "integer" },
+                            "example": "ten"
+                        }],
+                        "responses": { "200": { "description": "OK" } }
+                    },
+                    "delete": {
+                        "responses": { "204": { "description": "Deleted" } }
+                    }
+                }
+            }
+        });
+
+        assert_eq!(
+            errors_for(spec),
+            vec![
+                ExampleError {
+                    pointer: "/paths/~1users~1{id}/parameters/0/example".to_string(),
+                    reason: "Expected an integer, found a string".to_string(),
+                },
+                ExampleError {
+                    pointer: "/paths/~1users~1{id}/get/parameters/1/example".to_string(),
+                    reason: "Expected an integer, found a string".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn validates_a_single_example_against_a_component_schema() {
+        let spec = parse_json(
+            &serde_json::json!({
+                "openapi": "3.0.0",
+                "info": { "title": "Test", "version": "1.0.0" },
+                "paths": {},
+                "components": { "schemas": { "User": user_schema() } }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let schema = &spec.components.as_ref().unwrap().schemas["User"];
+
+        let valid: Value =
+            serde_json::from_value(serde_json::json!({ "id": 1, "email": "ann@example.com" }))
+                .unwrap();
+        let invalid: Value = serde_json::from_value(serde_json::json!({ "id": 1 })).unwrap();
+
+        assert_eq!(validate_example(schema, &valid), Ok(()));
+        assert_eq!(
+            validate_example(schema, &invalid),
+            Err("Missing required property `email`".to_string())
+        );
+    }
+}